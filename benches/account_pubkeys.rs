@@ -0,0 +1,74 @@
+//! Building instruction account lists from several threads at once: a single
+//! mutex-guarded buffer shared by all parsing tasks versus a per-call SmallVec.
+
+use std::thread;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use solana_streamer_sdk::streaming::event_parser::core::event_parser::build_account_pubkeys;
+
+const INSTRUCTIONS_PER_THREAD: usize = 2_000;
+const TRANSACTION_ACCOUNTS: usize = 40;
+const INSTRUCTION_ACCOUNTS: usize = 17;
+
+fn instruction_accounts() -> Vec<Vec<u8>> {
+    (0..INSTRUCTIONS_PER_THREAD)
+        .map(|i| {
+            (0..INSTRUCTION_ACCOUNTS).map(|j| ((i + j * 7) % TRANSACTION_ACCOUNTS) as u8).collect()
+        })
+        .collect()
+}
+
+fn run_threads(threads: usize, work: impl Fn() + Sync) {
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(&work);
+        }
+    });
+}
+
+fn bench_account_pubkeys(c: &mut Criterion) {
+    let accounts: Vec<Pubkey> = (0..TRANSACTION_ACCOUNTS).map(|_| Pubkey::new_unique()).collect();
+    let instructions = instruction_accounts();
+    let shared = Mutex::new(Vec::<Pubkey>::with_capacity(32));
+
+    let mut group = c.benchmark_group("account_pubkeys");
+    for threads in [1, 4, 8] {
+        group.bench_with_input(BenchmarkId::new("shared_mutex", threads), &threads, |b, &n| {
+            b.iter(|| {
+                run_threads(n, || {
+                    for indices in &instructions {
+                        // 旧实现：加锁填充共享缓冲区后再复制一份
+                        let pubkeys = {
+                            let mut cache = shared.lock();
+                            cache.clear();
+                            cache.extend(
+                                indices.iter().filter_map(|&i| accounts.get(i as usize).copied()),
+                            );
+                            cache.to_vec()
+                        };
+                        black_box(pubkeys);
+                    }
+                })
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("per_call_smallvec", threads),
+            &threads,
+            |b, &n| {
+                b.iter(|| {
+                    run_threads(n, || {
+                        for indices in &instructions {
+                            black_box(build_account_pubkeys(indices, &accounts));
+                        }
+                    })
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_account_pubkeys);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+//! Program-id prefilter on firehose-style traffic, where fewer than 1% of
+//! transactions touch a watched program.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use solana_sdk::pubkey::Pubkey;
+use solana_streamer_sdk::streaming::event_parser::core::program_filter::ProgramIdFilter;
+
+const TRANSACTIONS: usize = 10_000;
+const KEYS_PER_TRANSACTION: usize = 24;
+/// One in this many transactions includes a watched program
+const MATCH_EVERY: usize = 200;
+
+/// `Pubkey::new_unique` keys are zero in the fingerprint bytes and would all pass it
+fn random_key() -> Pubkey {
+    Pubkey::new_from_array(rand::random())
+}
+
+fn firehose(watched: &[Pubkey]) -> Vec<Vec<Pubkey>> {
+    (0..TRANSACTIONS)
+        .map(|i| {
+            let mut keys: Vec<Pubkey> = (0..KEYS_PER_TRANSACTION).map(|_| random_key()).collect();
+            if i % MATCH_EVERY == 0 {
+                keys[KEYS_PER_TRANSACTION / 2] = watched[i % watched.len()];
+            }
+            keys
+        })
+        .collect()
+}
+
+fn bench_program_filter(c: &mut Criterion) {
+    let watched: Vec<Pubkey> = (0..12).map(|_| random_key()).collect();
+    let transactions = firehose(&watched);
+    let filter = ProgramIdFilter::new(watched.iter().copied());
+
+    let mut group = c.benchmark_group("program_filter");
+    group.bench_function("linear_scan", |b| {
+        b.iter(|| {
+            transactions
+                .iter()
+                .filter(|keys| keys.iter().any(|key| watched.contains(black_box(key))))
+                .count()
+        })
+    });
+    group.bench_function("bloom_fingerprint", |b| {
+        b.iter(|| transactions.iter().filter(|keys| filter.any_match(black_box(keys))).count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_program_filter);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+//! Parser coverage of a recorded debug bundle
+//!
+//! Usage: `cargo run --example coverage_report -- <bundle-dir>`
+
+use anyhow::Result;
+use solana_streamer_sdk::streaming::event_parser::core::coverage::CoverageTracker;
+use solana_streamer_sdk::streaming::event_parser::core::event_parser::EventParser;
+use solana_streamer_sdk::streaming::event_parser::Protocol;
+use solana_streamer_sdk::streaming::grpc::AccountPretty;
+use solana_streamer_sdk::streaming::store::DebugBundle;
+
+fn main() -> Result<()> {
+    let dir = std::env::args().nth(1).ok_or_else(|| anyhow::anyhow!("usage: <bundle-dir>"))?;
+    let bundle = DebugBundle::load(&dir)?;
+
+    let parser = EventParser::new(
+        vec![Protocol::RaydiumCpmm, Protocol::RaydiumClmm, Protocol::RaydiumAmmV4],
+        None,
+    );
+    let mut tracker = CoverageTracker::new(&parser);
+    for update in &bundle.updates {
+        if let Some(info) = update.transaction.as_ref() {
+            tracker.record_grpc_transaction(info);
+        }
+    }
+    for (pubkey, snapshot) in &bundle.accounts {
+        tracker.record_account(&AccountPretty {
+            pubkey: *pubkey,
+            owner: snapshot.owner,
+            lamports: snapshot.lamports,
+            data: snapshot.data.clone(),
+            slot: snapshot.slot,
+            ..Default::default()
+        });
+    }
+
+    println!(
+        "{} transactions, {} accounts from {}\n",
+        bundle.updates.len(),
+        bundle.accounts.len(),
+        dir
+    );
+    print!("{}", tracker.report());
+    Ok(())
+}
@@ -0,0 +1,130 @@
+//! Ad-hoc decoding of a single instruction or account, for unexplained bytes seen in the stream
+//!
+//! Usage:
+//!   `cargo run --example decode -- decode-ix --program <id> --data <base58|base64> [--accounts a,b,c]`
+//!   `cargo run --example decode -- decode-account --program <id> --data <base64> [--pubkey <key>]`
+//!
+//! The built-in protocols are always loaded; add `--configs <dir>` to also decode with
+//! the protocol configs (JSON / TOML) in that directory, e.g. `configs/protocols`.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_sdk::account::Account;
+use solana_sdk::bs58;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::{
+    compiled_instruction::CompiledInstruction, v0, MessageHeader, VersionedMessage,
+};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_streamer_sdk::streaming::event_parser::config::{ConfigLoader, DynamicEventParser};
+use solana_streamer_sdk::streaming::event_parser::core::event_parser::EventParser;
+use solana_streamer_sdk::streaming::event_parser::core::RawTransactionMeta;
+use solana_streamer_sdk::streaming::event_parser::{Protocol, UnifiedEvent};
+
+const USAGE: &str = "usage: decode-ix --program <id> --data <base58|base64> [--accounts a,b,c] [--configs <dir>]\n       decode-account --program <id> --data <base64> [--pubkey <key>] [--configs <dir>]";
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let command = args.next().ok_or_else(|| anyhow!(USAGE))?;
+    let mut options = HashMap::new();
+    while let Some(flag) = args.next() {
+        let name = flag.strip_prefix("--").ok_or_else(|| anyhow!("unexpected argument {flag}"))?;
+        let value = args.next().ok_or_else(|| anyhow!("missing value for --{name}"))?;
+        options.insert(name.to_string(), value);
+    }
+    let option = |name: &str| options.get(name).ok_or_else(|| anyhow!("--{name} is required"));
+    let program = Pubkey::from_str(option("program")?).context("invalid --program")?;
+    let parser = build_parser(options.get("configs").map(String::as_str))?;
+
+    let events: Vec<Box<dyn UnifiedEvent>> = match command.as_str() {
+        "decode-ix" => {
+            let data = decode_bytes(option("data")?)?;
+            let accounts = options
+                .get("accounts")
+                .map(|list| {
+                    list.split(',')
+                        .filter(|key| !key.is_empty())
+                        .map(|key| Pubkey::from_str(key.trim()).with_context(|| key.to_string()))
+                        .collect::<Result<Vec<_>>>()
+                })
+                .transpose()?
+                .unwrap_or_default();
+            // 不含内联指令：依赖内联转账取金额的 swap 事件只有指令参数
+            let transaction = single_instruction(program, data, accounts);
+            parser.parse_transaction(&transaction, &RawTransactionMeta::default())?
+        }
+        "decode-account" => {
+            let data = STANDARD.decode(option("data")?).context("--data is not base64")?;
+            let pubkey = options
+                .get("pubkey")
+                .map(|key| Pubkey::from_str(key))
+                .transpose()
+                .context("invalid --pubkey")?
+                .unwrap_or_default();
+            let account = Account { owner: program, data, ..Default::default() };
+            parser.parse_account_data(pubkey, &account, 0).into_iter().collect()
+        }
+        _ => bail!(USAGE),
+    };
+
+    if events.is_empty() {
+        println!("no parser matched the data");
+    }
+    for event in events {
+        println!("{}", serde_json::to_string_pretty(&event.to_json())?);
+    }
+    Ok(())
+}
+
+fn build_parser(configs: Option<&str>) -> Result<EventParser> {
+    let mut parser = EventParser::new(
+        vec![Protocol::RaydiumCpmm, Protocol::RaydiumClmm, Protocol::RaydiumAmmV4],
+        None,
+    );
+    for protocol_config in
+        configs.map(ConfigLoader::load_from_directory).transpose()?.unwrap_or_default()
+    {
+        for config in DynamicEventParser::create_configs(&protocol_config)? {
+            parser.add_dynamic_config(config);
+        }
+    }
+    Ok(parser)
+}
+
+/// Instruction data as printed by explorers (base58) or RPC / logs (base64)
+fn decode_bytes(data: &str) -> Result<Vec<u8>> {
+    if let Ok(bytes) = bs58::decode(data).into_vec() {
+        return Ok(bytes);
+    }
+    STANDARD.decode(data).context("--data is neither base58 nor base64")
+}
+
+/// A transaction holding only the instruction, accounts in the given order
+fn single_instruction(
+    program: Pubkey,
+    data: Vec<u8>,
+    accounts: Vec<Pubkey>,
+) -> VersionedTransaction {
+    let mut account_keys = accounts;
+    let account_indexes = (0..account_keys.len() as u8).collect();
+    account_keys.push(program);
+    VersionedTransaction {
+        signatures: vec![Signature::default()],
+        message: VersionedMessage::V0(v0::Message {
+            header: MessageHeader { num_required_signatures: 1, ..Default::default() },
+            instructions: vec![CompiledInstruction {
+                program_id_index: (account_keys.len() - 1) as u8,
+                accounts: account_indexes,
+                data,
+            }],
+            account_keys,
+            recent_blockhash: Hash::default(),
+            ..Default::default()
+        }),
+    }
+}
@@ -50,6 +50,8 @@ async fn main() -> Result<()> {
         account_include: vec![PUMPFUN_PROGRAM_ID.to_string()],
         account_exclude: vec![],
         account_required: vec![],
+        protocols: vec![],
+        signers: vec![],
     };
 
     let account_filter = AccountFilter { account: vec![], owner: vec![], filters: vec![] };
@@ -91,6 +93,8 @@ async fn main() -> Result<()> {
         account_include: vec![PUMPFUN_PROGRAM_ID.to_string(), RAYDIUM_CPMM_PROGRAM_ID.to_string()],
         account_exclude: vec![],
         account_required: vec![],
+        protocols: vec![],
+        signers: vec![],
     };
 
     if let Err(e) = client
@@ -117,6 +121,8 @@ async fn main() -> Result<()> {
         account_include: vec![RAYDIUM_CPMM_PROGRAM_ID.to_string()],
         account_exclude: vec![],
         account_required: vec![],
+        protocols: vec![],
+        signers: vec![],
     };
 
     if let Err(e) = client
@@ -143,6 +149,8 @@ async fn main() -> Result<()> {
         account_include: vec![PUMPFUN_PROGRAM_ID.to_string()],
         account_exclude: vec![],
         account_required: vec![],
+        protocols: vec![],
+        signers: vec![],
     };
 
     if let Err(e) = client
@@ -169,6 +177,8 @@ async fn main() -> Result<()> {
         account_include: vec![],
         account_exclude: vec![],
         account_required: vec![],
+        protocols: vec![],
+        signers: vec![],
     };
 
     if let Err(e) = client
@@ -201,6 +211,8 @@ async fn main() -> Result<()> {
         account_include: vec![],
         account_exclude: vec![],
         account_required: vec![random_pubkey_1.to_string(), random_pubkey_2.to_string()],
+        protocols: vec![],
+        signers: vec![],
     };
 
     if let Err(e) = client
@@ -266,6 +278,8 @@ async fn main() -> Result<()> {
                 account_include: vec![],
                 account_exclude: vec![],
                 account_required: vec![],
+                protocols: vec![],
+                signers: vec![],
             }],
             vec![AccountFilter { account: vec![], owner: vec![], filters: vec![] }],
             None,
@@ -337,6 +351,8 @@ async fn main() -> Result<()> {
                 account_include: vec![RAYDIUM_CPMM_PROGRAM_ID.to_string()],
                 account_exclude: vec![],
                 account_required: vec![],
+                protocols: vec![],
+                signers: vec![],
             }],
             vec![AccountFilter { account: vec![], owner: vec![], filters: vec![] }],
             None,
@@ -370,6 +386,8 @@ async fn main() -> Result<()> {
                 account_include: vec![RAYDIUM_CPMM_PROGRAM_ID.to_string()],
                 account_exclude: vec![],
                 account_required: vec![],
+                protocols: vec![],
+                signers: vec![],
             }],
             vec![AccountFilter { account: vec![], owner: vec![], filters: vec![] }],
             None,
@@ -404,6 +422,8 @@ async fn main() -> Result<()> {
                 account_include: vec![RAYDIUM_CPMM_PROGRAM_ID.to_string()],
                 account_exclude: vec![],
                 account_required: vec![],
+                protocols: vec![],
+                signers: vec![],
             }],
             vec![AccountFilter { account: vec![], owner: vec![], filters: vec![] }],
             None,
@@ -422,6 +442,8 @@ async fn main() -> Result<()> {
                         account_include: vec![RAYDIUM_CPMM_PROGRAM_ID.to_string()],
                         account_exclude: vec![],
                         account_required: vec![],
+                        protocols: vec![],
+                        signers: vec![],
                     }],
                     vec![AccountFilter { account: vec![], owner: vec![], filters: vec![] }],
                     None,
@@ -459,6 +481,8 @@ async fn main() -> Result<()> {
                 account_include: vec![RAYDIUM_CPMM_PROGRAM_ID.to_string()],
                 account_exclude: vec![],
                 account_required: vec![],
+                protocols: vec![],
+                signers: vec![],
             }],
             vec![AccountFilter { account: vec![], owner: vec![], filters: vec![] }],
             None,
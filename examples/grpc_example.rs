@@ -1,37 +1,17 @@
-use solana_streamer_sdk::{
-    match_event,
-    streaming::{
-        event_parser::{
-            common::{filter::EventTypeFilter, EventType},
-            core::account_event_parser::{NonceAccountEvent, TokenAccountEvent, TokenInfoEvent},
-            protocols::{
-                raydium_amm_v4::{
-                    parser::RAYDIUM_AMM_V4_PROGRAM_ID, RaydiumAmmV4AmmInfoAccountEvent,
-                    RaydiumAmmV4DepositEvent, RaydiumAmmV4Initialize2Event, RaydiumAmmV4SwapEvent,
-                    RaydiumAmmV4WithdrawEvent, RaydiumAmmV4WithdrawPnlEvent,
-                },
-                raydium_clmm::{
-                    parser::RAYDIUM_CLMM_PROGRAM_ID, RaydiumClmmAmmConfigAccountEvent,
-                    RaydiumClmmClosePositionEvent, RaydiumClmmCreatePoolEvent,
-                    RaydiumClmmDecreaseLiquidityV2Event, RaydiumClmmIncreaseLiquidityV2Event,
-                    RaydiumClmmOpenPositionV2Event, RaydiumClmmOpenPositionWithToken22NftEvent,
-                    RaydiumClmmPoolStateAccountEvent, RaydiumClmmSwapEvent, RaydiumClmmSwapV2Event,
-                    RaydiumClmmTickArrayStateAccountEvent,
-                },
-                raydium_cpmm::{
-                    parser::RAYDIUM_CPMM_PROGRAM_ID, RaydiumCpmmAmmConfigAccountEvent,
-                    RaydiumCpmmDepositEvent, RaydiumCpmmInitializeEvent,
-                    RaydiumCpmmPoolStateAccountEvent, RaydiumCpmmSwapEvent,
-                    RaydiumCpmmWithdrawEvent,
-                },
-                BlockMetaEvent,
-            },
-            Protocol, UnifiedEvent,
+use solana_streamer_sdk::streaming::{
+    event_parser::{
+        common::{filter::EventTypeFilter, EventType},
+        protocols::{
+            raydium_amm_v4::parser::RAYDIUM_AMM_V4_PROGRAM_ID,
+            raydium_clmm::parser::RAYDIUM_CLMM_PROGRAM_ID,
+            raydium_cpmm::parser::RAYDIUM_CPMM_PROGRAM_ID,
         },
-        grpc::ClientConfig,
-        yellowstone_grpc::{AccountFilter, TransactionFilter},
-        YellowstoneGrpc,
+        Protocol,
     },
+    grpc::ClientConfig,
+    util::{EventPrinter, PrintFormat, Verbosity},
+    yellowstone_grpc::{AccountFilter, TransactionFilter},
+    YellowstoneGrpc,
 };
 
 #[tokio::main]
@@ -56,7 +36,8 @@ async fn test_grpc() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("GRPC client created successfully");
 
-    let callback = create_event_callback();
+    let callback =
+        EventPrinter::new(PrintFormat::Detailed).with_verbosity(Verbosity::Normal).callback();
 
     // Will try to parse corresponding protocol events from transactions
     let protocols = vec![
@@ -81,6 +62,8 @@ async fn test_grpc() -> Result<(), Box<dyn std::error::Error>> {
         account_include: account_include.clone(),
         account_exclude,
         account_required,
+        protocols: vec![],
+        signers: vec![],
     };
 
     // Listen to account data belonging to owner programs -> account event monitoring
@@ -105,6 +88,10 @@ async fn test_grpc() -> Result<(), Box<dyn std::error::Error>> {
             EventType::RaydiumClmmCreatePool,
             EventType::RaydiumClmmOpenPositionWithToken22Nft,
             EventType::RaydiumClmmOpenPositionV2,
+            EventType::RaydiumClmmCollectProtocolFee,
+            EventType::RaydiumClmmCollectFundFee,
+            EventType::RaydiumClmmCollectRemainingRewards,
+            EventType::RaydiumClmmUpdateRewardInfos,
             // Raydium AMM V4 events
             EventType::RaydiumAmmV4SwapBaseIn,
             EventType::RaydiumAmmV4SwapBaseOut,
@@ -144,100 +131,3 @@ async fn test_grpc() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
-
-fn create_event_callback() -> impl Fn(Box<dyn UnifiedEvent>) {
-    |event: Box<dyn UnifiedEvent>| {
-        println!(
-            "🎉 Event received! Type: {:?}, transaction_index: {:?}",
-            event.event_type(),
-            event.transaction_index()
-        );
-        match_event!(event, {
-            // -------------------------- block meta -----------------------
-            BlockMetaEvent => |e: BlockMetaEvent| {
-                println!("BlockMetaEvent: {:?}", e.metadata.handle_us);
-            },
-            // -------------------------- raydium_cpmm -----------------------
-            RaydiumCpmmSwapEvent => |e: RaydiumCpmmSwapEvent| {
-                println!("RaydiumCpmmSwapEvent: {e:?}");
-            },
-            RaydiumCpmmDepositEvent => |e: RaydiumCpmmDepositEvent| {
-                println!("RaydiumCpmmDepositEvent: {e:?}");
-            },
-            RaydiumCpmmInitializeEvent => |e: RaydiumCpmmInitializeEvent| {
-                println!("RaydiumCpmmInitializeEvent: {e:?}");
-            },
-            RaydiumCpmmWithdrawEvent => |e: RaydiumCpmmWithdrawEvent| {
-                println!("RaydiumCpmmWithdrawEvent: {e:?}");
-            },
-            // -------------------------- raydium_clmm -----------------------
-            RaydiumClmmSwapEvent => |e: RaydiumClmmSwapEvent| {
-                println!("RaydiumClmmSwapEvent: {e:?}");
-            },
-            RaydiumClmmSwapV2Event => |e: RaydiumClmmSwapV2Event| {
-                println!("RaydiumClmmSwapV2Event: {e:?}");
-            },
-            RaydiumClmmClosePositionEvent => |e: RaydiumClmmClosePositionEvent| {
-                println!("RaydiumClmmClosePositionEvent: {e:?}");
-            },
-            RaydiumClmmDecreaseLiquidityV2Event => |e: RaydiumClmmDecreaseLiquidityV2Event| {
-                println!("RaydiumClmmDecreaseLiquidityV2Event: {e:?}");
-            },
-            RaydiumClmmCreatePoolEvent => |e: RaydiumClmmCreatePoolEvent| {
-                println!("RaydiumClmmCreatePoolEvent: {e:?}");
-            },
-            RaydiumClmmIncreaseLiquidityV2Event => |e: RaydiumClmmIncreaseLiquidityV2Event| {
-                println!("RaydiumClmmIncreaseLiquidityV2Event: {e:?}");
-            },
-            RaydiumClmmOpenPositionWithToken22NftEvent => |e: RaydiumClmmOpenPositionWithToken22NftEvent| {
-                println!("RaydiumClmmOpenPositionWithToken22NftEvent: {e:?}");
-            },
-            RaydiumClmmOpenPositionV2Event => |e: RaydiumClmmOpenPositionV2Event| {
-                println!("RaydiumClmmOpenPositionV2Event: {e:?}");
-            },
-            // -------------------------- raydium_amm_v4 -----------------------
-            RaydiumAmmV4SwapEvent => |e: RaydiumAmmV4SwapEvent| {
-                println!("RaydiumAmmV4SwapEvent: {e:?}");
-            },
-            RaydiumAmmV4DepositEvent => |e: RaydiumAmmV4DepositEvent| {
-                println!("RaydiumAmmV4DepositEvent: {e:?}");
-            },
-            RaydiumAmmV4Initialize2Event => |e: RaydiumAmmV4Initialize2Event| {
-                println!("RaydiumAmmV4Initialize2Event: {e:?}");
-            },
-            RaydiumAmmV4WithdrawEvent => |e: RaydiumAmmV4WithdrawEvent| {
-                println!("RaydiumAmmV4WithdrawEvent: {e:?}");
-            },
-            RaydiumAmmV4WithdrawPnlEvent => |e: RaydiumAmmV4WithdrawPnlEvent| {
-                println!("RaydiumAmmV4WithdrawPnlEvent: {e:?}");
-            },
-            RaydiumAmmV4AmmInfoAccountEvent => |e: RaydiumAmmV4AmmInfoAccountEvent| {
-                println!("RaydiumAmmV4AmmInfoAccountEvent: {e:?}");
-            },
-            RaydiumClmmAmmConfigAccountEvent => |e: RaydiumClmmAmmConfigAccountEvent| {
-                println!("RaydiumClmmAmmConfigAccountEvent: {e:?}");
-            },
-            RaydiumClmmPoolStateAccountEvent => |e: RaydiumClmmPoolStateAccountEvent| {
-                println!("RaydiumClmmPoolStateAccountEvent: {e:?}");
-            },
-            RaydiumClmmTickArrayStateAccountEvent => |e: RaydiumClmmTickArrayStateAccountEvent| {
-                println!("RaydiumClmmTickArrayStateAccountEvent: {e:?}");
-            },
-            RaydiumCpmmAmmConfigAccountEvent => |e: RaydiumCpmmAmmConfigAccountEvent| {
-                println!("RaydiumCpmmAmmConfigAccountEvent: {e:?}");
-            },
-            RaydiumCpmmPoolStateAccountEvent => |e: RaydiumCpmmPoolStateAccountEvent| {
-                println!("RaydiumCpmmPoolStateAccountEvent: {e:?}");
-            },
-            TokenAccountEvent => |e: TokenAccountEvent| {
-                println!("TokenAccountEvent: {e:?}");
-            },
-            NonceAccountEvent => |e: NonceAccountEvent| {
-                println!("NonceAccountEvent: {e:?}");
-            },
-            TokenInfoEvent => |e: TokenInfoEvent| {
-                println!("TokenInfoEvent: {e:?}");
-            },
-        });
-    }
-}
@@ -64,6 +64,8 @@ async fn main() -> anyhow::Result<()> {
         account_include: program_ids.clone(),
         account_exclude: vec![],
         account_required: vec![],
+        protocols: vec![],
+        signers: vec![],
     };
 
     let account_filter = AccountFilter {
@@ -0,0 +1,24 @@
+//! Upgrades a recorded event archive to the current envelope schema
+//!
+//! Usage: `cargo run --example migrate_archive -- <input> <output>`
+//!
+//! The input holds JSON lines of bare events or envelopes of any schema version. With
+//! `--features compression`, `.zst` archives are read and written (slot-framed, with a
+//! new `<output>.idx` index).
+
+use anyhow::{anyhow, Result};
+use solana_streamer_sdk::streaming::sink::{migrate_archive, ENVELOPE_SCHEMA_VERSION};
+
+fn main() -> Result<()> {
+    let usage = || anyhow!("usage: <input> <output>");
+    let mut args = std::env::args().skip(1);
+    let input = args.next().ok_or_else(usage)?;
+    let output = args.next().ok_or_else(usage)?;
+
+    let stats = migrate_archive(&input, &output)?;
+    println!(
+        "{}: {} records written to {} as schema version {}, {} upgraded",
+        input, stats.records, output, ENVELOPE_SCHEMA_VERSION, stats.upgraded
+    );
+    Ok(())
+}
@@ -49,8 +49,13 @@ async fn test_grpc() -> Result<(), Box<dyn std::error::Error>> {
     let account_required = vec![];
 
     // Listen to transaction data
-    let transaction_filter =
-        TransactionFilter { account_include, account_exclude, account_required };
+    let transaction_filter = TransactionFilter {
+        account_include,
+        account_exclude,
+        account_required,
+        protocols: vec![],
+        signers: vec![],
+    };
 
     let pump = Pubkey::from_str("pumpCmXqMfrsAkQ5r49WcJnRayYRqmXz6ae8H7H9Dfn").unwrap();
     let usdc = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
@@ -112,6 +117,7 @@ fn create_event_callback() -> impl Fn(Box<dyn UnifiedEvent>) {
             TokenAccountEvent => |e: TokenAccountEvent| {
                 println!("TokenAccount: {:?} amount: {:?}", e.pubkey, e.amount);
             },
+            _ => |_event: &dyn UnifiedEvent| {},
         });
     }
 }
@@ -37,8 +37,13 @@ async fn test_grpc() -> Result<(), Box<dyn std::error::Error>> {
     let account_required = vec![];
 
     // Listen to transaction data
-    let transaction_filter =
-        TransactionFilter { account_include, account_exclude, account_required };
+    let transaction_filter = TransactionFilter {
+        account_include,
+        account_exclude,
+        account_required,
+        protocols: vec![],
+        signers: vec![],
+    };
 
     let nonce_account = "use_your_nonce_account_here".to_string();
     // Listen to account data belonging to owner programs -> account event monitoring
@@ -0,0 +1,27 @@
+//! Raydium swaps above 1 SOL printed as JSON lines, via the pipeline builder
+use solana_streamer_sdk::prelude::*;
+
+#[tokio::main]
+async fn main() -> AnyResult<()> {
+    let store = Arc::new(EventStore::default());
+    let pipeline = PipelineBuilder::new("https://solana-yellowstone-grpc.publicnode.com:443")
+        .configure(|config| config.enable_metrics = true)
+        .without_accounts()
+        .event_types([
+            EventType::RaydiumCpmmSwapBaseInput,
+            EventType::RaydiumCpmmSwapBaseOutput,
+            EventType::RaydiumClmmSwap,
+            EventType::RaydiumClmmSwapV2,
+            EventType::RaydiumAmmV4SwapBaseIn,
+            EventType::RaydiumAmmV4SwapBaseOut,
+        ])
+        .filter(|event| TradeInfo::from_event(event).is_some_and(|t| t.amount_in >= 1_000_000_000))
+        .sink(store.clone())
+        .sink(Arc::new(JsonLinesSink::stdout()))
+        .build()?;
+
+    println!("Streaming, press Ctrl+C to stop...");
+    pipeline.run().await?;
+    println!("{} events in the last 5 minutes", store.len());
+    Ok(())
+}
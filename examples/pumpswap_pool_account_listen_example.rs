@@ -49,8 +49,13 @@ async fn test_grpc() -> Result<(), Box<dyn std::error::Error>> {
     let account_required = vec![];
 
     // Listen to transaction data
-    let transaction_filter =
-        TransactionFilter { account_include, account_exclude, account_required };
+    let transaction_filter = TransactionFilter {
+        account_include,
+        account_exclude,
+        account_required,
+        protocols: vec![],
+        signers: vec![],
+    };
 
     // Pump.fun AMM (PUMP-USDC) Market
     let pump_usdc = Pubkey::from_str("2uF4Xh61rDwxnG9woyxsVQP7zuA6kLFpb3NvnRQeoiSd").unwrap();
@@ -114,6 +119,7 @@ fn create_event_callback() -> impl Fn(Box<dyn UnifiedEvent>) {
             TokenAccountEvent => |e: TokenAccountEvent| {
                 println!("TokenAccount: {:?} amount: {:?}", e.pubkey, e.amount);
             },
+            _ => |_event: &dyn UnifiedEvent| {},
         });
     }
 }
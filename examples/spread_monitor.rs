@@ -0,0 +1,102 @@
+//! Cross-venue spread monitor: pool discovery, spread recording, throttled alerts
+//!
+//! Swaps of all Raydium venues feed a `SpreadRecorder`; every 10 seconds the latest
+//! spread of each pair is checked against a threshold (alerts rate-limited per mint by a
+//! `MintCircuitBreaker`), and every minute the spread percentiles are printed and the
+//! recording is saved to `spreads.bin` for offline threshold research.
+use std::time::Duration;
+
+use solana_streamer_sdk::prelude::*;
+use solana_streamer_sdk::streaming::analytics::{
+    CircuitBreakerConfig, MintCircuitBreaker, PoolDiscovery, SpreadRecorder, SpreadRecorderConfig,
+};
+use solana_streamer_sdk::streaming::common::{EventAgeGuard, StaleAction};
+
+/// Spread that triggers an alert
+const ALERT_THRESHOLD_BPS: f64 = 50.0;
+
+#[tokio::main]
+async fn main() -> AnyResult<()> {
+    let discovery = Arc::new(PoolDiscovery::default());
+    let recorder = Arc::new(SpreadRecorder::new(SpreadRecorderConfig::default()));
+    let breaker = Arc::new(MintCircuitBreaker::new(CircuitBreakerConfig {
+        max_emissions: 3,
+        window_ms: 60_000,
+        cooldown_ms: 300_000,
+    }));
+
+    let (pipeline_discovery, pipeline_recorder) = (discovery.clone(), recorder.clone());
+    let pipeline = PipelineBuilder::new("https://solana-yellowstone-grpc.publicnode.com:443")
+        .configure(|config| {
+            config.enable_metrics = true;
+            // 积压时的旧价格会制造虚假价差
+            config.max_event_age = Some(EventAgeGuard::new(200_000, StaleAction::Drop));
+        })
+        .without_accounts()
+        .on_event(move |event| {
+            if let Some(pool) = pipeline_discovery.on_event(event) {
+                println!(
+                    "New pool {} ({:?}): {} / {}",
+                    pool.pool, pool.protocol, pool.mint_a, pool.mint_b
+                );
+            }
+            pipeline_recorder.on_event(event);
+        })
+        .build()?;
+
+    let monitor_recorder = recorder.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(10));
+        let mut ticks = 0u64;
+        loop {
+            interval.tick().await;
+            ticks += 1;
+            let now_ms = chrono::Utc::now().timestamp_millis();
+            monitor_recorder.tick(now_ms);
+
+            for series in monitor_recorder.all_series() {
+                let last = series.len().checked_sub(1);
+                let Some(last) = last.filter(|&i| series.time_ms[i] > now_ms - 10_000) else {
+                    continue;
+                };
+                let spread_bps = series.spread_bps[last] as f64;
+                if spread_bps >= ALERT_THRESHOLD_BPS && breaker.allow(&series.token_mint) {
+                    println!(
+                        "Spread {:.1} bps on {}/{}: buy {:?}, sell {:?}",
+                        spread_bps,
+                        series.token_mint,
+                        series.quote_mint,
+                        series.low_venue[last],
+                        series.high_venue[last]
+                    );
+                }
+            }
+
+            if ticks % 6 == 0 {
+                for series in monitor_recorder.all_series().iter().filter(|s| s.len() >= 60) {
+                    let p = series.percentiles_bps(&[50.0, 95.0, 99.0]);
+                    println!(
+                        "{}/{}: p50 {:?} p95 {:?} p99 {:?} bps, {:.1}% of samples >= {} bps",
+                        series.token_mint,
+                        series.quote_mint,
+                        p[0],
+                        p[1],
+                        p[2],
+                        series.fraction_at_least(ALERT_THRESHOLD_BPS) * 100.0,
+                        ALERT_THRESHOLD_BPS
+                    );
+                }
+                println!("Alerts suppressed: {}", breaker.total_suppressed());
+                if let Err(e) = monitor_recorder.save("spreads.bin") {
+                    eprintln!("Failed to save spread recording: {}", e);
+                }
+            }
+        }
+    });
+
+    println!("Streaming, press Ctrl+C to stop...");
+    pipeline.run().await?;
+    recorder.save("spreads.bin")?;
+    println!("{} pools discovered", discovery.len());
+    Ok(())
+}
@@ -41,8 +41,13 @@ async fn test_grpc() -> Result<(), Box<dyn std::error::Error>> {
     let account_required = vec![];
 
     // Listen to transaction data
-    let transaction_filter =
-        TransactionFilter { account_include, account_exclude, account_required };
+    let transaction_filter = TransactionFilter {
+        account_include,
+        account_exclude,
+        account_required,
+        protocols: vec![],
+        signers: vec![],
+    };
 
     let account_to_listen = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string();
 
@@ -86,6 +91,7 @@ fn create_event_callback() -> impl Fn(Box<dyn UnifiedEvent>) {
             TokenInfoEvent => |e: TokenInfoEvent| {
                 println!("TokenInfoEvent: {:?}", e.decimals);
             },
+            _ => |_event: &dyn UnifiedEvent| {},
         });
     }
 }
@@ -0,0 +1,230 @@
+//! C ABI for embedding the streamer in non-Rust programs (feature `ffi`)
+//!
+//! Generate the header with `cbindgen --config cbindgen.toml --output solana_streamer.h`.
+//! Functions return 0 / non-null on success; on failure the message is available
+//! from [`ss_last_error`] on the same thread.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::ptr;
+
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use yellowstone_grpc_proto::geyser::CommitmentLevel;
+
+use crate::common::AnyResult;
+use crate::streaming::common::StreamClientConfig;
+use crate::streaming::event_parser::common::filter::EventTypeFilter;
+use crate::streaming::event_parser::{Protocol, UnifiedEvent};
+use crate::streaming::sink::event_envelope;
+use crate::streaming::yellowstone_grpc::{AccountFilter, TransactionFilter};
+use crate::streaming::YellowstoneGrpc;
+
+/// Receives one JSON event envelope; the string is only valid during the call
+pub type SsEventCallback = extern "C" fn(event_json: *const c_char, user_data: *mut c_void);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Opaque streamer handle
+pub struct SsStreamer {
+    runtime: tokio::runtime::Runtime,
+    grpc: YellowstoneGrpc,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct StreamerConfig {
+    endpoint: String,
+    #[serde(default)]
+    x_token: Option<String>,
+    #[serde(default)]
+    client: StreamClientConfig,
+}
+
+#[derive(Deserialize)]
+struct FfiAccountFilter {
+    #[serde(default)]
+    account: Vec<String>,
+    #[serde(default)]
+    owner: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SubscribeConfig {
+    protocols: Vec<Protocol>,
+    #[serde(default)]
+    bot_wallet: Option<String>,
+    #[serde(default)]
+    transaction_filters: Vec<TransactionFilter>,
+    #[serde(default)]
+    account_filters: Vec<FfiAccountFilter>,
+    #[serde(default)]
+    event_type_filter: Option<EventTypeFilter>,
+    /// "processed" / "confirmed" / "finalized"
+    #[serde(default)]
+    commitment: Option<String>,
+}
+
+impl StreamerConfig {
+    /// Runtime owning the stream tasks, and the client
+    pub(crate) fn build(self) -> AnyResult<(tokio::runtime::Runtime, YellowstoneGrpc)> {
+        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
+        let grpc = {
+            let _guard = runtime.enter();
+            YellowstoneGrpc::new_with_config(self.endpoint, self.x_token, self.client)?
+        };
+        Ok((runtime, grpc))
+    }
+}
+
+impl SubscribeConfig {
+    pub(crate) async fn subscribe(
+        self,
+        grpc: &YellowstoneGrpc,
+        callback: impl Fn(Box<dyn UnifiedEvent>) + Send + Sync + 'static,
+    ) -> AnyResult<()> {
+        let bot_wallet = self.bot_wallet.map(|wallet| wallet.parse::<Pubkey>()).transpose()?;
+        let commitment = self.commitment.as_deref().map(parse_commitment).transpose()?;
+        let account_filters = self
+            .account_filters
+            .into_iter()
+            .map(|filter| AccountFilter {
+                account: filter.account,
+                owner: filter.owner,
+                filters: vec![],
+            })
+            .collect();
+        grpc.subscribe_events_immediate(
+            self.protocols,
+            bot_wallet,
+            self.transaction_filters,
+            account_filters,
+            self.event_type_filter,
+            commitment,
+            callback,
+        )
+        .await
+    }
+}
+
+/// Callback target; the caller guarantees `user_data` may be used from the stream threads
+struct CallbackTarget {
+    callback: SsEventCallback,
+    user_data: *mut c_void,
+}
+
+unsafe impl Send for CallbackTarget {}
+unsafe impl Sync for CallbackTarget {}
+
+impl CallbackTarget {
+    fn deliver(&self, event: &dyn UnifiedEvent) {
+        // JSON 不会包含内部 NUL
+        if let Ok(json) = CString::new(event_envelope(event).to_string()) {
+            (self.callback)(json.as_ptr(), self.user_data);
+        }
+    }
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+unsafe fn read_json<'a, T: Deserialize<'a>>(json: *const c_char) -> AnyResult<T> {
+    if json.is_null() {
+        return Err(anyhow::anyhow!("null JSON argument"));
+    }
+    Ok(serde_json::from_str(CStr::from_ptr(json).to_str()?)?)
+}
+
+fn parse_commitment(commitment: &str) -> AnyResult<CommitmentLevel> {
+    CommitmentLevel::from_str_name(&commitment.to_ascii_uppercase())
+        .ok_or_else(|| anyhow::anyhow!("unknown commitment: {}", commitment))
+}
+
+/// Last error message of the calling thread, null if none; owned by the library
+#[no_mangle]
+pub extern "C" fn ss_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Creates a streamer from `{"endpoint": ..., "x_token": ..., "client": {StreamClientConfig}}`
+///
+/// # Safety
+/// `config_json` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn ss_streamer_new(config_json: *const c_char) -> *mut SsStreamer {
+    let result = read_json(config_json).and_then(StreamerConfig::build);
+    match result {
+        Ok((runtime, grpc)) => Box::into_raw(Box::new(SsStreamer { runtime, grpc })),
+        Err(e) => {
+            set_last_error(e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Starts a subscription; events are delivered to `callback` from the stream threads
+///
+/// `subscription_json`: `{"protocols": [...], "transaction_filters": [...],
+/// "account_filters": [{"account": [...], "owner": [...]}], "event_type_filter": {...},
+/// "bot_wallet": "...", "commitment": "processed"}`
+///
+/// # Safety
+/// `streamer` must come from [`ss_streamer_new`], `subscription_json` must be a valid
+/// NUL-terminated string, and `user_data` must stay valid until [`ss_streamer_free`].
+/// Do not call back into this API from inside `callback`.
+#[no_mangle]
+pub unsafe extern "C" fn ss_streamer_subscribe(
+    streamer: *mut SsStreamer,
+    subscription_json: *const c_char,
+    callback: SsEventCallback,
+    user_data: *mut c_void,
+) -> c_int {
+    let Some(streamer) = streamer.as_ref() else {
+        set_last_error("null streamer".to_string());
+        return -1;
+    };
+    let target = CallbackTarget { callback, user_data };
+    let result = read_json::<SubscribeConfig>(subscription_json).and_then(|config| {
+        streamer
+            .runtime
+            .block_on(config.subscribe(&streamer.grpc, move |event| target.deliver(event.as_ref())))
+    });
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            set_last_error(e.to_string());
+            -1
+        }
+    }
+}
+
+/// Stops the current subscription; callbacks already in flight may still complete
+///
+/// # Safety
+/// `streamer` must come from [`ss_streamer_new`].
+#[no_mangle]
+pub unsafe extern "C" fn ss_streamer_stop(streamer: *mut SsStreamer) -> c_int {
+    let Some(streamer) = streamer.as_ref() else {
+        set_last_error("null streamer".to_string());
+        return -1;
+    };
+    streamer.runtime.block_on(streamer.grpc.stop());
+    0
+}
+
+/// Stops and releases the streamer
+///
+/// # Safety
+/// `streamer` must come from [`ss_streamer_new`] and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ss_streamer_free(streamer: *mut SsStreamer) {
+    if streamer.is_null() {
+        return;
+    }
+    let streamer = Box::from_raw(streamer);
+    streamer.runtime.block_on(streamer.grpc.stop());
+    drop(streamer);
+}
@@ -1,3 +1,11 @@
 pub mod common;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "transport")]
+pub mod pipeline;
+pub mod prelude;
+#[cfg(feature = "transport")]
 pub mod protos;
+#[cfg(feature = "python")]
+mod python;
 pub mod streaming;
@@ -0,0 +1,580 @@
+//! End-to-end pipeline: gRPC source → parser → enrichers → filters → sinks / handlers
+//!
+//! ```rust,ignore
+//! use solana_streamer_sdk::prelude::*;
+//!
+//! PipelineBuilder::new("https://solana-yellowstone-grpc.publicnode.com:443")
+//!     .protocols([Protocol::RaydiumCpmm])
+//!     .filter_expr(r#"protocol == "RaydiumCpmm" && amount_in > 1e9"#)?
+//!     .sink(Arc::new(JsonLinesSink::stdout()))
+//!     .build()?
+//!     .run()
+//!     .await?;
+//! ```
+#[cfg(feature = "archive")]
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use solana_sdk::pubkey::Pubkey;
+use yellowstone_grpc_proto::geyser::CommitmentLevel;
+
+use crate::common::{AnyResult, SolanaRpcClient};
+#[cfg(feature = "archive")]
+use crate::streaming::archive::{read_car_slot, CarReader};
+use crate::streaming::common::StreamClientConfig;
+use crate::streaming::event_parser::common::filter::EventTypeFilter;
+use crate::streaming::event_parser::common::filter_expr::EventFilterExpr;
+use crate::streaming::event_parser::common::EventType;
+use crate::streaming::event_parser::core::event_parser::EventParser;
+use crate::streaming::event_parser::{Protocol, UnifiedEvent};
+use crate::streaming::sink::EventSink;
+use crate::streaming::store::fetch_block_events;
+use crate::streaming::yellowstone_grpc::{AccountFilter, TransactionFilter};
+use crate::streaming::YellowstoneGrpc;
+
+type Enricher = Arc<dyn Fn(&mut dyn UnifiedEvent) + Send + Sync>;
+type Predicate = Arc<dyn Fn(&dyn UnifiedEvent) -> bool + Send + Sync>;
+type Handler = Arc<dyn Fn(&dyn UnifiedEvent) + Send + Sync>;
+type Callback = Arc<dyn Fn(Box<dyn UnifiedEvent>) + Send + Sync>;
+
+/// Fluent setup of a [`Pipeline`]
+///
+/// Defaults: low-latency client config, every compiled-in protocol, transactions and
+/// accounts of those protocols' programs, no event type filter.
+pub struct PipelineBuilder {
+    endpoint: String,
+    x_token: Option<String>,
+    config: StreamClientConfig,
+    protocols: Vec<Protocol>,
+    bot_wallet: Option<Pubkey>,
+    commitment: Option<CommitmentLevel>,
+    transaction_filters: Vec<TransactionFilter>,
+    account_filters: Vec<AccountFilter>,
+    subscribe_accounts: bool,
+    event_types: Vec<EventType>,
+    enrichers: Vec<Enricher>,
+    filters: Vec<Predicate>,
+    sinks: Vec<Arc<dyn EventSink>>,
+    handlers: Vec<Handler>,
+    callback: Option<Callback>,
+    rpc: Option<Arc<SolanaRpcClient>>,
+    #[cfg(feature = "archive")]
+    archives: Vec<PathBuf>,
+}
+
+impl PipelineBuilder {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            x_token: None,
+            config: StreamClientConfig::low_latency(),
+            protocols: [Protocol::RaydiumCpmm, Protocol::RaydiumClmm, Protocol::RaydiumAmmV4]
+                .into_iter()
+                .filter(Protocol::is_enabled)
+                .collect(),
+            bot_wallet: None,
+            commitment: None,
+            transaction_filters: Vec::new(),
+            account_filters: Vec::new(),
+            subscribe_accounts: true,
+            event_types: Vec::new(),
+            enrichers: Vec::new(),
+            filters: Vec::new(),
+            sinks: Vec::new(),
+            handlers: Vec::new(),
+            callback: None,
+            rpc: None,
+            #[cfg(feature = "archive")]
+            archives: Vec::new(),
+        }
+    }
+
+    pub fn x_token(mut self, x_token: impl Into<String>) -> Self {
+        self.x_token = Some(x_token.into());
+        self
+    }
+
+    pub fn config(mut self, config: StreamClientConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Adjusts the client config in place, e.g. `.configure(|c| c.enable_metrics = true)`
+    pub fn configure(mut self, f: impl FnOnce(&mut StreamClientConfig)) -> Self {
+        f(&mut self.config);
+        self
+    }
+
+    pub fn protocols(mut self, protocols: impl IntoIterator<Item = Protocol>) -> Self {
+        self.protocols = protocols.into_iter().collect();
+        self
+    }
+
+    pub fn bot_wallet(mut self, bot_wallet: Pubkey) -> Self {
+        self.bot_wallet = Some(bot_wallet);
+        self
+    }
+
+    pub fn commitment(mut self, commitment: CommitmentLevel) -> Self {
+        self.commitment = Some(commitment);
+        self
+    }
+
+    /// Adds a transaction filter; once any is added, the default filter (programs of
+    /// the protocols) is not used. Repeated calls add further filters.
+    pub fn transaction_filter(mut self, filter: TransactionFilter) -> Self {
+        self.transaction_filters.push(filter);
+        self
+    }
+
+    /// Adds an account filter; once any is added, the default filter (accounts owned by
+    /// the protocols' programs) is not used. Repeated calls add further filters.
+    pub fn account_filter(mut self, filter: AccountFilter) -> Self {
+        self.account_filters.push(filter);
+        self
+    }
+
+    /// Transactions only, no account subscription
+    pub fn without_accounts(mut self) -> Self {
+        self.subscribe_accounts = false;
+        self
+    }
+
+    /// Only parse these event types
+    pub fn event_types(mut self, event_types: impl IntoIterator<Item = EventType>) -> Self {
+        self.event_types.extend(event_types);
+        self
+    }
+
+    /// Post-parse filter expression, see [`EventFilterExpr`]
+    pub fn filter_expr(mut self, expr: &str) -> AnyResult<Self> {
+        self.config.event_filter = Some(EventFilterExpr::parse(expr)?);
+        Ok(self)
+    }
+
+    /// Mutates events before filtering, in registration order
+    pub fn enrich(mut self, f: impl Fn(&mut dyn UnifiedEvent) + Send + Sync + 'static) -> Self {
+        self.enrichers.push(Arc::new(f));
+        self
+    }
+
+    /// Keeps events for which every predicate returns true
+    pub fn filter(mut self, f: impl Fn(&dyn UnifiedEvent) -> bool + Send + Sync + 'static) -> Self {
+        self.filters.push(Arc::new(f));
+        self
+    }
+
+    /// Writes kept events to the sink, errors are logged
+    pub fn sink(mut self, sink: Arc<dyn EventSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Observes kept events, e.g. a detector's `on_event`
+    pub fn on_event(mut self, f: impl Fn(&dyn UnifiedEvent) + Send + Sync + 'static) -> Self {
+        self.handlers.push(Arc::new(f));
+        self
+    }
+
+    /// Takes ownership of kept events after sinks and handlers
+    pub fn callback(mut self, f: impl Fn(Box<dyn UnifiedEvent>) + Send + Sync + 'static) -> Self {
+        self.callback = Some(Arc::new(f));
+        self
+    }
+
+    /// RPC client used to re-fetch blocks, see [`Pipeline::reprocess_slot`]
+    pub fn rpc(mut self, rpc: Arc<SolanaRpcClient>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+
+    /// Old Faithful epoch CAR searched by [`Pipeline::reprocess_slot`] before the RPC;
+    /// adds to earlier archives, which are searched in order
+    #[cfg(feature = "archive")]
+    pub fn archive(mut self, path: impl Into<PathBuf>) -> Self {
+        self.archives.push(path.into());
+        self
+    }
+
+    pub fn build(self) -> AnyResult<Pipeline> {
+        let client = YellowstoneGrpc::new_with_config(self.endpoint, self.x_token, self.config)?;
+        let programs: Vec<String> = self
+            .protocols
+            .iter()
+            .flat_map(|protocol| protocol.get_program_id())
+            .map(|program_id| program_id.to_string())
+            .collect();
+        let mut transaction_filters = self.transaction_filters;
+        if transaction_filters.is_empty() {
+            transaction_filters.push(TransactionFilter {
+                account_include: programs.clone(),
+                ..Default::default()
+            });
+        }
+        let mut account_filters = self.account_filters;
+        if !self.subscribe_accounts {
+            account_filters.clear();
+        } else if account_filters.is_empty() {
+            account_filters.push(AccountFilter {
+                account: vec![],
+                owner: programs,
+                filters: vec![],
+            });
+        }
+        let event_type_filter =
+            (!self.event_types.is_empty()).then(|| EventTypeFilter { include: self.event_types });
+        Ok(Pipeline {
+            client,
+            protocols: self.protocols,
+            bot_wallet: self.bot_wallet,
+            commitment: self.commitment,
+            transaction_filters,
+            account_filters,
+            event_type_filter,
+            rpc: self.rpc,
+            #[cfg(feature = "archive")]
+            archives: self.archives,
+            stages: Arc::new(Stages {
+                enrichers: self.enrichers,
+                filters: self.filters,
+                sinks: self.sinks,
+                handlers: self.handlers,
+                callback: self.callback,
+            }),
+        })
+    }
+}
+
+struct Stages {
+    enrichers: Vec<Enricher>,
+    filters: Vec<Predicate>,
+    sinks: Vec<Arc<dyn EventSink>>,
+    handlers: Vec<Handler>,
+    callback: Option<Callback>,
+}
+
+impl Stages {
+    fn process(&self, mut event: Box<dyn UnifiedEvent>) {
+        for enrich in &self.enrichers {
+            enrich(event.as_mut());
+        }
+        if !self.filters.iter().all(|keep| keep(event.as_ref())) {
+            return;
+        }
+        for sink in &self.sinks {
+            if let Err(e) = sink.send(event.as_ref()) {
+                log::error!("Failed to write event to sink: {}", e);
+            }
+        }
+        for handler in &self.handlers {
+            handler(event.as_ref());
+        }
+        if let Some(callback) = self.callback.as_ref() {
+            callback(event);
+        }
+    }
+
+    /// Enriches, filters and writes a re-parsed event to the sinks only; handlers and
+    /// the callback already acted on the live stream
+    fn replay(&self, mut event: Box<dyn UnifiedEvent>) -> bool {
+        for enrich in &self.enrichers {
+            enrich(event.as_mut());
+        }
+        if !self.filters.iter().all(|keep| keep(event.as_ref())) {
+            return false;
+        }
+        for sink in &self.sinks {
+            if let Err(e) = sink.send(event.as_ref()) {
+                log::error!("Failed to write replayed event to sink: {}", e);
+            }
+        }
+        true
+    }
+}
+
+/// A configured stream, created by [`PipelineBuilder::build`]
+pub struct Pipeline {
+    client: YellowstoneGrpc,
+    protocols: Vec<Protocol>,
+    bot_wallet: Option<Pubkey>,
+    commitment: Option<CommitmentLevel>,
+    transaction_filters: Vec<TransactionFilter>,
+    account_filters: Vec<AccountFilter>,
+    event_type_filter: Option<EventTypeFilter>,
+    rpc: Option<Arc<SolanaRpcClient>>,
+    #[cfg(feature = "archive")]
+    archives: Vec<PathBuf>,
+    stages: Arc<Stages>,
+}
+
+impl Pipeline {
+    /// The underlying client, e.g. for metrics or runtime subscription updates
+    pub fn client(&self) -> &YellowstoneGrpc {
+        &self.client
+    }
+
+    /// Subscribes and returns once the stream is running
+    pub async fn start(&self) -> AnyResult<()> {
+        let stages = self.stages.clone();
+        self.client
+            .subscribe_events_immediate(
+                self.protocols.clone(),
+                self.bot_wallet,
+                self.transaction_filters.clone(),
+                self.account_filters.clone(),
+                self.event_type_filter.clone(),
+                self.commitment,
+                move |event| stages.process(event),
+            )
+            .await
+    }
+
+    /// Runs until Ctrl+C, then stops the stream and flushes the sinks
+    pub async fn run(&self) -> AnyResult<()> {
+        self.start().await?;
+        tokio::signal::ctrl_c().await?;
+        self.stop().await;
+        Ok(())
+    }
+
+    /// Re-parses a finalized slot and re-emits its events to the sinks
+    ///
+    /// For healing downstream stores after a detected gap (e.g. a `SlotAuditor` report)
+    /// or a parser fix. The block is read from the archives set with
+    /// `PipelineBuilder::archive` when one holds the slot, otherwise fetched via the RPC
+    /// client set with [`PipelineBuilder::rpc`], and parsed with the pipeline's protocols
+    /// and event types; events are flagged `metadata.replayed`, pass enrichers and
+    /// filters, and go to the sinks only. Sinks are flushed afterwards. Returns the
+    /// number of events written.
+    pub async fn reprocess_slot(&self, slot: u64) -> AnyResult<usize> {
+        let mut parser = EventParser::new(self.protocols.clone(), self.event_type_filter.clone());
+        parser.protocol_switches = self.client.event_processor.protocol_switches.clone();
+        for protocol_config in &self.client.config.protocol_configs {
+            parser.add_protocol_config(protocol_config)?;
+        }
+        let parser = Arc::new(parser);
+        let events = match self.archive_events(&parser, slot).await? {
+            Some(events) => events,
+            None => {
+                let rpc = self.rpc.as_ref().ok_or_else(|| {
+                    anyhow!("slot {} is not in an archive and no RPC client is set", slot)
+                })?;
+                fetch_block_events(rpc, &parser, slot, false).await?
+            }
+        };
+        let written = self.replay_events(events)?;
+        log::info!("Reprocessed slot {}: {} events", slot, written);
+        Ok(written)
+    }
+
+    /// Events of `slot` from the first archive holding its block
+    #[cfg(feature = "archive")]
+    async fn archive_events(
+        &self,
+        parser: &Arc<EventParser>,
+        slot: u64,
+    ) -> AnyResult<Option<Vec<Box<dyn UnifiedEvent>>>> {
+        for path in &self.archives {
+            let (path, parser) = (path.clone(), parser.clone());
+            // 扫描 CAR 是阻塞 IO
+            let events = tokio::task::spawn_blocking(move || {
+                read_car_slot(CarReader::open(path)?, &parser, slot)
+            })
+            .await??;
+            if events.is_some() {
+                return Ok(events);
+            }
+        }
+        Ok(None)
+    }
+
+    #[cfg(not(feature = "archive"))]
+    async fn archive_events(
+        &self,
+        _parser: &Arc<EventParser>,
+        _slot: u64,
+    ) -> AnyResult<Option<Vec<Box<dyn UnifiedEvent>>>> {
+        Ok(None)
+    }
+
+    /// Flags, filters and writes re-parsed events to the sinks, then flushes them
+    fn replay_events(&self, events: Vec<Box<dyn UnifiedEvent>>) -> AnyResult<usize> {
+        let event_filter = self.client.config.event_filter.as_ref();
+        let mut written = 0;
+        for mut event in events {
+            event.metadata_mut().replayed = true;
+            if event_filter.is_some_and(|filter| !filter.matches(event.as_ref())) {
+                continue;
+            }
+            written += self.stages.replay(event) as usize;
+        }
+        for sink in &self.stages.sinks {
+            sink.flush()?;
+        }
+        Ok(written)
+    }
+
+    /// Stops the stream and flushes the sinks
+    pub async fn stop(&self) {
+        self.client.stop().await;
+        for sink in &self.stages.sinks {
+            if let Err(e) = sink.flush() {
+                log::error!("Failed to flush sink: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    const ENDPOINT: &str = "http://127.0.0.1:10000";
+
+    /// Appends `name` to the shared log for every event
+    struct LogSink {
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl EventSink for LogSink {
+        fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+            let replayed = if event.metadata().replayed { ":replayed" } else { "" };
+            self.log.lock().push(format!("sink:{}{}", event.slot(), replayed));
+            Ok(())
+        }
+
+        fn flush(&self) -> AnyResult<()> {
+            self.log.lock().push("flush".to_string());
+            Ok(())
+        }
+    }
+
+    fn swap(slot: u64) -> Box<dyn UnifiedEvent> {
+        let mut event = RaydiumCpmmSwapEvent::default();
+        event.metadata.slot = slot;
+        Box::new(event)
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        let programs: Vec<String> =
+            Protocol::RaydiumCpmm.get_program_id().iter().map(|p| p.to_string()).collect();
+        let pipeline =
+            PipelineBuilder::new(ENDPOINT).protocols([Protocol::RaydiumCpmm]).build().unwrap();
+        assert_eq!(pipeline.transaction_filters.len(), 1);
+        assert_eq!(pipeline.transaction_filters[0].account_include, programs);
+        assert_eq!(pipeline.account_filters.len(), 1);
+        assert_eq!(pipeline.account_filters[0].owner, programs);
+        assert!(pipeline.event_type_filter.is_none());
+
+        let pipeline = PipelineBuilder::new(ENDPOINT)
+            .protocols([Protocol::RaydiumCpmm])
+            .without_accounts()
+            .event_types([EventType::RaydiumCpmmSwapBaseInput])
+            .event_types([EventType::RaydiumCpmmSwapBaseOutput])
+            .build()
+            .unwrap();
+        assert!(pipeline.account_filters.is_empty());
+        assert_eq!(pipeline.transaction_filters[0].account_include, programs);
+        assert_eq!(
+            pipeline.event_type_filter.unwrap().include,
+            vec![EventType::RaydiumCpmmSwapBaseInput, EventType::RaydiumCpmmSwapBaseOutput]
+        );
+    }
+
+    #[test]
+    fn test_custom_filters_replace_defaults() {
+        let wallet = Pubkey::new_unique();
+        let pipeline = PipelineBuilder::new(ENDPOINT)
+            .protocols([Protocol::RaydiumCpmm])
+            .transaction_filter(TransactionFilter::for_wallets(&[wallet]))
+            .transaction_filter(TransactionFilter::default())
+            .account_filter(AccountFilter {
+                account: vec![wallet.to_string()],
+                owner: vec![],
+                filters: vec![],
+            })
+            .build()
+            .unwrap();
+        // 自定义过滤器累加，且不再包含默认过滤器
+        assert_eq!(pipeline.transaction_filters.len(), 2);
+        assert_eq!(pipeline.transaction_filters[0].account_include, vec![wallet.to_string()]);
+        assert_eq!(pipeline.account_filters.len(), 1);
+        assert!(pipeline.account_filters[0].owner.is_empty());
+    }
+
+    #[test]
+    fn test_stage_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let logger = |name: &'static str| {
+            let log = log.clone();
+            move |event: &dyn UnifiedEvent| log.lock().push(format!("{}:{}", name, event.slot()))
+        };
+        let enrich_log = log.clone();
+        let callback_log = log.clone();
+        let pipeline = PipelineBuilder::new(ENDPOINT)
+            .enrich(move |event: &mut dyn UnifiedEvent| {
+                enrich_log.lock().push(format!("enrich:{}", event.slot()));
+                event.metadata_mut().slot += 1;
+            })
+            .filter({
+                let log = logger("filter");
+                move |event: &dyn UnifiedEvent| {
+                    log(event);
+                    event.slot() < 10
+                }
+            })
+            .sink(Arc::new(LogSink { log: log.clone() }))
+            .on_event(logger("handler"))
+            .callback(move |event| callback_log.lock().push(format!("callback:{}", event.slot())))
+            .build()
+            .unwrap();
+
+        pipeline.stages.process(swap(1));
+        // 被过滤的事件不进入后续阶段
+        pipeline.stages.process(swap(20));
+        assert_eq!(
+            *log.lock(),
+            ["enrich:1", "filter:2", "sink:2", "handler:2", "callback:2", "enrich:20", "filter:21"]
+        );
+    }
+
+    #[test]
+    fn test_replay_events() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let handler_log = log.clone();
+        let pipeline = PipelineBuilder::new(ENDPOINT)
+            .filter_expr("slot < 10")
+            .unwrap()
+            .sink(Arc::new(LogSink { log: log.clone() }))
+            .sink(Arc::new(LogSink { log: log.clone() }))
+            .on_event(move |event| handler_log.lock().push(format!("handler:{}", event.slot())))
+            .build()
+            .unwrap();
+
+        let written = pipeline.replay_events(vec![swap(1), swap(20), swap(3)]).unwrap();
+        // 回放的事件带 replayed 标记、经过 event_filter、只写入 sink，且每个 sink 都被 flush
+        assert_eq!(written, 2);
+        assert_eq!(
+            *log.lock(),
+            [
+                "sink:1:replayed",
+                "sink:1:replayed",
+                "sink:3:replayed",
+                "sink:3:replayed",
+                "flush",
+                "flush"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_slot_without_source() {
+        let pipeline = PipelineBuilder::new(ENDPOINT).build().unwrap();
+        assert!(pipeline.reprocess_slot(1).await.is_err());
+    }
+}
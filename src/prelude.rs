@@ -0,0 +1,26 @@
+//! Commonly used types, `use solana_streamer_sdk::prelude::*;`
+pub use std::sync::Arc;
+
+pub use solana_sdk::pubkey::Pubkey;
+pub use solana_sdk::signature::Signature;
+
+pub use crate::common::AnyResult;
+pub use crate::match_event;
+pub use crate::streaming::analytics::TradeInfo;
+pub use crate::streaming::common::ExpressLane;
+pub use crate::streaming::event_parser::common::filter::EventTypeFilter;
+pub use crate::streaming::event_parser::common::filter_expr::EventFilterExpr;
+pub use crate::streaming::event_parser::common::{EventMetadata, EventType, ProtocolType};
+pub use crate::streaming::event_parser::core::event_parser::EventParser;
+pub use crate::streaming::event_parser::{Protocol, UnifiedEvent};
+pub use crate::streaming::sink::{sink_callback, EventSink, JsonLinesSink};
+pub use crate::streaming::store::EventStore;
+
+#[cfg(feature = "transport")]
+pub use crate::pipeline::{Pipeline, PipelineBuilder};
+#[cfg(feature = "transport")]
+pub use crate::streaming::common::StreamClientConfig;
+#[cfg(feature = "transport")]
+pub use crate::streaming::yellowstone_grpc::{AccountFilter, TransactionFilter};
+#[cfg(feature = "transport")]
+pub use crate::streaming::{ShredStreamGrpc, YellowstoneGrpc};
@@ -0,0 +1,126 @@
+//! Python bindings (feature `python`), built with maturin
+//!
+//! ```python
+//! import solana_streamer_sdk as ss
+//!
+//! stream = ss.subscribe({
+//!     "endpoint": "https://solana-yellowstone-grpc.publicnode.com:443",
+//!     "protocols": ["RaydiumCpmm"],
+//! })
+//! for event in stream:
+//!     print(event["event_type"], event["signature"])
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crossbeam::channel::{bounded, Receiver, RecvTimeoutError};
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::Deserialize;
+
+use crate::ffi::{StreamerConfig, SubscribeConfig};
+use crate::streaming::sink::event_envelope;
+use crate::streaming::YellowstoneGrpc;
+
+/// Events buffered for the Python side before new ones are dropped
+pub const DEFAULT_PY_BACKLOG: usize = 10_000;
+/// How often a blocked `__next__` checks for Ctrl-C
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Deserialize)]
+struct PyStreamConfig {
+    #[serde(flatten)]
+    streamer: StreamerConfig,
+    #[serde(flatten)]
+    subscription: SubscribeConfig,
+    #[serde(default = "default_backlog")]
+    backlog: usize,
+}
+
+fn default_backlog() -> usize {
+    DEFAULT_PY_BACKLOG
+}
+
+/// Iterator of event envelopes as dicts
+#[pyclass(module = "solana_streamer_sdk")]
+pub struct EventStream {
+    runtime: tokio::runtime::Runtime,
+    grpc: YellowstoneGrpc,
+    events: Receiver<String>,
+    dropped: Arc<AtomicU64>,
+}
+
+#[pymethods]
+impl EventStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Blocks until the next event; the GIL is released while waiting
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        loop {
+            match py.allow_threads(|| self.events.recv_timeout(SIGNAL_CHECK_INTERVAL)) {
+                Ok(json) => {
+                    let event = py.import_bound("json")?.call_method1("loads", (json,))?;
+                    return Ok(Some(event.unbind()));
+                }
+                Err(RecvTimeoutError::Timeout) => py.check_signals()?,
+                Err(RecvTimeoutError::Disconnected) => return Ok(None),
+            }
+        }
+    }
+
+    /// Events discarded because the consumer fell more than `backlog` behind
+    #[getter]
+    fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Stops the subscription; events already buffered can still be read
+    fn close(&self, py: Python<'_>) {
+        py.allow_threads(|| self.runtime.block_on(self.grpc.stop()));
+    }
+}
+
+/// Subscribes with a config dict (or JSON string) and returns an [`EventStream`]
+///
+/// The config combines the C ABI's streamer and subscription objects, plus an
+/// optional `backlog`.
+#[pyfunction]
+fn subscribe(py: Python<'_>, config: &Bound<'_, PyAny>) -> PyResult<EventStream> {
+    let json: String = if config.is_instance_of::<PyDict>() {
+        py.import_bound("json")?.call_method1("dumps", (config,))?.extract()?
+    } else {
+        config.extract()?
+    };
+    let config: PyStreamConfig =
+        serde_json::from_str(&json).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    let (runtime, grpc) =
+        config.streamer.build().map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    let (tx, events) = bounded(config.backlog.max(1));
+    let dropped = Arc::new(AtomicU64::new(0));
+    let dropped_counter = dropped.clone();
+    let subscription = config.subscription;
+    py.allow_threads(|| {
+        runtime.block_on(subscription.subscribe(&grpc, move |event| {
+            // Python 侧跟不上时丢弃，避免阻塞解析线程
+            if tx.try_send(event_envelope(event.as_ref()).to_string()).is_err() {
+                dropped_counter.fetch_add(1, Ordering::Relaxed);
+            }
+        }))
+    })
+    .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    Ok(EventStream { runtime, grpc, events, dropped })
+}
+
+#[pymodule]
+fn solana_streamer_sdk(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<EventStream>()?;
+    m.add_function(wrap_pyfunction!(subscribe, m)?)?;
+    Ok(())
+}
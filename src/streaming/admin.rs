@@ -0,0 +1,178 @@
+//! Minimal admin HTTP endpoint for runtime introspection
+//!
+//! ```text
+//! GET    /health            -> {"status": "ok", "subscribed": true, ...}
+//! GET    /metrics           -> PerformanceMetrics
+//! GET    /filters           -> live SubscribeRequest, see `SubscriptionPlan::describe`
+//! GET    /caches            -> MemoryReport
+//! GET    /watchlist         -> ["mint", ...]
+//! POST   /watchlist/<mint>  -> watch the mint
+//! DELETE /watchlist/<mint>  -> stop watching it
+//! GET    /paused            -> ["RaydiumClmm", ...]
+//! POST   /paused/<protocol> -> stop parsing the protocol
+//! DELETE /paused/<protocol> -> resume it
+//! POST   /flush             -> flush the registered sinks (checkpoints exactly-once sinks)
+//! ```
+//!
+//! There is no authentication, bind it to a loopback or otherwise private address.
+
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::common::AnyResult;
+use crate::streaming::event_parser::Protocol;
+use crate::streaming::grpc::SubscriptionPlan;
+use crate::streaming::sink::EventSink;
+use crate::streaming::YellowstoneGrpc;
+
+/// Embedded admin server of a [`YellowstoneGrpc`] client
+#[derive(Clone)]
+pub struct AdminServer {
+    client: YellowstoneGrpc,
+    sinks: Vec<Arc<dyn EventSink>>,
+}
+
+impl AdminServer {
+    pub fn new(client: YellowstoneGrpc) -> Self {
+        Self { client, sinks: Vec::new() }
+    }
+
+    /// Registers a sink flushed by `POST /flush`
+    pub fn with_sink(mut self, sink: Arc<dyn EventSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Serves requests until the returned task is aborted
+    pub async fn serve(self, addr: SocketAddr) -> AnyResult<JoinHandle<()>> {
+        let listener = TcpListener::bind(addr).await?;
+        log::info!("Admin server listening on {}", listener.local_addr()?);
+        let server = Arc::new(self);
+        Ok(tokio::spawn(async move {
+            loop {
+                let (stream, peer) = match listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        log::warn!("Admin server accept failed: {}", e);
+                        continue;
+                    }
+                };
+                let server = server.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = server.handle_connection(stream).await {
+                        log::warn!("Admin request from {} failed: {}", peer, e);
+                    }
+                });
+            }
+        }))
+    }
+
+    async fn handle_connection(&self, stream: TcpStream) -> AnyResult<()> {
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).await?;
+        // 请求体不需要，读完请求头即可
+        let mut header = String::new();
+        while reader.read_line(&mut header).await? > 0 && !header.trim_end().is_empty() {
+            header.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+        let (status, body) = match self.route(method, path.trim_end_matches('/')).await {
+            Ok(response) => response,
+            Err(e) => ("500 Internal Server Error", json!({ "error": e.to_string() })),
+        };
+        let body = body.to_string();
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        reader.get_mut().write_all(response.as_bytes()).await?;
+        Ok(())
+    }
+
+    async fn route(&self, method: &str, path: &str) -> AnyResult<(&'static str, Value)> {
+        let client = &self.client;
+        match (method, path) {
+            ("GET", "/health") => ok(json!({
+                "status": "ok",
+                "endpoint": client.endpoint,
+                "subscribed": client.active_subscription.load(Ordering::Acquire),
+            })),
+            ("GET", "/metrics") => ok(serde_json::to_value(client.get_metrics())?),
+            ("GET", "/caches") => ok(serde_json::to_value(client.memory_report())?),
+            ("GET", "/filters") => ok(self.filters().await),
+            ("GET", "/watchlist") => {
+                let mints: Vec<String> =
+                    client.watchlist().mints().iter().map(|m| m.to_string()).collect();
+                ok(json!(mints))
+            }
+            ("GET", "/paused") => {
+                let paused: Vec<String> =
+                    client.paused_protocols().iter().map(|p| p.to_string()).collect();
+                ok(json!(paused))
+            }
+            ("POST", "/flush") => {
+                for sink in &self.sinks {
+                    sink.flush()?;
+                }
+                ok(json!({ "flushed": self.sinks.len() }))
+            }
+            (_, path) if path.starts_with("/paused/") => {
+                let Ok(protocol) = Protocol::from_str(&path["/paused/".len()..]) else {
+                    return Ok(("400 Bad Request", json!({ "error": "unknown protocol" })));
+                };
+                let changed = match method {
+                    "POST" | "PUT" => client.pause_protocol(protocol),
+                    "DELETE" => client.resume_protocol(protocol),
+                    _ => {
+                        return Ok((
+                            "405 Method Not Allowed",
+                            json!({ "error": "method not allowed" }),
+                        ))
+                    }
+                };
+                ok(json!({ "changed": changed }))
+            }
+            (_, path) => {
+                let Some(mint) = path.strip_prefix("/watchlist/") else {
+                    return Ok(("404 Not Found", json!({ "error": "not found" })));
+                };
+                let Ok(mint) = Pubkey::from_str(mint) else {
+                    return Ok(("400 Bad Request", json!({ "error": "invalid mint" })));
+                };
+                let changed = match method {
+                    "POST" | "PUT" => client.watch_mints(vec![mint]).await?,
+                    "DELETE" => client.unwatch_mints(vec![mint]).await?,
+                    _ => {
+                        return Ok((
+                            "405 Method Not Allowed",
+                            json!({ "error": "method not allowed" }),
+                        ))
+                    }
+                };
+                ok(json!({ "changed": changed }))
+            }
+        }
+    }
+
+    async fn filters(&self) -> Value {
+        let request = self.client.current_request.read().await.clone().unwrap_or_default();
+        SubscriptionPlan::new(request).describe()
+    }
+}
+
+fn ok(body: Value) -> AnyResult<(&'static str, Value)> {
+    Ok(("200 OK", body))
+}
@@ -0,0 +1,327 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use super::trade::TradeInfo;
+use crate::streaming::event_parser::common::ProtocolType;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default rollup window (one minute)
+pub const DEFAULT_ACTIVITY_WINDOW_MS: i64 = 60_000;
+/// Default number of pools listed in each summary
+pub const DEFAULT_TOP_POOLS: usize = 5;
+/// Default time a window stays open after its end, so events whose block time lags
+/// their arrival still land in their window
+pub const ACTIVITY_FLUSH_DELAY_MS: i64 = 2_000;
+
+/// Trade count for a single pool within a window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolActivity {
+    pub pool: Pubkey,
+    pub tx_count: u64,
+}
+
+/// Input-side volume of a single mint within a window, in raw token units
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintVolume {
+    pub mint: Pubkey,
+    pub amount: u128,
+}
+
+/// Per-protocol aggregate over one rollup window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolActivitySummary {
+    pub protocol: ProtocolType,
+    /// Window start (inclusive), milliseconds since epoch
+    pub window_start_ms: i64,
+    /// Window end (exclusive), milliseconds since epoch
+    pub window_end_ms: i64,
+    pub tx_count: u64,
+    pub unique_wallets: u64,
+    /// Input-side volume per mint, descending
+    pub volume: Vec<MintVolume>,
+    /// Most active pools by trade count, descending
+    pub top_pools: Vec<PoolActivity>,
+}
+
+#[derive(Default)]
+struct ProtocolWindow {
+    signatures: HashSet<solana_sdk::signature::Signature>,
+    wallets: HashSet<Pubkey>,
+    volume_by_mint: HashMap<Pubkey, u128>,
+    pools: HashMap<Pubkey, u64>,
+}
+
+#[derive(Default)]
+struct WindowState {
+    /// 窗口开始时间 -> 各协议统计，多个窗口可同时打开
+    windows: BTreeMap<i64, HashMap<ProtocolType, ProtocolWindow>>,
+    /// 此前的窗口均已关闭
+    closed_until_ms: Option<i64>,
+    latest_event_ms: Option<i64>,
+    /// 最近一次收到事件的本地时间，空闲期间水位按此后经过的时间推进
+    last_recv_ms: Option<i64>,
+    late_events: u64,
+}
+
+/// Rolls swap events up into periodic [`ProtocolActivitySummary`] records
+///
+/// Windows are aligned to the event (block) time and every event counts in the window
+/// of its own time, so summaries are reproducible when replaying historical data. A
+/// window closes once the newest event time (or the `flush_expired` / `flush_idle`
+/// watermark) is past its end by the allowed lateness; events of closed windows are
+/// dropped and counted in [`late_events`](Self::late_events). Feed every event through
+/// [`on_event`](Self::on_event) and call [`flush_idle`](Self::flush_idle) periodically
+/// so idle windows close; `YellowstoneGrpc::enable_activity_summaries` does both for a
+/// live subscription.
+pub struct ProtocolActivityAggregator {
+    window_ms: i64,
+    top_pools: usize,
+    allowed_lateness_ms: i64,
+    state: Mutex<WindowState>,
+}
+
+impl Default for ProtocolActivityAggregator {
+    fn default() -> Self {
+        Self::new(DEFAULT_ACTIVITY_WINDOW_MS, DEFAULT_TOP_POOLS)
+    }
+}
+
+impl ProtocolActivityAggregator {
+    pub fn new(window_ms: i64, top_pools: usize) -> Self {
+        Self {
+            window_ms: window_ms.max(1),
+            top_pools,
+            allowed_lateness_ms: ACTIVITY_FLUSH_DELAY_MS,
+            state: Mutex::new(WindowState::default()),
+        }
+    }
+
+    /// How long a window stays open after its end (default: [`ACTIVITY_FLUSH_DELAY_MS`])
+    pub fn with_allowed_lateness(mut self, allowed_lateness_ms: i64) -> Self {
+        self.allowed_lateness_ms = allowed_lateness_ms.max(0);
+        self
+    }
+
+    pub fn window_ms(&self) -> i64 {
+        self.window_ms
+    }
+
+    /// Events dropped because their window had already closed
+    pub fn late_events(&self) -> u64 {
+        self.state.lock().late_events
+    }
+
+    /// Records an event; returns the summaries of the windows it closes
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Vec<ProtocolActivitySummary> {
+        let Some(trade) = TradeInfo::from_event(event) else {
+            return Vec::new();
+        };
+        let time_ms = trade.time_ms(event.recv_us());
+        let window_start = time_ms - time_ms.rem_euclid(self.window_ms);
+
+        let mut state = self.state.lock();
+        if state.closed_until_ms.is_some_and(|closed| window_start < closed) {
+            state.late_events += 1;
+            return Vec::new();
+        }
+        let window = state
+            .windows
+            .entry(window_start)
+            .or_default()
+            .entry(trade.protocol.clone())
+            .or_default();
+        if window.signatures.insert(trade.signature) {
+            window.wallets.insert(trade.user);
+        }
+        *window.volume_by_mint.entry(trade.input_mint).or_default() += trade.amount_in as u128;
+        *window.pools.entry(trade.pool).or_default() += 1;
+
+        let latest = state.latest_event_ms.map_or(time_ms, |latest| latest.max(time_ms));
+        state.latest_event_ms = Some(latest);
+        let recv_ms = event.recv_us() / 1_000;
+        if recv_ms > 0 {
+            state.last_recv_ms = Some(state.last_recv_ms.map_or(recv_ms, |last| last.max(recv_ms)));
+        }
+        self.close_until(&mut state, latest)
+    }
+
+    /// Closes windows during idle periods, `now_ms` being the local clock
+    ///
+    /// The watermark is the newest event time plus the time elapsed since the last event
+    /// was received, so block times lagging the local clock do not close windows before
+    /// their events arrived.
+    pub fn flush_idle(&self, now_ms: i64) -> Vec<ProtocolActivitySummary> {
+        let mut state = self.state.lock();
+        let (Some(latest), Some(last_recv)) = (state.latest_event_ms, state.last_recv_ms) else {
+            return Vec::new();
+        };
+        self.close_until(&mut state, latest + (now_ms - last_recv).max(0))
+    }
+
+    /// Closes the windows that `now_ms` is past by the allowed lateness, for idle
+    /// periods with no events
+    pub fn flush_expired(&self, now_ms: i64) -> Vec<ProtocolActivitySummary> {
+        self.close_until(&mut self.state.lock(), now_ms)
+    }
+
+    /// Closes all open windows unconditionally
+    pub fn flush(&self) -> Vec<ProtocolActivitySummary> {
+        let mut state = self.state.lock();
+        let Some(last) = state.windows.keys().next_back().copied() else {
+            return Vec::new();
+        };
+        state.closed_until_ms = Some(last + self.window_ms);
+        let windows = std::mem::take(&mut state.windows);
+        windows.into_iter().flat_map(|(start, window)| self.drain(start, window)).collect()
+    }
+
+    fn close_until(&self, state: &mut WindowState, now_ms: i64) -> Vec<ProtocolActivitySummary> {
+        let watermark = now_ms - self.allowed_lateness_ms;
+        // 结束时间不晚于水位的窗口全部关闭
+        let closed_until = watermark - watermark.rem_euclid(self.window_ms);
+        if state.closed_until_ms.is_some_and(|closed| closed >= closed_until) {
+            return Vec::new();
+        }
+        state.closed_until_ms = Some(closed_until);
+        let open = state.windows.split_off(&(closed_until - self.window_ms + 1));
+        let closed = std::mem::replace(&mut state.windows, open);
+        closed.into_iter().flat_map(|(start, window)| self.drain(start, window)).collect()
+    }
+
+    fn drain(
+        &self,
+        window_start_ms: i64,
+        protocols: HashMap<ProtocolType, ProtocolWindow>,
+    ) -> Vec<ProtocolActivitySummary> {
+        protocols
+            .into_iter()
+            .map(|(protocol, window)| {
+                let mut pools: Vec<PoolActivity> = window
+                    .pools
+                    .into_iter()
+                    .map(|(pool, tx_count)| PoolActivity { pool, tx_count })
+                    .collect();
+                pools.sort_by(|a, b| b.tx_count.cmp(&a.tx_count));
+                pools.truncate(self.top_pools);
+                let mut volume: Vec<MintVolume> = window
+                    .volume_by_mint
+                    .into_iter()
+                    .map(|(mint, amount)| MintVolume { mint, amount })
+                    .collect();
+                volume.sort_by(|a, b| b.amount.cmp(&a.amount));
+                ProtocolActivitySummary {
+                    protocol,
+                    window_start_ms,
+                    window_end_ms: window_start_ms + self.window_ms,
+                    tx_count: window.signatures.len() as u64,
+                    unique_wallets: window.wallets.len() as u64,
+                    volume,
+                    top_pools: pools,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::EventMetadata;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+    use solana_sdk::signature::Signature;
+
+    fn swap(pool: Pubkey, payer: Pubkey, block_time_ms: i64) -> RaydiumCpmmSwapEvent {
+        RaydiumCpmmSwapEvent {
+            metadata: EventMetadata {
+                signature: Signature::new_unique(),
+                block_time_ms,
+                protocol: ProtocolType::RaydiumCpmm,
+                ..Default::default()
+            },
+            pool_state: pool,
+            payer,
+            amount_in: 100,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_window_rollover_and_expiry() {
+        let aggregator = ProtocolActivityAggregator::new(1_000, 1).with_allowed_lateness(0);
+        let (hot, cold, wallet) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(aggregator.on_event(&swap(hot, wallet, 100)).is_empty());
+        assert!(aggregator.on_event(&swap(hot, wallet, 200)).is_empty());
+        assert!(aggregator.on_event(&swap(cold, Pubkey::new_unique(), 900)).is_empty());
+        // 窗口未结束时不关闭
+        assert!(aggregator.flush_expired(999).is_empty());
+
+        // 下一窗口的首个事件关闭上一窗口
+        let summaries = aggregator.on_event(&swap(hot, wallet, 1_500));
+        assert_eq!(summaries.len(), 1);
+        let summary = &summaries[0];
+        assert_eq!((summary.window_start_ms, summary.window_end_ms), (0, 1_000));
+        assert_eq!((summary.tx_count, summary.unique_wallets), (3, 2));
+        assert_eq!(summary.top_pools, vec![PoolActivity { pool: hot, tx_count: 2 }]);
+        assert_eq!(summary.volume[0].amount, 300);
+
+        // 空闲期间由定时器按时间关闭窗口
+        assert!(aggregator.flush_expired(1_999).is_empty());
+        let summaries = aggregator.flush_expired(2_000);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!((summaries[0].window_start_ms, summaries[0].tx_count), (1_000, 1));
+        assert!(aggregator.flush_expired(10_000).is_empty());
+    }
+
+    #[test]
+    fn test_late_events_counted_in_their_window() {
+        let aggregator = ProtocolActivityAggregator::new(1_000, 1).with_allowed_lateness(2_000);
+        let (pool, wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(aggregator.on_event(&swap(pool, wallet, 100)).is_empty());
+        assert!(aggregator.on_event(&swap(pool, wallet, 1_500)).is_empty());
+        // 迟到的事件计入自己的窗口，而不是当前窗口
+        assert!(aggregator.on_event(&swap(pool, wallet, 900)).is_empty());
+
+        let summaries = aggregator.on_event(&swap(pool, wallet, 3_100));
+        assert_eq!(summaries.len(), 1);
+        assert_eq!((summaries[0].window_start_ms, summaries[0].tx_count), (0, 2));
+
+        // 窗口关闭后再到达的事件被丢弃并计数
+        assert!(aggregator.on_event(&swap(pool, wallet, 500)).is_empty());
+        assert_eq!(aggregator.late_events(), 1);
+
+        let summaries = aggregator.flush();
+        let windows: Vec<(i64, u64)> =
+            summaries.iter().map(|s| (s.window_start_ms, s.tx_count)).collect();
+        assert_eq!(windows, vec![(1_000, 1), (3_000, 1)]);
+        assert!(aggregator.flush().is_empty());
+    }
+
+    #[test]
+    fn test_idle_flush_follows_lagging_block_time() {
+        let aggregator = ProtocolActivityAggregator::new(1_000, 1);
+        let (pool, wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // 区块时间落后本地时钟约 30 秒
+        let lagging = |block_time_ms, recv_ms: i64| {
+            let mut event = swap(pool, wallet, block_time_ms);
+            event.metadata.recv_us = recv_ms * 1_000;
+            event
+        };
+        assert!(aggregator.on_event(&lagging(100, 30_000)).is_empty());
+        assert!(aggregator.on_event(&lagging(900, 30_100)).is_empty());
+
+        // 按本地时钟早已超过窗口结束，但事件时间还没有
+        assert!(aggregator.flush_idle(30_500).is_empty());
+        assert!(aggregator.on_event(&lagging(950, 30_600)).is_empty());
+        assert!(aggregator.flush_idle(32_600).is_empty());
+
+        // 最新事件时间 950 加上空闲的 2_100 超过窗口结束 2 秒
+        let summaries = aggregator.flush_idle(32_700);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!((summaries[0].window_start_ms, summaries[0].tx_count), (0, 3));
+        assert_eq!(aggregator.late_events(), 0);
+    }
+}
@@ -0,0 +1,262 @@
+use dashmap::{DashMap, DashSet};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::match_event;
+use crate::streaming::event_parser::protocols::raydium_amm_v4::{
+    types::AmmInfo, RaydiumAmmV4AmmInfoAccountEvent, RaydiumAmmV4DepositEvent,
+    RaydiumAmmV4Initialize2Event, RaydiumAmmV4SwapEvent, RaydiumAmmV4WithdrawEvent,
+};
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Vaults, mints and OpenBook market of an AMM V4 pool
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmmV4PoolLink {
+    pub amm: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub lp_mint: Pubkey,
+    /// serum / OpenBook market
+    pub market: Pubkey,
+    pub market_program: Pubkey,
+    /// 仅 AmmInfo 中有，initialize2 学到的链接为 None
+    pub coin_decimals: Option<u8>,
+    pub pc_decimals: Option<u8>,
+}
+
+impl AmmV4PoolLink {
+    pub fn from_amm_info(amm: Pubkey, info: &AmmInfo) -> Self {
+        Self {
+            amm,
+            coin_mint: info.coin_mint,
+            pc_mint: info.pc_mint,
+            coin_vault: info.token_coin,
+            pc_vault: info.token_pc,
+            lp_mint: info.lp_mint,
+            market: info.market,
+            market_program: info.serum_dex,
+            coin_decimals: u8::try_from(info.coin_decimals).ok(),
+            pc_decimals: u8::try_from(info.pc_decimals).ok(),
+        }
+    }
+
+    /// Whether the decimals are known, i.e. the AmmInfo has been seen
+    pub fn is_complete(&self) -> bool {
+        self.coin_decimals.is_some() && self.pc_decimals.is_some()
+    }
+
+    /// Price in pc per coin from raw vault balances (e.g. post token balances of a swap)
+    ///
+    /// Vault balances leave out funds parked in the OpenBook open orders and pending pnl,
+    /// which is close enough for quoting but not for exact reserves.
+    pub fn price(&self, coin_vault_amount: u64, pc_vault_amount: u64) -> Option<f64> {
+        if coin_vault_amount == 0 {
+            return None;
+        }
+        let coin = coin_vault_amount as f64 / 10f64.powi(self.coin_decimals? as i32);
+        let pc = pc_vault_amount as f64 / 10f64.powi(self.pc_decimals? as i32);
+        Some(pc / coin)
+    }
+}
+
+/// AMM V4 pool → vaults / mints / market mapping
+///
+/// AMM V4 swaps carry the vault and market accounts but neither mints nor decimals,
+/// so a price can only be derived once the pool's AmmInfo is known. Links are learned
+/// from AmmInfo account updates and `initialize2`; pools first sighted in a swap or
+/// liquidity event without a complete link are queued in [`unresolved`](Self::unresolved)
+/// for `resolve_pending` (transport feature) to fetch.
+#[derive(Debug, Default)]
+pub struct AmmV4MarketResolver {
+    links: DashMap<Pubkey, AmmV4PoolLink>,
+    /// vault -> amm
+    vaults: DashMap<Pubkey, Pubkey>,
+    unresolved: DashSet<Pubkey>,
+}
+
+impl AmmV4MarketResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, amm: &Pubkey) -> Option<AmmV4PoolLink> {
+        self.links.get(amm).map(|link| link.clone())
+    }
+
+    /// Pool owning the coin or pc vault
+    pub fn by_vault(&self, vault: &Pubkey) -> Option<AmmV4PoolLink> {
+        let amm = *self.vaults.get(vault)?;
+        self.get(&amm)
+    }
+
+    /// Caches the link, keeping already known decimals
+    pub fn insert(&self, mut link: AmmV4PoolLink) {
+        if let Some(known) = self.links.get(&link.amm) {
+            link.coin_decimals = link.coin_decimals.or(known.coin_decimals);
+            link.pc_decimals = link.pc_decimals.or(known.pc_decimals);
+        }
+        if link.is_complete() {
+            self.unresolved.remove(&link.amm);
+        } else {
+            self.unresolved.insert(link.amm);
+        }
+        self.vaults.insert(link.coin_vault, link.amm);
+        self.vaults.insert(link.pc_vault, link.amm);
+        self.links.insert(link.amm, link);
+    }
+
+    /// Pools seen without a complete link
+    pub fn unresolved(&self) -> Vec<Pubkey> {
+        self.unresolved.iter().map(|amm| *amm).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    /// Learns from AmmInfo / initialize2 and queues unknown pools of other AMM V4 events
+    pub fn on_event(&self, event: &dyn UnifiedEvent) {
+        let mut link: Option<AmmV4PoolLink> = None;
+        let mut sighted: Option<Pubkey> = None;
+        match_event!(event, {
+            ref RaydiumAmmV4AmmInfoAccountEvent => |e: &RaydiumAmmV4AmmInfoAccountEvent| {
+                link = Some(AmmV4PoolLink::from_amm_info(e.pubkey, &e.amm_info));
+            },
+            ref RaydiumAmmV4Initialize2Event => |e: &RaydiumAmmV4Initialize2Event| {
+                link = Some(AmmV4PoolLink {
+                    amm: e.amm,
+                    coin_mint: e.coin_mint,
+                    pc_mint: e.pc_mint,
+                    coin_vault: e.pool_coin_token_account,
+                    pc_vault: e.pool_pc_token_account,
+                    lp_mint: e.lp_mint,
+                    market: e.serum_market,
+                    market_program: e.serum_program,
+                    coin_decimals: None,
+                    pc_decimals: None,
+                });
+            },
+            ref RaydiumAmmV4SwapEvent => |e: &RaydiumAmmV4SwapEvent| {
+                sighted = Some(e.amm);
+            },
+            ref RaydiumAmmV4DepositEvent => |e: &RaydiumAmmV4DepositEvent| {
+                sighted = Some(e.amm);
+            },
+            ref RaydiumAmmV4WithdrawEvent => |e: &RaydiumAmmV4WithdrawEvent| {
+                sighted = Some(e.amm);
+            },
+            _ => |_event: &dyn UnifiedEvent| {},
+        });
+        if let Some(link) = link {
+            self.insert(link);
+        } else if let Some(amm) = sighted {
+            if !self.links.get(&amm).is_some_and(|link| link.is_complete()) {
+                self.unresolved.insert(amm);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "transport")]
+mod rpc {
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::{AmmV4MarketResolver, AmmV4PoolLink};
+    use crate::common::{AnyResult, SolanaRpcClient};
+    use crate::streaming::event_parser::protocols::raydium_amm_v4::types::amm_info_decode;
+
+    /// getMultipleAccounts 单次上限
+    const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+    impl AmmV4MarketResolver {
+        /// Returns the cached link, fetching the pool's AmmInfo if it is missing or incomplete
+        pub async fn resolve(
+            &self,
+            rpc: &SolanaRpcClient,
+            amm: &Pubkey,
+        ) -> AnyResult<AmmV4PoolLink> {
+            if let Some(link) = self.get(amm).filter(|link| link.is_complete()) {
+                return Ok(link);
+            }
+            let account = rpc.get_account(amm).await?;
+            let info = amm_info_decode(&account.data)
+                .ok_or_else(|| anyhow::anyhow!("account {} is not an AMM V4 pool", amm))?;
+            let link = AmmV4PoolLink::from_amm_info(*amm, &info);
+            self.insert(link.clone());
+            Ok(link)
+        }
+
+        /// Fetches the AmmInfo of every unresolved pool, returns how many were resolved
+        ///
+        /// Pools whose account is missing or not an AmmInfo are dropped from the queue.
+        pub async fn resolve_pending(&self, rpc: &SolanaRpcClient) -> AnyResult<usize> {
+            let pending = self.unresolved();
+            let mut resolved = 0;
+            for chunk in pending.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+                let accounts = rpc.get_multiple_accounts(chunk).await?;
+                for (amm, account) in chunk.iter().zip(accounts) {
+                    match account.and_then(|account| amm_info_decode(&account.data)) {
+                        Some(info) => {
+                            self.insert(AmmV4PoolLink::from_amm_info(*amm, &info));
+                            resolved += 1;
+                        }
+                        None => {
+                            log::debug!("AMM V4 pool {} could not be resolved", amm);
+                            self.unresolved.remove(amm);
+                        }
+                    }
+                }
+            }
+            Ok(resolved)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amm_v4_market_resolver() {
+        let resolver = AmmV4MarketResolver::new();
+        let (amm, coin_vault, pc_vault) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        resolver.on_event(&RaydiumAmmV4SwapEvent { amm, ..Default::default() });
+        assert_eq!(resolver.unresolved(), vec![amm]);
+
+        resolver.on_event(&RaydiumAmmV4Initialize2Event {
+            amm,
+            pool_coin_token_account: coin_vault,
+            pool_pc_token_account: pc_vault,
+            ..Default::default()
+        });
+        let link = resolver.by_vault(&pc_vault).unwrap();
+        assert_eq!(link.amm, amm);
+        assert_eq!(link.price(1, 1), None);
+        assert_eq!(resolver.unresolved(), vec![amm]);
+
+        let amm_info = AmmInfo {
+            coin_decimals: 9,
+            pc_decimals: 6,
+            token_coin: coin_vault,
+            token_pc: pc_vault,
+            ..Default::default()
+        };
+        resolver.on_event(&RaydiumAmmV4AmmInfoAccountEvent {
+            pubkey: amm,
+            amm_info,
+            ..Default::default()
+        });
+        assert!(resolver.unresolved().is_empty());
+        // 2 SOL / 300 USDC
+        let price = resolver.get(&amm).unwrap().price(2_000_000_000, 300_000_000).unwrap();
+        assert!((price - 150.0).abs() < 1e-9);
+    }
+}
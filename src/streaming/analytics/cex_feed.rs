@@ -0,0 +1,279 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::spread::ExternalQuote;
+use crate::common::AnyResult;
+use crate::streaming::event_parser::common::{CustomId, ProtocolType};
+
+pub const BINANCE_WS_URL: &str = "wss://stream.binance.com:9443/stream";
+pub const COINBASE_WS_URL: &str = "wss://ws-feed.exchange.coinbase.com";
+
+/// Reconnect backoff bounds of a price feed
+const INITIAL_BACKOFF_MS: u64 = 500;
+const MAX_BACKOFF_MS: u64 = 30_000;
+
+/// Receives the quotes of a [`PriceFeed`]
+pub type QuoteCallback = Arc<dyn Fn(ExternalQuote) + Send + Sync>;
+
+/// Exchange symbol mapped to the on-chain pair it prices
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CexPair {
+    /// Exchange symbol, e.g. `SOLUSDC` on Binance or `SOL-USD` on Coinbase
+    pub symbol: String,
+    pub token_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub token_decimals: u8,
+    pub quote_decimals: u8,
+}
+
+impl CexPair {
+    fn quote(&self, venue: &ProtocolType, price: f64, time_ms: i64) -> ExternalQuote {
+        ExternalQuote {
+            venue: venue.clone(),
+            token_mint: self.token_mint,
+            quote_mint: self.quote_mint,
+            price,
+            token_decimals: self.token_decimals,
+            quote_decimals: self.quote_decimals,
+            time_ms,
+        }
+    }
+}
+
+/// Source of off-chain quotes, e.g. a centralized exchange
+///
+/// Implementations reconnect on their own; the returned task runs until aborted.
+/// Quotes are typically passed to `SpreadRecorder::on_external_quote`, so the exchange
+/// shows up as one more venue next to the on-chain pools.
+pub trait PriceFeed: Send + Sync {
+    /// Pseudo-venue the quotes are attributed to
+    fn venue(&self) -> ProtocolType;
+
+    fn spawn(self: Arc<Self>, on_quote: QuoteCallback) -> JoinHandle<()>;
+}
+
+/// Binance best bid/ask (`bookTicker`) mid prices
+pub struct BinanceFeed {
+    url: String,
+    venue: ProtocolType,
+    pairs: Vec<CexPair>,
+}
+
+impl BinanceFeed {
+    pub fn new(pairs: Vec<CexPair>) -> Self {
+        Self {
+            url: BINANCE_WS_URL.into(),
+            venue: ProtocolType::Custom(CustomId::intern("binance")),
+            pairs,
+        }
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    fn stream_url(&self) -> String {
+        let streams: Vec<String> = self
+            .pairs
+            .iter()
+            .map(|pair| format!("{}@bookTicker", pair.symbol.to_lowercase()))
+            .collect();
+        format!("{}?streams={}", self.url, streams.join("/"))
+    }
+
+    /// Parses a combined-stream message; `bookTicker` carries no timestamp, the receive
+    /// time is used
+    fn parse(&self, text: &str, recv_ms: i64) -> Option<ExternalQuote> {
+        let value: Value = serde_json::from_str(text).ok()?;
+        let data = value.get("data").unwrap_or(&value);
+        let symbol = data.get("s")?.as_str()?;
+        let pair = self.pairs.iter().find(|pair| pair.symbol.eq_ignore_ascii_case(symbol))?;
+        let mid = mid_price(data.get("b")?, data.get("a")?)?;
+        Some(pair.quote(&self.venue, mid, recv_ms))
+    }
+}
+
+impl PriceFeed for BinanceFeed {
+    fn venue(&self) -> ProtocolType {
+        self.venue.clone()
+    }
+
+    fn spawn(self: Arc<Self>, on_quote: QuoteCallback) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let url = self.stream_url();
+            run_websocket("Binance", &url, None, |text, recv_ms| {
+                if let Some(quote) = self.parse(text, recv_ms) {
+                    on_quote(quote);
+                }
+            })
+            .await
+        })
+    }
+}
+
+/// Coinbase Exchange `ticker` channel mid prices
+pub struct CoinbaseFeed {
+    url: String,
+    venue: ProtocolType,
+    pairs: Vec<CexPair>,
+}
+
+impl CoinbaseFeed {
+    pub fn new(pairs: Vec<CexPair>) -> Self {
+        Self {
+            url: COINBASE_WS_URL.into(),
+            venue: ProtocolType::Custom(CustomId::intern("coinbase")),
+            pairs,
+        }
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    fn subscribe_message(&self) -> String {
+        let products: Vec<&str> = self.pairs.iter().map(|pair| pair.symbol.as_str()).collect();
+        serde_json::json!({
+            "type": "subscribe",
+            "product_ids": products,
+            "channels": ["ticker"],
+        })
+        .to_string()
+    }
+
+    fn parse(&self, text: &str, recv_ms: i64) -> Option<ExternalQuote> {
+        let value: Value = serde_json::from_str(text).ok()?;
+        if value.get("type")?.as_str()? != "ticker" {
+            return None;
+        }
+        let product = value.get("product_id")?.as_str()?;
+        let pair = self.pairs.iter().find(|pair| pair.symbol == product)?;
+        let mid = mid_price(value.get("best_bid")?, value.get("best_ask")?)?;
+        let time_ms = value
+            .get("time")
+            .and_then(Value::as_str)
+            .and_then(|time| chrono::DateTime::parse_from_rfc3339(time).ok())
+            .map_or(recv_ms, |time| time.timestamp_millis());
+        Some(pair.quote(&self.venue, mid, time_ms))
+    }
+}
+
+impl PriceFeed for CoinbaseFeed {
+    fn venue(&self) -> ProtocolType {
+        self.venue.clone()
+    }
+
+    fn spawn(self: Arc<Self>, on_quote: QuoteCallback) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let subscribe = self.subscribe_message();
+            run_websocket("Coinbase", &self.url, Some(subscribe), |text, recv_ms| {
+                if let Some(quote) = self.parse(text, recv_ms) {
+                    on_quote(quote);
+                }
+            })
+            .await
+        })
+    }
+}
+
+/// Mid of bid and ask given as decimal strings
+fn mid_price(bid: &Value, ask: &Value) -> Option<f64> {
+    let bid: f64 = bid.as_str()?.parse().ok()?;
+    let ask: f64 = ask.as_str()?.parse().ok()?;
+    (bid > 0.0 && ask > 0.0).then_some((bid + ask) / 2.0)
+}
+
+/// Keeps a websocket connected, passing every text message to `on_text`
+async fn run_websocket(
+    name: &str,
+    url: &str,
+    subscribe: Option<String>,
+    on_text: impl Fn(&str, i64),
+) {
+    let mut backoff_ms = INITIAL_BACKOFF_MS;
+    loop {
+        match read_websocket(url, subscribe.as_deref(), &on_text).await {
+            Ok(()) => {
+                log::warn!("{} price feed closed, reconnecting", name);
+                backoff_ms = INITIAL_BACKOFF_MS;
+            }
+            Err(e) => {
+                log::warn!("{} price feed failed: {}, retrying in {} ms", name, e, backoff_ms);
+                backoff_ms = (backoff_ms * 2).min(MAX_BACKOFF_MS);
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+    }
+}
+
+async fn read_websocket(
+    url: &str,
+    subscribe: Option<&str>,
+    on_text: &impl Fn(&str, i64),
+) -> AnyResult<()> {
+    let (mut socket, _) = tokio_tungstenite::connect_async(url).await?;
+    if let Some(subscribe) = subscribe {
+        socket.send(Message::text(subscribe)).await?;
+    }
+    while let Some(message) = socket.next().await {
+        match message? {
+            Message::Text(text) => on_text(&text, chrono::Utc::now().timestamp_millis()),
+            Message::Ping(payload) => socket.send(Message::Pong(payload)).await?,
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::analytics::WSOL_MINT;
+
+    #[test]
+    fn test_cex_ticker_parsing() {
+        let usdc = Pubkey::new_unique();
+        let pair = |symbol: &str| CexPair {
+            symbol: symbol.into(),
+            token_mint: WSOL_MINT,
+            quote_mint: usdc,
+            token_decimals: 9,
+            quote_decimals: 6,
+        };
+
+        let binance = BinanceFeed::new(vec![pair("SOLUSDC")]);
+        let quote = binance
+            .parse(
+                r#"{"stream":"solusdc@bookTicker","data":{"u":1,"s":"SOLUSDC","b":"150.10","B":"3","a":"150.30","A":"2"}}"#,
+                1_000,
+            )
+            .unwrap();
+        assert!((quote.price - 150.2).abs() < 1e-9);
+        assert_eq!(quote.time_ms, 1_000);
+        // 1 SOL = 1e9 lamports，150.2 USDC = 150.2e6
+        assert!((quote.raw_price() - 0.1502).abs() < 1e-12);
+        assert_eq!(binance.stream_url(), format!("{}?streams=solusdc@bookTicker", BINANCE_WS_URL));
+
+        let coinbase = CoinbaseFeed::new(vec![pair("SOL-USD")]);
+        let quote = coinbase
+            .parse(
+                r#"{"type":"ticker","product_id":"SOL-USD","best_bid":"99","best_ask":"101","time":"2024-01-01T00:00:00.250Z"}"#,
+                0,
+            )
+            .unwrap();
+        assert_eq!(quote.price, 100.0);
+        assert_eq!(quote.time_ms, 1_704_067_200_250);
+        assert_eq!(quote.venue, coinbase.venue());
+        assert!(coinbase.parse(r#"{"type":"heartbeat","product_id":"SOL-USD"}"#, 0).is_none());
+    }
+}
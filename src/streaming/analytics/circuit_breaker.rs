@@ -0,0 +1,228 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::streaming::event_parser::common::high_performance_clock::{system_clock, Clock};
+
+/// Default emissions allowed per mint within one window
+pub const DEFAULT_BREAKER_MAX_EMISSIONS: usize = 20;
+/// Default length of the counting window
+pub const DEFAULT_BREAKER_WINDOW_MS: u64 = 10_000;
+/// Default time a tripped mint stays suppressed
+pub const DEFAULT_BREAKER_COOLDOWN_MS: u64 = 60_000;
+
+/// Limits of a [`MintCircuitBreaker`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    /// Emissions allowed per mint within `window_ms`; the next one trips the breaker
+    pub max_emissions: usize,
+    pub window_ms: u64,
+    /// Time a tripped mint stays suppressed
+    pub cooldown_ms: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            max_emissions: DEFAULT_BREAKER_MAX_EMISSIONS,
+            window_ms: DEFAULT_BREAKER_WINDOW_MS,
+            cooldown_ms: DEFAULT_BREAKER_COOLDOWN_MS,
+        }
+    }
+}
+
+/// Suppression counters of one mint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintSuppression {
+    pub mint: Pubkey,
+    /// Signals dropped since the breaker was created
+    pub suppressed: u64,
+    /// Times the breaker tripped for this mint
+    pub trips: u64,
+    /// Whether the mint is in its cool-down right now
+    pub tripped: bool,
+}
+
+#[derive(Debug, Default)]
+struct MintState {
+    /// 窗口内放行的时间戳（微秒）
+    emitted_us: VecDeque<i64>,
+    tripped_until_us: Option<i64>,
+    suppressed: u64,
+    trips: u64,
+}
+
+/// Per-mint circuit breaker for detector signals
+///
+/// A single mint in a frenzy (a launch, a bot war) can produce hundreds of sandwich or
+/// early-trade signals a minute and drown out everything else. Each mint may emit
+/// `max_emissions` signals per `window_ms`; the next one trips the breaker and the
+/// mint is silenced for `cooldown_ms`. Suppressed signals are counted per mint.
+///
+/// Shared through an `Arc`, one breaker can guard several detectors, see
+/// `SandwichDetector::with_circuit_breaker` and `NewPoolWatcher::with_circuit_breaker`.
+#[derive(Debug)]
+pub struct MintCircuitBreaker {
+    config: CircuitBreakerConfig,
+    clock: Arc<dyn Clock>,
+    state: Mutex<HashMap<Pubkey, MintState>>,
+}
+
+impl Default for MintCircuitBreaker {
+    fn default() -> Self {
+        Self::new(CircuitBreakerConfig::default())
+    }
+}
+
+impl MintCircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self { config, clock: system_clock(), state: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Records an emission for the mint, returns false if it must be suppressed
+    pub fn allow(&self, mint: &Pubkey) -> bool {
+        let now = self.clock.now_micros();
+        let window_us = (self.config.window_ms as i64).saturating_mul(1000);
+        let mut state = self.state.lock();
+        let entry = state.entry(*mint).or_default();
+
+        match entry.tripped_until_us {
+            Some(until) if now < until => {
+                entry.suppressed += 1;
+                return false;
+            }
+            Some(_) => {
+                entry.tripped_until_us = None;
+                entry.emitted_us.clear();
+            }
+            None => {}
+        }
+
+        while entry.emitted_us.front().is_some_and(|t| now - *t >= window_us) {
+            entry.emitted_us.pop_front();
+        }
+        if entry.emitted_us.len() >= self.config.max_emissions {
+            entry.tripped_until_us =
+                Some(now.saturating_add((self.config.cooldown_ms as i64).saturating_mul(1000)));
+            entry.emitted_us.clear();
+            entry.suppressed += 1;
+            entry.trips += 1;
+            log::warn!(
+                "Circuit breaker tripped for mint {}: more than {} signals in {} ms",
+                mint,
+                self.config.max_emissions,
+                self.config.window_ms
+            );
+            return false;
+        }
+        entry.emitted_us.push_back(now);
+        true
+    }
+
+    /// Keeps the signals whose mint is allowed; signals without a mint always pass
+    pub fn filter<T>(&self, signals: Vec<T>, mint_of: impl Fn(&T) -> Option<Pubkey>) -> Vec<T> {
+        signals
+            .into_iter()
+            .filter(|signal| mint_of(signal).is_none_or(|mint| self.allow(&mint)))
+            .collect()
+    }
+
+    pub fn is_tripped(&self, mint: &Pubkey) -> bool {
+        let now = self.clock.now_micros();
+        self.state
+            .lock()
+            .get(mint)
+            .and_then(|entry| entry.tripped_until_us)
+            .is_some_and(|until| now < until)
+    }
+
+    /// Signals suppressed for the mint
+    pub fn suppressed(&self, mint: &Pubkey) -> u64 {
+        self.state.lock().get(mint).map_or(0, |entry| entry.suppressed)
+    }
+
+    /// Signals suppressed across all mints
+    pub fn total_suppressed(&self) -> u64 {
+        self.state.lock().values().map(|entry| entry.suppressed).sum()
+    }
+
+    /// Counters of every mint that had a signal suppressed, most suppressed first
+    pub fn suppressions(&self) -> Vec<MintSuppression> {
+        let now = self.clock.now_micros();
+        let mut out: Vec<MintSuppression> = self
+            .state
+            .lock()
+            .iter()
+            .filter(|(_, entry)| entry.suppressed > 0)
+            .map(|(mint, entry)| MintSuppression {
+                mint: *mint,
+                suppressed: entry.suppressed,
+                trips: entry.trips,
+                tripped: entry.tripped_until_us.is_some_and(|until| now < until),
+            })
+            .collect();
+        out.sort_by(|a, b| b.suppressed.cmp(&a.suppressed).then(a.mint.cmp(&b.mint)));
+        out
+    }
+
+    /// Drops mints that are neither tripped nor emitted within the window, keeping counters
+    /// of mints that were suppressed
+    pub fn prune(&self) {
+        let now = self.clock.now_micros();
+        let window_us = (self.config.window_ms as i64).saturating_mul(1000);
+        self.state.lock().retain(|_, entry| {
+            entry.suppressed > 0
+                || entry.tripped_until_us.is_some_and(|until| now < until)
+                || entry.emitted_us.back().is_some_and(|t| now - *t < window_us)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::streaming::event_parser::common::high_performance_clock::MockClock;
+
+    #[test]
+    fn test_circuit_breaker_trips_and_recovers() {
+        let clock = Arc::new(MockClock::new(0));
+        let config =
+            CircuitBreakerConfig { max_emissions: 3, window_ms: 1_000, cooldown_ms: 5_000 };
+        let breaker = MintCircuitBreaker::new(config).with_clock(clock.clone());
+        let (hot, calm) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert!((0..3).all(|_| breaker.allow(&hot)));
+        assert!(!breaker.allow(&hot));
+        assert!(breaker.is_tripped(&hot));
+        assert!(breaker.allow(&calm));
+
+        // 冷却期内即使窗口已过也保持抑制
+        clock.advance(Duration::from_secs(2));
+        let kept = breaker.filter(vec![hot, calm, hot], |mint| Some(*mint));
+        assert_eq!(kept, vec![calm]);
+        assert_eq!(breaker.suppressed(&hot), 3);
+
+        clock.advance(Duration::from_secs(4));
+        assert!(breaker.allow(&hot));
+        assert_eq!(
+            breaker.suppressions(),
+            vec![MintSuppression { mint: hot, suppressed: 3, trips: 1, tripped: false }]
+        );
+        assert_eq!(breaker.total_suppressed(), 3);
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use super::window::{ClosedWindow, TumblingWindow};
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default rollup window (one minute)
+pub const DEFAULT_COMPUTE_UNITS_WINDOW_MS: i64 = 60_000;
+
+/// Compute units consumed by transactions calling one program within a window
+///
+/// Units are per transaction, so a transaction touching several programs counts for each.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgramComputeSummary {
+    pub program_id: Pubkey,
+    /// Window start (inclusive), milliseconds since epoch
+    pub window_start_ms: i64,
+    /// Window end (exclusive), milliseconds since epoch
+    pub window_end_ms: i64,
+    pub tx_count: u64,
+    pub total_units: u64,
+    pub avg_units: u64,
+    pub p50_units: u64,
+    pub p95_units: u64,
+    pub max_units: u64,
+    /// Average of the program's previous window, to spot CU profile changes after upgrades
+    pub previous_avg_units: Option<u64>,
+}
+
+impl ProgramComputeSummary {
+    /// Relative change of the average against the previous window
+    pub fn avg_change(&self) -> Option<f64> {
+        let previous = self.previous_avg_units.filter(|avg| *avg > 0)?;
+        Some(self.avg_units as f64 / previous as f64 - 1.0)
+    }
+}
+
+#[derive(Default)]
+struct ProgramWindow {
+    /// 同一交易的多个事件连续到达，按最近签名去重
+    last_signature: Option<Signature>,
+    units: Vec<u64>,
+}
+
+struct ComputeState {
+    window: TumblingWindow<Pubkey, ProgramWindow>,
+    previous_avg: HashMap<Pubkey, u64>,
+}
+
+/// Rolls `compute_budget.units_consumed` up per program into [`ProgramComputeSummary`] records
+///
+/// Windows are aligned to the event (block) time like [`ProtocolActivityAggregator`].
+/// Events without status meta (shred stream) carry no consumed units and are ignored.
+///
+/// [`ProtocolActivityAggregator`]: super::ProtocolActivityAggregator
+pub struct ComputeUnitsAggregator {
+    state: Mutex<ComputeState>,
+}
+
+impl Default for ComputeUnitsAggregator {
+    fn default() -> Self {
+        Self::new(DEFAULT_COMPUTE_UNITS_WINDOW_MS)
+    }
+}
+
+impl ComputeUnitsAggregator {
+    pub fn new(window_ms: i64) -> Self {
+        Self {
+            state: Mutex::new(ComputeState {
+                window: TumblingWindow::new(window_ms),
+                previous_avg: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Records an event; returns the summaries of the previous window once it closes
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Vec<ProgramComputeSummary> {
+        let metadata = event.metadata();
+        let Some(units) = metadata.compute_budget.units_consumed else {
+            return Vec::new();
+        };
+        let time_ms = if metadata.block_time_ms > 0 {
+            metadata.block_time_ms
+        } else {
+            event.recv_us() / 1000
+        };
+
+        let mut state = self.state.lock();
+        let closed = state.window.advance(time_ms);
+        let summaries = Self::summarize(&mut state, closed);
+
+        let window = state.window.entry(metadata.program_id);
+        if window.last_signature != Some(metadata.signature) {
+            window.last_signature = Some(metadata.signature);
+            window.units.push(units);
+        }
+        summaries
+    }
+
+    /// Closes the current window if `now_ms` is past its end, for idle periods with no events
+    pub fn flush_expired(&self, now_ms: i64) -> Vec<ProgramComputeSummary> {
+        let mut state = self.state.lock();
+        let closed = state.window.close_expired(now_ms);
+        Self::summarize(&mut state, closed)
+    }
+
+    /// Closes the current window unconditionally
+    pub fn flush(&self) -> Vec<ProgramComputeSummary> {
+        let mut state = self.state.lock();
+        let closed = state.window.close();
+        Self::summarize(&mut state, closed)
+    }
+
+    fn summarize(
+        state: &mut ComputeState,
+        closed: Option<ClosedWindow<Pubkey, ProgramWindow>>,
+    ) -> Vec<ProgramComputeSummary> {
+        let Some(closed) = closed else {
+            return Vec::new();
+        };
+        closed
+            .entries
+            .into_iter()
+            .map(|(program_id, mut window)| {
+                window.units.sort_unstable();
+                let units = &window.units;
+                let percentile = |p: usize| units[(units.len() - 1) * p / 100];
+                let total_units: u64 = units.iter().sum();
+                let avg_units = total_units / units.len() as u64;
+                let previous_avg_units = state.previous_avg.insert(program_id, avg_units);
+                ProgramComputeSummary {
+                    program_id,
+                    window_start_ms: closed.start_ms,
+                    window_end_ms: closed.end_ms,
+                    tx_count: units.len() as u64,
+                    total_units,
+                    avg_units,
+                    p50_units: percentile(50),
+                    p95_units: percentile(95),
+                    max_units: units.last().copied().unwrap_or_default(),
+                    previous_avg_units,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::{ComputeBudgetInfo, EventMetadata};
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    fn swap(program_id: Pubkey, block_time_ms: i64, units: u64) -> RaydiumCpmmSwapEvent {
+        RaydiumCpmmSwapEvent {
+            metadata: EventMetadata {
+                signature: Signature::new_unique(),
+                block_time_ms,
+                program_id,
+                compute_budget: ComputeBudgetInfo {
+                    units_consumed: Some(units),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compute_units_per_program() {
+        let aggregator = ComputeUnitsAggregator::new(1_000);
+        let program = Pubkey::new_unique();
+        let event = swap(program, 100, 40_000);
+        assert!(aggregator.on_event(&event).is_empty());
+        // 同一交易的第二个事件不重复计数
+        assert!(aggregator.on_event(&event).is_empty());
+        assert!(aggregator.on_event(&swap(program, 200, 60_000)).is_empty());
+
+        let summaries = aggregator.on_event(&swap(program, 1_100, 100_000));
+        assert_eq!(summaries.len(), 1);
+        assert_eq!((summaries[0].tx_count, summaries[0].avg_units), (2, 50_000));
+        assert_eq!((summaries[0].p50_units, summaries[0].max_units), (40_000, 60_000));
+        assert_eq!(summaries[0].previous_avg_units, None);
+
+        let summaries = aggregator.flush();
+        assert_eq!(summaries[0].previous_avg_units, Some(50_000));
+        assert_eq!(summaries[0].avg_change(), Some(1.0));
+    }
+}
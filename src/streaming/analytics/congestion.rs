@@ -0,0 +1,249 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Signature;
+
+use crate::streaming::event_parser::protocols::BlockMetaEvent;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// 未订阅区块元数据时价格样本不会按 slot 过期，以此为上限
+const MAX_PRICE_SAMPLES: usize = 100_000;
+
+/// Network congestion, ordered from calm to worst
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CongestionLevel {
+    #[default]
+    Low,
+    Elevated,
+    High,
+    Severe,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CongestionConfig {
+    /// Number of recent slots the metrics cover (default: 150, about one minute)
+    pub window_slots: u64,
+    /// Executed transactions per block counted as full, for the fullness ratio
+    pub block_tx_capacity: u64,
+    /// Thresholds for `Elevated`, `High` and `Severe`, each checked with `>=`
+    pub skip_rate: [f64; 3],
+    /// Median priority fee, micro-lamports per CU
+    pub median_cu_price: [u64; 3],
+    pub block_fullness: [f64; 3],
+}
+
+impl Default for CongestionConfig {
+    fn default() -> Self {
+        Self {
+            window_slots: 150,
+            block_tx_capacity: 1_500,
+            skip_rate: [0.05, 0.1, 0.25],
+            median_cu_price: [10_000, 100_000, 1_000_000],
+            block_fullness: [0.6, 0.8, 0.95],
+        }
+    }
+}
+
+/// Current congestion metrics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CongestionSnapshot {
+    pub level: CongestionLevel,
+    /// Latest block slot seen
+    pub slot: u64,
+    /// Skipped slots / slots elapsed within the window
+    pub skip_rate: f64,
+    /// Median `compute_budget.unit_price` of streamed transactions, 0 without samples
+    pub median_cu_price: u64,
+    pub avg_cu_price: u64,
+    /// Average executed transactions per block / `block_tx_capacity`
+    pub block_fullness: f64,
+}
+
+/// Level transition, passed to subscribers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CongestionChanged {
+    pub previous: CongestionLevel,
+    pub snapshot: CongestionSnapshot,
+}
+
+pub type CongestionListener = Arc<dyn Fn(&CongestionChanged) + Send + Sync>;
+
+struct BlockSample {
+    slot: u64,
+    skipped: u64,
+    tx_count: u64,
+}
+
+#[derive(Default)]
+struct CongestionState {
+    blocks: VecDeque<BlockSample>,
+    /// (slot, unit price)
+    prices: VecDeque<(u64, u64)>,
+    last_signature: Option<Signature>,
+    snapshot: CongestionSnapshot,
+}
+
+/// Derives a [`CongestionLevel`] from block meta and transaction events
+///
+/// Skipped slots come from `BlockMetaEvent::parent_slot` gaps, so block meta must be
+/// subscribed; CU prices come from the compute budget of every other event. The
+/// level is re-evaluated once per block and the worst metric wins. Subsystems that
+/// adapt to congestion either poll [`level`](Self::level) or [`subscribe`](Self::subscribe)
+/// to transitions.
+pub struct CongestionMonitor {
+    config: CongestionConfig,
+    state: Mutex<CongestionState>,
+    listeners: RwLock<Vec<CongestionListener>>,
+}
+
+impl Default for CongestionMonitor {
+    fn default() -> Self {
+        Self::new(CongestionConfig::default())
+    }
+}
+
+impl CongestionMonitor {
+    pub fn new(mut config: CongestionConfig) -> Self {
+        config.window_slots = config.window_slots.max(1);
+        config.block_tx_capacity = config.block_tx_capacity.max(1);
+        Self {
+            config,
+            state: Mutex::new(CongestionState::default()),
+            listeners: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Calls `listener` on every level transition
+    pub fn subscribe<F>(&self, listener: F)
+    where
+        F: Fn(&CongestionChanged) + Send + Sync + 'static,
+    {
+        self.listeners.write().push(Arc::new(listener));
+    }
+
+    pub fn level(&self) -> CongestionLevel {
+        self.state.lock().snapshot.level
+    }
+
+    pub fn snapshot(&self) -> CongestionSnapshot {
+        self.state.lock().snapshot.clone()
+    }
+
+    /// Records an event; returns the transition when a block changes the level
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<CongestionChanged> {
+        let mut state = self.state.lock();
+        let Some(block) = event.as_any().downcast_ref::<BlockMetaEvent>() else {
+            let metadata = event.metadata();
+            let price = metadata.compute_budget.unit_price?;
+            // 同一交易的多个事件只计一次
+            if state.last_signature == Some(metadata.signature) {
+                return None;
+            }
+            state.last_signature = Some(metadata.signature);
+            state.prices.push_back((metadata.slot, price));
+            if state.prices.len() > MAX_PRICE_SAMPLES {
+                state.prices.pop_front();
+            }
+            return None;
+        };
+
+        let skipped = match block.parent_slot {
+            0 => 0,
+            parent => block.slot.saturating_sub(parent + 1),
+        };
+        state.blocks.push_back(BlockSample {
+            slot: block.slot,
+            skipped,
+            tx_count: block.executed_transaction_count,
+        });
+        let changed = self.evaluate(&mut state, block.slot);
+        drop(state);
+        if let Some(changed) = changed.as_ref() {
+            for listener in self.listeners.read().iter() {
+                listener(changed);
+            }
+        }
+        changed
+    }
+
+    fn evaluate(&self, state: &mut CongestionState, slot: u64) -> Option<CongestionChanged> {
+        let start = slot.saturating_sub(self.config.window_slots);
+        while state.blocks.front().is_some_and(|b| b.slot <= start) {
+            state.blocks.pop_front();
+        }
+        while state.prices.front().is_some_and(|(s, _)| *s <= start) {
+            state.prices.pop_front();
+        }
+
+        let produced = state.blocks.len() as u64;
+        let skipped: u64 = state.blocks.iter().map(|b| b.skipped).sum();
+        let skip_rate = skipped as f64 / (produced + skipped).max(1) as f64;
+        let txs: u64 = state.blocks.iter().map(|b| b.tx_count).sum();
+        let block_fullness =
+            txs as f64 / produced.max(1) as f64 / self.config.block_tx_capacity as f64;
+        let mut prices: Vec<u64> = state.prices.iter().map(|(_, price)| *price).collect();
+        prices.sort_unstable();
+        let median_cu_price = prices.get(prices.len() / 2).copied().unwrap_or_default();
+        let avg_cu_price = prices.iter().sum::<u64>() / (prices.len() as u64).max(1);
+
+        let level_of = |value: f64, thresholds: [f64; 3]| match thresholds
+            .iter()
+            .rposition(|threshold| value >= *threshold)
+        {
+            Some(2) => CongestionLevel::Severe,
+            Some(1) => CongestionLevel::High,
+            Some(_) => CongestionLevel::Elevated,
+            None => CongestionLevel::Low,
+        };
+        let level = level_of(skip_rate, self.config.skip_rate)
+            .max(level_of(block_fullness, self.config.block_fullness))
+            .max(level_of(median_cu_price as f64, self.config.median_cu_price.map(|p| p as f64)));
+
+        let previous = state.snapshot.level;
+        state.snapshot = CongestionSnapshot {
+            level,
+            slot,
+            skip_rate,
+            median_cu_price,
+            avg_cu_price,
+            block_fullness,
+        };
+        (level != previous)
+            .then(|| CongestionChanged { previous, snapshot: state.snapshot.clone() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(slot: u64, parent_slot: u64) -> BlockMetaEvent {
+        BlockMetaEvent { slot, parent_slot, executed_transaction_count: 300, ..Default::default() }
+    }
+
+    #[test]
+    fn test_congestion_from_skipped_slots() {
+        let monitor =
+            CongestionMonitor::new(CongestionConfig { window_slots: 10, ..Default::default() });
+        let transitions = Arc::new(Mutex::new(Vec::new()));
+        let sink = transitions.clone();
+        monitor.subscribe(move |changed| sink.lock().push(changed.snapshot.level));
+
+        for slot in 1..=5 {
+            assert!(monitor.on_event(&block(slot, slot - 1)).is_none());
+        }
+        assert_eq!(monitor.level(), CongestionLevel::Low);
+
+        // 6..8 被跳过：3 / (6 + 3)
+        let changed = monitor.on_event(&block(9, 5)).unwrap();
+        assert_eq!(changed.previous, CongestionLevel::Low);
+        assert_eq!(changed.snapshot.level, CongestionLevel::Severe);
+        assert_eq!(*transitions.lock(), vec![CongestionLevel::Severe]);
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::match_event;
+#[cfg(feature = "raydium-amm-v4")]
+use crate::streaming::event_parser::protocols::raydium_amm_v4::{
+    types::Fees, RaydiumAmmV4AmmInfoAccountEvent,
+};
+#[cfg(feature = "raydium-clmm")]
+use crate::streaming::event_parser::protocols::raydium_clmm::{
+    RaydiumClmmAmmConfigAccountEvent, RaydiumClmmPoolStateAccountEvent, RaydiumClmmSwapEvent,
+    RaydiumClmmSwapV2Event,
+};
+#[cfg(feature = "raydium-cpmm")]
+use crate::streaming::event_parser::protocols::raydium_cpmm::{
+    RaydiumCpmmAmmConfigAccountEvent, RaydiumCpmmPoolStateAccountEvent, RaydiumCpmmSwapEvent,
+};
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Denominator of normalized fee rates (Raydium's `FEE_RATE_DENOMINATOR_VALUE`)
+pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+/// Where a pool's fee came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FeeSource {
+    /// Shared AmmConfig account (CPMM / CLMM fee tiers)
+    AmmConfig(Pubkey),
+    /// Fee stored on the pool itself (AMM v4)
+    Pool,
+}
+
+/// Trade fee of a pool, normalized to [`FEE_RATE_DENOMINATOR`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolFee {
+    pub trade_fee_rate: u64,
+    pub source: FeeSource,
+}
+
+impl PoolFee {
+    /// Fee as a fraction, e.g. 0.0025 for 25 bps
+    pub fn fraction(&self) -> f64 {
+        self.trade_fee_rate as f64 / FEE_RATE_DENOMINATOR as f64
+    }
+
+    pub fn bps(&self) -> f64 {
+        self.fraction() * 10_000.0
+    }
+}
+
+#[derive(Default)]
+struct FeeState {
+    /// AmmConfig account -> trade fee rate
+    configs: HashMap<Pubkey, u64>,
+    /// pool -> AmmConfig account
+    pool_configs: HashMap<Pubkey, Pubkey>,
+    /// pool -> trade fee rate stored on the pool
+    pool_fees: HashMap<Pubkey, u64>,
+}
+
+/// Per-pool trade fee registry built from AmmConfig and pool accounts
+///
+/// Feed account updates and swap events through [`on_event`](Self::on_event): config
+/// accounts provide the fee tiers, pool accounts and swaps link pools to their config.
+/// Prices derived from reserves can then be turned into what a trader actually gets
+/// with [`net_price`](Self::net_price).
+#[derive(Default)]
+pub struct FeeSchedule {
+    state: RwLock<FeeState>,
+}
+
+impl FeeSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records fee data carried by the event, returns whether anything was learned
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> bool {
+        let mut config: Option<(Pubkey, u64)> = None;
+        let mut pool_config: Option<(Pubkey, Pubkey)> = None;
+        let mut pool_fee: Option<(Pubkey, u64)> = None;
+
+        match_event!(event, {
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmAmmConfigAccountEvent => |e: RaydiumCpmmAmmConfigAccountEvent| {
+                config = Some((e.pubkey, e.amm_config.trade_fee_rate));
+            },
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmPoolStateAccountEvent => |e: RaydiumCpmmPoolStateAccountEvent| {
+                pool_config = Some((e.pubkey, e.pool_state.amm_config));
+            },
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmSwapEvent => |e: RaydiumCpmmSwapEvent| {
+                pool_config = Some((e.pool_state, e.amm_config));
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmAmmConfigAccountEvent => |e: RaydiumClmmAmmConfigAccountEvent| {
+                config = Some((e.pubkey, e.amm_config.trade_fee_rate as u64));
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmPoolStateAccountEvent => |e: RaydiumClmmPoolStateAccountEvent| {
+                pool_config = Some((e.pubkey, e.pool_state.amm_config));
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmSwapEvent => |e: RaydiumClmmSwapEvent| {
+                pool_config = Some((e.pool_state, e.amm_config));
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmSwapV2Event => |e: RaydiumClmmSwapV2Event| {
+                pool_config = Some((e.pool_state, e.amm_config));
+            },
+            #[cfg(feature = "raydium-amm-v4")]
+            RaydiumAmmV4AmmInfoAccountEvent => |e: RaydiumAmmV4AmmInfoAccountEvent| {
+                pool_fee = amm_v4_fee_rate(&e.amm_info.fees).map(|rate| (e.pubkey, rate));
+            },
+            _ => |_event: &dyn UnifiedEvent| {},
+        });
+
+        if config.is_none() && pool_config.is_none() && pool_fee.is_none() {
+            return false;
+        }
+        let mut state = self.state.write();
+        if let Some((key, rate)) = config {
+            state.configs.insert(key, rate);
+        }
+        if let Some((pool, key)) = pool_config.filter(|(_, key)| *key != Pubkey::default()) {
+            state.pool_configs.insert(pool, key);
+        }
+        if let Some((pool, rate)) = pool_fee {
+            state.pool_fees.insert(pool, rate);
+        }
+        true
+    }
+
+    /// Sets an AmmConfig fee tier directly, e.g. from an RPC snapshot
+    pub fn set_config_fee(&self, amm_config: Pubkey, trade_fee_rate: u64) {
+        self.state.write().configs.insert(amm_config, trade_fee_rate);
+    }
+
+    /// Sets a pool's fee directly, overriding its AmmConfig
+    pub fn set_pool_fee(&self, pool: Pubkey, trade_fee_rate: u64) {
+        self.state.write().pool_fees.insert(pool, trade_fee_rate);
+    }
+
+    /// Fee of a pool, None until both the pool's config and the config's fee are known
+    pub fn fee(&self, pool: &Pubkey) -> Option<PoolFee> {
+        let state = self.state.read();
+        if let Some(&rate) = state.pool_fees.get(pool) {
+            return Some(PoolFee { trade_fee_rate: rate, source: FeeSource::Pool });
+        }
+        let config = state.pool_configs.get(pool)?;
+        let rate = *state.configs.get(config)?;
+        Some(PoolFee { trade_fee_rate: rate, source: FeeSource::AmmConfig(*config) })
+    }
+
+    /// `gross_price` (output per input, before fees) reduced by the pool's trade fee
+    pub fn net_price(&self, pool: &Pubkey, gross_price: f64) -> Option<f64> {
+        self.fee(pool).map(|fee| gross_price * (1.0 - fee.fraction()))
+    }
+
+    /// Swap output computed without fees, reduced by the pool's trade fee
+    pub fn net_amount_out(&self, pool: &Pubkey, gross_amount_out: u64) -> Option<u64> {
+        let fee = self.fee(pool)?;
+        let kept = FEE_RATE_DENOMINATOR.saturating_sub(fee.trade_fee_rate) as u128;
+        Some((gross_amount_out as u128 * kept / FEE_RATE_DENOMINATOR as u128) as u64)
+    }
+
+    pub fn len(&self) -> usize {
+        let state = self.state.read();
+        state.pool_configs.len() + state.pool_fees.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// AMM v4 swap fee (`swap_fee_numerator / swap_fee_denominator`) normalized to ppm
+#[cfg(feature = "raydium-amm-v4")]
+fn amm_v4_fee_rate(fees: &Fees) -> Option<u64> {
+    if fees.swap_fee_denominator == 0 {
+        return None;
+    }
+    Some(
+        (fees.swap_fee_numerator as u128 * FEE_RATE_DENOMINATOR as u128
+            / fees.swap_fee_denominator as u128) as u64,
+    )
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_schedule_from_config() {
+        let fees = FeeSchedule::new();
+        let (pool, config) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let swap =
+            RaydiumCpmmSwapEvent { pool_state: pool, amm_config: config, ..Default::default() };
+        assert!(fees.on_event(&swap));
+        assert_eq!(fees.fee(&pool), None);
+
+        fees.set_config_fee(config, 2_500);
+        let fee = fees.fee(&pool).unwrap();
+        assert_eq!(fee.source, FeeSource::AmmConfig(config));
+        assert_eq!(fee.bps(), 25.0);
+        assert_eq!(fees.net_amount_out(&pool, 1_000_000), Some(997_500));
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey, pubkey::Pubkey};
+use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
+
+use super::new_pool::DEFAULT_MAX_TRACKED_WALLETS;
+use super::trade::TradeInfo;
+use crate::streaming::event_parser::UnifiedEvent;
+
+const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
+/// System 程序 Transfer / TransferWithSeed 指令编号
+const SYSTEM_TRANSFER: u32 = 2;
+const SYSTEM_TRANSFER_WITH_SEED: u32 = 11;
+/// 向上追溯资金来源的最大层数
+const MAX_FUNDING_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FundingClusterConfig {
+    /// Transfers below this are not treated as initial funding (default: 0.01 SOL)
+    pub min_funding_lamports: u64,
+    /// Wallets sharing a funder form a cluster from this many members on (default: 2)
+    pub min_cluster_size: usize,
+    /// Funders of more wallets than this are treated as exchanges and never cluster (default: 500)
+    pub max_fanout: usize,
+    /// Known exchange / faucet wallets to ignore as funders
+    pub ignored_funders: Vec<Pubkey>,
+    pub max_wallets: usize,
+}
+
+impl Default for FundingClusterConfig {
+    fn default() -> Self {
+        Self {
+            min_funding_lamports: 10_000_000,
+            min_cluster_size: 2,
+            max_fanout: 500,
+            ignored_funders: Vec::new(),
+            max_wallets: DEFAULT_MAX_TRACKED_WALLETS,
+        }
+    }
+}
+
+/// A SOL transfer from `funder` to `wallet`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FundingTransfer {
+    pub funder: Pubkey,
+    pub wallet: Pubkey,
+    pub lamports: u64,
+    pub slot: u64,
+}
+
+#[derive(Default)]
+struct FundingState {
+    /// 首次出现的 slot，已出现的钱包不再视为新钱包
+    first_seen: HashMap<Pubkey, u64>,
+    /// wallet -> 初始资金来源
+    funder_of: HashMap<Pubkey, Pubkey>,
+    /// funder -> 资助的新钱包数
+    fanout: HashMap<Pubkey, usize>,
+}
+
+/// Clusters wallets by the source of their initial SOL funding
+///
+/// The first transfer a wallet is seen receiving, before it was seen sending or trading,
+/// is its funding; wallets sharing a funder form a cluster identified by the topmost
+/// funder of the chain. Feed System transfers with [`on_transaction`](Self::on_transaction)
+/// (e.g. from `YellowstoneGrpc::subscribe_system`) and swap events with
+/// [`on_event`](Self::on_event) so trading wallets are known to be established.
+pub struct WalletFundingClusters {
+    config: FundingClusterConfig,
+    state: Mutex<FundingState>,
+}
+
+impl Default for WalletFundingClusters {
+    fn default() -> Self {
+        Self::new(FundingClusterConfig::default())
+    }
+}
+
+impl WalletFundingClusters {
+    pub fn new(config: FundingClusterConfig) -> Self {
+        Self { config, state: Mutex::new(FundingState::default()) }
+    }
+
+    /// Records a transfer, returns whether it was the initial funding of a new wallet
+    pub fn on_transfer(&self, transfer: FundingTransfer) -> bool {
+        let mut state = self.state.lock();
+        self.evict_if_full(&mut state);
+        state.first_seen.entry(transfer.funder).or_insert(transfer.slot);
+        if transfer.lamports < self.config.min_funding_lamports
+            || transfer.funder == transfer.wallet
+            || self.config.ignored_funders.contains(&transfer.funder)
+            || state.first_seen.contains_key(&transfer.wallet)
+        {
+            state.first_seen.entry(transfer.wallet).or_insert(transfer.slot);
+            return false;
+        }
+        state.first_seen.insert(transfer.wallet, transfer.slot);
+        state.funder_of.insert(transfer.wallet, transfer.funder);
+        *state.fanout.entry(transfer.funder).or_default() += 1;
+        true
+    }
+
+    /// Records the System transfers of a successful transaction, returns the new fundings
+    pub fn on_transaction(&self, slot: u64, tx: &SubscribeUpdateTransactionInfo) -> usize {
+        system_transfers(tx)
+            .into_iter()
+            .filter(|&(funder, wallet, lamports)| {
+                self.on_transfer(FundingTransfer { funder, wallet, lamports, slot })
+            })
+            .count()
+    }
+
+    /// Marks the trading wallet as established
+    pub fn on_event(&self, event: &dyn UnifiedEvent) {
+        if let Some(trade) = TradeInfo::from_event(event) {
+            let mut state = self.state.lock();
+            self.evict_if_full(&mut state);
+            state.first_seen.entry(trade.user).or_insert(trade.slot);
+        }
+    }
+
+    pub fn funder_of(&self, wallet: &Pubkey) -> Option<Pubkey> {
+        self.state.lock().funder_of.get(wallet).copied()
+    }
+
+    /// Cluster ID (topmost known funder) of the wallet, None when it is not in a cluster
+    pub fn cluster_of(&self, wallet: &Pubkey) -> Option<Pubkey> {
+        let state = self.state.lock();
+        let funder = *state.funder_of.get(wallet)?;
+        let fanout = state.fanout.get(&funder).copied().unwrap_or(0);
+        if fanout < self.config.min_cluster_size || fanout > self.config.max_fanout {
+            return None;
+        }
+        let mut root = funder;
+        for _ in 0..MAX_FUNDING_DEPTH {
+            match state.funder_of.get(&root) {
+                Some(parent)
+                    if state.fanout.get(parent).copied().unwrap_or(0) <= self.config.max_fanout =>
+                {
+                    root = *parent
+                }
+                _ => break,
+            }
+        }
+        Some(root)
+    }
+
+    /// Wallets in the cluster, scans all tracked wallets
+    pub fn cluster_members(&self, cluster: &Pubkey) -> Vec<Pubkey> {
+        let wallets: Vec<Pubkey> = self.state.lock().funder_of.keys().copied().collect();
+        wallets.into_iter().filter(|wallet| self.cluster_of(wallet) == Some(*cluster)).collect()
+    }
+
+    /// 超过上限时丢弃较早出现的一半钱包
+    fn evict_if_full(&self, state: &mut FundingState) {
+        if state.first_seen.len() < self.config.max_wallets.max(2) {
+            return;
+        }
+        let mut slots: Vec<u64> = state.first_seen.values().copied().collect();
+        let (_, cutoff, _) = slots.select_nth_unstable(slots.len() / 2);
+        let cutoff = *cutoff;
+        state.first_seen.retain(|_, seen| *seen > cutoff);
+        let FundingState { first_seen, funder_of, fanout } = state;
+        funder_of.retain(|wallet, funder| {
+            let keep = first_seen.contains_key(wallet);
+            if !keep {
+                if let Some(count) = fanout.get_mut(funder) {
+                    *count = count.saturating_sub(1);
+                }
+            }
+            keep
+        });
+        fanout.retain(|_, count| *count > 0);
+    }
+}
+
+/// (from, to, lamports) of the System transfers in a successful transaction, inner ones included
+fn system_transfers(tx: &SubscribeUpdateTransactionInfo) -> Vec<(Pubkey, Pubkey, u64)> {
+    if tx.meta.as_ref().is_none_or(|meta| meta.err.is_some()) {
+        return Vec::new();
+    }
+    system_instructions(tx)
+        .into_iter()
+        .filter_map(|ix| {
+            let lamports = u64::from_le_bytes(ix.data.get(4..12)?.try_into().ok()?);
+            let (from, to) = match ix.kind {
+                SYSTEM_TRANSFER => (ix.accounts.first()?, ix.accounts.get(1)?),
+                SYSTEM_TRANSFER_WITH_SEED => (ix.accounts.first()?, ix.accounts.get(2)?),
+                _ => return None,
+            };
+            Some((*from, *to, lamports))
+        })
+        .collect()
+}
+
+/// A System program instruction of a transaction with its accounts resolved
+pub(super) struct SystemInstruction<'a> {
+    pub kind: u32,
+    pub accounts: Vec<Pubkey>,
+    pub data: &'a [u8],
+}
+
+/// System program instructions of a transaction in execution order, inner ones included
+pub(super) fn system_instructions(
+    tx: &SubscribeUpdateTransactionInfo,
+) -> Vec<SystemInstruction<'_>> {
+    let (Some(transaction), Some(meta)) = (tx.transaction.as_ref(), tx.meta.as_ref()) else {
+        return Vec::new();
+    };
+    let Some(message) = transaction.message.as_ref() else {
+        return Vec::new();
+    };
+    let accounts: Vec<Pubkey> = message
+        .account_keys
+        .iter()
+        .chain(&meta.loaded_writable_addresses)
+        .chain(&meta.loaded_readonly_addresses)
+        .map(|key| Pubkey::try_from(key.as_slice()).unwrap_or_default())
+        .collect();
+    let mut instructions = Vec::new();
+    for (index, ix) in message.instructions.iter().enumerate() {
+        let outer = std::iter::once((ix.program_id_index, &ix.accounts, &ix.data));
+        // 内联指令紧随其所属的顶层指令
+        let inner = meta
+            .inner_instructions
+            .iter()
+            .filter(|inner| inner.index as usize == index)
+            .flat_map(|inner| inner.instructions.iter())
+            .map(|ix| (ix.program_id_index, &ix.accounts, &ix.data));
+        for (program, ix_accounts, data) in outer.chain(inner) {
+            if accounts.get(program as usize) != Some(&SYSTEM_PROGRAM_ID) {
+                continue;
+            }
+            let Some(kind) = data.get(..4).and_then(|kind| kind.try_into().ok()) else {
+                continue;
+            };
+            let Some(ix_accounts) = ix_accounts
+                .iter()
+                .map(|index| accounts.get(*index as usize).copied())
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            instructions.push(SystemInstruction {
+                kind: u32::from_le_bytes(kind),
+                accounts: ix_accounts,
+                data,
+            });
+        }
+    }
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_funding_clusters() {
+        let clusters = WalletFundingClusters::new(FundingClusterConfig {
+            max_fanout: 3,
+            ..Default::default()
+        });
+        let fund = |funder, wallet, slot| {
+            clusters.on_transfer(FundingTransfer { funder, wallet, lamports: 50_000_000, slot })
+        };
+        let (root, sybil_funder) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let sybils = [Pubkey::new_unique(), Pubkey::new_unique()];
+        assert!(fund(root, sybil_funder, 1));
+        assert!(fund(sybil_funder, sybils[0], 2));
+        assert_eq!(clusters.cluster_of(&sybils[0]), None);
+        assert!(fund(sybil_funder, sybils[1], 3));
+        assert_eq!(clusters.cluster_of(&sybils[0]), Some(root));
+        assert_eq!(clusters.cluster_members(&root).len(), 2);
+
+        // 已出现过的钱包再收到转账不算初始资金
+        assert!(!fund(Pubkey::new_unique(), sybils[0], 4));
+
+        let exchange = Pubkey::new_unique();
+        let users: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        for user in &users {
+            fund(exchange, *user, 5);
+        }
+        assert_eq!(clusters.cluster_of(&users[0]), None);
+    }
+}
@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::match_event;
+use crate::streaming::event_parser::core::account_event_parser::{
+    TokenAccountEvent, TokenInfoEvent,
+};
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Number of holders counted in the top-holder share
+pub const DEFAULT_TOP_HOLDERS: usize = 10;
+/// Top-holder share change (absolute, 0.05 = 5 points) that triggers an event
+pub const DEFAULT_CONCENTRATION_CHANGE: f64 = 0.05;
+/// Default cap on token accounts tracked per mint
+pub const DEFAULT_MAX_HOLDER_ACCOUNTS: usize = 10_000;
+
+/// Balance of one holder (token accounts of the same owner are summed)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HolderBalance {
+    pub owner: Pubkey,
+    pub amount: u64,
+    /// Share of supply (or of tracked balances while the supply is unknown)
+    pub share: f64,
+}
+
+/// Holder distribution of a mint at a point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HolderConcentration {
+    pub mint: Pubkey,
+    pub slot: u64,
+    /// Mint supply, `None` until a mint account update has been seen
+    pub supply: Option<u64>,
+    /// Holders with a non-zero balance among the tracked accounts; a lower bound once
+    /// the per-mint account cap has dropped small balances
+    pub holder_count: usize,
+    pub top_holders: Vec<HolderBalance>,
+    /// Combined share of `top_holders`
+    pub top_share: f64,
+    /// Herfindahl-Hirschman index over all tracked holders (0.0 - 1.0)
+    pub hhi: f64,
+}
+
+/// Emitted when a watched mint's top-holder share moves past the threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HolderConcentrationChanged {
+    pub current: HolderConcentration,
+    /// Top share at the previous emission, `None` for the first one
+    pub previous_top_share: Option<f64>,
+}
+
+#[derive(Default)]
+struct MintHolders {
+    supply: Option<u64>,
+    /// token account -> (owner, amount)
+    accounts: HashMap<Pubkey, (Pubkey, u64)>,
+    last_emitted_top_share: Option<f64>,
+}
+
+impl MintHolders {
+    /// Records a non-zero balance; at the cap the smallest balance makes room, or the
+    /// update itself is dropped when it is the smallest
+    fn upsert(&mut self, account: Pubkey, owner: Pubkey, amount: u64, max_accounts: usize) {
+        if !self.accounts.contains_key(&account) && self.accounts.len() >= max_accounts {
+            let smallest = self
+                .accounts
+                .iter()
+                .min_by_key(|(_, (_, amount))| *amount)
+                .map(|(account, (_, amount))| (*account, *amount));
+            match smallest {
+                Some((smallest, smallest_amount)) if smallest_amount < amount => {
+                    self.accounts.remove(&smallest);
+                }
+                _ => return,
+            }
+        }
+        self.accounts.insert(account, (owner, amount));
+    }
+}
+
+/// Tracks the holder distribution of watched mints from token account updates
+///
+/// Feed it `TokenAccountEvent`/`TokenInfoEvent`s from a subscription built with
+/// [`HolderConcentrationTracker::account_filters`]; optionally seed it from RPC with
+/// [`HolderConcentrationTracker::seed_largest_accounts`] so the initial picture does
+/// not depend on which accounts happen to change first.
+///
+/// At most [`DEFAULT_MAX_HOLDER_ACCOUNTS`] token accounts are kept per mint (see
+/// [`with_max_accounts_per_mint`](Self::with_max_accounts_per_mint)); beyond that the
+/// smallest balances are dropped, which leaves the top holders exact and makes the
+/// holder count a lower bound.
+pub struct HolderConcentrationTracker {
+    top_n: usize,
+    change_threshold: f64,
+    max_accounts_per_mint: usize,
+    mints: Mutex<HashMap<Pubkey, MintHolders>>,
+}
+
+impl Default for HolderConcentrationTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_TOP_HOLDERS, DEFAULT_CONCENTRATION_CHANGE)
+    }
+}
+
+impl HolderConcentrationTracker {
+    pub fn new(top_n: usize, change_threshold: f64) -> Self {
+        Self {
+            top_n: top_n.max(1),
+            change_threshold,
+            max_accounts_per_mint: DEFAULT_MAX_HOLDER_ACCOUNTS,
+            mints: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the cap on token accounts tracked per mint
+    pub fn with_max_accounts_per_mint(mut self, max_accounts: usize) -> Self {
+        self.max_accounts_per_mint = max_accounts.max(self.top_n);
+        self
+    }
+
+    /// Starts tracking a mint; no-op if it is already watched
+    pub fn watch(&self, mint: Pubkey) {
+        self.mints.lock().entry(mint).or_default();
+    }
+
+    /// Stops tracking a mint and drops its balances
+    pub fn unwatch(&self, mint: &Pubkey) {
+        self.mints.lock().remove(mint);
+    }
+
+    pub fn watched_mints(&self) -> Vec<Pubkey> {
+        self.mints.lock().keys().copied().collect()
+    }
+
+    /// Current distribution of a watched mint
+    pub fn snapshot(&self, mint: &Pubkey) -> Option<HolderConcentration> {
+        let mints = self.mints.lock();
+        mints.get(mint).map(|holders| self.compute(*mint, 0, holders))
+    }
+
+    /// Applies an account update; returns an event if the concentration changed materially
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<HolderConcentrationChanged> {
+        let mut update: Option<(Pubkey, Pubkey, Pubkey, u64)> = None;
+        let mut supply: Option<(Pubkey, u64)> = None;
+        match_event!(event, {
+            TokenAccountEvent => |e: TokenAccountEvent| {
+                update = e.amount.map(|amount| (e.mint, e.pubkey, e.token_owner, amount));
+            },
+            TokenInfoEvent => |e: TokenInfoEvent| {
+                supply = Some((e.pubkey, e.supply));
+            },
+            _ => |_event: &dyn UnifiedEvent| {},
+        });
+
+        let mut mints = self.mints.lock();
+        let mint = if let Some((mint, account, owner, amount)) = update {
+            let holders = mints.get_mut(&mint)?;
+            if amount == 0 {
+                holders.accounts.remove(&account);
+            } else {
+                holders.upsert(account, owner, amount, self.max_accounts_per_mint);
+            }
+            mint
+        } else if let Some((mint, value)) = supply {
+            mints.get_mut(&mint)?.supply = Some(value);
+            mint
+        } else {
+            return None;
+        };
+
+        let holders = mints.get_mut(&mint)?;
+        let current = self.compute(mint, event.slot(), holders);
+        let previous_top_share = holders.last_emitted_top_share;
+        let changed = match previous_top_share {
+            Some(previous) => (current.top_share - previous).abs() >= self.change_threshold,
+            None => current.holder_count > 0,
+        };
+        if !changed {
+            return None;
+        }
+        holders.last_emitted_top_share = Some(current.top_share);
+        Some(HolderConcentrationChanged { current, previous_top_share })
+    }
+
+    fn compute(&self, mint: Pubkey, slot: u64, holders: &MintHolders) -> HolderConcentration {
+        let mut by_owner: HashMap<Pubkey, u64> = HashMap::new();
+        for (owner, amount) in holders.accounts.values() {
+            *by_owner.entry(*owner).or_default() += amount;
+        }
+        let tracked: u128 = by_owner.values().map(|&a| a as u128).sum();
+        // 供应量未知时以已跟踪余额作为分母
+        let total = match holders.supply {
+            Some(supply) if supply > 0 => supply as u128,
+            _ => tracked,
+        };
+        let share = |amount: u64| if total == 0 { 0.0 } else { amount as f64 / total as f64 };
+
+        let mut balances: Vec<(Pubkey, u64)> = by_owner.into_iter().collect();
+        balances.sort_by(|a, b| b.1.cmp(&a.1));
+        let hhi = balances.iter().map(|(_, amount)| share(*amount).powi(2)).sum();
+        let top_holders: Vec<HolderBalance> = balances
+            .iter()
+            .take(self.top_n)
+            .map(|(owner, amount)| HolderBalance {
+                owner: *owner,
+                amount: *amount,
+                share: share(*amount),
+            })
+            .collect();
+
+        HolderConcentration {
+            mint,
+            slot,
+            supply: holders.supply,
+            holder_count: balances.len(),
+            top_share: top_holders.iter().map(|h| h.share).sum(),
+            top_holders,
+            hhi,
+        }
+    }
+}
+
+#[cfg(feature = "transport")]
+mod rpc {
+    use std::str::FromStr;
+
+    use solana_sdk::pubkey::Pubkey;
+    use spl_token::solana_program::program_pack::Pack;
+    use spl_token::state::Account;
+    use spl_token_2022::{extension::StateWithExtensions, state::Account as Account2022};
+    use yellowstone_grpc_proto::geyser::{
+        subscribe_request_filter_accounts_filter::Filter,
+        subscribe_request_filter_accounts_filter_memcmp::Data,
+        SubscribeRequestFilterAccountsFilter, SubscribeRequestFilterAccountsFilterMemcmp,
+    };
+
+    use super::HolderConcentrationTracker;
+    use crate::common::{AnyResult, SolanaRpcClient};
+    use crate::streaming::yellowstone_grpc::AccountFilter;
+
+    impl HolderConcentrationTracker {
+        /// Account subscriptions covering the watched mints and all their token accounts
+        ///
+        /// One filter per mint is needed since memcmp filters within a filter are AND-ed.
+        /// Every balance change of every token account of a watched mint is streamed, and
+        /// geyser offers no "largest accounts only" filter: an established mint with
+        /// hundreds of thousands of holders costs that much bandwidth and callback time.
+        /// Watch such mints sparingly; the per-mint cap bounds memory, not the stream.
+        pub fn account_filters(&self) -> Vec<AccountFilter> {
+            let mints = self.watched_mints();
+            if mints.is_empty() {
+                return Vec::new();
+            }
+            let mut filters = vec![AccountFilter {
+                account: mints.iter().map(|m| m.to_string()).collect(),
+                owner: vec![],
+                filters: vec![],
+            }];
+            // 代币账户布局中 mint 位于偏移 0
+            filters.extend(mints.iter().map(|mint| AccountFilter {
+                account: vec![],
+                owner: vec![spl_token::ID.to_string(), spl_token_2022::ID.to_string()],
+                filters: vec![SubscribeRequestFilterAccountsFilter {
+                    filter: Some(Filter::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp {
+                        offset: 0,
+                        data: Some(Data::Bytes(mint.to_bytes().to_vec())),
+                    })),
+                }],
+            }));
+            filters
+        }
+
+        /// Loads the supply and largest token accounts of a watched mint from RPC
+        pub async fn seed_largest_accounts(
+            &self,
+            rpc: &SolanaRpcClient,
+            mint: &Pubkey,
+        ) -> AnyResult<()> {
+            let supply = rpc.get_token_supply(mint).await?.amount.parse::<u64>()?;
+            let largest = rpc.get_token_largest_accounts(mint).await?;
+            let addresses = largest
+                .iter()
+                .map(|b| Pubkey::from_str(&b.address))
+                .collect::<Result<Vec<_>, _>>()?;
+            let accounts = rpc.get_multiple_accounts(&addresses).await?;
+
+            let mut mints = self.mints.lock();
+            let holders = mints.entry(*mint).or_default();
+            holders.supply = Some(supply);
+            for (address, account) in addresses.into_iter().zip(accounts) {
+                let Some(account) = account else {
+                    continue;
+                };
+                let state = if account.owner.to_bytes() == spl_token_2022::ID.to_bytes() {
+                    StateWithExtensions::<Account2022>::unpack(&account.data)
+                        .ok()
+                        .map(|info| (info.base.owner.to_bytes(), info.base.amount))
+                } else {
+                    Account::unpack(&account.data)
+                        .ok()
+                        .map(|info| (info.owner.to_bytes(), info.amount))
+                };
+                if let Some((owner, amount)) = state.filter(|(_, amount)| *amount > 0) {
+                    holders.upsert(
+                        address,
+                        Pubkey::new_from_array(owner),
+                        amount,
+                        self.max_accounts_per_mint,
+                    );
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance(mint: Pubkey, account: Pubkey, owner: Pubkey, amount: u64) -> TokenAccountEvent {
+        TokenAccountEvent {
+            pubkey: account,
+            amount: Some(amount),
+            token_owner: owner,
+            mint,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_concentration_change_emitted() {
+        let tracker = HolderConcentrationTracker::new(1, 0.05);
+        let mint = Pubkey::new_unique();
+        let (whale, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let whale_account = Pubkey::new_unique();
+        tracker.watch(mint);
+
+        // 未监视的 mint 不处理
+        assert!(tracker
+            .on_event(&balance(Pubkey::new_unique(), whale_account, whale, 1))
+            .is_none());
+        tracker.on_event(&TokenInfoEvent { pubkey: mint, supply: 1_000, ..Default::default() });
+
+        let first = tracker.on_event(&balance(mint, whale_account, whale, 500)).unwrap();
+        assert_eq!(first.previous_top_share, None);
+        assert!((first.current.top_share - 0.5).abs() < 1e-9);
+        // 其他持有者的小额变化不触发
+        assert!(tracker.on_event(&balance(mint, Pubkey::new_unique(), other, 10)).is_none());
+
+        let changed = tracker.on_event(&balance(mint, whale_account, whale, 200)).unwrap();
+        assert_eq!(changed.previous_top_share, Some(0.5));
+        assert_eq!(changed.current.top_holders[0].owner, whale);
+        assert!((changed.current.top_share - 0.2).abs() < 1e-9);
+        assert_eq!(changed.current.holder_count, 2);
+    }
+
+    #[test]
+    fn test_accounts_per_mint_capped() {
+        let tracker = HolderConcentrationTracker::new(2, 1.0).with_max_accounts_per_mint(3);
+        let mint = Pubkey::new_unique();
+        tracker.watch(mint);
+        let owners: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        for (i, owner) in owners.iter().enumerate() {
+            tracker.on_event(&balance(mint, Pubkey::new_unique(), *owner, (i as u64 + 1) * 100));
+        }
+        // 低于最小余额的新账户被丢弃
+        tracker.on_event(&balance(mint, Pubkey::new_unique(), Pubkey::new_unique(), 50));
+
+        let snapshot = tracker.snapshot(&mint).unwrap();
+        assert_eq!(snapshot.holder_count, 3);
+        let top: Vec<Pubkey> = snapshot.top_holders.iter().map(|h| h.owner).collect();
+        assert_eq!(top, vec![owners[4], owners[3]]);
+        assert!((snapshot.top_share - 900.0 / 1_200.0).abs() < 1e-9);
+    }
+}
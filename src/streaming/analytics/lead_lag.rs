@@ -0,0 +1,344 @@
+use std::collections::{BTreeMap, HashMap};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use super::trade::{normalize_sol_mint, TradeInfo};
+use crate::streaming::event_parser::common::ProtocolType;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default price sampling interval (1 second)
+pub const DEFAULT_LEAD_LAG_BUCKET_MS: i64 = 1_000;
+/// Default correlation window (2 minutes of buckets)
+pub const DEFAULT_LEAD_LAG_WINDOW_BUCKETS: usize = 120;
+/// Default largest lag tested, in buckets
+pub const DEFAULT_LEAD_LAG_MAX_LAG_BUCKETS: usize = 5;
+/// Default report cadence (1 minute)
+pub const DEFAULT_LEAD_LAG_REPORT_INTERVAL_MS: i64 = 60_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LeadLagConfig {
+    /// Each venue's price is sampled (last trade) once per bucket
+    pub bucket_ms: i64,
+    /// Buckets the correlations are computed over
+    pub window_buckets: usize,
+    /// Lags from 0 up to this many buckets are tested in both directions
+    pub max_lag_buckets: usize,
+    /// Venues need trades in at least this many buckets of the window to be compared
+    pub min_trade_buckets: usize,
+    pub report_interval_ms: i64,
+    /// Treat native SOL as WSOL when keying pairs (default: true)
+    pub normalize_native_sol: bool,
+}
+
+impl Default for LeadLagConfig {
+    fn default() -> Self {
+        Self {
+            bucket_ms: DEFAULT_LEAD_LAG_BUCKET_MS,
+            window_buckets: DEFAULT_LEAD_LAG_WINDOW_BUCKETS,
+            max_lag_buckets: DEFAULT_LEAD_LAG_MAX_LAG_BUCKETS,
+            min_trade_buckets: 10,
+            report_interval_ms: DEFAULT_LEAD_LAG_REPORT_INTERVAL_MS,
+            normalize_native_sol: true,
+        }
+    }
+}
+
+/// How price moves of one venue relate to another's for the same pair
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VenueLeadLag {
+    pub leader: ProtocolType,
+    pub follower: ProtocolType,
+    /// How far the follower trails the leader, 0 when they move together
+    pub lag_ms: i64,
+    /// Return correlation at `lag_ms`, the best lag tested
+    pub correlation: f64,
+    /// Return correlation without lag
+    pub correlation_at_zero: f64,
+}
+
+/// Lead-lag relations between the venues trading one pair within a window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeadLagReport {
+    pub token_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    /// Window start (inclusive), milliseconds since epoch
+    pub window_start_ms: i64,
+    /// Window end (exclusive), milliseconds since epoch
+    pub window_end_ms: i64,
+    /// One entry per compared venue pair, strongest correlation first
+    pub venues: Vec<VenueLeadLag>,
+}
+
+/// (token mint, quote mint)
+type PairKey = (Pubkey, Pubkey);
+
+#[derive(Default)]
+struct LeadLagState {
+    /// pair -> venue -> bucket start -> last price of the bucket
+    prices: HashMap<PairKey, HashMap<ProtocolType, BTreeMap<i64, f64>>>,
+    current_bucket_ms: Option<i64>,
+    next_report_ms: Option<i64>,
+}
+
+/// Finds which venue's price moves lead the others, per token pair
+///
+/// Swap prices are sampled per venue into buckets aligned to event (block) time; every
+/// `report_interval_ms` the bucket returns of each pair of venues are cross-correlated
+/// at lags up to `max_lag_buckets` and the best lag is reported. A venue that leads is
+/// where price discovery happens (quote from it), followers are where to hedge.
+pub struct LeadLagAnalyzer {
+    config: LeadLagConfig,
+    state: Mutex<LeadLagState>,
+}
+
+impl Default for LeadLagAnalyzer {
+    fn default() -> Self {
+        Self::new(LeadLagConfig::default())
+    }
+}
+
+impl LeadLagAnalyzer {
+    pub fn new(mut config: LeadLagConfig) -> Self {
+        config.bucket_ms = config.bucket_ms.max(1);
+        config.window_buckets = config.window_buckets.max(config.max_lag_buckets + 3);
+        config.min_trade_buckets = config.min_trade_buckets.max(2);
+        Self { config, state: Mutex::new(LeadLagState::default()) }
+    }
+
+    /// Records a swap; returns the reports when the event closes a report interval
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Vec<LeadLagReport> {
+        let Some(trade) = TradeInfo::from_event_with(event, self.config.normalize_native_sol)
+        else {
+            return Vec::new();
+        };
+        self.on_trade(&trade, trade.time_ms(event.recv_us()))
+    }
+
+    pub fn on_trade(&self, trade: &TradeInfo, time_ms: i64) -> Vec<LeadLagReport> {
+        let Some(side) = trade.token_side() else {
+            return Vec::new();
+        };
+        let (quote_mint, quote_amount) = if side.is_buy {
+            (trade.input_mint, trade.amount_in)
+        } else {
+            (trade.output_mint, trade.amount_out)
+        };
+        if side.amount == 0 || quote_amount == 0 {
+            return Vec::new();
+        }
+        // 原始单位之比，精度差异在收益率中抵消
+        let price = quote_amount as f64 / side.amount as f64;
+        let bucket_ms = time_ms - time_ms.rem_euclid(self.config.bucket_ms);
+
+        let mut state = self.state.lock();
+        let reports = self.advance(&mut state, bucket_ms);
+        // 迟到的事件计入当前桶
+        let bucket_ms = state.current_bucket_ms.unwrap_or(bucket_ms);
+        state
+            .prices
+            .entry((side.mint, normalize_sol_mint(quote_mint, self.config.normalize_native_sol)))
+            .or_default()
+            .entry(trade.protocol.clone())
+            .or_default()
+            .insert(bucket_ms, price);
+        reports
+    }
+
+    /// Emits due reports for idle periods with no events
+    pub fn tick(&self, now_ms: i64) -> Vec<LeadLagReport> {
+        let mut state = self.state.lock();
+        self.advance(&mut state, now_ms - now_ms.rem_euclid(self.config.bucket_ms))
+    }
+
+    /// Reports over the window ending before the current bucket, for all pairs
+    pub fn report(&self) -> Vec<LeadLagReport> {
+        let state = self.state.lock();
+        match state.current_bucket_ms {
+            Some(current) => self.reports(&state, current),
+            None => Vec::new(),
+        }
+    }
+
+    fn advance(&self, state: &mut LeadLagState, bucket_ms: i64) -> Vec<LeadLagReport> {
+        if state.current_bucket_ms.is_some_and(|current| bucket_ms <= current) {
+            return Vec::new();
+        }
+        state.current_bucket_ms = Some(bucket_ms);
+        let next_report_ms =
+            *state.next_report_ms.get_or_insert(bucket_ms + self.config.report_interval_ms);
+        if bucket_ms < next_report_ms {
+            return Vec::new();
+        }
+        state.next_report_ms = Some(bucket_ms + self.config.report_interval_ms);
+
+        // 保留一个桶之前的价格作为窗口起点的前值
+        let keep_from = self.window_start(bucket_ms) - self.config.bucket_ms;
+        state.prices.retain(|_, venues| {
+            venues.retain(|_, buckets| {
+                *buckets = buckets.split_off(&keep_from);
+                !buckets.is_empty()
+            });
+            !venues.is_empty()
+        });
+        self.reports(state, bucket_ms)
+    }
+
+    fn window_start(&self, end_ms: i64) -> i64 {
+        end_ms - self.config.window_buckets as i64 * self.config.bucket_ms
+    }
+
+    fn reports(&self, state: &LeadLagState, end_ms: i64) -> Vec<LeadLagReport> {
+        let start_ms = self.window_start(end_ms);
+        let mut reports: Vec<LeadLagReport> = state
+            .prices
+            .iter()
+            .filter_map(|(&(token_mint, quote_mint), venues)| {
+                let series: Vec<(&ProtocolType, Vec<f64>)> = venues
+                    .iter()
+                    .filter_map(|(venue, buckets)| {
+                        Some((venue, self.returns(buckets, start_ms, end_ms)?))
+                    })
+                    .collect();
+                let mut compared = Vec::new();
+                for (i, (a, returns_a)) in series.iter().enumerate() {
+                    for (b, returns_b) in &series[i + 1..] {
+                        compared.push(self.lead_lag(a, returns_a, b, returns_b));
+                    }
+                }
+                compared.retain(|pair| pair.correlation.is_finite());
+                if compared.is_empty() {
+                    return None;
+                }
+                compared.sort_by(|x, y| y.correlation.total_cmp(&x.correlation));
+                Some(LeadLagReport {
+                    token_mint,
+                    quote_mint,
+                    window_start_ms: start_ms,
+                    window_end_ms: end_ms,
+                    venues: compared,
+                })
+            })
+            .collect();
+        reports.sort_by_key(|report| (report.token_mint, report.quote_mint));
+        reports
+    }
+
+    /// Log returns of the forward-filled bucket prices in `[start_ms, end_ms)`, None
+    /// when the venue traded in too few buckets
+    fn returns(
+        &self,
+        buckets: &BTreeMap<i64, f64>,
+        start_ms: i64,
+        end_ms: i64,
+    ) -> Option<Vec<f64>> {
+        if buckets.range(start_ms..end_ms).count() < self.config.min_trade_buckets {
+            return None;
+        }
+        let mut last = buckets.range(..start_ms).next_back().map(|(_, price)| *price);
+        let mut returns = Vec::with_capacity(self.config.window_buckets);
+        let mut bucket_ms = start_ms;
+        while bucket_ms < end_ms {
+            let price = buckets.get(&bucket_ms).copied().or(last);
+            returns.push(match (last, price) {
+                (Some(previous), Some(price)) => (price / previous).ln(),
+                _ => 0.0,
+            });
+            last = price;
+            bucket_ms += self.config.bucket_ms;
+        }
+        Some(returns)
+    }
+
+    fn lead_lag(
+        &self,
+        a: &ProtocolType,
+        returns_a: &[f64],
+        b: &ProtocolType,
+        returns_b: &[f64],
+    ) -> VenueLeadLag {
+        let correlation_at_zero = correlation(returns_a, returns_b);
+        // lag > 0：a 领先 b（b 在 lag 个桶之后跟随）
+        let mut best = (0i64, correlation_at_zero);
+        for lag in 1..=self.config.max_lag_buckets {
+            let a_leads = correlation(&returns_a[..returns_a.len() - lag], &returns_b[lag..]);
+            let b_leads = correlation(&returns_b[..returns_b.len() - lag], &returns_a[lag..]);
+            for (signed_lag, value) in [(lag as i64, a_leads), (-(lag as i64), b_leads)] {
+                if value > best.1 || !best.1.is_finite() {
+                    best = (signed_lag, value);
+                }
+            }
+        }
+        let (leader, follower) = if best.0 < 0 { (b, a) } else { (a, b) };
+        VenueLeadLag {
+            leader: leader.clone(),
+            follower: follower.clone(),
+            lag_ms: best.0.abs() * self.config.bucket_ms,
+            correlation: best.1,
+            correlation_at_zero,
+        }
+    }
+}
+
+/// Pearson correlation, NaN when either series is constant
+fn correlation(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len().min(y.len()) as f64;
+    if n < 2.0 {
+        return f64::NAN;
+    }
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (a, b) in x.iter().zip(y) {
+        cov += (a - mean_x) * (b - mean_y);
+        var_x += (a - mean_x).powi(2);
+        var_y += (b - mean_y).powi(2);
+    }
+    cov / (var_x * var_y).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::analytics::trade::WSOL_MINT;
+
+    #[test]
+    fn test_lead_lag_detects_leader() {
+        let analyzer = LeadLagAnalyzer::new(LeadLagConfig {
+            window_buckets: 60,
+            report_interval_ms: 60_000,
+            ..Default::default()
+        });
+        let token = Pubkey::new_unique();
+        // CLMM 的价格变动比 CPMM 早 2 个桶
+        let price_at = |t: i64| 1_000.0 + ((t * 7919) % 97) as f64;
+        let trade = |protocol: ProtocolType, price: f64| TradeInfo {
+            protocol,
+            input_mint: WSOL_MINT,
+            output_mint: token,
+            amount_in: (price * 1_000.0) as u64,
+            amount_out: 1_000,
+            ..Default::default()
+        };
+        let mut reports = Vec::new();
+        for t in 0..=61 {
+            let time_ms = 1_700_000_000_000 + t * 1_000;
+            reports
+                .extend(analyzer.on_trade(&trade(ProtocolType::RaydiumClmm, price_at(t)), time_ms));
+            reports.extend(
+                analyzer.on_trade(&trade(ProtocolType::RaydiumCpmm, price_at(t - 2)), time_ms),
+            );
+        }
+        assert_eq!(reports.len(), 1);
+        let venues = &reports[0].venues[0];
+        assert_eq!((reports[0].token_mint, reports[0].quote_mint), (token, WSOL_MINT));
+        assert_eq!(
+            (&venues.leader, &venues.follower),
+            (&ProtocolType::RaydiumClmm, &ProtocolType::RaydiumCpmm)
+        );
+        assert_eq!(venues.lag_ms, 2_000);
+        assert!(venues.correlation > 0.9);
+    }
+}
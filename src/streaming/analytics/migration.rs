@@ -0,0 +1,390 @@
+use std::collections::{HashMap, VecDeque};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use super::trade::{normalize_sol_mint, QUOTE_MINTS};
+use crate::match_event;
+use crate::streaming::event_parser::common::ProtocolType;
+#[cfg(feature = "raydium-amm-v4")]
+use crate::streaming::event_parser::protocols::raydium_amm_v4::{
+    RaydiumAmmV4AmmInfoAccountEvent, RaydiumAmmV4DepositEvent, RaydiumAmmV4Initialize2Event,
+    RaydiumAmmV4WithdrawEvent,
+};
+#[cfg(feature = "raydium-clmm")]
+use crate::streaming::event_parser::protocols::raydium_clmm::{
+    RaydiumClmmCreatePoolEvent, RaydiumClmmDecreaseLiquidityV2Event,
+    RaydiumClmmIncreaseLiquidityV2Event,
+};
+#[cfg(feature = "raydium-cpmm")]
+use crate::streaming::event_parser::protocols::raydium_cpmm::{
+    RaydiumCpmmDepositEvent, RaydiumCpmmInitializeEvent, RaydiumCpmmWithdrawEvent,
+};
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default number of slots between removal and re-add that still counts as a migration
+pub const DEFAULT_MIGRATION_WINDOW_SLOTS: u64 = 150;
+/// Default cap on pools whose mints are remembered
+pub const DEFAULT_MAX_MIGRATION_POOLS: usize = 100_000;
+
+/// Liquidity of a token removed from one pool and added to another shortly after
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiquidityMigration {
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub source_venue: ProtocolType,
+    pub source_pool: Pubkey,
+    pub removed_by: Pubkey,
+    pub removed_slot: u64,
+    pub removed_signature: Signature,
+    /// Quote-side limit amount of the removal, None when the instruction has none
+    pub removed_quote_amount: Option<u64>,
+    pub destination_venue: ProtocolType,
+    pub destination_pool: Pubkey,
+    pub added_by: Pubkey,
+    pub added_slot: u64,
+    pub added_signature: Signature,
+    pub added_quote_amount: Option<u64>,
+    /// The destination pool was created by the add
+    pub pool_created: bool,
+    /// Removal and add were signed by the same wallet
+    pub same_wallet: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChangeKind {
+    Created,
+    Added,
+    Removed,
+}
+
+/// One add / remove of liquidity, normalized across venues
+#[derive(Debug, Clone)]
+struct LiquidityChange {
+    kind: ChangeKind,
+    pool: Pubkey,
+    user: Pubkey,
+    /// None when the event does not carry the mints (AMM V4 deposit / withdraw)
+    mints: Option<(Pubkey, Pubkey)>,
+    amounts: (Option<u64>, Option<u64>),
+}
+
+#[derive(Debug, Clone)]
+struct Removal {
+    venue: ProtocolType,
+    pool: Pubkey,
+    user: Pubkey,
+    slot: u64,
+    signature: Signature,
+    quote_mint: Pubkey,
+    quote_amount: Option<u64>,
+}
+
+#[derive(Default)]
+struct MigrationState {
+    /// pool -> (mint_a, mint_b)
+    pool_mints: HashMap<Pubkey, (Pubkey, Pubkey)>,
+    pool_order: VecDeque<Pubkey>,
+    /// token mint -> recent removals, oldest first
+    removals: HashMap<Pubkey, VecDeque<Removal>>,
+}
+
+/// Correlates liquidity leaving one pool with liquidity (or a new pool) appearing
+/// for the same token elsewhere, e.g. CPMM → CLMM
+///
+/// Removals and adds are matched on the token (non-quote) mint within `window_slots`;
+/// both sides must move at least `min_quote_amount` of the quote mint when the
+/// instruction carries an amount. Instructions only carry slippage limits, so the
+/// amounts are the user's minimum (removal) / maximum (add) and not exact.
+pub struct MigrationTracker {
+    window_slots: u64,
+    min_quote_amount: u64,
+    max_pools: usize,
+    normalize_native_sol: bool,
+    state: Mutex<MigrationState>,
+}
+
+impl Default for MigrationTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_MIGRATION_WINDOW_SLOTS, 0)
+    }
+}
+
+impl MigrationTracker {
+    pub fn new(window_slots: u64, min_quote_amount: u64) -> Self {
+        Self {
+            window_slots,
+            min_quote_amount,
+            max_pools: DEFAULT_MAX_MIGRATION_POOLS,
+            normalize_native_sol: true,
+            state: Mutex::new(MigrationState::default()),
+        }
+    }
+
+    pub fn with_max_pools(mut self, max_pools: usize) -> Self {
+        self.max_pools = max_pools.max(1);
+        self
+    }
+
+    /// Whether a native SOL quote side is reported as WSOL (default: true)
+    pub fn with_native_sol_normalization(mut self, enabled: bool) -> Self {
+        self.normalize_native_sol = enabled;
+        self
+    }
+
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<LiquidityMigration> {
+        let mut state = self.state.lock();
+        #[cfg(feature = "raydium-amm-v4")]
+        if let Some(e) = event.as_any().downcast_ref::<RaydiumAmmV4AmmInfoAccountEvent>() {
+            self.remember_pool(&mut state, e.pubkey, (e.amm_info.coin_mint, e.amm_info.pc_mint));
+            return None;
+        }
+
+        let change = Self::extract(event)?;
+        let mints = match change.mints {
+            Some(mints) => {
+                self.remember_pool(&mut state, change.pool, mints);
+                mints
+            }
+            None => *state.pool_mints.get(&change.pool)?,
+        };
+        let (mint, quote_mint, quote_amount) =
+            split_quote(mints, change.amounts, self.normalize_native_sol)?;
+        if quote_amount.is_some_and(|amount| amount < self.min_quote_amount) {
+            return None;
+        }
+
+        let metadata = event.metadata();
+        let slot = metadata.slot;
+        let window_slots = self.window_slots;
+        let removals = state.removals.entry(mint).or_default();
+        removals.retain(|removal| slot.saturating_sub(removal.slot) <= window_slots);
+
+        if change.kind == ChangeKind::Removed {
+            removals.push_back(Removal {
+                venue: metadata.protocol.clone(),
+                pool: change.pool,
+                user: change.user,
+                slot,
+                signature: metadata.signature,
+                quote_mint,
+                quote_amount,
+            });
+            return None;
+        }
+
+        // 优先匹配同一钱包的最近一次移除
+        let position = removals
+            .iter()
+            .rposition(|r| r.pool != change.pool && r.user == change.user)
+            .or_else(|| removals.iter().rposition(|r| r.pool != change.pool))?;
+        let removal = removals.remove(position)?;
+        if removals.is_empty() {
+            state.removals.remove(&mint);
+        }
+        Some(LiquidityMigration {
+            mint,
+            quote_mint: removal.quote_mint,
+            source_venue: removal.venue,
+            source_pool: removal.pool,
+            removed_by: removal.user,
+            removed_slot: removal.slot,
+            removed_signature: removal.signature,
+            removed_quote_amount: removal.quote_amount,
+            destination_venue: metadata.protocol.clone(),
+            destination_pool: change.pool,
+            added_by: change.user,
+            added_slot: slot,
+            added_signature: metadata.signature,
+            added_quote_amount: quote_amount,
+            pool_created: change.kind == ChangeKind::Created,
+            same_wallet: removal.user == change.user,
+        })
+    }
+
+    fn remember_pool(&self, state: &mut MigrationState, pool: Pubkey, mints: (Pubkey, Pubkey)) {
+        if state.pool_mints.insert(pool, mints).is_none() {
+            state.pool_order.push_back(pool);
+            if state.pool_order.len() > self.max_pools {
+                if let Some(oldest) = state.pool_order.pop_front() {
+                    state.pool_mints.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    fn extract(event: &dyn UnifiedEvent) -> Option<LiquidityChange> {
+        let mut change: Option<LiquidityChange> = None;
+        let mut set = |kind, pool, user, mints, amounts| {
+            change = Some(LiquidityChange { kind, pool, user, mints, amounts });
+        };
+
+        match_event!(event, {
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmInitializeEvent => |e: RaydiumCpmmInitializeEvent| {
+                set(
+                    ChangeKind::Created,
+                    e.pool_state,
+                    e.creator,
+                    Some((e.token0_mint, e.token1_mint)),
+                    (Some(e.init_amount0), Some(e.init_amount1)),
+                );
+            },
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmDepositEvent => |e: RaydiumCpmmDepositEvent| {
+                set(
+                    ChangeKind::Added,
+                    e.pool_state,
+                    e.owner,
+                    Some((e.vault0_mint, e.vault1_mint)),
+                    (Some(e.maximum_token0_amount), Some(e.maximum_token1_amount)),
+                );
+            },
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmWithdrawEvent => |e: RaydiumCpmmWithdrawEvent| {
+                set(
+                    ChangeKind::Removed,
+                    e.pool_state,
+                    e.owner,
+                    Some((e.vault0_mint, e.vault1_mint)),
+                    (Some(e.minimum_token0_amount), Some(e.minimum_token1_amount)),
+                );
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmCreatePoolEvent => |e: RaydiumClmmCreatePoolEvent| {
+                set(
+                    ChangeKind::Created,
+                    e.pool_state,
+                    e.pool_creator,
+                    Some((e.token_mint0, e.token_mint1)),
+                    (None, None),
+                );
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmIncreaseLiquidityV2Event => |e: RaydiumClmmIncreaseLiquidityV2Event| {
+                set(
+                    ChangeKind::Added,
+                    e.pool_state,
+                    e.nft_owner,
+                    Some((e.vault0_mint, e.vault1_mint)),
+                    (Some(e.amount0_max), Some(e.amount1_max)),
+                );
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmDecreaseLiquidityV2Event => |e: RaydiumClmmDecreaseLiquidityV2Event| {
+                set(
+                    ChangeKind::Removed,
+                    e.pool_state,
+                    e.nft_owner,
+                    Some((e.vault0_mint, e.vault1_mint)),
+                    (Some(e.amount0_min), Some(e.amount1_min)),
+                );
+            },
+            #[cfg(feature = "raydium-amm-v4")]
+            RaydiumAmmV4Initialize2Event => |e: RaydiumAmmV4Initialize2Event| {
+                set(
+                    ChangeKind::Created,
+                    e.amm,
+                    e.user_wallet,
+                    Some((e.coin_mint, e.pc_mint)),
+                    (Some(e.init_coin_amount), Some(e.init_pc_amount)),
+                );
+            },
+            #[cfg(feature = "raydium-amm-v4")]
+            RaydiumAmmV4DepositEvent => |e: RaydiumAmmV4DepositEvent| {
+                set(
+                    ChangeKind::Added,
+                    e.amm,
+                    e.user_owner,
+                    None,
+                    (Some(e.max_coin_amount), Some(e.max_pc_amount)),
+                );
+            },
+            #[cfg(feature = "raydium-amm-v4")]
+            RaydiumAmmV4WithdrawEvent => |e: RaydiumAmmV4WithdrawEvent| {
+                set(ChangeKind::Removed, e.amm, e.user_owner, None, (None, None));
+            },
+            _ => |_event: &dyn UnifiedEvent| {},
+        });
+        change
+    }
+}
+
+/// (token mint, quote mint, quote amount), None for quote/quote pools
+fn split_quote(
+    (mint_a, mint_b): (Pubkey, Pubkey),
+    (amount_a, amount_b): (Option<u64>, Option<u64>),
+    normalize_native_sol: bool,
+) -> Option<(Pubkey, Pubkey, Option<u64>)> {
+    let (mint_a, mint_b) = (
+        normalize_sol_mint(mint_a, normalize_native_sol),
+        normalize_sol_mint(mint_b, normalize_native_sol),
+    );
+    match (QUOTE_MINTS.contains(&mint_a), QUOTE_MINTS.contains(&mint_b)) {
+        (true, true) => None,
+        (true, false) => Some((mint_b, mint_a, amount_a)),
+        (false, _) => Some((mint_a, mint_b, amount_b)),
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm", feature = "raydium-clmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::analytics::WSOL_MINT;
+    use crate::streaming::event_parser::common::EventMetadata;
+
+    #[test]
+    fn test_cpmm_to_clmm_migration() {
+        let tracker = MigrationTracker::new(10, 1_000);
+        let (mint, wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (cpmm_pool, clmm_pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let at = |slot| EventMetadata { slot, ..Default::default() };
+
+        let withdraw = RaydiumCpmmWithdrawEvent {
+            metadata: at(100),
+            owner: wallet,
+            pool_state: cpmm_pool,
+            vault0_mint: WSOL_MINT,
+            vault1_mint: mint,
+            minimum_token0_amount: 50_000,
+            ..Default::default()
+        };
+        assert!(tracker.on_event(&withdraw).is_none());
+        // 数量低于阈值的移除不参与关联
+        assert!(tracker
+            .on_event(&RaydiumCpmmWithdrawEvent {
+                minimum_token0_amount: 10,
+                owner: Pubkey::new_unique(),
+                ..withdraw.clone()
+            })
+            .is_none());
+
+        let migration = tracker
+            .on_event(&RaydiumClmmCreatePoolEvent {
+                metadata: at(104),
+                pool_creator: wallet,
+                pool_state: clmm_pool,
+                token_mint0: mint,
+                token_mint1: WSOL_MINT,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!((migration.source_pool, migration.destination_pool), (cpmm_pool, clmm_pool));
+        assert_eq!(migration.removed_quote_amount, Some(50_000));
+        assert!(migration.pool_created && migration.same_wallet);
+
+        // 超出时间窗口
+        assert!(tracker.on_event(&withdraw).is_none());
+        assert!(tracker
+            .on_event(&RaydiumClmmIncreaseLiquidityV2Event {
+                metadata: at(200),
+                pool_state: clmm_pool,
+                vault0_mint: mint,
+                vault1_mint: WSOL_MINT,
+                amount1_max: 5_000,
+                ..Default::default()
+            })
+            .is_none());
+    }
+}
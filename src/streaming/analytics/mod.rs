@@ -0,0 +1,50 @@
+// 分析模块 - 基于事件流的聚合与检测
+pub mod activity;
+#[cfg(feature = "raydium-amm-v4")]
+pub mod amm_v4_market;
+#[cfg(feature = "cex-feeds")]
+pub mod cex_feed;
+pub mod circuit_breaker;
+pub mod compute_units;
+pub mod congestion;
+pub mod fees;
+pub mod funding;
+pub mod holders;
+pub mod lead_lag;
+pub mod migration;
+pub mod new_pool;
+pub mod pool_discovery;
+pub mod sandwich;
+pub mod spread;
+pub mod token_listing;
+pub mod top_movers;
+pub mod trade;
+pub mod tx_cost;
+pub mod wallet_activity;
+pub mod wash_trading;
+mod window;
+
+// 重新导出主要类型
+pub use activity::*;
+#[cfg(feature = "raydium-amm-v4")]
+pub use amm_v4_market::*;
+#[cfg(feature = "cex-feeds")]
+pub use cex_feed::*;
+pub use circuit_breaker::*;
+pub use compute_units::*;
+pub use congestion::*;
+pub use fees::*;
+pub use funding::*;
+pub use holders::*;
+pub use lead_lag::*;
+pub use migration::*;
+pub use new_pool::*;
+pub use pool_discovery::*;
+pub use sandwich::*;
+pub use spread::*;
+pub use token_listing::*;
+pub use top_movers::*;
+pub use trade::*;
+pub use tx_cost::*;
+pub use wallet_activity::*;
+pub use wash_trading::*;
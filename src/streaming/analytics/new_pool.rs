@@ -0,0 +1,412 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use super::circuit_breaker::MintCircuitBreaker;
+use super::trade::{normalize_sol_mint, TradeInfo};
+use crate::match_event;
+use crate::streaming::event_parser::common::ProtocolType;
+use crate::streaming::event_parser::core::account_event_parser::TokenInfoEvent;
+#[cfg(feature = "raydium-amm-v4")]
+use crate::streaming::event_parser::protocols::raydium_amm_v4::RaydiumAmmV4Initialize2Event;
+#[cfg(feature = "raydium-clmm")]
+use crate::streaming::event_parser::protocols::raydium_clmm::RaydiumClmmCreatePoolEvent;
+#[cfg(feature = "raydium-cpmm")]
+use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmInitializeEvent;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default number of slots a new pool is watched for
+pub const DEFAULT_NEW_POOL_WATCH_SLOTS: u64 = 10;
+/// Default cap on concurrently watched pools
+pub const DEFAULT_MAX_WATCHED_POOLS: usize = 512;
+/// Default cap on wallets remembered for wallet-age estimation
+pub const DEFAULT_MAX_TRACKED_WALLETS: usize = 200_000;
+/// Name of the transaction filter carrying the watched pools
+pub const NEW_POOL_FILTER_NAME: &str = "new_pools";
+
+/// A newly created pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewPoolInfo {
+    pub pool: Pubkey,
+    pub protocol: ProtocolType,
+    pub creator: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub created_slot: u64,
+    pub signature: Signature,
+}
+
+/// A trade on a watched pool within its first N slots
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EarlyTradeEvent {
+    pub trade: TradeInfo,
+    pub pool_created_slot: u64,
+    /// 0 = same slot as pool creation
+    pub slots_since_creation: u64,
+    /// Transaction index within the block, when the source provides it
+    pub position_in_block: Option<u64>,
+    /// Priority fee requested by the transaction (lamports)
+    pub priority_fee_lamports: Option<u64>,
+    /// Slot at which the buyer was first observed by this watcher
+    pub buyer_first_seen_slot: u64,
+    /// `trade.slot - buyer_first_seen_slot`; 0 means the wallet is new to this stream
+    pub buyer_age_slots: u64,
+}
+
+/// The pool creator selling the pool's token within the watch window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DevSellEvent {
+    pub trade: TradeInfo,
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    /// Token amount sold (raw units)
+    pub amount_sold: u64,
+    pub pool_created_slot: u64,
+    pub slots_since_creation: u64,
+    /// Mint supply, once a `TokenInfoEvent` of the mint was seen or set via
+    /// [`NewPoolWatcher::set_mint_supply`]
+    pub supply: Option<u64>,
+    /// Share of the supply sold, in basis points
+    pub supply_sold_bps: Option<u64>,
+}
+
+impl DevSellEvent {
+    /// Share of the supply sold, in percent
+    pub fn supply_sold_pct(&self) -> Option<f64> {
+        self.supply_sold_bps.map(|bps| bps as f64 / 100.0)
+    }
+}
+
+/// Output of [`NewPoolWatcher::on_event`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NewPoolWatchEvent {
+    /// A pool-create was seen and a watch was opened; subscribe to the pool for priority updates
+    PoolDetected(NewPoolInfo),
+    /// Early trade on a watched pool
+    EarlyTrade(Box<EarlyTradeEvent>),
+    /// The creator sold the pool's token, emitted after its `EarlyTrade`
+    DevSellDetected(Box<DevSellEvent>),
+    /// The watch window closed; the focused subscription can be dropped
+    WatchExpired(Pubkey),
+}
+
+#[derive(Default)]
+struct WatcherState {
+    pools: HashMap<Pubkey, NewPoolInfo>,
+    wallet_first_seen: HashMap<Pubkey, u64>,
+    /// 按首次出现的 slot 排序的钱包，超出上限时从最早的开始淘汰
+    wallets_by_first_seen: BTreeSet<(u64, Pubkey)>,
+    /// 被监视池子的 mint 供应量
+    mint_supplies: HashMap<Pubkey, u64>,
+    highest_slot: u64,
+}
+
+/// Watches newly created pools for their first N slots and emits enriched early trades
+///
+/// The standard snipe-monitoring pattern: feed every event through
+/// [`on_event`](Self::on_event); on `PoolDetected` add the pool to the subscription, on
+/// `WatchExpired` remove it again. `YellowstoneGrpc::enable_new_pool_watch` does both
+/// for a live subscription, through a dedicated transaction filter of the watched pools.
+///
+/// Wallet age is measured from the first time this watcher saw the wallet trade,
+/// so it is only meaningful after the watcher has been running for a while.
+///
+/// Sells of the pool's token by its creator are additionally reported as
+/// `DevSellDetected`; subscribe to the mint accounts (or call
+/// [`set_mint_supply`](Self::set_mint_supply)) to get the share of supply sold.
+pub struct NewPoolWatcher {
+    watch_slots: u64,
+    max_pools: usize,
+    max_wallets: usize,
+    circuit_breaker: Option<Arc<MintCircuitBreaker>>,
+    normalize_native_sol: bool,
+    state: Mutex<WatcherState>,
+}
+
+impl Default for NewPoolWatcher {
+    fn default() -> Self {
+        Self::new(DEFAULT_NEW_POOL_WATCH_SLOTS)
+    }
+}
+
+impl NewPoolWatcher {
+    pub fn new(watch_slots: u64) -> Self {
+        Self {
+            watch_slots,
+            max_pools: DEFAULT_MAX_WATCHED_POOLS,
+            max_wallets: DEFAULT_MAX_TRACKED_WALLETS,
+            circuit_breaker: None,
+            normalize_native_sol: true,
+            state: Mutex::new(WatcherState::default()),
+        }
+    }
+
+    /// Sets the caps on watched pools and remembered wallets
+    pub fn with_limits(mut self, max_pools: usize, max_wallets: usize) -> Self {
+        self.max_pools = max_pools;
+        self.max_wallets = max_wallets;
+        self
+    }
+
+    /// Suppresses `EarlyTrade` and `DevSellDetected` of mints that trip the breaker;
+    /// pool lifecycle events always pass
+    pub fn with_circuit_breaker(mut self, breaker: Arc<MintCircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// Whether native SOL is reported as WSOL in pool mints and trades (default: true)
+    pub fn with_native_sol_normalization(mut self, enabled: bool) -> Self {
+        self.normalize_native_sol = enabled;
+        self
+    }
+
+    /// Pools currently under watch
+    pub fn watched_pools(&self) -> Vec<Pubkey> {
+        self.state.lock().pools.keys().copied().collect()
+    }
+
+    /// Supply of a watched pool's mint, used for the share sold in `DevSellDetected`
+    pub fn set_mint_supply(&self, mint: Pubkey, supply: u64) {
+        self.state.lock().mint_supplies.insert(mint, supply);
+    }
+
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Vec<NewPoolWatchEvent> {
+        let mut out = Vec::new();
+        let mut state = self.state.lock();
+
+        if event.slot() > state.highest_slot {
+            state.highest_slot = event.slot();
+            self.expire(&mut state, &mut out);
+        }
+
+        if let Some(e) = event.as_any().downcast_ref::<TokenInfoEvent>() {
+            let watched =
+                state.pools.values().any(|pool| pool.mint_a == e.pubkey || pool.mint_b == e.pubkey);
+            if watched {
+                state.mint_supplies.insert(e.pubkey, e.supply);
+            }
+            return out;
+        }
+
+        if let Some(info) = self.detect_pool(event) {
+            if state.pools.len() < self.max_pools && !state.pools.contains_key(&info.pool) {
+                state.pools.insert(info.pool, info.clone());
+                out.push(NewPoolWatchEvent::PoolDetected(info));
+            }
+            return out;
+        }
+
+        let Some(trade) = TradeInfo::from_event_with(event, self.normalize_native_sol) else {
+            return out;
+        };
+
+        let buyer_first_seen_slot = match state.wallet_first_seen.get(&trade.user).copied() {
+            Some(slot) => slot,
+            None => {
+                // 超出上限时只淘汰最早见到的钱包
+                while state.wallet_first_seen.len() >= self.max_wallets {
+                    let Some((_, wallet)) = state.wallets_by_first_seen.pop_first() else {
+                        break;
+                    };
+                    state.wallet_first_seen.remove(&wallet);
+                }
+                state.wallet_first_seen.insert(trade.user, trade.slot);
+                state.wallets_by_first_seen.insert((trade.slot, trade.user));
+                trade.slot
+            }
+        };
+
+        let Some(pool) = state.pools.get(&trade.pool) else {
+            return out;
+        };
+        let slots_since_creation = trade.slot.saturating_sub(pool.created_slot);
+        if slots_since_creation > self.watch_slots {
+            return out;
+        }
+
+        let dev_sell = (trade.user == pool.creator)
+            .then(|| trade.token_side())
+            .flatten()
+            .filter(|side| !side.is_buy)
+            .map(|side| {
+                let supply = state.mint_supplies.get(&side.mint).copied().filter(|s| *s > 0);
+                DevSellEvent {
+                    trade: trade.clone(),
+                    creator: pool.creator,
+                    mint: side.mint,
+                    amount_sold: side.amount,
+                    pool_created_slot: pool.created_slot,
+                    slots_since_creation,
+                    supply,
+                    supply_sold_bps: supply
+                        .map(|supply| (side.amount as u128 * 10_000 / supply as u128) as u64),
+                }
+            });
+
+        // 熔断的 mint 连同其 DevSell 一起丢弃
+        if let (Some(breaker), Some(side)) = (&self.circuit_breaker, trade.token_side()) {
+            if !breaker.allow(&side.mint) {
+                return out;
+            }
+        }
+        out.push(NewPoolWatchEvent::EarlyTrade(Box::new(EarlyTradeEvent {
+            pool_created_slot: pool.created_slot,
+            slots_since_creation,
+            position_in_block: trade.transaction_index,
+            priority_fee_lamports: event.metadata().compute_budget.priority_fee_lamports(),
+            buyer_first_seen_slot,
+            buyer_age_slots: trade.slot.saturating_sub(buyer_first_seen_slot),
+            trade,
+        })));
+        if let Some(dev_sell) = dev_sell {
+            out.push(NewPoolWatchEvent::DevSellDetected(Box::new(dev_sell)));
+        }
+        out
+    }
+
+    fn expire(&self, state: &mut WatcherState, out: &mut Vec<NewPoolWatchEvent>) {
+        let highest_slot = state.highest_slot;
+        let watch_slots = self.watch_slots;
+        state.pools.retain(|pool, info| {
+            let alive = highest_slot.saturating_sub(info.created_slot) <= watch_slots;
+            if !alive {
+                out.push(NewPoolWatchEvent::WatchExpired(*pool));
+            }
+            alive
+        });
+        let pools = &state.pools;
+        state
+            .mint_supplies
+            .retain(|mint, _| pools.values().any(|p| p.mint_a == *mint || p.mint_b == *mint));
+    }
+
+    fn detect_pool(&self, event: &dyn UnifiedEvent) -> Option<NewPoolInfo> {
+        let mut info: Option<NewPoolInfo> = None;
+        let metadata = event.metadata();
+        let base = |pool, creator, mint_a, mint_b| NewPoolInfo {
+            pool,
+            protocol: metadata.protocol.clone(),
+            creator,
+            mint_a: normalize_sol_mint(mint_a, self.normalize_native_sol),
+            mint_b: normalize_sol_mint(mint_b, self.normalize_native_sol),
+            created_slot: metadata.slot,
+            signature: metadata.signature,
+        };
+
+        match_event!(event, {
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmCreatePoolEvent => |e: RaydiumClmmCreatePoolEvent| {
+                info = Some(base(e.pool_state, e.pool_creator, e.token_mint0, e.token_mint1));
+            },
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmInitializeEvent => |e: RaydiumCpmmInitializeEvent| {
+                info = Some(base(e.pool_state, e.creator, e.token0_mint, e.token1_mint));
+            },
+            #[cfg(feature = "raydium-amm-v4")]
+            RaydiumAmmV4Initialize2Event => |e: RaydiumAmmV4Initialize2Event| {
+                info = Some(base(e.amm, e.user_wallet, e.coin_mint, e.pc_mint));
+            },
+            _ => |_event: &dyn UnifiedEvent| {},
+        });
+        info
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::analytics::WSOL_MINT;
+    use crate::streaming::event_parser::common::{ComputeBudgetInfo, EventMetadata, EventType};
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    #[test]
+    fn test_dev_sell_detected() {
+        let watcher = NewPoolWatcher::new(10);
+        let (pool, creator, mint) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let metadata = |slot| EventMetadata {
+            slot,
+            event_type: EventType::RaydiumCpmmSwapBaseInput,
+            ..Default::default()
+        };
+
+        watcher.on_event(&RaydiumCpmmInitializeEvent {
+            metadata: metadata(100),
+            creator,
+            pool_state: pool,
+            token0_mint: WSOL_MINT,
+            token1_mint: mint,
+            ..Default::default()
+        });
+        watcher.on_event(&TokenInfoEvent {
+            metadata: metadata(101),
+            pubkey: mint,
+            supply: 1_000_000,
+            ..Default::default()
+        });
+        let out = watcher.on_event(&RaydiumCpmmSwapEvent {
+            metadata: metadata(103),
+            payer: creator,
+            pool_state: pool,
+            input_token_mint: mint,
+            output_token_mint: WSOL_MINT,
+            amount_in: 250_000,
+            ..Default::default()
+        });
+
+        let Some(NewPoolWatchEvent::DevSellDetected(dev_sell)) = out.last() else {
+            panic!("expected DevSellDetected, got {:?}", out);
+        };
+        assert_eq!(dev_sell.slots_since_creation, 3);
+        assert_eq!(dev_sell.amount_sold, 250_000);
+        assert_eq!(dev_sell.supply_sold_pct(), Some(25.0));
+    }
+
+    #[test]
+    fn test_wallet_eviction_and_priority_fee() {
+        let watcher = NewPoolWatcher::new(10).with_limits(8, 2);
+        let (pool, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let wallets: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        watcher.on_event(&RaydiumCpmmInitializeEvent {
+            metadata: EventMetadata { slot: 100, ..Default::default() },
+            pool_state: pool,
+            token0_mint: WSOL_MINT,
+            token1_mint: mint,
+            ..Default::default()
+        });
+        let buy = |payer, slot, compute_budget| {
+            let out = watcher.on_event(&RaydiumCpmmSwapEvent {
+                metadata: EventMetadata { slot, compute_budget, ..Default::default() },
+                payer,
+                pool_state: pool,
+                input_token_mint: WSOL_MINT,
+                output_token_mint: mint,
+                ..Default::default()
+            });
+            match out.last() {
+                Some(NewPoolWatchEvent::EarlyTrade(trade)) => trade.as_ref().clone(),
+                other => panic!("expected EarlyTrade, got {:?}", other),
+            }
+        };
+
+        // 3 条指令、无 SetComputeUnitLimit：默认上限 600k CU
+        let budget = ComputeBudgetInfo {
+            unit_price: Some(1_000_000),
+            instruction_count: 3,
+            ..Default::default()
+        };
+        assert_eq!(buy(wallets[0], 101, budget).priority_fee_lamports, Some(600_000));
+        let budget = ComputeBudgetInfo { unit_limit: Some(50_000), ..budget };
+        assert_eq!(buy(wallets[1], 102, budget).priority_fee_lamports, Some(50_000));
+
+        // 上限 2 个钱包：只淘汰最早的 wallets[0]
+        assert_eq!(buy(wallets[2], 103, budget).buyer_age_slots, 0);
+        assert_eq!(buy(wallets[1], 104, budget).buyer_first_seen_slot, 102);
+        assert_eq!(buy(wallets[2], 105, budget).buyer_first_seen_slot, 103);
+        assert_eq!(buy(wallets[0], 106, budget).buyer_first_seen_slot, 106);
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::{HashMap, VecDeque};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use super::trade::normalize_sol_mint;
+use crate::match_event;
+use crate::streaming::event_parser::common::ProtocolType;
+#[cfg(feature = "raydium-amm-v4")]
+use crate::streaming::event_parser::protocols::raydium_amm_v4::RaydiumAmmV4Initialize2Event;
+#[cfg(feature = "raydium-clmm")]
+use crate::streaming::event_parser::protocols::raydium_clmm::RaydiumClmmCreatePoolEvent;
+#[cfg(feature = "raydium-cpmm")]
+use crate::streaming::event_parser::protocols::raydium_cpmm::{
+    RaydiumCpmmDepositEvent, RaydiumCpmmInitializeEvent, RaydiumCpmmWithdrawEvent,
+};
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default cap on remembered pools
+pub const DEFAULT_MAX_DISCOVERED_POOLS: usize = 100_000;
+
+/// Account linkage of a pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscoveredPool {
+    pub pool: Pubkey,
+    pub protocol: ProtocolType,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    /// None for CLMM pools, whose positions are NFTs
+    pub lp_mint: Option<Pubkey>,
+    /// Slot of the pool-create, None if the pool was learned from a liquidity event
+    pub created_slot: Option<u64>,
+}
+
+#[derive(Default)]
+struct DiscoveryState {
+    pools: HashMap<Pubkey, DiscoveredPool>,
+    /// vault / lp mint -> pool
+    accounts: HashMap<Pubkey, Pubkey>,
+    order: VecDeque<Pubkey>,
+}
+
+impl DiscoveryState {
+    fn remove(&mut self, pool: &Pubkey) {
+        if let Some(info) = self.pools.remove(pool) {
+            for account in
+                [Some(info.vault_a), Some(info.vault_b), info.lp_mint].into_iter().flatten()
+            {
+                if self.accounts.get(&account) == Some(pool) {
+                    self.accounts.remove(&account);
+                }
+            }
+        }
+    }
+}
+
+/// Pool ↔ vault / lp mint registry built from pool-create and liquidity events
+///
+/// Token balance changes and account updates often reference only a vault or the
+/// lp mint. Feed every event through [`on_event`](Self::on_event), then resolve such
+/// references with [`resolve`](Self::resolve) to get the pool and its mints.
+pub struct PoolDiscovery {
+    max_pools: usize,
+    normalize_native_sol: bool,
+    state: RwLock<DiscoveryState>,
+}
+
+impl Default for PoolDiscovery {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_DISCOVERED_POOLS)
+    }
+}
+
+impl PoolDiscovery {
+    pub fn new(max_pools: usize) -> Self {
+        Self {
+            max_pools: max_pools.max(1),
+            normalize_native_sol: true,
+            state: RwLock::new(DiscoveryState::default()),
+        }
+    }
+
+    /// Whether native SOL is reported as WSOL in pool mints (default: true)
+    pub fn with_native_sol_normalization(mut self, enabled: bool) -> Self {
+        self.normalize_native_sol = enabled;
+        self
+    }
+
+    /// Learns the pool linkage carried by the event
+    ///
+    /// Returns the pool if it was not known yet, or was known only from a liquidity event
+    /// and is now confirmed by its pool-create.
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<DiscoveredPool> {
+        let info = self.extract(event)?;
+        let mut state = self.state.write();
+        if let Some(known) = state.pools.get(&info.pool) {
+            if known.created_slot.is_some() || info.created_slot.is_none() {
+                return None;
+            }
+            state.remove(&info.pool);
+        } else {
+            state.order.push_back(info.pool);
+        }
+
+        for account in [Some(info.vault_a), Some(info.vault_b), info.lp_mint].into_iter().flatten()
+        {
+            state.accounts.insert(account, info.pool);
+        }
+        state.pools.insert(info.pool, info.clone());
+
+        while state.pools.len() > self.max_pools {
+            let Some(oldest) = state.order.pop_front() else {
+                break;
+            };
+            state.remove(&oldest);
+        }
+        Some(info)
+    }
+
+    pub fn get(&self, pool: &Pubkey) -> Option<DiscoveredPool> {
+        self.state.read().pools.get(pool).cloned()
+    }
+
+    /// Pool owning the vault or lp mint (a pool address resolves to itself)
+    pub fn resolve(&self, account: &Pubkey) -> Option<DiscoveredPool> {
+        let state = self.state.read();
+        let pool = state.accounts.get(account).unwrap_or(account);
+        state.pools.get(pool).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.read().pools.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.read().pools.is_empty()
+    }
+
+    fn extract(&self, event: &dyn UnifiedEvent) -> Option<DiscoveredPool> {
+        let mut info: Option<DiscoveredPool> = None;
+        let metadata = event.metadata();
+        let base = |pool, mints: (Pubkey, Pubkey), vaults: (Pubkey, Pubkey), lp_mint, created| {
+            DiscoveredPool {
+                pool,
+                protocol: metadata.protocol.clone(),
+                mint_a: normalize_sol_mint(mints.0, self.normalize_native_sol),
+                mint_b: normalize_sol_mint(mints.1, self.normalize_native_sol),
+                vault_a: vaults.0,
+                vault_b: vaults.1,
+                lp_mint,
+                created_slot: created.then_some(metadata.slot),
+            }
+        };
+
+        match_event!(event, {
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmInitializeEvent => |e: RaydiumCpmmInitializeEvent| {
+                info = Some(base(
+                    e.pool_state,
+                    (e.token0_mint, e.token1_mint),
+                    (e.token0_vault, e.token1_vault),
+                    Some(e.lp_mint),
+                    true,
+                ));
+            },
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmDepositEvent => |e: RaydiumCpmmDepositEvent| {
+                info = Some(base(
+                    e.pool_state,
+                    (e.vault0_mint, e.vault1_mint),
+                    (e.token0_vault, e.token1_vault),
+                    Some(e.lp_mint),
+                    false,
+                ));
+            },
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmWithdrawEvent => |e: RaydiumCpmmWithdrawEvent| {
+                info = Some(base(
+                    e.pool_state,
+                    (e.vault0_mint, e.vault1_mint),
+                    (e.token0_vault, e.token1_vault),
+                    Some(e.lp_mint),
+                    false,
+                ));
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmCreatePoolEvent => |e: RaydiumClmmCreatePoolEvent| {
+                info = Some(base(
+                    e.pool_state,
+                    (e.token_mint0, e.token_mint1),
+                    (e.token_vault0, e.token_vault1),
+                    None,
+                    true,
+                ));
+            },
+            #[cfg(feature = "raydium-amm-v4")]
+            RaydiumAmmV4Initialize2Event => |e: RaydiumAmmV4Initialize2Event| {
+                info = Some(base(
+                    e.amm,
+                    (e.coin_mint, e.pc_mint),
+                    (e.pool_coin_token_account, e.pool_pc_token_account),
+                    Some(e.lp_mint),
+                    true,
+                ));
+            },
+            _ => |_event: &dyn UnifiedEvent| {},
+        });
+        info
+    }
+}
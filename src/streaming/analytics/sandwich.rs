@@ -0,0 +1,261 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use super::circuit_breaker::MintCircuitBreaker;
+use super::trade::TradeInfo;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Slots kept open for late-arriving trades before a slot is analyzed
+pub const DEFAULT_SANDWICH_SLOT_LAG: u64 = 2;
+
+/// A same-slot front-run → victim(s) → back-run pattern on one pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SandwichDetected {
+    pub slot: u64,
+    pub pool: Pubkey,
+    pub attacker: Pubkey,
+    pub front_run: TradeInfo,
+    pub victims: Vec<TradeInfo>,
+    pub back_run: TradeInfo,
+    /// Mint the attacker started and ended with (front-run input)
+    pub profit_mint: Pubkey,
+    /// back_run.amount_out - front_run.amount_in, in `profit_mint` raw units
+    pub attacker_profit: i128,
+    /// Value extracted from the victims, approximated by the attacker's gross profit
+    pub estimated_victim_loss: u64,
+}
+
+#[derive(Default)]
+struct DetectorState {
+    /// slot -> pool -> trades in arrival order
+    slots: BTreeMap<u64, HashMap<Pubkey, Vec<TradeInfo>>>,
+    highest_slot: u64,
+}
+
+/// Detects sandwich attacks from swap events, ordered by transaction index within a slot
+///
+/// Trades whose mints cannot be determined (no parsed swap data) are ignored, since
+/// the front-run and back-run are matched by reversed direction.
+pub struct SandwichDetector {
+    slot_lag: u64,
+    known_bundlers: HashSet<Pubkey>,
+    circuit_breaker: Option<Arc<MintCircuitBreaker>>,
+    state: Mutex<DetectorState>,
+}
+
+impl Default for SandwichDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_SANDWICH_SLOT_LAG)
+    }
+}
+
+impl SandwichDetector {
+    pub fn new(slot_lag: u64) -> Self {
+        Self {
+            slot_lag,
+            known_bundlers: HashSet::new(),
+            circuit_breaker: None,
+            state: Mutex::new(DetectorState::default()),
+        }
+    }
+
+    /// Wallets treated as a single attacker (e.g. a bundler rotating signers)
+    pub fn with_known_bundlers(mut self, bundlers: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.known_bundlers.extend(bundlers);
+        self
+    }
+
+    /// Suppresses detections of mints that trip the breaker, keyed by the traded token
+    pub fn with_circuit_breaker(mut self, breaker: Arc<MintCircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// Buffers a trade; returns detections for slots older than the configured lag
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Vec<SandwichDetected> {
+        let mut state = self.state.lock();
+        if let Some(trade) = TradeInfo::from_event(event) {
+            if trade.input_mint != Pubkey::default() && trade.output_mint != Pubkey::default() {
+                state
+                    .slots
+                    .entry(trade.slot)
+                    .or_default()
+                    .entry(trade.pool)
+                    .or_default()
+                    .push(trade);
+            }
+        }
+        if event.slot() <= state.highest_slot {
+            return Vec::new();
+        }
+        state.highest_slot = event.slot();
+
+        let cutoff = state.highest_slot.saturating_sub(self.slot_lag);
+        let open = state.slots.split_off(&cutoff);
+        let closed = std::mem::replace(&mut state.slots, open);
+        drop(state);
+
+        let detections = closed
+            .into_iter()
+            .flat_map(|(_, pools)| pools.into_values())
+            .flat_map(|trades| self.analyze(trades))
+            .collect();
+        self.throttle(detections)
+    }
+
+    /// Analyzes every buffered slot
+    pub fn flush(&self) -> Vec<SandwichDetected> {
+        let slots = std::mem::take(&mut self.state.lock().slots);
+        let detections = slots
+            .into_iter()
+            .flat_map(|(_, pools)| pools.into_values())
+            .flat_map(|trades| self.analyze(trades))
+            .collect();
+        self.throttle(detections)
+    }
+
+    fn throttle(&self, detections: Vec<SandwichDetected>) -> Vec<SandwichDetected> {
+        match &self.circuit_breaker {
+            Some(breaker) => breaker.filter(detections, |d| {
+                Some(d.front_run.token_side().map_or(d.profit_mint, |side| side.mint))
+            }),
+            None => detections,
+        }
+    }
+
+    fn same_attacker(&self, a: &Pubkey, b: &Pubkey) -> bool {
+        a == b || (self.known_bundlers.contains(a) && self.known_bundlers.contains(b))
+    }
+
+    /// Trades of a single pool within a single slot
+    fn analyze(&self, mut trades: Vec<TradeInfo>) -> Vec<SandwichDetected> {
+        if trades.len() < 3 {
+            return Vec::new();
+        }
+        // 没有 transaction_index 时保持到达顺序（sort_by_key 是稳定排序）
+        trades.sort_by_key(|t| (t.transaction_index.unwrap_or(u64::MAX), t.outer_index));
+
+        let mut detections = Vec::new();
+        let mut used = vec![false; trades.len()];
+        for front_idx in 0..trades.len() {
+            if used[front_idx] {
+                continue;
+            }
+            let front = &trades[front_idx];
+            let back_idx = (front_idx + 2..trades.len()).find(|&i| {
+                let back = &trades[i];
+                !used[i]
+                    && self.same_attacker(&front.user, &back.user)
+                    && back.input_mint == front.output_mint
+                    && back.output_mint == front.input_mint
+            });
+            let Some(back_idx) = back_idx else {
+                continue;
+            };
+
+            let victims: Vec<TradeInfo> = trades[front_idx + 1..back_idx]
+                .iter()
+                .filter(|t| {
+                    !self.same_attacker(&front.user, &t.user)
+                        && t.input_mint == front.input_mint
+                        && t.output_mint == front.output_mint
+                })
+                .cloned()
+                .collect();
+            if victims.is_empty() {
+                continue;
+            }
+
+            let back = &trades[back_idx];
+            let attacker_profit = back.amount_out as i128 - front.amount_in as i128;
+            detections.push(SandwichDetected {
+                slot: front.slot,
+                pool: front.pool,
+                attacker: front.user,
+                front_run: front.clone(),
+                victims,
+                back_run: back.clone(),
+                profit_mint: front.input_mint,
+                attacker_profit,
+                estimated_victim_loss: attacker_profit.clamp(0, u64::MAX as i128) as u64,
+            });
+            used[front_idx] = true;
+            used[back_idx] = true;
+        }
+        detections
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::{EventMetadata, EventType, SwapData};
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    fn swap(
+        user: Pubkey,
+        tx_index: u64,
+        from: Pubkey,
+        to: Pubkey,
+        from_amount: u64,
+        to_amount: u64,
+    ) -> RaydiumCpmmSwapEvent {
+        let mut metadata = EventMetadata {
+            slot: 100,
+            transaction_index: Some(tx_index),
+            event_type: EventType::RaydiumCpmmSwapBaseInput,
+            ..Default::default()
+        };
+        metadata.set_swap_data(SwapData {
+            from_mint: from,
+            to_mint: to,
+            from_amount,
+            to_amount,
+            ..Default::default()
+        });
+        RaydiumCpmmSwapEvent {
+            metadata,
+            payer: user,
+            pool_state: Pubkey::new_from_array([9; 32]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detects_sandwich() {
+        let (sol, token) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (attacker, victim) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let detector = SandwichDetector::new(0);
+
+        // 乱序到达，按 transaction_index 排序后识别
+        detector.on_event(&swap(attacker, 7, token, sol, 1_000, 1_050));
+        detector.on_event(&swap(attacker, 3, sol, token, 1_000, 1_000));
+        detector.on_event(&swap(victim, 5, sol, token, 500, 450));
+
+        let detections = detector.flush();
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].attacker, attacker);
+        assert_eq!(detections[0].victims.len(), 1);
+        assert_eq!(detections[0].victims[0].user, victim);
+        assert_eq!(detections[0].attacker_profit, 50);
+        assert_eq!(detections[0].estimated_victim_loss, 50);
+    }
+
+    #[test]
+    fn test_ignores_round_trip_without_victim() {
+        let (sol, token) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (trader, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let detector = SandwichDetector::new(0);
+
+        detector.on_event(&swap(trader, 1, sol, token, 1_000, 1_000));
+        // 反方向的交易不算受害者
+        detector.on_event(&swap(other, 2, token, sol, 100, 90));
+        detector.on_event(&swap(trader, 3, token, sol, 1_000, 990));
+
+        assert!(detector.flush().is_empty());
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use super::trade::{normalize_sol_mint, TradeInfo};
+use crate::common::AnyResult;
+use crate::streaming::event_parser::common::ProtocolType;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default spread sampling interval (1 second)
+pub const DEFAULT_SPREAD_SAMPLE_MS: i64 = 1_000;
+/// Default age after which a venue's last trade price no longer counts (30 seconds)
+pub const DEFAULT_SPREAD_MAX_PRICE_AGE_MS: i64 = 30_000;
+/// Default samples kept per pair (one day at 1 second)
+pub const DEFAULT_SPREAD_MAX_SAMPLES: usize = 86_400;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpreadRecorderConfig {
+    /// One spread sample per pair is recorded per interval
+    pub sample_ms: i64,
+    /// Venues whose last trade is older than this are left out of a sample
+    pub max_price_age_ms: i64,
+    /// Oldest samples are dropped beyond this many per pair
+    pub max_samples: usize,
+    /// (token mint, quote mint) pairs to record, empty records every pair traded on
+    /// two or more venues
+    pub pairs: Vec<(Pubkey, Pubkey)>,
+    /// Treat native SOL as WSOL when keying pairs (default: true)
+    pub normalize_native_sol: bool,
+}
+
+impl Default for SpreadRecorderConfig {
+    fn default() -> Self {
+        Self {
+            sample_ms: DEFAULT_SPREAD_SAMPLE_MS,
+            max_price_age_ms: DEFAULT_SPREAD_MAX_PRICE_AGE_MS,
+            max_samples: DEFAULT_SPREAD_MAX_SAMPLES,
+            pairs: Vec::new(),
+            normalize_native_sol: true,
+        }
+    }
+}
+
+/// Cross-venue spread time series of one pair, stored column by column
+///
+/// Every sample is recorded, whether or not the spread is large enough to trade, so the
+/// distribution can be used to pick a profit threshold.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpreadSeries {
+    pub token_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    /// Sample time (end of the sampling interval), milliseconds since epoch
+    pub time_ms: Vec<i64>,
+    /// (highest - lowest) / lowest venue price, in basis points
+    pub spread_bps: Vec<f32>,
+    /// Venue with the highest price
+    pub high_venue: Vec<ProtocolType>,
+    /// Venue with the lowest price
+    pub low_venue: Vec<ProtocolType>,
+}
+
+impl SpreadSeries {
+    pub fn len(&self) -> usize {
+        self.time_ms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.time_ms.is_empty()
+    }
+
+    /// Spread at percentile `p` (0-100), nearest rank
+    pub fn percentile_bps(&self, p: f64) -> Option<f64> {
+        self.percentiles_bps(&[p]).pop().flatten()
+    }
+
+    /// Spreads at several percentiles, sorting the column once
+    pub fn percentiles_bps(&self, ps: &[f64]) -> Vec<Option<f64>> {
+        let mut sorted = self.spread_bps.clone();
+        sorted.sort_by(f32::total_cmp);
+        ps.iter()
+            .map(|p| {
+                if sorted.is_empty() {
+                    return None;
+                }
+                let rank = (p.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize;
+                Some(sorted[rank.saturating_sub(1)] as f64)
+            })
+            .collect()
+    }
+
+    /// Share of samples (0-1) with a spread of at least `threshold_bps`, i.e. how often
+    /// an opportunity at that threshold would have been seen
+    pub fn fraction_at_least(&self, threshold_bps: f64) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let hits = self.spread_bps.iter().filter(|s| **s as f64 >= threshold_bps).count();
+        hits as f64 / self.len() as f64
+    }
+
+    /// Samples with `start_ms <= time_ms < end_ms`
+    pub fn between(&self, start_ms: i64, end_ms: i64) -> SpreadSeries {
+        // 时间列按升序写入
+        let from = self.time_ms.partition_point(|t| *t < start_ms);
+        let to = self.time_ms.partition_point(|t| *t < end_ms).max(from);
+        SpreadSeries {
+            token_mint: self.token_mint,
+            quote_mint: self.quote_mint,
+            time_ms: self.time_ms[from..to].to_vec(),
+            spread_bps: self.spread_bps[from..to].to_vec(),
+            high_venue: self.high_venue[from..to].to_vec(),
+            low_venue: self.low_venue[from..to].to_vec(),
+        }
+    }
+
+    fn push(&mut self, time_ms: i64, spread_bps: f32, high: ProtocolType, low: ProtocolType) {
+        self.time_ms.push(time_ms);
+        self.spread_bps.push(spread_bps);
+        self.high_venue.push(high);
+        self.low_venue.push(low);
+    }
+
+    fn truncate_front(&mut self, max: usize) {
+        if self.len() <= max {
+            return;
+        }
+        // 超出上限时一次丢弃最早的四分之一，避免每个样本都移动整列
+        let drop = self.len() - max + max / 4;
+        self.time_ms.drain(..drop);
+        self.spread_bps.drain(..drop);
+        self.high_venue.drain(..drop);
+        self.low_venue.drain(..drop);
+    }
+}
+
+/// Price of a pair quoted outside the chain, fed to [`SpreadRecorder::on_external_quote`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalQuote {
+    /// Pseudo-venue, e.g. `ProtocolType::Custom(CustomId::intern("binance"))`
+    pub venue: ProtocolType,
+    pub token_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    /// Quote units per token in display units (e.g. 150.25 USDC per SOL)
+    pub price: f64,
+    pub token_decimals: u8,
+    pub quote_decimals: u8,
+    /// Quote time, milliseconds since epoch
+    pub time_ms: i64,
+}
+
+impl ExternalQuote {
+    /// Price in raw units, comparable with on-chain swap prices
+    pub fn raw_price(&self) -> f64 {
+        self.price * 10f64.powi(self.quote_decimals as i32 - self.token_decimals as i32)
+    }
+}
+
+/// (token mint, quote mint)
+type PairKey = (Pubkey, Pubkey);
+
+#[derive(Default)]
+struct RecorderState {
+    /// pair -> venue -> (last price, time of the trade)
+    prices: HashMap<PairKey, HashMap<ProtocolType, (f64, i64)>>,
+    series: HashMap<PairKey, SpreadSeries>,
+    current_sample_ms: Option<i64>,
+}
+
+/// Records the cross-venue spread of token pairs at a fixed interval
+///
+/// The last swap price per venue is kept for every recorded pair; at the end of each
+/// `sample_ms` interval the spread between the highest and lowest venue price is
+/// appended to the pair's [`SpreadSeries`]. Sampling follows event (block) time, so a
+/// replay produces the same series as the live run. The recording can be saved to and
+/// loaded from a file for offline analysis.
+pub struct SpreadRecorder {
+    config: SpreadRecorderConfig,
+    state: Mutex<RecorderState>,
+}
+
+impl Default for SpreadRecorder {
+    fn default() -> Self {
+        Self::new(SpreadRecorderConfig::default())
+    }
+}
+
+impl SpreadRecorder {
+    pub fn new(mut config: SpreadRecorderConfig) -> Self {
+        config.sample_ms = config.sample_ms.max(1);
+        config.max_samples = config.max_samples.max(1);
+        for pair in &mut config.pairs {
+            pair.1 = normalize_sol_mint(pair.1, config.normalize_native_sol);
+        }
+        Self { config, state: Mutex::new(RecorderState::default()) }
+    }
+
+    /// Records a swap; samples the spreads when the event closes an interval
+    pub fn on_event(&self, event: &dyn UnifiedEvent) {
+        if let Some(trade) = TradeInfo::from_event_with(event, self.config.normalize_native_sol) {
+            self.on_trade(&trade, trade.time_ms(event.recv_us()));
+        }
+    }
+
+    pub fn on_trade(&self, trade: &TradeInfo, time_ms: i64) {
+        let Some(side) = trade.token_side() else {
+            return;
+        };
+        let (quote_mint, quote_amount) = if side.is_buy {
+            (trade.input_mint, trade.amount_in)
+        } else {
+            (trade.output_mint, trade.amount_out)
+        };
+        if side.amount == 0 || quote_amount == 0 {
+            return;
+        }
+        let pair = (side.mint, self.quote_mint(quote_mint));
+        if !self.config.pairs.is_empty() && !self.config.pairs.contains(&pair) {
+            return;
+        }
+        // 原始单位之比，同一交易对内精度一致
+        let price = quote_amount as f64 / side.amount as f64;
+        self.record_price(pair, trade.protocol.clone(), price, time_ms);
+    }
+
+    /// Records an off-chain quote (e.g. a CEX mid price) as a pseudo-venue
+    pub fn on_external_quote(&self, quote: &ExternalQuote) {
+        let pair = (quote.token_mint, self.quote_mint(quote.quote_mint));
+        if !self.config.pairs.is_empty() && !self.config.pairs.contains(&pair) {
+            return;
+        }
+        if !(quote.price.is_finite() && quote.price > 0.0) {
+            return;
+        }
+        self.record_price(pair, quote.venue.clone(), quote.raw_price(), quote.time_ms);
+    }
+
+    fn quote_mint(&self, mint: Pubkey) -> Pubkey {
+        normalize_sol_mint(mint, self.config.normalize_native_sol)
+    }
+
+    fn record_price(&self, pair: PairKey, venue: ProtocolType, price: f64, time_ms: i64) {
+        let mut state = self.state.lock();
+        self.advance(&mut state, time_ms);
+        state.prices.entry(pair).or_default().insert(venue, (price, time_ms));
+    }
+
+    /// Closes the current interval if `now_ms` is past its end, for idle periods with no events
+    pub fn tick(&self, now_ms: i64) {
+        self.advance(&mut self.state.lock(), now_ms);
+    }
+
+    /// Recorded series of a pair
+    pub fn series(&self, token_mint: &Pubkey, quote_mint: &Pubkey) -> Option<SpreadSeries> {
+        self.state.lock().series.get(&(*token_mint, self.quote_mint(*quote_mint))).cloned()
+    }
+
+    /// Recorded series of all pairs, ordered by pair
+    pub fn all_series(&self) -> Vec<SpreadSeries> {
+        let mut series: Vec<SpreadSeries> = self.state.lock().series.values().cloned().collect();
+        series.sort_by_key(|s| (s.token_mint, s.quote_mint));
+        series
+    }
+
+    /// Writes all series to a file (bincode)
+    pub fn save(&self, path: impl AsRef<Path>) -> AnyResult<()> {
+        let bytes = bincode::serialize(&self.all_series())?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Reads series written by [`save`](Self::save)
+    pub fn load(path: impl AsRef<Path>) -> AnyResult<Vec<SpreadSeries>> {
+        Ok(bincode::deserialize(&std::fs::read(path)?)?)
+    }
+
+    fn advance(&self, state: &mut RecorderState, time_ms: i64) {
+        let sample_ms = time_ms - time_ms.rem_euclid(self.config.sample_ms);
+        let current = *state.current_sample_ms.get_or_insert(sample_ms);
+        if sample_ms <= current {
+            return;
+        }
+        state.current_sample_ms = Some(sample_ms);
+        let end_ms = current + self.config.sample_ms;
+        let min_time_ms = end_ms - self.config.max_price_age_ms;
+
+        let RecorderState { prices, series, .. } = state;
+        prices.retain(|&(token_mint, quote_mint), venues| {
+            venues.retain(|_, (_, seen_ms)| *seen_ms >= min_time_ms);
+            let high = venues.iter().max_by(|a, b| a.1 .0.total_cmp(&b.1 .0));
+            let low = venues.iter().min_by(|a, b| a.1 .0.total_cmp(&b.1 .0));
+            if let (Some((high, &(high_price, _))), Some((low, &(low_price, _)))) = (high, low) {
+                if venues.len() >= 2 && low_price > 0.0 {
+                    let spread_bps = ((high_price - low_price) / low_price * 10_000.0) as f32;
+                    let entry = series.entry((token_mint, quote_mint)).or_insert_with(|| {
+                        SpreadSeries { token_mint, quote_mint, ..Default::default() }
+                    });
+                    entry.push(end_ms, spread_bps, high.clone(), low.clone());
+                    entry.truncate_front(self.config.max_samples);
+                }
+            }
+            !venues.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::analytics::trade::WSOL_MINT;
+
+    #[test]
+    fn test_spread_recorder_percentiles() {
+        let recorder = SpreadRecorder::default();
+        let token = Pubkey::new_unique();
+        let trade = |protocol: ProtocolType, quote: u64| TradeInfo {
+            protocol,
+            input_mint: WSOL_MINT,
+            output_mint: token,
+            amount_in: quote,
+            amount_out: 1_000_000,
+            ..Default::default()
+        };
+        // CLMM 价格比 CPMM 高 t bps，t = 1..=100
+        for t in 1..=100i64 {
+            let time_ms = 1_700_000_000_000 + t * 1_000;
+            recorder.on_trade(&trade(ProtocolType::RaydiumCpmm, 1_000_000), time_ms);
+            recorder
+                .on_trade(&trade(ProtocolType::RaydiumClmm, 1_000_000 + t as u64 * 100), time_ms);
+        }
+        recorder.tick(1_700_000_000_000 + 101_000);
+
+        let series = recorder.series(&token, &WSOL_MINT).unwrap();
+        assert_eq!(series.len(), 100);
+        assert_eq!(series.high_venue[0], ProtocolType::RaydiumClmm);
+        assert_eq!(series.percentile_bps(50.0).map(f64::round), Some(50.0));
+        assert_eq!(series.percentile_bps(95.0).map(f64::round), Some(95.0));
+        assert!((series.fraction_at_least(79.5) - 0.21).abs() < 1e-9);
+        assert_eq!(
+            series.between(1_700_000_000_000 + 11_000, 1_700_000_000_000 + 21_000).len(),
+            10
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use super::trade::{normalize_sol_mint, QUOTE_MINTS};
+use crate::match_event;
+use crate::streaming::event_parser::common::ProtocolType;
+use crate::streaming::event_parser::core::account_event_parser::{MintRiskFlags, TokenInfoEvent};
+#[cfg(feature = "raydium-amm-v4")]
+use crate::streaming::event_parser::protocols::raydium_amm_v4::RaydiumAmmV4Initialize2Event;
+#[cfg(feature = "raydium-clmm")]
+use crate::streaming::event_parser::protocols::raydium_clmm::RaydiumClmmCreatePoolEvent;
+#[cfg(feature = "raydium-cpmm")]
+use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmInitializeEvent;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default number of listed tokens remembered for deduplication
+pub const DEFAULT_MAX_LISTED_TOKENS: usize = 100_000;
+/// Default number of mint accounts whose authorities are remembered
+pub const DEFAULT_MAX_MINT_AUTHORITIES: usize = 50_000;
+
+/// First pool of a token on any supported venue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewTokenListed {
+    pub mint: Pubkey,
+    /// The other side of the pool, usually SOL / USDC / USDT
+    pub quote_mint: Pubkey,
+    pub venue: ProtocolType,
+    pub pool: Pubkey,
+    pub creator: Pubkey,
+    /// Initial liquidity in raw units, None when the venue adds it separately (CLMM)
+    pub initial_token_amount: Option<u64>,
+    pub initial_quote_amount: Option<u64>,
+    /// Known when the mint account was seen before the listing (subscribe to mint
+    /// accounts of the token programs to get it)
+    pub mint_authority_revoked: Option<bool>,
+    pub freeze_authority_revoked: Option<bool>,
+    /// Token-2022 risk flags, known under the same condition as the authorities
+    #[serde(default)]
+    pub risk: Option<MintRiskFlags>,
+    pub slot: u64,
+    pub signature: Signature,
+}
+
+#[derive(Default)]
+struct ListingState {
+    listed: HashSet<Pubkey>,
+    listed_order: VecDeque<Pubkey>,
+    /// mint -> (mint_authority, freeze_authority, risk)
+    authorities: HashMap<Pubkey, (Option<Pubkey>, Option<Pubkey>, MintRiskFlags)>,
+    authorities_order: VecDeque<Pubkey>,
+}
+
+/// Consolidated token launch feed across all supported venues
+///
+/// Feed every event through [`on_event`](Self::on_event); the first pool created for a
+/// mint yields a [`NewTokenListed`], pools of an already listed mint (on the same or
+/// another venue) are ignored. Pools between two quote mints are never reported.
+pub struct TokenListingFeed {
+    max_tokens: usize,
+    max_authorities: usize,
+    normalize_native_sol: bool,
+    state: Mutex<ListingState>,
+}
+
+impl Default for TokenListingFeed {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_LISTED_TOKENS)
+    }
+}
+
+impl TokenListingFeed {
+    pub fn new(max_tokens: usize) -> Self {
+        Self {
+            max_tokens: max_tokens.max(1),
+            max_authorities: DEFAULT_MAX_MINT_AUTHORITIES,
+            normalize_native_sol: true,
+            state: Mutex::new(ListingState::default()),
+        }
+    }
+
+    /// Whether a native SOL quote side is reported as WSOL (default: true)
+    pub fn with_native_sol_normalization(mut self, enabled: bool) -> Self {
+        self.normalize_native_sol = enabled;
+        self
+    }
+
+    /// Whether a listing of the mint was already reported
+    pub fn is_listed(&self, mint: &Pubkey) -> bool {
+        self.state.lock().listed.contains(mint)
+    }
+
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<NewTokenListed> {
+        let mut state = self.state.lock();
+        if let Some(e) = event.as_any().downcast_ref::<TokenInfoEvent>() {
+            if state
+                .authorities
+                .insert(e.pubkey, (e.mint_authority, e.freeze_authority, e.risk))
+                .is_none()
+            {
+                state.authorities_order.push_back(e.pubkey);
+                if state.authorities_order.len() > self.max_authorities {
+                    if let Some(oldest) = state.authorities_order.pop_front() {
+                        state.authorities.remove(&oldest);
+                    }
+                }
+            }
+            return None;
+        }
+
+        let mut listing = self.extract(event)?;
+        if state.listed.contains(&listing.mint) {
+            return None;
+        }
+        state.listed.insert(listing.mint);
+        state.listed_order.push_back(listing.mint);
+        if state.listed_order.len() > self.max_tokens {
+            if let Some(oldest) = state.listed_order.pop_front() {
+                state.listed.remove(&oldest);
+            }
+        }
+        if let Some((mint_authority, freeze_authority, risk)) = state.authorities.get(&listing.mint)
+        {
+            listing.mint_authority_revoked = Some(mint_authority.is_none());
+            listing.freeze_authority_revoked = Some(freeze_authority.is_none());
+            listing.risk = Some(*risk);
+        }
+        Some(listing)
+    }
+
+    fn extract(&self, event: &dyn UnifiedEvent) -> Option<NewTokenListed> {
+        let mut listing: Option<NewTokenListed> = None;
+        let metadata = event.metadata();
+        // (pool, creator, mint_a, mint_b, amount_a, amount_b)
+        let mut build = |pool, creator, mint_a, mint_b, amount_a, amount_b| {
+            let normalize = |mint| normalize_sol_mint(mint, self.normalize_native_sol);
+            let (mint_a, mint_b) = (normalize(mint_a), normalize(mint_b));
+            let (mint, quote_mint, token_amount, quote_amount) =
+                match (QUOTE_MINTS.contains(&mint_a), QUOTE_MINTS.contains(&mint_b)) {
+                    (true, true) => return,
+                    (true, false) => (mint_b, mint_a, amount_b, amount_a),
+                    (false, _) => (mint_a, mint_b, amount_a, amount_b),
+                };
+            listing = Some(NewTokenListed {
+                mint,
+                quote_mint,
+                venue: metadata.protocol.clone(),
+                pool,
+                creator,
+                initial_token_amount: token_amount,
+                initial_quote_amount: quote_amount,
+                mint_authority_revoked: None,
+                freeze_authority_revoked: None,
+                risk: None,
+                slot: metadata.slot,
+                signature: metadata.signature,
+            });
+        };
+
+        match_event!(event, {
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmInitializeEvent => |e: RaydiumCpmmInitializeEvent| {
+                build(
+                    e.pool_state,
+                    e.creator,
+                    e.token0_mint,
+                    e.token1_mint,
+                    Some(e.init_amount0),
+                    Some(e.init_amount1),
+                );
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmCreatePoolEvent => |e: RaydiumClmmCreatePoolEvent| {
+                build(e.pool_state, e.pool_creator, e.token_mint0, e.token_mint1, None, None);
+            },
+            #[cfg(feature = "raydium-amm-v4")]
+            RaydiumAmmV4Initialize2Event => |e: RaydiumAmmV4Initialize2Event| {
+                build(
+                    e.amm,
+                    e.user_wallet,
+                    e.coin_mint,
+                    e.pc_mint,
+                    Some(e.init_coin_amount),
+                    Some(e.init_pc_amount),
+                );
+            },
+            _ => |_event: &dyn UnifiedEvent| {},
+        });
+        listing
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm", feature = "raydium-clmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::analytics::WSOL_MINT;
+
+    #[test]
+    fn test_token_listing_deduplicated() {
+        let feed = TokenListingFeed::default();
+        let mint = Pubkey::new_unique();
+        assert!(feed
+            .on_event(&TokenInfoEvent {
+                pubkey: mint,
+                mint_authority: None,
+                freeze_authority: Some(Pubkey::new_unique()),
+                risk: MintRiskFlags { non_transferable: true, ..Default::default() },
+                ..Default::default()
+            })
+            .is_none());
+
+        let listing = feed
+            .on_event(&RaydiumCpmmInitializeEvent {
+                pool_state: Pubkey::new_unique(),
+                token0_mint: WSOL_MINT,
+                token1_mint: mint,
+                init_amount0: 5_000_000_000,
+                init_amount1: 800_000_000,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!((listing.mint, listing.quote_mint), (mint, WSOL_MINT));
+        assert_eq!(listing.initial_token_amount, Some(800_000_000));
+        assert_eq!(listing.initial_quote_amount, Some(5_000_000_000));
+        assert_eq!(listing.mint_authority_revoked, Some(true));
+        assert_eq!(listing.freeze_authority_revoked, Some(false));
+        assert!(listing.risk.is_some_and(|risk| risk.is_risky()));
+
+        // 其他协议上的同一代币不重复上报
+        assert!(feed
+            .on_event(&RaydiumClmmCreatePoolEvent {
+                token_mint0: mint,
+                token_mint1: WSOL_MINT,
+                ..Default::default()
+            })
+            .is_none());
+        assert!(feed.is_listed(&mint));
+    }
+}
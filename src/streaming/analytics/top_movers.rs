@@ -0,0 +1,283 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use super::activity::MintVolume;
+use super::trade::TradeInfo;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default bucket size of the rolling counters (10 seconds)
+pub const DEFAULT_TOP_MOVERS_BUCKET_MS: i64 = 10_000;
+/// Default leaderboard window, also the retention of the counters (5 minutes)
+pub const DEFAULT_TOP_MOVERS_WINDOW_MS: i64 = 300_000;
+/// Default leaderboard size
+pub const DEFAULT_TOP_MOVERS_COUNT: usize = 10;
+
+/// Metric the leaderboard is ordered by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopMoverRanking {
+    #[default]
+    Trades,
+    UniqueBuyers,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TopMoversConfig {
+    pub bucket_ms: i64,
+    /// Window of the leaderboard behind [`TopMoverChanged`]; older counters are dropped
+    pub window_ms: i64,
+    pub count: usize,
+    pub ranking: TopMoverRanking,
+}
+
+impl Default for TopMoversConfig {
+    fn default() -> Self {
+        Self {
+            bucket_ms: DEFAULT_TOP_MOVERS_BUCKET_MS,
+            window_ms: DEFAULT_TOP_MOVERS_WINDOW_MS,
+            count: DEFAULT_TOP_MOVERS_COUNT,
+            ranking: TopMoverRanking::default(),
+        }
+    }
+}
+
+/// Trading activity of one mint within a window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintActivity {
+    pub mint: Pubkey,
+    pub trades: u64,
+    pub buys: u64,
+    pub sells: u64,
+    pub unique_buyers: u64,
+    /// Token-side volume in raw units
+    pub token_volume: u128,
+    /// Quote-side volume per quote mint, descending
+    pub quote_volume: Vec<MintVolume>,
+}
+
+/// Leaderboard update, emitted when membership or order of the top mints changes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopMoverChanged {
+    /// End (exclusive) of the ranked window, milliseconds since epoch
+    pub window_end_ms: i64,
+    pub entered: Vec<Pubkey>,
+    pub exited: Vec<Pubkey>,
+    pub ranking: Vec<MintActivity>,
+}
+
+#[derive(Default)]
+struct Bucket {
+    trades: u64,
+    buys: u64,
+    sells: u64,
+    buyers: HashSet<Pubkey>,
+    token_volume: u128,
+    quote_volume: HashMap<Pubkey, u128>,
+}
+
+#[derive(Default)]
+struct MoversState {
+    current_bucket_ms: Option<i64>,
+    /// mint -> (bucket start, counters), oldest first
+    mints: HashMap<Pubkey, VecDeque<(i64, Bucket)>>,
+    leaders: Vec<Pubkey>,
+}
+
+/// Per-mint rolling trade counters for "trending tokens" features
+///
+/// Buckets are aligned to event (block) time like [`ProtocolActivityAggregator`]'s
+/// windows. The leaderboard is recomputed whenever a bucket closes, so
+/// [`on_event`](Self::on_event) returns at most one [`TopMoverChanged`] per bucket.
+///
+/// [`ProtocolActivityAggregator`]: super::ProtocolActivityAggregator
+pub struct TopMovers {
+    config: TopMoversConfig,
+    state: Mutex<MoversState>,
+}
+
+impl Default for TopMovers {
+    fn default() -> Self {
+        Self::new(TopMoversConfig::default())
+    }
+}
+
+impl TopMovers {
+    pub fn new(mut config: TopMoversConfig) -> Self {
+        config.bucket_ms = config.bucket_ms.max(1);
+        config.window_ms = config.window_ms.max(config.bucket_ms);
+        Self { config, state: Mutex::new(MoversState::default()) }
+    }
+
+    /// Records a swap; returns the leaderboard change when the event closes a bucket
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<TopMoverChanged> {
+        let trade = TradeInfo::from_event(event)?;
+        let side = trade.token_side()?;
+        let time_ms = trade.time_ms(event.recv_us());
+        let bucket_ms = time_ms - time_ms.rem_euclid(self.config.bucket_ms);
+
+        let mut state = self.state.lock();
+        let changed = self.advance(&mut state, bucket_ms);
+        // 迟到的事件计入当前桶
+        let bucket_ms = state.current_bucket_ms.unwrap_or(bucket_ms);
+        let buckets = state.mints.entry(side.mint).or_default();
+        if buckets.back().is_none_or(|(start, _)| *start != bucket_ms) {
+            buckets.push_back((bucket_ms, Bucket::default()));
+        }
+        let bucket = &mut buckets.back_mut().expect("bucket just pushed").1;
+        bucket.trades += 1;
+        bucket.token_volume += side.amount as u128;
+        let (quote_mint, quote_amount) = if side.is_buy {
+            bucket.buys += 1;
+            bucket.buyers.insert(trade.user);
+            (trade.input_mint, trade.amount_in)
+        } else {
+            bucket.sells += 1;
+            (trade.output_mint, trade.amount_out)
+        };
+        *bucket.quote_volume.entry(quote_mint).or_default() += quote_amount as u128;
+        changed
+    }
+
+    /// Closes the current bucket if `now_ms` is past its end, for idle periods with no events
+    pub fn tick(&self, now_ms: i64) -> Option<TopMoverChanged> {
+        let mut state = self.state.lock();
+        self.advance(&mut state, now_ms - now_ms.rem_euclid(self.config.bucket_ms))
+    }
+
+    /// Top `n` mints over the last `window_ms`, including the current partial bucket
+    pub fn top_movers(&self, window_ms: i64, n: usize) -> Vec<MintActivity> {
+        let state = self.state.lock();
+        let Some(current) = state.current_bucket_ms else {
+            return Vec::new();
+        };
+        let end_ms = current + self.config.bucket_ms;
+        self.rank(&state, end_ms - window_ms, end_ms, n)
+    }
+
+    /// Current leaderboard members, best first
+    pub fn leaders(&self) -> Vec<Pubkey> {
+        self.state.lock().leaders.clone()
+    }
+
+    fn advance(&self, state: &mut MoversState, bucket_ms: i64) -> Option<TopMoverChanged> {
+        match state.current_bucket_ms {
+            Some(current) if bucket_ms > current => {}
+            Some(_) => return None,
+            None => {
+                state.current_bucket_ms = Some(bucket_ms);
+                return None;
+            }
+        }
+        state.current_bucket_ms = Some(bucket_ms);
+
+        let start_ms = bucket_ms - self.config.window_ms;
+        state.mints.retain(|_, buckets| {
+            while buckets.front().is_some_and(|(start, _)| *start < start_ms) {
+                buckets.pop_front();
+            }
+            !buckets.is_empty()
+        });
+
+        let ranking = self.rank(state, start_ms, bucket_ms, self.config.count);
+        let leaders: Vec<Pubkey> = ranking.iter().map(|activity| activity.mint).collect();
+        if leaders == state.leaders {
+            return None;
+        }
+        let entered = leaders.iter().filter(|m| !state.leaders.contains(m)).copied().collect();
+        let exited = state.leaders.iter().filter(|m| !leaders.contains(m)).copied().collect();
+        state.leaders = leaders;
+        Some(TopMoverChanged { window_end_ms: bucket_ms, entered, exited, ranking })
+    }
+
+    fn rank(&self, state: &MoversState, start_ms: i64, end_ms: i64, n: usize) -> Vec<MintActivity> {
+        let mut ranking: Vec<MintActivity> = state
+            .mints
+            .iter()
+            .filter_map(|(mint, buckets)| {
+                let mut activity = MintActivity {
+                    mint: *mint,
+                    trades: 0,
+                    buys: 0,
+                    sells: 0,
+                    unique_buyers: 0,
+                    token_volume: 0,
+                    quote_volume: Vec::new(),
+                };
+                let mut buyers = HashSet::new();
+                let mut quote_volume: HashMap<Pubkey, u128> = HashMap::new();
+                for (_, bucket) in buckets.iter().filter(|(s, _)| *s >= start_ms && *s < end_ms) {
+                    activity.trades += bucket.trades;
+                    activity.buys += bucket.buys;
+                    activity.sells += bucket.sells;
+                    activity.token_volume += bucket.token_volume;
+                    buyers.extend(bucket.buyers.iter().copied());
+                    for (quote, amount) in &bucket.quote_volume {
+                        *quote_volume.entry(*quote).or_default() += amount;
+                    }
+                }
+                if activity.trades == 0 {
+                    return None;
+                }
+                activity.unique_buyers = buyers.len() as u64;
+                activity.quote_volume = quote_volume
+                    .into_iter()
+                    .map(|(mint, amount)| MintVolume { mint, amount })
+                    .collect();
+                activity.quote_volume.sort_by(|a, b| b.amount.cmp(&a.amount));
+                Some(activity)
+            })
+            .collect();
+        let key = |a: &MintActivity| match self.config.ranking {
+            TopMoverRanking::Trades => (a.trades, a.unique_buyers),
+            TopMoverRanking::UniqueBuyers => (a.unique_buyers, a.trades),
+        };
+        ranking.sort_by(|a, b| key(b).cmp(&key(a)).then_with(|| a.mint.cmp(&b.mint)));
+        ranking.truncate(n);
+        ranking
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::analytics::WSOL_MINT;
+    use crate::streaming::event_parser::common::EventMetadata;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    fn buy(mint: Pubkey, block_time_ms: i64) -> RaydiumCpmmSwapEvent {
+        RaydiumCpmmSwapEvent {
+            metadata: EventMetadata { block_time_ms, ..Default::default() },
+            payer: Pubkey::new_unique(),
+            input_token_mint: WSOL_MINT,
+            output_token_mint: mint,
+            max_amount_in: 1_000,
+            amount_out: 50,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_top_movers_leaderboard() {
+        let movers =
+            TopMovers::new(TopMoversConfig { bucket_ms: 1_000, count: 1, ..Default::default() });
+        let (hot, cold) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(movers.on_event(&buy(hot, 100)).is_none());
+        assert!(movers.on_event(&buy(hot, 200)).is_none());
+        assert!(movers.on_event(&buy(cold, 300)).is_none());
+
+        let top = movers.top_movers(60_000, 2);
+        assert_eq!(top[0].mint, hot);
+        assert_eq!((top[0].trades, top[0].unique_buyers), (2, 2));
+        assert_eq!(top[0].quote_volume, vec![MintVolume { mint: WSOL_MINT, amount: 2_000 }]);
+
+        let changed = movers.on_event(&buy(cold, 1_500)).unwrap();
+        assert_eq!(changed.window_end_ms, 1_000);
+        assert_eq!(changed.entered, vec![hot]);
+        assert!(movers.on_event(&buy(hot, 1_600)).is_none());
+        assert!(movers.tick(2_100).is_none());
+    }
+}
@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::match_event;
+use crate::streaming::common::MintInfoCache;
+use crate::streaming::event_parser::common::{EventType, ProtocolType};
+use crate::streaming::event_parser::core::account_event_parser::MintRiskFlags;
+#[cfg(feature = "raydium-amm-v4")]
+use crate::streaming::event_parser::protocols::raydium_amm_v4::RaydiumAmmV4SwapEvent;
+#[cfg(feature = "raydium-clmm")]
+use crate::streaming::event_parser::protocols::raydium_clmm::{
+    RaydiumClmmSwapEvent, RaydiumClmmSwapV2Event,
+};
+#[cfg(feature = "raydium-cpmm")]
+use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Wrapped SOL mint
+pub const WSOL_MINT: Pubkey = solana_sdk::pubkey!("So11111111111111111111111111111111111111112");
+/// Placeholder mint used for native (unwrapped) SOL
+pub const NATIVE_SOL_MINT: Pubkey =
+    solana_sdk::pubkey!("So11111111111111111111111111111111111111111");
+/// USDC mint
+pub const USDC_MINT: Pubkey = solana_sdk::pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+/// USDT mint
+pub const USDT_MINT: Pubkey = solana_sdk::pubkey!("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB");
+
+/// Mints treated as the quote side of a pair
+pub const QUOTE_MINTS: &[Pubkey] = &[WSOL_MINT, NATIVE_SOL_MINT, USDC_MINT, USDT_MINT];
+
+/// Maps native SOL to [`WSOL_MINT`] when `enabled`
+///
+/// Wrapping and unwrapping in the same transaction otherwise splits one SOL pair into
+/// two, which breaks mint-based pairing. The analytics modules that pair by mint take
+/// the setting per instance (on by default); parsed events keep the mint they carried.
+pub fn normalize_sol_mint(mint: Pubkey, enabled: bool) -> Pubkey {
+    if enabled && mint == NATIVE_SOL_MINT {
+        WSOL_MINT
+    } else {
+        mint
+    }
+}
+
+/// Token side of a trade, see [`TradeInfo::token_side`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenSide {
+    pub mint: Pubkey,
+    /// true when the user received the token
+    pub is_buy: bool,
+    /// Token amount (raw units)
+    pub amount: u64,
+}
+
+/// Protocol-independent view of a swap event, shared by the analytics modules
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeInfo {
+    pub signature: Signature,
+    pub slot: u64,
+    pub transaction_index: Option<u64>,
+    pub outer_index: i64,
+    pub inner_index: Option<i64>,
+    pub block_time_ms: i64,
+    pub protocol: ProtocolType,
+    pub event_type: EventType,
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    /// Pubkey::default() when the mint cannot be determined
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    /// Actual amounts when swap_data was parsed, otherwise the instruction's limit amounts
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Amount reaching the pool after Token-2022 transfer fees (equal to `amount_in`
+    /// until [`TradeInfo::apply_transfer_fees`] is called)
+    pub amount_in_net: u64,
+    /// Amount the user actually received after Token-2022 transfer fees
+    pub amount_out_net: u64,
+    /// Token-2022 risk flags of the mints, None until [`TradeInfo::apply_mint_risk`]
+    /// finds the mint in the cache
+    #[serde(default)]
+    pub input_mint_risk: Option<MintRiskFlags>,
+    #[serde(default)]
+    pub output_mint_risk: Option<MintRiskFlags>,
+}
+
+impl TradeInfo {
+    /// Extracts a trade from a swap event, `None` for any other event
+    ///
+    /// Native SOL is reported as WSOL, see [`from_event_with`](Self::from_event_with).
+    pub fn from_event(event: &dyn UnifiedEvent) -> Option<Self> {
+        Self::from_event_with(event, true)
+    }
+
+    /// Like [`from_event`](Self::from_event), with native SOL normalization optional
+    pub fn from_event_with(event: &dyn UnifiedEvent, normalize_native_sol: bool) -> Option<Self> {
+        let mut trade: Option<TradeInfo> = None;
+
+        match_event!(event, {
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmSwapEvent => |e: RaydiumCpmmSwapEvent| {
+                let base_input = e.metadata.event_type == EventType::RaydiumCpmmSwapBaseInput;
+                trade = Some(TradeInfo {
+                    pool: e.pool_state,
+                    user: e.payer,
+                    input_mint: e.input_token_mint,
+                    output_mint: e.output_token_mint,
+                    amount_in: if base_input { e.amount_in } else { e.max_amount_in },
+                    amount_out: if base_input { e.minimum_amount_out } else { e.amount_out },
+                    ..Default::default()
+                });
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmSwapEvent => |e: RaydiumClmmSwapEvent| {
+                let (amount_in, amount_out) = if e.is_base_input {
+                    (e.amount, e.other_amount_threshold)
+                } else {
+                    (e.other_amount_threshold, e.amount)
+                };
+                trade = Some(TradeInfo {
+                    pool: e.pool_state,
+                    user: e.payer,
+                    amount_in,
+                    amount_out,
+                    ..Default::default()
+                });
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmSwapV2Event => |e: RaydiumClmmSwapV2Event| {
+                let (amount_in, amount_out) = if e.is_base_input {
+                    (e.amount, e.other_amount_threshold)
+                } else {
+                    (e.other_amount_threshold, e.amount)
+                };
+                trade = Some(TradeInfo {
+                    pool: e.pool_state,
+                    user: e.payer,
+                    input_mint: e.input_vault_mint,
+                    output_mint: e.output_vault_mint,
+                    amount_in,
+                    amount_out,
+                    ..Default::default()
+                });
+            },
+            #[cfg(feature = "raydium-amm-v4")]
+            RaydiumAmmV4SwapEvent => |e: RaydiumAmmV4SwapEvent| {
+                let base_in = e.metadata.event_type == EventType::RaydiumAmmV4SwapBaseIn;
+                trade = Some(TradeInfo {
+                    pool: e.amm,
+                    user: e.user_source_owner,
+                    amount_in: if base_in { e.amount_in } else { e.max_amount_in },
+                    amount_out: if base_in { e.minimum_amount_out } else { e.amount_out },
+                    ..Default::default()
+                });
+            },
+            _ => |_event: &dyn UnifiedEvent| {},
+        });
+
+        let mut trade = trade?;
+        let metadata = event.metadata();
+        trade.signature = metadata.signature;
+        trade.slot = metadata.slot;
+        trade.transaction_index = metadata.transaction_index;
+        trade.outer_index = metadata.outer_index;
+        trade.inner_index = metadata.inner_index;
+        trade.block_time_ms = metadata.block_time_ms;
+        trade.protocol = metadata.protocol.clone();
+        trade.event_type = metadata.event_type.clone();
+
+        // 优先使用内联转账解析出的实际成交数据
+        if let Some(swap_data) = metadata.swap_data.as_ref() {
+            if swap_data.from_mint != Pubkey::default() {
+                trade.input_mint = swap_data.from_mint;
+            }
+            if swap_data.to_mint != Pubkey::default() {
+                trade.output_mint = swap_data.to_mint;
+            }
+            if swap_data.from_amount > 0 {
+                trade.amount_in = swap_data.from_amount;
+            }
+            if swap_data.to_amount > 0 {
+                trade.amount_out = swap_data.to_amount;
+            }
+        }
+        trade.input_mint = normalize_sol_mint(trade.input_mint, normalize_native_sol);
+        trade.output_mint = normalize_sol_mint(trade.output_mint, normalize_native_sol);
+        trade.amount_in_net = trade.amount_in;
+        trade.amount_out_net = trade.amount_out;
+
+        Some(trade)
+    }
+
+    /// Fills the net amounts using the transfer fee configs of the traded mints
+    pub fn apply_transfer_fees(&mut self, mints: &MintInfoCache) {
+        self.amount_in_net = mints.net_amount(&self.input_mint, self.amount_in);
+        self.amount_out_net = mints.net_amount(&self.output_mint, self.amount_out);
+    }
+
+    /// Fills the risk flags of the traded mints from the cache
+    pub fn apply_mint_risk(&mut self, mints: &MintInfoCache) {
+        self.input_mint_risk = mints.risk(&self.input_mint);
+        self.output_mint_risk = mints.risk(&self.output_mint);
+    }
+
+    /// Whether either traded mint has a risky Token-2022 extension
+    pub fn is_risky(&self) -> bool {
+        [self.input_mint_risk, self.output_mint_risk].iter().flatten().any(MintRiskFlags::is_risky)
+    }
+
+    /// The non-quote side of the trade
+    ///
+    /// `None` when the mints are unknown or both sides are quote mints. When neither
+    /// side is a quote mint the output side is reported as a buy.
+    pub fn token_side(&self) -> Option<TokenSide> {
+        if self.input_mint == Pubkey::default() || self.output_mint == Pubkey::default() {
+            return None;
+        }
+        let input_is_quote = QUOTE_MINTS.contains(&self.input_mint);
+        let output_is_quote = QUOTE_MINTS.contains(&self.output_mint);
+        match (input_is_quote, output_is_quote) {
+            (true, true) => None,
+            (_, false) => {
+                Some(TokenSide { mint: self.output_mint, is_buy: true, amount: self.amount_out })
+            }
+            (false, true) => {
+                Some(TokenSide { mint: self.input_mint, is_buy: false, amount: self.amount_in })
+            }
+        }
+    }
+
+    /// Event time in milliseconds, falls back to receive time when block time is unknown
+    pub fn time_ms(&self, recv_us: i64) -> i64 {
+        if self.block_time_ms > 0 {
+            self.block_time_ms
+        } else {
+            recv_us / 1000
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_sol_normalized() {
+        assert_eq!(normalize_sol_mint(NATIVE_SOL_MINT, true), WSOL_MINT);
+        assert_eq!(normalize_sol_mint(NATIVE_SOL_MINT, false), NATIVE_SOL_MINT);
+        assert_eq!(normalize_sol_mint(USDC_MINT, true), USDC_MINT);
+        let trade =
+            TradeInfo { input_mint: NATIVE_SOL_MINT, output_mint: USDC_MINT, ..Default::default() };
+        assert!(trade.token_side().is_none());
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::{HashMap, HashSet};
+
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey, pubkey::Pubkey, signature::Signature};
+use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
+
+use super::funding::system_instructions;
+
+/// Base fee charged per transaction signature
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Jito tip payment accounts
+pub const JITO_TIP_ACCOUNTS: [Pubkey; 8] = [
+    pubkey!("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    pubkey!("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    pubkey!("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    pubkey!("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    pubkey!("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    pubkey!("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    pubkey!("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+/// System 程序 CreateAccount / Transfer / CreateAccountWithSeed 指令编号
+const SYSTEM_CREATE_ACCOUNT: u32 = 0;
+const SYSTEM_TRANSFER: u32 = 2;
+const SYSTEM_CREATE_ACCOUNT_WITH_SEED: u32 = 3;
+
+/// What one transaction cost the wallet that paid for it, in lamports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionCost {
+    /// Fee payer
+    pub wallet: Pubkey,
+    pub signature: Signature,
+    pub slot: u64,
+    /// Signature fee
+    pub base_fee: u64,
+    /// Compute unit price × limit, the part of `meta.fee` above the base fee
+    pub priority_fee: u64,
+    /// Transfers from the wallet to the Jito tip accounts
+    pub jito_tip: u64,
+    /// Lamports the wallet funded new accounts with (token accounts, PDAs)
+    pub rent_paid: u64,
+    /// Failed transactions only pay fees, tips and rent are rolled back
+    pub failed: bool,
+}
+
+impl TransactionCost {
+    /// Breaks down the cost of a gRPC transaction, None without status meta
+    pub fn from_transaction(slot: u64, tx: &SubscribeUpdateTransactionInfo) -> Option<Self> {
+        let meta = tx.meta.as_ref()?;
+        let message = tx.transaction.as_ref()?.message.as_ref()?;
+        let wallet = Pubkey::try_from(message.account_keys.first()?.as_slice()).ok()?;
+        let signatures = message
+            .header
+            .as_ref()
+            .map(|header| header.num_required_signatures as u64)
+            .unwrap_or(1)
+            .max(1);
+        let base_fee = (signatures * LAMPORTS_PER_SIGNATURE).min(meta.fee);
+        let mut cost = Self {
+            wallet,
+            signature: Signature::try_from(tx.signature.as_slice()).unwrap_or_default(),
+            slot,
+            base_fee,
+            priority_fee: meta.fee - base_fee,
+            jito_tip: 0,
+            rent_paid: 0,
+            failed: meta.err.is_some(),
+        };
+        if cost.failed {
+            return Some(cost);
+        }
+        for ix in system_instructions(tx) {
+            if ix.accounts.first() != Some(&wallet) {
+                continue;
+            }
+            let lamports_at = |offset: usize| {
+                ix.data
+                    .get(offset..offset + 8)
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .unwrap_or(0)
+            };
+            match ix.kind {
+                SYSTEM_TRANSFER
+                    if ix.accounts.get(1).is_some_and(|to| JITO_TIP_ACCOUNTS.contains(to)) =>
+                {
+                    cost.jito_tip += lamports_at(4);
+                }
+                SYSTEM_CREATE_ACCOUNT => cost.rent_paid += lamports_at(4),
+                SYSTEM_CREATE_ACCOUNT_WITH_SEED => {
+                    // base(32) 之后是 u64 长度前缀的 seed，再之后是 lamports
+                    let seed_len = lamports_at(36) as usize;
+                    cost.rent_paid += lamports_at(44 + seed_len);
+                }
+                _ => {}
+            }
+        }
+        Some(cost)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.base_fee + self.priority_fee + self.jito_tip + self.rent_paid
+    }
+}
+
+/// Cost totals of a wallet since tracking started
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletCostTotals {
+    pub transactions: u64,
+    pub failed_transactions: u64,
+    pub base_fee: u64,
+    pub priority_fee: u64,
+    pub jito_tip: u64,
+    pub rent_paid: u64,
+}
+
+impl WalletCostTotals {
+    pub fn total(&self) -> u64 {
+        self.base_fee + self.priority_fee + self.jito_tip + self.rent_paid
+    }
+
+    fn add(&mut self, cost: &TransactionCost) {
+        self.transactions += 1;
+        self.failed_transactions += cost.failed as u64;
+        self.base_fee += cost.base_fee;
+        self.priority_fee += cost.priority_fee;
+        self.jito_tip += cost.jito_tip;
+        self.rent_paid += cost.rent_paid;
+    }
+}
+
+/// Per-transaction cost events for watched wallets
+///
+/// Swap events only carry traded amounts, so PnL built from them misses what the
+/// wallet spent on fees, tips and rent. Feed the wallets' transactions (including
+/// failed ones, which still pay fees) through [`on_transaction`](Self::on_transaction)
+/// to get a [`TransactionCost`] for each transaction a watched wallet paid for, and
+/// the running [`totals`](Self::totals) per wallet.
+#[derive(Default)]
+pub struct TransactionCostTracker {
+    wallets: RwLock<HashSet<Pubkey>>,
+    totals: Mutex<HashMap<Pubkey, WalletCostTotals>>,
+}
+
+impl TransactionCostTracker {
+    pub fn new(wallets: impl IntoIterator<Item = Pubkey>) -> Self {
+        Self { wallets: RwLock::new(wallets.into_iter().collect()), ..Default::default() }
+    }
+
+    pub fn watch(&self, wallet: Pubkey) -> bool {
+        self.wallets.write().insert(wallet)
+    }
+
+    /// Stops watching the wallet and drops its totals
+    pub fn unwatch(&self, wallet: &Pubkey) -> bool {
+        self.totals.lock().remove(wallet);
+        self.wallets.write().remove(wallet)
+    }
+
+    pub fn is_watched(&self, wallet: &Pubkey) -> bool {
+        self.wallets.read().contains(wallet)
+    }
+
+    /// Cost of the transaction when a watched wallet paid for it
+    pub fn on_transaction(
+        &self,
+        slot: u64,
+        tx: &SubscribeUpdateTransactionInfo,
+    ) -> Option<TransactionCost> {
+        let cost = TransactionCost::from_transaction(slot, tx)?;
+        if !self.is_watched(&cost.wallet) {
+            return None;
+        }
+        self.totals.lock().entry(cost.wallet).or_default().add(&cost);
+        Some(cost)
+    }
+
+    pub fn totals(&self, wallet: &Pubkey) -> WalletCostTotals {
+        self.totals.lock().get(wallet).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use yellowstone_grpc_proto::prelude::{
+        CompiledInstruction, InnerInstruction, InnerInstructions, Message, MessageHeader,
+        Transaction, TransactionStatusMeta,
+    };
+
+    use super::*;
+
+    fn system_ix(program: u32, accounts: Vec<u8>, kind: u32, lamports: u64) -> CompiledInstruction {
+        let mut data = kind.to_le_bytes().to_vec();
+        data.extend_from_slice(&lamports.to_le_bytes());
+        CompiledInstruction { program_id_index: program, accounts, data }
+    }
+
+    #[test]
+    fn test_transaction_cost_breakdown() {
+        let (wallet, new_account, tip) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), JITO_TIP_ACCOUNTS[0]);
+        let system = pubkey!("11111111111111111111111111111111");
+        let account_keys =
+            [wallet, new_account, tip, system].iter().map(|key| key.to_bytes().to_vec()).collect();
+        let create = system_ix(3, vec![0, 1], SYSTEM_CREATE_ACCOUNT, 2_039_280);
+        let tx = SubscribeUpdateTransactionInfo {
+            signature: Signature::new_unique().as_ref().to_vec(),
+            transaction: Some(Transaction {
+                message: Some(Message {
+                    header: Some(MessageHeader {
+                        num_required_signatures: 1,
+                        ..Default::default()
+                    }),
+                    account_keys,
+                    instructions: vec![system_ix(3, vec![0, 2], SYSTEM_TRANSFER, 100_000)],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            meta: Some(TransactionStatusMeta {
+                fee: 25_000,
+                inner_instructions: vec![InnerInstructions {
+                    index: 0,
+                    instructions: vec![InnerInstruction {
+                        program_id_index: create.program_id_index,
+                        accounts: create.accounts,
+                        data: create.data,
+                        stack_height: Some(2),
+                    }],
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let tracker = TransactionCostTracker::default();
+        assert_eq!(tracker.on_transaction(7, &tx), None);
+        tracker.watch(wallet);
+        let cost = tracker.on_transaction(7, &tx).unwrap();
+        assert_eq!((cost.base_fee, cost.priority_fee), (5_000, 20_000));
+        assert_eq!((cost.jito_tip, cost.rent_paid), (100_000, 2_039_280));
+        assert_eq!(tracker.totals(&wallet).total(), cost.total());
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use super::trade::TradeInfo;
+use crate::match_event;
+use crate::streaming::event_parser::common::{EventType, ProtocolType};
+#[cfg(feature = "raydium-amm-v4")]
+use crate::streaming::event_parser::protocols::raydium_amm_v4::{
+    RaydiumAmmV4DepositEvent, RaydiumAmmV4Initialize2Event, RaydiumAmmV4WithdrawEvent,
+};
+#[cfg(feature = "raydium-clmm")]
+use crate::streaming::event_parser::protocols::raydium_clmm::{
+    RaydiumClmmCreatePoolEvent, RaydiumClmmDecreaseLiquidityV2Event,
+    RaydiumClmmIncreaseLiquidityV2Event,
+};
+#[cfg(feature = "raydium-cpmm")]
+use crate::streaming::event_parser::protocols::raydium_cpmm::{
+    RaydiumCpmmDepositEvent, RaydiumCpmmInitializeEvent, RaydiumCpmmWithdrawEvent,
+};
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default number of activities kept per wallet
+pub const DEFAULT_WALLET_HISTORY: usize = 1_000;
+
+/// One DEX action of a tracked wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletActivity {
+    pub wallet: Pubkey,
+    pub protocol: ProtocolType,
+    pub event_type: EventType,
+    pub pool: Pubkey,
+    /// Set for swaps
+    pub trade: Option<TradeInfo>,
+    pub signature: Signature,
+    pub slot: u64,
+    pub transaction_index: Option<u64>,
+    pub outer_index: i64,
+    pub inner_index: Option<i64>,
+    pub block_time_ms: i64,
+}
+
+impl WalletActivity {
+    /// Chain order: slot, transaction within the slot, instruction within the transaction
+    pub fn order_key(&self) -> (u64, u64, i64, i64) {
+        (
+            self.slot,
+            self.transaction_index.unwrap_or(u64::MAX),
+            self.outer_index,
+            self.inner_index.unwrap_or(-1),
+        )
+    }
+}
+
+/// Wallet acting in the event (swap user, liquidity owner, pool creator) and the pool
+pub fn event_wallet(event: &dyn UnifiedEvent) -> Option<(Pubkey, Pubkey)> {
+    if let Some(trade) = TradeInfo::from_event(event) {
+        return Some((trade.user, trade.pool));
+    }
+    let mut actor: Option<(Pubkey, Pubkey)> = None;
+    match_event!(event, {
+        #[cfg(feature = "raydium-cpmm")]
+        RaydiumCpmmInitializeEvent => |e: RaydiumCpmmInitializeEvent| {
+            actor = Some((e.creator, e.pool_state));
+        },
+        #[cfg(feature = "raydium-cpmm")]
+        RaydiumCpmmDepositEvent => |e: RaydiumCpmmDepositEvent| {
+            actor = Some((e.owner, e.pool_state));
+        },
+        #[cfg(feature = "raydium-cpmm")]
+        RaydiumCpmmWithdrawEvent => |e: RaydiumCpmmWithdrawEvent| {
+            actor = Some((e.owner, e.pool_state));
+        },
+        #[cfg(feature = "raydium-clmm")]
+        RaydiumClmmCreatePoolEvent => |e: RaydiumClmmCreatePoolEvent| {
+            actor = Some((e.pool_creator, e.pool_state));
+        },
+        #[cfg(feature = "raydium-clmm")]
+        RaydiumClmmIncreaseLiquidityV2Event => |e: RaydiumClmmIncreaseLiquidityV2Event| {
+            actor = Some((e.nft_owner, e.pool_state));
+        },
+        #[cfg(feature = "raydium-clmm")]
+        RaydiumClmmDecreaseLiquidityV2Event => |e: RaydiumClmmDecreaseLiquidityV2Event| {
+            actor = Some((e.nft_owner, e.pool_state));
+        },
+        #[cfg(feature = "raydium-amm-v4")]
+        RaydiumAmmV4Initialize2Event => |e: RaydiumAmmV4Initialize2Event| {
+            actor = Some((e.user_wallet, e.amm));
+        },
+        #[cfg(feature = "raydium-amm-v4")]
+        RaydiumAmmV4DepositEvent => |e: RaydiumAmmV4DepositEvent| {
+            actor = Some((e.user_owner, e.amm));
+        },
+        #[cfg(feature = "raydium-amm-v4")]
+        RaydiumAmmV4WithdrawEvent => |e: RaydiumAmmV4WithdrawEvent| {
+            actor = Some((e.user_owner, e.amm));
+        },
+        _ => |_event: &dyn UnifiedEvent| {},
+    });
+    actor
+}
+
+/// Per-wallet, chain-ordered DEX activity of a set of wallets
+///
+/// Pair it with `TransactionFilter::for_wallets` (or use `YellowstoneGrpc::subscribe_wallets`)
+/// so the stream only carries transactions the wallets signed. Events of those
+/// transactions where the wallet is not the acting party are dropped here.
+pub struct WalletActivityTracker {
+    wallets: RwLock<BTreeSet<Pubkey>>,
+    max_history: usize,
+    history: Mutex<HashMap<Pubkey, VecDeque<WalletActivity>>>,
+}
+
+impl WalletActivityTracker {
+    pub fn new(wallets: impl IntoIterator<Item = Pubkey>) -> Self {
+        Self {
+            wallets: RwLock::new(wallets.into_iter().collect()),
+            max_history: DEFAULT_WALLET_HISTORY,
+            history: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_max_history(mut self, max_history: usize) -> Self {
+        self.max_history = max_history.max(1);
+        self
+    }
+
+    pub fn add_wallets(&self, wallets: impl IntoIterator<Item = Pubkey>) -> usize {
+        let mut tracked = self.wallets.write();
+        wallets.into_iter().filter(|wallet| tracked.insert(*wallet)).count()
+    }
+
+    /// Stops tracking the wallets and drops their history
+    pub fn remove_wallets(&self, wallets: impl IntoIterator<Item = Pubkey>) -> usize {
+        let mut tracked = self.wallets.write();
+        let mut history = self.history.lock();
+        wallets
+            .into_iter()
+            .filter(|wallet| {
+                history.remove(wallet);
+                tracked.remove(wallet)
+            })
+            .count()
+    }
+
+    /// Tracked wallets in a stable order
+    pub fn wallets(&self) -> Vec<Pubkey> {
+        self.wallets.read().iter().copied().collect()
+    }
+
+    /// Records the event if a tracked wallet is its acting party
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Option<WalletActivity> {
+        let (wallet, pool) = event_wallet(event)?;
+        if !self.wallets.read().contains(&wallet) {
+            return None;
+        }
+        let metadata = event.metadata();
+        let activity = WalletActivity {
+            wallet,
+            protocol: metadata.protocol.clone(),
+            event_type: metadata.event_type.clone(),
+            pool,
+            trade: TradeInfo::from_event(event),
+            signature: metadata.signature,
+            slot: metadata.slot,
+            transaction_index: metadata.transaction_index,
+            outer_index: metadata.outer_index,
+            inner_index: metadata.inner_index,
+            block_time_ms: metadata.block_time_ms,
+        };
+
+        let mut history = self.history.lock();
+        let entries = history.entry(wallet).or_default();
+        // 乱序到达时按链上顺序插入
+        let key = activity.order_key();
+        let position = entries.partition_point(|entry| entry.order_key() <= key);
+        entries.insert(position, activity.clone());
+        if entries.len() > self.max_history {
+            entries.pop_front();
+        }
+        Some(activity)
+    }
+
+    /// Recorded activity of the wallet, oldest first
+    pub fn history(&self, wallet: &Pubkey) -> Vec<WalletActivity> {
+        self.history.lock().get(wallet).map(|h| h.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    /// Activity of the wallet after `slot`, oldest first
+    pub fn history_since(&self, wallet: &Pubkey, slot: u64) -> Vec<WalletActivity> {
+        self.history
+            .lock()
+            .get(wallet)
+            .map(|h| h.iter().filter(|a| a.slot > slot).cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::EventMetadata;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    #[test]
+    fn test_wallet_history_ordered() {
+        let wallet = Pubkey::new_unique();
+        let tracker = WalletActivityTracker::new([wallet]);
+        let swap = |payer, slot, transaction_index| RaydiumCpmmSwapEvent {
+            metadata: EventMetadata {
+                slot,
+                transaction_index: Some(transaction_index),
+                ..Default::default()
+            },
+            payer,
+            ..Default::default()
+        };
+
+        assert!(tracker.on_event(&swap(Pubkey::new_unique(), 10, 0)).is_none());
+        tracker.on_event(&swap(wallet, 11, 3)).unwrap();
+        tracker.on_event(&swap(wallet, 11, 1)).unwrap();
+        tracker.on_event(&RaydiumCpmmDepositEvent {
+            metadata: EventMetadata { slot: 9, ..Default::default() },
+            owner: wallet,
+            ..Default::default()
+        });
+
+        let history = tracker.history(&wallet);
+        assert_eq!(
+            history.iter().map(|a| (a.slot, a.transaction_index)).collect::<Vec<_>>(),
+            vec![(9, None), (11, Some(1)), (11, Some(3))]
+        );
+        assert!(history[0].trade.is_none() && history[1].trade.is_some());
+        assert_eq!(tracker.history_since(&wallet, 10).len(), 2);
+    }
+}
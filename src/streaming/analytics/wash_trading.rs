@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use super::funding::WalletFundingClusters;
+use super::trade::TradeInfo;
+use super::window::{ClosedWindow, TumblingWindow};
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default analysis window (five minutes)
+pub const DEFAULT_WASH_WINDOW_MS: i64 = 300_000;
+
+/// Tuning for [`WashTradingAnalyzer`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WashTradingConfig {
+    /// Window length in milliseconds
+    pub window_ms: i64,
+    /// Size of the wallet cluster whose volume share is measured
+    pub cluster_size: usize,
+    /// Mints with fewer trades in a window are not scored
+    pub min_trades: u64,
+    /// Reports with a score below this are flagged
+    pub flag_threshold: f64,
+}
+
+impl Default for WashTradingConfig {
+    fn default() -> Self {
+        Self {
+            window_ms: DEFAULT_WASH_WINDOW_MS,
+            cluster_size: 3,
+            min_trades: 20,
+            flag_threshold: 0.5,
+        }
+    }
+}
+
+/// Volume-quality assessment of a mint over one window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolumeQualityReport {
+    pub mint: Pubkey,
+    pub window_start_ms: i64,
+    pub window_end_ms: i64,
+    pub trade_count: u64,
+    pub unique_wallets: u64,
+    /// Token volume (raw units, buys + sells)
+    pub total_volume: u128,
+    /// Share of volume from the `cluster_size` most active wallets
+    pub top_cluster_share: f64,
+    /// Share of volume that is matched buy/sell round-trips by the same wallet
+    pub round_trip_share: f64,
+    pub top_wallets: Vec<Pubkey>,
+    /// 1.0 = organic looking volume, 0.0 = almost certainly wash traded
+    pub score: f64,
+    pub flagged: bool,
+}
+
+#[derive(Default, Clone, Copy)]
+struct WalletFlow {
+    bought: u128,
+    sold: u128,
+}
+
+#[derive(Default)]
+struct MintWindow {
+    trades: u64,
+    wallets: HashMap<Pubkey, WalletFlow>,
+}
+
+/// Flags mints whose volume is dominated by a small wallet cluster or self round-trips
+///
+/// Volume is attributed to the non-quote side of each trade (see
+/// [`TradeInfo::token_side`]); reports are emitted when a window closes.
+pub struct WashTradingAnalyzer {
+    config: WashTradingConfig,
+    window: Mutex<TumblingWindow<Pubkey, MintWindow>>,
+    clusters: Option<Arc<WalletFundingClusters>>,
+}
+
+impl Default for WashTradingAnalyzer {
+    fn default() -> Self {
+        Self::new(WashTradingConfig::default())
+    }
+}
+
+impl WashTradingAnalyzer {
+    pub fn new(config: WashTradingConfig) -> Self {
+        let window = Mutex::new(TumblingWindow::new(config.window_ms));
+        Self { config, window, clusters: None }
+    }
+
+    /// Attributes volume of wallets sharing a funder to their cluster ID instead of each wallet
+    pub fn with_funding_clusters(mut self, clusters: Arc<WalletFundingClusters>) -> Self {
+        self.clusters = Some(clusters);
+        self
+    }
+
+    /// Records a trade; returns reports for the previous window once it closes
+    pub fn on_event(&self, event: &dyn UnifiedEvent) -> Vec<VolumeQualityReport> {
+        let Some(trade) = TradeInfo::from_event(event) else {
+            return Vec::new();
+        };
+        let Some(side) = trade.token_side() else {
+            return Vec::new();
+        };
+        let time_ms = trade.time_ms(event.recv_us());
+
+        let mut windows = self.window.lock();
+        let reports = self.score_window(windows.advance(time_ms));
+
+        let window = windows.entry(side.mint);
+        window.trades += 1;
+        let wallet = self
+            .clusters
+            .as_ref()
+            .and_then(|clusters| clusters.cluster_of(&trade.user))
+            .unwrap_or(trade.user);
+        let flow = window.wallets.entry(wallet).or_default();
+        if side.is_buy {
+            flow.bought += side.amount as u128;
+        } else {
+            flow.sold += side.amount as u128;
+        }
+        reports
+    }
+
+    /// Closes the current window and scores it
+    pub fn flush(&self) -> Vec<VolumeQualityReport> {
+        let closed = self.window.lock().close();
+        self.score_window(closed)
+    }
+
+    fn score_window(
+        &self,
+        closed: Option<ClosedWindow<Pubkey, MintWindow>>,
+    ) -> Vec<VolumeQualityReport> {
+        let Some(closed) = closed else {
+            return Vec::new();
+        };
+        closed
+            .entries
+            .into_iter()
+            .filter(|(_, window)| window.trades >= self.config.min_trades)
+            .filter_map(|(mint, window)| self.score(mint, window, closed.start_ms, closed.end_ms))
+            .collect()
+    }
+
+    fn score(
+        &self,
+        mint: Pubkey,
+        window: MintWindow,
+        window_start_ms: i64,
+        window_end_ms: i64,
+    ) -> Option<VolumeQualityReport> {
+        let mut wallets: Vec<(Pubkey, WalletFlow)> = window.wallets.into_iter().collect();
+        let total_volume: u128 = wallets.iter().map(|(_, f)| f.bought + f.sold).sum();
+        if total_volume == 0 {
+            return None;
+        }
+        let round_trip_volume: u128 = wallets.iter().map(|(_, f)| f.bought.min(f.sold) * 2).sum();
+
+        wallets.sort_by(|a, b| (b.1.bought + b.1.sold).cmp(&(a.1.bought + a.1.sold)));
+        let cluster = &wallets[..self.config.cluster_size.min(wallets.len())];
+        let cluster_volume: u128 = cluster.iter().map(|(_, f)| f.bought + f.sold).sum();
+
+        let top_cluster_share = cluster_volume as f64 / total_volume as f64;
+        let round_trip_share = round_trip_volume as f64 / total_volume as f64;
+        // 前几名钱包占比超过一半开始扣分，全部由其贡献时扣满
+        let concentration_penalty = ((top_cluster_share - 0.5) / 0.5).clamp(0.0, 1.0);
+        let score = (1.0 - round_trip_share) * (1.0 - concentration_penalty);
+
+        Some(VolumeQualityReport {
+            mint,
+            window_start_ms,
+            window_end_ms,
+            trade_count: window.trades,
+            unique_wallets: wallets.len() as u64,
+            total_volume,
+            top_cluster_share,
+            round_trip_share,
+            top_wallets: cluster.iter().map(|(wallet, _)| *wallet).collect(),
+            score,
+            flagged: score < self.config.flag_threshold,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::analytics::WSOL_MINT;
+    use crate::streaming::event_parser::common::{EventMetadata, EventType};
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    fn swap(user: Pubkey, mint: Pubkey, is_buy: bool, amount: u64) -> RaydiumCpmmSwapEvent {
+        let (input, output) = if is_buy { (WSOL_MINT, mint) } else { (mint, WSOL_MINT) };
+        RaydiumCpmmSwapEvent {
+            metadata: EventMetadata {
+                event_type: EventType::RaydiumCpmmSwapBaseInput,
+                block_time_ms: 100,
+                ..Default::default()
+            },
+            payer: user,
+            input_token_mint: input,
+            output_token_mint: output,
+            amount_in: amount,
+            minimum_amount_out: amount,
+            ..Default::default()
+        }
+    }
+
+    fn analyzer() -> WashTradingAnalyzer {
+        WashTradingAnalyzer::new(WashTradingConfig {
+            window_ms: 1_000,
+            min_trades: 4,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_self_trade_loop_flagged() {
+        let analyzer = analyzer();
+        let (mint, wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+        for _ in 0..5 {
+            analyzer.on_event(&swap(wallet, mint, true, 1_000));
+            analyzer.on_event(&swap(wallet, mint, false, 1_000));
+        }
+
+        let reports = analyzer.flush();
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!((report.mint, report.trade_count, report.unique_wallets), (mint, 10, 1));
+        assert_eq!(report.round_trip_share, 1.0);
+        assert_eq!(report.top_wallets, vec![wallet]);
+        assert!(report.flagged);
+    }
+
+    #[test]
+    fn test_unrelated_wallets_not_flagged() {
+        let analyzer = analyzer();
+        let mint = Pubkey::new_unique();
+        for i in 0..20 {
+            analyzer.on_event(&swap(Pubkey::new_unique(), mint, i % 3 != 0, 1_000 + i));
+        }
+        // 下一个窗口的交易关闭当前窗口
+        let reports = analyzer.on_event(&RaydiumCpmmSwapEvent {
+            metadata: EventMetadata {
+                event_type: EventType::RaydiumCpmmSwapBaseInput,
+                block_time_ms: 1_100,
+                ..Default::default()
+            },
+            ..swap(Pubkey::new_unique(), mint, true, 1_000)
+        });
+
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!((report.window_start_ms, report.window_end_ms), (0, 1_000));
+        assert_eq!(report.unique_wallets, 20);
+        assert_eq!(report.round_trip_share, 0.0);
+        assert!(report.top_cluster_share < 0.5);
+        assert_eq!(report.score, 1.0);
+        assert!(!report.flagged);
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Per-key state of the current tumbling window, aligned to multiples of the window length
+///
+/// Shared bookkeeping of the windowed analytics: a window closes when the first event
+/// of a later window arrives (or on an explicit flush), late events count in the current
+/// window.
+pub(crate) struct TumblingWindow<K, V> {
+    window_ms: i64,
+    start_ms: Option<i64>,
+    entries: HashMap<K, V>,
+}
+
+/// A window handed out when it closes
+pub(crate) struct ClosedWindow<K, V> {
+    pub start_ms: i64,
+    /// Exclusive
+    pub end_ms: i64,
+    pub entries: HashMap<K, V>,
+}
+
+impl<K: Eq + Hash, V: Default> TumblingWindow<K, V> {
+    pub fn new(window_ms: i64) -> Self {
+        Self { window_ms: window_ms.max(1), start_ms: None, entries: HashMap::new() }
+    }
+
+    pub fn window_ms(&self) -> i64 {
+        self.window_ms
+    }
+
+    /// Moves to the window containing `time_ms`; returns the previous window if it closed
+    pub fn advance(&mut self, time_ms: i64) -> Option<ClosedWindow<K, V>> {
+        let window_start = time_ms - time_ms.rem_euclid(self.window_ms);
+        match self.start_ms {
+            Some(current) if window_start > current => {
+                let closed = self.close();
+                self.start_ms = Some(window_start);
+                closed
+            }
+            // 迟到的事件计入当前窗口
+            Some(_) => None,
+            None => {
+                self.start_ms = Some(window_start);
+                None
+            }
+        }
+    }
+
+    /// State of `key` in the current window
+    pub fn entry(&mut self, key: K) -> &mut V {
+        self.entries.entry(key).or_default()
+    }
+
+    /// Closes the current window if `now_ms` is past its end, for idle periods
+    pub fn close_expired(&mut self, now_ms: i64) -> Option<ClosedWindow<K, V>> {
+        match self.start_ms {
+            Some(start) if now_ms >= start + self.window_ms => self.close(),
+            _ => None,
+        }
+    }
+
+    /// Closes the current window unconditionally
+    pub fn close(&mut self) -> Option<ClosedWindow<K, V>> {
+        let start_ms = self.start_ms.take()?;
+        Some(ClosedWindow {
+            start_ms,
+            end_ms: start_ms + self.window_ms,
+            entries: std::mem::take(&mut self.entries),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tumbling_window() {
+        let mut window: TumblingWindow<u8, u64> = TumblingWindow::new(1_000);
+        assert!(window.advance(1_500).is_none());
+        *window.entry(1) += 1;
+        // 迟到的事件计入当前窗口
+        assert!(window.advance(900).is_none());
+        *window.entry(1) += 1;
+        assert!(window.close_expired(1_999).is_none());
+
+        let closed = window.advance(2_100).unwrap();
+        assert_eq!((closed.start_ms, closed.end_ms), (1_000, 2_000));
+        assert_eq!(closed.entries[&1], 2);
+
+        let closed = window.close_expired(3_000).unwrap();
+        assert_eq!(closed.start_ms, 2_000);
+        assert!(closed.entries.is_empty());
+        assert!(window.close().is_none());
+    }
+}
@@ -0,0 +1,484 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+use ciborium::value::Value;
+use parking_lot::Mutex;
+use prost::Message;
+use solana_sdk::message::compiled_instruction::CompiledInstruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{InnerInstruction, InnerInstructions};
+use yellowstone_grpc_proto::solana::storage::confirmed_block::TransactionStatusMeta;
+
+use crate::common::AnyResult;
+use crate::streaming::event_parser::core::event_parser::EventParser;
+use crate::streaming::event_parser::core::RawTransactionMeta;
+use crate::streaming::event_parser::UnifiedEvent;
+use crate::streaming::sink::EventSink;
+
+/// Old Faithful 节点类型
+const KIND_TRANSACTION: u64 = 0;
+const KIND_BLOCK: u64 = 2;
+const KIND_DATA_FRAME: u64 = 6;
+
+/// DAG-CBOR link tag
+const CID_TAG: u64 = 42;
+
+/// Reader of CARv1 files, yielding `(cid, data)` blocks
+pub struct CarReader<R> {
+    reader: R,
+}
+
+impl CarReader<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> AnyResult<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+        Self::new(BufReader::with_capacity(1 << 20, file))
+    }
+}
+
+impl<R: Read> CarReader<R> {
+    /// Reads and checks the header
+    pub fn new(mut reader: R) -> AnyResult<Self> {
+        let len = read_varint(&mut reader)?.ok_or_else(|| anyhow!("empty CAR file"))?;
+        let mut header = vec![0u8; len as usize];
+        reader.read_exact(&mut header)?;
+        let header: Value = ciborium::from_reader(header.as_slice())?;
+        let version = map_get(&header, "version").and_then(as_u64);
+        if version != Some(1) {
+            bail!("unsupported CAR version: {:?}", version);
+        }
+        Ok(Self { reader })
+    }
+
+    /// Next block, None at the end of the file
+    pub fn next_block(&mut self) -> AnyResult<Option<(Vec<u8>, Vec<u8>)>> {
+        let Some(len) = read_varint(&mut self.reader)? else {
+            return Ok(None);
+        };
+        let mut section = vec![0u8; len as usize];
+        self.reader.read_exact(&mut section)?;
+        let data = section.split_off(cid_len(&section)?);
+        Ok(Some((section, data)))
+    }
+}
+
+/// Counters of a replay run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayStats {
+    pub blocks: u64,
+    pub transactions: u64,
+    /// Failed transactions, skipped like on the live stream
+    pub failed: u64,
+    /// Parsed without meta (missing frames or pre-protobuf metadata); inner-instruction
+    /// events of these are lost
+    pub without_meta: u64,
+    pub events: u64,
+}
+
+struct DataFrame {
+    data: Vec<u8>,
+    next: Vec<Vec<u8>>,
+}
+
+struct PendingTransaction {
+    slot: u64,
+    index: Option<u64>,
+    data: Vec<u8>,
+    metadata: Option<Vec<u8>>,
+}
+
+/// Replays the transactions of an Old Faithful epoch CAR through `parser` into `sink`
+///
+/// Blocks are written after their transactions, so transactions are held until their
+/// block node supplies the block time, then parsed in transaction order. Sinks are
+/// flushed at the end.
+pub fn replay_car<R: Read>(
+    mut reader: CarReader<R>,
+    parser: &EventParser,
+    sink: &dyn EventSink,
+) -> AnyResult<ReplayStats> {
+    let mut stats = ReplayStats::default();
+    // 跨块的数据帧（大交易被拆分），每个区块后清空
+    let mut frames: HashMap<Vec<u8>, DataFrame> = HashMap::new();
+    let mut pending: Vec<PendingTransaction> = Vec::new();
+
+    while let Some((cid, data)) = reader.next_block()? {
+        let node: Value = ciborium::from_reader(data.as_slice())
+            .with_context(|| format!("invalid CBOR node {}", hex::encode(&cid)))?;
+        let Some(fields) = node.as_array() else {
+            continue;
+        };
+        match fields.first().and_then(as_u64) {
+            Some(KIND_DATA_FRAME) => {
+                if let Some(frame) = decode_data_frame(&node) {
+                    frames.insert(cid, frame);
+                }
+            }
+            Some(KIND_TRANSACTION) => {
+                pending.extend(decode_transaction(fields, &frames));
+            }
+            Some(KIND_BLOCK) => {
+                stats.blocks += 1;
+                replay_pending(&mut pending, block_time(fields), parser, sink, &mut stats)?;
+                frames.clear();
+            }
+            _ => {}
+        }
+    }
+    replay_pending(&mut pending, None, parser, sink, &mut stats)?;
+    sink.flush()?;
+    Ok(stats)
+}
+
+/// Parses the transactions of one slot from an Old Faithful epoch CAR
+///
+/// Scans the CAR up to the block node of `slot`. Returns None when the CAR has no block
+/// for it (a skipped slot or another epoch); failed transactions are skipped like in
+/// [`replay_car`].
+pub fn read_car_slot<R: Read>(
+    mut reader: CarReader<R>,
+    parser: &EventParser,
+    slot: u64,
+) -> AnyResult<Option<Vec<Box<dyn UnifiedEvent>>>> {
+    let mut stats = ReplayStats::default();
+    let mut frames: HashMap<Vec<u8>, DataFrame> = HashMap::new();
+    let mut pending: Vec<PendingTransaction> = Vec::new();
+
+    while let Some((cid, data)) = reader.next_block()? {
+        let node: Value = ciborium::from_reader(data.as_slice())
+            .with_context(|| format!("invalid CBOR node {}", hex::encode(&cid)))?;
+        let Some(fields) = node.as_array() else {
+            continue;
+        };
+        match fields.first().and_then(as_u64) {
+            Some(KIND_DATA_FRAME) => {
+                if let Some(frame) = decode_data_frame(&node) {
+                    frames.insert(cid, frame);
+                }
+            }
+            Some(KIND_TRANSACTION) => {
+                if fields.get(3).and_then(as_u64) == Some(slot) {
+                    pending.extend(decode_transaction(fields, &frames));
+                }
+            }
+            Some(KIND_BLOCK) => {
+                let block_slot = fields.get(1).and_then(as_u64).unwrap_or(0);
+                if block_slot == slot {
+                    let collector = CollectSink::default();
+                    replay_pending(
+                        &mut pending,
+                        block_time(fields),
+                        parser,
+                        &collector,
+                        &mut stats,
+                    )?;
+                    return Ok(Some(collector.events.into_inner()));
+                }
+                // CAR 按 slot 顺序写入，越过目标即可停止
+                if block_slot > slot {
+                    return Ok(None);
+                }
+                frames.clear();
+            }
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Keeps the replayed events in memory
+#[derive(Default)]
+struct CollectSink {
+    events: Mutex<Vec<Box<dyn UnifiedEvent>>>,
+}
+
+impl EventSink for CollectSink {
+    fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+        self.events.lock().push(event.clone_boxed());
+        Ok(())
+    }
+}
+
+/// `[kind, data, metadata, slot, index]`, None when data frames are missing
+fn decode_transaction(
+    fields: &[Value],
+    frames: &HashMap<Vec<u8>, DataFrame>,
+) -> Option<PendingTransaction> {
+    let slot = fields.get(3).and_then(as_u64).unwrap_or(0);
+    let Some(data) = fields.get(1).and_then(|f| assemble(f, frames)) else {
+        log::warn!("Skipping transaction with missing data frames in slot {}", slot);
+        return None;
+    };
+    let metadata = fields.get(2).and_then(|f| assemble(f, frames));
+    let index = fields.get(4).and_then(as_u64);
+    Some(PendingTransaction { slot, index, data, metadata })
+}
+
+/// `[kind, slot, shredding, entries, [parent_slot, block_time, ...], rewards]`
+fn block_time(fields: &[Value]) -> Option<i64> {
+    fields
+        .get(4)
+        .and_then(Value::as_array)
+        .and_then(|meta| meta.get(1))
+        .and_then(as_u64)
+        .map(|t| t as i64)
+}
+
+fn replay_pending(
+    pending: &mut Vec<PendingTransaction>,
+    block_time: Option<i64>,
+    parser: &EventParser,
+    sink: &dyn EventSink,
+    stats: &mut ReplayStats,
+) -> AnyResult<()> {
+    pending.sort_by_key(|tx| (tx.slot, tx.index));
+    for tx in pending.drain(..) {
+        stats.transactions += 1;
+        let status = tx.metadata.as_deref().and_then(decode_meta);
+        if status.as_ref().is_some_and(|status| status.err.is_some()) {
+            stats.failed += 1;
+            continue;
+        }
+        if status.is_none() {
+            stats.without_meta += 1;
+        }
+        let meta = raw_meta(tx.slot, block_time, tx.index, status);
+        match parser.parse_transaction_bytes(&tx.data, &meta) {
+            Ok(events) => {
+                stats.events += events.len() as u64;
+                for event in events {
+                    sink.send(event.as_ref())?;
+                }
+            }
+            Err(e) => log::warn!("Failed to decode transaction in slot {}: {}", tx.slot, e),
+        }
+    }
+    Ok(())
+}
+
+/// zstd 压缩的 protobuf meta；早期 epoch 的 bincode 格式返回 None
+fn decode_meta(bytes: &[u8]) -> Option<TransactionStatusMeta> {
+    if bytes.is_empty() {
+        return None;
+    }
+    let decompressed = zstd::decode_all(bytes).ok()?;
+    TransactionStatusMeta::decode(decompressed.as_slice()).ok()
+}
+
+fn raw_meta(
+    slot: u64,
+    block_time: Option<i64>,
+    transaction_index: Option<u64>,
+    status: Option<TransactionStatusMeta>,
+) -> RawTransactionMeta {
+    let mut meta = RawTransactionMeta { slot, block_time, transaction_index, ..Default::default() };
+    let Some(status) = status else {
+        return meta;
+    };
+    let to_pubkeys = |keys: Vec<Vec<u8>>| {
+        keys.into_iter().filter_map(|key| Pubkey::try_from(key.as_slice()).ok()).collect()
+    };
+    meta.compute_units_consumed = status.compute_units_consumed;
+    meta.loaded_writable_addresses = to_pubkeys(status.loaded_writable_addresses);
+    meta.loaded_readonly_addresses = to_pubkeys(status.loaded_readonly_addresses);
+    meta.inner_instructions = status
+        .inner_instructions
+        .into_iter()
+        .map(|inner| InnerInstructions {
+            index: inner.index as u8,
+            instructions: inner
+                .instructions
+                .into_iter()
+                .map(|ix| InnerInstruction {
+                    instruction: CompiledInstruction {
+                        program_id_index: ix.program_id_index as u8,
+                        accounts: ix.accounts,
+                        data: ix.data,
+                    },
+                    stack_height: ix.stack_height,
+                })
+                .collect(),
+        })
+        .collect();
+    meta
+}
+
+/// `[kind, hash, index, total, data, next]`
+fn decode_data_frame(value: &Value) -> Option<DataFrame> {
+    let fields = value.as_array()?;
+    let data = fields.get(4)?.as_bytes()?.clone();
+    let next = fields
+        .get(5)
+        .and_then(Value::as_array)
+        .map(|links| links.iter().filter_map(as_cid).collect())
+        .unwrap_or_default();
+    Some(DataFrame { data, next })
+}
+
+/// Concatenates an inline frame with its continuation frames
+fn assemble(value: &Value, frames: &HashMap<Vec<u8>, DataFrame>) -> Option<Vec<u8>> {
+    let first = decode_data_frame(value)?;
+    let mut data = first.data;
+    let mut next = first.next;
+    while !next.is_empty() {
+        let mut following = Vec::new();
+        for cid in &next {
+            let frame = frames.get(cid)?;
+            data.extend_from_slice(&frame.data);
+            following.extend(frame.next.iter().cloned());
+        }
+        next = following;
+    }
+    Some(data)
+}
+
+fn as_u64(value: &Value) -> Option<u64> {
+    value.as_integer().and_then(|i| u64::try_from(i).ok())
+}
+
+fn as_cid(value: &Value) -> Option<Vec<u8>> {
+    match value {
+        // DAG-CBOR 链接前缀一个 0x00 字节
+        Value::Tag(CID_TAG, inner) => inner.as_bytes().and_then(|b| b.get(1..)).map(<[u8]>::to_vec),
+        _ => None,
+    }
+}
+
+fn map_get<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    value.as_map()?.iter().find(|(k, _)| k.as_text() == Some(key)).map(|(_, v)| v)
+}
+
+/// Unsigned LEB128, None on a clean end of file
+fn read_varint(reader: &mut impl Read) -> AnyResult<Option<u64>> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8; 1];
+        if let Err(e) = reader.read_exact(&mut byte) {
+            if e.kind() == ErrorKind::UnexpectedEof && shift == 0 {
+                return Ok(None);
+            }
+            return Err(e.into());
+        }
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+    bail!("varint overflow")
+}
+
+/// Byte length of the CID at the start of a section
+fn cid_len(section: &[u8]) -> AnyResult<usize> {
+    // CIDv0: 裸 sha2-256 multihash
+    if section.starts_with(&[0x12, 0x20]) {
+        return Ok(34);
+    }
+    let mut cursor = section;
+    // version, codec, multihash code, digest length
+    let mut fields = [0u64; 4];
+    for field in &mut fields {
+        *field = read_varint(&mut cursor)?.ok_or_else(|| anyhow!("truncated CID"))?;
+    }
+    let len = section.len() - cursor.len() + fields[3] as usize;
+    if len > section.len() {
+        bail!("truncated CID");
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(mut value: u64, out: &mut Vec<u8>) {
+        while value >= 0x80 {
+            out.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn section(cid: &[u8], node: &Value, out: &mut Vec<u8>) {
+        let mut data = Vec::new();
+        ciborium::into_writer(node, &mut data).unwrap();
+        varint((cid.len() + data.len()) as u64, out);
+        out.extend_from_slice(cid);
+        out.extend_from_slice(&data);
+    }
+
+    fn header() -> Vec<u8> {
+        let mut car = Vec::new();
+        let mut header = Vec::new();
+        let header_value = Value::Map(vec![
+            (Value::Text("roots".into()), Value::Array(vec![])),
+            (Value::Text("version".into()), Value::Integer(1.into())),
+        ]);
+        ciborium::into_writer(&header_value, &mut header).unwrap();
+        varint(header.len() as u64, &mut car);
+        car.extend_from_slice(&header);
+        car
+    }
+
+    /// CIDv1, dag-cbor, sha2-256, 32 字节摘要
+    fn cid(seed: u8) -> Vec<u8> {
+        [[0x01, 0x71, 0x12, 0x20].as_slice(), &[seed; 32]].concat()
+    }
+
+    #[test]
+    fn test_car_reader_assembles_frames() {
+        let mut car = header();
+        let cid = cid(7);
+        let frame = |data: &[u8], next: Option<&[u8]>| {
+            Value::Array(vec![
+                Value::Integer(KIND_DATA_FRAME.into()),
+                Value::Null,
+                Value::Null,
+                Value::Null,
+                Value::Bytes(data.to_vec()),
+                next.map_or(Value::Null, |cid| {
+                    let link = [[0u8].as_slice(), cid].concat();
+                    Value::Array(vec![Value::Tag(CID_TAG, Box::new(Value::Bytes(link)))])
+                }),
+            ])
+        };
+        section(&cid, &frame(b"world", None), &mut car);
+
+        let mut reader = CarReader::new(car.as_slice()).unwrap();
+        let (block_cid, data) = reader.next_block().unwrap().unwrap();
+        assert_eq!(block_cid, cid);
+        assert!(reader.next_block().unwrap().is_none());
+
+        let node: Value = ciborium::from_reader(data.as_slice()).unwrap();
+        let frames = HashMap::from([(block_cid, decode_data_frame(&node).unwrap())]);
+        assert_eq!(assemble(&frame(b"hello ", Some(&cid)), &frames).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn test_read_car_slot() {
+        let mut car = header();
+        for slot in [5u64, 7] {
+            let block = Value::Array(vec![
+                Value::Integer(KIND_BLOCK.into()),
+                Value::Integer(slot.into()),
+                Value::Null,
+                Value::Array(vec![]),
+                Value::Array(vec![
+                    Value::Integer((slot - 1).into()),
+                    Value::Integer(1_700_000_000.into()),
+                ]),
+                Value::Null,
+            ]);
+            section(&cid(slot as u8), &block, &mut car);
+        }
+        let parser = EventParser::new(vec![], None);
+        let read =
+            |slot| read_car_slot(CarReader::new(car.as_slice()).unwrap(), &parser, slot).unwrap();
+        assert!(read(5).unwrap().is_empty());
+        // 跳过的 slot 和 CAR 范围之外的 slot
+        assert!(read(6).is_none());
+        assert!(read(9).is_none());
+    }
+}
@@ -0,0 +1,5 @@
+// 归档模块 - 离线读取历史数据并用与实时流相同的解析器回放
+pub mod car;
+
+// 重新导出主要类型
+pub use car::*;
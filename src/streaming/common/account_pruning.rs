@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::match_event;
+use crate::streaming::event_parser::common::high_performance_clock::{system_clock, Clock};
+#[cfg(feature = "raydium-amm-v4")]
+use crate::streaming::event_parser::protocols::raydium_amm_v4::RaydiumAmmV4AmmInfoAccountEvent;
+#[cfg(feature = "raydium-clmm")]
+use crate::streaming::event_parser::protocols::raydium_clmm::{
+    RaydiumClmmPoolStateAccountEvent, RaydiumClmmTickArrayStateAccountEvent,
+};
+#[cfg(feature = "raydium-cpmm")]
+use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmPoolStateAccountEvent;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default time without updates after which an account is pruned (30 min)
+pub const DEFAULT_PRUNE_IDLE_MS: u64 = 30 * 60 * 1000;
+/// Default interval between pruning passes
+pub const DEFAULT_PRUNE_CHECK_INTERVAL_MS: u64 = 60_000;
+
+/// When subscribed accounts are dropped from the subscription
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountPruningConfig {
+    /// Prune accounts without any update for this long
+    pub idle_ms: u64,
+    /// Prune pools whose liquidity falls below this, in the protocol's own unit
+    /// (CPMM `lp_supply`, CLMM `liquidity`, AMM V4 `lp_amount`)
+    pub min_liquidity: Option<u128>,
+    /// Interval between pruning passes
+    pub check_interval_ms: u64,
+}
+
+impl Default for AccountPruningConfig {
+    fn default() -> Self {
+        Self {
+            idle_ms: DEFAULT_PRUNE_IDLE_MS,
+            min_liquidity: None,
+            check_interval_ms: DEFAULT_PRUNE_CHECK_INTERVAL_MS,
+        }
+    }
+}
+
+/// Why an account was pruned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PruneReason {
+    /// No update for `idle_ms`
+    Idle { idle_ms: u64 },
+    /// Pool liquidity below `min_liquidity`
+    Drained { liquidity: u128 },
+    /// Tick array of a pruned pool
+    PoolPruned { pool: Pubkey },
+}
+
+/// A pruning decision, announced as `SystemEvent::AccountsPruned`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountPruned {
+    pub account: Pubkey,
+    pub reason: PruneReason,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct TrackedAccount {
+    last_update_us: i64,
+    liquidity: Option<u128>,
+    /// 所属池（CLMM tick array）
+    pool: Option<Pubkey>,
+}
+
+/// Decides which subscribed accounts to drop on long-running deployments
+///
+/// Discovery-driven subscriptions only ever grow: every new pool adds its pool and
+/// tick array accounts. The pruner tracks the subscribed accounts, records activity
+/// from raw updates ([`touch`](Self::touch)) and liquidity from parsed pool events
+/// ([`on_event`](Self::on_event)), and [`due`](Self::due) returns the accounts that
+/// went quiet or were drained. See `YellowstoneGrpc::enable_account_pruning`.
+#[derive(Debug)]
+pub struct AccountPruner {
+    config: AccountPruningConfig,
+    clock: Arc<dyn Clock>,
+    accounts: Mutex<HashMap<Pubkey, TrackedAccount>>,
+}
+
+impl Default for AccountPruner {
+    fn default() -> Self {
+        Self::new(AccountPruningConfig::default())
+    }
+}
+
+impl AccountPruner {
+    pub fn new(config: AccountPruningConfig) -> Self {
+        Self { config, clock: system_clock(), accounts: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn config(&self) -> &AccountPruningConfig {
+        &self.config
+    }
+
+    /// Starts tracking subscribed accounts, idle time counts from now
+    pub fn track(&self, accounts: impl IntoIterator<Item = Pubkey>) {
+        let now = self.clock.now_micros();
+        let mut tracked = self.accounts.lock();
+        for account in accounts {
+            tracked
+                .entry(account)
+                .or_insert(TrackedAccount { last_update_us: now, ..Default::default() });
+        }
+    }
+
+    /// Stops tracking accounts, e.g. after they were unsubscribed by hand
+    pub fn untrack(&self, accounts: &[Pubkey]) {
+        let mut tracked = self.accounts.lock();
+        for account in accounts {
+            tracked.remove(account);
+        }
+    }
+
+    /// Records an update of a tracked account
+    pub fn touch(&self, account: &Pubkey) {
+        if let Some(tracked) = self.accounts.lock().get_mut(account) {
+            tracked.last_update_us = self.clock.now_micros();
+        }
+    }
+
+    /// Records pool liquidity and tick array ownership from parsed account events
+    pub fn on_event(&self, event: &dyn UnifiedEvent) {
+        let mut liquidity: Option<(Pubkey, u128)> = None;
+        let mut tick_array: Option<(Pubkey, Pubkey)> = None;
+        match_event!(event, {
+            #[cfg(feature = "raydium-cpmm")]
+            RaydiumCpmmPoolStateAccountEvent => |e: RaydiumCpmmPoolStateAccountEvent| {
+                liquidity = Some((e.pubkey, e.pool_state.lp_supply as u128));
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmPoolStateAccountEvent => |e: RaydiumClmmPoolStateAccountEvent| {
+                liquidity = Some((e.pubkey, e.pool_state.liquidity));
+            },
+            #[cfg(feature = "raydium-clmm")]
+            RaydiumClmmTickArrayStateAccountEvent => |e: RaydiumClmmTickArrayStateAccountEvent| {
+                tick_array = Some((e.pubkey, e.tick_array_state.pool_id));
+            },
+            #[cfg(feature = "raydium-amm-v4")]
+            RaydiumAmmV4AmmInfoAccountEvent => |e: RaydiumAmmV4AmmInfoAccountEvent| {
+                liquidity = Some((e.pubkey, e.amm_info.lp_amount as u128));
+            },
+            _ => |_event: &dyn UnifiedEvent| {},
+        });
+        let mut tracked = self.accounts.lock();
+        if let Some((pool, amount)) = liquidity {
+            if let Some(account) = tracked.get_mut(&pool) {
+                account.liquidity = Some(amount);
+            }
+        }
+        if let Some((account, pool)) = tick_array {
+            if let Some(account) = tracked.get_mut(&account) {
+                account.pool = Some(pool);
+            }
+        }
+    }
+
+    /// Removes and returns the accounts to unsubscribe
+    pub fn due(&self) -> Vec<AccountPruned> {
+        let now = self.clock.now_micros();
+        let idle_us = (self.config.idle_ms as i64).saturating_mul(1000);
+        let mut tracked = self.accounts.lock();
+        let mut pruned: Vec<AccountPruned> = tracked
+            .iter()
+            .filter_map(|(account, state)| {
+                let reason = match (state.liquidity, self.config.min_liquidity) {
+                    (Some(liquidity), Some(min)) if liquidity < min => {
+                        PruneReason::Drained { liquidity }
+                    }
+                    _ if now - state.last_update_us >= idle_us => {
+                        PruneReason::Idle { idle_ms: ((now - state.last_update_us) / 1000) as u64 }
+                    }
+                    _ => return None,
+                };
+                Some(AccountPruned { account: *account, reason })
+            })
+            .collect();
+        for entry in &pruned {
+            tracked.remove(&entry.account);
+        }
+        // 池被移除后其 tick array 不再有意义，一并移除
+        let pools: Vec<Pubkey> = pruned.iter().map(|entry| entry.account).collect();
+        let orphans: Vec<(Pubkey, Pubkey)> = tracked
+            .iter()
+            .filter_map(|(account, state)| {
+                state.pool.filter(|pool| pools.contains(pool)).map(|pool| (*account, pool))
+            })
+            .collect();
+        for (account, pool) in orphans {
+            tracked.remove(&account);
+            pruned.push(AccountPruned { account, reason: PruneReason::PoolPruned { pool } });
+        }
+        pruned
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(all(test, feature = "raydium-clmm"))]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::streaming::event_parser::common::high_performance_clock::MockClock;
+    use crate::streaming::event_parser::protocols::raydium_clmm::types::{
+        PoolState, TickArrayState,
+    };
+
+    #[test]
+    fn test_account_pruning() {
+        let clock = Arc::new(MockClock::new(0));
+        let config = AccountPruningConfig {
+            idle_ms: 60_000,
+            min_liquidity: Some(1_000),
+            ..Default::default()
+        };
+        let pruner = AccountPruner::new(config).with_clock(clock.clone());
+        let (drained, tick_array, busy, quiet) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        pruner.track([drained, tick_array, busy, quiet]);
+        pruner.on_event(&RaydiumClmmPoolStateAccountEvent {
+            pubkey: drained,
+            pool_state: PoolState { liquidity: 10, ..Default::default() },
+            ..Default::default()
+        });
+        pruner.on_event(&RaydiumClmmTickArrayStateAccountEvent {
+            pubkey: tick_array,
+            tick_array_state: TickArrayState { pool_id: drained, ..Default::default() },
+            ..Default::default()
+        });
+
+        let pruned = pruner.due();
+        assert_eq!(pruned.len(), 2);
+        assert!(pruned.contains(&AccountPruned {
+            account: drained,
+            reason: PruneReason::Drained { liquidity: 10 },
+        }));
+        assert!(pruned.contains(&AccountPruned {
+            account: tick_array,
+            reason: PruneReason::PoolPruned { pool: drained },
+        }));
+
+        clock.advance(Duration::from_secs(45));
+        pruner.touch(&busy);
+        clock.advance(Duration::from_secs(30));
+        let pruned = pruner.due();
+        assert_eq!(
+            pruned,
+            vec![AccountPruned { account: quiet, reason: PruneReason::Idle { idle_ms: 75_000 } }]
+        );
+        assert_eq!(pruner.len(), 1);
+    }
+}
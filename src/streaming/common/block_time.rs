@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+
+use parking_lot::RwLock;
+
+use crate::streaming::event_parser::common::BlockTimeSource;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Nominal slot duration used to extrapolate past the known slots
+pub const DEFAULT_SLOT_DURATION_MS: i64 = 400;
+/// Number of slot → time anchors kept
+pub const DEFAULT_MAX_BLOCK_TIME_ANCHORS: usize = 1024;
+
+/// Fills missing block times from known slot times
+///
+/// At processed commitment `block_time` is often absent, which leaves `block_time_ms`
+/// at 0 and breaks anything that ages events. Anchors come from `BlockMetaEvent`s and
+/// events that carry an actual block time; missing times are interpolated between the
+/// nearest anchors, or extrapolated by [`DEFAULT_SLOT_DURATION_MS`] per slot. The local
+/// clock is never used, so estimates are unaffected by clock skew on this host; without
+/// any anchor the block time stays 0 with [`BlockTimeSource::Missing`].
+pub struct BlockTimeEstimator {
+    slot_duration_ms: i64,
+    max_anchors: usize,
+    anchors: RwLock<BTreeMap<u64, i64>>,
+}
+
+impl Default for BlockTimeEstimator {
+    fn default() -> Self {
+        Self::new(DEFAULT_SLOT_DURATION_MS, DEFAULT_MAX_BLOCK_TIME_ANCHORS)
+    }
+}
+
+impl BlockTimeEstimator {
+    pub fn new(slot_duration_ms: i64, max_anchors: usize) -> Self {
+        Self {
+            slot_duration_ms: slot_duration_ms.max(1),
+            max_anchors: max_anchors.max(1),
+            anchors: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Records the actual block time of a slot
+    pub fn record(&self, slot: u64, block_time_ms: i64) {
+        if block_time_ms <= 0 {
+            return;
+        }
+        let mut anchors = self.anchors.write();
+        anchors.insert(slot, block_time_ms);
+        while anchors.len() > self.max_anchors {
+            anchors.pop_first();
+        }
+    }
+
+    /// Estimated block time of a slot in milliseconds, `None` without any anchor
+    pub fn estimate(&self, slot: u64) -> Option<i64> {
+        let anchors = self.anchors.read();
+        let below = anchors.range(..=slot).next_back();
+        let above = anchors.range(slot..).next();
+        match (below, above) {
+            (Some((&s0, &t0)), Some((&s1, &t1))) => {
+                if s0 == s1 {
+                    return Some(t0);
+                }
+                // 区块时间只有秒级精度，插值时保持单调
+                let span = (t1 - t0).max(0);
+                Some(t0 + span * (slot - s0) as i64 / (s1 - s0) as i64)
+            }
+            (Some((&s0, &t0)), None) => Some(t0 + (slot - s0) as i64 * self.slot_duration_ms),
+            (None, Some((&s1, &t1))) => Some(t1 - (s1 - slot) as i64 * self.slot_duration_ms),
+            (None, None) => None,
+        }
+    }
+
+    /// Learns from an event with an actual block time, or fills in a missing one
+    ///
+    /// Returns true if the event's block time was estimated.
+    pub fn apply(&self, event: &mut dyn UnifiedEvent) -> bool {
+        let metadata = event.metadata_mut();
+        match metadata.block_time_source {
+            BlockTimeSource::Actual => {
+                self.record(metadata.slot, metadata.block_time_ms);
+                false
+            }
+            BlockTimeSource::Estimated => false,
+            BlockTimeSource::Missing => match self.estimate(metadata.slot) {
+                Some(block_time_ms) => {
+                    metadata.block_time_ms = block_time_ms;
+                    metadata.block_time = block_time_ms / 1000;
+                    metadata.block_time_source = BlockTimeSource::Estimated;
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.anchors.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.anchors.read().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::protocols::block::block_meta_event::BlockMetaEvent;
+
+    #[test]
+    fn test_estimate_interpolates_and_extrapolates() {
+        let estimator = BlockTimeEstimator::new(400, 2);
+        assert_eq!(estimator.estimate(100), None);
+
+        estimator.record(100, 1_000_000);
+        estimator.record(110, 1_004_000);
+        estimator.record(105, 0);
+        assert_eq!(estimator.len(), 2);
+        assert_eq!(estimator.estimate(100), Some(1_000_000));
+        assert_eq!(estimator.estimate(105), Some(1_002_000));
+        assert_eq!(estimator.estimate(112), Some(1_004_800));
+        assert_eq!(estimator.estimate(98), Some(999_200));
+
+        // 秒级精度导致的时间倒退不会产生倒退的估算
+        estimator.record(120, 1_003_000);
+        assert_eq!(estimator.len(), 2);
+        assert_eq!(estimator.estimate(115), Some(1_004_000));
+        assert_eq!(estimator.estimate(100), Some(1_004_000 - 10 * 400));
+    }
+
+    #[test]
+    fn test_apply_fills_missing_block_time() {
+        let estimator = BlockTimeEstimator::default();
+        let mut missing = BlockMetaEvent::new(11, String::new(), 0, 0);
+        assert!(!estimator.apply(&mut missing));
+        assert_eq!(missing.metadata.block_time_source, BlockTimeSource::Missing);
+
+        let mut actual = BlockMetaEvent::new(10, String::new(), 5_000_000, 0);
+        assert!(!estimator.apply(&mut actual));
+        assert!(estimator.apply(&mut missing));
+        assert_eq!(missing.metadata.block_time_ms, 5_000_400);
+        assert_eq!(missing.metadata.block_time, 5_000);
+        assert_eq!(missing.metadata.block_time_source, BlockTimeSource::Estimated);
+    }
+}
@@ -0,0 +1,253 @@
+use std::sync::atomic::{AtomicI64, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, Weak};
+use std::time::Instant;
+
+use crossbeam::channel::{bounded, Sender, TrySendError};
+use serde::{Deserialize, Serialize};
+
+use super::metrics::MetricsManager;
+use crate::streaming::event_parser::common::high_performance_clock::get_high_perf_clock;
+use crate::streaming::event_parser::UnifiedEvent;
+
+type Callback = Arc<dyn Fn(Box<dyn UnifiedEvent>) + Send + Sync>;
+
+/// Default time one callback invocation may take
+pub const DEFAULT_CALLBACK_BUDGET_US: u64 = 1_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CallbackBudgetConfig {
+    /// Time one callback invocation may take
+    pub budget_us: u64,
+    /// Minimum interval between slow-callback warnings
+    pub warn_interval_ms: u64,
+    /// Move the callback to worker threads after this many consecutive over-budget
+    /// invocations (0 = never)
+    pub offload_after: u32,
+    /// Worker threads of the offload pool, more than one gives up event order
+    pub offload_workers: usize,
+    /// Events queued for the pool, newer events are dropped when it is full
+    pub offload_queue_size: usize,
+}
+
+impl Default for CallbackBudgetConfig {
+    fn default() -> Self {
+        Self {
+            budget_us: DEFAULT_CALLBACK_BUDGET_US,
+            warn_interval_ms: 5_000,
+            offload_after: 0,
+            offload_workers: 1,
+            offload_queue_size: 10_000,
+        }
+    }
+}
+
+/// Callback timing counters
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallbackStats {
+    pub invocations: u64,
+    pub over_budget: u64,
+    pub max_us: u64,
+    pub total_us: u64,
+    /// Whether the callback runs on the offload pool
+    pub offloaded: bool,
+    /// Events dropped because the offload queue was full
+    pub dropped: u64,
+}
+
+impl CallbackStats {
+    pub fn avg_us(&self) -> f64 {
+        if self.invocations == 0 {
+            return 0.0;
+        }
+        self.total_us as f64 / self.invocations as f64
+    }
+}
+
+/// Measures every callback invocation against a time budget
+///
+/// Over-budget invocations are counted (`slow_callbacks_count` in the metrics) and
+/// logged at most every `warn_interval_ms`. With `offload_after` set, a callback that
+/// stays over budget is moved to a worker pool for good, so the ingest loop only pays
+/// for a queue push.
+pub struct CallbackBudget {
+    config: CallbackBudgetConfig,
+    metrics: Option<MetricsManager>,
+    invocations: AtomicU64,
+    over_budget: AtomicU64,
+    max_us: AtomicU64,
+    total_us: AtomicU64,
+    dropped: AtomicU64,
+    consecutive_slow: AtomicU32,
+    last_warn_us: AtomicI64,
+    offload: OnceLock<Sender<Box<dyn UnifiedEvent>>>,
+}
+
+impl CallbackBudget {
+    pub fn new(config: CallbackBudgetConfig) -> Self {
+        Self {
+            config,
+            metrics: None,
+            invocations: AtomicU64::new(0),
+            over_budget: AtomicU64::new(0),
+            max_us: AtomicU64::new(0),
+            total_us: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            consecutive_slow: AtomicU32::new(0),
+            last_warn_us: AtomicI64::new(i64::MIN),
+            offload: OnceLock::new(),
+        }
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsManager) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Wraps the callback so every invocation goes through the budget
+    pub fn wrap(self: &Arc<Self>, callback: Callback) -> Callback {
+        let budget = self.clone();
+        Arc::new(move |event: Box<dyn UnifiedEvent>| budget.invoke(&callback, event))
+    }
+
+    pub fn is_offloaded(&self) -> bool {
+        self.offload.get().is_some()
+    }
+
+    pub fn stats(&self) -> CallbackStats {
+        CallbackStats {
+            invocations: self.invocations.load(Ordering::Relaxed),
+            over_budget: self.over_budget.load(Ordering::Relaxed),
+            max_us: self.max_us.load(Ordering::Relaxed),
+            total_us: self.total_us.load(Ordering::Relaxed),
+            offloaded: self.is_offloaded(),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    fn invoke(self: &Arc<Self>, callback: &Callback, event: Box<dyn UnifiedEvent>) {
+        if let Some(sender) = self.offload.get() {
+            if let Err(TrySendError::Full(_)) = sender.try_send(event) {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                if let Some(metrics) = self.metrics.as_ref() {
+                    metrics.increment_dropped_events();
+                }
+            }
+            return;
+        }
+        if !self.measure(callback, event) {
+            self.consecutive_slow.store(0, Ordering::Relaxed);
+            return;
+        }
+        let consecutive = self.consecutive_slow.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.config.offload_after > 0 && consecutive >= self.config.offload_after {
+            self.start_offload(callback);
+        }
+    }
+
+    /// Runs the callback and records its duration, returns whether it was over budget
+    fn measure(&self, callback: &Callback, event: Box<dyn UnifiedEvent>) -> bool {
+        let start = Instant::now();
+        callback(event);
+        let elapsed_us = start.elapsed().as_micros() as u64;
+        self.invocations.fetch_add(1, Ordering::Relaxed);
+        self.total_us.fetch_add(elapsed_us, Ordering::Relaxed);
+        self.max_us.fetch_max(elapsed_us, Ordering::Relaxed);
+        if elapsed_us <= self.config.budget_us {
+            return false;
+        }
+        let over_budget = self.over_budget.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.increment_slow_callbacks();
+        }
+        let now = get_high_perf_clock();
+        let last = self.last_warn_us.load(Ordering::Relaxed);
+        if now.saturating_sub(last) >= self.config.warn_interval_ms as i64 * 1000
+            && self
+                .last_warn_us
+                .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            log::warn!(
+                "Callback took {}us, budget {}us ({} over budget so far)",
+                elapsed_us,
+                self.config.budget_us,
+                over_budget
+            );
+        }
+        true
+    }
+
+    fn start_offload(self: &Arc<Self>, callback: &Callback) {
+        self.offload.get_or_init(|| {
+            let (sender, receiver) =
+                bounded::<Box<dyn UnifiedEvent>>(self.config.offload_queue_size.max(1));
+            for index in 0..self.config.offload_workers.max(1) {
+                let receiver = receiver.clone();
+                let callback = callback.clone();
+                // 弱引用：预算对象释放后发送端关闭，工作线程随之退出
+                let budget: Weak<Self> = Arc::downgrade(self);
+                let spawned = std::thread::Builder::new()
+                    .name(format!("callback-offload-{}", index))
+                    .spawn(move || {
+                        for event in receiver {
+                            match budget.upgrade() {
+                                Some(budget) => {
+                                    budget.measure(&callback, event);
+                                }
+                                None => callback(event),
+                            }
+                        }
+                    });
+                if let Err(e) = spawned {
+                    log::error!("Failed to spawn callback offload worker: {}", e);
+                }
+            }
+            log::warn!(
+                "Callback over budget {} times in a row, moved to {} worker thread(s)",
+                self.config.offload_after,
+                self.config.offload_workers.max(1)
+            );
+            sender
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::streaming::event_parser::protocols::BlockMetaEvent;
+
+    #[test]
+    fn test_slow_callback_offloaded() {
+        let budget = Arc::new(CallbackBudget::new(CallbackBudgetConfig {
+            budget_us: 100,
+            offload_after: 2,
+            ..Default::default()
+        }));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let callback = budget.wrap(Arc::new(move |_event: Box<dyn UnifiedEvent>| {
+            std::thread::sleep(Duration::from_millis(2));
+            counter.fetch_add(1, Ordering::Relaxed);
+        }));
+
+        callback(Box::new(BlockMetaEvent::default()));
+        assert!(!budget.is_offloaded());
+        callback(Box::new(BlockMetaEvent::default()));
+        assert!(budget.is_offloaded());
+        callback(Box::new(BlockMetaEvent::default()));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while budget.stats().invocations < 3 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let stats = budget.stats();
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+        assert_eq!((stats.invocations, stats.over_budget), (3, 3));
+        assert!(stats.max_us >= 2_000);
+    }
+}
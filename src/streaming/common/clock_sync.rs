@@ -0,0 +1,316 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+use tokio::task::JoinHandle;
+
+use crate::common::AnyResult;
+use crate::streaming::event_parser::common::high_performance_clock::{system_clock, Clock};
+
+/// Seconds between the NTP epoch (1900) and the Unix epoch
+const NTP_UNIX_OFFSET_SECS: i64 = 2_208_988_800;
+const NTP_PACKET_LEN: usize = 48;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockSyncConfig {
+    /// NTP servers (`host:port`), the one with the smallest error bound wins each poll
+    pub servers: Vec<String>,
+    /// Requests per server and poll, the lowest round trip is kept
+    pub samples_per_poll: usize,
+    pub poll_interval_secs: u64,
+    pub timeout_ms: u64,
+    /// Assumed worst-case drift of the local clock, widens the error bound as the last
+    /// measurement ages
+    pub max_drift_ppm: u64,
+}
+
+impl Default for ClockSyncConfig {
+    fn default() -> Self {
+        Self {
+            servers: vec!["pool.ntp.org:123".into(), "time.google.com:123".into()],
+            samples_per_poll: 4,
+            poll_interval_secs: 64,
+            timeout_ms: 1_000,
+            max_drift_ppm: 50,
+        }
+    }
+}
+
+/// Measured offset of the local clock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockOffset {
+    /// UTC minus local time
+    pub offset_us: i64,
+    /// The true offset lies within `offset_us ± error_bound_us` at measurement time
+    pub error_bound_us: i64,
+    /// Round trip of the request the offset was taken from
+    pub round_trip_us: i64,
+    /// Local time of the measurement
+    pub measured_at_us: i64,
+    /// Server address, or the name passed to [`ClockSync::record`]
+    pub source: String,
+}
+
+/// A local timestamp converted to UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UtcTimestamp {
+    pub utc_us: i64,
+    /// The true time lies within `utc_us ± error_bound_us`
+    pub error_bound_us: i64,
+}
+
+/// Estimates the local clock's offset from UTC, so `recv_us` can be compared across
+/// machines and against external feeds
+///
+/// `recv_us` is taken from the local clock, which can be milliseconds off. The
+/// estimator queries NTP servers ([`spawn`](Self::spawn) or [`poll`](Self::poll)) and
+/// keeps the offset with the tightest error bound; [`to_utc`](Self::to_utc) then turns
+/// a local timestamp into UTC with an error bound. Hosts disciplined by PTP (ptp4l /
+/// phc2sys) can feed the offset reported by their daemon through
+/// [`record`](Self::record) instead.
+#[derive(Debug)]
+pub struct ClockSync {
+    config: ClockSyncConfig,
+    clock: Arc<dyn Clock>,
+    offset: RwLock<Option<ClockOffset>>,
+}
+
+impl Default for ClockSync {
+    fn default() -> Self {
+        Self::new(ClockSyncConfig::default())
+    }
+}
+
+impl ClockSync {
+    pub fn new(config: ClockSyncConfig) -> Self {
+        Self { config, clock: system_clock(), offset: RwLock::new(None) }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Last accepted offset
+    pub fn offset(&self) -> Option<ClockOffset> {
+        self.offset.read().clone()
+    }
+
+    /// Records an offset from an external source (e.g. a PTP daemon)
+    pub fn record(&self, source: impl Into<String>, offset_us: i64, error_bound_us: i64) {
+        self.accept(ClockOffset {
+            offset_us,
+            error_bound_us,
+            round_trip_us: 0,
+            measured_at_us: self.clock.now_micros(),
+            source: source.into(),
+        });
+    }
+
+    /// Converts a local timestamp (e.g. `recv_us`) to UTC; None before the first
+    /// measurement
+    pub fn to_utc(&self, local_us: i64) -> Option<UtcTimestamp> {
+        let offset = self.offset.read();
+        let offset = offset.as_ref()?;
+        Some(UtcTimestamp {
+            utc_us: local_us + offset.offset_us,
+            error_bound_us: offset.error_bound_us + self.drift_us(offset, local_us),
+        })
+    }
+
+    /// Queries every configured server once and accepts the best measurement
+    pub async fn poll(&self) -> AnyResult<ClockOffset> {
+        let mut best: Option<ClockOffset> = None;
+        let mut last_error = None;
+        for server in &self.config.servers {
+            match self.query_server(server).await {
+                Ok(offset) => {
+                    if best.as_ref().is_none_or(|b| offset.error_bound_us < b.error_bound_us) {
+                        best = Some(offset);
+                    }
+                }
+                Err(e) => {
+                    log::debug!("NTP query to {} failed: {}", server, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        let best =
+            best.ok_or_else(|| last_error.unwrap_or_else(|| anyhow!("no NTP servers configured")))?;
+        self.accept(best.clone());
+        Ok(best)
+    }
+
+    /// Polls every `poll_interval_secs` in the background
+    pub fn spawn(self: &Arc<Self>) -> JoinHandle<()> {
+        let this = self.clone();
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(this.config.poll_interval_secs.max(1)));
+            loop {
+                interval.tick().await;
+                match this.poll().await {
+                    Ok(offset) => log::debug!(
+                        "Clock offset {} us ± {} us via {}",
+                        offset.offset_us,
+                        offset.error_bound_us,
+                        offset.source
+                    ),
+                    Err(e) => log::warn!("Clock offset measurement failed: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Replaces the current offset unless it is still tighter after drift
+    fn accept(&self, candidate: ClockOffset) {
+        let mut current = self.offset.write();
+        let keep = current.as_ref().is_some_and(|current| {
+            current.error_bound_us + self.drift_us(current, candidate.measured_at_us)
+                < candidate.error_bound_us
+        });
+        if !keep {
+            *current = Some(candidate);
+        }
+    }
+
+    fn drift_us(&self, offset: &ClockOffset, at_us: i64) -> i64 {
+        let age_us = (at_us - offset.measured_at_us).unsigned_abs();
+        (age_us.saturating_mul(self.config.max_drift_ppm) / 1_000_000) as i64
+    }
+
+    async fn query_server(&self, server: &str) -> AnyResult<ClockOffset> {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket.connect(server).await?;
+        let timeout = Duration::from_millis(self.config.timeout_ms);
+        let mut best: Option<ClockOffset> = None;
+        for _ in 0..self.config.samples_per_poll.max(1) {
+            let t1 = self.clock.now_micros();
+            let request = ntp_request(t1);
+            socket.send(&request).await?;
+            let mut response = [0u8; NTP_PACKET_LEN];
+            let received = tokio::time::timeout(timeout, socket.recv(&mut response)).await;
+            let t4 = self.clock.now_micros();
+            match received {
+                Ok(Ok(NTP_PACKET_LEN)) => {}
+                Ok(Ok(len)) => bail!("short NTP response of {} bytes", len),
+                Ok(Err(e)) => return Err(e.into()),
+                Err(_) => continue,
+            }
+            // 超时请求的迟到响应与当前请求不匹配，跳过
+            let offset = match ntp_offset(&request, &response, t4, server) {
+                Ok(offset) => offset,
+                Err(e) => {
+                    log::debug!("Ignoring NTP response from {}: {}", server, e);
+                    continue;
+                }
+            };
+            if best.as_ref().is_none_or(|b| offset.round_trip_us < b.round_trip_us) {
+                best = Some(offset);
+            }
+        }
+        best.ok_or_else(|| anyhow!("NTP server {} did not answer", server))
+    }
+}
+
+/// Client request carrying `t1` as transmit timestamp, echoed back by the server
+fn ntp_request(t1_us: i64) -> [u8; NTP_PACKET_LEN] {
+    let mut packet = [0u8; NTP_PACKET_LEN];
+    // LI = 0, VN = 4, Mode = 3 (client)
+    packet[0] = 0x23;
+    packet[40..48].copy_from_slice(&to_ntp_timestamp(t1_us).to_be_bytes());
+    packet
+}
+
+/// Offset from a server response; `t4_us` is the local receive time
+fn ntp_offset(
+    request: &[u8; NTP_PACKET_LEN],
+    response: &[u8; NTP_PACKET_LEN],
+    t4_us: i64,
+    source: &str,
+) -> AnyResult<ClockOffset> {
+    if response[0] & 0x07 != 4 {
+        bail!("not an NTP server response");
+    }
+    if response[1] == 0 {
+        bail!("NTP kiss-of-death from {}", source);
+    }
+    if response[24..32] != request[40..48] {
+        bail!("NTP response does not match the request");
+    }
+    let read_u32 = |at: usize| u32::from_be_bytes(response[at..at + 4].try_into().unwrap());
+    let read_u64 = |at: usize| u64::from_be_bytes(response[at..at + 8].try_into().unwrap());
+    // 16.16 定点秒
+    let short_us = |value: u32| (value as i64 * 1_000_000) >> 16;
+    let root_delay_us = short_us(read_u32(4));
+    let root_dispersion_us = short_us(read_u32(8));
+
+    let t1 = from_ntp_timestamp(read_u64(24));
+    let t2 = from_ntp_timestamp(read_u64(32));
+    let t3 = from_ntp_timestamp(read_u64(40));
+    let round_trip_us = ((t4_us - t1) - (t3 - t2)).max(0);
+    Ok(ClockOffset {
+        offset_us: ((t2 - t1) + (t3 - t4_us)) / 2,
+        // 同步距离：半个往返 + 服务器到参考源的误差
+        error_bound_us: round_trip_us / 2 + root_delay_us / 2 + root_dispersion_us,
+        round_trip_us,
+        measured_at_us: t4_us,
+        source: source.to_string(),
+    })
+}
+
+fn to_ntp_timestamp(unix_us: i64) -> u64 {
+    let secs = unix_us.div_euclid(1_000_000) + NTP_UNIX_OFFSET_SECS;
+    let frac = ((unix_us.rem_euclid(1_000_000) as u64) << 32) / 1_000_000;
+    ((secs as u64) << 32) | frac
+}
+
+fn from_ntp_timestamp(timestamp: u64) -> i64 {
+    let secs = (timestamp >> 32) as i64 - NTP_UNIX_OFFSET_SECS;
+    let micros = ((timestamp & 0xffff_ffff) * 1_000_000) >> 32;
+    secs * 1_000_000 + micros as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::high_performance_clock::MockClock;
+
+    #[test]
+    fn test_ntp_offset_and_utc_conversion() {
+        // 本地时钟比 UTC 慢 2ms，单程 500us，服务器处理 100us
+        let t1 = 1_700_000_000_000_000;
+        let request = ntp_request(t1);
+        let mut response = [0u8; NTP_PACKET_LEN];
+        response[0] = 0x24;
+        response[1] = 1;
+        response[8..12].copy_from_slice(&(1u32 << 4).to_be_bytes());
+        response[24..32].copy_from_slice(&request[40..48]);
+        response[32..40].copy_from_slice(&to_ntp_timestamp(t1 + 2_500).to_be_bytes());
+        response[40..48].copy_from_slice(&to_ntp_timestamp(t1 + 2_600).to_be_bytes());
+        let t4 = t1 + 1_100;
+
+        let offset = ntp_offset(&request, &response, t4, "test").unwrap();
+        assert!((offset.offset_us - 2_000).abs() <= 1);
+        assert!((offset.round_trip_us - 1_000).abs() <= 1);
+        // 1/4096 秒的根离散度约 244us
+        assert!((offset.error_bound_us - 744).abs() <= 2);
+
+        let clock = Arc::new(MockClock::new(t4));
+        let sync = ClockSync::new(ClockSyncConfig { max_drift_ppm: 100, ..Default::default() })
+            .with_clock(clock);
+        assert_eq!(sync.to_utc(t4), None);
+        sync.accept(offset.clone());
+        let utc = sync.to_utc(t4 + 10_000_000).unwrap();
+        assert_eq!(utc.utc_us, t4 + 10_000_000 + offset.offset_us);
+        assert_eq!(utc.error_bound_us, offset.error_bound_us + 1_000);
+
+        // 更宽的测量不会覆盖仍然更紧的旧值
+        sync.record("ptp", 0, 50_000);
+        assert_eq!(sync.offset(), Some(offset));
+    }
+}
@@ -1,7 +1,24 @@
+use super::callback_budget::CallbackBudgetConfig;
+use super::conflation::AccountConflationConfig;
 use super::constants::*;
+use super::event_age::EventAgeGuard;
+use super::express::ExpressLane;
+use super::runtime::RuntimeConfig;
+use super::sampling::SamplingConfig;
+use super::slo::SloConfig;
+use crate::streaming::event_parser::common::filter_expr::EventFilterExpr;
+use crate::streaming::event_parser::common::program_labels::ProgramLabel;
+use crate::streaming::event_parser::common::validation::StrictAction;
+use crate::streaming::event_parser::config::ProtocolConfig;
+use crate::streaming::event_parser::core::canary::ParserCanary;
+use crate::streaming::event_parser::core::global_state::DEFAULT_MAX_SIGNATURES;
+use crate::streaming::grpc::compat::ProtoCompat;
+use crate::streaming::grpc::interceptor::RequestInterceptor;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// Backpressure handling strategy
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum BackpressureStrategy {
     /// Block and wait (default)
     Block,
@@ -16,7 +33,8 @@ impl Default for BackpressureStrategy {
 }
 
 /// Backpressure configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct BackpressureConfig {
     /// Channel size (default: 1000)
     pub permits: usize,
@@ -31,7 +49,8 @@ impl Default for BackpressureConfig {
 }
 
 /// Connection configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ConnectionConfig {
     /// Connection timeout in seconds (default: 10)
     pub connect_timeout: u64,
@@ -39,6 +58,18 @@ pub struct ConnectionConfig {
     pub request_timeout: u64,
     /// Maximum decoding message size in bytes (default: 10MB)
     pub max_decoding_message_size: usize,
+    /// Interval of client pings in seconds, 0 disables them (default: 10)
+    pub ping_interval: u64,
+    /// Reconnect when nothing was received for this many seconds, 0 disables the
+    /// check (default: 30)
+    pub stale_timeout: u64,
+    /// Extra metadata headers sent with every request, e.g. `api-key` (default: none)
+    pub metadata_headers: Vec<(String, String)>,
+    /// Request compatibility with older yellowstone-grpc servers (default: auto-detect)
+    pub proto_compat: ProtoCompat,
+    /// Hooks applied to every request after the headers (default: none, not serialized)
+    #[serde(skip)]
+    pub interceptors: Vec<RequestInterceptor>,
 }
 
 impl Default for ConnectionConfig {
@@ -47,12 +78,59 @@ impl Default for ConnectionConfig {
             connect_timeout: DEFAULT_CONNECT_TIMEOUT,
             request_timeout: DEFAULT_REQUEST_TIMEOUT,
             max_decoding_message_size: DEFAULT_MAX_DECODING_MESSAGE_SIZE,
+            ping_interval: DEFAULT_PING_INTERVAL,
+            stale_timeout: DEFAULT_STALE_STREAM_TIMEOUT,
+            metadata_headers: Vec::new(),
+            proto_compat: ProtoCompat::default(),
+            interceptors: Vec::new(),
         }
     }
 }
 
+impl ConnectionConfig {
+    /// Adds a metadata header sent with every request
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata_headers.push((key.into(), value.into()));
+        self
+    }
+
+    /// Adds an `authorization: Basic ...` header
+    pub fn with_basic_auth(self, username: &str, password: &str) -> Self {
+        use base64::Engine;
+        let encoded =
+            base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+        self.with_header("authorization", format!("Basic {}", encoded))
+    }
+
+    /// Adds an `authorization: Bearer ...` header
+    pub fn with_bearer_token(self, token: &str) -> Self {
+        self.with_header("authorization", format!("Bearer {}", token))
+    }
+
+    /// Adds a per-request interceptor
+    pub fn with_interceptor(mut self, interceptor: RequestInterceptor) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+}
+
+/// Caps for internal caches, to bound memory in long-running deployments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryConfig {
+    /// Maximum signatures kept in the global dev-address state (default: 1000)
+    pub max_signatures: usize,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self { max_signatures: DEFAULT_MAX_SIGNATURES }
+    }
+}
+
 /// Common client configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StreamClientConfig {
     /// Connection configuration
     pub connection: ConnectionConfig,
@@ -60,6 +138,60 @@ pub struct StreamClientConfig {
     pub backpressure: BackpressureConfig,
     /// Whether performance monitoring is enabled (default: false)
     pub enable_metrics: bool,
+    /// Thread pinning and runtime tuning (default: OS scheduling)
+    pub runtime: RuntimeConfig,
+    /// Internal cache caps
+    pub memory: MemoryConfig,
+    /// Latency SLO alarms (default: none)
+    pub slo: SloConfig,
+    /// Hold each slot's transactions until its block meta has been delivered, when block
+    /// meta is subscribed (default: false, adds up to a block time of latency)
+    pub block_meta_first: bool,
+    /// Subscribe to full blocks instead of single transactions; a block's meta and its
+    /// transactions then arrive together and in order (default: false)
+    pub block_subscription: bool,
+    /// Subscribe to entry updates and annotate events with their `entry_index`
+    /// (default: false)
+    pub subscribe_entries: bool,
+    /// Expression events must match before reaching the callback, e.g.
+    /// `protocol == "RaydiumCpmm" && amount_in > 1e9` (default: none)
+    pub event_filter: Option<EventFilterExpr>,
+    /// Strict mode: drop or flag swaps with zero amounts / default mints and events built
+    /// from padded accounts, checked before `event_filter` (default: off)
+    pub strict_validation: Option<StrictAction>,
+    /// Conflate queued account updates per pubkey (latest wins) and deliver them every
+    /// `flush_interval_ms`, for consumers that only need current state (default: off)
+    pub account_conflation: Option<AccountConflationConfig>,
+    /// Express lane: events of its pools / mints bypass validation, filtering and the
+    /// regular callback and go to its dedicated channel (default: none, not serialized)
+    #[serde(skip)]
+    pub express_lane: Option<Arc<ExpressLane>>,
+    /// Shadow-parse sampled gRPC transactions with a candidate parser and report how its
+    /// output diverges, the live output is unaffected (default: none, not serialized)
+    #[serde(skip)]
+    pub parser_canary: Option<Arc<ParserCanary>>,
+    /// Time budget of each callback invocation, with slow-callback warnings and optional
+    /// offloading to worker threads (default: none, callbacks are not timed)
+    pub callback_budget: Option<CallbackBudgetConfig>,
+    /// Deterministically process only a fraction of the transactions, e.g. for staging
+    /// deployments on mainnet traffic (default: none, everything is processed)
+    pub sampling: Option<SamplingConfig>,
+    /// Drop or flag events older than the budget (receive → callback) when they reach the
+    /// callback, e.g. behind a backlog (default: none)
+    pub max_event_age: Option<EventAgeGuard>,
+    /// Extra program / bot wallet labels on top of the built-in registry, used for
+    /// `routed_via` and `submitted_by_known_bot` (default: none)
+    pub program_labels: Vec<ProgramLabel>,
+    /// Split account filters that only select a known protocol's program (`owner`) into
+    /// one `datasize` filter per parsed account kind, so positions and other unparsed
+    /// accounts are dropped by the server. Accounts of other sizes are no longer
+    /// delivered, and the split filters take consecutive `account_N` names, shifting
+    /// the names of later filters (default: false)
+    pub account_size_filters: bool,
+    /// Protocol configs applied to the client's parser: instructions with an
+    /// `account_index_map` patch the account order of built-in parsers, the others add
+    /// dynamic events (default: none)
+    pub protocol_configs: Vec<ProtocolConfig>,
 }
 
 impl Default for StreamClientConfig {
@@ -68,6 +200,23 @@ impl Default for StreamClientConfig {
             connection: ConnectionConfig::default(),
             backpressure: BackpressureConfig::default(),
             enable_metrics: false,
+            runtime: RuntimeConfig::default(),
+            memory: MemoryConfig::default(),
+            slo: SloConfig::default(),
+            block_meta_first: false,
+            block_subscription: false,
+            subscribe_entries: false,
+            event_filter: None,
+            strict_validation: None,
+            account_conflation: None,
+            express_lane: None,
+            parser_canary: None,
+            callback_budget: None,
+            sampling: None,
+            max_event_age: None,
+            program_labels: Vec::new(),
+            account_size_filters: false,
+            protocol_configs: Vec::new(),
         }
     }
 }
@@ -89,6 +238,23 @@ impl StreamClientConfig {
                 strategy: BackpressureStrategy::Drop,
             },
             enable_metrics: false,
+            runtime: RuntimeConfig::default(),
+            memory: MemoryConfig::default(),
+            slo: SloConfig::default(),
+            block_meta_first: false,
+            block_subscription: false,
+            subscribe_entries: false,
+            event_filter: None,
+            strict_validation: None,
+            account_conflation: None,
+            express_lane: None,
+            parser_canary: None,
+            callback_budget: None,
+            sampling: None,
+            max_event_age: None,
+            program_labels: Vec::new(),
+            account_size_filters: false,
+            protocol_configs: Vec::new(),
         }
     }
 
@@ -106,7 +272,63 @@ impl StreamClientConfig {
             connection: ConnectionConfig::default(),
             backpressure: BackpressureConfig { permits: 4000, strategy: BackpressureStrategy::Block },
             enable_metrics: false,
+            runtime: RuntimeConfig::default(),
+            memory: MemoryConfig::default(),
+            slo: SloConfig::default(),
+            block_meta_first: false,
+            block_subscription: false,
+            subscribe_entries: false,
+            event_filter: None,
+            strict_validation: None,
+            account_conflation: None,
+            express_lane: None,
+            parser_canary: None,
+            callback_budget: None,
+            sampling: None,
+            max_event_age: None,
+            program_labels: Vec::new(),
+            account_size_filters: false,
+            protocol_configs: Vec::new(),
         }
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::common::LatencySlo;
+
+    #[test]
+    fn test_client_config_round_trip() {
+        let mut config = StreamClientConfig::high_throughput();
+        config.connection = ConnectionConfig::default().with_header("api-key", "secret");
+        config.slo.slos.push(LatencySlo::new("all", 5_000));
+        config.block_meta_first = true;
+        config.event_filter = Some("slot > 100 && !is_bot".parse().unwrap());
+        config.strict_validation = Some(StrictAction::Flag);
+
+        let json = serde_json::to_string(&config).unwrap();
+        let decoded: StreamClientConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.backpressure.permits, 20000);
+        assert!(matches!(decoded.backpressure.strategy, BackpressureStrategy::Drop));
+        assert_eq!(decoded.connection.metadata_headers, config.connection.metadata_headers);
+        assert_eq!(decoded.slo.slos[0].name, "all");
+        assert!(decoded.block_meta_first);
+        assert_eq!(decoded.event_filter, config.event_filter);
+        assert_eq!(decoded.strict_validation, Some(StrictAction::Flag));
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+    }
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        // 已移除的 account_cache_max_accounts 仍可出现在旧配置中
+        let config: StreamClientConfig = serde_json::from_str(
+            r#"{"enable_metrics": true, "memory": {"max_signatures": 10, "account_cache_max_accounts": 256}}"#,
+        )
+        .unwrap();
+        assert!(config.enable_metrics);
+        assert_eq!(config.memory.max_signatures, 10);
+        assert_eq!(config.connection.connect_timeout, DEFAULT_CONNECT_TIMEOUT);
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::streaming::grpc::AccountPretty;
+
+/// Default flush cadence of conflated account updates
+pub const DEFAULT_CONFLATION_FLUSH_INTERVAL_MS: u64 = 100;
+/// Default number of distinct pending accounts that forces an early flush
+pub const DEFAULT_CONFLATION_MAX_ACCOUNTS: usize = 10_000;
+
+/// Latest-wins conflation of account updates, for consumers that only need current state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountConflationConfig {
+    /// How often pending updates are parsed and delivered
+    pub flush_interval_ms: u64,
+    /// Flush early once this many distinct accounts are pending
+    pub max_accounts: usize,
+}
+
+impl Default for AccountConflationConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval_ms: DEFAULT_CONFLATION_FLUSH_INTERVAL_MS,
+            max_accounts: DEFAULT_CONFLATION_MAX_ACCOUNTS,
+        }
+    }
+}
+
+/// Buffers account updates by pubkey, keeping only the newest one until the next flush
+///
+/// Updates older (by slot) than the pending one are discarded, so out-of-order delivery
+/// never regresses an account.
+#[derive(Debug, Default)]
+pub struct AccountConflator {
+    config: AccountConflationConfig,
+    pending: Mutex<HashMap<Pubkey, AccountPretty>>,
+    conflated: AtomicU64,
+    pub(crate) flusher_running: AtomicBool,
+}
+
+impl AccountConflator {
+    pub fn new(config: AccountConflationConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    pub fn flush_interval(&self) -> Duration {
+        Duration::from_millis(self.config.flush_interval_ms.max(1))
+    }
+
+    /// Buffers the update, returns true when the buffer is full and should be flushed now
+    pub fn push(&self, account: AccountPretty) -> bool {
+        let mut pending = self.pending.lock();
+        match pending.get_mut(&account.pubkey) {
+            Some(current) => {
+                if account.slot >= current.slot {
+                    *current = account;
+                }
+                self.conflated.fetch_add(1, Ordering::Relaxed);
+            }
+            None => {
+                pending.insert(account.pubkey, account);
+            }
+        }
+        pending.len() >= self.config.max_accounts
+    }
+
+    /// Takes the pending updates in slot order
+    pub fn drain(&self) -> Vec<AccountPretty> {
+        let mut accounts: Vec<_> =
+            std::mem::take(&mut *self.pending.lock()).into_values().collect();
+        accounts.sort_unstable_by_key(|account| (account.slot, account.recv_us));
+        accounts
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.lock().is_empty()
+    }
+
+    /// Updates superseded by (or older than) another update of the same account
+    pub fn conflated(&self) -> u64 {
+        self.conflated.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(pubkey: Pubkey, slot: u64, lamports: u64) -> AccountPretty {
+        AccountPretty { pubkey, slot, lamports, ..Default::default() }
+    }
+
+    #[test]
+    fn test_account_conflation_latest_wins() {
+        let conflator = AccountConflator::new(AccountConflationConfig {
+            max_accounts: 2,
+            ..Default::default()
+        });
+        let (pool, vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert!(!conflator.push(update(pool, 10, 1)));
+        assert!(!conflator.push(update(pool, 12, 2)));
+        assert!(!conflator.push(update(pool, 11, 3)));
+        assert!(conflator.push(update(vault, 9, 4)));
+        assert_eq!(conflator.conflated(), 2);
+
+        let drained = conflator.drain();
+        assert_eq!(
+            drained.iter().map(|a| (a.pubkey, a.lamports)).collect::<Vec<_>>(),
+            vec![(vault, 4), (pool, 2)]
+        );
+        assert!(conflator.is_empty());
+    }
+}
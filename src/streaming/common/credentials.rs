@@ -0,0 +1,280 @@
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use parking_lot::Mutex;
+use tonic::metadata::AsciiMetadataValue;
+use tonic::Request;
+
+use crate::common::AnyResult;
+
+/// Source of an auth token (gRPC x-token, sink credentials, ...)
+///
+/// `token` is called every time a connection is established, so providers backed
+/// by rotating secrets are picked up on the next reconnect without restarting.
+///
+/// Built in are static, environment and file tokens. Cloud secret managers (AWS
+/// Secrets Manager, GCP Secret Manager) are not bundled; implement this trait with
+/// the vendor SDK, or mount the secret as a file and use [`FileCredentialProvider`].
+pub trait CredentialProvider: Debug + Send + Sync {
+    /// Returns the current token, `None` if the endpoint needs no auth
+    fn token(&self) -> AnyResult<Option<String>>;
+}
+
+/// Shared credential provider handle
+pub type SharedCredentialProvider = Arc<dyn CredentialProvider>;
+
+/// Sets the provider's current token as the `x-token` header of `request`
+///
+/// Used by connections without an interceptor (ShredStream); call it per subscribe so
+/// a rotated token is picked up on the next reconnect.
+pub fn authorize_request<T>(
+    request: &mut Request<T>,
+    credentials: &dyn CredentialProvider,
+) -> AnyResult<()> {
+    if let Some(token) = credentials.token()? {
+        let value = AsciiMetadataValue::try_from(token.as_str())
+            .map_err(|e| anyhow!("invalid x-token value: {}", e))?;
+        request.metadata_mut().insert("x-token", value);
+    }
+    Ok(())
+}
+
+/// Fixed token, used for the plain `x_token: Option<String>` constructors
+#[derive(Clone, Default)]
+pub struct StaticCredentialProvider {
+    token: Option<String>,
+}
+
+impl StaticCredentialProvider {
+    pub fn new(token: Option<String>) -> Self {
+        Self { token }
+    }
+}
+
+impl Debug for StaticCredentialProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaticCredentialProvider")
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl CredentialProvider for StaticCredentialProvider {
+    fn token(&self) -> AnyResult<Option<String>> {
+        Ok(self.token.clone())
+    }
+}
+
+/// Reads the token from an environment variable on every call
+#[derive(Debug, Clone)]
+pub struct EnvCredentialProvider {
+    var: String,
+    required: bool,
+}
+
+impl EnvCredentialProvider {
+    /// Missing variable is an error
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into(), required: true }
+    }
+
+    /// Missing variable means no auth
+    pub fn optional(var: impl Into<String>) -> Self {
+        Self { var: var.into(), required: false }
+    }
+}
+
+impl CredentialProvider for EnvCredentialProvider {
+    fn token(&self) -> AnyResult<Option<String>> {
+        match std::env::var(&self.var) {
+            Ok(value) if !value.trim().is_empty() => Ok(Some(value.trim().to_string())),
+            _ if self.required => Err(anyhow!("credential env var {} is not set", self.var)),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Reads the token from a file (e.g. a mounted Kubernetes/Vault secret) on every call
+#[derive(Debug, Clone)]
+pub struct FileCredentialProvider {
+    path: PathBuf,
+}
+
+impl FileCredentialProvider {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CredentialProvider for FileCredentialProvider {
+    fn token(&self) -> AnyResult<Option<String>> {
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("failed to read credential file {}", self.path.display()))?;
+        let token = content.trim();
+        Ok(if token.is_empty() { None } else { Some(token.to_string()) })
+    }
+}
+
+/// Caches another provider's token and refreshes it after `ttl`
+///
+/// Wrap custom providers that call a secret store with this so every reconnect
+/// doesn't hit the store, while rotated tokens still take effect within `ttl`.
+///
+/// The inner provider is called without holding the cache lock, and only one caller
+/// refreshes an expired token at a time; the others keep using the expired token
+/// meanwhile instead of waiting on a slow store.
+#[derive(Debug)]
+pub struct RefreshingCredentialProvider {
+    inner: SharedCredentialProvider,
+    ttl: Duration,
+    cached: Mutex<Option<(Instant, Option<String>)>>,
+    refreshing: AtomicBool,
+}
+
+impl RefreshingCredentialProvider {
+    pub fn new(inner: SharedCredentialProvider, ttl: Duration) -> Self {
+        Self { inner, ttl, cached: Mutex::new(None), refreshing: AtomicBool::new(false) }
+    }
+
+    /// Drops the cached token so the next call goes to the inner provider
+    pub fn invalidate(&self) {
+        *self.cached.lock() = None;
+    }
+}
+
+impl CredentialProvider for RefreshingCredentialProvider {
+    fn token(&self) -> AnyResult<Option<String>> {
+        let stale = match self.cached.lock().as_ref() {
+            Some((fetched_at, token)) if fetched_at.elapsed() < self.ttl => {
+                return Ok(token.clone())
+            }
+            Some((_, token)) => Some(token.clone()),
+            None => None,
+        };
+        // 已有其他调用方在刷新时先用过期 token；没有缓存时只能等待
+        let claimed = !self.refreshing.swap(true, Ordering::AcqRel);
+        if let (false, Some(token)) = (claimed, stale) {
+            return Ok(token);
+        }
+        let result = self.inner.token();
+        if claimed {
+            self.refreshing.store(false, Ordering::Release);
+        }
+        let token = result?;
+        *self.cached.lock() = Some((Instant::now(), token.clone()));
+        Ok(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::mpsc;
+
+    use super::*;
+
+    /// Returns `token-<n>` on the n-th call, fails while `failing` is set; while `slow`
+    /// is set a call signals `entered` and waits for `release`
+    #[derive(Debug, Default)]
+    struct RotatingProvider {
+        calls: AtomicUsize,
+        failing: AtomicBool,
+        slow: AtomicBool,
+        gate: Mutex<Option<(mpsc::Sender<()>, mpsc::Receiver<()>)>>,
+    }
+
+    impl CredentialProvider for RotatingProvider {
+        fn token(&self) -> AnyResult<Option<String>> {
+            if self.failing.load(Ordering::Relaxed) {
+                return Err(anyhow!("secret store unavailable"));
+            }
+            if self.slow.load(Ordering::Relaxed) {
+                let gate = self.gate.lock();
+                let (entered, release) = gate.as_ref().unwrap();
+                entered.send(()).unwrap();
+                release.recv().unwrap();
+            }
+            let n = self.calls.fetch_add(1, Ordering::Relaxed);
+            Ok(Some(format!("token-{}", n)))
+        }
+    }
+
+    fn x_token(credentials: &dyn CredentialProvider) -> AnyResult<Option<String>> {
+        let mut request = Request::new(());
+        authorize_request(&mut request, credentials)?;
+        Ok(request.metadata().get("x-token").map(|v| v.to_str().unwrap().to_string()))
+    }
+
+    #[test]
+    fn test_rotated_token_used_on_next_request() {
+        let inner = Arc::new(RotatingProvider::default());
+        let provider = RefreshingCredentialProvider::new(inner.clone(), Duration::from_secs(60));
+
+        assert_eq!(x_token(&provider).unwrap().as_deref(), Some("token-0"));
+        // 未过期时使用缓存
+        assert_eq!(x_token(&provider).unwrap().as_deref(), Some("token-0"));
+        assert_eq!(inner.calls.load(Ordering::Relaxed), 1);
+
+        provider.invalidate();
+        assert_eq!(x_token(&provider).unwrap().as_deref(), Some("token-1"));
+
+        let expiring = RefreshingCredentialProvider::new(inner.clone(), Duration::ZERO);
+        assert_eq!(x_token(&expiring).unwrap().as_deref(), Some("token-2"));
+        assert_eq!(x_token(&expiring).unwrap().as_deref(), Some("token-3"));
+
+        assert_eq!(x_token(&StaticCredentialProvider::default()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_refresh_failure_is_not_cached() {
+        let inner = Arc::new(RotatingProvider::default());
+        let provider = RefreshingCredentialProvider::new(inner.clone(), Duration::ZERO);
+
+        inner.failing.store(true, Ordering::Relaxed);
+        let err = x_token(&provider).unwrap_err();
+        assert!(err.to_string().contains("secret store unavailable"));
+
+        // 恢复后下一次重连即可拿到新 token
+        inner.failing.store(false, Ordering::Relaxed);
+        assert_eq!(x_token(&provider).unwrap().as_deref(), Some("token-0"));
+
+        let missing = EnvCredentialProvider::new("SOLANA_STREAMER_TEST_MISSING_TOKEN");
+        assert!(x_token(&missing).is_err());
+        let optional = EnvCredentialProvider::optional("SOLANA_STREAMER_TEST_MISSING_TOKEN");
+        assert_eq!(x_token(&optional).unwrap(), None);
+    }
+
+    #[test]
+    fn test_slow_refresh_does_not_block_other_callers() {
+        let inner = Arc::new(RotatingProvider::default());
+        let provider = Arc::new(RefreshingCredentialProvider::new(inner.clone(), Duration::ZERO));
+        assert_eq!(provider.token().unwrap().as_deref(), Some("token-0"));
+
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel();
+        *inner.gate.lock() = Some((entered_tx, release_rx));
+        inner.slow.store(true, Ordering::Relaxed);
+        let refresher = {
+            let provider = provider.clone();
+            std::thread::spawn(move || provider.token().unwrap())
+        };
+        entered_rx.recv().unwrap();
+
+        // 刷新进行中，其他调用方立即拿到过期 token
+        let (done_tx, done_rx) = mpsc::channel();
+        let waiter = {
+            let provider = provider.clone();
+            std::thread::spawn(move || done_tx.send(provider.token().unwrap()).unwrap())
+        };
+        let stale = done_rx.recv_timeout(Duration::from_secs(5)).expect("caller was blocked");
+        assert_eq!(stale.as_deref(), Some("token-0"));
+        waiter.join().unwrap();
+
+        release_tx.send(()).unwrap();
+        assert_eq!(refresher.join().unwrap().as_deref(), Some("token-1"));
+    }
+}
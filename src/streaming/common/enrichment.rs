@@ -0,0 +1,208 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+use crate::common::SolanaRpcClient;
+use crate::streaming::event_parser::core::event_parser::EventParser;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Accounts per `getMultipleAccounts` call allowed by the RPC
+pub const MAX_ENRICHMENT_BATCH: usize = 100;
+
+/// Enrichment scheduler settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnrichmentConfig {
+    /// Maximum RPC calls per second (default: 5)
+    pub max_qps: u32,
+    /// Accounts per RPC call, capped at [`MAX_ENRICHMENT_BATCH`] (default: 100)
+    pub max_batch: usize,
+    /// How long a partial batch waits for more requests, in milliseconds (default: 20)
+    pub batch_delay_ms: u64,
+    /// Queued accounts beyond this are rejected (default: 10,000)
+    pub max_pending: usize,
+}
+
+impl Default for EnrichmentConfig {
+    fn default() -> Self {
+        Self {
+            max_qps: 5,
+            max_batch: MAX_ENRICHMENT_BATCH,
+            batch_delay_ms: 20,
+            max_pending: 10_000,
+        }
+    }
+}
+
+/// Result of one enriched account
+#[derive(Debug)]
+pub struct Enrichment {
+    pub pubkey: Pubkey,
+    /// Slot the account was read at
+    pub slot: u64,
+    /// None when the account does not exist
+    pub account: Option<Account>,
+    /// The account parsed with the scheduler's parser (e.g. `TokenInfoEvent` for mints)
+    pub event: Option<Box<dyn UnifiedEvent>>,
+}
+
+#[derive(Default)]
+struct EnrichmentQueue {
+    queue: VecDeque<Pubkey>,
+    // 排队中和请求中的账户，重复请求会被合并
+    pending: HashSet<Pubkey>,
+}
+
+impl EnrichmentQueue {
+    fn push(&mut self, pubkey: Pubkey, max_pending: usize) -> bool {
+        if self.pending.len() >= max_pending || !self.pending.insert(pubkey) {
+            return false;
+        }
+        self.queue.push_back(pubkey);
+        true
+    }
+
+    fn take_batch(&mut self, max_batch: usize) -> Vec<Pubkey> {
+        let n = self.queue.len().min(max_batch);
+        self.queue.drain(..n).collect()
+    }
+
+    fn complete(&mut self, batch: &[Pubkey]) {
+        for pubkey in batch {
+            self.pending.remove(pubkey);
+        }
+    }
+}
+
+struct SchedulerState {
+    config: EnrichmentConfig,
+    queue: Mutex<EnrichmentQueue>,
+    notify: Notify,
+    shutdown: AtomicBool,
+}
+
+/// Background RPC enrichment off the event hot path
+///
+/// Callers only enqueue accounts (mints, token accounts, pools); a background task
+/// coalesces duplicates, batches them into `getMultipleAccounts` calls under a strict
+/// QPS limit, parses each account and hands the result to the completion callback,
+/// which can patch caches such as [`MintInfoCache`](super::MintInfoCache) or forward
+/// the parsed account as a follow-up event.
+#[derive(Clone)]
+pub struct EnrichmentScheduler {
+    state: Arc<SchedulerState>,
+}
+
+impl EnrichmentScheduler {
+    /// Starts the background task on the current tokio runtime
+    pub fn spawn<F>(
+        rpc: Arc<SolanaRpcClient>,
+        parser: Arc<EventParser>,
+        config: EnrichmentConfig,
+        callback: F,
+    ) -> Self
+    where
+        F: Fn(Enrichment) + Send + Sync + 'static,
+    {
+        let state = Arc::new(SchedulerState {
+            config,
+            queue: Mutex::new(EnrichmentQueue::default()),
+            notify: Notify::new(),
+            shutdown: AtomicBool::new(false),
+        });
+        tokio::spawn(Self::run(state.clone(), rpc, parser, callback));
+        Self { state }
+    }
+
+    /// Queues an account, false if it is already pending or the queue is full
+    pub fn request(&self, pubkey: Pubkey) -> bool {
+        let queued = self.state.queue.lock().push(pubkey, self.state.config.max_pending);
+        if queued {
+            self.state.notify.notify_one();
+        }
+        queued
+    }
+
+    /// Number of queued or in-flight accounts
+    pub fn pending(&self) -> usize {
+        self.state.queue.lock().pending.len()
+    }
+
+    pub fn shutdown(&self) {
+        self.state.shutdown.store(true, Ordering::Relaxed);
+        self.state.notify.notify_one();
+    }
+
+    async fn run<F>(
+        state: Arc<SchedulerState>,
+        rpc: Arc<SolanaRpcClient>,
+        parser: Arc<EventParser>,
+        callback: F,
+    ) where
+        F: Fn(Enrichment) + Send + Sync + 'static,
+    {
+        let max_batch = state.config.max_batch.clamp(1, MAX_ENRICHMENT_BATCH);
+        let min_interval = Duration::from_secs(1) / state.config.max_qps.max(1);
+        let mut next_call = Instant::now();
+
+        while !state.shutdown.load(Ordering::Relaxed) {
+            let queued = state.queue.lock().queue.len();
+            if queued == 0 {
+                state.notify.notified().await;
+                continue;
+            }
+            if queued < max_batch {
+                tokio::time::sleep(Duration::from_millis(state.config.batch_delay_ms)).await;
+            }
+            tokio::time::sleep_until(next_call).await;
+            next_call = Instant::now() + min_interval;
+
+            let batch = state.queue.lock().take_batch(max_batch);
+            match rpc.get_multiple_accounts_with_commitment(&batch, rpc.commitment()).await {
+                Ok(response) => {
+                    let slot = response.context.slot;
+                    for (pubkey, account) in batch.iter().zip(response.value) {
+                        let event = account
+                            .as_ref()
+                            .and_then(|account| parser.parse_account_data(*pubkey, account, slot));
+                        callback(Enrichment { pubkey: *pubkey, slot, account, event });
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Enrichment of {} accounts failed: {}", batch.len(), e);
+                }
+            }
+            state.queue.lock().complete(&batch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_coalesces_requests() {
+        let mut queue = EnrichmentQueue::default();
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        assert!(queue.push(a, 2));
+        assert!(!queue.push(a, 2));
+        assert!(queue.push(b, 2));
+        assert!(!queue.push(Pubkey::new_unique(), 2));
+
+        let batch = queue.take_batch(1);
+        assert_eq!(batch, vec![a]);
+        // 请求中的账户仍会被合并
+        assert!(!queue.push(a, 3));
+        queue.complete(&batch);
+        assert!(queue.push(a, 3));
+    }
+}
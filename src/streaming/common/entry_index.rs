@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+
+use parking_lot::RwLock;
+
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Number of slots whose entries are kept
+pub const DEFAULT_MAX_ENTRY_SLOTS: usize = 256;
+
+/// Maps transactions to the entry they were executed in
+///
+/// Entries are the batches a leader records into a slot, so the entry index orders
+/// transactions more coarsely but more faithfully than `transaction_index` alone.
+/// Events processed before their entry arrived keep `entry_index = None`, unless
+/// `block_meta_first` or a block subscription holds them until the slot is complete.
+pub struct EntryIndexTracker {
+    max_slots: usize,
+    // slot -> (起始交易序号, 结束交易序号（不含）, entry 序号)
+    entries: RwLock<BTreeMap<u64, Vec<(u64, u64, u64)>>>,
+}
+
+impl Default for EntryIndexTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_ENTRY_SLOTS)
+    }
+}
+
+impl EntryIndexTracker {
+    pub fn new(max_slots: usize) -> Self {
+        Self { max_slots: max_slots.max(1), entries: RwLock::new(BTreeMap::new()) }
+    }
+
+    /// Records an entry update; tick entries without transactions are skipped
+    pub fn record(
+        &self,
+        slot: u64,
+        index: u64,
+        starting_transaction_index: u64,
+        executed_transaction_count: u64,
+    ) {
+        if executed_transaction_count == 0 {
+            return;
+        }
+        let end = starting_transaction_index + executed_transaction_count;
+        let mut entries = self.entries.write();
+        entries.entry(slot).or_default().push((starting_transaction_index, end, index));
+        while entries.len() > self.max_slots {
+            entries.pop_first();
+        }
+    }
+
+    /// Entry index of the transaction at `transaction_index` within `slot`
+    pub fn entry_index(&self, slot: u64, transaction_index: u64) -> Option<u64> {
+        self.entries.read().get(&slot).and_then(|ranges| {
+            ranges
+                .iter()
+                .find(|(start, end, _)| (*start..*end).contains(&transaction_index))
+                .map(|(_, _, index)| *index)
+        })
+    }
+
+    /// Fills in the event's entry index when it is known
+    ///
+    /// Returns true if the entry index was set.
+    pub fn apply(&self, event: &mut dyn UnifiedEvent) -> bool {
+        let metadata = event.metadata_mut();
+        if metadata.entry_index.is_some() {
+            return false;
+        }
+        let Some(transaction_index) = metadata.transaction_index else {
+            return false;
+        };
+        metadata.entry_index = self.entry_index(metadata.slot, transaction_index);
+        metadata.entry_index.is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.read().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_index_lookup() {
+        let tracker = EntryIndexTracker::new(2);
+        tracker.record(10, 0, 0, 0);
+        tracker.record(10, 1, 0, 3);
+        tracker.record(10, 2, 3, 2);
+        assert_eq!(tracker.entry_index(10, 0), Some(1));
+        assert_eq!(tracker.entry_index(10, 2), Some(1));
+        assert_eq!(tracker.entry_index(10, 4), Some(2));
+        assert_eq!(tracker.entry_index(10, 5), None);
+
+        tracker.record(11, 0, 0, 1);
+        tracker.record(12, 0, 0, 1);
+        assert_eq!(tracker.len(), 2);
+        assert_eq!(tracker.entry_index(10, 0), None);
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// What the age guard does with an event older than its budget
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleAction {
+    /// Do not deliver the event
+    #[default]
+    Drop,
+    /// Deliver it with `metadata.stale` set
+    Flag,
+}
+
+/// Maximum time between receiving an event and handing it to the callback
+///
+/// Behind a backlog (slow callback, burst after a reconnect) events reach the callback
+/// long after they were received; acting on them is acting on a past market. Events older
+/// than `max_age_us` are dropped or flagged, and counted in `stale_events_count` of the
+/// metrics either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventAgeGuard {
+    pub max_age_us: i64,
+    pub action: StaleAction,
+}
+
+impl Default for EventAgeGuard {
+    fn default() -> Self {
+        Self { max_age_us: 50_000, action: StaleAction::Drop }
+    }
+}
+
+impl EventAgeGuard {
+    pub fn new(max_age_us: i64, action: StaleAction) -> Self {
+        Self { max_age_us, action }
+    }
+
+    /// Whether the event is past its budget at `now_us`; events without a receive time
+    /// are never stale
+    pub fn is_stale(&self, event: &dyn UnifiedEvent, now_us: i64) -> bool {
+        let recv_us = event.recv_us();
+        recv_us > 0 && now_us - recv_us > self.max_age_us
+    }
+
+    /// Applies the action to a stale event, returns false if it must be dropped
+    pub fn apply(&self, event: &mut dyn UnifiedEvent) -> bool {
+        match self.action {
+            StaleAction::Drop => false,
+            StaleAction::Flag => {
+                event.metadata_mut().stale = true;
+                true
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::EventMetadata;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    #[test]
+    fn test_event_age_guard() {
+        let mut event = RaydiumCpmmSwapEvent {
+            metadata: EventMetadata { recv_us: 1_000_000, ..Default::default() },
+            ..Default::default()
+        };
+        let guard = EventAgeGuard::new(10_000, StaleAction::Flag);
+        assert!(!guard.is_stale(&event, 1_010_000));
+        assert!(guard.is_stale(&event, 1_010_001));
+        assert!(guard.apply(&mut event));
+        assert!(event.metadata.stale);
+        assert!(!EventAgeGuard::new(10_000, StaleAction::Drop).apply(&mut event));
+    }
+}
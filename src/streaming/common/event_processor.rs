@@ -1,22 +1,36 @@
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use crossbeam_queue::SegQueue;
-use solana_sdk::pubkey::Pubkey;
+use dashmap::DashMap;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
 
 use crate::common::AnyResult;
-use crate::streaming::common::BackpressureStrategy;
+use crate::streaming::common::{
+    AccountConflator, BackpressureStrategy, BlockTimeEstimator, CallbackBudget, EntryIndexTracker,
+    EventAgeGuard, ExpressLane, MemoryReport, SloMonitor, DEFAULT_SLO_TICK_MS,
+};
 use crate::streaming::common::{
     MetricsEventType, MetricsManager, StreamClientConfig as ClientConfig,
 };
 use crate::streaming::event_parser::common::filter::EventTypeFilter;
+use crate::streaming::event_parser::common::filter_expr::EventFilterExpr;
+use crate::streaming::event_parser::common::high_performance_clock::get_high_perf_clock;
+use crate::streaming::event_parser::common::program_labels::ProgramRegistry;
+use crate::streaming::event_parser::common::validation::StrictAction;
+use crate::streaming::event_parser::common::BlockTimeSource;
 use crate::streaming::event_parser::core::account_event_parser::AccountEventParser;
 use crate::streaming::event_parser::core::common_event_parser::CommonEventParser;
 
 use crate::streaming::event_parser::core::event_parser::EventParser;
+use crate::streaming::event_parser::core::global_state::GlobalState;
+use crate::streaming::event_parser::core::protocol_switches::ProtocolSwitches;
 use crate::streaming::event_parser::{core::traits::UnifiedEvent, Protocol};
 use crate::streaming::grpc::{BackpressureConfig, EventPretty};
 use crate::streaming::shred::TransactionWithSlot;
+use crate::streaming::store::DebugRecorder;
 use once_cell::sync::OnceCell;
 
 pub enum EventSource {
@@ -24,6 +38,111 @@ pub enum EventSource {
     Shred,
 }
 
+type EventCallback = Arc<dyn Fn(Box<dyn UnifiedEvent>) + Send + Sync>;
+
+/// Steps between parsing and the user callback, shared by transaction, account and
+/// block meta events so every event type passes the same checks
+pub(crate) struct EventDelivery {
+    callback: Option<EventCallback>,
+    metrics_manager: MetricsManager,
+    slo_monitor: Option<Arc<SloMonitor>>,
+    block_time_estimator: Arc<BlockTimeEstimator>,
+    entry_index: Arc<EntryIndexTracker>,
+    event_filter: Option<EventFilterExpr>,
+    strict_validation: Option<StrictAction>,
+    express_lane: Option<Arc<ExpressLane>>,
+    max_event_age: Option<EventAgeGuard>,
+}
+
+impl EventDelivery {
+    fn new(
+        config: &ClientConfig,
+        metrics_manager: &MetricsManager,
+        slo_monitor: &Option<Arc<SloMonitor>>,
+        block_time_estimator: &Arc<BlockTimeEstimator>,
+        entry_index: &Arc<EntryIndexTracker>,
+        callback: Option<EventCallback>,
+    ) -> Self {
+        Self {
+            callback,
+            metrics_manager: metrics_manager.clone(),
+            slo_monitor: slo_monitor.clone(),
+            block_time_estimator: block_time_estimator.clone(),
+            entry_index: entry_index.clone(),
+            event_filter: config.event_filter.clone(),
+            strict_validation: config.strict_validation,
+            express_lane: config.express_lane.clone(),
+            max_event_age: config.max_event_age,
+        }
+    }
+
+    /// Runs the event through the delivery steps and the callback
+    ///
+    /// Returns false if the event was dropped (invalid, filtered out or stale); events
+    /// diverted to the express lane count as delivered.
+    pub(crate) fn deliver(&self, event: Box<dyn UnifiedEvent>) -> bool {
+        let mut event = match self.express_lane.as_ref() {
+            Some(lane) => match lane.route(event) {
+                Some(event) => event,
+                None => return true,
+            },
+            None => event,
+        };
+        self.block_time_estimator.apply(event.as_mut());
+        self.entry_index.apply(event.as_mut());
+        if self.strict_validation.is_some_and(|action| !action.apply(event.as_mut())) {
+            return false;
+        }
+        if self.event_filter.as_ref().is_some_and(|filter| !filter.matches(event.as_ref())) {
+            return false;
+        }
+        if let Some(guard) = self.max_event_age.as_ref() {
+            if guard.is_stale(event.as_ref(), get_high_perf_clock()) {
+                self.metrics_manager.increment_stale_events();
+                if !guard.apply(event.as_mut()) {
+                    return false;
+                }
+            }
+        }
+        if let Some(slo_monitor) = self.slo_monitor.as_ref() {
+            for breach in slo_monitor.record(event.as_ref()) {
+                self.metrics_manager.record_slo_breach(&breach);
+            }
+        }
+        if event.metadata().has_padded_accounts {
+            self.metrics_manager.increment_padded_events();
+        }
+        self.metrics_manager.record_latency(event.as_ref());
+        if let Some(callback) = self.callback.as_ref() {
+            callback(event);
+        }
+        true
+    }
+}
+
+/// Closes SLO windows on a timer so a stalled stream is still evaluated; stops once the
+/// monitor is dropped
+fn spawn_slo_timer(slo_monitor: &Arc<SloMonitor>, metrics_manager: MetricsManager) {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        log::warn!("No tokio runtime, latency SLO windows only close on new events");
+        return;
+    };
+    let slo_monitor = Arc::downgrade(slo_monitor);
+    runtime.spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_millis(DEFAULT_SLO_TICK_MS));
+        loop {
+            interval.tick().await;
+            let Some(slo_monitor) = slo_monitor.upgrade() else {
+                break;
+            };
+            for breach in slo_monitor.tick() {
+                metrics_manager.record_slo_breach(&breach);
+            }
+        }
+    });
+}
+
 /// High-performance Event processor using SegQueue for all strategies
 pub struct EventProcessor {
     pub(crate) metrics_manager: MetricsManager,
@@ -32,22 +151,73 @@ pub struct EventProcessor {
     pub(crate) protocols: Vec<Protocol>,
     pub(crate) event_type_filter: Option<EventTypeFilter>,
     pub(crate) callback: Option<Arc<dyn Fn(Box<dyn UnifiedEvent>) + Send + Sync>>,
+    /// 回调前的公共处理步骤，设置回调时重建
+    pub(crate) delivery: Arc<EventDelivery>,
     pub(crate) backpressure_config: BackpressureConfig,
     pub(crate) grpc_queue: Arc<SegQueue<(EventPretty, Option<Pubkey>)>>,
     pub(crate) shred_queue: Arc<SegQueue<(TransactionWithSlot, Option<Pubkey>)>>,
     pub(crate) grpc_pending_count: Arc<AtomicUsize>,
     pub(crate) shred_pending_count: Arc<AtomicUsize>,
     pub(crate) processing_shutdown: Arc<AtomicBool>,
+    pub(crate) slo_monitor: Option<Arc<SloMonitor>>,
+    pub(crate) block_time_estimator: Arc<BlockTimeEstimator>,
+    pub(crate) entry_index: Arc<EntryIndexTracker>,
+    /// 交易过滤器名称 -> 该过滤器使用的协议
+    pub(crate) filter_scopes: Arc<parking_lot::RwLock<HashMap<String, Vec<Protocol>>>>,
+    /// 交易过滤器名称 -> 该过滤器要求的签名者
+    pub(crate) filter_signers: Arc<parking_lot::RwLock<HashMap<String, Vec<Pubkey>>>>,
+    /// 按命中的过滤器组合缓存的解析器
+    pub(crate) scoped_parsers: Arc<DashMap<Vec<String>, Arc<EventParser>>>,
+    pub(crate) global_state: Arc<GlobalState>,
+    /// 解析失败计数，达到阈值时转储调试包
+    pub(crate) debug_recorder: Arc<parking_lot::RwLock<Option<Arc<DebugRecorder>>>>,
+    /// 账户更新合并缓冲（仅保留每个账户的最新更新）
+    pub(crate) account_conflator: Option<Arc<AccountConflator>>,
+    /// 回调执行预算（计时、慢回调告警与自动卸载）
+    pub(crate) callback_budget: Option<Arc<CallbackBudget>>,
+    /// 运行时协议暂停开关，本处理器构建的所有解析器共享
+    pub(crate) protocol_switches: Arc<ProtocolSwitches>,
 }
 
 impl EventProcessor {
     pub fn new(metrics_manager: MetricsManager, config: ClientConfig) -> Self {
+        Self::new_with_state(metrics_manager, config, Arc::new(GlobalState::new()))
+    }
+
+    /// 使用外部传入的状态创建，多个处理器可共享同一份开发者地址状态
+    pub fn new_with_state(
+        metrics_manager: MetricsManager,
+        config: ClientConfig,
+        global_state: Arc<GlobalState>,
+    ) -> Self {
         let backpressure_config = config.backpressure.clone();
         let grpc_queue = Arc::new(SegQueue::new());
         let shred_queue = Arc::new(SegQueue::new());
         let grpc_pending_count = Arc::new(AtomicUsize::new(0));
         let shred_pending_count = Arc::new(AtomicUsize::new(0));
         let processing_shutdown = Arc::new(AtomicBool::new(false));
+        let slo_monitor =
+            (!config.slo.slos.is_empty()).then(|| Arc::new(SloMonitor::new(config.slo.clone())));
+        if let Some(slo_monitor) = slo_monitor.as_ref() {
+            spawn_slo_timer(slo_monitor, metrics_manager.clone());
+        }
+        let account_conflator = config
+            .account_conflation
+            .clone()
+            .map(|conflation| Arc::new(AccountConflator::new(conflation)));
+        let callback_budget = config.callback_budget.clone().map(|budget| {
+            Arc::new(CallbackBudget::new(budget).with_metrics(metrics_manager.clone()))
+        });
+        let block_time_estimator = Arc::new(BlockTimeEstimator::default());
+        let entry_index = Arc::new(EntryIndexTracker::default());
+        let delivery = Arc::new(EventDelivery::new(
+            &config,
+            &metrics_manager,
+            &slo_monitor,
+            &block_time_estimator,
+            &entry_index,
+            None,
+        ));
 
         Self {
             metrics_manager,
@@ -57,11 +227,23 @@ impl EventProcessor {
             event_type_filter: None,
             backpressure_config,
             callback: None,
+            delivery,
             grpc_queue,
             shred_queue,
             grpc_pending_count,
             shred_pending_count,
             processing_shutdown,
+            slo_monitor,
+            block_time_estimator,
+            entry_index,
+            filter_scopes: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            filter_signers: Arc::new(parking_lot::RwLock::new(HashMap::new())),
+            scoped_parsers: Arc::new(DashMap::new()),
+            global_state,
+            debug_recorder: Arc::new(parking_lot::RwLock::new(None)),
+            account_conflator,
+            callback_budget,
+            protocol_switches: Arc::new(ProtocolSwitches::default()),
         }
     }
 
@@ -77,30 +259,120 @@ impl EventProcessor {
         self.event_type_filter = event_type_filter;
 
         self.backpressure_config = backpressure_config;
-        self.callback = callback;
-        let protocols_ref = &self.protocols;
-        let event_type_filter_ref = self.event_type_filter.as_ref();
-        self.parser_cache.get_or_init(|| {
-            Arc::new(EventParser::new(protocols_ref.clone(), event_type_filter_ref.cloned()))
-        });
+        self.callback = match (callback, self.callback_budget.as_ref()) {
+            (Some(callback), Some(budget)) => Some(budget.wrap(callback)),
+            (callback, _) => callback,
+        };
+        self.delivery = Arc::new(EventDelivery::new(
+            &self.config,
+            &self.metrics_manager,
+            &self.slo_monitor,
+            &self.block_time_estimator,
+            &self.entry_index,
+            self.callback.clone(),
+        ));
+        let protocols = self.protocols.clone();
+        self.parser_cache.get_or_init(|| self.build_parser(protocols));
+        self.global_state.set_max_signatures(self.config.memory.max_signatures);
 
         if matches!(self.backpressure_config.strategy, BackpressureStrategy::Block) {
             self.start_block_processing_thread(source);
         }
+        self.start_conflation_flusher();
+    }
+
+    pub fn global_state(&self) -> &Arc<GlobalState> {
+        &self.global_state
     }
 
     pub fn get_parser(&self) -> Arc<EventParser> {
         self.parser_cache.get().unwrap().clone()
     }
 
-    fn create_adapter_callback(&self) -> Arc<dyn Fn(Box<dyn UnifiedEvent>) + Send + Sync> {
-        let callback = self.callback.clone().unwrap();
-        let metrics_manager = self.metrics_manager.clone();
+    fn build_parser(&self, protocols: Vec<Protocol>) -> Arc<EventParser> {
+        let mut parser = EventParser::new(protocols, self.event_type_filter.clone());
+        parser.protocol_switches = self.protocol_switches.clone();
+        if !self.config.program_labels.is_empty() {
+            let mut registry = ProgramRegistry::default();
+            registry.extend(self.config.program_labels.iter().cloned());
+            parser.program_registry = Arc::new(registry);
+        }
+        for protocol_config in &self.config.protocol_configs {
+            if let Err(e) = parser.add_protocol_config(protocol_config) {
+                log::error!("Ignoring protocol config {}: {e:?}", protocol_config.name);
+            }
+        }
+        Arc::new(parser)
+    }
+
+    /// Sets which protocols parse the transactions matched by each named filter
+    ///
+    /// Filters missing from `scopes` use the full protocol set.
+    pub fn set_filter_scopes(&self, scopes: HashMap<String, Vec<Protocol>>) {
+        *self.filter_scopes.write() = scopes;
+        self.scoped_parsers.clear();
+    }
+
+    /// Sets the accounts one of which must sign the transactions matched by each named filter
+    ///
+    /// Filters missing from `signers` accept any transaction.
+    pub fn set_filter_signers(&self, signers: HashMap<String, Vec<Pubkey>>) {
+        *self.filter_signers.write() = signers;
+    }
+
+    /// Whether a transaction matched by `filters` meets their signer requirements
+    ///
+    /// Accepted if any matched filter has no requirement or one of its signers signed it.
+    pub fn accepts_signers(&self, filters: &[String], tx: &SubscribeUpdateTransactionInfo) -> bool {
+        let required = self.filter_signers.read();
+        if required.is_empty() || filters.is_empty() {
+            return true;
+        }
+        filters
+            .iter()
+            .any(|name| required.get(name).is_none_or(|signers| is_signed_by(tx, signers)))
+    }
 
+    /// Parser for a transaction matched by `filters`
+    ///
+    /// Scoped only if every matched filter is scoped, then with the union of their protocols.
+    pub fn get_parser_for_filters(&self, filters: &[String]) -> Arc<EventParser> {
+        if filters.is_empty() {
+            return self.get_parser();
+        }
+        let mut protocols: Vec<Protocol> = Vec::new();
+        {
+            let scopes = self.filter_scopes.read();
+            if scopes.is_empty() {
+                return self.get_parser();
+            }
+            for name in filters {
+                let Some(scope) = scopes.get(name) else {
+                    return self.get_parser();
+                };
+                for protocol in scope {
+                    if !protocols.contains(protocol) {
+                        protocols.push(protocol.clone());
+                    }
+                }
+            }
+        }
+        let mut key = filters.to_vec();
+        key.sort();
+        self.scoped_parsers.entry(key).or_insert_with(|| self.build_parser(protocols)).clone()
+    }
+
+    fn create_adapter_callback(&self) -> Arc<dyn Fn(Box<dyn UnifiedEvent>) + Send + Sync> {
+        let delivery = self.delivery.clone();
         Arc::new(move |event: Box<dyn UnifiedEvent>| {
             let processing_time_us = event.handle_us() as f64;
-            callback(event);
-            metrics_manager.update_metrics(MetricsEventType::Transaction, 1, processing_time_us);
+            if delivery.deliver(event) {
+                delivery.metrics_manager.update_metrics(
+                    MetricsEventType::Transaction,
+                    1,
+                    processing_time_us,
+                );
+            }
         })
     }
 
@@ -109,9 +381,57 @@ impl EventProcessor {
         event_pretty: EventPretty,
         bot_wallet: Option<Pubkey>,
     ) -> AnyResult<()> {
+        if let EventPretty::Transaction(transaction_pretty) = &event_pretty {
+            if !self.is_sampled(&transaction_pretty.signature) {
+                return Ok(());
+            }
+        }
+        let event_pretty = match (event_pretty, self.account_conflator.as_ref()) {
+            (EventPretty::Account(account), Some(conflator)) => {
+                if conflator.push(account) {
+                    self.flush_conflated_accounts().await?;
+                }
+                return Ok(());
+            }
+            (event_pretty, _) => event_pretty,
+        };
         self.apply_backpressure_control(event_pretty, bot_wallet).await
     }
 
+    /// Hands the conflated account updates to the normal processing path
+    async fn flush_conflated_accounts(&self) -> AnyResult<()> {
+        if let Some(conflator) = self.account_conflator.as_ref() {
+            for account in conflator.drain() {
+                self.apply_backpressure_control(EventPretty::Account(account), None).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn start_conflation_flusher(&self) {
+        let Some(conflator) = self.account_conflator.clone() else {
+            return;
+        };
+        if conflator
+            .flusher_running
+            .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            return;
+        }
+        let processor = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(conflator.flush_interval());
+            while !processor.processing_shutdown.load(Ordering::Relaxed) {
+                ticker.tick().await;
+                if let Err(e) = processor.flush_conflated_accounts().await {
+                    log::error!("Error flushing conflated account updates: {}", e);
+                }
+            }
+            conflator.flusher_running.store(false, Ordering::Relaxed);
+        });
+    }
+
     async fn apply_backpressure_control(
         &self,
         event_pretty: EventPretty,
@@ -170,7 +490,7 @@ impl EventProcessor {
             EventPretty::Account(account_pretty) => {
                 self.metrics_manager.add_account_process_count();
                 let account_event = AccountEventParser::parse_account_event(
-                    &self.protocols,
+                    &self.protocol_switches.active(&self.protocols),
                     account_pretty,
                     self.event_type_filter.as_ref(),
                 );
@@ -182,6 +502,10 @@ impl EventProcessor {
             }
             EventPretty::Transaction(transaction_pretty) => {
                 self.metrics_manager.add_tx_process_count();
+                // 服务端只能按出现的账户匹配，签名者要求在解析前检查
+                if !self.accepts_signers(&transaction_pretty.filters, &transaction_pretty.grpc_tx) {
+                    return Ok(());
+                }
                 let slot = transaction_pretty.slot;
                 let signature = transaction_pretty.signature;
                 let block_time = transaction_pretty.block_time;
@@ -189,9 +513,35 @@ impl EventProcessor {
                 let transaction_index = transaction_pretty.transaction_index;
                 let grpc_tx = transaction_pretty.grpc_tx;
 
-                let parser = self.get_parser();
+                let parser = self.get_parser_for_filters(&transaction_pretty.filters);
+                if let Some(canary) =
+                    self.config.parser_canary.as_ref().filter(|canary| canary.should_sample())
+                {
+                    // 影子解析在独立任务中进行，不影响实时输出
+                    let canary = canary.clone();
+                    let live = parser.clone();
+                    let grpc_tx = grpc_tx.clone();
+                    let block_time = block_time.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = canary
+                            .shadow_grpc_transaction(
+                                &live,
+                                grpc_tx,
+                                signature,
+                                Some(slot),
+                                block_time,
+                                recv_us,
+                                bot_wallet,
+                                transaction_index,
+                            )
+                            .await
+                        {
+                            log::debug!("Canary shadow parse failed: {}", e);
+                        }
+                    });
+                }
                 let adapter_callback = self.create_adapter_callback();
-                parser
+                let result = parser
                     .parse_grpc_transaction_owned(
                         grpc_tx,
                         signature,
@@ -202,20 +552,31 @@ impl EventProcessor {
                         transaction_index,
                         adapter_callback,
                     )
-                    .await?;
+                    .await;
+                if result.is_err() {
+                    if let Some(recorder) = self.debug_recorder.read().as_ref() {
+                        recorder.record_parse_error();
+                    }
+                }
+                result?;
             }
             EventPretty::BlockMeta(block_meta_pretty) => {
                 self.metrics_manager.add_block_meta_process_count();
-                let block_time_ms = block_meta_pretty
+                let actual_block_time_ms = block_meta_pretty
                     .block_time
-                    .map(|ts| ts.seconds * 1000 + ts.nanos as i64 / 1_000_000)
-                    .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
-                let block_meta_event = CommonEventParser::generate_block_meta_event(
-                    block_meta_pretty.slot,
-                    block_meta_pretty.block_hash,
-                    block_time_ms,
-                    block_meta_pretty.recv_us,
-                );
+                    .map(|ts| ts.seconds * 1000 + ts.nanos as i64 / 1_000_000);
+                // 缺失时按已知 slot 时间估算；没有锚点时保持 Missing（block_time_ms 为 0）
+                let estimated_block_time_ms = match actual_block_time_ms {
+                    Some(_) => None,
+                    None => self.block_time_estimator.estimate(block_meta_pretty.slot),
+                };
+                let block_time_ms =
+                    actual_block_time_ms.or(estimated_block_time_ms).unwrap_or_default();
+                let mut block_meta_event =
+                    CommonEventParser::generate_block_meta_event(block_meta_pretty, block_time_ms);
+                if estimated_block_time_ms.is_some() {
+                    block_meta_event.metadata_mut().block_time_source = BlockTimeSource::Estimated;
+                }
                 let processing_time_us = block_meta_event.handle_us() as f64;
                 self.invoke_callback(block_meta_event);
                 self.update_metrics(MetricsEventType::BlockMeta, 1, processing_time_us);
@@ -226,9 +587,7 @@ impl EventProcessor {
     }
 
     pub fn invoke_callback(&self, event: Box<dyn UnifiedEvent>) {
-        if let Some(callback) = self.callback.as_ref() {
-            callback(event);
-        }
+        self.delivery.deliver(event);
     }
 
     pub async fn process_shred_transaction_immediate(
@@ -236,6 +595,10 @@ impl EventProcessor {
         transaction_with_slot: TransactionWithSlot,
         bot_wallet: Option<Pubkey>,
     ) -> AnyResult<()> {
+        if transaction_with_slot.transaction.signatures.first().is_some_and(|s| !self.is_sampled(s))
+        {
+            return Ok(());
+        }
         self.process_shred_transaction(transaction_with_slot, bot_wallet).await
     }
 
@@ -244,9 +607,18 @@ impl EventProcessor {
         transaction_with_slot: TransactionWithSlot,
         bot_wallet: Option<Pubkey>,
     ) -> AnyResult<()> {
+        if transaction_with_slot.transaction.signatures.first().is_some_and(|s| !self.is_sampled(s))
+        {
+            return Ok(());
+        }
         self.apply_shred_backpressure_control(transaction_with_slot, bot_wallet).await
     }
 
+    /// 采样在解析前进行，未命中的交易直接丢弃
+    fn is_sampled(&self, signature: &Signature) -> bool {
+        self.config.sampling.is_none_or(|sampling| sampling.keeps(signature))
+    }
+
     async fn apply_shred_backpressure_control(
         &self,
         transaction_with_slot: TransactionWithSlot,
@@ -345,18 +717,13 @@ impl EventProcessor {
         let shutdown_flag_clone = Arc::clone(&self.processing_shutdown);
         let processor = self.clone();
         let processor_clone = self.clone();
+        let runtime_config = self.config.runtime.clone();
         // Dedicated thread with busy-wait and lock-free processing
         match source {
             EventSource::Grpc => {
                 std::thread::spawn(move || {
-                    let mut worker_threads =
-                        std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4); // 如果获取失败则回退到4个线程
-
-                    let rt = tokio::runtime::Builder::new_multi_thread()
-                        .worker_threads(worker_threads)
-                        .enable_all()
-                        .build()
-                        .unwrap();
+                    runtime_config.apply_parser_thread();
+                    let rt = runtime_config.build_parser_runtime().unwrap();
 
                     while !shutdown_flag.load(Ordering::Relaxed) {
                         if let Some((event_pretty, bot_wallet)) = grpc_queue.pop() {
@@ -376,14 +743,8 @@ impl EventProcessor {
             EventSource::Shred => {
                 // Shred processing with same low-latency optimization
                 std::thread::spawn(move || {
-                    let worker_threads =
-                        std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4); // 如果获取失败则回退到4个线程
-
-                    let rt = tokio::runtime::Builder::new_multi_thread()
-                        .worker_threads(worker_threads)
-                        .enable_all()
-                        .build()
-                        .unwrap();
+                    runtime_config.apply_parser_thread();
+                    let rt = runtime_config.build_parser_runtime().unwrap();
 
                     while !shutdown_flag_clone.load(Ordering::Relaxed) {
                         if let Some((transaction_with_slot, bot_wallet)) = shred_queue.pop() {
@@ -404,11 +765,53 @@ impl EventProcessor {
         }
     }
 
+    /// Approximate memory usage of caches and queues owned by this processor
+    pub fn memory_report(&self) -> MemoryReport {
+        MemoryReport::collect(self)
+    }
+
+    /// Latency SLO monitor, `None` when no SLOs are configured
+    pub fn slo_monitor(&self) -> Option<Arc<SloMonitor>> {
+        self.slo_monitor.clone()
+    }
+
+    /// Account update conflation buffer, `None` unless `account_conflation` is configured
+    pub fn account_conflator(&self) -> Option<Arc<AccountConflator>> {
+        self.account_conflator.clone()
+    }
+
+    /// Callback timing, `None` unless `callback_budget` is configured
+    pub fn callback_budget(&self) -> Option<Arc<CallbackBudget>> {
+        self.callback_budget.clone()
+    }
+
+    pub fn block_time_estimator(&self) -> Arc<BlockTimeEstimator> {
+        self.block_time_estimator.clone()
+    }
+
+    pub fn entry_index_tracker(&self) -> Arc<EntryIndexTracker> {
+        self.entry_index.clone()
+    }
+
     pub fn stop_processing(&self) {
         self.processing_shutdown.store(true, Ordering::Relaxed);
     }
 }
 
+/// Whether any of `signers` is among the transaction's signing accounts
+fn is_signed_by(tx: &SubscribeUpdateTransactionInfo, signers: &[Pubkey]) -> bool {
+    let Some(message) = tx.transaction.as_ref().and_then(|tx| tx.message.as_ref()) else {
+        return false;
+    };
+    let num_signers =
+        message.header.as_ref().map_or(1, |header| header.num_required_signatures as usize);
+    message
+        .account_keys
+        .iter()
+        .take(num_signers)
+        .any(|key| signers.iter().any(|signer| signer.as_ref() == key.as_slice()))
+}
+
 impl Clone for EventProcessor {
     fn clone(&self) -> Self {
         Self {
@@ -419,11 +822,110 @@ impl Clone for EventProcessor {
             event_type_filter: self.event_type_filter.clone(),
             backpressure_config: self.backpressure_config.clone(),
             callback: self.callback.clone(),
+            delivery: self.delivery.clone(),
             grpc_queue: self.grpc_queue.clone(),
             shred_queue: self.shred_queue.clone(),
             grpc_pending_count: self.grpc_pending_count.clone(),
             shred_pending_count: self.shred_pending_count.clone(),
             processing_shutdown: self.processing_shutdown.clone(),
+            slo_monitor: self.slo_monitor.clone(),
+            block_time_estimator: self.block_time_estimator.clone(),
+            entry_index: self.entry_index.clone(),
+            filter_scopes: self.filter_scopes.clone(),
+            filter_signers: self.filter_signers.clone(),
+            scoped_parsers: self.scoped_parsers.clone(),
+            global_state: self.global_state.clone(),
+            debug_recorder: self.debug_recorder.clone(),
+            account_conflator: self.account_conflator.clone(),
+            callback_budget: self.callback_budget.clone(),
+            protocol_switches: self.protocol_switches.clone(),
         }
     }
 }
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::common::{EventAgeGuard, StaleAction};
+    use crate::streaming::event_parser::common::{EventMetadata, ProtocolType};
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+    use yellowstone_grpc_proto::prelude::{Message, MessageHeader, Transaction};
+
+    fn swap(recv_us: i64) -> Box<dyn UnifiedEvent> {
+        Box::new(RaydiumCpmmSwapEvent {
+            metadata: EventMetadata { recv_us, handle_us: 40, ..Default::default() },
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_transaction_events_pass_delivery_checks() {
+        let config = ClientConfig {
+            enable_metrics: true,
+            max_event_age: Some(EventAgeGuard::new(1_000_000, StaleAction::Drop)),
+            ..Default::default()
+        };
+        let mut processor =
+            EventProcessor::new(MetricsManager::new(true, "test".to_string()), config);
+        let delivered = Arc::new(AtomicUsize::new(0));
+        let counter = delivered.clone();
+        processor.set_protocols_and_event_type_filter(
+            EventSource::Grpc,
+            vec![Protocol::RaydiumCpmm],
+            None,
+            BackpressureConfig { strategy: BackpressureStrategy::Drop, ..Default::default() },
+            Some(Arc::new(move |_| {
+                counter.fetch_add(1, Ordering::Relaxed);
+            })),
+        );
+
+        // 交易事件与账户事件走同一交付流程：记录延迟，超时丢弃
+        let adapter = processor.create_adapter_callback();
+        let now_us = get_high_perf_clock();
+        adapter(swap(now_us));
+        adapter(swap(now_us - 5_000_000));
+        assert_eq!(delivered.load(Ordering::Relaxed), 1);
+        let metrics = &processor.metrics_manager;
+        assert_eq!(metrics.get_stale_events_count(), 1);
+        let histograms = metrics.get_latency_histograms();
+        assert_eq!(histograms.len(), 1);
+        assert_eq!(histograms[0].protocol, ProtocolType::RaydiumCpmm);
+        assert_eq!((histograms[0].count, histograms[0].sum_us), (1, 40));
+    }
+
+    #[test]
+    fn test_filter_signers() {
+        let (wallet, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let transaction = |keys: [Pubkey; 2]| SubscribeUpdateTransactionInfo {
+            transaction: Some(Transaction {
+                message: Some(Message {
+                    header: Some(MessageHeader {
+                        num_required_signatures: 1,
+                        ..Default::default()
+                    }),
+                    account_keys: keys.iter().map(|key| key.to_bytes().to_vec()).collect(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let signed = transaction([wallet, other]);
+        let touched = transaction([other, wallet]);
+        let client = ["client".to_string()];
+
+        let processor = EventProcessor::new(
+            MetricsManager::new(false, "test".to_string()),
+            ClientConfig::default(),
+        );
+        assert!(processor.accepts_signers(&client, &touched));
+        processor.set_filter_signers(HashMap::from([("client".to_string(), vec![wallet])]));
+        assert!(processor.accepts_signers(&client, &signed));
+        // 钱包出现在交易中但不是签名者
+        assert!(!processor.accepts_signers(&client, &touched));
+        // 同时命中没有签名者要求的过滤器
+        assert!(
+            processor.accepts_signers(&["client".to_string(), "client_1".to_string()], &touched)
+        );
+    }
+}
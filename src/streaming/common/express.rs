@@ -0,0 +1,162 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crossbeam::channel::{self, Receiver, Sender, TrySendError};
+use parking_lot::RwLock;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::streaming::analytics::TradeInfo;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default capacity of the express channel
+pub const DEFAULT_EXPRESS_CAPACITY: usize = 10_000;
+
+/// Express path for events of selected pools / mints
+///
+/// Matching events skip strict validation, the event filter, SLO recording and the
+/// regular callback, and are pushed as-is onto a dedicated channel read via
+/// [`receiver`](Self::receiver); everything else takes the standard path. Swaps match on
+/// their pool and input/output mints. When the channel is full the event falls back to the
+/// standard path instead of being dropped.
+///
+/// ```rust,ignore
+/// let lane = Arc::new(ExpressLane::default());
+/// lane.add_pools([pool]);
+/// config.express_lane = Some(lane.clone());
+/// std::thread::spawn(move || for event in lane.receiver() { /* strategy */ });
+/// ```
+#[derive(Debug)]
+pub struct ExpressLane {
+    pools: RwLock<HashSet<Pubkey>>,
+    mints: RwLock<HashSet<Pubkey>>,
+    sender: Sender<Box<dyn UnifiedEvent>>,
+    receiver: Receiver<Box<dyn UnifiedEvent>>,
+    delivered: AtomicU64,
+    overflowed: AtomicU64,
+}
+
+impl Default for ExpressLane {
+    fn default() -> Self {
+        Self::new(DEFAULT_EXPRESS_CAPACITY)
+    }
+}
+
+impl ExpressLane {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, receiver) = channel::bounded(capacity.max(1));
+        Self {
+            pools: RwLock::new(HashSet::new()),
+            mints: RwLock::new(HashSet::new()),
+            sender,
+            receiver,
+            delivered: AtomicU64::new(0),
+            overflowed: AtomicU64::new(0),
+        }
+    }
+
+    /// Adds express pools, returns how many were new
+    pub fn add_pools(&self, pools: impl IntoIterator<Item = Pubkey>) -> usize {
+        let mut express = self.pools.write();
+        pools.into_iter().filter(|pool| express.insert(*pool)).count()
+    }
+
+    pub fn remove_pools(&self, pools: impl IntoIterator<Item = Pubkey>) -> usize {
+        let mut express = self.pools.write();
+        pools.into_iter().filter(|pool| express.remove(pool)).count()
+    }
+
+    /// Adds express mints, returns how many were new
+    pub fn add_mints(&self, mints: impl IntoIterator<Item = Pubkey>) -> usize {
+        let mut express = self.mints.write();
+        mints.into_iter().filter(|mint| express.insert(*mint)).count()
+    }
+
+    pub fn remove_mints(&self, mints: impl IntoIterator<Item = Pubkey>) -> usize {
+        let mut express = self.mints.write();
+        mints.into_iter().filter(|mint| express.remove(mint)).count()
+    }
+
+    /// Receiving end of the express channel, may be cloned for several consumers
+    pub fn receiver(&self) -> Receiver<Box<dyn UnifiedEvent>> {
+        self.receiver.clone()
+    }
+
+    /// Whether the event touches an express pool or mint
+    pub fn matches(&self, event: &dyn UnifiedEvent) -> bool {
+        let (pools, mints) = (self.pools.read(), self.mints.read());
+        if pools.is_empty() && mints.is_empty() {
+            return false;
+        }
+        if let Some(swap_data) = event.metadata().swap_data.as_ref() {
+            if mints.contains(&swap_data.from_mint) || mints.contains(&swap_data.to_mint) {
+                return true;
+            }
+        }
+        TradeInfo::from_event(event).is_some_and(|trade| {
+            pools.contains(&trade.pool)
+                || mints.contains(&trade.input_mint)
+                || mints.contains(&trade.output_mint)
+        })
+    }
+
+    /// Sends a matching event down the express channel
+    ///
+    /// Returns the event when it must take the standard path (no match or channel full).
+    pub fn route(&self, event: Box<dyn UnifiedEvent>) -> Option<Box<dyn UnifiedEvent>> {
+        if !self.matches(event.as_ref()) {
+            return Some(event);
+        }
+        match self.sender.try_send(event) {
+            Ok(()) => {
+                self.delivered.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            Err(TrySendError::Full(event)) | Err(TrySendError::Disconnected(event)) => {
+                self.overflowed.fetch_add(1, Ordering::Relaxed);
+                Some(event)
+            }
+        }
+    }
+
+    /// Events delivered on the express channel
+    pub fn delivered(&self) -> u64 {
+        self.delivered.load(Ordering::Relaxed)
+    }
+
+    /// Matching events sent down the standard path because the channel was full
+    pub fn overflowed(&self) -> u64 {
+        self.overflowed.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    #[test]
+    fn test_express_lane_routing() {
+        let lane = ExpressLane::new(1);
+        let (pool, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let swap = |pool_state: Pubkey, input_token_mint: Pubkey| -> Box<dyn UnifiedEvent> {
+            Box::new(RaydiumCpmmSwapEvent {
+                amount_in: 1,
+                pool_state,
+                input_token_mint,
+                ..Default::default()
+            })
+        };
+
+        assert!(lane.route(swap(pool, mint)).is_some());
+        lane.add_pools([pool]);
+        assert!(lane.route(swap(pool, Pubkey::new_unique())).is_none());
+        // 通道已满，回退到普通路径
+        assert!(lane.route(swap(pool, mint)).is_some());
+        assert_eq!((lane.delivered(), lane.overflowed()), (1, 1));
+        assert!(lane.receiver().try_recv().is_ok());
+
+        lane.add_mints([mint]);
+        assert!(lane.route(swap(Pubkey::new_unique(), mint)).is_none());
+        assert!(lane.route(swap(Pubkey::new_unique(), Pubkey::new_unique())).is_some());
+    }
+}
@@ -0,0 +1,84 @@
+use crate::streaming::grpc::EventPretty;
+use crate::streaming::shred::TransactionWithSlot;
+use serde::{Deserialize, Serialize};
+
+use super::event_processor::EventProcessor;
+
+/// Approximate memory usage of the streamer's internal caches
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryReport {
+    /// Dev-address state of the processor (bytes)
+    pub global_state_bytes: usize,
+    /// Number of signatures tracked by the dev-address state
+    pub global_state_signatures: usize,
+    /// Events waiting in the processing queues
+    pub queued_events: usize,
+    /// Inline size of the queued events (bytes, excludes transaction payloads)
+    pub queued_bytes: usize,
+}
+
+impl MemoryReport {
+    /// Total of all byte estimates
+    pub fn total_bytes(&self) -> usize {
+        self.global_state_bytes + self.queued_bytes
+    }
+
+    /// Collects a report from the given processor
+    pub fn collect(processor: &EventProcessor) -> Self {
+        let global_state = &processor.global_state;
+        let grpc_pending = processor.grpc_pending_count.load(std::sync::atomic::Ordering::Relaxed);
+        let shred_pending =
+            processor.shred_pending_count.load(std::sync::atomic::Ordering::Relaxed);
+
+        Self {
+            global_state_bytes: global_state.approx_memory_bytes(),
+            global_state_signatures: global_state.get_signature_count(),
+            queued_events: grpc_pending + shred_pending,
+            queued_bytes: grpc_pending * std::mem::size_of::<EventPretty>()
+                + shred_pending * std::mem::size_of::<TransactionWithSlot>(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+    use super::*;
+    use crate::streaming::common::{MetricsManager, StreamClientConfig};
+    use crate::streaming::event_parser::core::global_state::GlobalState;
+
+    #[test]
+    fn test_memory_report_counts() {
+        let state = Arc::new(GlobalState::new());
+        let processor = EventProcessor::new_with_state(
+            MetricsManager::new(false, "test".to_string()),
+            StreamClientConfig::default(),
+            state.clone(),
+        );
+        let report = processor.memory_report();
+        assert_eq!((report.global_state_signatures, report.queued_events), (0, 0));
+        assert_eq!(report.total_bytes(), 0);
+
+        let (first, second) = (Signature::new_unique(), Signature::new_unique());
+        state.add_dev_address(&first, Pubkey::new_unique());
+        let one_entry = processor.memory_report().global_state_bytes;
+        state.add_dev_address(&first, Pubkey::new_unique());
+        state.add_dev_address(&second, Pubkey::new_unique());
+        processor.grpc_pending_count.store(3, Ordering::Relaxed);
+        processor.shred_pending_count.store(2, Ordering::Relaxed);
+
+        let report = processor.memory_report();
+        assert_eq!(report.global_state_signatures, 2);
+        // 两个签名条目共三个地址
+        assert_eq!(report.global_state_bytes, 2 * one_entry + std::mem::size_of::<Pubkey>());
+        assert_eq!(report.queued_events, 5);
+        let queued_bytes =
+            3 * std::mem::size_of::<EventPretty>() + 2 * std::mem::size_of::<TransactionWithSlot>();
+        assert_eq!(report.queued_bytes, queued_bytes);
+        assert_eq!(report.total_bytes(), report.global_state_bytes + queued_bytes);
+    }
+}
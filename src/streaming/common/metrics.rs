@@ -1,7 +1,13 @@
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
+use dashmap::DashMap;
+use serde::Serialize;
+
 use super::constants::*;
+use super::slo::SloBreach;
+use crate::streaming::event_parser::common::{EventType as ParsedEventType, ProtocolType};
+use crate::streaming::event_parser::UnifiedEvent;
 
 /// Event type enumeration
 #[derive(Debug, Clone, Copy)]
@@ -224,8 +230,88 @@ impl AtomicProcessingTimeStats {
     }
 }
 
+/// Upper bounds (μs) of the latency histogram buckets, followed by an unbounded bucket
+pub const LATENCY_BUCKETS_US: [u64; 16] = [
+    10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000,
+    500_000, 1_000_000,
+];
+
+/// 接收到回调的延迟直方图（原子计数，无锁记录）
+#[derive(Debug)]
+struct AtomicLatencyHistogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS_US.len() + 1],
+    count: AtomicU64,
+    sum_us: AtomicU64,
+}
+
+impl AtomicLatencyHistogram {
+    fn new() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+            count: AtomicU64::new(0),
+            sum_us: AtomicU64::new(0),
+        }
+    }
+
+    #[inline]
+    fn record(&self, latency_us: u64) {
+        let index = LATENCY_BUCKETS_US.partition_point(|bound| *bound < latency_us);
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(latency_us, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self, protocol: ProtocolType, event_type: ParsedEventType) -> LatencyHistogram {
+        LatencyHistogram {
+            protocol,
+            event_type,
+            count: self.count.load(Ordering::Relaxed),
+            sum_us: self.sum_us.load(Ordering::Relaxed),
+            buckets: self.buckets.iter().map(|bucket| bucket.load(Ordering::Relaxed)).collect(),
+        }
+    }
+}
+
+/// Receive → callback latency histogram of one protocol and event type
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatencyHistogram {
+    pub protocol: ProtocolType,
+    pub event_type: ParsedEventType,
+    pub count: u64,
+    pub sum_us: u64,
+    /// Events per bucket (not cumulative), bounded by [`LATENCY_BUCKETS_US`] then unbounded
+    pub buckets: Vec<u64>,
+}
+
+impl LatencyHistogram {
+    pub fn avg_us(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.sum_us as f64 / self.count as f64
+    }
+
+    /// Upper bound of the bucket holding the percentile (0.0 - 1.0), None without samples
+    ///
+    /// Latencies beyond the last bound report `u64::MAX`.
+    pub fn percentile_us(&self, percentile: f64) -> Option<u64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = ((self.count as f64 * percentile.clamp(0.0, 1.0)).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(LATENCY_BUCKETS_US.get(index).copied().unwrap_or(u64::MAX));
+            }
+        }
+        Some(u64::MAX)
+    }
+}
+
 /// Processing time statistics result
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ProcessingTimeStats {
     pub min_us: f64,
     pub max_us: f64,
@@ -233,7 +319,7 @@ pub struct ProcessingTimeStats {
 }
 
 /// Event metrics snapshot
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EventMetricsSnapshot {
     pub process_count: u64,
     pub events_processed: u64,
@@ -241,7 +327,7 @@ pub struct EventMetricsSnapshot {
 }
 
 /// Compatibility structure - complete performance metrics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PerformanceMetrics {
     pub uptime: std::time::Duration,
     pub tx_metrics: EventMetricsSnapshot,
@@ -249,6 +335,16 @@ pub struct PerformanceMetrics {
     pub block_meta_metrics: EventMetricsSnapshot,
     pub processing_stats: ProcessingTimeStats,
     pub dropped_events_count: u64,
+    /// 含补齐账户的残缺事件数（见 `EventMetadata::has_padded_accounts`）
+    pub padded_events_count: u64,
+    /// 超出执行预算的回调次数（见 `CallbackBudgetConfig`）
+    pub slow_callbacks_count: u64,
+    /// 交付时已超出时限的事件数（见 `EventAgeGuard`）
+    pub stale_events_count: u64,
+    /// 按协议和事件类型拆分的接收到回调延迟
+    pub latency_histograms: Vec<LatencyHistogram>,
+    /// 各延迟 SLO 的违约窗口数（见 `SloMonitor`），按名称排序
+    pub slo_breaches: Vec<(String, u64)>,
 }
 
 impl PerformanceMetrics {
@@ -268,6 +364,11 @@ impl PerformanceMetrics {
             block_meta_metrics: default_metrics,
             processing_stats: default_stats,
             dropped_events_count: 0,
+            padded_events_count: 0,
+            slow_callbacks_count: 0,
+            stale_events_count: 0,
+            latency_histograms: Vec::new(),
+            slo_breaches: Vec::new(),
         }
     }
 }
@@ -280,6 +381,16 @@ pub struct HighPerformanceMetrics {
     processing_stats: AtomicProcessingTimeStats,
     // 丢弃事件指标
     dropped_events_count: AtomicU64,
+    // 含补齐账户的事件指标
+    padded_events_count: AtomicU64,
+    // 超出执行预算的回调指标
+    slow_callbacks_count: AtomicU64,
+    // 超时事件指标
+    stale_events_count: AtomicU64,
+    // 按协议和事件类型拆分的延迟直方图
+    latency_histograms: DashMap<(ProtocolType, ParsedEventType), AtomicLatencyHistogram>,
+    // 按 SLO 名称统计的违约窗口数
+    slo_breaches: DashMap<String, AtomicU64>,
 }
 
 impl HighPerformanceMetrics {
@@ -298,6 +409,11 @@ impl HighPerformanceMetrics {
             processing_stats: AtomicProcessingTimeStats::new(),
             // 初始化丢弃事件指标
             dropped_events_count: AtomicU64::new(0),
+            padded_events_count: AtomicU64::new(0),
+            slow_callbacks_count: AtomicU64::new(0),
+            stale_events_count: AtomicU64::new(0),
+            latency_histograms: DashMap::new(),
+            slo_breaches: DashMap::new(),
         }
     }
 
@@ -332,6 +448,59 @@ impl HighPerformanceMetrics {
         self.dropped_events_count.load(Ordering::Relaxed)
     }
 
+    /// 获取含补齐账户的事件计数
+    #[inline]
+    pub fn get_padded_events_count(&self) -> u64 {
+        self.padded_events_count.load(Ordering::Relaxed)
+    }
+
+    /// 获取超出执行预算的回调计数
+    #[inline]
+    pub fn get_slow_callbacks_count(&self) -> u64 {
+        self.slow_callbacks_count.load(Ordering::Relaxed)
+    }
+
+    /// 获取超时事件计数
+    #[inline]
+    pub fn get_stale_events_count(&self) -> u64 {
+        self.stale_events_count.load(Ordering::Relaxed)
+    }
+
+    /// 记录一个事件的接收到回调延迟
+    fn record_latency(&self, protocol: ProtocolType, event_type: ParsedEventType, latency_us: u64) {
+        let key = (protocol, event_type);
+        if let Some(histogram) = self.latency_histograms.get(&key) {
+            histogram.record(latency_us);
+            return;
+        }
+        self.latency_histograms
+            .entry(key)
+            .or_insert_with(AtomicLatencyHistogram::new)
+            .record(latency_us);
+    }
+
+    /// 各协议、事件类型的延迟直方图快照
+    pub fn get_latency_histograms(&self) -> Vec<LatencyHistogram> {
+        let mut histograms: Vec<LatencyHistogram> = self
+            .latency_histograms
+            .iter()
+            .map(|entry| entry.value().snapshot(entry.key().0.clone(), entry.key().1.clone()))
+            .collect();
+        histograms.sort_by_cached_key(|h| format!("{:?}/{:?}", h.protocol, h.event_type));
+        histograms
+    }
+
+    /// 各 SLO 的违约窗口数，按名称排序
+    pub fn get_slo_breaches(&self) -> Vec<(String, u64)> {
+        let mut breaches: Vec<(String, u64)> = self
+            .slo_breaches
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().load(Ordering::Relaxed)))
+            .collect();
+        breaches.sort();
+        breaches
+    }
+
     /// 更新窗口指标（后台任务调用）
     fn update_window_metrics(&self, event_type: EventType, window_duration_nanos: u64) {
         let now_nanos =
@@ -428,6 +597,28 @@ impl MetricsManager {
         self.metrics.processing_stats.update(processing_time_us, count);
     }
 
+    /// 记录事件的接收到回调延迟（`handle_us`），按协议和事件类型分桶
+    #[inline]
+    pub fn record_latency(&self, event: &dyn UnifiedEvent) {
+        if !self.enable_metrics {
+            return;
+        }
+        let metadata = event.metadata();
+        self.metrics.record_latency(
+            metadata.protocol.clone(),
+            metadata.event_type.clone(),
+            event.handle_us().max(0) as u64,
+        );
+    }
+
+    /// Receive → callback latency histograms per protocol and event type
+    ///
+    /// A regression on a low-volume path stays visible here while the global
+    /// processing stats are dominated by the busiest protocol.
+    pub fn get_latency_histograms(&self) -> Vec<LatencyHistogram> {
+        self.metrics.get_latency_histograms()
+    }
+
     /// 记录慢处理操作
     #[inline]
     pub fn log_slow_processing(&self, processing_time_us: f64, event_count: usize) {
@@ -461,6 +652,26 @@ impl MetricsManager {
         self.metrics.get_dropped_events_count()
     }
 
+    /// 获取含补齐账户的事件计数
+    pub fn get_padded_events_count(&self) -> u64 {
+        self.metrics.get_padded_events_count()
+    }
+
+    /// 获取超出执行预算的回调计数
+    pub fn get_slow_callbacks_count(&self) -> u64 {
+        self.metrics.get_slow_callbacks_count()
+    }
+
+    /// 获取超时事件计数
+    pub fn get_stale_events_count(&self) -> u64 {
+        self.metrics.get_stale_events_count()
+    }
+
+    /// 获取各 SLO 的违约窗口数
+    pub fn get_slo_breaches(&self) -> Vec<(String, u64)> {
+        self.metrics.get_slo_breaches()
+    }
+
     /// 打印性能指标（非阻塞）
     pub fn print_metrics(&self) {
         println!("\n📊 {} Performance Metrics", self.stream_name);
@@ -471,6 +682,21 @@ impl MetricsManager {
         if dropped_count > 0 {
             println!("\n⚠️  Dropped Events: {}", dropped_count);
         }
+        let padded_count = self.get_padded_events_count();
+        if padded_count > 0 {
+            println!("⚠️  Events With Padded Accounts: {}", padded_count);
+        }
+        let slow_callbacks = self.get_slow_callbacks_count();
+        if slow_callbacks > 0 {
+            println!("⚠️  Slow Callbacks: {}", slow_callbacks);
+        }
+        let stale_events = self.get_stale_events_count();
+        if stale_events > 0 {
+            println!("⚠️  Stale Events: {}", stale_events);
+        }
+        for (slo, breaches) in self.get_slo_breaches() {
+            println!("⚠️  SLO {} Breached Windows: {}", slo, breaches);
+        }
 
         // 打印事件指标表格（包含处理时间统计）
         println!("┌─────────────┬──────────────┬──────────────────┬─────────────┬─────────────┬─────────────┐");
@@ -491,6 +717,30 @@ impl MetricsManager {
         }
 
         println!("└─────────────┴──────────────┴──────────────────┴─────────────┴─────────────┴─────────────┘");
+
+        // 按协议和事件类型打印延迟分位数（桶上界）
+        let histograms = self.get_latency_histograms();
+        if !histograms.is_empty() {
+            println!(
+                "\n   Latency by protocol / event type (recv → callback, bucket upper bound):"
+            );
+            for histogram in histograms {
+                let bound = |p: f64| match histogram.percentile_us(p) {
+                    Some(u64::MAX) => "inf".to_string(),
+                    Some(us) => format!("{us}us"),
+                    None => "-".to_string(),
+                };
+                println!(
+                    "   {:?} {:?}: count {} avg {:.2}us p50 <= {} p99 <= {}",
+                    histogram.protocol,
+                    histogram.event_type,
+                    histogram.count,
+                    histogram.avg_us(),
+                    bound(0.5),
+                    bound(0.99)
+                );
+            }
+        }
         println!();
     }
 
@@ -533,6 +783,11 @@ impl MetricsManager {
             block_meta_metrics: self.get_event_metrics(EventType::BlockMeta),
             processing_stats: self.get_processing_stats(),
             dropped_events_count: self.metrics.get_dropped_events_count(),
+            padded_events_count: self.metrics.get_padded_events_count(),
+            slow_callbacks_count: self.metrics.get_slow_callbacks_count(),
+            stale_events_count: self.metrics.get_stale_events_count(),
+            latency_histograms: self.metrics.get_latency_histograms(),
+            slo_breaches: self.metrics.get_slo_breaches(),
         }
     }
 
@@ -582,6 +837,47 @@ impl MetricsManager {
         }
     }
 
+    /// 增加含补齐账户的事件计数
+    #[inline]
+    pub fn increment_padded_events(&self) {
+        if self.enable_metrics {
+            self.metrics.padded_events_count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// 增加超出执行预算的回调计数
+    #[inline]
+    pub fn increment_slow_callbacks(&self) {
+        if self.enable_metrics {
+            self.metrics.slow_callbacks_count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// 增加超时事件计数
+    #[inline]
+    pub fn increment_stale_events(&self) {
+        if self.enable_metrics {
+            self.metrics.stale_events_count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// 记录一个违约的 SLO 窗口
+    #[inline]
+    pub fn record_slo_breach(&self, breach: &SloBreach) {
+        if !self.enable_metrics {
+            return;
+        }
+        if let Some(count) = self.metrics.slo_breaches.get(&breach.slo) {
+            count.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.metrics
+            .slo_breaches
+            .entry(breach.slo.clone())
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
     /// 批量增加丢弃事件计数
     #[inline]
     pub fn increment_dropped_events_by(&self, count: u64) {
@@ -620,3 +916,23 @@ impl Clone for MetricsManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_histogram_percentiles() {
+        let histogram = AtomicLatencyHistogram::new();
+        for latency_us in [5, 40, 40, 300, 2_000_000] {
+            histogram.record(latency_us);
+        }
+        let snapshot =
+            histogram.snapshot(ProtocolType::RaydiumClmm, ParsedEventType::RaydiumClmmSwap);
+        assert_eq!(snapshot.count, 5);
+        assert_eq!(snapshot.percentile_us(0.5), Some(50));
+        assert_eq!(snapshot.percentile_us(0.8), Some(500));
+        assert_eq!(snapshot.percentile_us(1.0), Some(u64::MAX));
+        assert_eq!(snapshot.buckets.iter().sum::<u64>(), 5);
+    }
+}
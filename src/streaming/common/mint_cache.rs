@@ -0,0 +1,246 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use solana_sdk::epoch_schedule::EpochSchedule;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::streaming::event_parser::core::account_event_parser::{
+    MintRiskFlags, TokenInfoEvent, TransferFeeInfo,
+};
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Cached mint metadata
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintInfo {
+    pub decimals: u8,
+    /// spl-token or token-2022
+    pub token_program: Pubkey,
+    pub transfer_fee: Option<TransferFeeInfo>,
+    #[serde(default)]
+    pub risk: MintRiskFlags,
+}
+
+/// Mint metadata cache, fed from mint account updates or loaded on demand via RPC
+///
+/// Used to turn gross transfer amounts into what the recipient actually receives
+/// for Token-2022 mints with a transfer fee.
+///
+/// The epoch that selects the older or newer fee rate is derived from event slots with
+/// the cluster's epoch schedule. The default is mainnet's (432,000-slot epochs without
+/// warmup, also used by devnet and testnet); pass the schedule from `getEpochSchedule`
+/// via [`with_epoch_schedule`](Self::with_epoch_schedule) for clusters with warmup
+/// epochs such as a fresh `solana-test-validator`.
+#[derive(Debug)]
+pub struct MintInfoCache {
+    mints: DashMap<Pubkey, MintInfo>,
+    epoch: AtomicU64,
+    epoch_schedule: EpochSchedule,
+}
+
+impl Default for MintInfoCache {
+    fn default() -> Self {
+        Self {
+            mints: DashMap::new(),
+            epoch: AtomicU64::new(0),
+            epoch_schedule: EpochSchedule::without_warmup(),
+        }
+    }
+}
+
+impl MintInfoCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the epoch schedule used to map slots to epochs
+    pub fn with_epoch_schedule(mut self, epoch_schedule: EpochSchedule) -> Self {
+        self.epoch_schedule = epoch_schedule;
+        self
+    }
+
+    pub fn get(&self, mint: &Pubkey) -> Option<MintInfo> {
+        self.mints.get(mint).map(|info| *info)
+    }
+
+    pub fn insert(&self, mint: Pubkey, info: MintInfo) {
+        self.mints.insert(mint, info);
+    }
+
+    pub fn len(&self) -> usize {
+        self.mints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mints.is_empty()
+    }
+
+    /// Epoch used to pick the active transfer fee rate
+    pub fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::Relaxed)
+    }
+
+    pub fn set_epoch(&self, epoch: u64) {
+        self.epoch.fetch_max(epoch, Ordering::Relaxed);
+    }
+
+    /// Updates the cache from a `TokenInfoEvent`; other events only advance the epoch
+    pub fn on_event(&self, event: &dyn UnifiedEvent) {
+        if event.slot() > 0 {
+            self.set_epoch(self.epoch_schedule.get_epoch(event.slot()));
+        }
+        if let Some(e) = event.as_any().downcast_ref::<TokenInfoEvent>() {
+            self.insert(
+                e.pubkey,
+                MintInfo {
+                    decimals: e.decimals,
+                    token_program: e.owner,
+                    transfer_fee: e.transfer_fee,
+                    risk: e.risk,
+                },
+            );
+        }
+    }
+
+    /// Transfer fee withheld on a transfer of `gross` (0 for unknown or fee-less mints)
+    pub fn transfer_fee(&self, mint: &Pubkey, gross: u64) -> u64 {
+        gross - self.net_amount(mint, gross)
+    }
+
+    /// Token-2022 risk flags of a cached mint
+    pub fn risk(&self, mint: &Pubkey) -> Option<MintRiskFlags> {
+        self.get(mint).map(|info| info.risk)
+    }
+
+    /// Amount received for a transfer of `gross`
+    pub fn net_amount(&self, mint: &Pubkey, gross: u64) -> u64 {
+        match self.get(mint).and_then(|info| info.transfer_fee) {
+            Some(fee) => fee.net_amount(self.epoch(), gross),
+            None => gross,
+        }
+    }
+}
+
+#[cfg(feature = "transport")]
+mod rpc {
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::{MintInfo, MintInfoCache};
+    use crate::common::{AnyResult, SolanaRpcClient};
+    use crate::streaming::event_parser::common::EventMetadata;
+    use crate::streaming::event_parser::core::account_event_parser::{
+        AccountEventParser, TokenInfoEvent,
+    };
+    use crate::streaming::event_parser::UnifiedEvent;
+    use crate::streaming::grpc::AccountPretty;
+
+    impl MintInfoCache {
+        /// Fetches a mint account via RPC and caches it
+        pub async fn load(&self, rpc: &SolanaRpcClient, mint: &Pubkey) -> AnyResult<MintInfo> {
+            if let Some(info) = self.get(mint) {
+                return Ok(info);
+            }
+            let account = rpc.get_account(mint).await?;
+            let pretty = AccountPretty {
+                pubkey: *mint,
+                owner: account.owner,
+                lamports: account.lamports,
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data,
+                ..Default::default()
+            };
+            let event =
+                AccountEventParser::parse_token_account_event(&pretty, EventMetadata::default())
+                    .ok_or_else(|| anyhow::anyhow!("failed to parse mint account {}", mint))?;
+            let info = event
+                .as_any()
+                .downcast_ref::<TokenInfoEvent>()
+                .map(|e| MintInfo {
+                    decimals: e.decimals,
+                    token_program: e.owner,
+                    transfer_fee: e.transfer_fee,
+                    risk: e.risk,
+                })
+                .ok_or_else(|| anyhow::anyhow!("account {} is not a mint", mint))?;
+            self.insert(*mint, info);
+            Ok(info)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::analytics::TradeInfo;
+    use crate::streaming::event_parser::common::EventMetadata;
+    use crate::streaming::event_parser::core::account_event_parser::TransferFeeRate;
+    use solana_sdk::clock::DEFAULT_SLOTS_PER_EPOCH;
+
+    fn fee_mint(mint: Pubkey, slot: u64) -> TokenInfoEvent {
+        TokenInfoEvent {
+            metadata: EventMetadata { slot, ..Default::default() },
+            pubkey: mint,
+            decimals: 6,
+            transfer_fee: Some(TransferFeeInfo {
+                older: TransferFeeRate { epoch: 0, maximum_fee: u64::MAX, basis_points: 100 },
+                newer: TransferFeeRate { epoch: 10, maximum_fee: 1_000, basis_points: 500 },
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_transfer_fee_rate_follows_epoch() {
+        let cache = MintInfoCache::new();
+        let (mint, quote) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut trade = TradeInfo {
+            input_mint: mint,
+            output_mint: quote,
+            amount_in: 10_000,
+            amount_out: 500,
+            ..Default::default()
+        };
+
+        // 第 9 个 epoch 的最后一个 slot 仍使用旧费率
+        cache.on_event(&fee_mint(mint, 10 * DEFAULT_SLOTS_PER_EPOCH - 1));
+        assert_eq!(cache.epoch(), 9);
+        trade.apply_transfer_fees(&cache);
+        assert_eq!(trade.amount_in_net, 9_900);
+        assert_eq!(trade.amount_out_net, 500);
+
+        cache.on_event(&fee_mint(mint, 10 * DEFAULT_SLOTS_PER_EPOCH));
+        assert_eq!(cache.epoch(), 10);
+        trade.apply_transfer_fees(&cache);
+        assert_eq!(trade.amount_in_net, 9_500);
+    }
+
+    #[test]
+    fn test_transfer_fee_capped_at_maximum() {
+        let cache = MintInfoCache::new();
+        let mint = Pubkey::new_unique();
+        cache.on_event(&fee_mint(mint, 10 * DEFAULT_SLOTS_PER_EPOCH));
+
+        // 5% 为 50,000，超过 maximum_fee
+        let mut trade = TradeInfo {
+            input_mint: Pubkey::new_unique(),
+            output_mint: mint,
+            amount_in: 1,
+            amount_out: 1_000_000,
+            ..Default::default()
+        };
+        trade.apply_transfer_fees(&cache);
+        assert_eq!(trade.amount_out_net, 999_000);
+        assert_eq!(cache.transfer_fee(&mint, 1_000_000), 1_000);
+        assert_eq!(cache.transfer_fee(&mint, 10_000), 500);
+    }
+
+    #[test]
+    fn test_epoch_schedule_with_warmup() {
+        let cache = MintInfoCache::new().with_epoch_schedule(EpochSchedule::default());
+        cache.on_event(&fee_mint(Pubkey::new_unique(), 100));
+        // 预热期的 epoch 较短，不能按固定长度换算
+        assert_eq!(cache.epoch(), EpochSchedule::default().get_epoch(100));
+        assert_ne!(cache.epoch(), 100 / DEFAULT_SLOTS_PER_EPOCH);
+    }
+}
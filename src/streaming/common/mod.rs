@@ -1,15 +1,65 @@
-// 公用模块 - 包含流处理相关的通用功能
+// 公用模块 - 包含流处理相关的通用功能，连接/运行时相关部分需要 `transport` feature
+#[cfg(feature = "transport")]
 pub mod config;
+#[cfg(feature = "transport")]
 pub mod metrics;
 pub mod constants;
+#[cfg(feature = "transport")]
 pub mod subscription;
+#[cfg(feature = "transport")]
 pub mod event_processor;
 pub mod simd_utils;
+#[cfg(feature = "transport")]
+pub mod runtime;
+#[cfg(feature = "transport")]
+pub mod memory;
+#[cfg(feature = "transport")]
+pub mod credentials;
+pub mod mint_cache;
+pub mod conflation;
+pub mod express;
+pub mod slo;
+pub mod block_time;
+pub mod entry_index;
+pub mod watchlist;
+pub mod sampling;
+pub mod event_age;
+pub mod account_pruning;
+#[cfg(feature = "transport")]
+pub mod enrichment;
+#[cfg(feature = "transport")]
+pub mod callback_budget;
+#[cfg(feature = "transport")]
+pub mod clock_sync;
 
 // 重新导出主要类型
+#[cfg(feature = "transport")]
 pub use config::*;
+#[cfg(feature = "transport")]
 pub use metrics::*;
 pub use constants::*;
+#[cfg(feature = "transport")]
 pub use subscription::*;
+#[cfg(feature = "transport")]
 pub use event_processor::*;
-pub use simd_utils::*;
\ No newline at end of file
+pub use simd_utils::*;
+#[cfg(feature = "transport")]
+pub use runtime::*;
+#[cfg(feature = "transport")]
+pub use memory::*;
+#[cfg(feature = "transport")]
+pub use credentials::*;
+pub use mint_cache::*;
+pub use conflation::*;
+pub use express::*;
+pub use slo::*;
+pub use block_time::*;
+pub use entry_index::*;
+pub use watchlist::*;
+pub use sampling::*;
+pub use event_age::*;
+pub use account_pruning::*;
+#[cfg(feature = "transport")]
+pub use enrichment::*;
+#[cfg(feature = "transport")]
+pub use clock_sync::*;
\ No newline at end of file
@@ -0,0 +1,251 @@
+use std::cell::Cell;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use futures::future::{abortable, AbortHandle};
+use serde::{Deserialize, Serialize};
+use thread_priority::{ThreadPriority, ThreadPriorityValue};
+
+/// Name of the thread started by [`RuntimeConfig::spawn_reader`]
+pub const READER_THREAD_NAME: &str = "stream-reader";
+
+thread_local! {
+    // 当前线程是否已按 worker 设置绑核与优先级
+    static WORKER_TUNED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runtime and thread placement tuning for the latency-critical path
+///
+/// All fields are optional; the default leaves scheduling entirely to the OS,
+/// which matches the previous behaviour.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeConfig {
+    /// Core to pin the gRPC/shred reader runtime to (see [`RuntimeConfig::spawn_reader`])
+    pub reader_core: Option<usize>,
+    /// Core to pin the dedicated parser thread (Block backpressure mode) to
+    pub parser_core: Option<usize>,
+    /// Cores assigned round-robin to the parser runtime's worker threads (not its blocking pool)
+    pub worker_cores: Vec<usize>,
+    /// Number of parser runtime worker threads (default: available parallelism)
+    pub worker_threads: Option<usize>,
+    /// Priority (0-99) applied to the reader/parser threads
+    pub thread_priority: Option<u8>,
+    /// Use a current-thread runtime for parsing instead of a multi-thread one
+    pub current_thread_runtime: bool,
+}
+
+impl RuntimeConfig {
+    /// Pins the calling thread to `parser_core` and applies `thread_priority`
+    pub fn apply_parser_thread(&self) {
+        if let Some(core) = self.parser_core {
+            if !pin_current_thread(core) {
+                log::warn!("Failed to pin parser thread to core {}", core);
+            }
+        }
+        self.apply_priority();
+    }
+
+    /// Builds the runtime used by the dedicated parser thread
+    pub fn build_parser_runtime(&self) -> std::io::Result<tokio::runtime::Runtime> {
+        self.build_parser_runtime_with(|| {})
+    }
+
+    /// [`build_parser_runtime`](Self::build_parser_runtime), calling `on_tuned` on each
+    /// worker thread once it is pinned
+    fn build_parser_runtime_with(
+        &self,
+        on_tuned: impl Fn() + Send + Sync + 'static,
+    ) -> std::io::Result<tokio::runtime::Runtime> {
+        if self.current_thread_runtime {
+            return tokio::runtime::Builder::new_current_thread().enable_all().build();
+        }
+
+        let worker_threads = self
+            .worker_threads
+            .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4)); // 如果获取失败则回退到4个线程
+
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        builder.worker_threads(worker_threads).enable_all();
+
+        if !self.worker_cores.is_empty() || self.thread_priority.is_some() {
+            let cores = Arc::new(self.worker_cores.clone());
+            let next = Arc::new(AtomicUsize::new(0));
+            let priority = self.thread_priority;
+            // on_thread_start 也会作用于 blocking 线程池；只有 worker 线程经调度器 park，
+            // 因此在首次 park 时设置，blocking 线程保持由系统调度
+            builder.on_thread_park(move || {
+                if WORKER_TUNED.with(|tuned| tuned.replace(true)) {
+                    return;
+                }
+                if !cores.is_empty() {
+                    let core = cores[next.fetch_add(1, Ordering::Relaxed) % cores.len()];
+                    pin_current_thread(core);
+                }
+                if let Some(priority) = priority {
+                    set_current_thread_priority(priority);
+                }
+                on_tuned();
+            });
+        }
+
+        builder.build()
+    }
+
+    /// Builds a current-thread runtime pinned to `reader_core`
+    ///
+    /// [`spawn_reader`](Self::spawn_reader) drives the subscription future with it on a
+    /// dedicated thread, so the stream reader never shares a core with the parser workers.
+    pub fn build_reader_runtime(&self) -> std::io::Result<tokio::runtime::Runtime> {
+        if let Some(core) = self.reader_core {
+            if !pin_current_thread(core) {
+                log::warn!("Failed to pin reader thread to core {}", core);
+            }
+        }
+        self.apply_priority();
+        tokio::runtime::Builder::new_current_thread().enable_all().build()
+    }
+
+    /// Spawns a stream reader future
+    ///
+    /// With `reader_core` set the future runs on a dedicated thread driving
+    /// [`build_reader_runtime`](Self::build_reader_runtime), together with any tasks it
+    /// spawns; otherwise it is spawned on the current runtime. Aborting the returned
+    /// handle stops the reader in both cases.
+    pub fn spawn_reader<F>(&self, future: F) -> std::io::Result<tokio::task::JoinHandle<()>>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        if self.reader_core.is_none() {
+            return Ok(tokio::spawn(future));
+        }
+
+        let (future, abort_handle) = abortable(future);
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+        let (done_tx, done_rx) = tokio::sync::oneshot::channel::<()>();
+        let config = self.clone();
+        std::thread::Builder::new().name(READER_THREAD_NAME.to_string()).spawn(move || {
+            let runtime = match config.build_reader_runtime() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+            let _ = ready_tx.send(Ok(()));
+            let _ = runtime.block_on(future);
+            drop(done_tx);
+        })?;
+        ready_rx.recv().map_err(|_| std::io::Error::other("reader thread exited"))??;
+
+        Ok(tokio::spawn(async move {
+            // 句柄被 abort 时随之终止读取线程上的任务
+            let _abort = AbortOnDrop(abort_handle);
+            let _ = done_rx.await;
+        }))
+    }
+
+    fn apply_priority(&self) {
+        if let Some(priority) = self.thread_priority {
+            set_current_thread_priority(priority);
+        }
+    }
+}
+
+struct AbortOnDrop(AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Pins the calling thread to the given core id, returns false if the core does not exist
+pub fn pin_current_thread(core_id: usize) -> bool {
+    core_affinity::get_core_ids()
+        .and_then(|ids| ids.into_iter().find(|id| id.id == core_id))
+        .map(core_affinity::set_for_current)
+        .unwrap_or(false)
+}
+
+/// Sets the calling thread's priority on a 0-99 scale, returns false on failure
+pub fn set_current_thread_priority(priority: u8) -> bool {
+    let value = match ThreadPriorityValue::try_from(priority.min(99)) {
+        Ok(value) => value,
+        Err(e) => {
+            log::warn!("Invalid thread priority {}: {}", priority, e);
+            return false;
+        }
+    };
+    match thread_priority::set_current_thread_priority(ThreadPriority::Crossplatform(value)) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("Failed to set thread priority {}: {:?}", priority, e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::runtime::RuntimeFlavor;
+
+    #[test]
+    fn test_parser_runtime_tunes_workers_only() {
+        let config =
+            RuntimeConfig { worker_cores: vec![0], worker_threads: Some(2), ..Default::default() };
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runtime = config
+            .build_parser_runtime_with(move || {
+                let _ = tx.send(std::thread::current().id());
+            })
+            .unwrap();
+        assert_eq!(runtime.handle().runtime_flavor(), RuntimeFlavor::MultiThread);
+        // 空闲的 worker 都会 park，等两个 worker 都完成设置后再检查
+        let tuned: Vec<_> =
+            (0..2).map(|_| rx.recv_timeout(Duration::from_secs(10)).unwrap()).collect();
+        assert_ne!(tuned[0], tuned[1]);
+
+        let (worker, blocking) = runtime.block_on(async {
+            let worker =
+                tokio::spawn(async { (std::thread::current().id(), WORKER_TUNED.with(Cell::get)) });
+            let blocking = tokio::task::spawn_blocking(|| WORKER_TUNED.with(Cell::get));
+            (worker.await.unwrap(), blocking.await.unwrap())
+        });
+        assert!(tuned.contains(&worker.0));
+        assert!(worker.1);
+        assert!(!blocking);
+
+        let config = RuntimeConfig { current_thread_runtime: true, ..Default::default() };
+        let runtime = config.build_parser_runtime().unwrap();
+        assert_eq!(runtime.handle().runtime_flavor(), RuntimeFlavor::CurrentThread);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_reader_on_dedicated_thread() {
+        let config = RuntimeConfig { reader_core: Some(0), ..Default::default() };
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        config
+            .spawn_reader(async move {
+                let _ = tx.send(std::thread::current().name().map(str::to_string));
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(rx.await.unwrap().as_deref(), Some(READER_THREAD_NAME));
+
+        // abort 句柄后读取线程上的任务被丢弃
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let handle = config
+            .spawn_reader(async move {
+                let _tx = tx;
+                futures::future::pending::<()>().await;
+            })
+            .unwrap();
+        handle.abort();
+        assert!(rx.await.is_err());
+    }
+}
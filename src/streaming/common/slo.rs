@@ -0,0 +1,346 @@
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::streaming::event_parser::common::high_performance_clock::{system_clock, Clock};
+use crate::streaming::event_parser::common::EventType;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default evaluation window for latency SLOs
+pub const DEFAULT_SLO_WINDOW: Duration = Duration::from_secs(60);
+/// Windows with fewer samples are not evaluated
+pub const DEFAULT_SLO_MIN_SAMPLES: usize = 20;
+/// Interval at which the stream client closes due windows without waiting for events
+pub const DEFAULT_SLO_TICK_MS: u64 = 500;
+
+/// Latency objective on `handle_us` (receive → callback) for a set of event types
+///
+/// ```rust,ignore
+/// // CPMM swaps handled within 5ms at p99
+/// LatencySlo::new("cpmm-swap", 5_000)
+///     .for_event_types([EventType::RaydiumCpmmSwapBaseInput, EventType::RaydiumCpmmSwapBaseOutput])
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencySlo {
+    pub name: String,
+    /// Event types covered, empty = all events
+    pub event_types: Vec<EventType>,
+    /// Percentile checked, 0.0 - 1.0
+    pub percentile: f64,
+    pub threshold_us: i64,
+    pub window: Duration,
+    pub min_samples: usize,
+    /// Report a breach when a window that follows traffic closes without any sample,
+    /// i.e. the covered events stopped arriving (default: true)
+    #[serde(default = "default_alert_on_stall")]
+    pub alert_on_stall: bool,
+}
+
+fn default_alert_on_stall() -> bool {
+    true
+}
+
+impl LatencySlo {
+    /// p99 over [`DEFAULT_SLO_WINDOW`] for all event types
+    pub fn new(name: impl Into<String>, threshold_us: i64) -> Self {
+        Self {
+            name: name.into(),
+            event_types: Vec::new(),
+            percentile: 0.99,
+            threshold_us,
+            window: DEFAULT_SLO_WINDOW,
+            min_samples: DEFAULT_SLO_MIN_SAMPLES,
+            alert_on_stall: true,
+        }
+    }
+
+    pub fn for_event_types(mut self, event_types: impl IntoIterator<Item = EventType>) -> Self {
+        self.event_types = event_types.into_iter().collect();
+        self
+    }
+
+    pub fn with_percentile(mut self, percentile: f64) -> Self {
+        self.percentile = percentile.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples;
+        self
+    }
+
+    /// Whether an empty window after traffic counts as a breach, off for event types
+    /// that are naturally bursty
+    pub fn with_stall_alerts(mut self, alert_on_stall: bool) -> Self {
+        self.alert_on_stall = alert_on_stall;
+        self
+    }
+
+    fn matches(&self, event_type: &EventType) -> bool {
+        self.event_types.is_empty() || self.event_types.contains(event_type)
+    }
+}
+
+/// Alarm raised when a window's percentile latency exceeds the SLO threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SloBreach {
+    pub slo: String,
+    pub percentile: f64,
+    /// Percentile latency, or the window length for a stall
+    pub observed_us: i64,
+    pub threshold_us: i64,
+    pub samples: usize,
+    pub window: Duration,
+    /// No covered event arrived during the window
+    #[serde(default)]
+    pub stalled: bool,
+}
+
+/// Handler invoked for every [`SloBreach`]
+#[derive(Clone)]
+pub struct SloAlarmHandler(pub Arc<dyn Fn(&SloBreach) + Send + Sync>);
+
+impl SloAlarmHandler {
+    pub fn new(f: impl Fn(&SloBreach) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+impl Debug for SloAlarmHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SloAlarmHandler")
+    }
+}
+
+/// SLO settings of a stream client
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SloConfig {
+    pub slos: Vec<LatencySlo>,
+    /// Called on breach in addition to the warning log (not serialized)
+    #[serde(skip)]
+    pub on_breach: Option<SloAlarmHandler>,
+}
+
+struct SloWindow {
+    /// 窗口开始时间（微秒）
+    started_us: i64,
+    samples: Vec<i64>,
+    /// 上一个窗口有样本，空窗口才视为停滞
+    had_traffic: bool,
+}
+
+/// Evaluates latency SLOs over tumbling windows
+///
+/// Windows close on [`record`](Self::record) and on [`tick`](Self::tick); the stream
+/// client calls `tick` from a timer so a stalled stream is still evaluated, and counts
+/// the breaches in its metrics (`slo_breaches`).
+pub struct SloMonitor {
+    config: SloConfig,
+    windows: Vec<Mutex<SloWindow>>,
+    breaches: Vec<AtomicU64>,
+    clock: Arc<dyn Clock>,
+}
+
+impl SloMonitor {
+    pub fn new(config: SloConfig) -> Self {
+        Self::with_clock(config, system_clock())
+    }
+
+    /// Monitor whose windows follow the given time source
+    pub fn with_clock(config: SloConfig, clock: Arc<dyn Clock>) -> Self {
+        let now = clock.now_micros();
+        let windows = config
+            .slos
+            .iter()
+            .map(|_| {
+                Mutex::new(SloWindow { started_us: now, samples: Vec::new(), had_traffic: false })
+            })
+            .collect();
+        let breaches = config.slos.iter().map(|_| AtomicU64::new(0)).collect();
+        Self { config, windows, breaches, clock }
+    }
+
+    /// Records the event's handle time; returns breaches of windows that just closed
+    pub fn record(&self, event: &dyn UnifiedEvent) -> Vec<SloBreach> {
+        let event_type = event.event_type();
+        let handle_us = event.handle_us();
+        let mut breaches = Vec::new();
+        for (idx, slo) in self.config.slos.iter().enumerate() {
+            if !slo.matches(&event_type) {
+                continue;
+            }
+            let mut window = self.windows[idx].lock();
+            window.samples.push(handle_us);
+            let breach = self.close_if_due(slo, &mut window);
+            drop(window);
+            breaches.extend(breach.map(|breach| self.report(idx, breach)));
+        }
+        breaches
+    }
+
+    /// Closes every window that has run its length, including windows without events
+    pub fn tick(&self) -> Vec<SloBreach> {
+        let mut breaches = Vec::new();
+        for (idx, slo) in self.config.slos.iter().enumerate() {
+            let breach = self.close_if_due(slo, &mut self.windows[idx].lock());
+            breaches.extend(breach.map(|breach| self.report(idx, breach)));
+        }
+        breaches
+    }
+
+    fn close_if_due(&self, slo: &LatencySlo, window: &mut SloWindow) -> Option<SloBreach> {
+        let now = self.clock.now_micros();
+        let elapsed_us = now - window.started_us;
+        if elapsed_us < slo.window.as_micros() as i64 {
+            return None;
+        }
+        let samples = std::mem::take(&mut window.samples);
+        window.started_us = now;
+        let had_traffic = std::mem::replace(&mut window.had_traffic, !samples.is_empty());
+        if samples.is_empty() {
+            return (had_traffic && slo.alert_on_stall).then(|| SloBreach {
+                slo: slo.name.clone(),
+                percentile: slo.percentile,
+                observed_us: elapsed_us,
+                threshold_us: slo.threshold_us,
+                samples: 0,
+                window: slo.window,
+                stalled: true,
+            });
+        }
+        Self::evaluate(slo, samples)
+    }
+
+    fn report(&self, idx: usize, breach: SloBreach) -> SloBreach {
+        self.breaches[idx].fetch_add(1, Ordering::Relaxed);
+        if breach.stalled {
+            log::warn!(
+                "Latency SLO {} breached: no events for {}us",
+                breach.slo,
+                breach.observed_us
+            );
+        } else {
+            log::warn!(
+                "Latency SLO {} breached: p{} = {}us > {}us over {} samples",
+                breach.slo,
+                breach.percentile * 100.0,
+                breach.observed_us,
+                breach.threshold_us,
+                breach.samples
+            );
+        }
+        if let Some(handler) = &self.config.on_breach {
+            (handler.0)(&breach);
+        }
+        breach
+    }
+
+    /// Number of breached windows per SLO name
+    pub fn breach_counts(&self) -> Vec<(String, u64)> {
+        self.config
+            .slos
+            .iter()
+            .zip(&self.breaches)
+            .map(|(slo, count)| (slo.name.clone(), count.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    fn evaluate(slo: &LatencySlo, mut samples: Vec<i64>) -> Option<SloBreach> {
+        if samples.is_empty() || samples.len() < slo.min_samples {
+            return None;
+        }
+        let rank =
+            ((samples.len() as f64 * slo.percentile).ceil() as usize).clamp(1, samples.len());
+        let (_, observed, _) = samples.select_nth_unstable(rank - 1);
+        let observed_us = *observed;
+        (observed_us > slo.threshold_us).then(|| SloBreach {
+            slo: slo.name.clone(),
+            percentile: slo.percentile,
+            observed_us,
+            threshold_us: slo.threshold_us,
+            samples: samples.len(),
+            window: slo.window,
+            stalled: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::high_performance_clock::MockClock;
+    use crate::streaming::event_parser::protocols::block::block_meta_event::BlockMetaEvent;
+
+    fn event(handle_us: i64) -> BlockMetaEvent {
+        let mut event = BlockMetaEvent::new(1, String::new(), 0, 0);
+        event.set_handle_us(handle_us);
+        event
+    }
+
+    fn monitor(clock: &Arc<MockClock>) -> SloMonitor {
+        let slo = LatencySlo::new("block-meta", 1_000)
+            .for_event_types([EventType::BlockMeta])
+            .with_window(Duration::from_secs(1))
+            .with_min_samples(2);
+        SloMonitor::with_clock(SloConfig { slos: vec![slo], on_breach: None }, clock.clone())
+    }
+
+    #[test]
+    fn test_window_rollover_and_breach() {
+        let clock = Arc::new(MockClock::new(0));
+        let monitor = monitor(&clock);
+
+        assert!(monitor.record(&event(500)).is_empty());
+        clock.advance(Duration::from_millis(1_100));
+        // 窗口关闭：两个样本中 p99 为 500us，未超阈值
+        assert!(monitor.record(&event(600)).is_empty());
+
+        for _ in 0..3 {
+            assert!(monitor.record(&event(5_000)).is_empty());
+        }
+        clock.advance(Duration::from_millis(1_000));
+        let breaches = monitor.tick();
+        assert_eq!(breaches.len(), 1);
+        assert_eq!((breaches[0].observed_us, breaches[0].samples), (5_000, 3));
+        assert!(!breaches[0].stalled);
+        assert_eq!(monitor.breach_counts(), vec![("block-meta".to_string(), 1)]);
+
+        // 新窗口从关闭时开始，未满一个窗口不会再次评估
+        clock.advance(Duration::from_millis(500));
+        assert!(monitor.tick().is_empty());
+    }
+
+    #[test]
+    fn test_stalled_stream_reported_by_tick() {
+        let clock = Arc::new(MockClock::new(0));
+        let monitor = monitor(&clock);
+
+        // 从未有过流量时空窗口不算停滞
+        clock.advance(Duration::from_secs(1));
+        assert!(monitor.tick().is_empty());
+
+        monitor.record(&event(100));
+        clock.advance(Duration::from_secs(1));
+        assert!(monitor.tick().is_empty());
+        clock.advance(Duration::from_secs(1));
+        let breaches = monitor.tick();
+        assert_eq!(breaches.len(), 1);
+        assert!(breaches[0].stalled);
+        assert_eq!(breaches[0].observed_us, 1_000_000);
+
+        // 同一次停滞只报告一次
+        clock.advance(Duration::from_secs(1));
+        assert!(monitor.tick().is_empty());
+        assert_eq!(monitor.breach_counts(), vec![("block-meta".to_string(), 1)]);
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::BTreeSet;
+
+use parking_lot::RwLock;
+use solana_sdk::pubkey::Pubkey;
+
+/// Name of the transaction filter carrying the watched mints
+pub const WATCHLIST_FILTER_NAME: &str = "watchlist";
+
+/// Mints watched at runtime
+///
+/// Shared by a live client (see `YellowstoneGrpc::watch_mints`), which subscribes to
+/// transactions touching any watched mint, and by user-side consumers such as
+/// detectors that only care about watched tokens.
+#[derive(Debug, Default)]
+pub struct Watchlist {
+    mints: RwLock<BTreeSet<Pubkey>>,
+}
+
+impl Watchlist {
+    pub fn new(mints: impl IntoIterator<Item = Pubkey>) -> Self {
+        Self { mints: RwLock::new(mints.into_iter().collect()) }
+    }
+
+    /// Adds mints, returns how many were not watched yet
+    pub fn add(&self, mints: impl IntoIterator<Item = Pubkey>) -> usize {
+        let mut watched = self.mints.write();
+        mints.into_iter().filter(|mint| watched.insert(*mint)).count()
+    }
+
+    /// Removes mints, returns how many were watched
+    pub fn remove(&self, mints: impl IntoIterator<Item = Pubkey>) -> usize {
+        let mut watched = self.mints.write();
+        mints.into_iter().filter(|mint| watched.remove(mint)).count()
+    }
+
+    pub fn contains(&self, mint: &Pubkey) -> bool {
+        self.mints.read().contains(mint)
+    }
+
+    /// Watched mints in a stable order
+    pub fn mints(&self) -> Vec<Pubkey> {
+        self.mints.read().iter().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.mints.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mints.read().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchlist_add_remove() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let watchlist = Watchlist::default();
+        assert_eq!(watchlist.add([a, b, a]), 2);
+        assert!(watchlist.contains(&a));
+        assert_eq!(watchlist.remove([a, Pubkey::new_unique()]), 1);
+        assert_eq!(watchlist.mints(), vec![b]);
+    }
+}
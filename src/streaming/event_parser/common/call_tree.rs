@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+use solana_transaction_status::InnerInstructions;
+
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// 顶层指令的调用深度，CPI 每层加一
+pub const TRANSACTION_LEVEL_STACK_HEIGHT: u32 = 1;
+
+/// One instruction invocation in a transaction's CPI call tree
+#[derive(Debug, Clone)]
+pub struct CallNode {
+    pub program_id: Pubkey,
+    pub outer_index: i64,
+    /// None for top-level instructions
+    pub inner_index: Option<i64>,
+    pub stack_height: u32,
+    /// Index of the invoking instruction in [`CallTree::nodes`]
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    /// Parsed events of this instruction, see [`CallTree::attach`]
+    pub events: Vec<Box<dyn UnifiedEvent>>,
+}
+
+/// CPI call tree of a transaction (program → invoked programs)
+///
+/// Rebuilt from inner instruction stack heights, so consumers can tell a swap sent
+/// directly to the DEX from one routed through an aggregator or a bot program. Inner
+/// instructions without a stack height (old transactions) are treated as direct
+/// children of their top-level instruction.
+#[derive(Debug, Clone, Default)]
+pub struct CallTree {
+    nodes: Vec<CallNode>,
+    roots: Vec<usize>,
+    index: HashMap<(i64, Option<i64>), usize>,
+}
+
+impl CallTree {
+    /// `accounts` must include addresses loaded from lookup tables
+    pub fn from_versioned_transaction(
+        transaction: &VersionedTransaction,
+        accounts: &[Pubkey],
+        inner_instructions: &[InnerInstructions],
+    ) -> Self {
+        let program = |idx: u8| accounts.get(idx as usize).copied().unwrap_or_default();
+        let mut tree = Self::default();
+        for (outer_index, instruction) in transaction.message.instructions().iter().enumerate() {
+            let outer_index = outer_index as i64;
+            tree.push(program(instruction.program_id_index), outer_index, None, None);
+            let Some(inner) =
+                inner_instructions.iter().find(|inner| inner.index as i64 == outer_index)
+            else {
+                continue;
+            };
+            for (inner_index, inner_instruction) in inner.instructions.iter().enumerate() {
+                tree.push(
+                    program(inner_instruction.instruction.program_id_index),
+                    outer_index,
+                    Some(inner_index as i64),
+                    inner_instruction.stack_height,
+                );
+            }
+        }
+        tree
+    }
+
+    /// `accounts` must include addresses loaded from lookup tables
+    pub fn from_grpc_transaction(
+        instructions: &[yellowstone_grpc_proto::prelude::CompiledInstruction],
+        accounts: &[Pubkey],
+        inner_instructions: &[yellowstone_grpc_proto::prelude::InnerInstructions],
+    ) -> Self {
+        let program = |idx: u32| accounts.get(idx as usize).copied().unwrap_or_default();
+        let mut tree = Self::default();
+        for (outer_index, instruction) in instructions.iter().enumerate() {
+            let outer_index = outer_index as i64;
+            tree.push(program(instruction.program_id_index), outer_index, None, None);
+            let Some(inner) =
+                inner_instructions.iter().find(|inner| inner.index as i64 == outer_index)
+            else {
+                continue;
+            };
+            for (inner_index, inner_instruction) in inner.instructions.iter().enumerate() {
+                tree.push(
+                    program(inner_instruction.program_id_index),
+                    outer_index,
+                    Some(inner_index as i64),
+                    inner_instruction.stack_height,
+                );
+            }
+        }
+        tree
+    }
+
+    /// Appends instructions in execution order, top-level before its inner instructions
+    fn push(
+        &mut self,
+        program_id: Pubkey,
+        outer_index: i64,
+        inner_index: Option<i64>,
+        stack_height: Option<u32>,
+    ) {
+        let id = self.nodes.len();
+        let (stack_height, parent) = match inner_index {
+            None => {
+                self.roots.push(id);
+                (TRANSACTION_LEVEL_STACK_HEIGHT, None)
+            }
+            Some(_) => {
+                let stack_height =
+                    stack_height.unwrap_or(TRANSACTION_LEVEL_STACK_HEIGHT + 1).max(2);
+                // 从上一条指令向上回溯，找到深度更浅的最近调用者
+                let mut parent = id.checked_sub(1);
+                while let Some(p) = parent {
+                    if self.nodes[p].stack_height < stack_height {
+                        break;
+                    }
+                    parent = self.nodes[p].parent;
+                }
+                (stack_height, parent)
+            }
+        };
+        if let Some(p) = parent {
+            self.nodes[p].children.push(id);
+        }
+        self.index.insert((outer_index, inner_index), id);
+        self.nodes.push(CallNode {
+            program_id,
+            outer_index,
+            inner_index,
+            stack_height,
+            parent,
+            children: Vec::new(),
+            events: Vec::new(),
+        });
+    }
+
+    pub fn nodes(&self) -> &[CallNode] {
+        &self.nodes
+    }
+
+    /// Top-level instructions
+    pub fn roots(&self) -> impl Iterator<Item = &CallNode> {
+        self.roots.iter().map(|&id| &self.nodes[id])
+    }
+
+    pub fn find(&self, outer_index: i64, inner_index: Option<i64>) -> Option<usize> {
+        self.index.get(&(outer_index, inner_index)).copied()
+    }
+
+    /// Node of the instruction that produced the event
+    pub fn node_of(&self, event: &dyn UnifiedEvent) -> Option<&CallNode> {
+        self.find(event.outer_index(), event.inner_index()).map(|id| &self.nodes[id])
+    }
+
+    /// Attaches a parsed event of this transaction to its node, false if none matches
+    pub fn attach(&mut self, event: Box<dyn UnifiedEvent>) -> bool {
+        match self.find(event.outer_index(), event.inner_index()) {
+            Some(id) => {
+                self.nodes[id].events.push(event);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Callers of a node, innermost first, ending at the top-level instruction
+    pub fn ancestors(&self, id: usize) -> impl Iterator<Item = &CallNode> {
+        std::iter::successors(self.nodes.get(id).and_then(|node| node.parent), |&p| {
+            self.nodes[p].parent
+        })
+        .map(|p| &self.nodes[p])
+    }
+
+    /// Program of the top-level instruction the event was executed under
+    pub fn root_program(&self, event: &dyn UnifiedEvent) -> Option<Pubkey> {
+        let id = self.find(event.outer_index(), event.inner_index())?;
+        Some(self.ancestors(id).last().unwrap_or(&self.nodes[id]).program_id)
+    }
+
+    /// Whether the event's instruction was invoked directly by the transaction
+    pub fn is_direct(&self, event: &dyn UnifiedEvent) -> bool {
+        self.node_of(event).is_some_and(|node| node.parent.is_none())
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::EventMetadata;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    #[test]
+    fn test_call_tree_nesting() {
+        let (aggregator, dex, token) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut tree = CallTree::default();
+        tree.push(dex, 0, None, None);
+        tree.push(token, 0, Some(0), Some(2));
+        tree.push(aggregator, 1, None, None);
+        tree.push(dex, 1, Some(0), Some(2));
+        tree.push(token, 1, Some(1), Some(3));
+        tree.push(token, 1, Some(2), Some(3));
+        tree.push(token, 1, Some(3), Some(2));
+
+        assert_eq!(tree.roots().count(), 2);
+        let dex_node = tree.find(1, Some(0)).unwrap();
+        assert_eq!(tree.nodes()[dex_node].children.len(), 2);
+        assert_eq!(tree.nodes()[tree.find(1, Some(3)).unwrap()].parent, tree.find(1, None));
+
+        let swap = |outer_index, inner_index| RaydiumCpmmSwapEvent {
+            metadata: EventMetadata { outer_index, inner_index, ..Default::default() },
+            ..Default::default()
+        };
+        assert!(tree.is_direct(&swap(0, None)));
+        assert!(!tree.is_direct(&swap(1, Some(0))));
+        assert_eq!(tree.root_program(&swap(1, Some(0))), Some(aggregator));
+        assert!(tree.attach(Box::new(swap(1, Some(0)))));
+        assert_eq!(tree.nodes()[dex_node].events.len(), 1);
+    }
+}
@@ -0,0 +1,174 @@
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    io::{Error, ErrorKind, Read, Write},
+    sync::LazyLock,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use parking_lot::RwLock;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Default)]
+struct CustomNames {
+    ids: HashMap<&'static str, u32>,
+    names: Vec<&'static str>,
+}
+
+static CUSTOM_NAMES: LazyLock<RwLock<CustomNames>> =
+    LazyLock::new(|| RwLock::new(CustomNames::default()));
+
+/// Interned name of a config-defined protocol or event type
+///
+/// `ProtocolType::Custom` and `EventType::Custom` carry this instead of a `String`, so
+/// comparing and hashing them is an integer operation. Names are registered explicitly
+/// with [`CustomId::intern`] (by the protocol config loader and the CEX feed adapters)
+/// and live for the process lifetime. IDs are process-local: serde and borsh write the
+/// name, and reading one back only resolves names that are already registered, so
+/// decoding untrusted input never grows the registry.
+#[derive(Clone, Copy)]
+pub struct CustomId {
+    id: u32,
+    name: &'static str,
+}
+
+impl CustomId {
+    /// Returns the ID of `name`, registering it on first use
+    ///
+    /// Each new name is leaked for the process lifetime; call this for names that come
+    /// from code or loaded configs, never for names read from recorded data.
+    pub fn intern(name: &str) -> Self {
+        if let Some(id) = Self::lookup(name) {
+            return id;
+        }
+        let mut registry = CUSTOM_NAMES.write();
+        // 获取写锁期间可能已被其他线程注册
+        if let Some(id) = registry.ids.get(name) {
+            return Self { id: *id, name: registry.names[*id as usize] };
+        }
+        let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+        let id = registry.names.len() as u32;
+        registry.names.push(name);
+        registry.ids.insert(name, id);
+        Self { id, name }
+    }
+
+    /// ID of an already registered name
+    pub fn lookup(name: &str) -> Option<Self> {
+        let registry = CUSTOM_NAMES.read();
+        registry.ids.get(name).map(|id| Self { id: *id, name: registry.names[*id as usize] })
+    }
+
+    pub fn id(self) -> u32 {
+        self.id
+    }
+
+    pub fn name(self) -> &'static str {
+        self.name
+    }
+
+    fn unregistered(name: &str) -> String {
+        format!("unregistered custom name `{}`", name)
+    }
+}
+
+impl PartialEq for CustomId {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for CustomId {}
+
+impl Hash for CustomId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl PartialOrd for CustomId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CustomId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl From<&str> for CustomId {
+    fn from(name: &str) -> Self {
+        Self::intern(name)
+    }
+}
+
+impl fmt::Display for CustomId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl fmt::Debug for CustomId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.name(), f)
+    }
+}
+
+impl Serialize for CustomId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::lookup(&name).ok_or_else(|| serde::de::Error::custom(Self::unregistered(&name)))
+    }
+}
+
+impl BorshSerialize for CustomId {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BorshSerialize::serialize(self.name(), writer)
+    }
+}
+
+impl BorshDeserialize for CustomId {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let name = String::deserialize_reader(reader)?;
+        Self::lookup(&name)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, Self::unregistered(&name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_id_interning() {
+        let id = CustomId::intern("test_custom_id_swap");
+        assert_eq!(CustomId::intern("test_custom_id_swap"), id);
+        assert_ne!(CustomId::intern("test_custom_id_deposit"), id);
+        assert_eq!(id.to_string(), "test_custom_id_swap");
+        assert_eq!(format!("{:?}", id), "\"test_custom_id_swap\"");
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(serde_json::from_str::<CustomId>(&json).unwrap(), id);
+        let bytes = borsh::to_vec(&id).unwrap();
+        assert_eq!(CustomId::try_from_slice(&bytes).unwrap(), id);
+    }
+
+    #[test]
+    fn test_custom_id_decode_does_not_register() {
+        let json = serde_json::to_string("test_custom_id_unregistered").unwrap();
+        assert!(serde_json::from_str::<CustomId>(&json).is_err());
+        let bytes = borsh::to_vec("test_custom_id_unregistered").unwrap();
+        assert!(CustomId::try_from_slice(&bytes).is_err());
+        assert!(CustomId::lookup("test_custom_id_unregistered").is_none());
+    }
+}
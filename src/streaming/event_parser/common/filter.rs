@@ -1,13 +1,21 @@
 use crate::streaming::event_parser::common::{
-    types::EventType, ACCOUNT_EVENT_TYPES, BLOCK_EVENT_TYPES,
+    types::{EventCategory, EventType},
+    ACCOUNT_EVENT_TYPES, BLOCK_EVENT_TYPES,
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct EventTypeFilter {
     pub include: Vec<EventType>,
 }
 
 impl EventTypeFilter {
+    /// Includes every built-in event type of the given categories
+    pub fn categories(categories: &[EventCategory]) -> Self {
+        Self { include: EventType::iter().filter(|e| categories.contains(&e.category())).collect() }
+    }
+
     pub fn include_transaction_event(&self) -> bool {
         self.include
             .iter()
@@ -22,3 +30,31 @@ impl EventTypeFilter {
         self.include.iter().any(|event| BLOCK_EVENT_TYPES.contains(event))
     }
 }
+
+#[cfg(all(test, feature = "transport"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::Protocol;
+    use crate::streaming::yellowstone_grpc::TransactionFilter;
+
+    #[test]
+    fn test_filters_round_trip() {
+        let filter = EventTypeFilter {
+            include: vec![EventType::RaydiumCpmmSwapBaseInput, EventType::BlockMeta],
+        };
+        let json = serde_json::to_string(&filter).unwrap();
+        let decoded: EventTypeFilter = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.include, filter.include);
+
+        let filter = TransactionFilter {
+            account_include: vec!["CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C".to_string()],
+            protocols: vec![Protocol::RaydiumCpmm],
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&filter).unwrap();
+        let decoded: TransactionFilter = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.account_include, filter.account_include);
+        assert_eq!(decoded.protocols, filter.protocols);
+        assert!(decoded.account_exclude.is_empty());
+    }
+}
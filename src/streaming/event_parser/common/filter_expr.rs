@@ -0,0 +1,390 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::common::AnyResult;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Post-parse event predicate written as a string expression
+///
+/// ```text
+/// protocol == "RaydiumCpmm" && (amount_in > 1e9 || event_type == "RaydiumCpmmSwapBaseOutput")
+/// ```
+///
+/// Supports `&&`, `||`, `!`, parentheses and `== != > >= < <=` between fields and
+/// string / number / `true` / `false` / `null` literals. Fields are looked up in
+/// `event_type`, `protocol`, `signature`, `slot`, `transaction_index`, `recv_us` and
+/// `handle_us`, then in the event's JSON (`UnifiedEvent::to_json`) and its `metadata`;
+/// dotted paths such as `swap_data.from_amount` reach nested fields. Missing fields are
+/// `null`, so `!is_bot` holds for events without an `is_bot` field. Pubkeys and
+/// signatures compare as base58 strings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct EventFilterExpr {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CompareOp, Operand),
+    Truthy(Operand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Field(Vec<String>),
+    Literal(Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    Not,
+    And,
+    Or,
+    Compare(CompareOp),
+    Number(f64),
+    Str(String),
+    Ident(String),
+}
+
+impl EventFilterExpr {
+    pub fn parse(source: &str) -> AnyResult<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens: &tokens, pos: 0 };
+        let expr = parser.or()?;
+        if parser.pos < tokens.len() {
+            bail!("unexpected token {:?} in filter expression", tokens[parser.pos]);
+        }
+        Ok(Self { source: source.to_string(), expr })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluates the expression against an event
+    pub fn matches(&self, event: &dyn UnifiedEvent) -> bool {
+        let body = event.to_json();
+        self.expr.eval(&EvalContext { event, body: &body })
+    }
+}
+
+impl FromStr for EventFilterExpr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for EventFilterExpr {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s)
+    }
+}
+
+impl From<EventFilterExpr> for String {
+    fn from(expr: EventFilterExpr) -> Self {
+        expr.source
+    }
+}
+
+impl fmt::Display for EventFilterExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn tokenize(source: &str) -> AnyResult<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('=', Some('=')) => (Token::Compare(CompareOp::Eq), 2),
+            ('!', Some('=')) => (Token::Compare(CompareOp::Ne), 2),
+            ('>', Some('=')) => (Token::Compare(CompareOp::Ge), 2),
+            ('<', Some('=')) => (Token::Compare(CompareOp::Le), 2),
+            ('>', _) => (Token::Compare(CompareOp::Gt), 1),
+            ('<', _) => (Token::Compare(CompareOp::Lt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('"', _) | ('\'', _) => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .ok_or_else(|| anyhow!("unterminated string in filter expression"))?;
+                let value: String = chars[i + 1..i + 1 + end].iter().collect();
+                (Token::Str(value), end + 2)
+            }
+            (c, _)
+                if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) =>
+            {
+                let len = chars[i + 1..]
+                    .iter()
+                    .position(|ch| {
+                        !(ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-' | '+'))
+                    })
+                    .unwrap_or(chars.len() - i - 1)
+                    + 1;
+                let text: String = chars[i..i + len].iter().filter(|&&ch| ch != '_').collect();
+                let number = text
+                    .parse::<f64>()
+                    .map_err(|_| anyhow!("invalid number {} in filter expression", text))?;
+                (Token::Number(number), len)
+            }
+            (c, _) if c.is_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .position(|ch| !(ch.is_alphanumeric() || matches!(ch, '_' | '.')))
+                    .unwrap_or(chars.len() - i);
+                (Token::Ident(chars[i..i + len].iter().collect()), len)
+            }
+            (c, _) => bail!("unexpected character '{}' in filter expression", c),
+        };
+        tokens.push(token);
+        i += len;
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn or(&mut self) -> AnyResult<Expr> {
+        let mut expr = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> AnyResult<Expr> {
+        let mut expr = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> AnyResult<Expr> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let expr = self.or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => bail!("missing ')' in filter expression"),
+                }
+            }
+            _ => {
+                let left = self.operand()?;
+                match self.peek() {
+                    Some(&Token::Compare(op)) => {
+                        self.pos += 1;
+                        Ok(Expr::Compare(left, op, self.operand()?))
+                    }
+                    _ => Ok(Expr::Truthy(left)),
+                }
+            }
+        }
+    }
+
+    fn operand(&mut self) -> AnyResult<Operand> {
+        let operand = match self.next() {
+            Some(Token::Number(n)) => Operand::Literal(Value::from(*n)),
+            Some(Token::Str(s)) => Operand::Literal(Value::String(s.clone())),
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Operand::Literal(Value::Bool(true)),
+                "false" => Operand::Literal(Value::Bool(false)),
+                "null" => Operand::Literal(Value::Null),
+                _ => Operand::Field(name.split('.').map(str::to_string).collect()),
+            },
+            Some(token) => bail!("unexpected token {:?} in filter expression", token),
+            None => bail!("unexpected end of filter expression"),
+        };
+        Ok(operand)
+    }
+}
+
+struct EvalContext<'a> {
+    event: &'a dyn UnifiedEvent,
+    body: &'a Value,
+}
+
+impl EvalContext<'_> {
+    fn field(&self, path: &[String]) -> Value {
+        if let [name] = path {
+            let metadata = self.event.metadata();
+            let value = match name.as_str() {
+                "event_type" => Some(Value::String(metadata.event_type.to_string())),
+                "protocol" => serde_json::to_value(&metadata.protocol).ok(),
+                "signature" => Some(Value::String(metadata.signature.to_string())),
+                "slot" => Some(Value::from(metadata.slot)),
+                "transaction_index" => Some(metadata.transaction_index.into()),
+                "recv_us" => Some(Value::from(metadata.recv_us)),
+                "handle_us" => Some(Value::from(metadata.handle_us)),
+                _ => None,
+            };
+            if let Some(value) = value {
+                return value;
+            }
+        }
+        let lookup =
+            |root: &Value| path.iter().try_fold(root, |value, key| value.get(key)).cloned();
+        lookup(self.body)
+            .or_else(|| self.body.get("metadata").and_then(lookup))
+            .map(normalize_bytes)
+            .unwrap_or(Value::Null)
+    }
+
+    fn value(&self, operand: &Operand) -> Value {
+        match operand {
+            Operand::Field(path) => self.field(path),
+            Operand::Literal(value) => value.clone(),
+        }
+    }
+}
+
+impl Expr {
+    fn eval(&self, ctx: &EvalContext) -> bool {
+        match self {
+            Expr::Or(a, b) => a.eval(ctx) || b.eval(ctx),
+            Expr::And(a, b) => a.eval(ctx) && b.eval(ctx),
+            Expr::Not(expr) => !expr.eval(ctx),
+            Expr::Truthy(operand) => truthy(&ctx.value(operand)),
+            Expr::Compare(left, op, right) => compare(&ctx.value(left), *op, &ctx.value(right)),
+        }
+    }
+}
+
+// 账户与签名序列化为字节数组，转成 base58 以便与字符串字面量比较
+fn normalize_bytes(value: Value) -> Value {
+    let Value::Array(items) = &value else {
+        return value;
+    };
+    let bytes: Option<Vec<u8>> =
+        items.iter().map(|item| item.as_u64().and_then(|b| u8::try_from(b).ok())).collect();
+    match bytes {
+        Some(bytes) if bytes.len() == 32 => {
+            Value::String(Pubkey::try_from(bytes.as_slice()).unwrap_or_default().to_string())
+        }
+        Some(bytes) if bytes.len() == 64 => {
+            Value::String(Signature::try_from(bytes.as_slice()).unwrap_or_default().to_string())
+        }
+        _ => value,
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+fn compare(left: &Value, op: CompareOp, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    match (op, ordering) {
+        (CompareOp::Eq, Some(ordering)) => ordering.is_eq(),
+        (CompareOp::Ne, Some(ordering)) => ordering.is_ne(),
+        (CompareOp::Eq, None) => left == right,
+        (CompareOp::Ne, None) => left != right,
+        (CompareOp::Gt, Some(ordering)) => ordering.is_gt(),
+        (CompareOp::Ge, Some(ordering)) => ordering.is_ge(),
+        (CompareOp::Lt, Some(ordering)) => ordering.is_lt(),
+        (CompareOp::Le, Some(ordering)) => ordering.is_le(),
+        (_, None) => false,
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::{EventMetadata, EventType, ProtocolType};
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    #[test]
+    fn test_filter_expr_matches() {
+        let event = RaydiumCpmmSwapEvent {
+            metadata: EventMetadata {
+                slot: 42,
+                protocol: ProtocolType::RaydiumCpmm,
+                event_type: EventType::RaydiumCpmmSwapBaseInput,
+                ..Default::default()
+            },
+            amount_in: 2_000_000_000,
+            ..Default::default()
+        };
+        let matches = |source: &str| EventFilterExpr::parse(source).unwrap().matches(&event);
+
+        assert!(matches(r#"protocol == "RaydiumCpmm" && amount_in > 1e9 && !is_bot"#));
+        assert!(matches("slot >= 40 && (amount_in < 1 || slot != 41)"));
+        assert!(!matches("amount_in > 1e9 && is_bot"));
+        assert!(matches(&format!("payer == '{}'", Pubkey::default())));
+        assert!(EventFilterExpr::parse("slot >").is_err());
+        assert!(EventFilterExpr::parse("(slot > 1").is_err());
+
+        let expr: EventFilterExpr = serde_json::from_str(r#""slot == 42""#).unwrap();
+        assert_eq!(serde_json::to_string(&expr).unwrap(), r#""slot == 42""#);
+    }
+}
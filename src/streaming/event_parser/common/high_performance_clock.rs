@@ -1,5 +1,8 @@
 use std::fmt::Debug;
-use std::time::Instant;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use web_time::Instant;
 
 /// 高性能时钟管理器，减少系统调用开销并最小化延迟
 #[derive(Debug)]
@@ -128,3 +131,53 @@ pub fn get_high_perf_clock() -> i64 {
 pub fn elapsed_micros_since(start_timestamp_us: i64) -> i64 {
     get_high_perf_clock() - start_timestamp_us
 }
+
+/// 时间源，带有过期/老化逻辑的组件通过它取当前时间
+///
+/// 默认为 [`SystemClock`]；测试中注入 [`MockClock`] 手动推进时间，回放时可将其设为录制的时间戳。
+pub trait Clock: Debug + Send + Sync {
+    /// 当前时间戳（微秒）
+    fn now_micros(&self) -> i64;
+}
+
+/// 基于全局高性能时钟的时间源
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    #[inline(always)]
+    fn now_micros(&self) -> i64 {
+        get_high_perf_clock()
+    }
+}
+
+/// 手动控制的时间源，用于确定性测试和回放
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now_us: AtomicI64,
+}
+
+impl MockClock {
+    pub fn new(now_us: i64) -> Self {
+        Self { now_us: AtomicI64::new(now_us) }
+    }
+
+    pub fn set(&self, now_us: i64) {
+        self.now_us.store(now_us, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now_us.fetch_add(duration.as_micros() as i64, Ordering::Relaxed);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_micros(&self) -> i64 {
+        self.now_us.load(Ordering::Relaxed)
+    }
+}
+
+/// 默认时间源
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
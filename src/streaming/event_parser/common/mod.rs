@@ -1,7 +1,12 @@
 pub mod types;
+pub mod custom_id;
+pub mod call_tree;
 pub mod utils;
 pub mod filter;
+pub mod filter_expr;
 pub mod high_performance_clock;
+pub mod program_labels;
+pub mod validation;
 
 /// 自动生成UnifiedEvent trait实现的宏
 #[macro_export]
@@ -45,10 +50,22 @@ macro_rules! impl_unified_event {
                 Box::new(self.clone())
             }
 
-            fn merge(&mut self, other: &dyn $crate::streaming::event_parser::core::traits::UnifiedEvent) {
+            fn merge_with(
+                &mut self,
+                other: &dyn $crate::streaming::event_parser::core::traits::UnifiedEvent,
+                _policy: $crate::streaming::event_parser::core::traits::MergePolicy,
+            ) {
+                use $crate::streaming::event_parser::core::traits::{is_default, MergePolicy};
                 if let Some(_e) = other.as_any().downcast_ref::<$struct_name>() {
                     $(
-                        self.$field = _e.$field.clone();
+                        let take = match _policy {
+                            MergePolicy::PreferInner => true,
+                            MergePolicy::PreferNonEmptyInner => !is_default(&_e.$field),
+                            MergePolicy::PreferOuter => is_default(&self.$field),
+                        };
+                        if take {
+                            self.$field = _e.$field.clone();
+                        }
                     )*
                 }
             }
@@ -71,9 +88,22 @@ macro_rules! impl_unified_event {
             fn transaction_index(&self) -> Option<u64> {
                 self.metadata.transaction_index
             }
+
+            fn metadata(&self) -> &$crate::streaming::event_parser::common::types::EventMetadata {
+                &self.metadata
+            }
+
+            fn metadata_mut(&mut self) -> &mut $crate::streaming::event_parser::common::types::EventMetadata {
+                &mut self.metadata
+            }
+
+            fn to_json(&self) -> serde_json::Value {
+                serde_json::to_value(self).unwrap_or_default()
+            }
         }
     };
 }
 
+pub use custom_id::*;
 pub use types::*;
 pub use utils::*;
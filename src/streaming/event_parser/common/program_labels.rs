@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey, pubkey::Pubkey};
+
+/// What a labeled program (or wallet) is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgramKind {
+    Aggregator,
+    Router,
+    Bot,
+    Bundler,
+    Other,
+}
+
+impl ProgramKind {
+    /// Swaps invoked through this program are reported as `routed_via`
+    pub fn is_routing(&self) -> bool {
+        matches!(self, Self::Aggregator | Self::Router)
+    }
+}
+
+/// Label of a well-known program ID, or of a bot wallet signing transactions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramLabel {
+    #[serde(with = "crate::streaming::event_parser::config::schema::pubkey_string")]
+    pub program_id: Pubkey,
+    pub name: String,
+    pub kind: ProgramKind,
+}
+
+impl ProgramLabel {
+    pub fn new(program_id: Pubkey, name: impl Into<String>, kind: ProgramKind) -> Self {
+        Self { program_id, name: name.into(), kind }
+    }
+}
+
+/// Registry labels attached to an event's metadata
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProgramAnnotation {
+    pub routed_via: Option<String>,
+    pub submitted_by_known_bot: bool,
+}
+
+/// 内置的知名程序
+const KNOWN_PROGRAMS: &[(Pubkey, &str, ProgramKind)] = &[
+    (pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"), "Jupiter", ProgramKind::Aggregator),
+    (pubkey!("JUP4Fb2cqiRUcaTHdrPC8h2gNsA2ETXiPDD33WcGuJB"), "Jupiter V4", ProgramKind::Aggregator),
+    (pubkey!("6m2CDdhRgxpH4WjvdzxAYbGxwdGUz5MziiL5jek2kBma"), "OKX DEX", ProgramKind::Aggregator),
+    (pubkey!("routeUGWgWzqBWFcrCfv8tritsqukccJPu3q5GPP3xS"), "Raydium Route", ProgramKind::Router),
+    (pubkey!("T1pyyaTNZsKv2WcRAB8oVnk93mLJw2XzjtVYqCsaHqt"), "Jito Tip", ProgramKind::Bundler),
+];
+
+/// Program ID → label registry used to annotate events
+///
+/// `default()` holds the built-in well-known programs; user labels from
+/// `StreamClientConfig::program_labels` are added on top and override them.
+#[derive(Debug, Clone)]
+pub struct ProgramRegistry {
+    labels: HashMap<Pubkey, ProgramLabel>,
+}
+
+impl Default for ProgramRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.extend(
+            KNOWN_PROGRAMS.iter().map(|&(id, name, kind)| ProgramLabel::new(id, name, kind)),
+        );
+        registry
+    }
+}
+
+impl ProgramRegistry {
+    pub fn empty() -> Self {
+        Self { labels: HashMap::new() }
+    }
+
+    pub fn insert(&mut self, label: ProgramLabel) {
+        self.labels.insert(label.program_id, label);
+    }
+
+    pub fn extend(&mut self, labels: impl IntoIterator<Item = ProgramLabel>) {
+        for label in labels {
+            self.insert(label);
+        }
+    }
+
+    pub fn label(&self, program_id: &Pubkey) -> Option<&ProgramLabel> {
+        self.labels.get(program_id)
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = &ProgramLabel> {
+        self.labels.values()
+    }
+
+    /// Labels for events of an instruction under the top-level `program_id`
+    pub fn annotate(&self, program_id: &Pubkey, fee_payer: Option<&Pubkey>) -> ProgramAnnotation {
+        let caller = self.label(program_id);
+        let is_bot =
+            |label: Option<&ProgramLabel>| label.is_some_and(|l| l.kind == ProgramKind::Bot);
+        ProgramAnnotation {
+            routed_via: caller.filter(|l| l.kind.is_routing()).map(|l| l.name.clone()),
+            submitted_by_known_bot: is_bot(caller) || is_bot(fee_payer.and_then(|p| self.label(p))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_annotation() {
+        let jupiter = KNOWN_PROGRAMS[0].0;
+        let bot_wallet = Pubkey::new_unique();
+        let mut registry = ProgramRegistry::default();
+        let labels: Vec<ProgramLabel> = serde_json::from_str(&format!(
+            r#"[{{"program_id": "{}", "name": "My Bot", "kind": "bot"}}]"#,
+            bot_wallet
+        ))
+        .unwrap();
+        registry.extend(labels);
+
+        let routed = registry.annotate(&jupiter, None);
+        assert_eq!(routed.routed_via.as_deref(), Some("Jupiter"));
+        assert!(!routed.submitted_by_known_bot);
+
+        let direct = registry.annotate(&Pubkey::new_unique(), Some(&bot_wallet));
+        assert_eq!(direct.routed_via, None);
+        assert!(direct.submitted_by_known_bot);
+    }
+}
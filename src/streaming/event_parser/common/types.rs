@@ -4,21 +4,24 @@ use serde::{Deserialize, Serialize};
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use std::{borrow::Cow, fmt, str::FromStr, sync::Arc};
 
+#[cfg(feature = "raydium-amm-v4")]
+use crate::streaming::event_parser::protocols::raydium_amm_v4::RaydiumAmmV4SwapEvent;
+#[cfg(feature = "raydium-clmm")]
+use crate::streaming::event_parser::protocols::raydium_clmm::{
+    RaydiumClmmCollectFundFeeEvent, RaydiumClmmCollectProtocolFeeEvent,
+    RaydiumClmmCollectRemainingRewardsEvent, RaydiumClmmDecreaseLiquidityV2Event,
+    RaydiumClmmSwapEvent, RaydiumClmmSwapV2Event,
+};
+#[cfg(feature = "raydium-cpmm")]
+use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
 use crate::{
     match_event,
-    streaming::{
-        common::SimdUtils,
-        event_parser::{
-            protocols::{
-                raydium_amm_v4::RaydiumAmmV4SwapEvent,
-                raydium_clmm::{RaydiumClmmSwapEvent, RaydiumClmmSwapV2Event},
-                raydium_cpmm::RaydiumCpmmSwapEvent,
-            },
-            UnifiedEvent,
-        },
-    },
+    streaming::{common::SimdUtils, event_parser::UnifiedEvent},
 };
 
+use super::custom_id::CustomId;
+use super::utils::{read_account, read_indexed_account, read_u64_le};
+
 // Object pool size configuration
 const EVENT_METADATA_POOL_SIZE: usize = 1000;
 
@@ -54,7 +57,16 @@ lazy_static::lazy_static! {
 }
 
 #[derive(
-    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
 )]
 pub enum ProtocolType {
     #[default]
@@ -62,12 +74,22 @@ pub enum ProtocolType {
     RaydiumClmm,
     RaydiumAmmV4,
     Common,
-    Custom(String),
+    /// Config-defined protocol, by interned name
+    Custom(CustomId),
 }
 
 /// Event type enumeration
 #[derive(
-    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
 )]
 pub enum EventType {
     // Raydium CPMM events
@@ -87,6 +109,10 @@ pub enum EventType {
     RaydiumClmmCreatePool,
     RaydiumClmmOpenPositionWithToken22Nft,
     RaydiumClmmOpenPositionV2,
+    RaydiumClmmCollectProtocolFee,
+    RaydiumClmmCollectFundFee,
+    RaydiumClmmCollectRemainingRewards,
+    RaydiumClmmUpdateRewardInfos,
 
     // Raydium AMM V4 events
     RaydiumAmmV4SwapBaseIn,
@@ -111,8 +137,8 @@ pub enum EventType {
     BlockMeta,
     Unknown,
 
-    // Dynamic/custom events
-    Custom(String),
+    // Dynamic/custom events, by interned name
+    Custom(CustomId),
 }
 
 pub const ACCOUNT_EVENT_TYPES: &[EventType] = &[
@@ -127,6 +153,148 @@ pub const ACCOUNT_EVENT_TYPES: &[EventType] = &[
 ];
 pub const BLOCK_EVENT_TYPES: &[EventType] = &[EventType::BlockMeta];
 
+/// Coarse grouping of [`EventType`]s across protocols
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventCategory {
+    /// Swaps
+    Trade,
+    /// Deposits, withdrawals, positions and fee / reward collection
+    Liquidity,
+    /// Pool creation and pool-level configuration
+    PoolLifecycle,
+    /// Account state updates
+    Account,
+    /// Block meta and unparsed events
+    System,
+    /// Dynamic (IDL / config) parser events
+    Custom,
+}
+
+impl EventType {
+    /// Every built-in variant, `Custom` excluded
+    pub const ALL: &'static [EventType] = &[
+        EventType::RaydiumCpmmSwapBaseInput,
+        EventType::RaydiumCpmmSwapBaseOutput,
+        EventType::RaydiumCpmmDeposit,
+        EventType::RaydiumCpmmInitialize,
+        EventType::RaydiumCpmmWithdraw,
+        EventType::RaydiumClmmSwap,
+        EventType::RaydiumClmmSwapV2,
+        EventType::RaydiumClmmClosePosition,
+        EventType::RaydiumClmmIncreaseLiquidityV2,
+        EventType::RaydiumClmmDecreaseLiquidityV2,
+        EventType::RaydiumClmmCreatePool,
+        EventType::RaydiumClmmOpenPositionWithToken22Nft,
+        EventType::RaydiumClmmOpenPositionV2,
+        EventType::RaydiumClmmCollectProtocolFee,
+        EventType::RaydiumClmmCollectFundFee,
+        EventType::RaydiumClmmCollectRemainingRewards,
+        EventType::RaydiumClmmUpdateRewardInfos,
+        EventType::RaydiumAmmV4SwapBaseIn,
+        EventType::RaydiumAmmV4SwapBaseOut,
+        EventType::RaydiumAmmV4Deposit,
+        EventType::RaydiumAmmV4Initialize2,
+        EventType::RaydiumAmmV4Withdraw,
+        EventType::RaydiumAmmV4WithdrawPnl,
+        EventType::AccountRaydiumAmmV4AmmInfo,
+        EventType::AccountRaydiumClmmAmmConfig,
+        EventType::AccountRaydiumClmmPoolState,
+        EventType::AccountRaydiumClmmTickArrayState,
+        EventType::AccountRaydiumCpmmAmmConfig,
+        EventType::AccountRaydiumCpmmPoolState,
+        EventType::NonceAccount,
+        EventType::TokenAccount,
+        EventType::BlockMeta,
+        EventType::Unknown,
+    ];
+
+    pub fn iter() -> impl Iterator<Item = EventType> {
+        Self::ALL.iter().cloned()
+    }
+
+    /// Built-in variants of a category, e.g. every swap for a "Trade" filter
+    pub fn of_category(category: EventCategory) -> Vec<EventType> {
+        Self::iter().filter(|event_type| event_type.category() == category).collect()
+    }
+
+    pub fn category(&self) -> EventCategory {
+        use EventType::*;
+        match self {
+            RaydiumCpmmSwapBaseInput
+            | RaydiumCpmmSwapBaseOutput
+            | RaydiumClmmSwap
+            | RaydiumClmmSwapV2
+            | RaydiumAmmV4SwapBaseIn
+            | RaydiumAmmV4SwapBaseOut => EventCategory::Trade,
+            RaydiumCpmmDeposit
+            | RaydiumCpmmWithdraw
+            | RaydiumClmmClosePosition
+            | RaydiumClmmIncreaseLiquidityV2
+            | RaydiumClmmDecreaseLiquidityV2
+            | RaydiumClmmOpenPositionWithToken22Nft
+            | RaydiumClmmOpenPositionV2
+            | RaydiumClmmCollectProtocolFee
+            | RaydiumClmmCollectFundFee
+            | RaydiumClmmCollectRemainingRewards
+            | RaydiumAmmV4Deposit
+            | RaydiumAmmV4Withdraw
+            | RaydiumAmmV4WithdrawPnl => EventCategory::Liquidity,
+            RaydiumCpmmInitialize
+            | RaydiumClmmCreatePool
+            | RaydiumClmmUpdateRewardInfos
+            | RaydiumAmmV4Initialize2 => EventCategory::PoolLifecycle,
+            AccountRaydiumAmmV4AmmInfo
+            | AccountRaydiumClmmAmmConfig
+            | AccountRaydiumClmmPoolState
+            | AccountRaydiumClmmTickArrayState
+            | AccountRaydiumCpmmAmmConfig
+            | AccountRaydiumCpmmPoolState
+            | NonceAccount
+            | TokenAccount => EventCategory::Account,
+            BlockMeta | Unknown => EventCategory::System,
+            Custom(_) => EventCategory::Custom,
+        }
+    }
+
+    /// Protocol the event belongs to, None for protocol-independent events
+    pub fn venue(&self) -> Option<ProtocolType> {
+        use EventType::*;
+        match self {
+            RaydiumCpmmSwapBaseInput
+            | RaydiumCpmmSwapBaseOutput
+            | RaydiumCpmmDeposit
+            | RaydiumCpmmInitialize
+            | RaydiumCpmmWithdraw
+            | AccountRaydiumCpmmAmmConfig
+            | AccountRaydiumCpmmPoolState => Some(ProtocolType::RaydiumCpmm),
+            RaydiumClmmSwap
+            | RaydiumClmmSwapV2
+            | RaydiumClmmClosePosition
+            | RaydiumClmmIncreaseLiquidityV2
+            | RaydiumClmmDecreaseLiquidityV2
+            | RaydiumClmmCreatePool
+            | RaydiumClmmOpenPositionWithToken22Nft
+            | RaydiumClmmOpenPositionV2
+            | RaydiumClmmCollectProtocolFee
+            | RaydiumClmmCollectFundFee
+            | RaydiumClmmCollectRemainingRewards
+            | RaydiumClmmUpdateRewardInfos
+            | AccountRaydiumClmmAmmConfig
+            | AccountRaydiumClmmPoolState
+            | AccountRaydiumClmmTickArrayState => Some(ProtocolType::RaydiumClmm),
+            RaydiumAmmV4SwapBaseIn
+            | RaydiumAmmV4SwapBaseOut
+            | RaydiumAmmV4Deposit
+            | RaydiumAmmV4Initialize2
+            | RaydiumAmmV4Withdraw
+            | RaydiumAmmV4WithdrawPnl
+            | AccountRaydiumAmmV4AmmInfo => Some(ProtocolType::RaydiumAmmV4),
+            NonceAccount | TokenAccount | BlockMeta | Unknown | Custom(_) => None,
+        }
+    }
+}
+
 impl fmt::Display for EventType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -149,6 +317,12 @@ impl fmt::Display for EventType {
                 write!(f, "RaydiumClmmOpenPositionWithToken22Nft")
             }
             EventType::RaydiumClmmOpenPositionV2 => write!(f, "RaydiumClmmOpenPositionV2"),
+            EventType::RaydiumClmmCollectProtocolFee => write!(f, "RaydiumClmmCollectProtocolFee"),
+            EventType::RaydiumClmmCollectFundFee => write!(f, "RaydiumClmmCollectFundFee"),
+            EventType::RaydiumClmmCollectRemainingRewards => {
+                write!(f, "RaydiumClmmCollectRemainingRewards")
+            }
+            EventType::RaydiumClmmUpdateRewardInfos => write!(f, "RaydiumClmmUpdateRewardInfos"),
             EventType::RaydiumAmmV4SwapBaseIn => write!(f, "RaydiumAmmV4SwapBaseIn"),
             EventType::RaydiumAmmV4SwapBaseOut => write!(f, "RaydiumAmmV4SwapBaseOut"),
             EventType::RaydiumAmmV4Deposit => write!(f, "RaydiumAmmV4Deposit"),
@@ -219,21 +393,133 @@ impl ProtocolInfo {
     Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
 )]
 pub struct SwapData {
+    /// Mint the user paid (for fee / reward collection: token0 or the reward mint)
     pub from_mint: Pubkey,
+    /// Mint the user received (for fee collection: token1)
     pub to_mint: Pubkey,
     pub from_amount: u64,
     pub to_amount: u64,
+    /// Pool (pool state / amm) account the tokens moved through
+    #[serde(default)]
+    pub pool: Pubkey,
+    /// Protocol of the pool
+    #[serde(default)]
+    pub venue: ProtocolType,
+    /// Why a side could not be resolved, None when complete
     pub description: Option<Cow<'static, str>>,
 }
 
+impl SwapData {
+    /// Both legs have a mint and an amount
+    pub fn is_complete(&self) -> bool {
+        self.from_mint != Pubkey::default()
+            && self.to_mint != Pubkey::default()
+            && self.from_amount > 0
+            && self.to_amount > 0
+    }
+}
+
+/// Token accounts a protocol instruction moves tokens between
+///
+/// Each protocol event builds one (`swap_accounts()`) so the inner transfers can be read
+/// the same way for every venue: a transfer from a user account into a pool vault is the
+/// `from` leg, a transfer out of a pool vault to a user account is the `to` leg, whatever
+/// the vaults are called. Mints left as default are taken from `TransferChecked`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SwapAccounts {
+    pub pool: Pubkey,
+    pub user_from_token: Pubkey,
+    pub user_to_token: Pubkey,
+    pub from_vault: Pubkey,
+    pub to_vault: Pubkey,
+    pub from_mint: Pubkey,
+    pub to_mint: Pubkey,
+    /// Both legs are paid out by the pool (fee / reward collection): `from_vault` pays
+    /// `user_from_token`, `to_vault` pays `user_to_token`
+    pub collect: bool,
+}
+
+impl SwapAccounts {
+    fn vault_mint(&self, vault: &Pubkey) -> Pubkey {
+        if *vault == self.from_vault {
+            self.from_mint
+        } else {
+            self.to_mint
+        }
+    }
+}
+
+/// Compute budget requested by the transaction (ComputeBudget program instructions)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComputeBudgetInfo {
+    /// SetComputeUnitLimit
+    pub unit_limit: Option<u32>,
+    /// SetComputeUnitPrice, in micro-lamports per compute unit
+    pub unit_price: Option<u64>,
+    /// Compute units the whole transaction consumed (`meta.compute_units_consumed`),
+    /// None without status meta (e.g. shred stream)
+    #[serde(default)]
+    pub units_consumed: Option<u64>,
+    /// Top-level instructions other than ComputeBudget ones, for the default unit limit
+    #[serde(default)]
+    pub instruction_count: u32,
+}
+
+impl ComputeBudgetInfo {
+    /// Default per-instruction compute unit limit when none is requested
+    pub const DEFAULT_UNIT_LIMIT: u32 = 200_000;
+    /// Maximum compute unit limit of a transaction
+    pub const MAX_UNIT_LIMIT: u32 = 1_400_000;
+
+    /// Unit limit the runtime applies: the requested one, else the per-instruction
+    /// default for each instruction, capped at [`MAX_UNIT_LIMIT`](Self::MAX_UNIT_LIMIT)
+    pub fn effective_unit_limit(&self) -> u32 {
+        // 未记录指令数时按单条指令计算
+        let default_limit = Self::DEFAULT_UNIT_LIMIT.saturating_mul(self.instruction_count.max(1));
+        self.unit_limit.unwrap_or(default_limit).min(Self::MAX_UNIT_LIMIT)
+    }
+
+    /// Priority fee in lamports (unit price × effective unit limit), `None` without a
+    /// price instruction
+    pub fn priority_fee_lamports(&self) -> Option<u64> {
+        let price = self.unit_price?;
+        let limit = self.effective_unit_limit() as u128;
+        Some(((price as u128 * limit + 999_999) / 1_000_000) as u64)
+    }
+
+    /// Share of the effective unit limit actually consumed
+    pub fn limit_utilization(&self) -> Option<f64> {
+        let limit = self.effective_unit_limit().max(1);
+        Some(self.units_consumed? as f64 / limit as f64)
+    }
+}
+
+/// Where `block_time` / `block_time_ms` came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BlockTimeSource {
+    /// Reported by the node
+    Actual,
+    /// Filled in by the block time estimator
+    Estimated,
+    /// Not available, block time is 0
+    #[default]
+    Missing,
+}
+
 /// Event metadata
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EventMetadata {
     pub signature: Signature,
     pub slot: u64,
     pub transaction_index: Option<u64>, // 新增：交易在slot中的索引
+    /// 交易所在 entry 在 slot 中的序号（需订阅 entry，见 `StreamClientConfig::subscribe_entries`）
+    #[serde(default)]
+    pub entry_index: Option<u64>,
     pub block_time: i64,
     pub block_time_ms: i64,
+    /// 区块时间来源（processed 级别常缺失，可能为估算值）
+    #[serde(default)]
+    pub block_time_source: BlockTimeSource,
     pub recv_us: i64,
     pub handle_us: i64,
     pub protocol: ProtocolType,
@@ -242,6 +528,30 @@ pub struct EventMetadata {
     pub swap_data: Option<SwapData>,
     pub outer_index: i64,
     pub inner_index: Option<i64>,
+    /// 指令调用深度：顶层指令为 1，每层 CPI 加一（早期交易的内联指令可能缺失）
+    #[serde(default)]
+    pub stack_height: Option<u32>,
+    /// 交易请求的计算预算（优先费）
+    #[serde(default)]
+    pub compute_budget: ComputeBudgetInfo,
+    /// 调用该协议的聚合器/路由程序标签，如 "Jupiter"（见 `ProgramRegistry`）
+    #[serde(default)]
+    pub routed_via: Option<String>,
+    /// 由已知机器人程序或钱包提交
+    #[serde(default)]
+    pub submitted_by_known_bot: bool,
+    /// 指令引用了交易中不存在的账户，相应字段为补齐的 `Pubkey::default()`，应视为残缺事件
+    #[serde(default)]
+    pub has_padded_accounts: bool,
+    /// 下游附加的标签，如地址黑名单命中（见 `DenyListSink`）
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 由 `Pipeline::reprocess_slot` 重新解析补发，而非实时流
+    #[serde(default)]
+    pub replayed: bool,
+    /// 交付回调时已超出 `EventAgeGuard` 的时限（`StaleAction::Flag`）
+    #[serde(default)]
+    pub stale: bool,
 }
 
 impl EventMetadata {
@@ -264,6 +574,11 @@ impl EventMetadata {
             slot,
             block_time,
             block_time_ms,
+            block_time_source: if block_time_ms > 0 {
+                BlockTimeSource::Actual
+            } else {
+                BlockTimeSource::Missing
+            },
             recv_us,
             handle_us: 0,
             protocol,
@@ -272,7 +587,16 @@ impl EventMetadata {
             swap_data: None,
             outer_index,
             inner_index,
+            stack_height: None,
             transaction_index,
+            entry_index: None,
+            compute_budget: ComputeBudgetInfo::default(),
+            routed_via: None,
+            submitted_by_known_bot: false,
+            has_padded_accounts: false,
+            tags: Vec::new(),
+            replayed: false,
+            stale: false,
         }
     }
 
@@ -295,293 +619,278 @@ lazy_static::lazy_static! {
     ];
 }
 
-/// Parse token transfer data from next instructions
-pub fn parse_swap_data_from_next_instructions(
-    event: &dyn UnifiedEvent,
-    inner_instruction: &solana_transaction_status::InnerInstructions,
-    current_index: i8,
-    accounts: &[Pubkey],
-) -> Option<SwapData> {
-    let mut swap_data = SwapData {
-        from_mint: Pubkey::default(),
-        to_mint: Pubkey::default(),
-        from_amount: 0,
-        to_amount: 0,
-        description: None,
-    };
-
-    // 先根据 event 取出关键信息
-    let mut user: Option<Pubkey> = None;
-    let mut from_mint: Option<Pubkey> = None;
-    let mut to_mint: Option<Pubkey> = None;
-    let mut user_from_token: Option<Pubkey> = None;
-    let mut user_to_token: Option<Pubkey> = None;
-    let mut from_vault: Option<Pubkey> = None;
-    let mut to_vault: Option<Pubkey> = None;
-
+/// Swap accounts of the protocol events that move tokens through a pool
+fn swap_accounts(event: &dyn UnifiedEvent) -> Option<SwapAccounts> {
+    let mut accounts = None;
     match_event!(&*event, {
+        #[cfg(feature = "raydium-cpmm")]
         RaydiumCpmmSwapEvent => |e: RaydiumCpmmSwapEvent| {
-            user = Some(e.payer);
-            from_mint = Some(e.input_token_mint);
-            to_mint   = Some(e.output_token_mint);
-            user_from_token = Some(e.input_token_account);
-            user_to_token   = Some(e.output_token_account);
-            from_vault = Some(e.input_vault);
-            to_vault   = Some(e.output_vault);
+            accounts = Some(e.swap_accounts());
         },
+        #[cfg(feature = "raydium-clmm")]
         RaydiumClmmSwapEvent => |e: RaydiumClmmSwapEvent| {
-            user = Some(e.payer);
-            swap_data.description = Some("Unable to get from_mint and to_mint from RaydiumClmmSwapEvent".into());
-            user_from_token = Some(e.input_token_account);
-            user_to_token   = Some(e.output_token_account);
-            from_vault = Some(e.input_vault);
-            to_vault   = Some(e.output_vault);
+            accounts = Some(e.swap_accounts());
         },
+        #[cfg(feature = "raydium-clmm")]
         RaydiumClmmSwapV2Event => |e: RaydiumClmmSwapV2Event| {
-            user = Some(e.payer);
-            from_mint = Some(e.input_vault_mint);
-            to_mint   = Some(e.output_vault_mint);
-            user_from_token = Some(e.input_token_account);
-            user_to_token   = Some(e.output_token_account);
-            from_vault = Some(e.input_vault);
-            to_vault   = Some(e.output_vault);
+            accounts = Some(e.swap_accounts());
         },
+        #[cfg(feature = "raydium-amm-v4")]
         RaydiumAmmV4SwapEvent => |e: RaydiumAmmV4SwapEvent| {
-            user = Some(e.user_source_owner);
-            swap_data.description = Some("Unable to get from_mint and to_mint from RaydiumAmmV4SwapEvent".into());
-            user_from_token = Some(e.user_source_token_account);
-            user_to_token   = Some(e.user_destination_token_account);
-            from_vault = Some(e.pool_pc_token_account);
-            to_vault   = Some(e.pool_coin_token_account);
+            accounts = Some(e.swap_accounts());
+        },
+        #[cfg(feature = "raydium-clmm")]
+        RaydiumClmmDecreaseLiquidityV2Event => |e: RaydiumClmmDecreaseLiquidityV2Event| {
+            accounts = Some(e.swap_accounts());
         },
+        #[cfg(feature = "raydium-clmm")]
+        RaydiumClmmCollectProtocolFeeEvent => |e: RaydiumClmmCollectProtocolFeeEvent| {
+            accounts = Some(e.swap_accounts());
+        },
+        #[cfg(feature = "raydium-clmm")]
+        RaydiumClmmCollectFundFeeEvent => |e: RaydiumClmmCollectFundFeeEvent| {
+            accounts = Some(e.swap_accounts());
+        },
+        #[cfg(feature = "raydium-clmm")]
+        RaydiumClmmCollectRemainingRewardsEvent => |e: RaydiumClmmCollectRemainingRewardsEvent| {
+            accounts = Some(e.swap_accounts());
+        },
+        _ => |_event: &dyn UnifiedEvent| {},
     });
+    accounts
+}
+
+/// Reads the swap legs from the token transfers following the protocol instruction
+///
+/// `instructions` yields (program id index, account indexes, data) of the inner
+/// instructions after the current one; scanning stops at the first non-transfer program.
+fn scan_swap_transfers<'a>(
+    event: &dyn UnifiedEvent,
+    instructions: impl Iterator<Item = (usize, &'a [u8], &'a [u8])>,
+    accounts: &[Pubkey],
+) -> Option<SwapData> {
+    let swap = swap_accounts(event)?;
+    let mut swap_data = SwapData {
+        pool: swap.pool,
+        venue: event.metadata().protocol.clone(),
+        ..Default::default()
+    };
+    let user_accounts = [swap.user_from_token, swap.user_to_token];
+    let vaults = [swap.from_vault, swap.to_vault];
 
-    let user_to_token = user_to_token.unwrap_or_default();
-    let user_from_token = user_from_token.unwrap_or_default();
-    let to_vault = to_vault.unwrap_or_default();
-    let from_vault = from_vault.unwrap_or_default();
-    let to_mint = to_mint.unwrap_or_default();
-    let from_mint = from_mint.unwrap_or_default();
-
-    // 单次循环完成提取和判断
-    for instruction in inner_instruction.instructions.iter().skip((current_index + 1) as usize) {
-        let compiled = &instruction.instruction;
-        let program_id = accounts[compiled.program_id_index as usize];
+    for (program_id_index, ix_accounts, data) in instructions {
+        let Some(program_id) = read_account(accounts, program_id_index) else {
+            break;
+        };
         if !SYSTEM_PROGRAMS.contains(&program_id) {
             break;
         }
-        let data = &compiled.data;
 
         // 使用 SIMD 验证数据格式
         if !SimdUtils::validate_data_format(data, 8) {
             continue;
         }
 
-        let get_pubkey = |i: usize| accounts[compiled.accounts[i] as usize];
-        let (source, destination, amount) = match data[0] {
-            12 if compiled.accounts.len() >= 4 => {
-                let amt = u64::from_le_bytes(data[1..9].try_into().unwrap());
-                (get_pubkey(0), get_pubkey(2), amt)
+        let get_pubkey = |i: usize| read_indexed_account(accounts, ix_accounts, i);
+        let (transfer, checked_mint) = match data[0] {
+            12 if ix_accounts.len() >= 4 => {
+                ((get_pubkey(0), get_pubkey(2), read_u64_le(data, 1)), get_pubkey(1))
             }
-            3 if compiled.accounts.len() >= 3 => {
-                let amt = u64::from_le_bytes(data[1..9].try_into().unwrap());
-                (get_pubkey(0), get_pubkey(1), amt)
+            3 if ix_accounts.len() >= 3 => {
+                ((get_pubkey(0), get_pubkey(1), read_u64_le(data, 1)), None)
             }
-            2 if compiled.accounts.len() >= 2 => {
-                let amt = u64::from_le_bytes(data[4..12].try_into().unwrap());
-                (get_pubkey(0), get_pubkey(1), amt)
+            2 if ix_accounts.len() >= 2 => {
+                ((get_pubkey(0), get_pubkey(1), read_u64_le(data, 4)), None)
             }
             _ => continue,
         };
+        // 账户索引越界或数据过短的指令直接跳过
+        let (Some(source), Some(destination), Some(amount)) = transfer else {
+            continue;
+        };
 
-        match (source, destination) {
-            (s, d) if s == user_to_token && d == to_vault => {
-                swap_data.from_mint = to_mint;
-                swap_data.from_amount = amount;
-            }
-            (s, d) if s == from_vault && d == user_from_token => {
-                swap_data.to_mint = from_mint;
-                swap_data.to_amount = amount;
-            }
-            (s, d) if s == user_from_token && d == from_vault => {
-                swap_data.from_mint = from_mint;
-                swap_data.from_amount = amount;
-            }
-            (s, d) if s == to_vault && d == user_to_token => {
-                swap_data.to_mint = to_mint;
-                swap_data.to_amount = amount;
+        let is_from_leg = if swap.collect {
+            match (source, destination) {
+                (s, d) if s == swap.from_vault && d == swap.user_from_token => true,
+                (s, d) if s == swap.to_vault && d == swap.user_to_token => false,
+                _ => continue,
             }
-            (s, d) if s == user_from_token && d == to_vault => {
-                swap_data.from_mint = from_mint;
-                swap_data.from_amount = amount;
-            }
-            (s, d) if s == from_vault && d == user_to_token => {
-                swap_data.to_mint = to_mint;
-                swap_data.to_amount = amount;
-            }
-            _ => {}
-        }
-        if swap_data.from_mint != Pubkey::default() && swap_data.to_mint != Pubkey::default() {
-            break;
+        } else if user_accounts.contains(&source) && vaults.contains(&destination) {
+            true
+        } else if vaults.contains(&source) && user_accounts.contains(&destination) {
+            false
+        } else {
+            continue;
+        };
+        let vault = if is_from_leg && !swap.collect { destination } else { source };
+        let mint = checked_mint.unwrap_or_else(|| swap.vault_mint(&vault));
+        if is_from_leg {
+            swap_data.from_mint = mint;
+            swap_data.from_amount = amount;
+        } else {
+            swap_data.to_mint = mint;
+            swap_data.to_amount = amount;
         }
         if swap_data.from_amount != 0 && swap_data.to_amount != 0 {
             break;
         }
     }
 
-    if swap_data.from_mint != Pubkey::default()
-        || swap_data.to_mint != Pubkey::default()
-        || swap_data.from_amount != 0
-        || swap_data.to_amount != 0
+    if swap_data.from_amount == 0 && swap_data.to_amount == 0 {
+        return None;
+    }
+    let unknown_mint = |mint: Pubkey, amount: u64| amount > 0 && mint == Pubkey::default();
+    if unknown_mint(swap_data.from_mint, swap_data.from_amount)
+        || unknown_mint(swap_data.to_mint, swap_data.to_amount)
     {
-        Some(swap_data)
-    } else {
-        None
+        swap_data.description =
+            Some("Mint not in instruction accounts and transfer is not TransferChecked".into());
     }
+    Some(swap_data)
 }
 
 /// Parse token transfer data from next instructions
-/// TODO: - wait refactor
+///
+/// `current_index` is the inner index of the protocol instruction, None when it is a
+/// top-level instruction and all of `inner_instruction` follows it.
+pub fn parse_swap_data_from_next_instructions(
+    event: &dyn UnifiedEvent,
+    inner_instruction: &solana_transaction_status::InnerInstructions,
+    current_index: Option<usize>,
+    accounts: &[Pubkey],
+) -> Option<SwapData> {
+    let instructions =
+        inner_instruction.instructions.iter().skip(current_index.map_or(0, |i| i + 1)).map(|ix| {
+            let compiled = &ix.instruction;
+            (compiled.program_id_index as usize, &compiled.accounts[..], &compiled.data[..])
+        });
+    scan_swap_transfers(event, instructions, accounts)
+}
+
+/// Parse token transfer data from next instructions, see
+/// [`parse_swap_data_from_next_instructions`]
 pub fn parse_swap_data_from_next_grpc_instructions(
     event: &dyn UnifiedEvent,
     inner_instruction: &yellowstone_grpc_proto::prelude::InnerInstructions,
-    current_index: i8,
+    current_index: Option<usize>,
     accounts: &[Pubkey],
 ) -> Option<SwapData> {
-    let mut swap_data = SwapData {
-        from_mint: Pubkey::default(),
-        to_mint: Pubkey::default(),
-        from_amount: 0,
-        to_amount: 0,
-        description: None,
-    };
-
-    // 先根据 event 取出关键信息
-    let mut user: Option<Pubkey> = None;
-    let mut from_mint: Option<Pubkey> = None;
-    let mut to_mint: Option<Pubkey> = None;
-    let mut user_from_token: Option<Pubkey> = None;
-    let mut user_to_token: Option<Pubkey> = None;
-    let mut from_vault: Option<Pubkey> = None;
-    let mut to_vault: Option<Pubkey> = None;
-
-    match_event!(&*event, {
-        RaydiumCpmmSwapEvent => |e: RaydiumCpmmSwapEvent| {
-            user = Some(e.payer);
-            from_mint = Some(e.input_token_mint);
-            to_mint   = Some(e.output_token_mint);
-            user_from_token = Some(e.input_token_account);
-            user_to_token   = Some(e.output_token_account);
-            from_vault = Some(e.input_vault);
-            to_vault   = Some(e.output_vault);
-        },
-        RaydiumClmmSwapEvent => |e: RaydiumClmmSwapEvent| {
-            user = Some(e.payer);
-            swap_data.description = Some("Unable to get from_mint and to_mint from RaydiumClmmSwapEvent".into());
-            user_from_token = Some(e.input_token_account);
-            user_to_token   = Some(e.output_token_account);
-            from_vault = Some(e.input_vault);
-            to_vault   = Some(e.output_vault);
-        },
-        RaydiumClmmSwapV2Event => |e: RaydiumClmmSwapV2Event| {
-            user = Some(e.payer);
-            from_mint = Some(e.input_vault_mint);
-            to_mint   = Some(e.output_vault_mint);
-            user_from_token = Some(e.input_token_account);
-            user_to_token   = Some(e.output_token_account);
-            from_vault = Some(e.input_vault);
-            to_vault   = Some(e.output_vault);
-        },
-        RaydiumAmmV4SwapEvent => |e: RaydiumAmmV4SwapEvent| {
-            user = Some(e.user_source_owner);
-            swap_data.description = Some("Unable to get from_mint and to_mint from RaydiumAmmV4SwapEvent".into());
-            user_from_token = Some(e.user_source_token_account);
-            user_to_token   = Some(e.user_destination_token_account);
-            from_vault = Some(e.pool_pc_token_account);
-            to_vault   = Some(e.pool_coin_token_account);
-        },
-    });
+    let instructions = inner_instruction
+        .instructions
+        .iter()
+        .skip(current_index.map_or(0, |i| i + 1))
+        .map(|ix| (ix.program_id_index as usize, &ix.accounts[..], &ix.data[..]));
+    scan_swap_transfers(event, instructions, accounts)
+}
 
-    let user_to_token = user_to_token.unwrap_or_default();
-    let user_from_token = user_from_token.unwrap_or_default();
-    let to_vault = to_vault.unwrap_or_default();
-    let from_vault = from_vault.unwrap_or_default();
-    let to_mint = to_mint.unwrap_or_default();
-    let from_mint = from_mint.unwrap_or_default();
-
-    // 单次循环完成提取和判断
-    for instruction in inner_instruction.instructions.iter().skip((current_index + 1) as usize) {
-        let compiled = &instruction;
-        let program_id = accounts[compiled.program_id_index as usize];
-        if !SYSTEM_PROGRAMS.contains(&program_id) {
-            break;
+#[cfg(all(test, feature = "raydium-clmm"))]
+mod tests {
+    use super::*;
+    use yellowstone_grpc_proto::prelude::{InnerInstruction, InnerInstructions};
+
+    fn transfer_checked(source: u8, mint: u8, destination: u8, amount: u64) -> InnerInstruction {
+        let mut data = vec![12];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.push(6);
+        InnerInstruction {
+            program_id_index: 0,
+            accounts: vec![source, mint, destination, 4],
+            data,
+            ..Default::default()
         }
-        let data = &compiled.data;
+    }
 
-        // 使用 SIMD 验证数据格式
-        if !SimdUtils::validate_data_format(data, 8) {
-            continue;
+    #[test]
+    fn test_event_type_categories() {
+        let all: std::collections::HashSet<String> =
+            EventType::iter().map(|e| e.to_string()).collect();
+        assert_eq!(all.len(), EventType::ALL.len());
+        for event_type in ACCOUNT_EVENT_TYPES {
+            assert_eq!(event_type.category(), EventCategory::Account);
         }
+        let trades = EventType::of_category(EventCategory::Trade);
+        assert_eq!(trades.len(), 6);
+        assert!(trades.iter().all(|e| e.venue().is_some()));
+        assert_eq!(EventType::BlockMeta.venue(), None);
+        assert_eq!(EventType::Custom("x".into()).category(), EventCategory::Custom);
+    }
 
-        let get_pubkey = |i: usize| accounts[compiled.accounts[i] as usize];
-        let (source, destination, amount) = match data[0] {
-            12 if compiled.accounts.len() >= 4 => {
-                let amt = u64::from_le_bytes(data[1..9].try_into().unwrap());
-                (get_pubkey(0), get_pubkey(2), amt)
-            }
-            3 if compiled.accounts.len() >= 3 => {
-                let amt = u64::from_le_bytes(data[1..9].try_into().unwrap());
-                (get_pubkey(0), get_pubkey(1), amt)
-            }
-            2 if compiled.accounts.len() >= 2 => {
-                let amt = u64::from_le_bytes(data[4..12].try_into().unwrap());
-                (get_pubkey(0), get_pubkey(1), amt)
-            }
-            _ => continue,
+    #[test]
+    fn test_collect_fee_amounts_from_inner_transfers() {
+        let accounts: Vec<Pubkey> = std::iter::once(SYSTEM_PROGRAMS[0])
+            .chain((0..7).map(|_| Pubkey::new_unique()))
+            .collect();
+        let event = RaydiumClmmCollectProtocolFeeEvent {
+            token_vault0: accounts[1],
+            vault0_mint: accounts[2],
+            recipient_token_account0: accounts[3],
+            token_vault1: accounts[5],
+            vault1_mint: accounts[6],
+            recipient_token_account1: accounts[7],
+            ..Default::default()
         };
+        let inner = InnerInstructions {
+            index: 0,
+            instructions: vec![transfer_checked(1, 2, 3, 1_500), transfer_checked(5, 6, 7, 20)],
+        };
+        let swap_data =
+            parse_swap_data_from_next_grpc_instructions(&event, &inner, None, &accounts).unwrap();
+        assert_eq!((swap_data.from_mint, swap_data.from_amount), (accounts[2], 1_500));
+        assert_eq!((swap_data.to_mint, swap_data.to_amount), (accounts[6], 20));
+    }
 
-        match (source, destination) {
-            (s, d) if s == user_to_token && d == to_vault => {
-                swap_data.from_mint = to_mint;
-                swap_data.from_amount = amount;
-            }
-            (s, d) if s == from_vault && d == user_from_token => {
-                swap_data.to_mint = from_mint;
-                swap_data.to_amount = amount;
-            }
-            (s, d) if s == user_from_token && d == from_vault => {
-                swap_data.from_mint = from_mint;
-                swap_data.from_amount = amount;
-            }
-            (s, d) if s == to_vault && d == user_to_token => {
-                swap_data.to_mint = to_mint;
-                swap_data.to_amount = amount;
-            }
-            (s, d) if s == user_from_token && d == to_vault => {
-                swap_data.from_mint = from_mint;
-                swap_data.from_amount = amount;
-            }
-            (s, d) if s == from_vault && d == user_to_token => {
-                swap_data.to_mint = to_mint;
-                swap_data.to_amount = amount;
-            }
-            _ => {}
-        }
-        if swap_data.from_mint != Pubkey::default() && swap_data.to_mint != Pubkey::default() {
-            break;
-        }
-        if swap_data.from_amount != 0 && swap_data.to_amount != 0 {
-            break;
-        }
+    #[test]
+    fn test_swap_legs_follow_transfer_direction() {
+        let accounts: Vec<Pubkey> = std::iter::once(SYSTEM_PROGRAMS[0])
+            .chain((0..7).map(|_| Pubkey::new_unique()))
+            .collect();
+        // v1 swap 不带 mint，且金库按 input / output 命名
+        let mut event = RaydiumClmmSwapEvent {
+            pool_state: accounts[7],
+            input_token_account: accounts[1],
+            input_vault: accounts[3],
+            output_vault: accounts[4],
+            output_token_account: accounts[6],
+            ..Default::default()
+        };
+        event.metadata.protocol = ProtocolType::RaydiumClmm;
+        let inner = InnerInstructions {
+            index: 0,
+            instructions: vec![transfer_checked(1, 2, 3, 1_000), transfer_checked(4, 5, 6, 42)],
+        };
+        let swap_data =
+            parse_swap_data_from_next_grpc_instructions(&event, &inner, None, &accounts).unwrap();
+        assert_eq!((swap_data.from_mint, swap_data.from_amount), (accounts[2], 1_000));
+        assert_eq!((swap_data.to_mint, swap_data.to_amount), (accounts[5], 42));
+        assert_eq!((swap_data.pool, swap_data.venue), (accounts[7], ProtocolType::RaydiumClmm));
+        assert!(swap_data.is_complete() && swap_data.description.is_none());
     }
 
-    if swap_data.from_mint != Pubkey::default()
-        || swap_data.to_mint != Pubkey::default()
-        || swap_data.from_amount != 0
-        || swap_data.to_amount != 0
-    {
-        Some(swap_data)
-    } else {
-        None
+    #[test]
+    fn test_swap_legs_after_many_inner_instructions() {
+        let accounts: Vec<Pubkey> = std::iter::once(SYSTEM_PROGRAMS[0])
+            .chain((0..7).map(|_| Pubkey::new_unique()))
+            .collect();
+        let event = RaydiumClmmSwapEvent {
+            input_token_account: accounts[1],
+            input_vault: accounts[3],
+            output_vault: accounts[4],
+            output_token_account: accounts[6],
+            ..Default::default()
+        };
+        // 索引 0 的转账属于之前的指令，当前指令位于 129，超出 i8 范围
+        let mut instructions = vec![transfer_checked(1, 2, 3, 999)];
+        instructions.extend((0..129).map(|_| transfer_checked(0, 0, 0, 1)));
+        instructions.push(transfer_checked(1, 2, 3, 1_000));
+        instructions.push(transfer_checked(4, 5, 6, 42));
+        let inner = InnerInstructions { index: 0, instructions };
+
+        let swap_data =
+            parse_swap_data_from_next_grpc_instructions(&event, &inner, Some(129), &accounts)
+                .unwrap();
+        assert_eq!((swap_data.from_mint, swap_data.from_amount), (accounts[2], 1_000));
+        assert_eq!((swap_data.to_mint, swap_data.to_amount), (accounts[5], 42));
+        assert!(parse_swap_data_from_next_grpc_instructions(&event, &inner, Some(131), &accounts)
+            .is_none());
     }
 }
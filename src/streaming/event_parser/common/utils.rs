@@ -1,4 +1,11 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use solana_sdk::pubkey::Pubkey;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use super::types::ComputeBudgetInfo;
+
+/// ComputeBudget 程序ID
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("ComputeBudget111111111111111111111111111111");
 
 /// 获取当前时间戳
 pub fn current_timestamp() -> i64 {
@@ -33,6 +40,22 @@ pub fn read_u64_le(data: &[u8], offset: usize) -> Option<u64> {
     Some(u64::from_le_bytes(bytes))
 }
 
+/// 安全地读取指令账户，越界返回 None
+#[inline]
+pub fn read_account(accounts: &[Pubkey], index: usize) -> Option<Pubkey> {
+    accounts.get(index).copied()
+}
+
+/// 安全地按编译指令中的账户索引读取交易账户，任一层越界返回 None
+#[inline]
+pub fn read_indexed_account(
+    accounts: &[Pubkey],
+    instruction_accounts: &[u8],
+    index: usize,
+) -> Option<Pubkey> {
+    accounts.get(*instruction_accounts.get(index)? as usize).copied()
+}
+
 pub fn read_i32_le(data: &[u8], offset: usize) -> Option<i32> {
     if data.len() < offset + 4 {
         return None;
@@ -108,3 +131,23 @@ pub fn format_pubkey_short(pubkey: &solana_sdk::pubkey::Pubkey) -> String {
         format!("{}...{}", &s[..4], &s[s.len() - 4..])
     }
 }
+
+/// 从交易的顶层指令中提取计算预算设置
+pub fn parse_compute_budget<'a>(
+    instructions: impl Iterator<Item = (u32, &'a [u8])>,
+    accounts: &[Pubkey],
+) -> ComputeBudgetInfo {
+    let mut info = ComputeBudgetInfo::default();
+    for (program_id_index, data) in instructions {
+        if accounts.get(program_id_index as usize) != Some(&COMPUTE_BUDGET_PROGRAM_ID) {
+            info.instruction_count += 1;
+            continue;
+        }
+        match data.first() {
+            Some(2) => info.unit_limit = read_u32_le(data, 1),
+            Some(3) => info.unit_price = read_u64_le(data, 1),
+            _ => {}
+        }
+    }
+    info
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "raydium-amm-v4")]
+use crate::streaming::event_parser::protocols::raydium_amm_v4::RaydiumAmmV4SwapEvent;
+#[cfg(feature = "raydium-clmm")]
+use crate::streaming::event_parser::protocols::raydium_clmm::{
+    RaydiumClmmSwapEvent, RaydiumClmmSwapV2Event,
+};
+#[cfg(feature = "raydium-cpmm")]
+use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// What strict validation does with an event failing [`validate_event`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrictAction {
+    /// Do not deliver the event
+    #[default]
+    Drop,
+    /// Deliver it with `invalid:<field>` added to `metadata.tags`
+    Flag,
+}
+
+impl StrictAction {
+    /// Validates the event and applies the action, returns false if it must be dropped
+    pub fn apply(self, event: &mut dyn UnifiedEvent) -> bool {
+        let violations = validate_event(event);
+        if violations.is_empty() {
+            return true;
+        }
+        match self {
+            StrictAction::Drop => false,
+            StrictAction::Flag => {
+                let tags = &mut event.metadata_mut().tags;
+                tags.extend(violations.into_iter().map(|field| format!("invalid:{field}")));
+                true
+            }
+        }
+    }
+}
+
+/// Critical fields of the event that are zero / `Pubkey::default()`
+///
+/// Swaps must move a nonzero amount through a known pool, with known mints where the
+/// event carries them; events built from padded accounts always fail.
+pub fn validate_event(event: &dyn UnifiedEvent) -> Vec<&'static str> {
+    let mut violations = Vec::new();
+    let mut require = |ok: bool, field: &'static str| {
+        if !ok {
+            violations.push(field);
+        }
+    };
+    let metadata = event.metadata();
+    require(!metadata.has_padded_accounts, "accounts");
+    if let Some(swap_data) = metadata.swap_data.as_ref() {
+        require(swap_data.from_mint != Pubkey::default(), "from_mint");
+        require(swap_data.to_mint != Pubkey::default(), "to_mint");
+    }
+
+    // 未启用任何协议 feature 时不使用
+    #[allow(unused_variables)]
+    let any = event.as_any();
+    #[cfg(feature = "raydium-cpmm")]
+    if let Some(e) = any.downcast_ref::<RaydiumCpmmSwapEvent>() {
+        require(e.amount_in > 0 || e.amount_out > 0, "amount");
+        require(e.pool_state != Pubkey::default(), "pool_state");
+        require(e.input_token_mint != Pubkey::default(), "input_token_mint");
+        require(e.output_token_mint != Pubkey::default(), "output_token_mint");
+    }
+    #[cfg(feature = "raydium-clmm")]
+    if let Some(e) = any.downcast_ref::<RaydiumClmmSwapEvent>() {
+        require(e.amount > 0, "amount");
+        require(e.pool_state != Pubkey::default(), "pool_state");
+    }
+    #[cfg(feature = "raydium-clmm")]
+    if let Some(e) = any.downcast_ref::<RaydiumClmmSwapV2Event>() {
+        require(e.amount > 0, "amount");
+        require(e.pool_state != Pubkey::default(), "pool_state");
+        require(e.input_vault_mint != Pubkey::default(), "input_vault_mint");
+        require(e.output_vault_mint != Pubkey::default(), "output_vault_mint");
+    }
+    #[cfg(feature = "raydium-amm-v4")]
+    if let Some(e) = any.downcast_ref::<RaydiumAmmV4SwapEvent>() {
+        require(e.amount_in > 0 || e.amount_out > 0, "amount");
+        require(e.amm != Pubkey::default(), "amm");
+    }
+    violations
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strict_validation() {
+        let valid = RaydiumCpmmSwapEvent {
+            amount_in: 1_000,
+            pool_state: Pubkey::new_unique(),
+            input_token_mint: Pubkey::new_unique(),
+            output_token_mint: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        assert!(validate_event(&valid).is_empty());
+
+        let mut invalid = RaydiumCpmmSwapEvent { amount_in: 0, ..valid.clone() };
+        invalid.output_token_mint = Pubkey::default();
+        assert_eq!(validate_event(&invalid), vec!["amount", "output_token_mint"]);
+
+        assert!(!StrictAction::Drop.apply(&mut invalid.clone()));
+        assert!(StrictAction::Flag.apply(&mut invalid));
+        assert_eq!(invalid.metadata.tags, vec!["invalid:amount", "invalid:output_token_mint"]);
+    }
+}
@@ -1,23 +1,25 @@
 use super::schema::{FieldType, InstructionConfig, ProtocolConfig};
 use crate::streaming::event_parser::{
-    common::{EventMetadata, EventType, ProtocolType},
-    core::event_parser::GenericEventParseConfig,
+    common::{CustomId, EventMetadata, EventType, ProtocolType},
+    core::{event_parser::GenericEventParseConfig, MergePolicy},
     UnifiedEvent,
 };
+use serde::{Deserialize, Serialize};
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
 /// Dynamic event that stores data from config-based parsing
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DynamicEvent {
     pub metadata: EventMetadata,
     pub instruction_name: String,
-    pub accounts: HashMap<String, Pubkey>,
-    pub data_fields: HashMap<String, DynamicFieldValue>,
+    pub accounts: BTreeMap<String, Pubkey>,
+    pub data_fields: BTreeMap<String, DynamicFieldValue>,
 }
 
 /// Dynamic field value supporting multiple types
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DynamicFieldValue {
     U8(u8),
     U16(u16),
@@ -34,6 +36,49 @@ pub enum DynamicFieldValue {
     String(String),
 }
 
+impl DynamicFieldValue {
+    /// Zero, `false`, the default pubkey or an empty string
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::U8(v) => *v == 0,
+            Self::U16(v) => *v == 0,
+            Self::U32(v) => *v == 0,
+            Self::U64(v) => *v == 0,
+            Self::U128(v) => *v == 0,
+            Self::I8(v) => *v == 0,
+            Self::I16(v) => *v == 0,
+            Self::I32(v) => *v == 0,
+            Self::I64(v) => *v == 0,
+            Self::I128(v) => *v == 0,
+            Self::Bool(v) => !*v,
+            Self::Pubkey(v) => *v == Pubkey::default(),
+            Self::String(v) => v.is_empty(),
+        }
+    }
+}
+
+impl DynamicEvent {
+    /// Unsigned integer field widened to u64 (None if missing or not an unsigned int)
+    pub fn u64_field(&self, name: &str) -> Option<u64> {
+        match self.data_fields.get(name)? {
+            DynamicFieldValue::U8(v) => Some(*v as u64),
+            DynamicFieldValue::U16(v) => Some(*v as u64),
+            DynamicFieldValue::U32(v) => Some(*v as u64),
+            DynamicFieldValue::U64(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// `amount_field` minus `fee_field` (missing fee counts as zero)
+    ///
+    /// Used for Token-2022 transfer-fee mints, where the gross transfer amount
+    /// differs from what the recipient actually receives.
+    pub fn net_amount(&self, amount_field: &str, fee_field: &str) -> Option<u64> {
+        let amount = self.u64_field(amount_field)?;
+        Some(amount.saturating_sub(self.u64_field(fee_field).unwrap_or(0)))
+    }
+}
+
 impl UnifiedEvent for DynamicEvent {
     fn event_type(&self) -> EventType {
         self.metadata.event_type.clone()
@@ -71,6 +116,27 @@ impl UnifiedEvent for DynamicEvent {
         Box::new(self.clone())
     }
 
+    fn merge_with(&mut self, other: &dyn UnifiedEvent, policy: MergePolicy) {
+        let Some(other) = other.as_any().downcast_ref::<DynamicEvent>() else {
+            return;
+        };
+        for (name, value) in &other.data_fields {
+            match policy {
+                MergePolicy::PreferInner => {
+                    self.data_fields.insert(name.clone(), value.clone());
+                }
+                MergePolicy::PreferNonEmptyInner => {
+                    if !value.is_empty() {
+                        self.data_fields.insert(name.clone(), value.clone());
+                    }
+                }
+                MergePolicy::PreferOuter => {
+                    self.data_fields.entry(name.clone()).or_insert_with(|| value.clone());
+                }
+            }
+        }
+    }
+
     fn set_swap_data(&mut self, _swap_data: crate::streaming::event_parser::common::SwapData) {
         // Can be implemented if needed
     }
@@ -90,6 +156,18 @@ impl UnifiedEvent for DynamicEvent {
     fn transaction_index(&self) -> Option<u64> {
         self.metadata.transaction_index
     }
+
+    fn metadata(&self) -> &EventMetadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut EventMetadata {
+        &mut self.metadata
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
 }
 
 /// Parser factory for dynamic config-based parsing
@@ -115,33 +193,21 @@ impl DynamicEventParser {
     }
 
     /// Create parser configs from a protocol config
-    /// Note: This stores instruction configs in global state for the parser function to access
+    ///
+    /// Each config carries its own instruction definition, so parsers built from
+    /// different config files never see each other's layouts.
     pub fn create_configs(
         protocol_config: &ProtocolConfig,
     ) -> anyhow::Result<Vec<GenericEventParseConfig>> {
-        use once_cell::sync::Lazy;
-        use parking_lot::RwLock;
-
-        // Global storage for dynamic configs
-        static DYNAMIC_CONFIGS: Lazy<RwLock<std::collections::HashMap<Vec<u8>, (ProtocolConfig, InstructionConfig)>>> =
-            Lazy::new(|| RwLock::new(std::collections::HashMap::new()));
-
         let mut configs = Vec::new();
-        let mut global_configs = DYNAMIC_CONFIGS.write();
 
         for instruction in &protocol_config.instructions {
             let discriminator = instruction.discriminator_bytes()?;
             let inner_discriminator = instruction.inner_discriminator_bytes()?.unwrap_or_default();
 
             // Create event type from config
-            let event_type = EventType::Custom(instruction.event_type.clone());
-            let protocol_type = ProtocolType::Custom(protocol_config.name.clone());
-
-            // Store in global map for parser function to access
-            global_configs.insert(
-                discriminator.clone(),
-                (protocol_config.clone(), instruction.clone()),
-            );
+            let event_type = EventType::Custom(CustomId::intern(&instruction.event_type));
+            let protocol_type = ProtocolType::Custom(CustomId::intern(&protocol_config.name));
 
             let config = GenericEventParseConfig {
                 program_id: protocol_config.program_id,
@@ -150,8 +216,9 @@ impl DynamicEventParser {
                 instruction_discriminator: Box::leak(discriminator.into_boxed_slice()),
                 event_type,
                 inner_instruction_parser: None,
-                instruction_parser: Some(parse_dynamic_instruction),
+                instruction_parser: None,
                 requires_inner_instruction: instruction.requires_inner_instruction,
+                dynamic_instruction: Some(Arc::new(instruction.clone())),
             };
 
             configs.push(config);
@@ -161,15 +228,14 @@ impl DynamicEventParser {
     }
 
     /// Parse a dynamic event from instruction data
-    fn parse_dynamic_event(
-        _protocol_config: &ProtocolConfig,
+    pub fn parse_instruction(
         instruction_config: &InstructionConfig,
         data: &[u8],
         accounts: &[Pubkey],
         metadata: EventMetadata,
     ) -> Option<Box<dyn UnifiedEvent>> {
         // Parse account fields
-        let mut account_map = HashMap::new();
+        let mut account_map = BTreeMap::new();
         for (idx, account_field) in instruction_config.accounts.iter().enumerate() {
             if let Some(pubkey) = accounts.get(idx) {
                 account_map.insert(account_field.name.clone(), *pubkey);
@@ -177,7 +243,7 @@ impl DynamicEventParser {
         }
 
         // Parse data fields
-        let mut data_fields = HashMap::new();
+        let mut data_fields = BTreeMap::new();
         for field in &instruction_config.data_fields {
             if let Some(value) = Self::parse_field(data, field.offset, &field.field_type) {
                 data_fields.insert(field.name.clone(), value);
@@ -192,6 +258,30 @@ impl DynamicEventParser {
         }))
     }
 
+    /// Parse the inner instruction (CPI event) of a dynamic instruction
+    pub fn parse_inner_instruction(
+        instruction_config: &InstructionConfig,
+        data: &[u8],
+        metadata: EventMetadata,
+    ) -> Option<Box<dyn UnifiedEvent>> {
+        instruction_config.inner_discriminator.as_ref()?;
+        let data_fields = instruction_config
+            .inner_data_fields
+            .iter()
+            .filter_map(|field| {
+                Self::parse_field(data, field.offset, &field.field_type)
+                    .map(|value| (field.name.clone(), value))
+            })
+            .collect();
+
+        Some(Box::new(DynamicEvent {
+            metadata,
+            instruction_name: instruction_config.name.clone(),
+            accounts: BTreeMap::new(),
+            data_fields,
+        }))
+    }
+
     /// Parse a single field from instruction data
     fn parse_field(data: &[u8], offset: usize, field_type: &FieldType) -> Option<DynamicFieldValue> {
         match field_type {
@@ -313,37 +403,3 @@ impl DynamicEventParser {
         }
     }
 }
-
-/// Global parser function for dynamic instructions
-/// This is used as the InstructionEventParser for dynamically loaded configs
-fn parse_dynamic_instruction(
-    data: &[u8],
-    accounts: &[Pubkey],
-    metadata: EventMetadata,
-) -> Option<Box<dyn UnifiedEvent>> {
-    use once_cell::sync::Lazy;
-    use parking_lot::RwLock;
-
-    // Access the global config storage
-    static DYNAMIC_CONFIGS: Lazy<RwLock<std::collections::HashMap<Vec<u8>, (ProtocolConfig, InstructionConfig)>>> =
-        Lazy::new(|| RwLock::new(std::collections::HashMap::new()));
-
-    // We need to find which instruction this is based on the event_type in metadata
-    // Since we don't have direct access to the discriminator here, we'll iterate
-    let configs = DYNAMIC_CONFIGS.read();
-
-    for (_disc, (protocol_config, instruction_config)) in configs.iter() {
-        let event_type_name = instruction_config.event_type.clone();
-        if metadata.event_type == EventType::Custom(event_type_name) {
-            return DynamicEventParser::parse_dynamic_event(
-                protocol_config,
-                instruction_config,
-                data,
-                accounts,
-                metadata,
-            );
-        }
-    }
-
-    None
-}
@@ -80,6 +80,7 @@ impl ConfigLoader {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use solana_sdk::pubkey::Pubkey;
 
     #[test]
     fn test_load_json() {
@@ -101,4 +102,120 @@ mod tests {
         assert_eq!(config.name, "test_protocol");
         assert_eq!(config.version, "1.0.0");
     }
+
+    #[test]
+    fn test_account_index_map_override() {
+        let json = r#"{
+            "name": "raydium_cpmm_hotfix",
+            "version": "1.0.1",
+            "program_id": "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C",
+            "instructions": [
+                {
+                    "name": "swap_base_input",
+                    "discriminator": "8fbe5adac41e33de",
+                    "event_type": "RaydiumCpmmSwapBaseInput",
+                    "accounts": [],
+                    "account_index_map": [1, 0, 5]
+                }
+            ]
+        }"#;
+
+        let config = ConfigLoader::load_from_json(json).unwrap();
+        let instruction = &config.instructions[0];
+        assert!(instruction.is_account_override());
+        let accounts: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        assert_eq!(
+            instruction.remap_accounts(&accounts),
+            vec![accounts[1], accounts[0], Pubkey::default()]
+        );
+    }
+
+    #[test]
+    fn test_orca_whirlpool_position_bundle() {
+        use crate::streaming::event_parser::config::dynamic_parser::{
+            DynamicEvent, DynamicEventParser, DynamicFieldValue,
+        };
+        use crate::streaming::event_parser::UnifiedEvent;
+
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/configs/protocols/orca_whirlpool.json");
+        let config = ConfigLoader::load_from_file(path).unwrap();
+        let open = config
+            .instructions
+            .iter()
+            .find(|instruction| instruction.name == "open_bundled_position")
+            .unwrap();
+        let mut data = 7u16.to_le_bytes().to_vec();
+        data.extend_from_slice(&(-128i32).to_le_bytes());
+        data.extend_from_slice(&64i32.to_le_bytes());
+        let accounts: Vec<Pubkey> = (0..8).map(|_| Pubkey::new_unique()).collect();
+        let event =
+            DynamicEventParser::parse_instruction(open, &data, &accounts, Default::default())
+                .unwrap();
+        let event = event.as_any().downcast_ref::<DynamicEvent>().unwrap();
+        assert_eq!(event.u64_field("bundle_index"), Some(7));
+        assert!(matches!(event.data_fields["tick_lower_index"], DynamicFieldValue::I32(-128)));
+        assert_eq!(event.accounts["whirlpool"], accounts[4]);
+        for name in ["collect_fees", "collect_fees_v2", "collect_reward", "collect_reward_v2"] {
+            assert!(config.instructions.iter().any(|instruction| instruction.name == name));
+        }
+    }
+
+    #[test]
+    fn test_merge_policy() {
+        use crate::streaming::event_parser::config::dynamic_parser::{
+            DynamicEvent, DynamicFieldValue,
+        };
+        use crate::streaming::event_parser::core::MergePolicy;
+        use crate::streaming::event_parser::UnifiedEvent;
+
+        let json = r#"{
+            "name": "test_protocol",
+            "version": "1.0.0",
+            "program_id": "11111111111111111111111111111111",
+            "instructions": [
+                { "name": "a", "discriminator": "01", "event_type": "A", "accounts": [] },
+                {
+                    "name": "b",
+                    "discriminator": "02",
+                    "event_type": "B",
+                    "accounts": [],
+                    "merge_policy": "prefer_outer"
+                }
+            ]
+        }"#;
+        let config = ConfigLoader::load_from_json(json).unwrap();
+        assert_eq!(config.instructions[0].merge_policy, MergePolicy::PreferInner);
+        assert_eq!(config.instructions[1].merge_policy, MergePolicy::PreferOuter);
+
+        let event = |fields: &[(&str, u64)]| DynamicEvent {
+            metadata: Default::default(),
+            instruction_name: "swap".to_string(),
+            accounts: Default::default(),
+            data_fields: fields
+                .iter()
+                .map(|(name, v)| (name.to_string(), DynamicFieldValue::U64(*v)))
+                .collect(),
+        };
+        let outer = event(&[("amount_in", 100)]);
+        let inner = event(&[("amount_in", 99), ("amount_out", 7)]);
+
+        let mut merged = outer.clone();
+        merged.merge(&inner);
+        assert_eq!(merged.u64_field("amount_in"), Some(99));
+
+        let mut merged = outer.clone();
+        merged.merge_with(&inner, MergePolicy::PreferOuter);
+        assert_eq!(merged.u64_field("amount_in"), Some(100));
+        assert_eq!(merged.u64_field("amount_out"), Some(7));
+
+        // 执行结果为 0 时，默认策略仍以内层为准
+        let zero = event(&[("amount_in", 0)]);
+        let mut merged = outer.clone();
+        merged.merge(&zero);
+        assert_eq!(merged.u64_field("amount_in"), Some(0));
+
+        let mut merged = outer.clone();
+        merged.merge_with(&zero, MergePolicy::PreferNonEmptyInner);
+        assert_eq!(merged.u64_field("amount_in"), Some(100));
+    }
 }
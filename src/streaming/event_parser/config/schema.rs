@@ -1,3 +1,4 @@
+use crate::streaming::event_parser::core::MergePolicy;
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
@@ -52,6 +53,22 @@ pub struct InstructionConfig {
     /// Inner instruction discriminator if needed
     #[serde(default)]
     pub inner_discriminator: Option<String>,
+
+    /// Fields decoded from the inner instruction (after its 16-byte discriminator),
+    /// merged into the event's data fields, e.g. amounts from an Anchor CPI event
+    #[serde(default)]
+    pub inner_data_fields: Vec<DataField>,
+
+    /// Account order override for a built-in protocol instruction with the same program
+    /// ID and discriminator: entry `i` is the instruction account index the built-in
+    /// parser reads as its `i`-th account. Empty keeps the compiled layout.
+    #[serde(default)]
+    pub account_index_map: Vec<usize>,
+
+    /// How the inner instruction event is merged into this one
+    /// (`prefer_inner`, `prefer_non_empty_inner` or `prefer_outer`, default `prefer_inner`)
+    #[serde(default)]
+    pub merge_policy: MergePolicy,
 }
 
 /// Account field definition
@@ -110,7 +127,7 @@ pub enum FieldType {
 }
 
 /// Event configuration for runtime event creation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventConfig {
     pub event_type: String,
     pub account_map: HashMap<String, usize>,
@@ -118,7 +135,7 @@ pub struct EventConfig {
 }
 
 /// Serde helper for Pubkey serialization
-mod pubkey_string {
+pub(crate) mod pubkey_string {
     use serde::{Deserialize, Deserializer, Serializer};
     use solana_sdk::pubkey::Pubkey;
     use std::str::FromStr;
@@ -182,6 +199,23 @@ impl InstructionConfig {
             .map_err(|e| anyhow::anyhow!("Failed to decode discriminator: {}", e))
     }
 
+    /// Whether this instruction only overrides a built-in parser's account layout
+    pub fn is_account_override(&self) -> bool {
+        !self.account_index_map.is_empty()
+    }
+
+    /// Reorder instruction accounts into the built-in parser's expected layout,
+    /// indices past the end resolve to `Pubkey::default()`
+    pub fn remap_accounts(&self, accounts: &[Pubkey]) -> Vec<Pubkey> {
+        if self.account_index_map.is_empty() {
+            return accounts.to_vec();
+        }
+        self.account_index_map
+            .iter()
+            .map(|&idx| accounts.get(idx).copied().unwrap_or_default())
+            .collect()
+    }
+
     /// Get inner discriminator as bytes
     pub fn inner_discriminator_bytes(&self) -> anyhow::Result<Option<Vec<u8>>> {
         if let Some(ref inner_disc) = self.inner_discriminator {
@@ -4,8 +4,11 @@ use crate::streaming::event_parser::common::filter::EventTypeFilter;
 use crate::streaming::event_parser::common::high_performance_clock::elapsed_micros_since;
 use crate::streaming::event_parser::common::{EventMetadata, EventType, ProtocolType};
 use crate::streaming::event_parser::core::traits::UnifiedEvent;
+#[cfg(feature = "raydium-amm-v4")]
 use crate::streaming::event_parser::protocols::raydium_amm_v4::parser::RAYDIUM_AMM_V4_PROGRAM_ID;
+#[cfg(feature = "raydium-clmm")]
 use crate::streaming::event_parser::protocols::raydium_clmm::parser::RAYDIUM_CLMM_PROGRAM_ID;
+#[cfg(feature = "raydium-cpmm")]
 use crate::streaming::event_parser::protocols::raydium_cpmm::parser::RAYDIUM_CPMM_PROGRAM_ID;
 use crate::streaming::event_parser::Protocol;
 use crate::streaming::grpc::AccountPretty;
@@ -15,8 +18,16 @@ use solana_sdk::pubkey::Pubkey;
 use spl_token::solana_program::program_pack::Pack;
 use spl_token::state::{Account, Mint};
 use spl_token_2022::{
-    extension::StateWithExtensions,
-    state::{Account as Account2022, Mint as Mint2022},
+    extension::{
+        default_account_state::DefaultAccountState,
+        interest_bearing_mint::InterestBearingConfig,
+        non_transferable::NonTransferable,
+        permanent_delegate::PermanentDelegate,
+        transfer_fee::{TransferFee, TransferFeeConfig},
+        transfer_hook::TransferHook,
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::{Account as Account2022, AccountState, Mint as Mint2022},
 };
 use std::collections::HashMap;
 use std::sync::OnceLock;
@@ -28,6 +39,8 @@ pub struct AccountEventParseConfig {
     pub protocol_type: ProtocolType,
     pub event_type: EventType,
     pub account_discriminator: &'static [u8],
+    /// 账户数据的固定长度，None 表示不限；订阅时转为 datasize 过滤，分发时长度不符直接跳过
+    pub account_data_size: Option<usize>,
     pub account_parser: AccountEventParserFn,
 }
 
@@ -41,7 +54,11 @@ pub struct TokenAccountEvent {
     pub owner: Pubkey,
     pub rent_epoch: u64,
     pub amount: Option<u64>,
+    /// 代币账户持有者（解析失败时为 owner）
     pub token_owner: Pubkey,
+    /// 代币账户对应的 mint（解析失败时为默认值）
+    #[serde(default)]
+    pub mint: Pubkey,
 }
 impl_unified_event!(TokenAccountEvent,);
 
@@ -70,9 +87,119 @@ pub struct TokenInfoEvent {
     pub rent_epoch: u64,
     pub supply: u64,
     pub decimals: u8,
+    /// Token-2022 转账手续费配置（无该扩展时为 None）
+    #[serde(default)]
+    pub transfer_fee: Option<TransferFeeInfo>,
+    /// None 表示已放弃铸币权限
+    #[serde(default)]
+    pub mint_authority: Option<Pubkey>,
+    #[serde(default)]
+    pub freeze_authority: Option<Pubkey>,
+    /// Token-2022 扩展中与交易风险相关的部分（spl-token 铸币为默认值）
+    #[serde(default)]
+    pub risk: MintRiskFlags,
 }
 impl_unified_event!(TokenInfoEvent,);
 
+/// Token-2022 transfer fee rate, effective from `epoch`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferFeeRate {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub basis_points: u16,
+}
+
+impl TransferFeeRate {
+    /// Fee withheld on a transfer of `amount` (same rounding as the token program)
+    pub fn calculate_fee(&self, amount: u64) -> u64 {
+        if self.basis_points == 0 || amount == 0 {
+            return 0;
+        }
+        let fee = (amount as u128 * self.basis_points as u128).div_ceil(10_000);
+        (fee as u64).min(self.maximum_fee)
+    }
+}
+
+/// Token-2022 TransferFeeConfig extension
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferFeeInfo {
+    pub older: TransferFeeRate,
+    pub newer: TransferFeeRate,
+}
+
+impl TransferFeeInfo {
+    /// Rate in effect for the given epoch
+    pub fn rate(&self, epoch: u64) -> &TransferFeeRate {
+        if epoch >= self.newer.epoch {
+            &self.newer
+        } else {
+            &self.older
+        }
+    }
+
+    /// Amount the recipient receives for a transfer of `gross`
+    pub fn net_amount(&self, epoch: u64, gross: u64) -> u64 {
+        gross.saturating_sub(self.rate(epoch).calculate_fee(gross))
+    }
+
+    fn from_extension(config: &TransferFeeConfig) -> Self {
+        let rate = |fee: &TransferFee| TransferFeeRate {
+            epoch: u64::from(fee.epoch),
+            maximum_fee: u64::from(fee.maximum_fee),
+            basis_points: u16::from(fee.transfer_fee_basis_points),
+        };
+        Self { older: rate(&config.older_transfer_fee), newer: rate(&config.newer_transfer_fee) }
+    }
+}
+
+/// Token-2022 mint extensions that put holders at risk
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintRiskFlags {
+    /// Can transfer or burn tokens from any account
+    pub permanent_delegate: Option<Pubkey>,
+    /// Program invoked on every transfer, which may reject sells
+    pub transfer_hook: Option<Pubkey>,
+    /// Tokens cannot be transferred at all (soulbound)
+    pub non_transferable: bool,
+    /// New token accounts start frozen
+    pub default_frozen: bool,
+    /// Current interest rate, UI amounts drift from raw amounts
+    pub interest_rate_bps: Option<i16>,
+}
+
+impl MintRiskFlags {
+    /// Whether any extension lets a third party move, block or lock holders' tokens
+    pub fn is_risky(&self) -> bool {
+        self.permanent_delegate.is_some()
+            || self.transfer_hook.is_some()
+            || self.non_transferable
+            || self.default_frozen
+    }
+
+    fn from_mint(mint: &StateWithExtensions<Mint2022>) -> Self {
+        // OptionalNonZeroPubkey 以全零表示 None
+        let key = |bytes: [u8; 32]| (bytes != [0; 32]).then(|| Pubkey::new_from_array(bytes));
+        Self {
+            permanent_delegate: mint
+                .get_extension::<PermanentDelegate>()
+                .ok()
+                .and_then(|ext| key(ext.delegate.0.to_bytes())),
+            transfer_hook: mint
+                .get_extension::<TransferHook>()
+                .ok()
+                .and_then(|ext| key(ext.program_id.0.to_bytes())),
+            non_transferable: mint.get_extension::<NonTransferable>().is_ok(),
+            default_frozen: mint
+                .get_extension::<DefaultAccountState>()
+                .is_ok_and(|ext| ext.state == AccountState::Frozen as u8),
+            interest_rate_bps: mint
+                .get_extension::<InterestBearingConfig>()
+                .ok()
+                .map(|ext| i16::from(ext.current_rate)),
+        }
+    }
+}
+
 /// 账户事件解析器
 pub type AccountEventParserFn =
     fn(account: &AccountPretty, metadata: EventMetadata) -> Option<Box<dyn UnifiedEvent>>;
@@ -88,18 +215,18 @@ static NONCE_CONFIG: OnceLock<AccountEventParseConfig> = OnceLock::new();
 pub struct AccountEventParser {}
 
 impl AccountEventParser {
-    pub fn configs(
-        protocols: &[Protocol],
-        event_type_filter: Option<&EventTypeFilter>,
-    ) -> Vec<AccountEventParseConfig> {
-        let protocols_map = PROTOCOL_CONFIGS_CACHE.get_or_init(|| {
+    fn protocol_configs() -> &'static HashMap<Protocol, Vec<AccountEventParseConfig>> {
+        PROTOCOL_CONFIGS_CACHE.get_or_init(|| {
+            #[allow(unused_mut)]
             let mut map: HashMap<Protocol, Vec<AccountEventParseConfig>> = HashMap::new();
+            #[cfg(feature = "raydium-cpmm")]
             map.insert(Protocol::RaydiumCpmm, vec![
                 AccountEventParseConfig {
                     program_id: RAYDIUM_CPMM_PROGRAM_ID,
                     protocol_type: ProtocolType::RaydiumCpmm,
                     event_type: EventType::AccountRaydiumCpmmAmmConfig,
                     account_discriminator: crate::streaming::event_parser::protocols::raydium_cpmm::discriminators::AMM_CONFIG,
+                    account_data_size: Some(crate::streaming::event_parser::protocols::raydium_cpmm::types::AMM_CONFIG_ACCOUNT_SIZE),
                     account_parser: crate::streaming::event_parser::protocols::raydium_cpmm::types::amm_config_parser,
                 },
                 AccountEventParseConfig {
@@ -107,15 +234,18 @@ impl AccountEventParser {
                     protocol_type: ProtocolType::RaydiumCpmm,
                     event_type: EventType::AccountRaydiumCpmmPoolState,
                     account_discriminator: crate::streaming::event_parser::protocols::raydium_cpmm::discriminators::POOL_STATE,
+                    account_data_size: Some(crate::streaming::event_parser::protocols::raydium_cpmm::types::POOL_STATE_ACCOUNT_SIZE),
                     account_parser: crate::streaming::event_parser::protocols::raydium_cpmm::types::pool_state_parser,
                 },
             ]);
+            #[cfg(feature = "raydium-clmm")]
             map.insert(Protocol::RaydiumClmm, vec![
                 AccountEventParseConfig {
                     program_id: RAYDIUM_CLMM_PROGRAM_ID,
                     protocol_type: ProtocolType::RaydiumClmm,
                     event_type: EventType::AccountRaydiumClmmAmmConfig,
                     account_discriminator: crate::streaming::event_parser::protocols::raydium_clmm::discriminators::AMM_CONFIG,
+                    account_data_size: Some(crate::streaming::event_parser::protocols::raydium_clmm::types::AMM_CONFIG_ACCOUNT_SIZE),
                     account_parser: crate::streaming::event_parser::protocols::raydium_clmm::types::amm_config_parser,
                 },
                 AccountEventParseConfig {
@@ -123,6 +253,7 @@ impl AccountEventParser {
                     protocol_type: ProtocolType::RaydiumClmm,
                     event_type: EventType::AccountRaydiumClmmPoolState,
                     account_discriminator: crate::streaming::event_parser::protocols::raydium_clmm::discriminators::POOL_STATE,
+                    account_data_size: Some(crate::streaming::event_parser::protocols::raydium_clmm::types::POOL_STATE_ACCOUNT_SIZE),
                     account_parser: crate::streaming::event_parser::protocols::raydium_clmm::types::pool_state_parser,
                 },
                 AccountEventParseConfig {
@@ -130,20 +261,53 @@ impl AccountEventParser {
                     protocol_type: ProtocolType::RaydiumClmm,
                     event_type: EventType::AccountRaydiumClmmTickArrayState,
                     account_discriminator: crate::streaming::event_parser::protocols::raydium_clmm::discriminators::TICK_ARRAY_STATE,
+                    account_data_size: Some(crate::streaming::event_parser::protocols::raydium_clmm::types::TICK_ARRAY_STATE_ACCOUNT_SIZE),
                     account_parser: crate::streaming::event_parser::protocols::raydium_clmm::types::tick_array_state_parser,
                 },
             ]);
+            #[cfg(feature = "raydium-amm-v4")]
             map.insert(Protocol::RaydiumAmmV4, vec![
                 AccountEventParseConfig {
                     program_id: RAYDIUM_AMM_V4_PROGRAM_ID,
                     protocol_type: ProtocolType::RaydiumAmmV4,
                     event_type: EventType::AccountRaydiumAmmV4AmmInfo,
                     account_discriminator: crate::streaming::event_parser::protocols::raydium_amm_v4::discriminators::AMM_INFO,
+                    account_data_size: Some(crate::streaming::event_parser::protocols::raydium_amm_v4::types::AMM_INFO_ACCOUNT_SIZE),
                     account_parser: crate::streaming::event_parser::protocols::raydium_amm_v4::types::amm_info_parser,
                 },
             ]);
             map
-        });
+        })
+    }
+
+    /// Data sizes of the accounts parsed for `program_id`, honouring the event type filter
+    ///
+    /// Empty when the program is unknown or one of its account kinds has no fixed size.
+    pub fn account_data_sizes(
+        program_id: &Pubkey,
+        event_type_filter: Option<&EventTypeFilter>,
+    ) -> Vec<usize> {
+        let configs: Vec<&AccountEventParseConfig> = Self::protocol_configs()
+            .values()
+            .flatten()
+            .filter(|config| config.program_id == *program_id)
+            .filter(|config| {
+                event_type_filter.is_none_or(|filter| filter.include.contains(&config.event_type))
+            })
+            .collect();
+        let sizes: Option<Vec<usize>> =
+            configs.iter().map(|config| config.account_data_size).collect();
+        let mut sizes = sizes.unwrap_or_default();
+        sizes.sort_unstable();
+        sizes.dedup();
+        sizes
+    }
+
+    pub fn configs(
+        protocols: &[Protocol],
+        event_type_filter: Option<&EventTypeFilter>,
+    ) -> Vec<AccountEventParseConfig> {
+        let protocols_map = Self::protocol_configs();
 
         let mut configs = Vec::new();
         let empty_vec = Vec::new();
@@ -177,6 +341,7 @@ impl AccountEventParser {
                 protocol_type: ProtocolType::Common,
                 event_type: EventType::NonceAccount,
                 account_discriminator: &[1, 0, 0, 0, 1, 0, 0, 0],
+                account_data_size: None,
                 account_parser: Self::parse_nonce_account_event,
             });
             configs.push(nonce_config.clone());
@@ -187,6 +352,7 @@ impl AccountEventParser {
             protocol_type: ProtocolType::Common,
             event_type: EventType::TokenAccount,
             account_discriminator: &[],
+            account_data_size: None,
             account_parser: Self::parse_token_account_event,
         });
         configs.push(common_config.clone());
@@ -201,6 +367,9 @@ impl AccountEventParser {
     ) -> Option<Box<dyn UnifiedEvent>> {
         let configs = Self::configs(protocols, event_type_filter);
         for config in configs {
+            if config.account_data_size.is_some_and(|size| account.data.len() != size) {
+                continue;
+            }
             if config.program_id == Pubkey::default()
                 || (account.owner == config.program_id
                     && SimdUtils::fast_discriminator_match(
@@ -238,9 +407,9 @@ impl AccountEventParser {
         let lamports = account.lamports;
         let owner = account.owner;
         let rent_epoch = account.rent_epoch;
-        // Spl Token Mint
-        if account.data.len() >= Mint::LEN {
-            if let Ok(mint) = Mint::unpack_from_slice(&account.data) {
+        // Spl Token2022 Mint，需先于 Spl Token 判断，否则扩展数据会被忽略
+        if account.owner.to_bytes() == spl_token_2022::ID.to_bytes() {
+            if let Ok(mint) = StateWithExtensions::<Mint2022>::unpack(&account.data) {
                 let mut event = TokenInfoEvent {
                     metadata,
                     pubkey,
@@ -248,17 +417,32 @@ impl AccountEventParser {
                     lamports,
                     owner,
                     rent_epoch,
-                    supply: mint.supply,
-                    decimals: mint.decimals,
+                    supply: mint.base.supply,
+                    decimals: mint.base.decimals,
+                    transfer_fee: mint
+                        .get_extension::<TransferFeeConfig>()
+                        .ok()
+                        .map(TransferFeeInfo::from_extension),
+                    risk: MintRiskFlags::from_mint(&mint),
+                    mint_authority: mint
+                        .base
+                        .mint_authority
+                        .map(|key| Pubkey::new_from_array(key.to_bytes()))
+                        .into(),
+                    freeze_authority: mint
+                        .base
+                        .freeze_authority
+                        .map(|key| Pubkey::new_from_array(key.to_bytes()))
+                        .into(),
                 };
                 let recv_delta = elapsed_micros_since(account.recv_us);
                 event.set_handle_us(recv_delta);
                 return Some(Box::new(event));
             }
         }
-        // Spl Token2022 Mint
-        if account.data.len() >= Account2022::LEN {
-            if let Ok(mint) = StateWithExtensions::<Mint2022>::unpack(&account.data) {
+        // Spl Token Mint
+        if account.data.len() >= Mint::LEN {
+            if let Ok(mint) = Mint::unpack_from_slice(&account.data) {
                 let mut event = TokenInfoEvent {
                     metadata,
                     pubkey,
@@ -266,20 +450,41 @@ impl AccountEventParser {
                     lamports,
                     owner,
                     rent_epoch,
-                    supply: mint.base.supply,
-                    decimals: mint.base.decimals,
+                    supply: mint.supply,
+                    decimals: mint.decimals,
+                    transfer_fee: None,
+                    risk: MintRiskFlags::default(),
+                    mint_authority: mint
+                        .mint_authority
+                        .map(|key| Pubkey::new_from_array(key.to_bytes()))
+                        .into(),
+                    freeze_authority: mint
+                        .freeze_authority
+                        .map(|key| Pubkey::new_from_array(key.to_bytes()))
+                        .into(),
                 };
                 let recv_delta = elapsed_micros_since(account.recv_us);
                 event.set_handle_us(recv_delta);
                 return Some(Box::new(event));
             }
         }
-        let amount = if account.owner.to_bytes() == spl_token_2022::ID.to_bytes() {
-            StateWithExtensions::<Account2022>::unpack(&account.data)
-                .ok()
-                .map(|info| info.base.amount)
+        // spl-token 的 Pubkey 与 solana-sdk 版本不同，按字节转换
+        let state = if account.owner.to_bytes() == spl_token_2022::ID.to_bytes() {
+            StateWithExtensions::<Account2022>::unpack(&account.data).ok().map(|info| {
+                (
+                    info.base.amount,
+                    Pubkey::new_from_array(info.base.owner.to_bytes()),
+                    Pubkey::new_from_array(info.base.mint.to_bytes()),
+                )
+            })
         } else {
-            Account::unpack(&account.data).ok().map(|info| info.amount)
+            Account::unpack(&account.data).ok().map(|info| {
+                (
+                    info.amount,
+                    Pubkey::new_from_array(info.owner.to_bytes()),
+                    Pubkey::new_from_array(info.mint.to_bytes()),
+                )
+            })
         };
 
         let mut event = TokenAccountEvent {
@@ -289,8 +494,9 @@ impl AccountEventParser {
             lamports,
             owner,
             rent_epoch,
-            amount,
-            token_owner: account.owner,
+            amount: state.map(|(amount, _, _)| amount),
+            token_owner: state.map(|(_, token_owner, _)| token_owner).unwrap_or(account.owner),
+            mint: state.map(|(_, _, mint)| mint).unwrap_or_default(),
         };
         let recv_delta = elapsed_micros_since(account.recv_us);
         event.set_handle_us(recv_delta);
@@ -323,3 +529,17 @@ impl AccountEventParser {
         None
     }
 }
+
+#[cfg(all(test, feature = "raydium-clmm"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_data_sizes() {
+        let program = RAYDIUM_CLMM_PROGRAM_ID;
+        assert_eq!(AccountEventParser::account_data_sizes(&program, None), vec![117, 1544, 10240]);
+        let filter = EventTypeFilter { include: vec![EventType::AccountRaydiumClmmPoolState] };
+        assert_eq!(AccountEventParser::account_data_sizes(&program, Some(&filter)), vec![1544]);
+        assert!(AccountEventParser::account_data_sizes(&Pubkey::new_unique(), None).is_empty());
+    }
+}
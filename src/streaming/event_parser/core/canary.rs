@@ -0,0 +1,340 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use prost_types::Timestamp;
+use serde::Serialize;
+use serde_json::Value;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
+
+use super::event_parser::EventParser;
+use crate::common::AnyResult;
+use crate::streaming::event_parser::common::filter::EventTypeFilter;
+use crate::streaming::event_parser::config::ProtocolConfig;
+use crate::streaming::event_parser::{Protocol, UnifiedEvent};
+
+/// Default number of divergences kept for inspection
+pub const DEFAULT_MAX_DIVERGENCES: usize = 100;
+
+/// How the candidate's output differs from the live output for one event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivergenceKind {
+    /// Parsed by the live config only
+    LiveOnly,
+    /// Parsed by the candidate config only
+    CandidateOnly,
+    /// Parsed by both, these fields differ (dotted JSON paths)
+    Fields(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CanaryDivergence {
+    pub signature: Signature,
+    pub slot: u64,
+    pub event_type: String,
+    pub outer_index: i64,
+    pub inner_index: Option<i64>,
+    pub kind: DivergenceKind,
+}
+
+/// Agreement between the live and the candidate parser so far
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CanaryReport {
+    pub transactions: u64,
+    pub matched: u64,
+    pub mismatched: u64,
+    pub live_only: u64,
+    pub candidate_only: u64,
+    /// Field path -> number of events where it differed
+    pub field_diffs: HashMap<String, u64>,
+    /// Most recent divergences, oldest first
+    pub recent: Vec<CanaryDivergence>,
+}
+
+impl CanaryReport {
+    /// Share of events both parsers produced identically, 100 when nothing was compared
+    pub fn match_rate_pct(&self) -> f64 {
+        let total = self.matched + self.mismatched + self.live_only + self.candidate_only;
+        if total == 0 {
+            return 100.0;
+        }
+        self.matched as f64 * 100.0 / total as f64
+    }
+}
+
+impl fmt::Display for CanaryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "transactions: {:>8}  match rate {:>6.2}%",
+            self.transactions,
+            self.match_rate_pct()
+        )?;
+        writeln!(
+            f,
+            "  matched {}  mismatched {}  live only {}  candidate only {}",
+            self.matched, self.mismatched, self.live_only, self.candidate_only
+        )?;
+        let mut fields: Vec<_> = self.field_diffs.iter().collect();
+        fields.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (field, count) in fields.into_iter().take(10) {
+            writeln!(f, "    field {:<32} {:>8}", field, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct CanaryState {
+    report: CanaryReport,
+    recent: VecDeque<CanaryDivergence>,
+}
+
+/// (event_type, outer_index, inner_index, 同位置的第几个事件)
+type EventKey = (String, i64, Option<i64>, usize);
+
+/// Shadow-parses transactions with a candidate parser and reports how its output
+/// differs from the live parser's, without touching the live output
+///
+/// Build the candidate from the new config set (e.g. updated discriminators or an
+/// account-layout override) and set it as `StreamClientConfig::parser_canary`: every
+/// `sample_every`-th gRPC transaction is then re-parsed by both parsers on a
+/// background task. Roll the config out once [`report`](Self::report) shows the
+/// expected match rate.
+pub struct ParserCanary {
+    candidate: EventParser,
+    sample_every: u64,
+    max_divergences: usize,
+    seen: AtomicU64,
+    state: Mutex<CanaryState>,
+}
+
+impl fmt::Debug for ParserCanary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParserCanary")
+            .field("sample_every", &self.sample_every)
+            .field("seen", &self.seen.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl ParserCanary {
+    pub fn new(candidate: EventParser) -> Self {
+        Self {
+            candidate,
+            sample_every: 1,
+            max_divergences: DEFAULT_MAX_DIVERGENCES,
+            seen: AtomicU64::new(0),
+            state: Mutex::new(CanaryState::default()),
+        }
+    }
+
+    /// Candidate parser: the built-in protocols patched by the candidate configs
+    pub fn from_configs(
+        protocols: Vec<Protocol>,
+        candidate_configs: &[ProtocolConfig],
+        event_type_filter: Option<EventTypeFilter>,
+    ) -> AnyResult<Self> {
+        let mut candidate = EventParser::new(protocols, event_type_filter);
+        for protocol_config in candidate_configs {
+            candidate.add_protocol_config(protocol_config)?;
+        }
+        Ok(Self::new(candidate))
+    }
+
+    /// Shadow-parse only every n-th transaction
+    pub fn with_sample_every(mut self, sample_every: u64) -> Self {
+        self.sample_every = sample_every.max(1);
+        self
+    }
+
+    pub fn with_max_divergences(mut self, max_divergences: usize) -> Self {
+        self.max_divergences = max_divergences;
+        self
+    }
+
+    pub fn candidate(&self) -> &EventParser {
+        &self.candidate
+    }
+
+    /// Whether the next transaction should be shadow-parsed
+    pub fn should_sample(&self) -> bool {
+        self.seen.fetch_add(1, Ordering::Relaxed) % self.sample_every == 0
+    }
+
+    /// Parses the transaction with both parsers and records the differences
+    #[allow(clippy::too_many_arguments)]
+    pub async fn shadow_grpc_transaction(
+        &self,
+        live: &EventParser,
+        grpc_tx: SubscribeUpdateTransactionInfo,
+        signature: Signature,
+        slot: Option<u64>,
+        block_time: Option<Timestamp>,
+        recv_us: i64,
+        bot_wallet: Option<Pubkey>,
+        transaction_index: Option<u64>,
+    ) -> AnyResult<()> {
+        let mut outputs = Vec::with_capacity(2);
+        for (parser, tx) in [(live, grpc_tx.clone()), (&self.candidate, grpc_tx)] {
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let sink = events.clone();
+            parser
+                .parse_grpc_transaction_owned(
+                    tx,
+                    signature,
+                    slot,
+                    block_time.clone(),
+                    recv_us,
+                    bot_wallet,
+                    transaction_index,
+                    Arc::new(move |event: Box<dyn UnifiedEvent>| sink.lock().push(event)),
+                )
+                .await?;
+            outputs.push(std::mem::take(&mut *events.lock()));
+        }
+        self.compare(&outputs[0], &outputs[1]);
+        Ok(())
+    }
+
+    /// Records the differences between the events both parsers produced for one
+    /// transaction
+    pub fn compare(&self, live: &[Box<dyn UnifiedEvent>], candidate: &[Box<dyn UnifiedEvent>]) {
+        let mut live = keyed(live);
+        let candidate = keyed(candidate);
+        let mut divergences = Vec::new();
+        let mut matched = 0;
+        let (mut mismatched, mut live_only, mut candidate_only) = (0, 0, 0);
+        let mut record = |event: &dyn UnifiedEvent, kind: DivergenceKind| {
+            divergences.push(CanaryDivergence {
+                signature: *event.signature(),
+                slot: event.slot(),
+                event_type: event.event_type().to_string(),
+                outer_index: event.outer_index(),
+                inner_index: event.inner_index(),
+                kind,
+            });
+        };
+        for (key, (event, candidate_json)) in &candidate {
+            match live.remove(key) {
+                None => {
+                    candidate_only += 1;
+                    record(*event, DivergenceKind::CandidateOnly);
+                }
+                Some((_, live_json)) => {
+                    let mut fields = Vec::new();
+                    diff_fields("", &live_json, candidate_json, &mut fields);
+                    if fields.is_empty() {
+                        matched += 1;
+                    } else {
+                        mismatched += 1;
+                        record(*event, DivergenceKind::Fields(fields));
+                    }
+                }
+            }
+        }
+        for (event, _) in live.values() {
+            live_only += 1;
+            record(*event, DivergenceKind::LiveOnly);
+        }
+
+        let mut state = self.state.lock();
+        let report = &mut state.report;
+        report.transactions += 1;
+        report.matched += matched;
+        report.mismatched += mismatched;
+        report.live_only += live_only;
+        report.candidate_only += candidate_only;
+        for divergence in &divergences {
+            if let DivergenceKind::Fields(fields) = &divergence.kind {
+                for field in fields {
+                    *report.field_diffs.entry(field.clone()).or_default() += 1;
+                }
+            }
+        }
+        for divergence in divergences {
+            state.recent.push_back(divergence);
+            if state.recent.len() > self.max_divergences {
+                state.recent.pop_front();
+            }
+        }
+    }
+
+    pub fn report(&self) -> CanaryReport {
+        let state = self.state.lock();
+        CanaryReport { recent: state.recent.iter().cloned().collect(), ..state.report.clone() }
+    }
+
+    pub fn reset(&self) {
+        *self.state.lock() = CanaryState::default();
+    }
+}
+
+fn keyed(events: &[Box<dyn UnifiedEvent>]) -> BTreeMap<EventKey, (&dyn UnifiedEvent, Value)> {
+    let mut keyed = BTreeMap::new();
+    for event in events {
+        let mut key = (event.event_type().to_string(), event.outer_index(), event.inner_index(), 0);
+        while keyed.contains_key(&key) {
+            key.3 += 1;
+        }
+        let mut json = event.to_json();
+        // 计时字段每次解析都不同，不参与比较
+        if let Some(metadata) = json.get_mut("metadata").and_then(Value::as_object_mut) {
+            metadata.remove("recv_us");
+            metadata.remove("handle_us");
+        }
+        keyed.insert(key, (event.as_ref(), json));
+    }
+    keyed
+}
+
+/// Dotted paths of the leaves that differ between two JSON values
+fn diff_fields(path: &str, live: &Value, candidate: &Value, out: &mut Vec<String>) {
+    match (live, candidate) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (name, value) in a {
+                let child = if path.is_empty() { name.clone() } else { format!("{path}.{name}") };
+                diff_fields(&child, value, b.get(name).unwrap_or(&Value::Null), out);
+            }
+            for (name, value) in b {
+                if !a.contains_key(name) && !value.is_null() {
+                    out.push(if path.is_empty() { name.clone() } else { format!("{path}.{name}") });
+                }
+            }
+        }
+        (a, b) if a != b => out.push(path.to_string()),
+        _ => {}
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::EventMetadata;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    #[test]
+    fn test_canary_reports_divergences() {
+        let canary = ParserCanary::new(EventParser::new(vec![], None));
+        let swap = |outer_index, amount_in, recv_us| -> Box<dyn UnifiedEvent> {
+            Box::new(RaydiumCpmmSwapEvent {
+                metadata: EventMetadata { outer_index, recv_us, ..Default::default() },
+                amount_in,
+                ..Default::default()
+            })
+        };
+
+        canary.compare(&[swap(0, 100, 1), swap(1, 5, 1)], &[swap(0, 100, 2), swap(1, 6, 2)]);
+        canary.compare(&[swap(2, 7, 1)], &[]);
+        let report = canary.report();
+        assert_eq!((report.transactions, report.matched, report.mismatched), (2, 1, 1));
+        assert_eq!(report.live_only, 1);
+        assert_eq!(report.field_diffs.get("amount_in"), Some(&1));
+        assert_eq!(report.recent[0].kind, DivergenceKind::Fields(vec!["amount_in".to_string()]));
+        assert!((report.match_rate_pct() - 100.0 / 3.0).abs() < 1e-9);
+    }
+}
@@ -1,17 +1,23 @@
 use crate::streaming::event_parser::common::high_performance_clock::elapsed_micros_since;
 use crate::streaming::event_parser::core::traits::UnifiedEvent;
 use crate::streaming::event_parser::protocols::block::block_meta_event::BlockMetaEvent;
+use crate::streaming::grpc::BlockMetaPretty;
 
 pub struct CommonEventParser {}
 
 impl CommonEventParser {
     pub fn generate_block_meta_event(
-        slot: u64,
-        block_hash: String,
+        block_meta: BlockMetaPretty,
         block_time_ms: i64,
-        recv_us: i64,
     ) -> Box<dyn UnifiedEvent> {
-        let mut block_meta_event = BlockMetaEvent::new(slot, block_hash, block_time_ms, recv_us);
+        let recv_us = block_meta.recv_us;
+        let mut block_meta_event =
+            BlockMetaEvent::new(block_meta.slot, block_meta.block_hash, block_time_ms, recv_us);
+        block_meta_event.parent_slot = block_meta.parent_slot;
+        block_meta_event.parent_block_hash = block_meta.parent_block_hash;
+        block_meta_event.block_height = block_meta.block_height;
+        block_meta_event.executed_transaction_count = block_meta.executed_transaction_count;
+        block_meta_event.leader_reward = block_meta.leader_reward;
         block_meta_event.set_handle_us(elapsed_micros_since(recv_us));
         Box::new(block_meta_event)
     }
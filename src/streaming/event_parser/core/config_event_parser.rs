@@ -35,18 +35,9 @@ impl ConfigurableEventParser {
         // Create base parser with static protocols
         let mut parser = EventParser::new(static_protocols, event_type_filter.clone());
 
-        // Merge dynamic configs into the parser
+        // Merge dynamic configs into the parser, account overrides patch built-in protocols
         for config in dynamic_configs {
-            let discriminator = config.instruction_discriminator.to_vec();
-            parser
-                .instruction_configs
-                .entry(discriminator)
-                .or_insert_with(Vec::new)
-                .push(config.clone());
-
-            if !parser.program_ids.contains(&config.program_id) {
-                parser.program_ids.push(config.program_id);
-            }
+            parser.add_dynamic_config(config);
         }
 
         Ok(Self { parser, configs })
@@ -71,24 +62,12 @@ impl ConfigurableEventParser {
         // Create base parser with static protocols
         let mut parser = EventParser::new(static_protocols, event_type_filter);
 
-        // Merge dynamic configs into the parser
+        // Merge dynamic configs into the parser, account overrides patch built-in protocols
         for config in dynamic_configs {
-            let discriminator = config.instruction_discriminator.to_vec();
-            parser
-                .instruction_configs
-                .entry(discriminator)
-                .or_insert_with(Vec::new)
-                .push(config.clone());
-
-            if !parser.program_ids.contains(&config.program_id) {
-                parser.program_ids.push(config.program_id);
-            }
+            parser.add_dynamic_config(config);
         }
 
-        Ok(Self {
-            parser,
-            configs: all_configs,
-        })
+        Ok(Self { parser, configs: all_configs })
     }
 
     /// Get all loaded protocol names
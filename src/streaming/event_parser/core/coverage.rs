@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
+
+use super::account_event_parser::{AccountEventParseConfig, AccountEventParser};
+use super::event_parser::EventParser;
+use super::raw_parser::RawTransactionMeta;
+use crate::streaming::event_parser::common::EventMetadata;
+use crate::streaming::grpc::AccountPretty;
+
+/// 协议未配置任何鉴别器时，未知鉴别器按此长度截取
+const DEFAULT_DISCRIMINATOR_LEN: usize = 8;
+
+/// Parser coverage of one watched program
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProgramCoverage {
+    pub program_id: Pubkey,
+    pub instructions: u64,
+    pub parsed_instructions: u64,
+    /// Hex discriminator of unparsed instructions -> count
+    pub unknown_instructions: HashMap<String, u64>,
+    pub accounts: u64,
+    pub decoded_accounts: u64,
+    /// Hex discriminator of undecoded accounts -> count
+    pub unknown_accounts: HashMap<String, u64>,
+}
+
+impl ProgramCoverage {
+    pub fn parsed_pct(&self) -> f64 {
+        pct(self.parsed_instructions, self.instructions)
+    }
+
+    pub fn decoded_pct(&self) -> f64 {
+        pct(self.decoded_accounts, self.accounts)
+    }
+
+    /// Most frequent unknown instruction discriminators
+    pub fn top_unknown_instructions(&self, n: usize) -> Vec<(String, u64)> {
+        top(&self.unknown_instructions, n)
+    }
+
+    /// Most frequent unknown account discriminators
+    pub fn top_unknown_accounts(&self, n: usize) -> Vec<(String, u64)> {
+        top(&self.unknown_accounts, n)
+    }
+}
+
+fn pct(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    part as f64 * 100.0 / total as f64
+}
+
+fn top(counts: &HashMap<String, u64>, n: usize) -> Vec<(String, u64)> {
+    let mut entries: Vec<_> = counts.iter().map(|(k, v)| (k.clone(), *v)).collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    entries.truncate(n);
+    entries
+}
+
+/// Coverage of all watched programs, printable as a text table
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CoverageReport {
+    pub programs: Vec<ProgramCoverage>,
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for program in &self.programs {
+            writeln!(f, "{}", program.program_id)?;
+            writeln!(
+                f,
+                "  instructions: {:>8}  parsed {:>6.2}%",
+                program.instructions,
+                program.parsed_pct()
+            )?;
+            for (discriminator, count) in program.top_unknown_instructions(5) {
+                writeln!(f, "    unknown {:<18} {:>8}", discriminator, count)?;
+            }
+            writeln!(
+                f,
+                "  accounts:     {:>8}  decoded {:>5.2}%",
+                program.accounts,
+                program.decoded_pct()
+            )?;
+            for (discriminator, count) in program.top_unknown_accounts(5) {
+                writeln!(f, "    unknown {:<18} {:>8}", discriminator, count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Counts, per program watched by an [`EventParser`], how much of the observed traffic
+/// its parsers understand
+///
+/// Feed it a stream sample or a recorded file (e.g. a `DebugBundle`) and print the
+/// [`report`](Self::report) to find instructions and accounts that still need a parser.
+pub struct CoverageTracker {
+    /// program -> known instruction discriminators (outer and CPI event)
+    instructions: HashMap<Pubkey, Vec<&'static [u8]>>,
+    accounts: HashMap<Pubkey, Vec<AccountEventParseConfig>>,
+    programs: HashMap<Pubkey, ProgramCoverage>,
+}
+
+impl CoverageTracker {
+    pub fn new(parser: &EventParser) -> Self {
+        let mut instructions: HashMap<Pubkey, Vec<&'static [u8]>> =
+            parser.program_ids.iter().map(|program| (*program, Vec::new())).collect();
+        for config in parser.instruction_configs.values().flatten() {
+            let known = instructions.entry(config.program_id).or_default();
+            for discriminator in
+                [config.instruction_discriminator, config.inner_instruction_discriminator]
+            {
+                if !discriminator.is_empty() && !known.contains(&discriminator) {
+                    known.push(discriminator);
+                }
+            }
+        }
+        let mut accounts: HashMap<Pubkey, Vec<AccountEventParseConfig>> = HashMap::new();
+        for config in AccountEventParser::configs(&parser.protocols, None) {
+            if instructions.contains_key(&config.program_id) {
+                accounts.entry(config.program_id).or_default().push(config);
+            }
+        }
+        Self { instructions, accounts, programs: HashMap::new() }
+    }
+
+    fn program(&mut self, program_id: &Pubkey) -> &mut ProgramCoverage {
+        self.programs
+            .entry(*program_id)
+            .or_insert_with(|| ProgramCoverage { program_id: *program_id, ..Default::default() })
+    }
+
+    /// Records one instruction (outer or inner); other programs are ignored
+    pub fn record_instruction(&mut self, program_id: &Pubkey, data: &[u8]) {
+        let Some(known) = self.instructions.get(program_id) else {
+            return;
+        };
+        let parsed = known.iter().any(|discriminator| data.starts_with(discriminator));
+        // 按最短的已知鉴别器截取，CPI 事件的长鉴别器不影响分组
+        let key_len = known.iter().map(|d| d.len()).min().unwrap_or(DEFAULT_DISCRIMINATOR_LEN);
+        let key = hex::encode(&data[..key_len.min(data.len())]);
+        let program = self.program(program_id);
+        program.instructions += 1;
+        if parsed {
+            program.parsed_instructions += 1;
+        } else {
+            *program.unknown_instructions.entry(key).or_default() += 1;
+        }
+    }
+
+    /// Records an account owned by a watched program
+    pub fn record_account(&mut self, account: &AccountPretty) {
+        if !self.instructions.contains_key(&account.owner) {
+            return;
+        }
+        let configs = self.accounts.get(&account.owner).map(Vec::as_slice).unwrap_or_default();
+        let decoded = configs.iter().any(|config| {
+            config.account_data_size.is_none_or(|size| size == account.data.len())
+                && account.data.starts_with(config.account_discriminator)
+                && (config.account_parser)(account, EventMetadata::default()).is_some()
+        });
+        let key_len = configs
+            .iter()
+            .map(|config| config.account_discriminator.len())
+            .max()
+            .filter(|len| *len > 0)
+            .unwrap_or(DEFAULT_DISCRIMINATOR_LEN);
+        let key = hex::encode(&account.data[..key_len.min(account.data.len())]);
+        let program = self.program(&account.owner);
+        program.accounts += 1;
+        if decoded {
+            program.decoded_accounts += 1;
+        } else {
+            *program.unknown_accounts.entry(key).or_default() += 1;
+        }
+    }
+
+    /// Records the outer and inner instructions of a gRPC transaction
+    pub fn record_grpc_transaction(&mut self, info: &SubscribeUpdateTransactionInfo) {
+        let Some(message) = info.transaction.as_ref().and_then(|tx| tx.message.as_ref()) else {
+            return;
+        };
+        let meta = info.meta.as_ref();
+        let accounts: Vec<Pubkey> = message
+            .account_keys
+            .iter()
+            .chain(meta.into_iter().flat_map(|meta| {
+                meta.loaded_writable_addresses.iter().chain(&meta.loaded_readonly_addresses)
+            }))
+            .map(|key| Pubkey::try_from(key.as_slice()).unwrap_or_default())
+            .collect();
+        let program = |index: u32| accounts.get(index as usize).copied();
+        let instructions = message
+            .instructions
+            .iter()
+            .map(|ix| (ix.program_id_index, ix.data.as_slice()))
+            .chain(meta.into_iter().flat_map(|meta| {
+                meta.inner_instructions.iter().flat_map(|inner| {
+                    inner.instructions.iter().map(|ix| (ix.program_id_index, ix.data.as_slice()))
+                })
+            }));
+        for (program_index, data) in instructions {
+            if let Some(program_id) = program(program_index) {
+                self.record_instruction(&program_id, data);
+            }
+        }
+    }
+
+    /// Records the outer and inner instructions of a decoded transaction
+    pub fn record_transaction(
+        &mut self,
+        transaction: &VersionedTransaction,
+        meta: &RawTransactionMeta,
+    ) {
+        let accounts: Vec<Pubkey> = transaction
+            .message
+            .static_account_keys()
+            .iter()
+            .chain(&meta.loaded_writable_addresses)
+            .chain(&meta.loaded_readonly_addresses)
+            .copied()
+            .collect();
+        let instructions = transaction
+            .message
+            .instructions()
+            .iter()
+            .map(|ix| (ix.program_id_index, ix.data.as_slice()))
+            .chain(meta.inner_instructions.iter().flat_map(|inner| {
+                inner
+                    .instructions
+                    .iter()
+                    .map(|ix| (ix.instruction.program_id_index, ix.instruction.data.as_slice()))
+            }));
+        for (program_index, data) in instructions {
+            if let Some(program_id) = accounts.get(program_index as usize) {
+                self.record_instruction(program_id, data);
+            }
+        }
+    }
+
+    /// Coverage so far, programs with the most traffic first
+    pub fn report(&self) -> CoverageReport {
+        let mut programs: Vec<_> = self.programs.values().cloned().collect();
+        programs.sort_by(|a, b| {
+            (b.instructions + b.accounts)
+                .cmp(&(a.instructions + a.accounts))
+                .then_with(|| a.program_id.cmp(&b.program_id))
+        });
+        CoverageReport { programs }
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::discriminators::SWAP_BASE_IN;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::parser::RAYDIUM_CPMM_PROGRAM_ID;
+    use crate::streaming::event_parser::Protocol;
+
+    #[test]
+    fn test_coverage_report() {
+        let parser = EventParser::new(vec![Protocol::RaydiumCpmm], None);
+        let mut tracker = CoverageTracker::new(&parser);
+
+        tracker.record_instruction(&RAYDIUM_CPMM_PROGRAM_ID, SWAP_BASE_IN);
+        tracker.record_instruction(&RAYDIUM_CPMM_PROGRAM_ID, &[7; 12]);
+        tracker.record_instruction(&RAYDIUM_CPMM_PROGRAM_ID, &[7; 8]);
+        tracker.record_instruction(&Pubkey::new_unique(), &[1]);
+        tracker.record_account(&AccountPretty {
+            owner: RAYDIUM_CPMM_PROGRAM_ID,
+            data: vec![9; 40],
+            ..Default::default()
+        });
+
+        let report = tracker.report();
+        assert_eq!(report.programs.len(), 1);
+        let program = &report.programs[0];
+        assert_eq!((program.instructions, program.parsed_instructions), (3, 1));
+        assert_eq!(program.top_unknown_instructions(1), vec![(hex::encode([7; 8]), 2)]);
+        assert_eq!((program.accounts, program.decoded_accounts), (1, 0));
+        assert!(report.to_string().contains("parsed  33.33%"));
+    }
+}
@@ -2,74 +2,62 @@ use crate::streaming::{
     common::SimdUtils,
     event_parser::{
         common::{
+            call_tree::TRANSACTION_LEVEL_STACK_HEIGHT,
             filter::EventTypeFilter,
-            high_performance_clock::{elapsed_micros_since, get_high_perf_clock},
-            parse_swap_data_from_next_grpc_instructions, parse_swap_data_from_next_instructions,
-            EventMetadata, EventType, ProtocolType,
+            high_performance_clock::{system_clock, Clock, MockClock},
+            parse_compute_budget, parse_swap_data_from_next_grpc_instructions,
+            parse_swap_data_from_next_instructions,
+            program_labels::{ProgramAnnotation, ProgramRegistry},
+            read_account, ComputeBudgetInfo, EventMetadata, EventType, ProtocolType,
         },
-        protocols::{
-            raydium_amm_v4::parser::RAYDIUM_AMM_V4_PROGRAM_ID,
-            raydium_clmm::parser::RAYDIUM_CLMM_PROGRAM_ID,
-            raydium_cpmm::parser::RAYDIUM_CPMM_PROGRAM_ID,
+        config::{DynamicEventParser, InstructionConfig, ProtocolConfig},
+        core::{
+            program_filter::ProgramIdFilter, protocol_switches::ProtocolSwitches,
+            raw_parser::RawTransactionMeta,
         },
         Protocol, UnifiedEvent,
     },
 };
 use prost_types::Timestamp;
-use solana_sdk::{bs58, message::compiled_instruction::CompiledInstruction, pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction};
-use solana_transaction_status::{
-    EncodedConfirmedTransactionWithStatusMeta, InnerInstruction, InnerInstructions, UiInstruction,
-};
+use smallvec::SmallVec;
+use solana_sdk::{message::compiled_instruction::CompiledInstruction, pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction};
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, InnerInstructions};
 use std::{
     collections::HashMap,
     sync::{Arc, LazyLock},
 };
 use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
 
-/// 高性能账户公钥缓存，避免重复Vec分配
-#[derive(Debug)]
-pub struct AccountPubkeyCache {
-    /// 预分配的账户公钥向量，避免每次重新分配
-    cache: Vec<Pubkey>,
+/// 指令账户公钥列表，32 个账户以内不分配堆内存
+pub type AccountPubkeys = SmallVec<[Pubkey; 32]>;
+
+/// 从指令账户索引构建账户公钥列表，越界索引被跳过
+///
+/// 每次调用独立构建，并发解析的任务之间不共享缓冲区，也不需要加锁。
+#[inline]
+pub fn build_account_pubkeys(
+    instruction_accounts: &[u8],
+    all_accounts: &[Pubkey],
+) -> AccountPubkeys {
+    instruction_accounts.iter().filter_map(|&idx| all_accounts.get(idx as usize).copied()).collect()
 }
 
-impl AccountPubkeyCache {
-    /// 创建新的账户公钥缓存
-    pub fn new() -> Self {
-        Self {
-            cache: Vec::with_capacity(32), // 预分配32个位置，覆盖大多数交易
-        }
-    }
-
-    /// 从指令账户索引构建账户公钥向量，重用缓存内存
-    #[inline]
-    pub fn build_account_pubkeys(
-        &mut self,
-        instruction_accounts: &[u8],
-        all_accounts: &[Pubkey],
-    ) -> &[Pubkey] {
-        self.cache.clear();
-
-        // 确保容量足够，避免动态扩容
-        if self.cache.capacity() < instruction_accounts.len() {
-            self.cache.reserve(instruction_accounts.len() - self.cache.capacity());
-        }
-
-        // 快速填充账户公钥
-        for &idx in instruction_accounts.iter() {
-            if (idx as usize) < all_accounts.len() {
-                self.cache.push(all_accounts[idx as usize]);
-            }
-        }
-
-        &self.cache
-    }
+/// 指令（程序索引，账户索引）中引用的最大账户索引
+fn max_account_index<'a>(instructions: impl Iterator<Item = (usize, &'a [u8])>) -> Option<usize> {
+    instructions
+        .filter_map(|(program, accounts)| {
+            accounts.iter().map(|&idx| idx as usize).chain(std::iter::once(program)).max()
+        })
+        .max()
 }
 
-impl Default for AccountPubkeyCache {
-    fn default() -> Self {
-        Self::new()
-    }
+/// 复制交易账户并补齐到 `max_index`（缺失的用 Pubkey::default() 填充），只分配一次
+fn padded_accounts(accounts: &[Pubkey], max_index: Option<usize>) -> Vec<Pubkey> {
+    let len = max_index.map_or(0, |idx| idx + 1).max(accounts.len());
+    let mut padded = Vec::with_capacity(len);
+    padded.extend_from_slice(accounts);
+    padded.resize(len, Pubkey::default());
+    padded
 }
 
 /// 内联指令事件解析器
@@ -91,44 +79,59 @@ pub struct GenericEventParseConfig {
     pub inner_instruction_parser: Option<InnerInstructionEventParser>,
     pub instruction_parser: Option<InstructionEventParser>,
     pub requires_inner_instruction: bool,
+    /// 配置文件加载的指令定义，设置时优先于上面的解析函数；
+    /// 内置协议上只用于覆盖账户顺序（`account_index_map`），仍由内置函数解析
+    pub dynamic_instruction: Option<Arc<InstructionConfig>>,
 }
 
+/// 已编译协议的解析配置，只包含启用了 cargo feature 的协议
 pub static EVENT_PARSERS: LazyLock<HashMap<Protocol, (Pubkey, &[GenericEventParseConfig])>> =
     LazyLock::new(|| {
         // 预分配容量，避免动态扩容
+        #[allow(unused_mut)]
         let mut parsers: HashMap<Protocol, (Pubkey, &[GenericEventParseConfig])> =
             HashMap::with_capacity(3);
-        parsers.insert(
-            Protocol::RaydiumCpmm,
-            (
-                RAYDIUM_CPMM_PROGRAM_ID,
-                crate::streaming::event_parser::protocols::raydium_cpmm::parser::CONFIGS,
-            ),
-        );
-        parsers.insert(
-            Protocol::RaydiumClmm,
-            (
-                RAYDIUM_CLMM_PROGRAM_ID,
-                crate::streaming::event_parser::protocols::raydium_clmm::parser::CONFIGS,
-            ),
-        );
-        parsers.insert(
-            Protocol::RaydiumAmmV4,
-            (
-                RAYDIUM_AMM_V4_PROGRAM_ID,
-                crate::streaming::event_parser::protocols::raydium_amm_v4::parser::CONFIGS,
-            ),
-        );
+        #[cfg(feature = "raydium-cpmm")]
+        {
+            use crate::streaming::event_parser::protocols::raydium_cpmm::parser;
+            parsers
+                .insert(Protocol::RaydiumCpmm, (parser::RAYDIUM_CPMM_PROGRAM_ID, parser::CONFIGS));
+        }
+        #[cfg(feature = "raydium-clmm")]
+        {
+            use crate::streaming::event_parser::protocols::raydium_clmm::parser;
+            parsers
+                .insert(Protocol::RaydiumClmm, (parser::RAYDIUM_CLMM_PROGRAM_ID, parser::CONFIGS));
+        }
+        #[cfg(feature = "raydium-amm-v4")]
+        {
+            use crate::streaming::event_parser::protocols::raydium_amm_v4::parser;
+            parsers.insert(
+                Protocol::RaydiumAmmV4,
+                (parser::RAYDIUM_AMM_V4_PROGRAM_ID, parser::CONFIGS),
+            );
+        }
         parsers
     });
 
 /// 通用事件解析器基类
 pub struct EventParser {
     pub program_ids: Vec<Pubkey>,
+    /// program_ids 的布隆指纹，用于快速跳过无关交易
+    pub program_filter: ProgramIdFilter,
     // pub inner_instruction_configs: HashMap<Vec<u8>, Vec<GenericEventParseConfig>>,
     pub instruction_configs: HashMap<Vec<u8>, Vec<GenericEventParseConfig>>,
-    /// 账户公钥缓存，避免重复分配
-    pub account_cache: parking_lot::Mutex<AccountPubkeyCache>,
+    /// 构造时的协议与过滤器，解析账户数据时复用
+    pub protocols: Vec<Protocol>,
+    pub event_type_filter: Option<EventTypeFilter>,
+    /// 知名程序标签，用于标注 `routed_via` / `submitted_by_known_bot`
+    pub program_registry: Arc<ProgramRegistry>,
+    /// 运行时协议暂停开关，同一客户端的解析器共享
+    pub protocol_switches: Arc<ProtocolSwitches>,
+    /// 确定性模式：不使用线程，时间取自 `clock`（见 `deterministic`）
+    pub deterministic: bool,
+    /// 生成 recv_us / handle_us 的时间源
+    pub clock: Arc<dyn Clock>,
 }
 
 impl EventParser {
@@ -136,8 +139,11 @@ impl EventParser {
         let mut instruction_configs = HashMap::with_capacity(protocols.len());
         let mut program_ids = Vec::with_capacity(protocols.len());
         // Configure all event types
-        for protocol in protocols {
-            let parse = EVENT_PARSERS.get(&protocol).unwrap();
+        for protocol in &protocols {
+            let Some(parse) = EVENT_PARSERS.get(protocol) else {
+                log::warn!("Protocol {} is not compiled in, enable its cargo feature", protocol);
+                continue;
+            };
             // Merge instruction_configs, append configurations to existing Vec
             parse
                 .1
@@ -158,9 +164,114 @@ impl EventParser {
             // Append program_ids (this is already appending)
             program_ids.push(parse.0);
         }
-        let account_cache = parking_lot::Mutex::new(AccountPubkeyCache::new());
+        let program_filter = ProgramIdFilter::new(program_ids.iter().copied());
+
+        Self {
+            program_ids,
+            program_filter,
+            instruction_configs,
+            protocols,
+            event_type_filter,
+            program_registry: Arc::new(ProgramRegistry::default()),
+            protocol_switches: Arc::new(ProtocolSwitches::default()),
+            deterministic: false,
+            clock: system_clock(),
+        }
+    }
+
+    /// Simulation mode: replaying the same input twice yields identical events
+    ///
+    /// Inner instruction lookups run on the calling thread instead of scoped threads,
+    /// and receive / handle timestamps come from a fixed clock at 0 (replace it with
+    /// [`with_clock`](Self::with_clock), e.g. a `MockClock` advanced by the replay).
+    /// Meant for reproducible backtests and debugging, not for live streaming.
+    pub fn deterministic(mut self) -> Self {
+        self.deterministic = true;
+        self.clock = Arc::new(MockClock::new(0));
+        self
+    }
 
-        Self { program_ids, instruction_configs, account_cache }
+    /// Time source of receive / handle timestamps
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 当前时间（微秒）
+    #[inline]
+    pub(crate) fn now_us(&self) -> i64 {
+        self.clock.now_micros()
+    }
+
+    /// 自 `recv_us` 起的处理耗时，确定性模式下不会为负
+    #[inline]
+    pub(crate) fn elapsed_us(&self, recv_us: i64) -> i64 {
+        let elapsed = self.clock.now_micros() - recv_us;
+        if self.deterministic {
+            elapsed.max(0)
+        } else {
+            elapsed
+        }
+    }
+
+    /// Stops parsing the protocol's instructions and accounts until resumed
+    ///
+    /// Takes effect on the next update, also for parsers sharing `protocol_switches`.
+    /// Returns false if the protocol was already paused.
+    pub fn pause_protocol(&self, protocol: &Protocol) -> bool {
+        self.protocol_switches.pause(protocol)
+    }
+
+    /// Resumes a paused protocol, returns false if it was not paused
+    pub fn resume_protocol(&self, protocol: &Protocol) -> bool {
+        self.protocol_switches.resume(protocol)
+    }
+
+    pub fn is_protocol_paused(&self, protocol: &Protocol) -> bool {
+        self.protocol_switches.is_paused(protocol)
+    }
+
+    /// 合并配置文件生成的解析配置
+    ///
+    /// 设置了 `account_index_map` 且命中已编译协议（相同程序ID和鉴别器）的指令只覆盖其账户顺序，
+    /// 不会新增动态事件，协议调整账户顺序时无需重新编译。
+    pub fn add_dynamic_config(&mut self, config: GenericEventParseConfig) {
+        let Some(instruction) = config.dynamic_instruction.clone() else {
+            return;
+        };
+        let discriminator = config.instruction_discriminator.to_vec();
+        if instruction.is_account_override() {
+            let mut overridden = 0;
+            for builtin in self.instruction_configs.get_mut(&discriminator).into_iter().flatten() {
+                if builtin.program_id == config.program_id && builtin.instruction_parser.is_some() {
+                    builtin.dynamic_instruction = Some(instruction.clone());
+                    overridden += 1;
+                }
+            }
+            if overridden == 0 {
+                log::warn!(
+                    "Account override {} matches no built-in instruction of {}",
+                    instruction.name,
+                    config.program_id
+                );
+            }
+            return;
+        }
+        let program_id = config.program_id;
+        self.instruction_configs.entry(discriminator).or_default().push(config);
+        if !self.program_ids.contains(&program_id) {
+            self.program_ids.push(program_id);
+            self.program_filter = ProgramIdFilter::new(self.program_ids.iter().copied());
+        }
+    }
+
+    /// 校验并合并一个协议配置文件的全部指令，规则同 [`add_dynamic_config`](Self::add_dynamic_config)
+    pub fn add_protocol_config(&mut self, protocol_config: &ProtocolConfig) -> anyhow::Result<()> {
+        protocol_config.validate()?;
+        for config in DynamicEventParser::create_configs(protocol_config)? {
+            self.add_dynamic_config(config);
+        }
+        Ok(())
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -175,29 +286,49 @@ impl EventParser {
         inner_instructions: &[yellowstone_grpc_proto::prelude::InnerInstructions],
         bot_wallet: Option<Pubkey>,
         transaction_index: Option<u64>,
+        compute_units_consumed: Option<u64>,
         callback: Arc<dyn for<'a> Fn(&'a Box<dyn UnifiedEvent>) + Send + Sync>,
     ) -> anyhow::Result<()> {
-        // 获取交易的指令和账户
-        let mut accounts = accounts.to_vec();
         // 检查交易中是否包含程序
-        let has_program = accounts.iter().any(|account| self.should_handle(account));
+        let has_program = self.program_filter.any_match(accounts);
         if has_program {
+            // 按所有指令引用的最大索引一次性补齐账户
+            let max_index = max_account_index(
+                compiled_instructions
+                    .iter()
+                    .map(|ix| (ix.program_id_index as usize, ix.accounts.as_slice()))
+                    .chain(inner_instructions.iter().flat_map(|inner| {
+                        inner
+                            .instructions
+                            .iter()
+                            .map(|ix| (ix.program_id_index as usize, ix.accounts.as_slice()))
+                    })),
+            );
+            // 补齐前的账户数，索引超出它的账户是补齐的
+            let account_count = accounts.len();
+            let accounts = padded_accounts(accounts, max_index);
+            let compute_budget = ComputeBudgetInfo {
+                units_consumed: compute_units_consumed,
+                ..parse_compute_budget(
+                    compiled_instructions
+                        .iter()
+                        .map(|ix| (ix.program_id_index, ix.data.as_slice())),
+                    &accounts,
+                )
+            };
             // 解析每个指令
             for (index, instruction) in compiled_instructions.iter().enumerate() {
                 if let Some(program_id) = accounts.get(instruction.program_id_index as usize) {
                     let program_id = *program_id; // 克隆程序ID，避免借用冲突
+                    let annotation = self.program_registry.annotate(&program_id, accounts.first());
                     let inner_instructions = inner_instructions
                         .iter()
                         .find(|inner_instruction| inner_instruction.index == index as u32);
-                    let max_idx = instruction.accounts.iter().max().unwrap_or(&0);
-                    // 补齐accounts(使用Pubkey::default())
-                    if *max_idx as usize >= accounts.len() {
-                        accounts.resize(*max_idx as usize + 1, Pubkey::default());
-                    }
                     if self.should_handle(&program_id) {
                         self.parse_events_from_grpc_instruction(
                             instruction,
                             &accounts,
+                            account_count,
                             signature,
                             slot.unwrap_or(0),
                             block_time,
@@ -206,6 +337,8 @@ impl EventParser {
                             None,
                             bot_wallet,
                             transaction_index,
+                            compute_budget,
+                            &annotation,
                             inner_instructions,
                             Arc::clone(&callback),
                         )?;
@@ -226,14 +359,17 @@ impl EventParser {
                             self.parse_events_from_grpc_instruction(
                                 &instruction,
                                 &accounts,
+                                account_count,
                                 signature,
                                 slot.unwrap_or(0),
                                 block_time,
                                 recv_us,
-                                inner_instructions.index as i64,
+                                index as i64,
                                 Some(inner_index as i64),
                                 bot_wallet,
                                 transaction_index,
+                                compute_budget,
+                                &annotation,
                                 Some(&inner_instructions),
                                 Arc::clone(&callback),
                             )?;
@@ -247,7 +383,7 @@ impl EventParser {
 
     /// 从VersionedTransaction中解析指令事件的通用方法
     #[allow(clippy::too_many_arguments)]
-    async fn parse_instruction_events_from_versioned_transaction(
+    pub(crate) fn parse_instruction_events_from_versioned_transaction(
         &self,
         transaction: &VersionedTransaction,
         signature: Signature,
@@ -258,30 +394,53 @@ impl EventParser {
         inner_instructions: &[InnerInstructions],
         bot_wallet: Option<Pubkey>,
         transaction_index: Option<u64>,
+        compute_units_consumed: Option<u64>,
         callback: Arc<dyn for<'a> Fn(&'a Box<dyn UnifiedEvent>) + Send + Sync>,
     ) -> anyhow::Result<()> {
         // 获取交易的指令和账户
         let compiled_instructions = transaction.message.instructions();
-        let mut accounts: Vec<Pubkey> = accounts.to_vec();
         // 检查交易中是否包含程序
-        let has_program = accounts.iter().any(|account| self.should_handle(account));
+        let has_program = self.program_filter.any_match(accounts);
         if has_program {
+            // 按所有指令引用的最大索引一次性补齐账户
+            let max_index = max_account_index(
+                compiled_instructions
+                    .iter()
+                    .map(|ix| (ix.program_id_index as usize, ix.accounts.as_slice()))
+                    .chain(inner_instructions.iter().flat_map(|inner| {
+                        inner.instructions.iter().map(|ix| {
+                            (
+                                ix.instruction.program_id_index as usize,
+                                ix.instruction.accounts.as_slice(),
+                            )
+                        })
+                    })),
+            );
+            // 补齐前的账户数，索引超出它的账户是补齐的
+            let account_count = accounts.len();
+            let accounts = padded_accounts(accounts, max_index);
+            let compute_budget = ComputeBudgetInfo {
+                units_consumed: compute_units_consumed,
+                ..parse_compute_budget(
+                    compiled_instructions
+                        .iter()
+                        .map(|ix| (ix.program_id_index as u32, ix.data.as_slice())),
+                    &accounts,
+                )
+            };
             // 解析每个指令
             for (index, instruction) in compiled_instructions.iter().enumerate() {
                 if let Some(program_id) = accounts.get(instruction.program_id_index as usize) {
                     let program_id = *program_id; // 克隆程序ID，避免借用冲突
+                    let annotation = self.program_registry.annotate(&program_id, accounts.first());
                     let inner_instructions = inner_instructions
                         .iter()
                         .find(|inner_instruction| inner_instruction.index == index as u8);
                     if self.should_handle(&program_id) {
-                        let max_idx = instruction.accounts.iter().max().unwrap_or(&0);
-                        // 补齐accounts(使用Pubkey::default())
-                        if *max_idx as usize >= accounts.len() {
-                            accounts.resize(*max_idx as usize + 1, Pubkey::default());
-                        }
                         self.parse_events_from_instruction(
                             instruction,
                             &accounts,
+                            account_count,
                             signature,
                             slot.unwrap_or(0),
                             block_time,
@@ -290,6 +449,8 @@ impl EventParser {
                             None,
                             bot_wallet,
                             transaction_index,
+                            compute_budget,
+                            &annotation,
                             inner_instructions,
                             Arc::clone(&callback),
                         )?;
@@ -302,6 +463,7 @@ impl EventParser {
                             self.parse_events_from_instruction(
                                 &inner_instruction.instruction,
                                 &accounts,
+                                account_count,
                                 signature,
                                 slot.unwrap_or(0),
                                 block_time,
@@ -310,6 +472,8 @@ impl EventParser {
                                 Some(inner_index as i64),
                                 bot_wallet,
                                 transaction_index,
+                                compute_budget,
+                                &annotation,
                                 Some(&inner_instructions),
                                 Arc::clone(&callback),
                             )?;
@@ -375,9 +539,9 @@ impl EventParser {
             inner_instructions,
             bot_wallet,
             transaction_index,
+            None,
             callback,
-        )
-        .await?;
+        )?;
         Ok(())
     }
 
@@ -427,8 +591,10 @@ impl EventParser {
                 let mut inner_instructions: Vec<
                     yellowstone_grpc_proto::solana::storage::confirmed_block::InnerInstructions,
                 > = vec![];
+                let mut compute_units_consumed = None;
 
                 if let Some(meta) = grpc_tx.meta {
+                    compute_units_consumed = meta.compute_units_consumed;
                     inner_instructions = meta.inner_instructions;
                     address_table_lookups.reserve(
                         meta.loaded_writable_addresses.len() + meta.loaded_readonly_addresses.len(),
@@ -470,6 +636,7 @@ impl EventParser {
                     &inner_instructions_arc,
                     bot_wallet,
                     transaction_index,
+                    compute_units_consumed,
                     callback.clone(),
                 )
                 .await?;
@@ -491,85 +658,24 @@ impl EventParser {
                 return Ok(());
             }
         };
-        let mut inner_instructions_vec: Vec<InnerInstructions> = Vec::new();
-        if let Some(meta) = &transaction.transaction.meta {
-            // 从meta中获取inner_instructions，处理OptionSerializer类型
-            if let solana_transaction_status::option_serializer::OptionSerializer::Some(
-                ui_inner_insts,
-            ) = &meta.inner_instructions
-            {
-                // 将UiInnerInstructions转换为InnerInstructions
-                for ui_inner in ui_inner_insts {
-                    let mut converted_instructions = Vec::new();
-
-                    // 转换每个UiInstruction为InnerInstruction
-                    for ui_instruction in &ui_inner.instructions {
-                        if let UiInstruction::Compiled(ui_compiled) = ui_instruction {
-                            // 解码base58编码的data
-                            if let Ok(data) = bs58::decode(&ui_compiled.data).into_vec() {
-                                // base64解码
-                                let compiled_instruction = CompiledInstruction {
-                                    program_id_index: ui_compiled.program_id_index,
-                                    accounts: ui_compiled.accounts.clone(),
-                                    data,
-                                };
-
-                                let inner_instruction = InnerInstruction {
-                                    instruction: compiled_instruction,
-                                    stack_height: ui_compiled.stack_height,
-                                };
-
-                                converted_instructions.push(inner_instruction);
-                            }
-                        }
-                    }
-
-                    let inner_instructions = InnerInstructions {
-                        index: ui_inner.index,
-                        instructions: converted_instructions,
-                    };
-
-                    inner_instructions_vec.push(inner_instructions);
-                }
-            }
-        }
-        let inner_instructions: &[InnerInstructions] = &inner_instructions_vec;
-
-        let meta = transaction.transaction.meta;
-        let mut address_table_lookups: Vec<Pubkey> = vec![];
-        if let Some(meta) = meta {
-            if let solana_transaction_status::option_serializer::OptionSerializer::Some(
-                loaded_addresses,
-            ) = &meta.loaded_addresses
-            {
-                address_table_lookups
-                    .reserve(loaded_addresses.writable.len() + loaded_addresses.readonly.len());
-                address_table_lookups.extend(
-                    loaded_addresses
-                        .writable
-                        .iter()
-                        .filter_map(|s| s.parse::<Pubkey>().ok())
-                        .chain(
-                            loaded_addresses
-                                .readonly
-                                .iter()
-                                .filter_map(|s| s.parse::<Pubkey>().ok()),
-                        ),
-                );
-            }
-        }
+        let meta = RawTransactionMeta::from_encoded(&transaction);
+        let inner_instructions: &[InnerInstructions] = &meta.inner_instructions;
+        let compute_units_consumed = meta.compute_units_consumed;
         let mut accounts = Vec::with_capacity(
-            versioned_tx.message.static_account_keys().len() + address_table_lookups.len(),
+            versioned_tx.message.static_account_keys().len()
+                + meta.loaded_writable_addresses.len()
+                + meta.loaded_readonly_addresses.len(),
         );
         accounts.extend_from_slice(versioned_tx.message.static_account_keys());
-        accounts.extend(address_table_lookups);
+        accounts.extend_from_slice(&meta.loaded_writable_addresses);
+        accounts.extend_from_slice(&meta.loaded_readonly_addresses);
         // 使用 Arc 包装共享数据，避免不必要的克隆
         let accounts_arc = Arc::new(accounts);
         let inner_instructions_arc = Arc::new(inner_instructions);
 
         let slot = transaction.slot;
         let block_time = transaction.block_time.map(|t| Timestamp { seconds: t as i64, nanos: 0 });
-        let recv_us = get_high_perf_clock();
+        let recv_us = self.now_us();
         let bot_wallet = None;
         let transaction_index = None;
         // 解析指令事件
@@ -583,9 +689,9 @@ impl EventParser {
             &inner_instructions_arc,
             bot_wallet,
             transaction_index,
+            compute_units_consumed,
             callback.clone(),
-        )
-        .await?;
+        )?;
 
         Ok(())
     }
@@ -604,7 +710,7 @@ impl EventParser {
         inner_index: Option<i64>,
         transaction_index: Option<u64>,
     ) -> Option<Box<dyn UnifiedEvent>> {
-        if let Some(parser) = config.inner_instruction_parser {
+        if config.inner_instruction_parser.is_some() || config.dynamic_instruction.is_some() {
             let timestamp = block_time.unwrap_or(Timestamp { seconds: 0, nanos: 0 });
             let block_time_ms = timestamp.seconds * 1000 + (timestamp.nanos as i64) / 1_000_000;
             let metadata = EventMetadata::new(
@@ -620,7 +726,12 @@ impl EventParser {
                 recv_us,
                 transaction_index,
             );
-            parser(data, metadata)
+            match &config.dynamic_instruction {
+                Some(instruction) if config.instruction_parser.is_none() => {
+                    DynamicEventParser::parse_inner_instruction(instruction, data, metadata)
+                }
+                _ => config.inner_instruction_parser.and_then(|parser| parser(data, metadata)),
+            }
         } else {
             None
         }
@@ -641,7 +752,7 @@ impl EventParser {
         inner_index: Option<i64>,
         transaction_index: Option<u64>,
     ) -> Option<Box<dyn UnifiedEvent>> {
-        if let Some(parser) = config.instruction_parser {
+        if config.instruction_parser.is_some() || config.dynamic_instruction.is_some() {
             let timestamp = block_time.unwrap_or(Timestamp { seconds: 0, nanos: 0 });
             let block_time_ms = timestamp.seconds * 1000 + (timestamp.nanos as i64) / 1_000_000;
             let metadata = EventMetadata::new(
@@ -657,7 +768,18 @@ impl EventParser {
                 recv_us,
                 transaction_index,
             );
-            parser(data, account_pubkeys, metadata)
+            match (&config.dynamic_instruction, config.instruction_parser) {
+                (Some(instruction), None) => DynamicEventParser::parse_instruction(
+                    instruction,
+                    data,
+                    account_pubkeys,
+                    metadata,
+                ),
+                (Some(instruction), Some(parser)) => {
+                    parser(data, &instruction.remap_accounts(account_pubkeys), metadata)
+                }
+                (None, parser) => parser.and_then(|parser| parser(data, account_pubkeys, metadata)),
+            }
         } else {
             None
         }
@@ -769,6 +891,7 @@ impl EventParser {
         &self,
         instruction: &CompiledInstruction,
         accounts: &[Pubkey],
+        account_count: usize,
         signature: Signature,
         slot: u64,
         block_time: Option<Timestamp>,
@@ -777,10 +900,14 @@ impl EventParser {
         inner_index: Option<i64>,
         bot_wallet: Option<Pubkey>,
         transaction_index: Option<u64>,
+        compute_budget: ComputeBudgetInfo,
+        annotation: &ProgramAnnotation,
         inner_instructions: Option<&InnerInstructions>,
         callback: Arc<dyn for<'a> Fn(&'a Box<dyn UnifiedEvent>) + Send + Sync>,
     ) -> anyhow::Result<()> {
-        let program_id = accounts[instruction.program_id_index as usize];
+        let Some(program_id) = read_account(accounts, instruction.program_id_index as usize) else {
+            return Ok(());
+        };
         if !self.should_handle(&program_id) {
             return Ok(());
         }
@@ -806,11 +933,10 @@ impl EventParser {
             return Ok(());
         }
 
-        // 使用缓存构建账户公钥列表，避免重复分配 (只需构建一次)
-        let account_pubkeys = {
-            let mut cache_guard = self.account_cache.lock();
-            cache_guard.build_account_pubkeys(&instruction.accounts, accounts).to_vec()
-        };
+        // 构建账户公钥列表 (只需构建一次)
+        let account_pubkeys = build_account_pubkeys(&instruction.accounts, accounts);
+        let has_padded_accounts =
+            instruction.accounts.iter().any(|&idx| idx as usize >= account_count);
 
         // 并行处理所有 (discriminator, config) 组合
         let all_results: Vec<_> = all_processing_params
@@ -833,6 +959,13 @@ impl EventParser {
             })
             .collect();
 
+        let stack_height = match inner_index {
+            None => Some(TRANSACTION_LEVEL_STACK_HEIGHT),
+            Some(i) => inner_instructions
+                .and_then(|ixs| ixs.instructions.get(i as usize))
+                .and_then(|ix| ix.stack_height),
+        };
+
         for (_disc, config, mut event) in all_results {
             // 阻塞处理：原有的同步逻辑
             let mut inner_instruction_event: Option<Box<dyn UnifiedEvent>> = None;
@@ -840,9 +973,14 @@ impl EventParser {
                 let inner_instructions_ref = inner_instructions.unwrap();
 
                 // 并行执行两个任务
-                let (inner_event_result, swap_data_result) = std::thread::scope(|s| {
-                    let inner_event_handle = s.spawn(|| {
-                        for inner_instruction in inner_instructions_ref.instructions.iter() {
+                let (inner_event_result, swap_data_result) = join_tasks(
+                    self.deterministic,
+                    || {
+                        // 只查找当前指令之后的内联指令，内联事件带自身的位置
+                        let first = inner_index.map_or(0, |i| i as usize + 1);
+                        for (position, inner_instruction) in
+                            inner_instructions_ref.instructions.iter().enumerate().skip(first)
+                        {
                             let result = self.parse_events_from_inner_instruction(
                                 &inner_instruction.instruction,
                                 signature,
@@ -850,7 +988,7 @@ impl EventParser {
                                 block_time,
                                 recv_us,
                                 outer_index,
-                                inner_index,
+                                Some(position as i64),
                                 transaction_index,
                                 &config,
                             );
@@ -859,24 +997,20 @@ impl EventParser {
                             }
                         }
                         None
-                    });
-
-                    let swap_data_handle = s.spawn(|| {
+                    },
+                    || {
                         if !event.swap_data_is_parsed() {
                             parse_swap_data_from_next_instructions(
                                 &*event,
                                 inner_instructions_ref,
-                                inner_index.unwrap_or(-1_i64) as i8,
+                                inner_index.map(|i| i as usize),
                                 &accounts,
                             )
                         } else {
                             None
                         }
-                    });
-
-                    // 等待两个任务完成
-                    (inner_event_handle.join().unwrap(), swap_data_handle.join().unwrap())
-                });
+                    },
+                );
 
                 inner_instruction_event = inner_event_result;
                 if let Some(swap_data) = swap_data_result {
@@ -891,10 +1025,20 @@ impl EventParser {
 
             // 合并事件
             if let Some(inner_instruction_event) = inner_instruction_event {
-                event.merge(&*inner_instruction_event);
+                let policy = config
+                    .dynamic_instruction
+                    .as_ref()
+                    .map(|instruction| instruction.merge_policy)
+                    .unwrap_or_default();
+                event.merge_with(&*inner_instruction_event, policy);
             }
             // 设置处理时间（使用高性能时钟）
-            event.set_handle_us(elapsed_micros_since(recv_us));
+            event.set_handle_us(self.elapsed_us(recv_us));
+            event.metadata_mut().compute_budget = compute_budget;
+            event.metadata_mut().stack_height = stack_height;
+            event.metadata_mut().routed_via = annotation.routed_via.clone();
+            event.metadata_mut().submitted_by_known_bot = annotation.submitted_by_known_bot;
+            event.metadata_mut().has_padded_accounts = has_padded_accounts;
             event = process_event(event, bot_wallet);
             callback(&event);
         }
@@ -908,6 +1052,7 @@ impl EventParser {
         &self,
         instruction: &yellowstone_grpc_proto::prelude::CompiledInstruction,
         accounts: &[Pubkey],
+        account_count: usize,
         signature: Signature,
         slot: u64,
         block_time: Option<Timestamp>,
@@ -916,10 +1061,14 @@ impl EventParser {
         inner_index: Option<i64>,
         bot_wallet: Option<Pubkey>,
         transaction_index: Option<u64>,
+        compute_budget: ComputeBudgetInfo,
+        annotation: &ProgramAnnotation,
         inner_instructions: Option<&yellowstone_grpc_proto::prelude::InnerInstructions>,
         callback: Arc<dyn for<'a> Fn(&'a Box<dyn UnifiedEvent>) + Send + Sync>,
     ) -> anyhow::Result<()> {
-        let program_id = accounts[instruction.program_id_index as usize];
+        let Some(program_id) = read_account(accounts, instruction.program_id_index as usize) else {
+            return Ok(());
+        };
         if !self.should_handle(&program_id) {
             return Ok(());
         }
@@ -945,11 +1094,10 @@ impl EventParser {
             return Ok(());
         }
 
-        // 使用缓存构建账户公钥列表，避免重复分配 (只需构建一次)
-        let account_pubkeys = {
-            let mut cache_guard = self.account_cache.lock();
-            cache_guard.build_account_pubkeys(&instruction.accounts, accounts).to_vec()
-        };
+        // 构建账户公钥列表 (只需构建一次)
+        let account_pubkeys = build_account_pubkeys(&instruction.accounts, accounts);
+        let has_padded_accounts =
+            instruction.accounts.iter().any(|&idx| idx as usize >= account_count);
 
         // 并行处理所有 (discriminator, config) 组合
         let all_results: Vec<_> = all_processing_params
@@ -972,6 +1120,13 @@ impl EventParser {
             })
             .collect();
 
+        let stack_height = match inner_index {
+            None => Some(TRANSACTION_LEVEL_STACK_HEIGHT),
+            Some(i) => inner_instructions
+                .and_then(|ixs| ixs.instructions.get(i as usize))
+                .and_then(|ix| ix.stack_height),
+        };
+
         for (_disc, config, mut event) in all_results {
             // 阻塞处理：原有的同步逻辑
             let mut inner_instruction_event: Option<Box<dyn UnifiedEvent>> = None;
@@ -979,9 +1134,14 @@ impl EventParser {
                 let inner_instructions_ref = inner_instructions.unwrap();
 
                 // 并行执行两个任务
-                let (inner_event_result, swap_data_result) = std::thread::scope(|s| {
-                    let inner_event_handle = s.spawn(|| {
-                        for inner_instruction in inner_instructions_ref.instructions.iter() {
+                let (inner_event_result, swap_data_result) = join_tasks(
+                    self.deterministic,
+                    || {
+                        // 只查找当前指令之后的内联指令，内联事件带自身的位置
+                        let first = inner_index.map_or(0, |i| i as usize + 1);
+                        for (position, inner_instruction) in
+                            inner_instructions_ref.instructions.iter().enumerate().skip(first)
+                        {
                             let result = self.parse_events_from_grpc_inner_instruction(
                                 &inner_instruction,
                                 signature,
@@ -989,7 +1149,7 @@ impl EventParser {
                                 block_time,
                                 recv_us,
                                 outer_index,
-                                inner_index,
+                                Some(position as i64),
                                 transaction_index,
                                 &config,
                             );
@@ -998,24 +1158,20 @@ impl EventParser {
                             }
                         }
                         None
-                    });
-
-                    let swap_data_handle = s.spawn(|| {
+                    },
+                    || {
                         if !event.swap_data_is_parsed() {
                             parse_swap_data_from_next_grpc_instructions(
                                 &*event,
                                 inner_instructions_ref,
-                                inner_index.unwrap_or(-1_i64) as i8,
+                                inner_index.map(|i| i as usize),
                                 &accounts,
                             )
                         } else {
                             None
                         }
-                    });
-
-                    // 等待两个任务完成
-                    (inner_event_handle.join().unwrap(), swap_data_handle.join().unwrap())
-                });
+                    },
+                );
 
                 inner_instruction_event = inner_event_result;
                 if let Some(swap_data) = swap_data_result {
@@ -1030,10 +1186,20 @@ impl EventParser {
 
             // 合并事件
             if let Some(inner_instruction_event) = inner_instruction_event {
-                event.merge(&*inner_instruction_event);
+                let policy = config
+                    .dynamic_instruction
+                    .as_ref()
+                    .map(|instruction| instruction.merge_policy)
+                    .unwrap_or_default();
+                event.merge_with(&*inner_instruction_event, policy);
             }
             // 设置处理时间（使用高性能时钟）
-            event.set_handle_us(elapsed_micros_since(recv_us));
+            event.set_handle_us(self.elapsed_us(recv_us));
+            event.metadata_mut().compute_budget = compute_budget;
+            event.metadata_mut().stack_height = stack_height;
+            event.metadata_mut().routed_via = annotation.routed_via.clone();
+            event.metadata_mut().submitted_by_known_bot = annotation.submitted_by_known_bot;
+            event.metadata_mut().has_padded_accounts = has_padded_accounts;
             event = process_event(event, bot_wallet);
             callback(&event);
         }
@@ -1041,7 +1207,8 @@ impl EventParser {
     }
 
     fn should_handle(&self, program_id: &Pubkey) -> bool {
-        self.program_ids.contains(program_id)
+        self.program_filter.contains(program_id)
+            && !self.protocol_switches.is_program_paused(program_id)
     }
 
     // fn supported_program_ids(&self) -> Vec<Pubkey> {
@@ -1055,3 +1222,271 @@ fn process_event(
 ) -> Box<dyn UnifiedEvent> {
     event
 }
+
+/// 并行执行两个任务；确定性模式和 wasm32（没有线程）下按顺序执行
+#[cfg(not(target_arch = "wasm32"))]
+fn join_tasks<A: Send, B: Send>(
+    sequential: bool,
+    a: impl FnOnce() -> A + Send,
+    b: impl FnOnce() -> B + Send,
+) -> (A, B) {
+    if sequential {
+        return (a(), b());
+    }
+    std::thread::scope(|s| {
+        let a = s.spawn(a);
+        let b = s.spawn(b);
+        (a.join().unwrap(), b.join().unwrap())
+    })
+}
+
+#[cfg(target_arch = "wasm32")]
+fn join_tasks<A, B>(_sequential: bool, a: impl FnOnce() -> A, b: impl FnOnce() -> B) -> (A, B) {
+    (a(), b())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::read_indexed_account;
+
+    #[test]
+    fn test_padded_accounts_out_of_range_indices() {
+        let accounts = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let instructions: [(usize, &[u8]); 2] = [(0, &[1]), (1, &[0, 5, 3])];
+        let max_index = max_account_index(instructions.into_iter());
+        assert_eq!(max_index, Some(5));
+
+        let padded = padded_accounts(&accounts, max_index);
+        assert_eq!(padded.len(), 6);
+        assert_eq!(padded[5], Pubkey::default());
+        assert_eq!(read_indexed_account(&accounts, &[0, 5], 0), Some(accounts[0]));
+        assert_eq!(read_indexed_account(&accounts, &[0, 5], 1), None);
+        assert_eq!(read_indexed_account(&accounts, &[0, 5], 2), None);
+    }
+
+    /// 配置文件的 account_index_map 改变内置解析器产出的账户
+    #[cfg(feature = "raydium-cpmm")]
+    #[test]
+    fn test_account_index_map_override_reorders_builtin_accounts() {
+        use crate::streaming::event_parser::config::ConfigLoader;
+        use crate::streaming::event_parser::protocols::raydium_cpmm::discriminators::SWAP_BASE_IN;
+        use crate::streaming::event_parser::protocols::raydium_cpmm::parser::RAYDIUM_CPMM_PROGRAM_ID;
+        use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+        use yellowstone_grpc_proto::prelude as grpc;
+
+        // 假设协议升级后 payer 与 authority 互换了位置
+        let hotfix = ConfigLoader::load_from_json(
+            r#"{
+                "name": "raydium_cpmm_hotfix",
+                "version": "1.0.1",
+                "program_id": "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C",
+                "instructions": [
+                    {
+                        "name": "swap_base_input",
+                        "discriminator": "8fbe5adac41e33de",
+                        "event_type": "RaydiumCpmmSwapBaseInput",
+                        "accounts": [],
+                        "account_index_map": [1, 0, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let mut accounts = vec![RAYDIUM_CPMM_PROGRAM_ID];
+        accounts.extend((0..13).map(|_| Pubkey::new_unique()));
+        let mut data = SWAP_BASE_IN.to_vec();
+        data.extend(1_000u64.to_le_bytes());
+        data.extend(900u64.to_le_bytes());
+        let instructions =
+            [grpc::CompiledInstruction { program_id_index: 0, accounts: (1..14).collect(), data }];
+        let parse = |parser: &EventParser| {
+            let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+            let sink = events.clone();
+            let callback: Arc<dyn for<'a> Fn(&'a Box<dyn UnifiedEvent>) + Send + Sync> =
+                Arc::new(move |event: &Box<dyn UnifiedEvent>| {
+                    let swap = event.as_any().downcast_ref::<RaydiumCpmmSwapEvent>().unwrap();
+                    sink.lock().unwrap().push((swap.payer, swap.authority));
+                });
+            futures::executor::block_on(parser.parse_instruction_events_from_grpc_transaction(
+                &instructions,
+                Signature::default(),
+                Some(1),
+                None,
+                0,
+                &accounts,
+                &[],
+                None,
+                None,
+                None,
+                callback,
+            ))
+            .unwrap();
+            let parsed = events.lock().unwrap().clone();
+            parsed
+        };
+
+        let mut parser = EventParser::new(vec![Protocol::RaydiumCpmm], None);
+        assert_eq!(parse(&parser), vec![(accounts[1], accounts[2])]);
+        parser.add_protocol_config(&hotfix).unwrap();
+        assert_eq!(parse(&parser), vec![(accounts[2], accounts[1])]);
+    }
+
+    /// 同一笔交易经 gRPC 与 RPC 两种路径解析，事件的索引应一致
+    #[cfg(feature = "raydium-cpmm")]
+    #[test]
+    fn test_grpc_and_versioned_indices_match() {
+        use crate::streaming::event_parser::protocols::raydium_cpmm::discriminators::SWAP_BASE_IN;
+        use crate::streaming::event_parser::protocols::raydium_cpmm::parser::RAYDIUM_CPMM_PROGRAM_ID;
+        use solana_sdk::message::{Message, MessageHeader, VersionedMessage};
+        use solana_transaction_status::InnerInstruction;
+        use yellowstone_grpc_proto::prelude as grpc;
+
+        // 路由程序（不解析）在第一个内联指令后 CPI 调用 CPMM swap
+        let mut accounts =
+            vec![Pubkey::new_unique(), Pubkey::new_unique(), RAYDIUM_CPMM_PROGRAM_ID];
+        accounts.extend((0..12).map(|_| Pubkey::new_unique()));
+        let swap_accounts: Vec<u8> = std::iter::once(0).chain(3..15).collect();
+        let mut swap_data = SWAP_BASE_IN.to_vec();
+        swap_data.extend(1_000u64.to_le_bytes());
+        swap_data.extend(900u64.to_le_bytes());
+
+        let outer = CompiledInstruction::new_from_raw_parts(1, vec![9], vec![]);
+        let inner = [(1u8, vec![1u8], vec![]), (2, swap_data, swap_accounts)];
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::Legacy(Message {
+                header: MessageHeader::default(),
+                account_keys: accounts.clone(),
+                recent_blockhash: Default::default(),
+                instructions: vec![outer.clone()],
+            }),
+        };
+        let rpc_inner = [InnerInstructions {
+            index: 0,
+            instructions: inner
+                .iter()
+                .map(|(program, data, ix_accounts)| InnerInstruction {
+                    instruction: CompiledInstruction::new_from_raw_parts(
+                        *program,
+                        data.clone(),
+                        ix_accounts.clone(),
+                    ),
+                    stack_height: Some(2),
+                })
+                .collect(),
+        }];
+        let grpc_outer = [grpc::CompiledInstruction {
+            program_id_index: 1,
+            accounts: vec![],
+            data: outer.data.clone(),
+        }];
+        let grpc_inner = [grpc::InnerInstructions {
+            index: 0,
+            instructions: inner
+                .iter()
+                .map(|(program, data, ix_accounts)| grpc::InnerInstruction {
+                    program_id_index: *program as u32,
+                    accounts: ix_accounts.clone(),
+                    data: data.clone(),
+                    stack_height: Some(2),
+                })
+                .collect(),
+        }];
+
+        let parser = EventParser::new(vec![Protocol::RaydiumCpmm], None);
+        let collect = || {
+            let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+            let sink = events.clone();
+            let callback: Arc<dyn for<'a> Fn(&'a Box<dyn UnifiedEvent>) + Send + Sync> =
+                Arc::new(move |event: &Box<dyn UnifiedEvent>| {
+                    let metadata = event.metadata();
+                    sink.lock().unwrap().push((
+                        metadata.event_type.clone(),
+                        metadata.outer_index,
+                        metadata.inner_index,
+                        metadata.stack_height,
+                        metadata.has_padded_accounts,
+                    ));
+                });
+            (events, callback)
+        };
+
+        let (rpc_events, callback) = collect();
+        parser
+            .parse_instruction_events_from_versioned_transaction(
+                &transaction,
+                Signature::default(),
+                Some(1),
+                None,
+                0,
+                &accounts,
+                &rpc_inner,
+                None,
+                None,
+                None,
+                callback,
+            )
+            .unwrap();
+        let (grpc_events, callback) = collect();
+        futures::executor::block_on(parser.parse_instruction_events_from_grpc_transaction(
+            &grpc_outer,
+            Signature::default(),
+            Some(1),
+            None,
+            0,
+            &accounts,
+            &grpc_inner,
+            None,
+            None,
+            None,
+            callback,
+        ))
+        .unwrap();
+
+        let expected = vec![(EventType::RaydiumCpmmSwapBaseInput, 0, Some(1), Some(2), false)];
+        assert_eq!(*rpc_events.lock().unwrap(), expected);
+        assert_eq!(*grpc_events.lock().unwrap(), expected);
+
+        // 缺少最后一个账户时仍会解析，但事件被标记为含补齐账户
+        let (padded_events, callback) = collect();
+        futures::executor::block_on(parser.parse_instruction_events_from_grpc_transaction(
+            &grpc_outer,
+            Signature::default(),
+            Some(1),
+            None,
+            0,
+            &accounts[..accounts.len() - 1],
+            &grpc_inner,
+            None,
+            None,
+            None,
+            callback,
+        ))
+        .unwrap();
+        let expected = vec![(EventType::RaydiumCpmmSwapBaseInput, 0, Some(1), Some(2), true)];
+        assert_eq!(*padded_events.lock().unwrap(), expected);
+
+        // 暂停的协议不再产生事件，恢复后立即生效
+        assert!(parser.pause_protocol(&Protocol::RaydiumCpmm));
+        let (paused_events, callback) = collect();
+        futures::executor::block_on(parser.parse_instruction_events_from_grpc_transaction(
+            &grpc_outer,
+            Signature::default(),
+            Some(1),
+            None,
+            0,
+            &accounts,
+            &grpc_inner,
+            None,
+            None,
+            None,
+            callback,
+        ))
+        .unwrap();
+        assert!(paused_events.lock().unwrap().is_empty());
+        assert!(parser.resume_protocol(&Protocol::RaydiumCpmm));
+        assert!(!parser.protocol_switches.any_paused());
+    }
+}
@@ -4,7 +4,8 @@ use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use dashmap::DashMap;
 use std::collections::BTreeSet;
 
-const MAX_SIGNATURES: usize = 1000;
+/// Default cap on tracked signatures
+pub const DEFAULT_MAX_SIGNATURES: usize = 1000;
 const CLEANUP_BATCH_SIZE: usize = 100;
 
 /// Signature-based trader addresses, completely lock-free
@@ -22,6 +23,8 @@ pub struct GlobalState {
     signature_count: AtomicUsize,
     /// Generation counter to handle cleanup races
     generation: AtomicU64,
+    /// Maximum number of signatures kept before eviction kicks in
+    max_signatures: AtomicUsize,
 }
 
 impl GlobalState {
@@ -31,13 +34,35 @@ impl GlobalState {
             signature_data: DashMap::new(),
             signature_count: AtomicUsize::new(0),
             generation: AtomicU64::new(0),
+            max_signatures: AtomicUsize::new(DEFAULT_MAX_SIGNATURES),
         }
     }
 
+    /// Set the signature cap; excess entries are evicted on the next insert
+    pub fn set_max_signatures(&self, max_signatures: usize) {
+        self.max_signatures.store(max_signatures, Ordering::Relaxed);
+    }
+
+    /// Get the signature cap
+    pub fn max_signatures(&self) -> usize {
+        self.max_signatures.load(Ordering::Relaxed)
+    }
+
+    /// Approximate heap + inline size of the stored data in bytes
+    pub fn approx_memory_bytes(&self) -> usize {
+        let entry_size =
+            std::mem::size_of::<Signature>() + std::mem::size_of::<SignatureAddresses>();
+        self.signature_data
+            .iter()
+            .map(|entry| entry_size + entry.dev_addresses.len() * std::mem::size_of::<Pubkey>())
+            .sum()
+    }
+
     /// Lock-free capacity management - cleanup old signatures when limit exceeded
     fn maybe_cleanup(&self) {
+        let max_signatures = self.max_signatures();
         let current_count = self.signature_count.load(Ordering::Relaxed);
-        if current_count <= MAX_SIGNATURES {
+        if current_count <= max_signatures {
             return;
         }
 
@@ -52,11 +77,12 @@ impl GlobalState {
             .map(|entry| *entry.key())
             .collect();
         
-        if signatures_to_remove.len() <= MAX_SIGNATURES {
+        if signatures_to_remove.len() <= max_signatures {
             return; // Race condition, already cleaned up
         }
-        
-        signatures_to_remove.truncate(CLEANUP_BATCH_SIZE);
+
+        let excess = signatures_to_remove.len() - max_signatures;
+        signatures_to_remove.truncate(excess.max(CLEANUP_BATCH_SIZE));
 
         // Remove old signatures atomically
         for signature in signatures_to_remove {
@@ -130,7 +156,9 @@ impl Default for GlobalState {
     }
 }
 
-/// Global state instance
+/// Process-wide default instance, used only by the convenience functions below
+///
+/// Stream clients own their own `GlobalState`; see `EventProcessor::global_state`.
 static GLOBAL_STATE: once_cell::sync::Lazy<GlobalState> =
     once_cell::sync::Lazy::new(GlobalState::new);
 
@@ -164,6 +192,11 @@ pub fn get_signature_count() -> usize {
     get_global_state().get_signature_count()
 }
 
+/// Convenience function: Set the global state signature cap
+pub fn set_max_signatures(max_signatures: usize) {
+    get_global_state().set_max_signatures(max_signatures);
+}
+
 /// High-performance: Check if address is a developer address in specific signature
 pub fn is_dev_address_in_signature(signature: &Signature, address: &Pubkey) -> bool {
     get_global_state().is_dev_address_in_signature(signature, address)
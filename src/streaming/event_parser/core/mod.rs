@@ -1,9 +1,16 @@
 pub mod account_event_parser;
+pub mod canary;
 pub mod common_event_parser;
 pub mod config_event_parser;
+pub mod coverage;
 pub mod global_state;
+pub mod program_filter;
+pub mod protocol_switches;
+pub mod raw_parser;
 pub mod traits;
-pub use traits::UnifiedEvent;
+pub use traits::{MergePolicy, UnifiedEvent};
 pub use config_event_parser::ConfigurableEventParser;
+pub use raw_parser::RawTransactionMeta;
+pub use protocol_switches::ProtocolSwitches;
 
 pub mod event_parser;
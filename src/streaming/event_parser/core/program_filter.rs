@@ -0,0 +1,147 @@
+use solana_sdk::pubkey::Pubkey;
+use wide::u64x4;
+
+/// 256-bit Bloom fingerprint of watched program ids
+///
+/// Most firehose transactions touch none of the watched programs, so the static
+/// account keys are tested against the fingerprint first; only keys that pass are
+/// compared against the exact id list. Pubkeys are uniformly distributed, so two of
+/// their bytes serve directly as the hash functions.
+#[derive(Debug, Clone)]
+pub struct ProgramIdFilter {
+    bloom: u64x4,
+    program_ids: Vec<Pubkey>,
+}
+
+impl ProgramIdFilter {
+    pub fn new(program_ids: impl IntoIterator<Item = Pubkey>) -> Self {
+        let mut program_ids: Vec<Pubkey> = program_ids.into_iter().collect();
+        program_ids.sort();
+        program_ids.dedup();
+        let bloom = program_ids
+            .iter()
+            .fold(u64x4::from([0u64; 4]), |bloom, id| bloom | Self::fingerprint(id));
+        Self { bloom, program_ids }
+    }
+
+    #[inline(always)]
+    fn fingerprint(key: &Pubkey) -> u64x4 {
+        let bytes = key.as_ref();
+        let mut lanes = [0u64; 4];
+        for h in [bytes[0], bytes[31]] {
+            lanes[(h >> 6) as usize] |= 1u64 << (h & 63);
+        }
+        u64x4::from(lanes)
+    }
+
+    /// False positives possible, false negatives not
+    #[inline(always)]
+    pub fn may_contain(&self, key: &Pubkey) -> bool {
+        let fingerprint = Self::fingerprint(key);
+        (self.bloom & fingerprint).to_array() == fingerprint.to_array()
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, key: &Pubkey) -> bool {
+        self.may_contain(key) && self.program_ids.binary_search(key).is_ok()
+    }
+
+    /// Whether any of the keys is a watched program
+    #[inline]
+    pub fn any_match(&self, keys: &[Pubkey]) -> bool {
+        keys.iter().any(|key| self.contains(key))
+    }
+
+    pub fn program_ids(&self) -> &[Pubkey] {
+        &self.program_ids
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.program_ids.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// splitmix64，测试用的可复现随机源
+    fn random_key(state: &mut u64) -> Pubkey {
+        let mut bytes = [0u8; 32];
+        for chunk in bytes.chunks_mut(8) {
+            *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = *state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            chunk.copy_from_slice(&(z ^ (z >> 31)).to_le_bytes());
+        }
+        Pubkey::new_from_array(bytes)
+    }
+
+    #[test]
+    fn test_watched_ids_never_rejected() {
+        let mut state = 7;
+        for round in 0..200 {
+            let mut watched: Vec<Pubkey> =
+                (0..=round % 64).map(|_| random_key(&mut state)).collect();
+            watched.push(Pubkey::new_unique());
+            let filter = ProgramIdFilter::new(watched.iter().copied());
+            for id in &watched {
+                assert!(filter.contains(id), "round {}: {} rejected", round, id);
+                let mut keys: Vec<Pubkey> = (0..8).map(|_| random_key(&mut state)).collect();
+                keys.insert(round % keys.len(), *id);
+                assert!(filter.any_match(&keys));
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_keys_mostly_rejected_by_fingerprint() {
+        let mut state = 11;
+        let filter = ProgramIdFilter::new((0..12).map(|_| random_key(&mut state)));
+        let passed = (0..10_000).filter(|_| filter.may_contain(&random_key(&mut state))).count();
+        // 12 个程序最多置 24 位，误判率约 (24/256)^2
+        assert!(passed < 500, "{} of 10000 random keys passed the fingerprint", passed);
+    }
+
+    /// 配置文件新增程序时重建过滤器，已有和新增的程序都不会被拒绝
+    #[test]
+    fn test_watched_ids_kept_after_dynamic_configs() {
+        use crate::streaming::event_parser::config::ConfigLoader;
+        use crate::streaming::event_parser::core::event_parser::EventParser;
+        use crate::streaming::event_parser::Protocol;
+
+        let mut state = 3;
+        let protocols: Vec<Protocol> =
+            [Protocol::RaydiumCpmm, Protocol::RaydiumClmm, Protocol::RaydiumAmmV4]
+                .into_iter()
+                .filter(Protocol::is_enabled)
+                .collect();
+        let mut parser = EventParser::new(protocols, None);
+        let mut watched = parser.program_filter.program_ids().to_vec();
+        for index in 0..40 {
+            let program_id = random_key(&mut state);
+            let config = ConfigLoader::load_from_json(&format!(
+                r#"{{
+                    "name": "dynamic_{index}",
+                    "version": "1.0.0",
+                    "program_id": "{program_id}",
+                    "instructions": [
+                        {{
+                            "name": "ping",
+                            "discriminator": "09",
+                            "event_type": "Ping",
+                            "accounts": []
+                        }}
+                    ]
+                }}"#
+            ))
+            .unwrap();
+            parser.add_protocol_config(&config).unwrap();
+            watched.push(program_id);
+            for id in &watched {
+                assert!(parser.program_filter.contains(id), "{} rejected after {}", id, index);
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::streaming::event_parser::Protocol;
+
+const PROTOCOLS: [Protocol; 3] =
+    [Protocol::RaydiumCpmm, Protocol::RaydiumClmm, Protocol::RaydiumAmmV4];
+
+/// Runtime pause switches of the built-in protocols
+///
+/// Shared by every parser of a client; a paused protocol's instructions and accounts are
+/// skipped from the next update on, without rebuilding parsers or reconnecting. Meant
+/// for shutting off a parser that misbehaves after a program upgrade. Config-defined
+/// protocols are not covered.
+#[derive(Debug, Default)]
+pub struct ProtocolSwitches {
+    paused: [AtomicBool; 3],
+    /// 任一协议暂停时为 true，热路径只读这一个标志
+    any_paused: AtomicBool,
+}
+
+impl ProtocolSwitches {
+    /// Pauses the protocol, returns false if it was already paused
+    pub fn pause(&self, protocol: &Protocol) -> bool {
+        let changed = !self.paused[index(protocol)].swap(true, Ordering::AcqRel);
+        self.any_paused.store(true, Ordering::Release);
+        if changed {
+            log::warn!("Protocol {} paused", protocol);
+        }
+        changed
+    }
+
+    /// Resumes the protocol, returns false if it was not paused
+    pub fn resume(&self, protocol: &Protocol) -> bool {
+        let changed = self.paused[index(protocol)].swap(false, Ordering::AcqRel);
+        self.any_paused
+            .store(self.paused.iter().any(|flag| flag.load(Ordering::Acquire)), Ordering::Release);
+        if changed {
+            log::info!("Protocol {} resumed", protocol);
+        }
+        changed
+    }
+
+    pub fn is_paused(&self, protocol: &Protocol) -> bool {
+        self.paused[index(protocol)].load(Ordering::Acquire)
+    }
+
+    /// Currently paused protocols
+    pub fn paused(&self) -> Vec<Protocol> {
+        PROTOCOLS.into_iter().filter(|protocol| self.is_paused(protocol)).collect()
+    }
+
+    #[inline]
+    pub fn any_paused(&self) -> bool {
+        self.any_paused.load(Ordering::Acquire)
+    }
+
+    /// Whether the program belongs to a paused protocol
+    #[inline]
+    pub fn is_program_paused(&self, program_id: &Pubkey) -> bool {
+        self.any_paused()
+            && PROTOCOLS.iter().any(|protocol| {
+                self.is_paused(protocol) && protocol.get_program_id().contains(program_id)
+            })
+    }
+
+    /// `protocols` without the paused ones, borrowed when nothing is paused
+    pub fn active<'a>(&self, protocols: &'a [Protocol]) -> Cow<'a, [Protocol]> {
+        if !self.any_paused() {
+            return Cow::Borrowed(protocols);
+        }
+        Cow::Owned(protocols.iter().filter(|protocol| !self.is_paused(protocol)).cloned().collect())
+    }
+}
+
+fn index(protocol: &Protocol) -> usize {
+    match protocol {
+        Protocol::RaydiumCpmm => 0,
+        Protocol::RaydiumClmm => 1,
+        Protocol::RaydiumAmmV4 => 2,
+    }
+}
@@ -0,0 +1,258 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use prost_types::Timestamp;
+use solana_sdk::account::Account;
+use solana_sdk::bs58;
+use solana_sdk::message::compiled_instruction::CompiledInstruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, InnerInstruction, InnerInstructions, UiInstruction,
+};
+
+use super::account_event_parser::AccountEventParser;
+use super::event_parser::EventParser;
+use crate::common::AnyResult;
+use crate::streaming::event_parser::UnifiedEvent;
+use crate::streaming::grpc::AccountPretty;
+
+/// Context of a raw transaction that is not part of its wire bytes
+#[derive(Debug, Clone, Default)]
+pub struct RawTransactionMeta {
+    pub slot: u64,
+    /// Unix seconds
+    pub block_time: Option<i64>,
+    pub transaction_index: Option<u64>,
+    /// `meta.compute_units_consumed`, exposed as `compute_budget.units_consumed`
+    pub compute_units_consumed: Option<u64>,
+    /// Address lookup table accounts, required to resolve v0 transactions
+    pub loaded_writable_addresses: Vec<Pubkey>,
+    pub loaded_readonly_addresses: Vec<Pubkey>,
+    /// Without them, events that need inner instruction data are skipped
+    pub inner_instructions: Vec<InnerInstructions>,
+}
+
+impl RawTransactionMeta {
+    /// Context of a transaction fetched over RPC (`getTransaction` / `getBlock`)
+    ///
+    /// Inner instructions are only available as compiled instructions, so fetch with
+    /// `json` or `base64` encoding rather than `jsonParsed`.
+    pub fn from_encoded(transaction: &EncodedConfirmedTransactionWithStatusMeta) -> Self {
+        let mut raw = RawTransactionMeta {
+            slot: transaction.slot,
+            block_time: transaction.block_time,
+            ..Default::default()
+        };
+        let Some(meta) = transaction.transaction.meta.as_ref() else {
+            return raw;
+        };
+        raw.compute_units_consumed = meta.compute_units_consumed.clone().into();
+        if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+            raw.loaded_writable_addresses =
+                loaded.writable.iter().filter_map(|key| key.parse().ok()).collect();
+            raw.loaded_readonly_addresses =
+                loaded.readonly.iter().filter_map(|key| key.parse().ok()).collect();
+        }
+        if let OptionSerializer::Some(inner) = &meta.inner_instructions {
+            raw.inner_instructions = inner
+                .iter()
+                .map(|ui_inner| InnerInstructions {
+                    index: ui_inner.index,
+                    instructions: ui_inner
+                        .instructions
+                        .iter()
+                        .filter_map(|instruction| match instruction {
+                            UiInstruction::Compiled(compiled) => Some(InnerInstruction {
+                                instruction: CompiledInstruction {
+                                    program_id_index: compiled.program_id_index,
+                                    accounts: compiled.accounts.clone(),
+                                    data: bs58::decode(&compiled.data).into_vec().ok()?,
+                                },
+                                stack_height: compiled.stack_height,
+                            }),
+                            UiInstruction::Parsed(_) => None,
+                        })
+                        .collect(),
+                })
+                .collect();
+        }
+        raw
+    }
+}
+
+/// 直接解析原始字节，不依赖任何订阅/传输层
+impl EventParser {
+    /// Parses a bincode-encoded (wire format) transaction
+    pub fn parse_transaction_bytes(
+        &self,
+        tx_bytes: &[u8],
+        meta: &RawTransactionMeta,
+    ) -> AnyResult<Vec<Box<dyn UnifiedEvent>>> {
+        let transaction: VersionedTransaction = bincode::deserialize(tx_bytes)?;
+        self.parse_transaction(&transaction, meta)
+    }
+
+    /// Parses an already decoded transaction; events are returned in instruction order
+    pub fn parse_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        meta: &RawTransactionMeta,
+    ) -> AnyResult<Vec<Box<dyn UnifiedEvent>>> {
+        let signature = transaction.signatures.first().copied().unwrap_or_default();
+        let static_keys = transaction.message.static_account_keys();
+        let mut accounts = Vec::with_capacity(
+            static_keys.len()
+                + meta.loaded_writable_addresses.len()
+                + meta.loaded_readonly_addresses.len(),
+        );
+        accounts.extend_from_slice(static_keys);
+        accounts.extend_from_slice(&meta.loaded_writable_addresses);
+        accounts.extend_from_slice(&meta.loaded_readonly_addresses);
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        self.parse_instruction_events_from_versioned_transaction(
+            transaction,
+            signature,
+            Some(meta.slot),
+            meta.block_time.map(|seconds| Timestamp { seconds, nanos: 0 }),
+            self.now_us(),
+            &accounts,
+            &meta.inner_instructions,
+            None,
+            meta.transaction_index,
+            meta.compute_units_consumed,
+            Arc::new(move |event: &Box<dyn UnifiedEvent>| sink.lock().push(event.clone())),
+        )?;
+        let events = std::mem::take(&mut *events.lock());
+        Ok(events)
+    }
+
+    /// Parses a transaction fetched over RPC, inner instructions and lookup table
+    /// addresses included; events are returned in instruction order
+    pub fn parse_from_encoded_confirmed_transaction(
+        &self,
+        transaction: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> AnyResult<Vec<Box<dyn UnifiedEvent>>> {
+        let versioned = transaction.transaction.transaction.decode().ok_or_else(|| {
+            anyhow::anyhow!("transaction cannot be decoded, fetch it with base64 encoding")
+        })?;
+        self.parse_transaction(&versioned, &RawTransactionMeta::from_encoded(transaction))
+    }
+
+    /// Parses account data with the protocols and filter this parser was built with
+    pub fn parse_account_data(
+        &self,
+        pubkey: Pubkey,
+        account: &Account,
+        slot: u64,
+    ) -> Option<Box<dyn UnifiedEvent>> {
+        let recv_us = self.now_us();
+        let account = AccountPretty {
+            slot,
+            pubkey,
+            executable: account.executable,
+            lamports: account.lamports,
+            owner: account.owner,
+            rent_epoch: account.rent_epoch,
+            data: account.data.clone(),
+            recv_us,
+            ..Default::default()
+        };
+        let mut event = AccountEventParser::parse_account_event(
+            &self.protocol_switches.active(&self.protocols),
+            account,
+            self.event_type_filter.as_ref(),
+        )?;
+        // 账户解析器使用全局时钟，按本解析器的时间源重算
+        event.set_handle_us(self.elapsed_us(recv_us));
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use solana_sdk::hash::Hash;
+    use solana_sdk::message::{v0, VersionedMessage};
+    use solana_sdk::signature::Signature;
+    use solana_transaction_status::{
+        EncodedTransaction, EncodedTransactionWithStatusMeta, TransactionBinaryEncoding,
+    };
+    use spl_token::solana_program::program_pack::Pack;
+
+    use super::*;
+    use crate::streaming::event_parser::core::account_event_parser::TokenInfoEvent;
+    use crate::streaming::event_parser::Protocol;
+
+    #[test]
+    fn test_parse_raw_bytes() {
+        let parser = EventParser::new(vec![Protocol::RaydiumCpmm], None);
+        assert!(parser
+            .parse_transaction_bytes(&[1, 2, 3], &RawTransactionMeta::default())
+            .is_err());
+
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(v0::Message {
+                account_keys: vec![Pubkey::new_unique()],
+                recent_blockhash: Hash::default(),
+                ..Default::default()
+            }),
+        };
+        let bytes = bincode::serialize(&transaction).unwrap();
+        let events =
+            parser.parse_transaction_bytes(&bytes, &RawTransactionMeta::default()).unwrap();
+        assert!(events.is_empty());
+
+        let encoded = EncodedConfirmedTransactionWithStatusMeta {
+            slot: 7,
+            transaction: EncodedTransactionWithStatusMeta {
+                transaction: EncodedTransaction::Binary(
+                    STANDARD.encode(&bytes),
+                    TransactionBinaryEncoding::Base64,
+                ),
+                meta: None,
+                version: None,
+            },
+            block_time: Some(1_700_000_000),
+        };
+        assert_eq!(RawTransactionMeta::from_encoded(&encoded).slot, 7);
+        assert!(parser.parse_from_encoded_confirmed_transaction(&encoded).unwrap().is_empty());
+
+        let mint =
+            spl_token::state::Mint { decimals: 6, is_initialized: true, ..Default::default() };
+        let mut data = vec![0u8; spl_token::state::Mint::LEN];
+        mint.pack_into_slice(&mut data);
+        let account = Account {
+            owner: Pubkey::new_from_array(spl_token::ID.to_bytes()),
+            data,
+            ..Default::default()
+        };
+        let event = parser.parse_account_data(Pubkey::new_unique(), &account, 42).unwrap();
+        let info = event.as_any().downcast_ref::<TokenInfoEvent>().unwrap();
+        assert_eq!(info.decimals, 6);
+        assert_eq!(event.slot(), 42);
+    }
+
+    #[test]
+    fn test_deterministic_mode() {
+        let parser = EventParser::new(vec![Protocol::RaydiumCpmm], None).deterministic();
+        let mint =
+            spl_token::state::Mint { decimals: 9, is_initialized: true, ..Default::default() };
+        let mut data = vec![0u8; spl_token::state::Mint::LEN];
+        mint.pack_into_slice(&mut data);
+        let account = Account {
+            owner: Pubkey::new_from_array(spl_token::ID.to_bytes()),
+            data,
+            ..Default::default()
+        };
+        let pubkey = Pubkey::new_unique();
+        let first = parser.parse_account_data(pubkey, &account, 42).unwrap();
+        let second = parser.parse_account_data(pubkey, &account, 42).unwrap();
+        assert_eq!((first.recv_us(), first.handle_us()), (0, 0));
+        assert_eq!(first.to_json().to_string(), second.to_json().to_string());
+    }
+}
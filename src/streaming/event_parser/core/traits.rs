@@ -1,9 +1,41 @@
+use crate::streaming::event_parser::common::EventMetadata;
 use crate::streaming::event_parser::common::EventType;
 use crate::streaming::event_parser::common::SwapData;
+use serde::{Deserialize, Serialize};
 use solana_sdk::signature::Signature;
 use std::fmt::Debug;
 
+/// How an instruction event absorbs the inner (CPI) event parsed for it
+///
+/// Only the fields an event type declares as mergeable are considered (the field list of
+/// `impl_unified_event!`, every data field of a `DynamicEvent`); metadata is never merged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergePolicy {
+    /// Execution data wins: every field takes the inner value, zeros included
+    #[default]
+    PreferInner,
+    /// Like `PreferInner`, but inner values equal to their type's default are skipped,
+    /// so an inner event that does not carry a field keeps the instruction's value
+    PreferNonEmptyInner,
+    /// Intent data wins: inner values only fill fields the instruction left empty
+    PreferOuter,
+}
+
+/// Whether `value` equals its type's default, used by the generated merge impls
+#[doc(hidden)]
+pub fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
 /// Unified Event Interface - All protocol events must implement this trait
+///
+/// Events declared with `impl_unified_event!` get every method generated. Hand-written
+/// implementations must provide [`metadata`](Self::metadata) and
+/// [`metadata_mut`](Self::metadata_mut) (returning the event's `EventMetadata` field),
+/// which became required methods when analytics started reading and enrichment started
+/// writing the metadata directly; they have no default because a detached placeholder
+/// would silently hide or drop the event's signature, slot and times.
 pub trait UnifiedEvent: Debug + Send + Sync {
     /// Get event type
     fn event_type(&self) -> EventType;
@@ -32,11 +64,14 @@ pub trait UnifiedEvent: Debug + Send + Sync {
     /// Clone the event
     fn clone_boxed(&self) -> Box<dyn UnifiedEvent>;
 
-    /// Merge events (optional implementation)
-    fn merge(&mut self, _other: &dyn UnifiedEvent) {
-        // Default implementation: no merging operation
+    /// Merge the inner event into this one with the default [`MergePolicy`]
+    fn merge(&mut self, other: &dyn UnifiedEvent) {
+        self.merge_with(other, MergePolicy::default());
     }
 
+    /// Merge the inner event into this one (default implementation: no merging)
+    fn merge_with(&mut self, _other: &dyn UnifiedEvent, _policy: MergePolicy) {}
+
     /// Set swap data
     fn set_swap_data(&mut self, swap_data: SwapData);
 
@@ -49,6 +84,17 @@ pub trait UnifiedEvent: Debug + Send + Sync {
 
     /// Get transaction index in slot
     fn transaction_index(&self) -> Option<u64>;
+
+    /// Get event metadata
+    fn metadata(&self) -> &EventMetadata;
+
+    /// Get mutable event metadata
+    fn metadata_mut(&mut self) -> &mut EventMetadata;
+
+    /// Serialize event to JSON (default: metadata only)
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self.metadata()).unwrap_or_default()
+    }
 }
 
 // 为Box<dyn UnifiedEvent>实现Clone
@@ -8,34 +8,156 @@ pub use protocols::types::Protocol;
 
 /// 宏：简化 downcast_ref 模式匹配
 ///
+/// 分支按顺序匹配，命中第一个后停止：
+/// - `Type => |e: Type| ...`：按值绑定（克隆事件）
+/// - `ref Type => |e: &Type| ...`：按引用绑定，避免克隆
+/// - `Type if |e: &Type| cond => ...`：带守卫，守卫不成立时继续匹配后续分支
+/// - `#[cfg(...)] Type => |e: Type| ...`：条件编译分支，用于按协议 feature 裁剪的事件类型
+/// - `_ => |event: &dyn UnifiedEvent| ...`：兜底分支，必须放在最后，处理未匹配的事件
+///
+/// 兜底分支不可省略，缺少时编译报错；确实要忽略未匹配的事件时写
+/// `_ => |_event: &dyn UnifiedEvent| {}`。
+///
 /// # 使用示例
-/// ```
-/// use sol_trade_sdk::event_parser::match_event;
+/// ```rust,ignore
+/// use solana_streamer_sdk::match_event;
 ///
 /// match_event!(event, {
-///     PumpSwapCreatePoolEvent => |typed_event| {
-///         println!("CreatePool event: {:?}", typed_event);
+///     RaydiumCpmmSwapEvent if |e: &RaydiumCpmmSwapEvent| e.amount_in > 0 => |e: RaydiumCpmmSwapEvent| {
+///         println!("Swap event: {:?}", e);
 ///     },
-///     PumpSwapDepositEvent => |typed_event| {
+///     ref RaydiumCpmmDepositEvent => |e: &RaydiumCpmmDepositEvent| {
 ///         // 处理存款事件
 ///     },
+///     _ => |event: &dyn UnifiedEvent| {
+///         println!("Unhandled event: {:?}", event.event_type());
+///     },
 /// });
 /// ```
 #[macro_export]
 macro_rules! match_event {
-    ($event:expr, {
-        $($event_type:ty => $handler:expr),* $(,)?
-    }) => {
-        $(
-            if let Some(typed_event) = $event.as_any().downcast_ref::<$event_type>() {
-                $handler(typed_event.clone());
-            } else
-        )*
+    // 内部规则：逐个分支展开
+    (@arms ($event:expr)) => {
+        compile_error!("match_event! requires a trailing `_ => |event: &dyn UnifiedEvent| ...` arm")
+    };
+    (@arms ($event:expr) _ => $handler:expr $(,)?) => {{
+        $handler(&*$event);
+    }};
+    (@arms ($event:expr) #[cfg($meta:meta)] $event_type:ty => $handler:expr $(, $($rest:tt)*)?) => {{
+        #[cfg($meta)]
+        {
+            $crate::match_event!(@arms ($event) $event_type => $handler, $($($rest)*)?)
+        }
+        #[cfg(not($meta))]
+        {
+            $crate::match_event!(@arms ($event) $($($rest)*)?)
+        }
+    }};
+    (@arms ($event:expr) ref $($event_type:ident)::+ if $guard:expr => $handler:expr $(, $($rest:tt)*)?) => {
+        if let Some(typed_event) = $event
+            .as_any()
+            .downcast_ref::<$($event_type)::+>()
+            .filter(|typed_event| $guard(*typed_event))
+        {
+            $handler(typed_event);
+        } else {
+            $crate::match_event!(@arms ($event) $($($rest)*)?)
+        }
+    };
+    (@arms ($event:expr) ref $event_type:ty => $handler:expr $(, $($rest:tt)*)?) => {
+        if let Some(typed_event) = $event.as_any().downcast_ref::<$event_type>() {
+            $handler(typed_event);
+        } else {
+            $crate::match_event!(@arms ($event) $($($rest)*)?)
+        }
+    };
+    (@arms ($event:expr) $($event_type:ident)::+ if $guard:expr => $handler:expr $(, $($rest:tt)*)?) => {
+        if let Some(typed_event) = $event
+            .as_any()
+            .downcast_ref::<$($event_type)::+>()
+            .filter(|typed_event| $guard(*typed_event))
         {
-            // 默认情况：什么都不做
+            $handler(typed_event.clone());
+        } else {
+            $crate::match_event!(@arms ($event) $($($rest)*)?)
         }
     };
+    (@arms ($event:expr) $event_type:ty => $handler:expr $(, $($rest:tt)*)?) => {
+        if let Some(typed_event) = $event.as_any().downcast_ref::<$event_type>() {
+            $handler(typed_event.clone());
+        } else {
+            $crate::match_event!(@arms ($event) $($($rest)*)?)
+        }
+    };
+    ($event:expr, { $($arms:tt)* }) => {
+        $crate::match_event!(@arms ($event) $($arms)*)
+    };
 }
 
 // 重新导出宏以便于使用
 pub use match_event;
+
+#[cfg(test)]
+mod tests {
+    use super::UnifiedEvent;
+    use crate::streaming::event_parser::common::EventMetadata;
+    use crate::streaming::event_parser::core::account_event_parser::{
+        NonceAccountEvent, TokenAccountEvent, TokenInfoEvent,
+    };
+    use crate::streaming::event_parser::core::MergePolicy;
+
+    #[derive(Clone, Debug, Default)]
+    struct MergeTestEvent {
+        metadata: EventMetadata,
+        amount_in: u64,
+        amount_out: u64,
+    }
+    crate::impl_unified_event!(MergeTestEvent, amount_in, amount_out);
+
+    fn classify(event: &dyn UnifiedEvent) -> &'static str {
+        let mut matched = "none";
+        crate::match_event!(event, {
+            TokenAccountEvent if |e: &TokenAccountEvent| e.amount.is_some() => |_e: TokenAccountEvent| {
+                matched = "token_with_amount";
+            },
+            ref TokenAccountEvent => |_e: &TokenAccountEvent| {
+                matched = "token";
+            },
+            TokenInfoEvent => |_e: TokenInfoEvent| {
+                matched = "info";
+            },
+            _ => |_event: &dyn UnifiedEvent| {
+                matched = "fallback";
+            },
+        });
+        matched
+    }
+
+    #[test]
+    fn test_match_event_guard_ref_and_fallback() {
+        let with_amount = TokenAccountEvent { amount: Some(1), ..Default::default() };
+        assert_eq!(classify(&with_amount), "token_with_amount");
+        assert_eq!(classify(&TokenAccountEvent::default()), "token");
+        assert_eq!(classify(&TokenInfoEvent::default()), "info");
+        assert_eq!(classify(&NonceAccountEvent::default()), "fallback");
+    }
+
+    #[test]
+    fn test_merge_policies_with_zero_inner_field() {
+        let outer = MergeTestEvent { amount_in: 100, amount_out: 50, ..Default::default() };
+        // 内层执行结果 amount_out 为 0
+        let inner = MergeTestEvent { amount_in: 99, amount_out: 0, ..Default::default() };
+
+        let mut merged = outer.clone();
+        merged.merge(&inner);
+        assert_eq!((merged.amount_in, merged.amount_out), (99, 0));
+
+        let mut merged = outer.clone();
+        merged.merge_with(&inner, MergePolicy::PreferNonEmptyInner);
+        assert_eq!((merged.amount_in, merged.amount_out), (99, 50));
+
+        let mut merged = MergeTestEvent { amount_out: 50, ..Default::default() };
+        merged.merge_with(&inner, MergePolicy::PreferOuter);
+        assert_eq!((merged.amount_in, merged.amount_out), (99, 50));
+    }
+}
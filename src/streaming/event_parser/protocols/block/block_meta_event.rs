@@ -2,7 +2,16 @@ use crate::impl_unified_event;
 use crate::streaming::event_parser::common::{types::EventType, EventMetadata};
 use borsh::BorshDeserialize;
 use serde::{Deserialize, Serialize};
-use solana_sdk::signature::Signature;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+/// 出块者的手续费奖励
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshDeserialize)]
+pub struct LeaderReward {
+    pub pubkey: Pubkey,
+    pub lamports: i64,
+    pub post_balance: u64,
+    pub commission: Option<u8>,
+}
 
 /// Block元数据事件
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshDeserialize)]
@@ -11,6 +20,18 @@ pub struct BlockMetaEvent {
     pub metadata: EventMetadata,
     pub slot: u64,
     pub block_hash: String,
+    #[serde(default)]
+    pub parent_slot: u64,
+    #[serde(default)]
+    pub parent_block_hash: String,
+    #[serde(default)]
+    pub block_height: Option<u64>,
+    /// 区块内已执行的交易数（含投票交易）
+    #[serde(default)]
+    pub executed_transaction_count: u64,
+    /// 节点未返回奖励时为 None
+    #[serde(default)]
+    pub leader_reward: Option<LeaderReward>,
 }
 
 impl BlockMetaEvent {
@@ -33,7 +54,7 @@ impl BlockMetaEvent {
             recv_us,
             None,
         );
-        Self { metadata, slot, block_hash }
+        Self { metadata, slot, block_hash, ..Default::default() }
     }
 }
 
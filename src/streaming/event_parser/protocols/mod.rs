@@ -1,6 +1,9 @@
 pub mod block;
+#[cfg(feature = "raydium-amm-v4")]
 pub mod raydium_amm_v4;
+#[cfg(feature = "raydium-clmm")]
 pub mod raydium_clmm;
+#[cfg(feature = "raydium-cpmm")]
 pub mod raydium_cpmm;
 pub mod types;
 pub use block::block_meta_event::BlockMetaEvent;
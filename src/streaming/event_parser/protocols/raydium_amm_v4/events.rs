@@ -1,4 +1,4 @@
-use crate::streaming::event_parser::common::EventMetadata;
+use crate::streaming::event_parser::common::{EventMetadata, SwapAccounts};
 use crate::{
     impl_unified_event, streaming::event_parser::protocols::raydium_amm_v4::types::AmmInfo,
 };
@@ -40,6 +40,20 @@ pub struct RaydiumAmmV4SwapEvent {
 
 impl_unified_event!(RaydiumAmmV4SwapEvent,);
 
+impl RaydiumAmmV4SwapEvent {
+    /// 指令不区分方向也不带 mint，两侧金库按 pc / coin 填写，mint 取自 TransferChecked
+    pub fn swap_accounts(&self) -> SwapAccounts {
+        SwapAccounts {
+            pool: self.amm,
+            user_from_token: self.user_source_token_account,
+            user_to_token: self.user_destination_token_account,
+            from_vault: self.pool_pc_token_account,
+            to_vault: self.pool_coin_token_account,
+            ..Default::default()
+        }
+    }
+}
+
 /// 添加流动性
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshDeserialize)]
 pub struct RaydiumAmmV4DepositEvent {
@@ -88,9 +102,10 @@ pub struct RaydiumAmmV4Initialize2Event {
     pub pc_mint: Pubkey,
     pub pool_coin_token_account: Pubkey,
     pub pool_pc_token_account: Pubkey,
-    pub pool_withdraw_queue: Pubkey,
     pub amm_target_orders: Pubkey,
-    pub pool_temp_lp: Pubkey,
+    pub amm_config: Pubkey,
+    /// 创建池手续费接收账户
+    pub create_fee_destination: Pubkey,
     pub serum_program: Pubkey,
     pub serum_market: Pubkey,
     pub user_wallet: Pubkey,
@@ -115,8 +130,9 @@ pub struct RaydiumAmmV4WithdrawEvent {
     pub lp_mint_address: Pubkey,
     pub pool_coin_token_account: Pubkey,
     pub pool_pc_token_account: Pubkey,
-    pub pool_withdraw_queue: Pubkey,
-    pub pool_temp_lp_token_account: Pubkey,
+    // 旧版指令才有这两个账户
+    pub pool_withdraw_queue: Option<Pubkey>,
+    pub pool_temp_lp_token_account: Option<Pubkey>,
     pub serum_program: Pubkey,
     pub serum_market: Pubkey,
     pub serum_coin_vault_account: Pubkey,
@@ -1,5 +1,5 @@
 use crate::streaming::event_parser::{
-    common::{read_u64_le, EventMetadata, EventType, ProtocolType},
+    common::{read_account, read_u64_le, EventMetadata, EventType, ProtocolType},
     core::event_parser::GenericEventParseConfig,
     protocols::raydium_amm_v4::{
         discriminators, RaydiumAmmV4DepositEvent, RaydiumAmmV4Initialize2Event,
@@ -24,6 +24,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_swap_base_input_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_AMM_V4_PROGRAM_ID,
@@ -34,6 +35,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_swap_base_output_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_AMM_V4_PROGRAM_ID,
@@ -44,6 +46,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_deposit_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_AMM_V4_PROGRAM_ID,
@@ -54,6 +57,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_initialize2_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_AMM_V4_PROGRAM_ID,
@@ -64,6 +68,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_withdraw_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_AMM_V4_PROGRAM_ID,
@@ -74,6 +79,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_withdraw_pnl_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
 ];
 
@@ -89,23 +95,23 @@ fn parse_withdraw_pnl_instruction(
 
     Some(Box::new(RaydiumAmmV4WithdrawPnlEvent {
         metadata,
-        token_program: accounts[0],
-        amm: accounts[1],
-        amm_config: accounts[2],
-        amm_authority: accounts[3],
-        amm_open_orders: accounts[4],
-        pool_coin_token_account: accounts[5],
-        pool_pc_token_account: accounts[6],
-        coin_pnl_token_account: accounts[7],
-        pc_pnl_token_account: accounts[8],
-        pnl_owner_account: accounts[9],
-        amm_target_orders: accounts[10],
-        serum_program: accounts[11],
-        serum_market: accounts[12],
-        serum_event_queue: accounts[13],
-        serum_coin_vault_account: accounts[14],
-        serum_pc_vault_account: accounts[15],
-        serum_vault_signer: accounts[16],
+        token_program: read_account(accounts, 0)?,
+        amm: read_account(accounts, 1)?,
+        amm_config: read_account(accounts, 2)?,
+        amm_authority: read_account(accounts, 3)?,
+        amm_open_orders: read_account(accounts, 4)?,
+        pool_coin_token_account: read_account(accounts, 5)?,
+        pool_pc_token_account: read_account(accounts, 6)?,
+        coin_pnl_token_account: read_account(accounts, 7)?,
+        pc_pnl_token_account: read_account(accounts, 8)?,
+        pnl_owner_account: read_account(accounts, 9)?,
+        amm_target_orders: read_account(accounts, 10)?,
+        serum_program: read_account(accounts, 11)?,
+        serum_market: read_account(accounts, 12)?,
+        serum_event_queue: read_account(accounts, 13)?,
+        serum_coin_vault_account: read_account(accounts, 14)?,
+        serum_pc_vault_account: read_account(accounts, 15)?,
+        serum_vault_signer: read_account(accounts, 16)?,
     }))
 }
 
@@ -115,37 +121,44 @@ fn parse_withdraw_instruction(
     accounts: &[Pubkey],
     metadata: EventMetadata,
 ) -> Option<Box<dyn UnifiedEvent>> {
-    if data.len() < 8 || accounts.len() < 22 {
+    if data.len() < 8 || accounts.len() < 20 {
         return None;
     }
     let amount = read_u64_le(data, 0)?;
 
+    // 当前版本 20 个账户（可选 referrer 21 个），旧版在池子 vault 之后多 withdraw queue 和 temp lp
+    let (pool_withdraw_queue, pool_temp_lp_token_account, rest) = if accounts.len() >= 22 {
+        (Some(read_account(accounts, 8)?), Some(read_account(accounts, 9)?), accounts.get(10..)?)
+    } else {
+        (None, None, accounts.get(8..)?)
+    };
+
     Some(Box::new(RaydiumAmmV4WithdrawEvent {
         metadata,
         amount,
 
-        token_program: accounts[0],
-        amm: accounts[1],
-        amm_authority: accounts[2],
-        amm_open_orders: accounts[3],
-        amm_target_orders: accounts[4],
-        lp_mint_address: accounts[5],
-        pool_coin_token_account: accounts[6],
-        pool_pc_token_account: accounts[7],
-        pool_withdraw_queue: accounts[8],
-        pool_temp_lp_token_account: accounts[9],
-        serum_program: accounts[10],
-        serum_market: accounts[11],
-        serum_coin_vault_account: accounts[12],
-        serum_pc_vault_account: accounts[13],
-        serum_vault_signer: accounts[14],
-        user_lp_token_account: accounts[15],
-        user_coin_token_account: accounts[16],
-        user_pc_token_account: accounts[17],
-        user_owner: accounts[18],
-        serum_event_queue: accounts[19],
-        serum_bids: accounts[20],
-        serum_asks: accounts[21],
+        token_program: read_account(accounts, 0)?,
+        amm: read_account(accounts, 1)?,
+        amm_authority: read_account(accounts, 2)?,
+        amm_open_orders: read_account(accounts, 3)?,
+        amm_target_orders: read_account(accounts, 4)?,
+        lp_mint_address: read_account(accounts, 5)?,
+        pool_coin_token_account: read_account(accounts, 6)?,
+        pool_pc_token_account: read_account(accounts, 7)?,
+        pool_withdraw_queue,
+        pool_temp_lp_token_account,
+        serum_program: read_account(rest, 0)?,
+        serum_market: read_account(rest, 1)?,
+        serum_coin_vault_account: read_account(rest, 2)?,
+        serum_pc_vault_account: read_account(rest, 3)?,
+        serum_vault_signer: read_account(rest, 4)?,
+        user_lp_token_account: read_account(rest, 5)?,
+        user_coin_token_account: read_account(rest, 6)?,
+        user_pc_token_account: read_account(rest, 7)?,
+        user_owner: read_account(rest, 8)?,
+        serum_event_queue: read_account(rest, 9)?,
+        serum_bids: read_account(rest, 10)?,
+        serum_asks: read_account(rest, 11)?,
     }))
 }
 
@@ -170,27 +183,27 @@ fn parse_initialize2_instruction(
         init_pc_amount,
         init_coin_amount,
 
-        token_program: accounts[0],
-        spl_associated_token_account: accounts[1],
-        system_program: accounts[2],
-        rent: accounts[3],
-        amm: accounts[4],
-        amm_authority: accounts[5],
-        amm_open_orders: accounts[6],
-        lp_mint: accounts[7],
-        coin_mint: accounts[8],
-        pc_mint: accounts[9],
-        pool_coin_token_account: accounts[10],
-        pool_pc_token_account: accounts[11],
-        pool_withdraw_queue: accounts[12],
-        amm_target_orders: accounts[13],
-        pool_temp_lp: accounts[14],
-        serum_program: accounts[15],
-        serum_market: accounts[16],
-        user_wallet: accounts[17],
-        user_token_coin: accounts[18],
-        user_token_pc: accounts[19],
-        user_lp_token_account: accounts[20],
+        token_program: read_account(accounts, 0)?,
+        spl_associated_token_account: read_account(accounts, 1)?,
+        system_program: read_account(accounts, 2)?,
+        rent: read_account(accounts, 3)?,
+        amm: read_account(accounts, 4)?,
+        amm_authority: read_account(accounts, 5)?,
+        amm_open_orders: read_account(accounts, 6)?,
+        lp_mint: read_account(accounts, 7)?,
+        coin_mint: read_account(accounts, 8)?,
+        pc_mint: read_account(accounts, 9)?,
+        pool_coin_token_account: read_account(accounts, 10)?,
+        pool_pc_token_account: read_account(accounts, 11)?,
+        amm_target_orders: read_account(accounts, 12)?,
+        amm_config: read_account(accounts, 13)?,
+        create_fee_destination: read_account(accounts, 14)?,
+        serum_program: read_account(accounts, 15)?,
+        serum_market: read_account(accounts, 16)?,
+        user_wallet: read_account(accounts, 17)?,
+        user_token_coin: read_account(accounts, 18)?,
+        user_token_pc: read_account(accounts, 19)?,
+        user_lp_token_account: read_account(accounts, 20)?,
     }))
 }
 
@@ -213,20 +226,20 @@ fn parse_deposit_instruction(
         max_pc_amount,
         base_side,
 
-        token_program: accounts[0],
-        amm: accounts[1],
-        amm_authority: accounts[2],
-        amm_open_orders: accounts[3],
-        amm_target_orders: accounts[4],
-        lp_mint_address: accounts[5],
-        pool_coin_token_account: accounts[6],
-        pool_pc_token_account: accounts[7],
-        serum_market: accounts[8],
-        user_coin_token_account: accounts[9],
-        user_pc_token_account: accounts[10],
-        user_lp_token_account: accounts[11],
-        user_owner: accounts[12],
-        serum_event_queue: accounts[13],
+        token_program: read_account(accounts, 0)?,
+        amm: read_account(accounts, 1)?,
+        amm_authority: read_account(accounts, 2)?,
+        amm_open_orders: read_account(accounts, 3)?,
+        amm_target_orders: read_account(accounts, 4)?,
+        lp_mint_address: read_account(accounts, 5)?,
+        pool_coin_token_account: read_account(accounts, 6)?,
+        pool_pc_token_account: read_account(accounts, 7)?,
+        serum_market: read_account(accounts, 8)?,
+        user_coin_token_account: read_account(accounts, 9)?,
+        user_pc_token_account: read_account(accounts, 10)?,
+        user_lp_token_account: read_account(accounts, 11)?,
+        user_owner: read_account(accounts, 12)?,
+        serum_event_queue: read_account(accounts, 13)?,
     }))
 }
 
@@ -254,24 +267,24 @@ fn parse_swap_base_output_instruction(
         max_amount_in,
         amount_out,
 
-        token_program: accounts[0],
-        amm: accounts[1],
-        amm_authority: accounts[2],
-        amm_open_orders: accounts[3],
-        amm_target_orders: Some(accounts[4]),
-        pool_coin_token_account: accounts[5],
-        pool_pc_token_account: accounts[6],
-        serum_program: accounts[7],
-        serum_market: accounts[8],
-        serum_bids: accounts[9],
-        serum_asks: accounts[10],
-        serum_event_queue: accounts[11],
-        serum_coin_vault_account: accounts[12],
-        serum_pc_vault_account: accounts[13],
-        serum_vault_signer: accounts[14],
-        user_source_token_account: accounts[15],
-        user_destination_token_account: accounts[16],
-        user_source_owner: accounts[17],
+        token_program: read_account(&accounts, 0)?,
+        amm: read_account(&accounts, 1)?,
+        amm_authority: read_account(&accounts, 2)?,
+        amm_open_orders: read_account(&accounts, 3)?,
+        amm_target_orders: Some(read_account(&accounts, 4)?),
+        pool_coin_token_account: read_account(&accounts, 5)?,
+        pool_pc_token_account: read_account(&accounts, 6)?,
+        serum_program: read_account(&accounts, 7)?,
+        serum_market: read_account(&accounts, 8)?,
+        serum_bids: read_account(&accounts, 9)?,
+        serum_asks: read_account(&accounts, 10)?,
+        serum_event_queue: read_account(&accounts, 11)?,
+        serum_coin_vault_account: read_account(&accounts, 12)?,
+        serum_pc_vault_account: read_account(&accounts, 13)?,
+        serum_vault_signer: read_account(&accounts, 14)?,
+        user_source_token_account: read_account(&accounts, 15)?,
+        user_destination_token_account: read_account(&accounts, 16)?,
+        user_source_owner: read_account(&accounts, 17)?,
 
         ..Default::default()
     }))
@@ -301,24 +314,24 @@ fn parse_swap_base_input_instruction(
         amount_in,
         minimum_amount_out,
 
-        token_program: accounts[0],
-        amm: accounts[1],
-        amm_authority: accounts[2],
-        amm_open_orders: accounts[3],
-        amm_target_orders: Some(accounts[4]),
-        pool_coin_token_account: accounts[5],
-        pool_pc_token_account: accounts[6],
-        serum_program: accounts[7],
-        serum_market: accounts[8],
-        serum_bids: accounts[9],
-        serum_asks: accounts[10],
-        serum_event_queue: accounts[11],
-        serum_coin_vault_account: accounts[12],
-        serum_pc_vault_account: accounts[13],
-        serum_vault_signer: accounts[14],
-        user_source_token_account: accounts[15],
-        user_destination_token_account: accounts[16],
-        user_source_owner: accounts[17],
+        token_program: read_account(&accounts, 0)?,
+        amm: read_account(&accounts, 1)?,
+        amm_authority: read_account(&accounts, 2)?,
+        amm_open_orders: read_account(&accounts, 3)?,
+        amm_target_orders: Some(read_account(&accounts, 4)?),
+        pool_coin_token_account: read_account(&accounts, 5)?,
+        pool_pc_token_account: read_account(&accounts, 6)?,
+        serum_program: read_account(&accounts, 7)?,
+        serum_market: read_account(&accounts, 8)?,
+        serum_bids: read_account(&accounts, 9)?,
+        serum_asks: read_account(&accounts, 10)?,
+        serum_event_queue: read_account(&accounts, 11)?,
+        serum_coin_vault_account: read_account(&accounts, 12)?,
+        serum_pc_vault_account: read_account(&accounts, 13)?,
+        serum_vault_signer: read_account(&accounts, 14)?,
+        user_source_token_account: read_account(&accounts, 15)?,
+        user_destination_token_account: read_account(&accounts, 16)?,
+        user_source_owner: read_account(&accounts, 17)?,
 
         ..Default::default()
     }))
@@ -78,6 +78,8 @@ pub struct AmmInfo {
 }
 
 pub const AMM_INFO_SIZE: usize = 752;
+/// AMM V4 账户没有 discriminator，链上长度即结构体长度
+pub const AMM_INFO_ACCOUNT_SIZE: usize = AMM_INFO_SIZE;
 
 pub fn amm_info_decode(data: &[u8]) -> Option<AmmInfo> {
     if data.len() < AMM_INFO_SIZE {
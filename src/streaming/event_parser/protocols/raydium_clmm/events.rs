@@ -1,4 +1,4 @@
-use crate::streaming::event_parser::common::EventMetadata;
+use crate::streaming::event_parser::common::{EventMetadata, SwapAccounts};
 use crate::streaming::event_parser::protocols::raydium_clmm::types::{PoolState, TickArrayState};
 use crate::{
     impl_unified_event, streaming::event_parser::protocols::raydium_clmm::types::AmmConfig,
@@ -29,6 +29,20 @@ pub struct RaydiumClmmSwapEvent {
 
 impl_unified_event!(RaydiumClmmSwapEvent,);
 
+impl RaydiumClmmSwapEvent {
+    /// v1 指令不带 mint，只能从 TransferChecked 中取得
+    pub fn swap_accounts(&self) -> SwapAccounts {
+        SwapAccounts {
+            pool: self.pool_state,
+            user_from_token: self.input_token_account,
+            user_to_token: self.output_token_account,
+            from_vault: self.input_vault,
+            to_vault: self.output_vault,
+            ..Default::default()
+        }
+    }
+}
+
 /// 交易v2
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RaydiumClmmSwapV2Event {
@@ -54,6 +68,21 @@ pub struct RaydiumClmmSwapV2Event {
 }
 impl_unified_event!(RaydiumClmmSwapV2Event,);
 
+impl RaydiumClmmSwapV2Event {
+    pub fn swap_accounts(&self) -> SwapAccounts {
+        SwapAccounts {
+            pool: self.pool_state,
+            user_from_token: self.input_token_account,
+            user_to_token: self.output_token_account,
+            from_vault: self.input_vault,
+            to_vault: self.output_vault,
+            from_mint: self.input_vault_mint,
+            to_mint: self.output_vault_mint,
+            collect: false,
+        }
+    }
+}
+
 /// 关闭仓位
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RaydiumClmmClosePositionEvent {
@@ -94,6 +123,134 @@ pub struct RaydiumClmmDecreaseLiquidityV2Event {
 }
 impl_unified_event!(RaydiumClmmDecreaseLiquidityV2Event,);
 
+impl RaydiumClmmDecreaseLiquidityV2Event {
+    /// 流动性为 0 的减仓只领取仓位手续费和奖励
+    pub fn is_fee_collection(&self) -> bool {
+        self.liquidity == 0
+    }
+
+    pub fn swap_accounts(&self) -> SwapAccounts {
+        SwapAccounts {
+            pool: self.pool_state,
+            user_from_token: self.recipient_token_account0,
+            user_to_token: self.recipient_token_account1,
+            from_vault: self.token_vault0,
+            to_vault: self.token_vault1,
+            from_mint: self.vault0_mint,
+            to_mint: self.vault1_mint,
+            collect: true,
+        }
+    }
+}
+
+/// 领取协议手续费，实际领取数量见 `metadata.swap_data`（from 为 token0，to 为 token1）
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaydiumClmmCollectProtocolFeeEvent {
+    pub metadata: EventMetadata,
+    pub amount0_requested: u64,
+    pub amount1_requested: u64,
+    pub owner: Pubkey,
+    pub pool_state: Pubkey,
+    pub amm_config: Pubkey,
+    pub token_vault0: Pubkey,
+    pub token_vault1: Pubkey,
+    pub vault0_mint: Pubkey,
+    pub vault1_mint: Pubkey,
+    pub recipient_token_account0: Pubkey,
+    pub recipient_token_account1: Pubkey,
+    pub token_program: Pubkey,
+    pub token_program2022: Pubkey,
+}
+impl_unified_event!(RaydiumClmmCollectProtocolFeeEvent,);
+
+impl RaydiumClmmCollectProtocolFeeEvent {
+    pub fn swap_accounts(&self) -> SwapAccounts {
+        SwapAccounts {
+            pool: self.pool_state,
+            user_from_token: self.recipient_token_account0,
+            user_to_token: self.recipient_token_account1,
+            from_vault: self.token_vault0,
+            to_vault: self.token_vault1,
+            from_mint: self.vault0_mint,
+            to_mint: self.vault1_mint,
+            collect: true,
+        }
+    }
+}
+
+/// 领取基金手续费，实际领取数量见 `metadata.swap_data`（from 为 token0，to 为 token1）
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaydiumClmmCollectFundFeeEvent {
+    pub metadata: EventMetadata,
+    pub amount0_requested: u64,
+    pub amount1_requested: u64,
+    pub owner: Pubkey,
+    pub pool_state: Pubkey,
+    pub amm_config: Pubkey,
+    pub token_vault0: Pubkey,
+    pub token_vault1: Pubkey,
+    pub vault0_mint: Pubkey,
+    pub vault1_mint: Pubkey,
+    pub recipient_token_account0: Pubkey,
+    pub recipient_token_account1: Pubkey,
+    pub token_program: Pubkey,
+    pub token_program2022: Pubkey,
+}
+impl_unified_event!(RaydiumClmmCollectFundFeeEvent,);
+
+impl RaydiumClmmCollectFundFeeEvent {
+    pub fn swap_accounts(&self) -> SwapAccounts {
+        SwapAccounts {
+            pool: self.pool_state,
+            user_from_token: self.recipient_token_account0,
+            user_to_token: self.recipient_token_account1,
+            from_vault: self.token_vault0,
+            to_vault: self.token_vault1,
+            from_mint: self.vault0_mint,
+            to_mint: self.vault1_mint,
+            collect: true,
+        }
+    }
+}
+
+/// 领取剩余奖励，实际领取数量见 `metadata.swap_data` 的 from 侧
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaydiumClmmCollectRemainingRewardsEvent {
+    pub metadata: EventMetadata,
+    pub reward_index: u8,
+    pub reward_funder: Pubkey,
+    pub funder_token_account: Pubkey,
+    pub pool_state: Pubkey,
+    pub reward_token_vault: Pubkey,
+    pub reward_vault_mint: Pubkey,
+    pub token_program: Pubkey,
+    pub token_program2022: Pubkey,
+    pub memo_program: Pubkey,
+}
+impl_unified_event!(RaydiumClmmCollectRemainingRewardsEvent,);
+
+impl RaydiumClmmCollectRemainingRewardsEvent {
+    /// 只有 from 侧（奖励代币）
+    pub fn swap_accounts(&self) -> SwapAccounts {
+        SwapAccounts {
+            pool: self.pool_state,
+            user_from_token: self.funder_token_account,
+            from_vault: self.reward_token_vault,
+            from_mint: self.reward_vault_mint,
+            collect: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// 更新池奖励信息
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaydiumClmmUpdateRewardInfosEvent {
+    pub metadata: EventMetadata,
+    pub pool_state: Pubkey,
+}
+impl_unified_event!(RaydiumClmmUpdateRewardInfosEvent,);
+
 /// 创建池
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RaydiumClmmCreatePoolEvent {
@@ -269,6 +426,10 @@ pub mod discriminators {
     pub const CREATE_POOL: &[u8] = &[233, 146, 209, 142, 207, 104, 64, 188];
     pub const OPEN_POSITION_WITH_TOKEN_22_NFT: &[u8] = &[77, 255, 174, 82, 125, 29, 201, 46];
     pub const OPEN_POSITION_V2: &[u8] = &[77, 184, 74, 214, 112, 86, 241, 199];
+    pub const COLLECT_PROTOCOL_FEE: &[u8] = &[136, 136, 252, 221, 194, 66, 126, 89];
+    pub const COLLECT_FUND_FEE: &[u8] = &[167, 138, 78, 149, 223, 194, 6, 126];
+    pub const COLLECT_REMAINING_REWARDS: &[u8] = &[18, 237, 166, 197, 34, 16, 213, 144];
+    pub const UPDATE_REWARD_INFOS: &[u8] = &[163, 172, 224, 52, 11, 154, 106, 223];
 
     // 账号鉴别器
     pub const AMM_CONFIG: &[u8] = &[218, 244, 33, 104, 203, 203, 43, 111];
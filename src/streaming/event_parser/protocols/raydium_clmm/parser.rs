@@ -1,14 +1,16 @@
 use crate::streaming::event_parser::{
     common::{
-        read_i32_le, read_option_bool, read_u128_le, read_u64_le, read_u8_le, EventMetadata,
-        EventType, ProtocolType,
+        read_account, read_i32_le, read_option_bool, read_u128_le, read_u64_le, read_u8_le,
+        EventMetadata, EventType, ProtocolType,
     },
     core::event_parser::GenericEventParseConfig,
     protocols::raydium_clmm::{
-        discriminators, RaydiumClmmClosePositionEvent, RaydiumClmmCreatePoolEvent,
-        RaydiumClmmDecreaseLiquidityV2Event, RaydiumClmmIncreaseLiquidityV2Event,
-        RaydiumClmmOpenPositionV2Event, RaydiumClmmOpenPositionWithToken22NftEvent,
-        RaydiumClmmSwapEvent, RaydiumClmmSwapV2Event,
+        discriminators, RaydiumClmmClosePositionEvent, RaydiumClmmCollectFundFeeEvent,
+        RaydiumClmmCollectProtocolFeeEvent, RaydiumClmmCollectRemainingRewardsEvent,
+        RaydiumClmmCreatePoolEvent, RaydiumClmmDecreaseLiquidityV2Event,
+        RaydiumClmmIncreaseLiquidityV2Event, RaydiumClmmOpenPositionV2Event,
+        RaydiumClmmOpenPositionWithToken22NftEvent, RaydiumClmmSwapEvent, RaydiumClmmSwapV2Event,
+        RaydiumClmmUpdateRewardInfosEvent,
     },
     UnifiedEvent,
 };
@@ -29,6 +31,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_swap_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_CLMM_PROGRAM_ID,
@@ -39,6 +42,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_swap_v2_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_CLMM_PROGRAM_ID,
@@ -49,6 +53,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_close_position_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_CLMM_PROGRAM_ID,
@@ -59,6 +64,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_decrease_liquidity_v2_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_CLMM_PROGRAM_ID,
@@ -69,6 +75,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_create_pool_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_CLMM_PROGRAM_ID,
@@ -79,6 +86,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_increase_liquidity_v2_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_CLMM_PROGRAM_ID,
@@ -89,6 +97,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_open_position_with_token_22_nft_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_CLMM_PROGRAM_ID,
@@ -99,6 +108,51 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_open_position_v2_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
+    },
+    GenericEventParseConfig {
+        program_id: RAYDIUM_CLMM_PROGRAM_ID,
+        protocol_type: ProtocolType::RaydiumClmm,
+        inner_instruction_discriminator: &[],
+        instruction_discriminator: discriminators::COLLECT_PROTOCOL_FEE,
+        event_type: EventType::RaydiumClmmCollectProtocolFee,
+        inner_instruction_parser: None,
+        instruction_parser: Some(parse_collect_protocol_fee_instruction),
+        requires_inner_instruction: false,
+        dynamic_instruction: None,
+    },
+    GenericEventParseConfig {
+        program_id: RAYDIUM_CLMM_PROGRAM_ID,
+        protocol_type: ProtocolType::RaydiumClmm,
+        inner_instruction_discriminator: &[],
+        instruction_discriminator: discriminators::COLLECT_FUND_FEE,
+        event_type: EventType::RaydiumClmmCollectFundFee,
+        inner_instruction_parser: None,
+        instruction_parser: Some(parse_collect_fund_fee_instruction),
+        requires_inner_instruction: false,
+        dynamic_instruction: None,
+    },
+    GenericEventParseConfig {
+        program_id: RAYDIUM_CLMM_PROGRAM_ID,
+        protocol_type: ProtocolType::RaydiumClmm,
+        inner_instruction_discriminator: &[],
+        instruction_discriminator: discriminators::COLLECT_REMAINING_REWARDS,
+        event_type: EventType::RaydiumClmmCollectRemainingRewards,
+        inner_instruction_parser: None,
+        instruction_parser: Some(parse_collect_remaining_rewards_instruction),
+        requires_inner_instruction: false,
+        dynamic_instruction: None,
+    },
+    GenericEventParseConfig {
+        program_id: RAYDIUM_CLMM_PROGRAM_ID,
+        protocol_type: ProtocolType::RaydiumClmm,
+        inner_instruction_discriminator: &[],
+        instruction_discriminator: discriminators::UPDATE_REWARD_INFOS,
+        event_type: EventType::RaydiumClmmUpdateRewardInfos,
+        inner_instruction_parser: None,
+        instruction_parser: Some(parse_update_reward_infos_instruction),
+        requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
 ];
 
@@ -122,29 +176,29 @@ fn parse_open_position_v2_instruction(
         amount1_max: read_u64_le(data, 40)?,
         with_metadata: read_u8_le(data, 48)? == 1,
         base_flag: read_option_bool(data, &mut 49)?,
-        payer: accounts[0],
-        position_nft_owner: accounts[1],
-        position_nft_mint: accounts[2],
-        position_nft_account: accounts[3],
-        metadata_account: accounts[4],
-        pool_state: accounts[5],
-        protocol_position: accounts[6],
-        tick_array_lower: accounts[7],
-        tick_array_upper: accounts[8],
-        personal_position: accounts[9],
-        token_account0: accounts[10],
-        token_account1: accounts[11],
-        token_vault0: accounts[12],
-        token_vault1: accounts[13],
-        rent: accounts[14],
-        system_program: accounts[15],
-        token_program: accounts[16],
-        associated_token_program: accounts[17],
-        metadata_program: accounts[18],
-        token_program2022: accounts[19],
-        vault0_mint: accounts[20],
-        vault1_mint: accounts[21],
-        remaining_accounts: accounts[22..].to_vec(),
+        payer: read_account(accounts, 0)?,
+        position_nft_owner: read_account(accounts, 1)?,
+        position_nft_mint: read_account(accounts, 2)?,
+        position_nft_account: read_account(accounts, 3)?,
+        metadata_account: read_account(accounts, 4)?,
+        pool_state: read_account(accounts, 5)?,
+        protocol_position: read_account(accounts, 6)?,
+        tick_array_lower: read_account(accounts, 7)?,
+        tick_array_upper: read_account(accounts, 8)?,
+        personal_position: read_account(accounts, 9)?,
+        token_account0: read_account(accounts, 10)?,
+        token_account1: read_account(accounts, 11)?,
+        token_vault0: read_account(accounts, 12)?,
+        token_vault1: read_account(accounts, 13)?,
+        rent: read_account(accounts, 14)?,
+        system_program: read_account(accounts, 15)?,
+        token_program: read_account(accounts, 16)?,
+        associated_token_program: read_account(accounts, 17)?,
+        metadata_program: read_account(accounts, 18)?,
+        token_program2022: read_account(accounts, 19)?,
+        vault0_mint: read_account(accounts, 20)?,
+        vault1_mint: read_account(accounts, 21)?,
+        remaining_accounts: accounts.get(22..)?.to_vec(),
     }))
 }
 
@@ -168,26 +222,26 @@ fn parse_open_position_with_token_22_nft_instruction(
         amount1_max: read_u64_le(data, 40)?,
         with_metadata: read_u8_le(data, 48)? == 1,
         base_flag: read_option_bool(data, &mut 49)?,
-        payer: accounts[0],
-        position_nft_owner: accounts[1],
-        position_nft_mint: accounts[2],
-        position_nft_account: accounts[3],
-        pool_state: accounts[4],
-        protocol_position: accounts[5],
-        tick_array_lower: accounts[6],
-        tick_array_upper: accounts[7],
-        personal_position: accounts[8],
-        token_account0: accounts[9],
-        token_account1: accounts[10],
-        token_vault0: accounts[11],
-        token_vault1: accounts[12],
-        rent: accounts[13],
-        system_program: accounts[14],
-        token_program: accounts[15],
-        associated_token_program: accounts[16],
-        token_program2022: accounts[17],
-        vault0_mint: accounts[18],
-        vault1_mint: accounts[19],
+        payer: read_account(accounts, 0)?,
+        position_nft_owner: read_account(accounts, 1)?,
+        position_nft_mint: read_account(accounts, 2)?,
+        position_nft_account: read_account(accounts, 3)?,
+        pool_state: read_account(accounts, 4)?,
+        protocol_position: read_account(accounts, 5)?,
+        tick_array_lower: read_account(accounts, 6)?,
+        tick_array_upper: read_account(accounts, 7)?,
+        personal_position: read_account(accounts, 8)?,
+        token_account0: read_account(accounts, 9)?,
+        token_account1: read_account(accounts, 10)?,
+        token_vault0: read_account(accounts, 11)?,
+        token_vault1: read_account(accounts, 12)?,
+        rent: read_account(accounts, 13)?,
+        system_program: read_account(accounts, 14)?,
+        token_program: read_account(accounts, 15)?,
+        associated_token_program: read_account(accounts, 16)?,
+        token_program2022: read_account(accounts, 17)?,
+        vault0_mint: read_account(accounts, 18)?,
+        vault1_mint: read_account(accounts, 19)?,
     }))
 }
 
@@ -206,21 +260,21 @@ fn parse_increase_liquidity_v2_instruction(
         amount0_max: read_u64_le(data, 16)?,
         amount1_max: read_u64_le(data, 24)?,
         base_flag: read_option_bool(data, &mut 32)?,
-        nft_owner: accounts[0],
-        nft_account: accounts[1],
-        pool_state: accounts[2],
-        protocol_position: accounts[3],
-        personal_position: accounts[4],
-        tick_array_lower: accounts[5],
-        tick_array_upper: accounts[6],
-        token_account0: accounts[7],
-        token_account1: accounts[8],
-        token_vault0: accounts[9],
-        token_vault1: accounts[10],
-        token_program: accounts[11],
-        token_program2022: accounts[12],
-        vault0_mint: accounts[13],
-        vault1_mint: accounts[14],
+        nft_owner: read_account(accounts, 0)?,
+        nft_account: read_account(accounts, 1)?,
+        pool_state: read_account(accounts, 2)?,
+        protocol_position: read_account(accounts, 3)?,
+        personal_position: read_account(accounts, 4)?,
+        tick_array_lower: read_account(accounts, 5)?,
+        tick_array_upper: read_account(accounts, 6)?,
+        token_account0: read_account(accounts, 7)?,
+        token_account1: read_account(accounts, 8)?,
+        token_vault0: read_account(accounts, 9)?,
+        token_vault1: read_account(accounts, 10)?,
+        token_program: read_account(accounts, 11)?,
+        token_program2022: read_account(accounts, 12)?,
+        vault0_mint: read_account(accounts, 13)?,
+        vault1_mint: read_account(accounts, 14)?,
     }))
 }
 
@@ -237,19 +291,19 @@ fn parse_create_pool_instruction(
         metadata,
         sqrt_price_x64: read_u128_le(data, 0)?,
         open_time: read_u64_le(data, 16)?,
-        pool_creator: accounts[0],
-        amm_config: accounts[1],
-        pool_state: accounts[2],
-        token_mint0: accounts[3],
-        token_mint1: accounts[4],
-        token_vault0: accounts[5],
-        token_vault1: accounts[6],
-        observation_state: accounts[7],
-        tick_array_bitmap: accounts[8],
-        token_program0: accounts[9],
-        token_program1: accounts[10],
-        system_program: accounts[11],
-        rent: accounts[12],
+        pool_creator: read_account(accounts, 0)?,
+        amm_config: read_account(accounts, 1)?,
+        pool_state: read_account(accounts, 2)?,
+        token_mint0: read_account(accounts, 3)?,
+        token_mint1: read_account(accounts, 4)?,
+        token_vault0: read_account(accounts, 5)?,
+        token_vault1: read_account(accounts, 6)?,
+        observation_state: read_account(accounts, 7)?,
+        tick_array_bitmap: read_account(accounts, 8)?,
+        token_program0: read_account(accounts, 9)?,
+        token_program1: read_account(accounts, 10)?,
+        system_program: read_account(accounts, 11)?,
+        rent: read_account(accounts, 12)?,
     }))
 }
 
@@ -267,23 +321,23 @@ fn parse_decrease_liquidity_v2_instruction(
         liquidity: read_u128_le(data, 0)?,
         amount0_min: read_u64_le(data, 16)?,
         amount1_min: read_u64_le(data, 24)?,
-        nft_owner: accounts[0],
-        nft_account: accounts[1],
-        personal_position: accounts[2],
-        pool_state: accounts[3],
-        protocol_position: accounts[4],
-        token_vault0: accounts[5],
-        token_vault1: accounts[6],
-        tick_array_lower: accounts[7],
-        tick_array_upper: accounts[8],
-        recipient_token_account0: accounts[9],
-        recipient_token_account1: accounts[10],
-        token_program: accounts[11],
-        token_program2022: accounts[12],
-        memo_program: accounts[13],
-        vault0_mint: accounts[14],
-        vault1_mint: accounts[15],
-        remaining_accounts: accounts[16..].to_vec(),
+        nft_owner: read_account(accounts, 0)?,
+        nft_account: read_account(accounts, 1)?,
+        personal_position: read_account(accounts, 2)?,
+        pool_state: read_account(accounts, 3)?,
+        protocol_position: read_account(accounts, 4)?,
+        token_vault0: read_account(accounts, 5)?,
+        token_vault1: read_account(accounts, 6)?,
+        tick_array_lower: read_account(accounts, 7)?,
+        tick_array_upper: read_account(accounts, 8)?,
+        recipient_token_account0: read_account(accounts, 9)?,
+        recipient_token_account1: read_account(accounts, 10)?,
+        token_program: read_account(accounts, 11)?,
+        token_program2022: read_account(accounts, 12)?,
+        memo_program: read_account(accounts, 13)?,
+        vault0_mint: read_account(accounts, 14)?,
+        vault1_mint: read_account(accounts, 15)?,
+        remaining_accounts: accounts.get(16..)?.to_vec(),
     }))
 }
 
@@ -298,12 +352,12 @@ fn parse_close_position_instruction(
     }
     Some(Box::new(RaydiumClmmClosePositionEvent {
         metadata,
-        nft_owner: accounts[0],
-        position_nft_mint: accounts[1],
-        position_nft_account: accounts[2],
-        personal_position: accounts[3],
-        system_program: accounts[4],
-        token_program: accounts[5],
+        nft_owner: read_account(accounts, 0)?,
+        position_nft_mint: read_account(accounts, 1)?,
+        position_nft_account: read_account(accounts, 2)?,
+        personal_position: read_account(accounts, 3)?,
+        system_program: read_account(accounts, 4)?,
+        token_program: read_account(accounts, 5)?,
     }))
 }
 
@@ -328,17 +382,17 @@ fn parse_swap_instruction(
         other_amount_threshold,
         sqrt_price_limit_x64,
         is_base_input: is_base_input == 1,
-        payer: accounts[0],
-        amm_config: accounts[1],
-        pool_state: accounts[2],
-        input_token_account: accounts[3],
-        output_token_account: accounts[4],
-        input_vault: accounts[5],
-        output_vault: accounts[6],
-        observation_state: accounts[7],
-        token_program: accounts[8],
-        tick_array: accounts[9],
-        remaining_accounts: accounts[10..].to_vec(),
+        payer: read_account(accounts, 0)?,
+        amm_config: read_account(accounts, 1)?,
+        pool_state: read_account(accounts, 2)?,
+        input_token_account: read_account(accounts, 3)?,
+        output_token_account: read_account(accounts, 4)?,
+        input_vault: read_account(accounts, 5)?,
+        output_vault: read_account(accounts, 6)?,
+        observation_state: read_account(accounts, 7)?,
+        token_program: read_account(accounts, 8)?,
+        tick_array: read_account(accounts, 9)?,
+        remaining_accounts: accounts.get(10..)?.to_vec(),
     }))
 }
 
@@ -362,19 +416,108 @@ fn parse_swap_v2_instruction(
         other_amount_threshold,
         sqrt_price_limit_x64,
         is_base_input: is_base_input == 1,
-        payer: accounts[0],
-        amm_config: accounts[1],
-        pool_state: accounts[2],
-        input_token_account: accounts[3],
-        output_token_account: accounts[4],
-        input_vault: accounts[5],
-        output_vault: accounts[6],
-        observation_state: accounts[7],
-        token_program: accounts[8],
-        token_program2022: accounts[9],
-        memo_program: accounts[10],
-        input_vault_mint: accounts[11],
-        output_vault_mint: accounts[12],
-        remaining_accounts: accounts[13..].to_vec(),
+        payer: read_account(accounts, 0)?,
+        amm_config: read_account(accounts, 1)?,
+        pool_state: read_account(accounts, 2)?,
+        input_token_account: read_account(accounts, 3)?,
+        output_token_account: read_account(accounts, 4)?,
+        input_vault: read_account(accounts, 5)?,
+        output_vault: read_account(accounts, 6)?,
+        observation_state: read_account(accounts, 7)?,
+        token_program: read_account(accounts, 8)?,
+        token_program2022: read_account(accounts, 9)?,
+        memo_program: read_account(accounts, 10)?,
+        input_vault_mint: read_account(accounts, 11)?,
+        output_vault_mint: read_account(accounts, 12)?,
+        remaining_accounts: accounts.get(13..)?.to_vec(),
+    }))
+}
+
+/// 解析领取协议手续费指令事件
+fn parse_collect_protocol_fee_instruction(
+    data: &[u8],
+    accounts: &[Pubkey],
+    metadata: EventMetadata,
+) -> Option<Box<dyn UnifiedEvent>> {
+    if data.len() < 16 || accounts.len() < 11 {
+        return None;
+    }
+    Some(Box::new(RaydiumClmmCollectProtocolFeeEvent {
+        metadata,
+        amount0_requested: read_u64_le(data, 0)?,
+        amount1_requested: read_u64_le(data, 8)?,
+        owner: read_account(accounts, 0)?,
+        pool_state: read_account(accounts, 1)?,
+        amm_config: read_account(accounts, 2)?,
+        token_vault0: read_account(accounts, 3)?,
+        token_vault1: read_account(accounts, 4)?,
+        vault0_mint: read_account(accounts, 5)?,
+        vault1_mint: read_account(accounts, 6)?,
+        recipient_token_account0: read_account(accounts, 7)?,
+        recipient_token_account1: read_account(accounts, 8)?,
+        token_program: read_account(accounts, 9)?,
+        token_program2022: read_account(accounts, 10)?,
+    }))
+}
+
+/// 解析领取基金手续费指令事件
+fn parse_collect_fund_fee_instruction(
+    data: &[u8],
+    accounts: &[Pubkey],
+    metadata: EventMetadata,
+) -> Option<Box<dyn UnifiedEvent>> {
+    if data.len() < 16 || accounts.len() < 11 {
+        return None;
+    }
+    Some(Box::new(RaydiumClmmCollectFundFeeEvent {
+        metadata,
+        amount0_requested: read_u64_le(data, 0)?,
+        amount1_requested: read_u64_le(data, 8)?,
+        owner: read_account(accounts, 0)?,
+        pool_state: read_account(accounts, 1)?,
+        amm_config: read_account(accounts, 2)?,
+        token_vault0: read_account(accounts, 3)?,
+        token_vault1: read_account(accounts, 4)?,
+        vault0_mint: read_account(accounts, 5)?,
+        vault1_mint: read_account(accounts, 6)?,
+        recipient_token_account0: read_account(accounts, 7)?,
+        recipient_token_account1: read_account(accounts, 8)?,
+        token_program: read_account(accounts, 9)?,
+        token_program2022: read_account(accounts, 10)?,
+    }))
+}
+
+/// 解析领取剩余奖励指令事件
+fn parse_collect_remaining_rewards_instruction(
+    data: &[u8],
+    accounts: &[Pubkey],
+    metadata: EventMetadata,
+) -> Option<Box<dyn UnifiedEvent>> {
+    if data.is_empty() || accounts.len() < 8 {
+        return None;
+    }
+    Some(Box::new(RaydiumClmmCollectRemainingRewardsEvent {
+        metadata,
+        reward_index: read_u8_le(data, 0)?,
+        reward_funder: read_account(accounts, 0)?,
+        funder_token_account: read_account(accounts, 1)?,
+        pool_state: read_account(accounts, 2)?,
+        reward_token_vault: read_account(accounts, 3)?,
+        reward_vault_mint: read_account(accounts, 4)?,
+        token_program: read_account(accounts, 5)?,
+        token_program2022: read_account(accounts, 6)?,
+        memo_program: read_account(accounts, 7)?,
+    }))
+}
+
+/// 解析更新奖励信息指令事件
+fn parse_update_reward_infos_instruction(
+    _data: &[u8],
+    accounts: &[Pubkey],
+    metadata: EventMetadata,
+) -> Option<Box<dyn UnifiedEvent>> {
+    Some(Box::new(RaydiumClmmUpdateRewardInfosEvent {
+        metadata,
+        pool_state: read_account(accounts, 0)?,
     }))
 }
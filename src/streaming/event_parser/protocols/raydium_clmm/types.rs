@@ -29,6 +29,8 @@ pub struct AmmConfig {
 }
 
 pub const AMM_CONFIG_SIZE: usize = 1 + 2 + 32 + 4 * 2 + 2 + 4 * 2 + 32 + 8 * 3;
+/// 链上账户总长度（含 8 字节 discriminator），用于 datasize 过滤
+pub const AMM_CONFIG_ACCOUNT_SIZE: usize = AMM_CONFIG_SIZE + 8;
 
 pub fn amm_config_decode(data: &[u8]) -> Option<AmmConfig> {
     if data.len() < AMM_CONFIG_SIZE {
@@ -117,6 +119,7 @@ pub struct PoolState {
 }
 
 pub const POOL_STATE_SIZE: usize = 1536;
+pub const POOL_STATE_ACCOUNT_SIZE: usize = POOL_STATE_SIZE + 8;
 
 pub fn pool_state_decode(data: &[u8]) -> Option<PoolState> {
     if data.len() < POOL_STATE_SIZE {
@@ -198,6 +201,7 @@ impl Default for TickArrayState {
 }
 
 pub const TICK_ARRAY_STATE_SIZE: usize = 10232;
+pub const TICK_ARRAY_STATE_ACCOUNT_SIZE: usize = TICK_ARRAY_STATE_SIZE + 8;
 
 pub fn tick_array_state_decode(data: &[u8]) -> Option<TickArrayState> {
     if data.len() < TICK_ARRAY_STATE_SIZE {
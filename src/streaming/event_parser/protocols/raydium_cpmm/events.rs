@@ -1,4 +1,4 @@
-use crate::streaming::event_parser::common::EventMetadata;
+use crate::streaming::event_parser::common::{EventMetadata, SwapAccounts};
 use crate::streaming::event_parser::protocols::raydium_cpmm::types::PoolState;
 use crate::{
     impl_unified_event, streaming::event_parser::protocols::raydium_cpmm::types::AmmConfig,
@@ -33,6 +33,21 @@ pub struct RaydiumCpmmSwapEvent {
 
 impl_unified_event!(RaydiumCpmmSwapEvent,);
 
+impl RaydiumCpmmSwapEvent {
+    pub fn swap_accounts(&self) -> SwapAccounts {
+        SwapAccounts {
+            pool: self.pool_state,
+            user_from_token: self.input_token_account,
+            user_to_token: self.output_token_account,
+            from_vault: self.input_vault,
+            to_vault: self.output_vault,
+            from_mint: self.input_token_mint,
+            to_mint: self.output_token_mint,
+            collect: false,
+        }
+    }
+}
+
 /// 存款
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshDeserialize)]
 pub struct RaydiumCpmmDepositEvent {
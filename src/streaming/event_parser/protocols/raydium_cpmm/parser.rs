@@ -1,7 +1,7 @@
 use solana_sdk::pubkey::Pubkey;
 
 use crate::streaming::event_parser::{
-    common::{read_u64_le, EventMetadata, EventType, ProtocolType},
+    common::{read_account, read_u64_le, EventMetadata, EventType, ProtocolType},
     core::event_parser::{EventParser, GenericEventParseConfig},
     protocols::raydium_cpmm::{
         discriminators, RaydiumCpmmDepositEvent, RaydiumCpmmInitializeEvent, RaydiumCpmmSwapEvent,
@@ -25,6 +25,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_swap_base_input_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_CPMM_PROGRAM_ID,
@@ -35,6 +36,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_swap_base_output_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_CPMM_PROGRAM_ID,
@@ -45,6 +47,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_deposit_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_CPMM_PROGRAM_ID,
@@ -55,6 +58,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_initialize_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
     GenericEventParseConfig {
         program_id: RAYDIUM_CPMM_PROGRAM_ID,
@@ -65,6 +69,7 @@ pub const CONFIGS: &[GenericEventParseConfig] = &[
         inner_instruction_parser: None,
         instruction_parser: Some(parse_withdraw_instruction),
         requires_inner_instruction: false,
+        dynamic_instruction: None,
     },
 ];
 
@@ -82,20 +87,20 @@ fn parse_withdraw_instruction(
         lp_token_amount: read_u64_le(data, 0)?,
         minimum_token0_amount: read_u64_le(data, 8)?,
         minimum_token1_amount: read_u64_le(data, 16)?,
-        owner: accounts[0],
-        authority: accounts[1],
-        pool_state: accounts[2],
-        owner_lp_token: accounts[3],
-        token0_account: accounts[4],
-        token1_account: accounts[5],
-        token0_vault: accounts[6],
-        token1_vault: accounts[7],
-        token_program: accounts[8],
-        token_program2022: accounts[9],
-        vault0_mint: accounts[10],
-        vault1_mint: accounts[11],
-        lp_mint: accounts[12],
-        memo_program: accounts[13],
+        owner: read_account(accounts, 0)?,
+        authority: read_account(accounts, 1)?,
+        pool_state: read_account(accounts, 2)?,
+        owner_lp_token: read_account(accounts, 3)?,
+        token0_account: read_account(accounts, 4)?,
+        token1_account: read_account(accounts, 5)?,
+        token0_vault: read_account(accounts, 6)?,
+        token1_vault: read_account(accounts, 7)?,
+        token_program: read_account(accounts, 8)?,
+        token_program2022: read_account(accounts, 9)?,
+        vault0_mint: read_account(accounts, 10)?,
+        vault1_mint: read_account(accounts, 11)?,
+        lp_mint: read_account(accounts, 12)?,
+        memo_program: read_account(accounts, 13)?,
     }))
 }
 
@@ -113,26 +118,26 @@ fn parse_initialize_instruction(
         init_amount0: read_u64_le(data, 0)?,
         init_amount1: read_u64_le(data, 8)?,
         open_time: read_u64_le(data, 16)?,
-        creator: accounts[0],
-        amm_config: accounts[1],
-        authority: accounts[2],
-        pool_state: accounts[3],
-        token0_mint: accounts[4],
-        token1_mint: accounts[5],
-        lp_mint: accounts[6],
-        creator_token0: accounts[7],
-        creator_token1: accounts[8],
-        creator_lp_token: accounts[9],
-        token0_vault: accounts[10],
-        token1_vault: accounts[11],
-        create_pool_fee: accounts[12],
-        observation_state: accounts[13],
-        token_program: accounts[14],
-        token0_program: accounts[15],
-        token1_program: accounts[16],
-        associated_token_program: accounts[17],
-        system_program: accounts[18],
-        rent: accounts[19],
+        creator: read_account(accounts, 0)?,
+        amm_config: read_account(accounts, 1)?,
+        authority: read_account(accounts, 2)?,
+        pool_state: read_account(accounts, 3)?,
+        token0_mint: read_account(accounts, 4)?,
+        token1_mint: read_account(accounts, 5)?,
+        lp_mint: read_account(accounts, 6)?,
+        creator_token0: read_account(accounts, 7)?,
+        creator_token1: read_account(accounts, 8)?,
+        creator_lp_token: read_account(accounts, 9)?,
+        token0_vault: read_account(accounts, 10)?,
+        token1_vault: read_account(accounts, 11)?,
+        create_pool_fee: read_account(accounts, 12)?,
+        observation_state: read_account(accounts, 13)?,
+        token_program: read_account(accounts, 14)?,
+        token0_program: read_account(accounts, 15)?,
+        token1_program: read_account(accounts, 16)?,
+        associated_token_program: read_account(accounts, 17)?,
+        system_program: read_account(accounts, 18)?,
+        rent: read_account(accounts, 19)?,
     }))
 }
 
@@ -150,19 +155,19 @@ fn parse_deposit_instruction(
         lp_token_amount: read_u64_le(data, 0)?,
         maximum_token0_amount: read_u64_le(data, 8)?,
         maximum_token1_amount: read_u64_le(data, 16)?,
-        owner: accounts[0],
-        authority: accounts[1],
-        pool_state: accounts[2],
-        owner_lp_token: accounts[3],
-        token0_account: accounts[4],
-        token1_account: accounts[5],
-        token0_vault: accounts[6],
-        token1_vault: accounts[7],
-        token_program: accounts[8],
-        token_program2022: accounts[9],
-        vault0_mint: accounts[10],
-        vault1_mint: accounts[11],
-        lp_mint: accounts[12],
+        owner: read_account(accounts, 0)?,
+        authority: read_account(accounts, 1)?,
+        pool_state: read_account(accounts, 2)?,
+        owner_lp_token: read_account(accounts, 3)?,
+        token0_account: read_account(accounts, 4)?,
+        token1_account: read_account(accounts, 5)?,
+        token0_vault: read_account(accounts, 6)?,
+        token1_vault: read_account(accounts, 7)?,
+        token_program: read_account(accounts, 8)?,
+        token_program2022: read_account(accounts, 9)?,
+        vault0_mint: read_account(accounts, 10)?,
+        vault1_mint: read_account(accounts, 11)?,
+        lp_mint: read_account(accounts, 12)?,
     }))
 }
 
@@ -183,19 +188,19 @@ fn parse_swap_base_input_instruction(
         metadata,
         amount_in,
         minimum_amount_out,
-        payer: accounts[0],
-        authority: accounts[1],
-        amm_config: accounts[2],
-        pool_state: accounts[3],
-        input_token_account: accounts[4],
-        output_token_account: accounts[5],
-        input_vault: accounts[6],
-        output_vault: accounts[7],
-        input_token_program: accounts[8],
-        output_token_program: accounts[9],
-        input_token_mint: accounts[10],
-        output_token_mint: accounts[11],
-        observation_state: accounts[12],
+        payer: read_account(accounts, 0)?,
+        authority: read_account(accounts, 1)?,
+        amm_config: read_account(accounts, 2)?,
+        pool_state: read_account(accounts, 3)?,
+        input_token_account: read_account(accounts, 4)?,
+        output_token_account: read_account(accounts, 5)?,
+        input_vault: read_account(accounts, 6)?,
+        output_vault: read_account(accounts, 7)?,
+        input_token_program: read_account(accounts, 8)?,
+        output_token_program: read_account(accounts, 9)?,
+        input_token_mint: read_account(accounts, 10)?,
+        output_token_mint: read_account(accounts, 11)?,
+        observation_state: read_account(accounts, 12)?,
         ..Default::default()
     }))
 }
@@ -216,19 +221,19 @@ fn parse_swap_base_output_instruction(
         metadata,
         max_amount_in,
         amount_out,
-        payer: accounts[0],
-        authority: accounts[1],
-        amm_config: accounts[2],
-        pool_state: accounts[3],
-        input_token_account: accounts[4],
-        output_token_account: accounts[5],
-        input_vault: accounts[6],
-        output_vault: accounts[7],
-        input_token_program: accounts[8],
-        output_token_program: accounts[9],
-        input_token_mint: accounts[10],
-        output_token_mint: accounts[11],
-        observation_state: accounts[12],
+        payer: read_account(accounts, 0)?,
+        authority: read_account(accounts, 1)?,
+        amm_config: read_account(accounts, 2)?,
+        pool_state: read_account(accounts, 3)?,
+        input_token_account: read_account(accounts, 4)?,
+        output_token_account: read_account(accounts, 5)?,
+        input_vault: read_account(accounts, 6)?,
+        output_vault: read_account(accounts, 7)?,
+        input_token_program: read_account(accounts, 8)?,
+        output_token_program: read_account(accounts, 9)?,
+        input_token_mint: read_account(accounts, 10)?,
+        output_token_mint: read_account(accounts, 11)?,
+        observation_state: read_account(accounts, 12)?,
         ..Default::default()
     }))
 }
@@ -28,6 +28,8 @@ pub struct AmmConfig {
 }
 
 pub const AMM_CONFIG_SIZE: usize = 228;
+/// 链上账户总长度（含 8 字节 discriminator），用于 datasize 过滤
+pub const AMM_CONFIG_ACCOUNT_SIZE: usize = AMM_CONFIG_SIZE + 8;
 
 pub fn amm_config_decode(data: &[u8]) -> Option<AmmConfig> {
     if data.len() < AMM_CONFIG_SIZE {
@@ -86,6 +88,7 @@ pub struct PoolState {
 }
 
 pub const POOL_STATE_SIZE: usize = 629;
+pub const POOL_STATE_ACCOUNT_SIZE: usize = POOL_STATE_SIZE + 8;
 
 pub fn pool_state_decode(data: &[u8]) -> Option<PoolState> {
     if data.len() < POOL_STATE_SIZE {
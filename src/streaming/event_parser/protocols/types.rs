@@ -1,12 +1,15 @@
-use crate::streaming::event_parser::protocols::{
-    raydium_amm_v4::parser::RAYDIUM_AMM_V4_PROGRAM_ID,
-    raydium_clmm::parser::RAYDIUM_CLMM_PROGRAM_ID, raydium_cpmm::parser::RAYDIUM_CPMM_PROGRAM_ID,
-};
+#[cfg(feature = "raydium-amm-v4")]
+use crate::streaming::event_parser::protocols::raydium_amm_v4::parser::RAYDIUM_AMM_V4_PROGRAM_ID;
+#[cfg(feature = "raydium-clmm")]
+use crate::streaming::event_parser::protocols::raydium_clmm::parser::RAYDIUM_CLMM_PROGRAM_ID;
+#[cfg(feature = "raydium-cpmm")]
+use crate::streaming::event_parser::protocols::raydium_cpmm::parser::RAYDIUM_CPMM_PROGRAM_ID;
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 
-/// 支持的协议
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// 支持的协议（变体始终存在，解析器由对应的 cargo feature 启用）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Protocol {
     RaydiumCpmm,
     RaydiumClmm,
@@ -14,11 +17,26 @@ pub enum Protocol {
 }
 
 impl Protocol {
+    /// Program ids of the protocol, empty when its feature is disabled
     pub fn get_program_id(&self) -> Vec<Pubkey> {
         match self {
+            #[cfg(feature = "raydium-cpmm")]
             Protocol::RaydiumCpmm => vec![RAYDIUM_CPMM_PROGRAM_ID],
+            #[cfg(feature = "raydium-clmm")]
             Protocol::RaydiumClmm => vec![RAYDIUM_CLMM_PROGRAM_ID],
+            #[cfg(feature = "raydium-amm-v4")]
             Protocol::RaydiumAmmV4 => vec![RAYDIUM_AMM_V4_PROGRAM_ID],
+            #[allow(unreachable_patterns)]
+            _ => vec![],
+        }
+    }
+
+    /// Whether the protocol's parser was compiled in
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            Protocol::RaydiumCpmm => cfg!(feature = "raydium-cpmm"),
+            Protocol::RaydiumClmm => cfg!(feature = "raydium-clmm"),
+            Protocol::RaydiumAmmV4 => cfg!(feature = "raydium-amm-v4"),
         }
     }
 }
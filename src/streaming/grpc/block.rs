@@ -0,0 +1,199 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use solana_sdk::pubkey::Pubkey;
+use yellowstone_grpc_proto::{
+    geyser::{
+        SubscribeRequest, SubscribeRequestFilterBlocks, SubscribeRequestFilterTransactions,
+        SubscribeUpdateBlock, SubscribeUpdateBlockMeta, SubscribeUpdateTransaction,
+        SubscribeUpdateTransactionInfo,
+    },
+    prost_types::Timestamp,
+};
+
+use super::pool::factory;
+use super::types::{BlockMetaPretty, TransactionPretty};
+
+/// Name of the single blocks filter of a block subscription
+pub const BLOCK_FILTER_NAME: &str = "client";
+
+/// Client-side half of a transaction filter
+///
+/// Blocks filters only know `account_include`, so vote, failed, excluded and
+/// non-required transactions are dropped here.
+#[derive(Debug, Clone)]
+struct BlockTransactionFilter {
+    name: String,
+    include: HashSet<Pubkey>,
+    exclude: HashSet<Pubkey>,
+    required: Vec<Pubkey>,
+}
+
+impl BlockTransactionFilter {
+    fn new(name: String, filter: &SubscribeRequestFilterTransactions) -> Self {
+        let parse = |keys: &[String]| keys.iter().filter_map(|k| Pubkey::from_str(k).ok());
+        Self {
+            name,
+            include: parse(&filter.account_include).collect(),
+            exclude: parse(&filter.account_exclude).collect(),
+            required: parse(&filter.account_required).collect(),
+        }
+    }
+
+    fn matches(&self, keys: &HashSet<Pubkey>) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|key| keys.contains(key)))
+            && !self.exclude.iter().any(|key| keys.contains(key))
+            && self.required.iter().all(|key| keys.contains(key))
+    }
+}
+
+/// Full-block subscription derived from the transaction filters of a request
+///
+/// The transaction filters are merged into one blocks filter, otherwise the node
+/// would send the same block once per filter. Which filters a transaction matched
+/// is recomputed locally, so per-filter protocol scopes keep working.
+#[derive(Debug, Clone)]
+pub struct BlockSubscription {
+    filters: Vec<BlockTransactionFilter>,
+}
+
+impl BlockSubscription {
+    /// Moves the request's transaction filters into a blocks filter
+    ///
+    /// Block meta comes with each block, so `blocks_meta` is cleared as well. Without
+    /// transaction filters no blocks are subscribed and None is returned.
+    pub fn from_request(request: &mut SubscribeRequest) -> Option<Self> {
+        if request.transactions.is_empty() {
+            request.blocks.clear();
+            return None;
+        }
+        let mut filters: Vec<BlockTransactionFilter> = request
+            .transactions
+            .drain()
+            .map(|(name, filter)| BlockTransactionFilter::new(name, &filter))
+            .collect();
+        filters.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // 任一过滤器不限制账户时，整个区块订阅也不能限制
+        let account_include = if filters.iter().any(|f| f.include.is_empty()) {
+            Vec::new()
+        } else {
+            let all: HashSet<&Pubkey> = filters.iter().flat_map(|f| &f.include).collect();
+            all.into_iter().map(|key| key.to_string()).collect()
+        };
+        request.blocks_meta.clear();
+        request.blocks.clear();
+        request.blocks.insert(
+            BLOCK_FILTER_NAME.to_string(),
+            SubscribeRequestFilterBlocks {
+                account_include,
+                include_transactions: Some(true),
+                include_accounts: Some(false),
+                include_entries: Some(false),
+            },
+        );
+        Some(Self { filters })
+    }
+
+    /// Splits a block into its meta and the matching transactions, in block order
+    pub fn split(
+        &self,
+        mut block: SubscribeUpdateBlock,
+        created_at: Option<Timestamp>,
+    ) -> (BlockMetaPretty, Vec<TransactionPretty>) {
+        let slot = block.slot;
+        let mut transactions = std::mem::take(&mut block.transactions);
+        transactions.sort_by_key(|tx| tx.index);
+        let transactions = transactions
+            .into_iter()
+            .filter_map(|tx| {
+                let filters = self.matching_filters(&tx)?;
+                let update = SubscribeUpdateTransaction { transaction: Some(tx), slot };
+                let mut pretty = factory::create_transaction_pretty_pooled(update, created_at);
+                pretty.filters = filters;
+                Some(pretty)
+            })
+            .collect();
+
+        let meta = SubscribeUpdateBlockMeta {
+            slot,
+            blockhash: block.blockhash,
+            rewards: block.rewards,
+            block_time: block.block_time,
+            block_height: block.block_height,
+            parent_slot: block.parent_slot,
+            parent_blockhash: block.parent_blockhash,
+            executed_transaction_count: block.executed_transaction_count,
+            entries_count: block.entries_count,
+        };
+        (factory::create_block_meta_pretty_pooled(meta, created_at), transactions)
+    }
+
+    /// Names of the filters the transaction matches, None if it is dropped
+    fn matching_filters(&self, tx: &SubscribeUpdateTransactionInfo) -> Option<Vec<String>> {
+        if tx.is_vote || tx.meta.as_ref().is_some_and(|meta| meta.err.is_some()) {
+            return None;
+        }
+        let message = tx.transaction.as_ref()?.message.as_ref()?;
+        let loaded = tx.meta.iter().flat_map(|meta| {
+            meta.loaded_writable_addresses.iter().chain(&meta.loaded_readonly_addresses)
+        });
+        let keys: HashSet<Pubkey> = message
+            .account_keys
+            .iter()
+            .chain(loaded)
+            .filter_map(|key| Pubkey::try_from(key.as_slice()).ok())
+            .collect();
+        let names: Vec<String> = self
+            .filters
+            .iter()
+            .filter(|filter| filter.matches(&keys))
+            .map(|filter| filter.name.clone())
+            .collect();
+        (!names.is_empty()).then_some(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maplit::hashmap;
+
+    #[test]
+    fn test_from_request_merges_filters() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let filter =
+            |include: Vec<String>, exclude: Vec<String>| SubscribeRequestFilterTransactions {
+                account_include: include,
+                account_exclude: exclude,
+                ..Default::default()
+            };
+        let mut request = SubscribeRequest {
+            transactions: hashmap! {
+                "client".to_string() => filter(vec![a.to_string()], vec![]),
+                "client_1".to_string() => filter(vec![b.to_string()], vec![a.to_string()]),
+            },
+            ..Default::default()
+        };
+        let subscription = BlockSubscription::from_request(&mut request).unwrap();
+        assert!(request.transactions.is_empty());
+        let mut include = request.blocks[BLOCK_FILTER_NAME].account_include.clone();
+        include.sort();
+        let mut expected = vec![a.to_string(), b.to_string()];
+        expected.sort();
+        assert_eq!(include, expected);
+
+        let keys = HashSet::from([a, b]);
+        let matched: Vec<&str> = subscription
+            .filters
+            .iter()
+            .filter(|f| f.matches(&keys))
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(matched, vec!["client"]);
+
+        assert!(BlockSubscription::from_request(&mut request).is_none());
+        assert!(request.blocks.is_empty());
+    }
+}
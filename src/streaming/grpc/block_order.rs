@@ -0,0 +1,79 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::types::TransactionPretty;
+
+/// Slots a transaction is held for while waiting for its block meta
+pub const DEFAULT_MAX_PENDING_SLOTS: u64 = 32;
+
+/// Holds transactions until their slot's block meta has been delivered
+///
+/// The node sends the block meta once the block is complete, i.e. after its
+/// transactions. With this buffer the block meta is processed first and the held
+/// transactions right after it. Slots whose block meta never arrives (skipped or
+/// forked off) are released once they fall `max_pending_slots` behind.
+pub struct BlockMetaOrdering {
+    max_pending_slots: u64,
+    pending: BTreeMap<u64, Vec<TransactionPretty>>,
+    delivered: BTreeSet<u64>,
+}
+
+impl Default for BlockMetaOrdering {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_PENDING_SLOTS)
+    }
+}
+
+impl BlockMetaOrdering {
+    pub fn new(max_pending_slots: u64) -> Self {
+        Self {
+            max_pending_slots: max_pending_slots.max(1),
+            pending: BTreeMap::new(),
+            delivered: BTreeSet::new(),
+        }
+    }
+
+    /// Holds the transaction unless its block meta was already delivered
+    ///
+    /// Returns the transactions to process now, including stale slots released
+    /// because the stream has moved past them.
+    pub fn on_transaction(&mut self, transaction: TransactionPretty) -> Vec<TransactionPretty> {
+        let slot = transaction.slot;
+        if self.delivered.contains(&slot) {
+            return vec![transaction];
+        }
+        self.pending.entry(slot).or_default().push(transaction);
+        self.release_stale(slot)
+    }
+
+    /// Marks the slot's block meta as delivered
+    ///
+    /// Call after the block meta has been processed; returns the transactions to
+    /// process next, the slot's own first, then any released stale slots.
+    pub fn on_block_meta(&mut self, slot: u64) -> Vec<TransactionPretty> {
+        self.delivered.insert(slot);
+        let mut ready = self.pending.remove(&slot).unwrap_or_default();
+        ready.extend(self.release_stale(slot));
+        ready
+    }
+
+    /// Number of transactions currently held
+    pub fn pending_len(&self) -> usize {
+        self.pending.values().map(Vec::len).sum()
+    }
+
+    fn release_stale(&mut self, latest_slot: u64) -> Vec<TransactionPretty> {
+        let horizon = latest_slot.saturating_sub(self.max_pending_slots);
+        let kept = self.pending.split_off(&horizon);
+        let mut released = Vec::new();
+        for (stale_slot, transactions) in std::mem::replace(&mut self.pending, kept) {
+            log::warn!(
+                "No block meta for slot {}, releasing {} held transactions",
+                stale_slot,
+                transactions.len()
+            );
+            released.extend(transactions);
+        }
+        self.delivered = self.delivered.split_off(&horizon);
+        released
+    }
+}
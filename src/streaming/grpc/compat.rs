@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use yellowstone_grpc_proto::geyser::SubscribeRequest;
+
+/// How subscription requests are adapted to the server's yellowstone-grpc version
+///
+/// Protobuf keeps field tags stable across renames, so updates from older or forked
+/// servers decode fine (unknown fields are skipped, missing ones are defaults). What
+/// breaks is the other direction: servers built before a request field existed reject
+/// or misread filters they do not know. `Legacy` strips those fields before sending.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtoCompat {
+    /// Ask the server with `GetVersion` on connect, `Legacy` for 1.x plugins (default)
+    #[default]
+    Auto,
+    /// Send requests as built
+    Latest,
+    /// Always strip request fields newer than the 1.x plugins
+    Legacy,
+}
+
+/// Version reported by a yellowstone-grpc server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerVersion {
+    /// Plugin package, e.g. `yellowstone-grpc-geyser`
+    pub package: Option<String>,
+    /// Plugin version, e.g. `1.14.1+solana.1.18.22`
+    pub version: String,
+}
+
+impl ServerVersion {
+    /// Parses the JSON returned by `GetVersion`, None for unrecognized formats
+    pub fn parse(raw: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(raw).ok()?;
+        let info = value.get("version")?;
+        Some(Self {
+            package: info.get("package").and_then(|p| p.as_str()).map(str::to_owned),
+            version: info.get("version")?.as_str()?.to_owned(),
+        })
+    }
+
+    pub fn major(&self) -> Option<u64> {
+        self.version.split(['.', '+', '-']).next()?.parse().ok()
+    }
+
+    /// 1.x plugins predate `from_slot`, `nonempty_txn_signature` and `interslot_updates`
+    pub fn is_legacy(&self) -> bool {
+        self.major().is_some_and(|major| major < 2)
+    }
+}
+
+/// Removes request fields unknown to 1.x plugins
+pub fn to_legacy_request(mut request: SubscribeRequest) -> SubscribeRequest {
+    request.from_slot = None;
+    for filter in request.accounts.values_mut() {
+        filter.nonempty_txn_signature = None;
+    }
+    for filter in request.slots.values_mut() {
+        filter.interslot_updates = None;
+    }
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yellowstone_grpc_proto::geyser::SubscribeRequestFilterAccounts;
+
+    #[test]
+    fn test_server_version_and_legacy_request() {
+        let raw = r#"{"version":{"package":"yellowstone-grpc-geyser","version":"1.14.1+solana.1.18.22","proto":"1.13.0"},"extra":{"hostname":"rpc"}}"#;
+        let version = ServerVersion::parse(raw).unwrap();
+        assert_eq!(version.major(), Some(1));
+        assert!(version.is_legacy());
+        assert!(!ServerVersion::parse(r#"{"version":{"version":"5.0.0"}}"#).unwrap().is_legacy());
+        assert_eq!(ServerVersion::parse("jito-fork"), None);
+
+        let mut request = SubscribeRequest { from_slot: Some(42), ..Default::default() };
+        request.accounts.insert(
+            "client".to_string(),
+            SubscribeRequestFilterAccounts {
+                nonempty_txn_signature: Some(true),
+                ..Default::default()
+            },
+        );
+        let request = to_legacy_request(request);
+        assert_eq!(request.from_slot, None);
+        assert_eq!(request.accounts["client"].nonempty_txn_signature, None);
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use tonic::metadata::{AsciiMetadataKey, AsciiMetadataValue};
+use tonic::{service::Interceptor, Request, Status};
+
+use crate::common::AnyResult;
+
+/// Per-request hook applied after the static headers, e.g. to sign requests
+#[derive(Clone)]
+pub struct RequestInterceptor(
+    pub Arc<dyn Fn(Request<()>) -> Result<Request<()>, Status> + Send + Sync>,
+);
+
+impl RequestInterceptor {
+    pub fn new(
+        f: impl Fn(Request<()>) -> Result<Request<()>, Status> + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+impl Debug for RequestInterceptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RequestInterceptor")
+    }
+}
+
+/// Interceptor attaching the x-token, custom metadata headers and user hooks to every request
+#[derive(Clone)]
+pub struct StreamInterceptor {
+    headers: Vec<(AsciiMetadataKey, AsciiMetadataValue)>,
+    interceptors: Vec<RequestInterceptor>,
+}
+
+impl StreamInterceptor {
+    /// Validates the header names/values up front so a typo fails at connect time
+    pub fn new(
+        x_token: Option<String>,
+        headers: &[(String, String)],
+        interceptors: &[RequestInterceptor],
+    ) -> AnyResult<Self> {
+        let mut parsed = Vec::with_capacity(headers.len() + 1);
+        if let Some(token) = x_token {
+            parsed.push(Self::parse_header("x-token", &token)?);
+        }
+        for (key, value) in headers {
+            parsed.push(Self::parse_header(key, value)?);
+        }
+        Ok(Self { headers: parsed, interceptors: interceptors.to_vec() })
+    }
+
+    fn parse_header(key: &str, value: &str) -> AnyResult<(AsciiMetadataKey, AsciiMetadataValue)> {
+        let key = AsciiMetadataKey::from_bytes(key.to_lowercase().as_bytes())
+            .map_err(|e| anyhow!("invalid metadata header name {}: {}", key, e))?;
+        let value = AsciiMetadataValue::try_from(value)
+            .map_err(|e| anyhow!("invalid metadata header value for {}: {}", key, e))?;
+        Ok((key, value))
+    }
+}
+
+impl Interceptor for StreamInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        for (key, value) in &self.headers {
+            request.metadata_mut().insert(key.clone(), value.clone());
+        }
+        for interceptor in &self.interceptors {
+            request = (interceptor.0)(request)?;
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_interceptor_sets_metadata() {
+        let headers = [("X-Client-Id".to_string(), "tests".to_string())];
+        let mut interceptor =
+            StreamInterceptor::new(Some("secret".to_string()), &headers, &[]).unwrap();
+        let request = interceptor.call(Request::new(())).unwrap();
+        assert_eq!(request.metadata().get("x-token").unwrap(), "secret");
+        assert_eq!(request.metadata().get("x-client-id").unwrap(), "tests");
+
+        let invalid = [("bad header".to_string(), "value".to_string())];
+        assert!(StreamInterceptor::new(None, &invalid, &[]).is_err());
+    }
+}
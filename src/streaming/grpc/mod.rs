@@ -1,16 +1,33 @@
-// gRPC 相关模块
+// gRPC 相关模块，connection / interceptor / subscription 需要 `transport` feature
+pub mod block;
+pub mod block_order;
+pub mod compat;
+#[cfg(feature = "transport")]
 pub mod connection;
+#[cfg(feature = "transport")]
+pub mod interceptor;
+pub mod plan;
 pub mod pool;
+#[cfg(feature = "transport")]
 pub mod subscription;
 pub mod types;
 
 // 重新导出主要类型
+pub use block::*;
+pub use block_order::*;
+pub use compat::*;
+#[cfg(feature = "transport")]
 pub use connection::*;
+#[cfg(feature = "transport")]
+pub use interceptor::*;
+pub use plan::*;
 pub use pool::*;
+#[cfg(feature = "transport")]
 pub use subscription::*;
 pub use types::*;
 
 // 从公用模块重新导出
+#[cfg(feature = "transport")]
 pub use crate::streaming::common::{
     BackpressureConfig, BackpressureStrategy, ConnectionConfig, MetricsManager, PerformanceMetrics,
     StreamClientConfig as ClientConfig,
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use yellowstone_grpc_proto::geyser::{
+    subscribe_request_filter_accounts_filter::Filter,
+    subscribe_request_filter_accounts_filter_memcmp::Data, CommitmentLevel, SubscribeRequest,
+    SubscribeRequestFilterAccountsFilter,
+};
+
+use super::compat::{ProtoCompat, ServerVersion};
+
+/// The exact `SubscribeRequest` a subscription would send, for debugging empty streams
+///
+/// Built by `YellowstoneGrpc::plan_subscription` from the same filters as
+/// `subscribe_events_immediate`, without connecting.
+#[derive(Debug, Clone)]
+pub struct SubscriptionPlan {
+    pub request: SubscribeRequest,
+}
+
+impl SubscriptionPlan {
+    pub fn new(request: SubscribeRequest) -> Self {
+        Self { request }
+    }
+
+    /// JSON view of the request: filters by name, memcmp filters decoded, commitment by name
+    pub fn describe(&self) -> Value {
+        let request = &self.request;
+        let transactions: serde_json::Map<String, Value> = request
+            .transactions
+            .iter()
+            .map(|(name, f)| {
+                let filter = json!({
+                    "vote": f.vote,
+                    "failed": f.failed,
+                    "signature": f.signature,
+                    "account_include": f.account_include,
+                    "account_exclude": f.account_exclude,
+                    "account_required": f.account_required,
+                });
+                (name.clone(), filter)
+            })
+            .collect();
+        let accounts: serde_json::Map<String, Value> = request
+            .accounts
+            .iter()
+            .map(|(name, f)| {
+                let filter = json!({
+                    "account": f.account,
+                    "owner": f.owner,
+                    "filters": f.filters.iter().map(describe_account_filter).collect::<Vec<_>>(),
+                    "nonempty_txn_signature": f.nonempty_txn_signature,
+                });
+                (name.clone(), filter)
+            })
+            .collect();
+        let blocks: serde_json::Map<String, Value> = request
+            .blocks
+            .iter()
+            .map(|(name, f)| {
+                let filter = json!({
+                    "account_include": f.account_include,
+                    "include_transactions": f.include_transactions,
+                    "include_accounts": f.include_accounts,
+                    "include_entries": f.include_entries,
+                });
+                (name.clone(), filter)
+            })
+            .collect();
+        let commitment = request.commitment.map(|level| {
+            CommitmentLevel::try_from(level)
+                .map(|level| level.as_str_name().to_string())
+                .unwrap_or_else(|_| level.to_string())
+        });
+        json!({
+            "commitment": commitment,
+            "transactions": transactions,
+            "accounts": accounts,
+            "blocks": blocks,
+            "blocks_meta": request.blocks_meta.keys().collect::<Vec<_>>(),
+            "slots": request.slots.keys().collect::<Vec<_>>(),
+            "entry": request.entry.keys().collect::<Vec<_>>(),
+            "from_slot": request.from_slot,
+        })
+    }
+
+    /// Problems visible without a server: invalid pubkeys, filters matching nothing or everything
+    pub fn warnings(&self) -> Vec<String> {
+        let request = &self.request;
+        let mut warnings = Vec::new();
+        let mut check_keys = |filter: &str, field: &str, keys: &[String]| {
+            for key in keys.iter().filter(|key| Pubkey::from_str(key).is_err()) {
+                warnings.push(format!("{filter}: invalid pubkey {key:?} in {field}"));
+            }
+        };
+        for (name, f) in &request.transactions {
+            check_keys(name, "account_include", &f.account_include);
+            check_keys(name, "account_exclude", &f.account_exclude);
+            check_keys(name, "account_required", &f.account_required);
+        }
+        for (name, f) in &request.accounts {
+            check_keys(name, "account", &f.account);
+            check_keys(name, "owner", &f.owner);
+        }
+        for (name, f) in &request.blocks {
+            check_keys(name, "account_include", &f.account_include);
+        }
+        for (name, f) in &request.transactions {
+            if f.account_include.is_empty()
+                && f.account_required.is_empty()
+                && f.signature.is_none()
+            {
+                warnings.push(format!("{name}: no account_include, matches every transaction"));
+            }
+        }
+        for (name, f) in &request.accounts {
+            if f.account.is_empty() && f.owner.is_empty() && f.filters.is_empty() {
+                warnings
+                    .push(format!("{name}: no account, owner or filters, matches every account"));
+            }
+        }
+        if request.transactions.is_empty()
+            && request.accounts.is_empty()
+            && request.blocks.is_empty()
+            && request.entry.is_empty()
+        {
+            warnings.push("no transaction, account or block filters, no events will arrive".into());
+        }
+        warnings
+    }
+}
+
+impl fmt::Display for SubscriptionPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe = serde_json::to_string_pretty(&self.describe()).map_err(|_| fmt::Error)?;
+        write!(f, "{describe}")
+    }
+}
+
+fn describe_account_filter(filter: &SubscribeRequestFilterAccountsFilter) -> Value {
+    match &filter.filter {
+        Some(Filter::Memcmp(memcmp)) => {
+            let data = match &memcmp.data {
+                Some(Data::Bytes(bytes)) => json!({ "bytes": bytes }),
+                Some(Data::Base58(data)) => json!({ "base58": data }),
+                Some(Data::Base64(data)) => json!({ "base64": data }),
+                None => Value::Null,
+            };
+            json!({ "memcmp": { "offset": memcmp.offset, "data": data } })
+        }
+        Some(Filter::Datasize(size)) => json!({ "datasize": size }),
+        Some(other) => json!({ "other": format!("{other:?}") }),
+        None => Value::Null,
+    }
+}
+
+/// Result of validating a [`SubscriptionPlan`] against an endpoint without subscribing
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunReport {
+    /// Raw `GetVersion` response, None if the call failed
+    pub server_version: Option<String>,
+    /// Whether requests will be sent in legacy form (see `ProtoCompat`)
+    pub legacy_requests: bool,
+    pub warnings: Vec<String>,
+    pub request: Value,
+}
+
+impl DryRunReport {
+    pub(crate) fn new(
+        plan: &SubscriptionPlan,
+        server_version: Option<String>,
+        compat: ProtoCompat,
+    ) -> Self {
+        let mut warnings = plan.warnings();
+        let legacy_server = server_version
+            .as_deref()
+            .and_then(ServerVersion::parse)
+            .is_some_and(|version| version.is_legacy());
+        let legacy_requests = match compat {
+            ProtoCompat::Auto => legacy_server,
+            ProtoCompat::Latest => false,
+            ProtoCompat::Legacy => true,
+        };
+        if legacy_requests && plan.request.from_slot.is_some() {
+            warnings.push("legacy requests drop from_slot".into());
+        }
+        if legacy_server && compat == ProtoCompat::Latest {
+            warnings.push("server is 1.x but proto_compat is Latest".into());
+        }
+        Self { server_version, legacy_requests, warnings, request: plan.describe() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yellowstone_grpc_proto::geyser::SubscribeRequestFilterTransactions;
+
+    #[test]
+    fn test_plan_describe_and_warnings() {
+        let program = Pubkey::new_unique();
+        let mut request = SubscribeRequest {
+            commitment: Some(CommitmentLevel::Confirmed as i32),
+            ..Default::default()
+        };
+        request.transactions.insert(
+            "client".to_string(),
+            SubscribeRequestFilterTransactions {
+                account_include: vec![program.to_string(), "not-a-key".to_string()],
+                ..Default::default()
+            },
+        );
+        let plan = SubscriptionPlan::new(request);
+        let describe = plan.describe();
+        assert_eq!(describe["commitment"], "CONFIRMED");
+        assert_eq!(describe["transactions"]["client"]["account_include"][0], program.to_string());
+        let warnings = plan.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("not-a-key"));
+
+        assert_eq!(SubscriptionPlan::new(SubscribeRequest::default()).warnings().len(), 1);
+    }
+}
@@ -1,12 +1,15 @@
 use super::types::{AccountPretty, BlockMetaPretty, TransactionPretty};
 use crate::streaming::event_parser::common::high_performance_clock::get_high_perf_clock;
+use crate::streaming::event_parser::protocols::block::block_meta_event::LeaderReward;
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use std::collections::VecDeque;
 use std::ops::DerefMut;
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use yellowstone_grpc_proto::{
     geyser::{SubscribeUpdateAccount, SubscribeUpdateBlockMeta, SubscribeUpdateTransaction},
     prost_types::Timestamp,
+    solana::storage::confirmed_block::RewardType,
 };
 
 /// 通用对象池特征
@@ -195,6 +198,21 @@ impl PooledBlockMetaPretty {
         self.block_meta.slot = block_update.slot;
         self.block_meta.block_hash = block_update.blockhash;
         self.block_meta.block_time = block_time;
+        self.block_meta.parent_slot = block_update.parent_slot;
+        self.block_meta.parent_block_hash = block_update.parent_blockhash;
+        self.block_meta.block_height = block_update.block_height.map(|h| h.block_height);
+        self.block_meta.executed_transaction_count = block_update.executed_transaction_count;
+        // 只保留出块者的手续费奖励，纪元边界的质押奖励可能非常多
+        self.block_meta.leader_reward = block_update.rewards.and_then(|rewards| {
+            rewards.rewards.into_iter().find(|r| r.reward_type == RewardType::Fee as i32).map(|r| {
+                LeaderReward {
+                    pubkey: Pubkey::from_str(&r.pubkey).unwrap_or_default(),
+                    lamports: r.lamports,
+                    post_balance: r.post_balance,
+                    commission: r.commission.parse().ok(),
+                }
+            })
+        });
         self.block_meta.recv_us = get_high_perf_clock();
     }
 }
@@ -206,6 +224,8 @@ impl Drop for PooledBlockMetaPretty {
             // 清理数据
             self.block_meta.block_hash.clear();
             self.block_meta.block_time = None;
+            self.block_meta.parent_block_hash.clear();
+            self.block_meta.leader_reward = None;
             pool.push_back(std::mem::take(&mut self.block_meta));
         }
     }
@@ -1,18 +1,37 @@
 use futures::{channel::mpsc, sink::Sink, Stream};
 use maplit::hashmap;
-use std::{collections::HashMap, time::Duration};
-use tonic::{transport::channel::ClientTlsConfig, Status};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tonic::{
+    transport::{channel::ClientTlsConfig, Endpoint},
+    Status,
+};
+use tonic_health::pb::health_client::HealthClient;
 use yellowstone_grpc_client::{GeyserGrpcClient, Interceptor};
+use yellowstone_grpc_proto::geyser::geyser_client::GeyserClient;
 use yellowstone_grpc_proto::geyser::{
-    CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccounts,
+    subscribe_request_filter_accounts_filter::Filter, CommitmentLevel, SubscribeRequest,
+    SubscribeRequestFilterAccounts, SubscribeRequestFilterAccountsFilter,
     SubscribeRequestFilterBlocksMeta, SubscribeRequestFilterTransactions, SubscribeUpdate,
 };
 
+use super::compat::{to_legacy_request, ProtoCompat, ServerVersion};
+use super::interceptor::StreamInterceptor;
 use super::types::AccountsFilterMap;
 use super::types::TransactionsFilterMap;
 use crate::common::AnyResult;
 use crate::streaming::common::StreamClientConfig as ClientConfig;
+use crate::streaming::common::{SharedCredentialProvider, StaticCredentialProvider};
 use crate::streaming::event_parser::common::filter::EventTypeFilter;
+use crate::streaming::event_parser::core::account_event_parser::AccountEventParser;
+use crate::streaming::event_parser::Protocol;
 use crate::streaming::yellowstone_grpc::AccountFilter;
 use crate::streaming::yellowstone_grpc::TransactionFilter;
 
@@ -20,25 +39,53 @@ use crate::streaming::yellowstone_grpc::TransactionFilter;
 #[derive(Clone)]
 pub struct SubscriptionManager {
     endpoint: String,
-    x_token: Option<String>,
+    credentials: SharedCredentialProvider,
     config: ClientConfig,
+    /// `ProtoCompat::Auto` 探测到的服务端是否为 1.x
+    legacy_server: Arc<AtomicBool>,
 }
 
 impl SubscriptionManager {
     /// Create a new subscription manager
     pub fn new(endpoint: String, x_token: Option<String>, config: ClientConfig) -> Self {
-        Self { endpoint, x_token, config }
+        Self::new_with_credentials(
+            endpoint,
+            Arc::new(StaticCredentialProvider::new(x_token)),
+            config,
+        )
+    }
+
+    /// Create a subscription manager whose x-token is resolved on every connect
+    pub fn new_with_credentials(
+        endpoint: String,
+        credentials: SharedCredentialProvider,
+        config: ClientConfig,
+    ) -> Self {
+        Self { endpoint, credentials, config, legacy_server: Arc::new(AtomicBool::new(false)) }
     }
 
     /// Create gRPC connection
+    ///
+    /// The channel is built by hand rather than through `GeyserGrpcClient::build_from_shared`
+    /// so that custom metadata headers and interceptors from the connection config can be
+    /// attached alongside the x-token.
     pub async fn connect(&self) -> AnyResult<GeyserGrpcClient<impl Interceptor>> {
-        let builder = GeyserGrpcClient::build_from_shared(self.endpoint.clone())?
-            .x_token(self.x_token.clone())?
+        let connection = &self.config.connection;
+        let interceptor = StreamInterceptor::new(
+            self.credentials.token()?,
+            &connection.metadata_headers,
+            &connection.interceptors,
+        )?;
+        let channel = Endpoint::from_shared(self.endpoint.clone())?
             .tls_config(ClientTlsConfig::new().with_native_roots())?
-            .max_decoding_message_size(self.config.connection.max_decoding_message_size)
-            .connect_timeout(Duration::from_secs(self.config.connection.connect_timeout))
-            .timeout(Duration::from_secs(self.config.connection.request_timeout));
-        Ok(builder.connect().await?)
+            .connect_timeout(Duration::from_secs(connection.connect_timeout))
+            .timeout(Duration::from_secs(connection.request_timeout))
+            .connect()
+            .await?;
+        let health = HealthClient::with_interceptor(channel.clone(), interceptor.clone());
+        let geyser = GeyserClient::with_interceptor(channel, interceptor)
+            .max_decoding_message_size(connection.max_decoding_message_size);
+        Ok(GeyserGrpcClient::new(health, geyser))
     }
 
     /// Create subscription request and return stream
@@ -53,6 +100,20 @@ impl SubscriptionManager {
         impl Stream<Item = Result<SubscribeUpdate, Status>>,
         SubscribeRequest,
     )> {
+        let subscribe_request =
+            self.build_subscribe_request(transactions, accounts, commitment, event_type_filter);
+        let (sink, stream) = self.subscribe(subscribe_request.clone()).await?;
+        Ok((sink, stream, subscribe_request))
+    }
+
+    /// Build the subscription request without connecting
+    pub fn build_subscribe_request(
+        &self,
+        transactions: Option<TransactionsFilterMap>,
+        accounts: Option<AccountsFilterMap>,
+        commitment: Option<CommitmentLevel>,
+        event_type_filter: Option<&EventTypeFilter>,
+    ) -> SubscribeRequest {
         let blocks_meta =
             if event_type_filter.is_some() && event_type_filter.unwrap().include_block_event() {
                 hashmap! { "".to_owned() => SubscribeRequestFilterBlocksMeta {} }
@@ -61,7 +122,7 @@ impl SubscriptionManager {
             } else {
                 hashmap! {}
             };
-        let subscribe_request = SubscribeRequest {
+        SubscribeRequest {
             accounts: accounts.unwrap_or_default(),
             transactions: transactions.unwrap_or_default(),
             blocks_meta,
@@ -71,10 +132,53 @@ impl SubscriptionManager {
                 Some(CommitmentLevel::Processed.into())
             },
             ..Default::default()
-        };
+        }
+    }
+
+    /// Connect and subscribe with a prepared request
+    pub async fn subscribe(
+        &self,
+        subscribe_request: SubscribeRequest,
+    ) -> AnyResult<(
+        impl Sink<SubscribeRequest, Error = mpsc::SendError>,
+        impl Stream<Item = Result<SubscribeUpdate, Status>>,
+    )> {
         let mut client = self.connect().await?;
-        let (sink, stream) = client.subscribe_with_request(Some(subscribe_request.clone())).await?;
-        Ok((sink, stream, subscribe_request))
+        if self.config.connection.proto_compat == ProtoCompat::Auto {
+            let version = match client.get_version().await {
+                Ok(response) => ServerVersion::parse(&response.version),
+                Err(e) => {
+                    log::debug!("GetVersion failed, assuming a current server: {}", e);
+                    None
+                }
+            };
+            let legacy = version.as_ref().is_some_and(ServerVersion::is_legacy);
+            let was_legacy = self.legacy_server.swap(legacy, Ordering::Relaxed);
+            if legacy && !was_legacy {
+                log::info!("Server runs yellowstone-grpc {:?}, using legacy requests", version);
+            }
+        }
+        let subscribe_request = self.adapt_request(subscribe_request);
+        Ok(client.subscribe_with_request(Some(subscribe_request)).await?)
+    }
+
+    /// Raw `GetVersion` response of the endpoint
+    pub async fn server_version(&self) -> AnyResult<String> {
+        Ok(self.connect().await?.get_version().await?.version)
+    }
+
+    /// Adapts a request to the server version, see [`ProtoCompat`]
+    pub fn adapt_request(&self, request: SubscribeRequest) -> SubscribeRequest {
+        let legacy = match self.config.connection.proto_compat {
+            ProtoCompat::Auto => self.legacy_server.load(Ordering::Relaxed),
+            ProtoCompat::Latest => false,
+            ProtoCompat::Legacy => true,
+        };
+        if legacy {
+            to_legacy_request(request)
+        } else {
+            request
+        }
     }
 
     /// Create account subscription request and return stream
@@ -91,19 +195,56 @@ impl SubscriptionManager {
         }
         let mut accounts = HashMap::new();
         for af in account_filter {
-            accounts.insert(
-                "".to_owned(),
-                SubscribeRequestFilterAccounts {
-                    account: af.account,
-                    owner: af.owner,
-                    filters: af.filters,
-                    nonempty_txn_signature: None,
-                },
-            );
+            for filters in self.account_filter_variants(&af, event_type_filter) {
+                accounts.insert(
+                    account_filter_name(accounts.len()),
+                    SubscribeRequestFilterAccounts {
+                        account: af.account.clone(),
+                        owner: af.owner.clone(),
+                        filters,
+                        nonempty_txn_signature: None,
+                    },
+                );
+            }
         }
         Some(accounts)
     }
 
+    /// Server-side filters of one account filter, one entry per resulting request filter
+    ///
+    /// A filter selecting only a known program's accounts is split by the data sizes of
+    /// the account kinds parsed for it (see `StreamClientConfig::account_size_filters`).
+    fn account_filter_variants(
+        &self,
+        af: &AccountFilter,
+        event_type_filter: Option<&EventTypeFilter>,
+    ) -> Vec<Vec<SubscribeRequestFilterAccountsFilter>> {
+        let owner = match af.owner.as_slice() {
+            [owner]
+                if self.config.account_size_filters
+                    && af.account.is_empty()
+                    && af.filters.is_empty() =>
+            {
+                owner.parse::<Pubkey>().ok()
+            }
+            _ => None,
+        };
+        let sizes = owner
+            .map(|owner| AccountEventParser::account_data_sizes(&owner, event_type_filter))
+            .unwrap_or_default();
+        if sizes.is_empty() {
+            return vec![af.filters.clone()];
+        }
+        sizes
+            .into_iter()
+            .map(|size| {
+                vec![SubscribeRequestFilterAccountsFilter {
+                    filter: Some(Filter::Datasize(size as u64)),
+                }]
+            })
+            .collect()
+    }
+
     /// Generate subscription request filter
     pub fn get_subscribe_request_filter(
         &self,
@@ -114,9 +255,9 @@ impl SubscriptionManager {
             return None;
         }
         let mut transactions = HashMap::new();
-        for tf in transaction_filter {
+        for (index, tf) in transaction_filter.into_iter().enumerate() {
             transactions.insert(
-                "client".to_string(),
+                transaction_filter_name(index),
                 SubscribeRequestFilterTransactions {
                     vote: Some(false),
                     failed: Some(false),
@@ -130,8 +271,132 @@ impl SubscriptionManager {
         Some(transactions)
     }
 
+    /// Protocol scopes of the transaction filters, keyed by filter name
+    ///
+    /// Filters without protocols are left out and parsed with the full protocol set.
+    pub fn get_transaction_filter_scopes(
+        transaction_filter: &[TransactionFilter],
+    ) -> HashMap<String, Vec<Protocol>> {
+        transaction_filter
+            .iter()
+            .enumerate()
+            .filter(|(_, tf)| !tf.protocols.is_empty())
+            .map(|(index, tf)| (transaction_filter_name(index), tf.protocols.clone()))
+            .collect()
+    }
+
+    /// Required signers of the transaction filters, keyed by filter name
+    ///
+    /// Filters without signers are left out and accept any transaction.
+    pub fn get_transaction_filter_signers(
+        transaction_filter: &[TransactionFilter],
+    ) -> HashMap<String, Vec<Pubkey>> {
+        transaction_filter
+            .iter()
+            .enumerate()
+            .filter(|(_, tf)| !tf.signers.is_empty())
+            .map(|(index, tf)| {
+                let signers = tf.signers.iter().filter_map(|signer| signer.parse().ok()).collect();
+                (transaction_filter_name(index), signers)
+            })
+            .collect()
+    }
+
     /// Get configuration
     pub fn get_config(&self) -> &ClientConfig {
         &self.config
     }
 }
+
+/// Name of the n-th transaction filter in the subscribe request
+///
+/// Each filter needs its own key, otherwise later filters replace earlier ones;
+/// the name also comes back in `SubscribeUpdate::filters` to tell which filter matched.
+pub fn transaction_filter_name(index: usize) -> String {
+    if index == 0 {
+        "client".to_string()
+    } else {
+        format!("client_{}", index)
+    }
+}
+
+/// Name of the n-th account filter in the subscribe request
+pub fn account_filter_name(index: usize) -> String {
+    format!("account_{}", index)
+}
+
+#[cfg(all(test, feature = "raydium-clmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::EventType;
+    use crate::streaming::event_parser::protocols::raydium_clmm::parser::RAYDIUM_CLMM_PROGRAM_ID;
+
+    fn manager(account_size_filters: bool) -> SubscriptionManager {
+        let config = ClientConfig { account_size_filters, ..Default::default() };
+        SubscriptionManager::new("http://127.0.0.1:10000".to_string(), None, config)
+    }
+
+    fn owner_filter() -> AccountFilter {
+        AccountFilter {
+            account: vec![],
+            owner: vec![RAYDIUM_CLMM_PROGRAM_ID.to_string()],
+            filters: vec![],
+        }
+    }
+
+    fn datasize(filter: &SubscribeRequestFilterAccounts) -> Option<u64> {
+        match filter.filters.as_slice() {
+            [SubscribeRequestFilterAccountsFilter { filter: Some(Filter::Datasize(size)) }] => {
+                Some(*size)
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_owner_only_filter_by_default() {
+        let wallet = Pubkey::new_unique().to_string();
+        let filters = vec![
+            owner_filter(),
+            AccountFilter { account: vec![wallet.clone()], owner: vec![], filters: vec![] },
+        ];
+        let accounts = manager(false).subscribe_with_account_request(filters, None).unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert!(accounts["account_0"].filters.is_empty());
+        assert_eq!(accounts["account_0"].owner, [RAYDIUM_CLMM_PROGRAM_ID.to_string()]);
+        assert_eq!(accounts["account_1"].account, [wallet]);
+    }
+
+    #[test]
+    fn test_account_size_filters_split_owner_filter() {
+        let wallet = Pubkey::new_unique().to_string();
+        let filters = vec![
+            owner_filter(),
+            AccountFilter { account: vec![wallet.clone()], owner: vec![], filters: vec![] },
+        ];
+        let accounts = manager(true).subscribe_with_account_request(filters, None).unwrap();
+        // 每种账户大小一个过滤器，后续过滤器的名称顺延
+        let sizes: Vec<_> = (0..3).map(|i| datasize(&accounts[&account_filter_name(i)])).collect();
+        assert_eq!(sizes, [Some(117), Some(1544), Some(10240)]);
+        assert_eq!(accounts["account_3"].account, [wallet]);
+        assert!(accounts["account_3"].filters.is_empty());
+
+        // 事件类型过滤只保留对应的账户大小
+        let event_types = EventTypeFilter { include: vec![EventType::AccountRaydiumClmmPoolState] };
+        let accounts = manager(true)
+            .subscribe_with_account_request(vec![owner_filter()], Some(&event_types))
+            .unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(datasize(&accounts["account_0"]), Some(1544));
+
+        // 未知程序或带自定义过滤器时保持原样
+        let unknown = AccountFilter {
+            account: vec![],
+            owner: vec![Pubkey::new_unique().to_string()],
+            filters: vec![],
+        };
+        let accounts = manager(true).subscribe_with_account_request(vec![unknown], None).unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert!(accounts["account_0"].filters.is_empty());
+    }
+}
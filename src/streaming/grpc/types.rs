@@ -1,3 +1,4 @@
+use crate::streaming::event_parser::protocols::block::block_meta_event::LeaderReward;
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use std::{collections::HashMap, fmt};
 use yellowstone_grpc_proto::{
@@ -51,6 +52,11 @@ pub struct BlockMetaPretty {
     pub slot: u64,
     pub block_hash: String,
     pub block_time: Option<Timestamp>,
+    pub parent_slot: u64,
+    pub parent_block_hash: String,
+    pub block_height: Option<u64>,
+    pub executed_transaction_count: u64,
+    pub leader_reward: Option<LeaderReward>,
     pub recv_us: i64,
 }
 
@@ -60,6 +66,9 @@ impl fmt::Debug for BlockMetaPretty {
             .field("slot", &self.slot)
             .field("block_hash", &self.block_hash)
             .field("block_time", &self.block_time)
+            .field("parent_slot", &self.parent_slot)
+            .field("block_height", &self.block_height)
+            .field("executed_transaction_count", &self.executed_transaction_count)
             .field("recv_us", &self.recv_us)
             .finish()
     }
@@ -74,6 +83,8 @@ pub struct TransactionPretty {
     pub signature: Signature,
     pub is_vote: bool,
     pub recv_us: i64,
+    /// 命中的订阅过滤器名称
+    pub filters: Vec<String>,
     pub grpc_tx: SubscribeUpdateTransactionInfo,
 }
 
@@ -85,6 +96,7 @@ impl fmt::Debug for TransactionPretty {
             .field("signature", &self.signature)
             .field("is_vote", &self.is_vote)
             .field("recv_us", &self.recv_us)
+            .field("filters", &self.filters)
             .finish()
     }
 }
@@ -98,6 +110,7 @@ impl Default for TransactionPretty {
             block_time: None,
             signature: Signature::default(),
             is_vote: false,
+            filters: Vec::new(),
             grpc_tx: SubscribeUpdateTransactionInfo::default(),
             recv_us: 0,
         }
@@ -1,11 +1,26 @@
+#[cfg(feature = "admin")]
+pub mod admin;
+pub mod analytics;
+#[cfg(feature = "archive")]
+pub mod archive;
 pub mod common;
 pub mod event_parser;
 pub mod grpc;
+#[cfg(feature = "transport")]
 pub mod shred;
+#[cfg(feature = "transport")]
 pub mod shred_stream;
+pub mod sink;
+pub mod store;
+pub mod util;
+#[cfg(feature = "transport")]
 pub mod yellowstone_grpc;
+#[cfg(feature = "transport")]
 pub mod yellowstone_sub_system;
 
+#[cfg(feature = "transport")]
 pub use shred::ShredStreamGrpc;
+#[cfg(feature = "transport")]
 pub use yellowstone_grpc::YellowstoneGrpc;
+#[cfg(feature = "transport")]
 pub use yellowstone_sub_system::{SystemEvent, TransferInfo};
@@ -6,17 +6,27 @@ use tonic::transport::Channel;
 use crate::common::AnyResult;
 use crate::protos::shredstream::shredstream_proxy_client::ShredstreamProxyClient;
 use crate::streaming::common::{
-    MetricsManager, PerformanceMetrics, StreamClientConfig, SubscriptionHandle,
+    MetricsManager, PerformanceMetrics, SharedCredentialProvider, StaticCredentialProvider,
+    StreamClientConfig, SubscriptionHandle,
 };
+use crate::streaming::event_parser::core::global_state::GlobalState;
+use crate::streaming::event_parser::core::ProtocolSwitches;
+use crate::streaming::event_parser::Protocol;
 
 /// ShredStream gRPC 客户端
 #[derive(Clone)]
 pub struct ShredStreamGrpc {
     pub shredstream_client: Arc<ShredstreamProxyClient<Channel>>,
     pub config: StreamClientConfig,
+    /// 每次订阅时读取，作为 x-token 发送
+    pub credentials: SharedCredentialProvider,
     pub metrics: Arc<RwLock<PerformanceMetrics>>,
     pub metrics_manager: MetricsManager,
     pub subscription_handle: Arc<Mutex<Option<SubscriptionHandle>>>,
+    /// 本客户端的开发者地址状态，重新订阅时保留
+    pub global_state: Arc<GlobalState>,
+    /// 运行时协议暂停开关，重新订阅时保留
+    pub protocol_switches: Arc<ProtocolSwitches>,
 }
 
 impl ShredStreamGrpc {
@@ -27,6 +37,19 @@ impl ShredStreamGrpc {
 
     /// 创建客户端，使用自定义配置
     pub async fn new_with_config(endpoint: String, config: StreamClientConfig) -> AnyResult<Self> {
+        Self::new_with_credentials(endpoint, Arc::new(StaticCredentialProvider::new(None)), config)
+            .await
+    }
+
+    /// Creates a client whose x-token comes from `credentials`
+    ///
+    /// The token is read on every `shredstream_subscribe`, so rotated secrets take
+    /// effect on the next resubscribe.
+    pub async fn new_with_credentials(
+        endpoint: String,
+        credentials: SharedCredentialProvider,
+        config: StreamClientConfig,
+    ) -> AnyResult<Self> {
         let shredstream_client = ShredstreamProxyClient::connect(endpoint.clone()).await?;
         let metrics = Arc::new(RwLock::new(PerformanceMetrics::new()));
 
@@ -35,9 +58,12 @@ impl ShredStreamGrpc {
         Ok(Self {
             shredstream_client: Arc::new(shredstream_client),
             config,
+            credentials,
             metrics: metrics.clone(),
             metrics_manager,
             subscription_handle: Arc::new(Mutex::new(None)),
+            global_state: Arc::new(GlobalState::new()),
+            protocol_switches: Arc::new(ProtocolSwitches::default()),
         })
     }
 
@@ -59,7 +85,6 @@ impl ShredStreamGrpc {
         Self::new_with_config(endpoint, StreamClientConfig::low_latency()).await
     }
 
-
     /// 获取当前配置
     pub fn get_config(&self) -> &StreamClientConfig {
         &self.config
@@ -90,6 +115,16 @@ impl ShredStreamGrpc {
         self.metrics_manager.start_auto_monitoring().await;
     }
 
+    /// Stops parsing the protocol on the live subscription, returns false if already paused
+    pub fn pause_protocol(&self, protocol: Protocol) -> bool {
+        self.protocol_switches.pause(&protocol)
+    }
+
+    /// Resumes a paused protocol, returns false if it was not paused
+    pub fn resume_protocol(&self, protocol: Protocol) -> bool {
+        self.protocol_switches.resume(&protocol)
+    }
+
     /// 停止当前订阅
     pub async fn stop(&self) {
         let mut handle_guard = self.subscription_handle.lock().await;
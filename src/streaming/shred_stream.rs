@@ -5,7 +5,7 @@ use solana_sdk::pubkey::Pubkey;
 
 use crate::common::AnyResult;
 use crate::protos::shredstream::SubscribeEntriesRequest;
-use crate::streaming::common::{EventProcessor, SubscriptionHandle};
+use crate::streaming::common::{authorize_request, EventProcessor, SubscriptionHandle};
 use crate::streaming::event_parser::common::filter::EventTypeFilter;
 use crate::streaming::event_parser::common::high_performance_clock::get_high_perf_clock;
 use crate::streaming::event_parser::{Protocol, UnifiedEvent};
@@ -37,8 +37,12 @@ impl ShredStreamGrpc {
         }
 
         // 创建事件处理器
-        let mut event_processor =
-            EventProcessor::new(self.metrics_manager.clone(), self.config.clone());
+        let mut event_processor = EventProcessor::new_with_state(
+            self.metrics_manager.clone(),
+            self.config.clone(),
+            self.global_state.clone(),
+        );
+        event_processor.protocol_switches = self.protocol_switches.clone();
         event_processor.set_protocols_and_event_type_filter(
             super::common::EventSource::Shred,
             protocols,
@@ -49,10 +53,11 @@ impl ShredStreamGrpc {
 
         // 启动流处理
         let mut client = (*self.shredstream_client).clone();
-        let request = tonic::Request::new(SubscribeEntriesRequest {});
+        let mut request = tonic::Request::new(SubscribeEntriesRequest {});
+        authorize_request(&mut request, self.credentials.as_ref())?;
         let mut stream = client.subscribe_entries(request).await?.into_inner();
         let event_processor_clone = event_processor.clone();
-        let stream_task = tokio::spawn(async move {
+        let stream_task = self.config.runtime.spawn_reader(async move {
             while let Some(message) = stream.next().await {
                 match message {
                     Ok(msg) => {
@@ -86,7 +91,7 @@ impl ShredStreamGrpc {
                     }
                 }
             }
-        });
+        })?;
 
         // 保存订阅句柄
         let subscription_handle = SubscriptionHandle::new(stream_task, None, metrics_handle);
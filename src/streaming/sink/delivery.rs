@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, HashSet};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use super::EventSink;
+use crate::common::AnyResult;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Slots below the watermark whose idempotency keys are still remembered
+pub const DEFAULT_WATERMARK_KEY_SLOTS: u64 = 32;
+
+/// Stable key of an event, identical across replays and restarts
+///
+/// Built from `(signature, event_type, outer_index, inner_index)`, suitable as a
+/// unique key for upserts in downstream databases.
+pub fn idempotency_key(event: &dyn UnifiedEvent) -> String {
+    let inner = event.inner_index().map_or_else(|| "-".to_string(), |i| i.to_string());
+    format!("{}:{}:{}:{}", event.signature(), event.event_type(), event.outer_index(), inner)
+}
+
+/// Delivery position of an event: slot, then position within the slot
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Watermark {
+    pub slot: u64,
+    /// `transaction_index << 32 | outer_index << 16 | (inner_index + 1)`
+    pub sequence: u64,
+}
+
+impl Watermark {
+    pub fn of(event: &dyn UnifiedEvent) -> Self {
+        let transaction_index = event.transaction_index().unwrap_or(0).min(u32::MAX as u64);
+        let outer = event.outer_index().clamp(0, u16::MAX as i64) as u64;
+        let inner = event.inner_index().map_or(0, |i| (i + 1).clamp(0, u16::MAX as i64)) as u64;
+        Self { slot: event.slot(), sequence: (transaction_index << 32) | (outer << 16) | inner }
+    }
+}
+
+#[derive(Default)]
+struct TrackerState {
+    watermark: Option<Watermark>,
+    keys: BTreeMap<u64, HashSet<String>>,
+}
+
+/// Per-sink watermark with idempotency-key deduplication
+///
+/// Events at or below the watermark were already delivered; this assumes events of a
+/// slot arrive in position order, which holds for archive replays and block
+/// subscriptions. Keys of recent slots are remembered as well, so redelivered events
+/// above the watermark are also dropped. Persist [`watermark`](Self::watermark)
+/// together with the sink's writes and pass it back to [`new`](Self::new) on restart.
+pub struct WatermarkTracker {
+    key_slots: u64,
+    state: Mutex<TrackerState>,
+}
+
+impl Default for WatermarkTracker {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl WatermarkTracker {
+    pub fn new(watermark: Option<Watermark>) -> Self {
+        Self::with_key_slots(watermark, DEFAULT_WATERMARK_KEY_SLOTS)
+    }
+
+    pub fn with_key_slots(watermark: Option<Watermark>, key_slots: u64) -> Self {
+        Self { key_slots, state: Mutex::new(TrackerState { watermark, keys: BTreeMap::new() }) }
+    }
+
+    pub fn watermark(&self) -> Option<Watermark> {
+        self.state.lock().watermark
+    }
+
+    /// Whether the event was already delivered
+    pub fn is_delivered(&self, event: &dyn UnifiedEvent) -> bool {
+        let state = self.state.lock();
+        if state.watermark.is_some_and(|w| Watermark::of(event) <= w) {
+            return true;
+        }
+        state.keys.get(&event.slot()).is_some_and(|keys| keys.contains(&idempotency_key(event)))
+    }
+
+    /// Records a delivered event and advances the watermark
+    pub fn mark_delivered(&self, event: &dyn UnifiedEvent) {
+        let mut state = self.state.lock();
+        let position = Watermark::of(event);
+        state.keys.entry(position.slot).or_default().insert(idempotency_key(event));
+        let watermark = state.watermark.map_or(position, |w| w.max(position));
+        state.watermark = Some(watermark);
+        let oldest = watermark.slot.saturating_sub(self.key_slots);
+        while state.keys.first_key_value().is_some_and(|(&slot, _)| slot < oldest) {
+            state.keys.pop_first();
+        }
+    }
+}
+
+/// Sink wrapper that drops events the tracker has already seen delivered
+pub struct ExactlyOnceSink<S: EventSink> {
+    inner: S,
+    tracker: WatermarkTracker,
+}
+
+impl<S: EventSink> ExactlyOnceSink<S> {
+    pub fn new(inner: S, tracker: WatermarkTracker) -> Self {
+        Self { inner, tracker }
+    }
+
+    pub fn tracker(&self) -> &WatermarkTracker {
+        &self.tracker
+    }
+}
+
+impl<S: EventSink> EventSink for ExactlyOnceSink<S> {
+    fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+        if self.tracker.is_delivered(event) {
+            return Ok(());
+        }
+        self.inner.send(event)?;
+        self.tracker.mark_delivered(event);
+        Ok(())
+    }
+
+    fn flush(&self) -> AnyResult<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::EventMetadata;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    fn event(slot: u64, transaction_index: u64) -> RaydiumCpmmSwapEvent {
+        RaydiumCpmmSwapEvent {
+            metadata: EventMetadata {
+                slot,
+                transaction_index: Some(transaction_index),
+                signature: solana_sdk::signature::Signature::new_unique(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_watermark_skips_delivered_events() {
+        let tracker = WatermarkTracker::default();
+        let first = event(10, 1);
+        let second = event(10, 2);
+        assert!(!tracker.is_delivered(&first));
+        tracker.mark_delivered(&first);
+        tracker.mark_delivered(&second);
+        assert!(tracker.is_delivered(&first));
+        assert_eq!(tracker.watermark(), Some(Watermark::of(&second)));
+
+        // 重启后从持久化的水位继续
+        let restored = WatermarkTracker::new(tracker.watermark());
+        assert!(restored.is_delivered(&first));
+        assert!(!restored.is_delivered(&event(10, 3)));
+        assert!(!restored.is_delivered(&event(11, 0)));
+        assert_ne!(idempotency_key(&first), idempotency_key(&second));
+    }
+}
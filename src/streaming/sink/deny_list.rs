@@ -0,0 +1,398 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::Context;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+
+use super::EventSink;
+use crate::common::AnyResult;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default refresh interval of deny-list sources (ten minutes)
+pub const DEFAULT_DENY_LIST_REFRESH_SECS: u64 = 600;
+
+/// What [`DenyListSink`] does with events involving a denied address
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DenyAction {
+    /// Do not forward the event
+    #[default]
+    Drop,
+    /// Forward it with `deny:<label>` added to `metadata.tags`
+    Tag,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DenyListConfig {
+    /// File paths or http(s) URLs, merged in order
+    pub sources: Vec<String>,
+    /// 0 表示只加载一次
+    pub refresh_secs: u64,
+    pub action: DenyAction,
+    /// Sent as `Authorization: Bearer` to http(s) sources, read on every refresh
+    #[cfg(feature = "transport")]
+    #[serde(skip)]
+    pub credentials: Option<crate::streaming::common::SharedCredentialProvider>,
+}
+
+impl Default for DenyListConfig {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            refresh_secs: DEFAULT_DENY_LIST_REFRESH_SECS,
+            action: DenyAction::default(),
+            #[cfg(feature = "transport")]
+            credentials: None,
+        }
+    }
+}
+
+/// Denied addresses (scam mints, sanctioned wallets, ...) with a label each
+///
+/// Sources are plain text, one `address[,label]` per line; blank lines and `#`
+/// comments are skipped and the label defaults to `denied`. A refresh replaces the
+/// whole list, so removals in the source take effect too.
+#[derive(Debug, Default)]
+pub struct DenyList {
+    entries: RwLock<HashMap<Pubkey, String>>,
+}
+
+impl DenyList {
+    pub fn new(entries: impl IntoIterator<Item = (Pubkey, String)>) -> Self {
+        Self { entries: RwLock::new(entries.into_iter().collect()) }
+    }
+
+    /// Parses the text format, failing on the first invalid address
+    pub fn parse(text: &str) -> AnyResult<HashMap<Pubkey, String>> {
+        let mut entries = HashMap::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (address, label) = line.split_once(',').unwrap_or((line, ""));
+            let pubkey = Pubkey::from_str(address.trim())
+                .with_context(|| format!("line {}: invalid address {:?}", number + 1, address))?;
+            let label = match label.trim() {
+                "" => "denied",
+                label => label,
+            };
+            entries.insert(pubkey, label.to_string());
+        }
+        Ok(entries)
+    }
+
+    /// Replaces the list, returns the new size
+    pub fn replace(&self, entries: HashMap<Pubkey, String>) -> usize {
+        let len = entries.len();
+        *self.entries.write() = entries;
+        len
+    }
+
+    /// Loads `sources` from disk, URLs need the `transport` feature and
+    /// [`refresh`](Self::refresh)
+    pub fn load_files(&self, sources: &[String]) -> AnyResult<usize> {
+        let mut entries = HashMap::new();
+        for source in sources {
+            let text = std::fs::read_to_string(source)
+                .with_context(|| format!("failed to read deny list {}", source))?;
+            entries.extend(Self::parse(&text).with_context(|| source.clone())?);
+        }
+        Ok(self.replace(entries))
+    }
+
+    pub fn label(&self, pubkey: &Pubkey) -> Option<String> {
+        self.entries.read().get(pubkey).cloned()
+    }
+
+    pub fn contains(&self, pubkey: &Pubkey) -> bool {
+        self.entries.read().contains_key(pubkey)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.read().is_empty()
+    }
+
+    /// Labels of the denied addresses found anywhere in the event, deduplicated
+    pub fn matches(&self, event: &dyn UnifiedEvent) -> Vec<String> {
+        let entries = self.entries.read();
+        if entries.is_empty() {
+            return Vec::new();
+        }
+        let mut labels = Vec::new();
+        visit_pubkeys(&event.to_json(), &mut |pubkey| {
+            if let Some(label) = entries.get(&pubkey) {
+                if !labels.contains(label) {
+                    labels.push(label.clone());
+                }
+            }
+        });
+        labels
+    }
+}
+
+// 账户序列化为 32 字节数组，自定义事件也可能直接写 base58 字符串
+fn visit_pubkeys(value: &Value, visit: &mut impl FnMut(Pubkey)) {
+    match value {
+        Value::Array(items) if items.len() == 32 => {
+            let bytes: Option<Vec<u8>> =
+                items.iter().map(|item| item.as_u64().and_then(|b| u8::try_from(b).ok())).collect();
+            match bytes.and_then(|bytes| Pubkey::try_from(bytes.as_slice()).ok()) {
+                Some(pubkey) => visit(pubkey),
+                None => items.iter().for_each(|item| visit_pubkeys(item, visit)),
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| visit_pubkeys(item, visit)),
+        Value::Object(map) => map.values().for_each(|item| visit_pubkeys(item, visit)),
+        Value::String(s) if (32..=44).contains(&s.len()) => {
+            if let Ok(pubkey) = Pubkey::from_str(s) {
+                visit(pubkey);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(feature = "transport")]
+mod refresh {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use anyhow::Context;
+
+    use super::{DenyList, DenyListConfig};
+    use crate::common::AnyResult;
+    use crate::streaming::common::CredentialProvider;
+
+    impl DenyList {
+        /// Loads every source, fetching http(s) URLs, and replaces the list
+        ///
+        /// Nothing is replaced if any source fails, so a flaky feed keeps the last list.
+        pub async fn refresh(&self, sources: &[String]) -> AnyResult<usize> {
+            self.refresh_with_credentials(sources, None).await
+        }
+
+        /// Like [`DenyList::refresh`], authenticating http(s) sources with `credentials`
+        ///
+        /// The token is fetched once per refresh, so rotated tokens are used on the next
+        /// tick; a provider error fails the refresh and keeps the current list.
+        pub async fn refresh_with_credentials(
+            &self,
+            sources: &[String],
+            credentials: Option<&dyn CredentialProvider>,
+        ) -> AnyResult<usize> {
+            let token = match credentials {
+                Some(provider) if sources.iter().any(|s| is_url(s)) => provider.token()?,
+                _ => None,
+            };
+            let mut entries = HashMap::new();
+            for source in sources {
+                let text = if is_url(source) {
+                    let mut request = reqwest::Client::new().get(source);
+                    if let Some(token) = &token {
+                        request = request.bearer_auth(token);
+                    }
+                    request.send().await?.error_for_status()?.text().await?
+                } else {
+                    tokio::fs::read_to_string(source).await?
+                };
+                let parsed =
+                    Self::parse(&text).with_context(|| format!("invalid deny list {}", source))?;
+                entries.extend(parsed);
+            }
+            Ok(self.replace(entries))
+        }
+
+        /// Loads the sources now and then every `refresh_secs`; abort the handle to stop
+        pub async fn spawn_refresh(
+            self: &Arc<Self>,
+            config: &DenyListConfig,
+        ) -> AnyResult<tokio::task::JoinHandle<()>> {
+            let credentials = config.credentials.clone();
+            let count =
+                self.refresh_with_credentials(&config.sources, credentials.as_deref()).await?;
+            log::info!("Loaded {} denied addresses", count);
+            let list = self.clone();
+            let sources = config.sources.clone();
+            let interval = Duration::from_secs(config.refresh_secs);
+            Ok(tokio::spawn(async move {
+                if interval.is_zero() {
+                    return;
+                }
+                let mut ticker = tokio::time::interval(interval);
+                ticker.tick().await;
+                loop {
+                    ticker.tick().await;
+                    match list.refresh_with_credentials(&sources, credentials.as_deref()).await {
+                        Ok(count) => log::debug!("Refreshed deny list: {} addresses", count),
+                        Err(e) => log::error!("Deny list refresh failed: {e:?}"),
+                    }
+                }
+            }))
+        }
+    }
+
+    fn is_url(source: &str) -> bool {
+        source.starts_with("http://") || source.starts_with("https://")
+    }
+}
+
+/// Drops or tags events touching a [`DenyList`] address before they reach `inner`
+///
+/// Addresses are looked up in every account-like field of the event (its JSON form),
+/// so this costs a serialization per event; place it in front of sinks that need it.
+pub struct DenyListSink {
+    list: Arc<DenyList>,
+    action: DenyAction,
+    inner: Arc<dyn EventSink>,
+    matched: AtomicU64,
+}
+
+impl DenyListSink {
+    pub fn new(list: Arc<DenyList>, action: DenyAction, inner: Arc<dyn EventSink>) -> Self {
+        Self { list, action, inner, matched: AtomicU64::new(0) }
+    }
+
+    /// Events that involved a denied address
+    pub fn matched(&self) -> u64 {
+        self.matched.load(Ordering::Relaxed)
+    }
+}
+
+impl EventSink for DenyListSink {
+    fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+        let labels = self.list.matches(event);
+        if labels.is_empty() {
+            return self.inner.send(event);
+        }
+        self.matched.fetch_add(1, Ordering::Relaxed);
+        match self.action {
+            DenyAction::Drop => Ok(()),
+            DenyAction::Tag => {
+                let mut tagged = event.clone_boxed();
+                let tags = &mut tagged.metadata_mut().tags;
+                tags.extend(labels.into_iter().map(|label| format!("deny:{label}")));
+                self.inner.send(tagged.as_ref())
+            }
+        }
+    }
+
+    fn flush(&self) -> AnyResult<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+    use parking_lot::Mutex;
+
+    #[derive(Default)]
+    struct CollectSink(Mutex<Vec<Vec<String>>>);
+
+    impl EventSink for CollectSink {
+        fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+            self.0.lock().push(event.metadata().tags.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_deny_list_drops_and_tags() {
+        let (scam, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let text = format!("# feed\n{scam}, scam token\n\n{} # no label\n", Pubkey::new_unique());
+        let list = Arc::new(DenyList::default());
+        assert_eq!(list.replace(DenyList::parse(&text).unwrap()), 2);
+        assert!(DenyList::parse("not-an-address").is_err());
+
+        let denied = RaydiumCpmmSwapEvent { output_token_mint: scam, ..Default::default() };
+        let clean = RaydiumCpmmSwapEvent { payer: user, ..Default::default() };
+
+        let out = Arc::new(CollectSink::default());
+        let drop = DenyListSink::new(list.clone(), DenyAction::Drop, out.clone());
+        drop.send(&denied).unwrap();
+        drop.send(&clean).unwrap();
+        assert_eq!(*out.0.lock(), vec![Vec::<String>::new()]);
+
+        let out = Arc::new(CollectSink::default());
+        let tag = DenyListSink::new(list, DenyAction::Tag, out.clone());
+        tag.send(&denied).unwrap();
+        assert_eq!(*out.0.lock(), vec![vec!["deny:scam token".to_string()]]);
+        assert_eq!(tag.matched(), 1);
+    }
+
+    /// Serves `body` to `requests` clients, returning each request's Authorization header
+    #[cfg(feature = "transport")]
+    async fn serve_auth(
+        body: String,
+        requests: usize,
+    ) -> (String, tokio::task::JoinHandle<Vec<Option<String>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/deny.txt", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut seen = Vec::new();
+            for _ in 0..requests {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    head.extend_from_slice(&buf[..n]);
+                }
+                let head = String::from_utf8_lossy(&head).to_string();
+                seen.push(head.lines().find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("authorization").then(|| value.trim().to_string())
+                }));
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+            seen
+        });
+        (url, handle)
+    }
+
+    #[cfg(feature = "transport")]
+    #[tokio::test]
+    async fn test_refresh_uses_rotated_credentials() {
+        use crate::streaming::common::{EnvCredentialProvider, FileCredentialProvider};
+
+        let (url, server) = serve_auth(format!("{}\n", Pubkey::new_unique()), 2).await;
+        let path = std::env::temp_dir().join(format!("deny-token-{}", Pubkey::new_unique()));
+        let provider = FileCredentialProvider::new(&path);
+        let list = DenyList::default();
+        let sources = vec![url];
+
+        std::fs::write(&path, "first\n").unwrap();
+        assert_eq!(list.refresh_with_credentials(&sources, Some(&provider)).await.unwrap(), 1);
+        std::fs::write(&path, "second").unwrap();
+        assert_eq!(list.refresh_with_credentials(&sources, Some(&provider)).await.unwrap(), 1);
+        let seen = server.await.unwrap();
+        assert_eq!(seen, vec![Some("Bearer first".to_string()), Some("Bearer second".to_string())]);
+
+        // 取 token 失败时不请求源，保留当前列表
+        std::fs::remove_file(&path).unwrap();
+        assert!(list.refresh_with_credentials(&sources, Some(&provider)).await.is_err());
+        let missing = EnvCredentialProvider::new("SOLANA_STREAMER_TEST_MISSING_DENY_TOKEN");
+        assert!(list.refresh_with_credentials(&sources, Some(&missing)).await.is_err());
+        assert_eq!(list.len(), 1);
+    }
+}
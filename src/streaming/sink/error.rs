@@ -0,0 +1,57 @@
+use std::io::ErrorKind;
+
+/// Failure of a sink write, returned (wrapped in `anyhow::Error`) by sink implementations
+///
+/// Wrappers such as [`SpillingSink`](super::SpillingSink) use it to tell outages, which
+/// are retried, from rejected events, which retrying cannot fix.
+#[derive(Debug, thiserror::Error)]
+pub enum SinkError {
+    /// The destination is down or unreachable (connection refused, broker unavailable)
+    #[error("sink unavailable: {0}")]
+    Unavailable(String),
+    #[error("sink write timed out after {0} ms")]
+    Timeout(u64),
+    /// The destination refused the record (schema mismatch, record too large)
+    #[error("record rejected by sink: {0}")]
+    Rejected(String),
+    #[error("failed to encode event: {0}")]
+    Encode(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl SinkError {
+    /// Whether retrying the same write later can succeed
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Unavailable(_) | Self::Timeout(_) => true,
+            Self::Rejected(_) | Self::Encode(_) => false,
+            Self::Io(e) => is_transient_io(e.kind()),
+        }
+    }
+}
+
+fn is_transient_io(kind: ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::BrokenPipe
+            | ErrorKind::TimedOut
+            | ErrorKind::Interrupted
+            | ErrorKind::WouldBlock
+    )
+}
+
+/// Whether a sink error is worth retrying
+///
+/// [`SinkError`]s classify themselves, bare I/O errors by kind; anything else
+/// (e.g. a serialization error) is treated as permanent.
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    if let Some(e) = error.downcast_ref::<SinkError>() {
+        return e.is_transient();
+    }
+    error.downcast_ref::<std::io::Error>().is_some_and(|e| is_transient_io(e.kind()))
+}
@@ -0,0 +1,273 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Instant;
+
+use parking_lot::{Condvar, Mutex};
+use serde::{Deserialize, Serialize};
+
+use super::EventSink;
+use crate::common::AnyResult;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default per-sink queue capacity
+pub const DEFAULT_SINK_QUEUE_SIZE: usize = 10_000;
+
+/// What a full sink queue does with new events
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SinkDropPolicy {
+    /// Discard the incoming event
+    #[default]
+    DropNewest,
+    /// Discard the oldest queued event to make room
+    DropOldest,
+    /// Wait for room, stalling the producer (and so every other sink)
+    Block,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SinkQueueConfig {
+    pub queue_size: usize,
+    pub drop_policy: SinkDropPolicy,
+}
+
+impl Default for SinkQueueConfig {
+    fn default() -> Self {
+        Self { queue_size: DEFAULT_SINK_QUEUE_SIZE, drop_policy: SinkDropPolicy::default() }
+    }
+}
+
+/// Queue state of one fan-out target
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SinkLag {
+    pub name: String,
+    /// Events waiting in the queue
+    pub queued: usize,
+    /// How long the oldest queued event has been waiting, 0 when the queue is empty
+    pub oldest_queued_ms: u64,
+    pub delivered: u64,
+    pub dropped: u64,
+    /// `send` calls that returned an error
+    pub failed: u64,
+}
+
+struct QueueState {
+    events: VecDeque<(Box<dyn UnifiedEvent>, Instant)>,
+    in_flight: bool,
+    closed: bool,
+}
+
+struct SinkQueue {
+    name: String,
+    config: SinkQueueConfig,
+    sink: Arc<dyn EventSink>,
+    state: Mutex<QueueState>,
+    /// 队列内容或发送状态变化时通知（工作线程、阻塞的生产者与 flush 共用）
+    changed: Condvar,
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl SinkQueue {
+    fn push(&self, event: Box<dyn UnifiedEvent>) {
+        let mut state = self.state.lock();
+        while state.events.len() >= self.config.queue_size && !state.closed {
+            match self.config.drop_policy {
+                SinkDropPolicy::DropNewest => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                SinkDropPolicy::DropOldest => {
+                    state.events.pop_front();
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                SinkDropPolicy::Block => self.changed.wait(&mut state),
+            }
+        }
+        state.events.push_back((event, Instant::now()));
+        self.changed.notify_all();
+    }
+
+    fn run(&self) {
+        loop {
+            let mut state = self.state.lock();
+            while state.events.is_empty() && !state.closed {
+                self.changed.wait(&mut state);
+            }
+            let Some((event, _)) = state.events.pop_front() else {
+                return;
+            };
+            state.in_flight = true;
+            drop(state);
+            // 唤醒等待空位的生产者
+            self.changed.notify_all();
+
+            match self.sink.send(event.as_ref()) {
+                Ok(()) => self.delivered.fetch_add(1, Ordering::Relaxed),
+                Err(e) => {
+                    log::error!("Sink {} failed to write event: {}", self.name, e);
+                    self.failed.fetch_add(1, Ordering::Relaxed)
+                }
+            };
+            self.state.lock().in_flight = false;
+            self.changed.notify_all();
+        }
+    }
+
+    fn wait_drained(&self) {
+        let mut state = self.state.lock();
+        while !state.events.is_empty() || state.in_flight {
+            self.changed.wait(&mut state);
+        }
+    }
+
+    fn lag(&self) -> SinkLag {
+        let state = self.state.lock();
+        SinkLag {
+            name: self.name.clone(),
+            queued: state.events.len(),
+            oldest_queued_ms: state
+                .events
+                .front()
+                .map_or(0, |(_, queued_at)| queued_at.elapsed().as_millis() as u64),
+            delivered: self.delivered.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Delivers every event to several sinks, each behind its own bounded queue
+///
+/// Each sink is driven by a dedicated thread, so a slow sink only fills its own queue
+/// and then drops according to its [`SinkDropPolicy`]; the others keep up.
+/// [`send`](EventSink::send) never fails, write errors are logged and counted in
+/// [`lag`](Self::lag). [`flush`](EventSink::flush) waits for every queue to drain before
+/// flushing the sinks. Dropping the fan-out delivers what is queued and stops the threads.
+#[derive(Default)]
+pub struct FanoutSink {
+    queues: Vec<Arc<SinkQueue>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl FanoutSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a sink and starts its delivery thread
+    pub fn with_sink(
+        mut self,
+        name: impl Into<String>,
+        sink: Arc<dyn EventSink>,
+        mut config: SinkQueueConfig,
+    ) -> AnyResult<Self> {
+        config.queue_size = config.queue_size.max(1);
+        let queue = Arc::new(SinkQueue {
+            name: name.into(),
+            config,
+            sink,
+            state: Mutex::new(QueueState {
+                events: VecDeque::new(),
+                in_flight: false,
+                closed: false,
+            }),
+            changed: Condvar::new(),
+            delivered: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+        });
+        let worker = queue.clone();
+        let handle = std::thread::Builder::new()
+            .name(format!("sink-{}", queue.name))
+            .spawn(move || worker.run())?;
+        self.queues.push(queue);
+        self.workers.push(handle);
+        Ok(self)
+    }
+
+    /// Queue state of every sink, in the order they were added
+    pub fn lag(&self) -> Vec<SinkLag> {
+        self.queues.iter().map(|queue| queue.lag()).collect()
+    }
+}
+
+impl EventSink for FanoutSink {
+    fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+        for queue in &self.queues {
+            queue.push(event.clone_boxed());
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> AnyResult<()> {
+        for queue in &self.queues {
+            queue.wait_drained();
+            queue.sink.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for FanoutSink {
+    fn drop(&mut self) {
+        for queue in &self.queues {
+            queue.state.lock().closed = true;
+            queue.changed.notify_all();
+        }
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    #[derive(Default)]
+    struct CountingSink {
+        gate: Mutex<()>,
+        count: AtomicU64,
+    }
+
+    impl EventSink for CountingSink {
+        fn send(&self, _event: &dyn UnifiedEvent) -> AnyResult<()> {
+            let _gate = self.gate.lock();
+            self.count.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_slow_sink_does_not_stall_others() {
+        let (fast, slow) = (Arc::new(CountingSink::default()), Arc::new(CountingSink::default()));
+        let config = SinkQueueConfig { queue_size: 2, drop_policy: SinkDropPolicy::DropNewest };
+        let fanout = FanoutSink::new()
+            .with_sink("fast", fast.clone(), SinkQueueConfig::default())
+            .unwrap()
+            .with_sink("slow", slow.clone(), config)
+            .unwrap();
+
+        // 阻塞慢 sink：最多一个在发送，两个排队，其余丢弃
+        let gate = slow.gate.lock();
+        for _ in 0..10 {
+            fanout.send(&RaydiumCpmmSwapEvent::default()).unwrap();
+        }
+        fanout.queues[0].wait_drained();
+        assert_eq!(fast.count.load(Ordering::Relaxed), 10);
+        assert!(fanout.lag()[1].queued <= 2);
+
+        drop(gate);
+        fanout.flush().unwrap();
+        let lag = fanout.lag();
+        assert_eq!(lag[1].queued, 0);
+        assert_eq!(lag[1].delivered + lag[1].dropped, 10);
+        assert!(lag[1].dropped >= 7);
+    }
+}
@@ -0,0 +1,313 @@
+use std::io::{BufWriter, Stdout, Write};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{EventSink, FieldProjection, RecordSink};
+use crate::common::AnyResult;
+use crate::streaming::analytics::TradeInfo;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Field that can be selected for [`JsonLinesSink`] output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonField {
+    EventType,
+    Signature,
+    Slot,
+    TransactionIndex,
+    Protocol,
+    /// `pool` and `user` of swaps
+    Accounts,
+    /// `input_mint` and `output_mint` of swaps
+    Mints,
+    /// `amount_in` and `amount_out` of swaps
+    Amounts,
+    /// `recv_us` and `handle_us`
+    Timing,
+}
+
+impl FromStr for JsonField {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "event_type" | "type" => Ok(Self::EventType),
+            "signature" | "sig" => Ok(Self::Signature),
+            "slot" => Ok(Self::Slot),
+            "transaction_index" | "tx_index" => Ok(Self::TransactionIndex),
+            "protocol" => Ok(Self::Protocol),
+            "accounts" => Ok(Self::Accounts),
+            "mints" => Ok(Self::Mints),
+            "amounts" => Ok(Self::Amounts),
+            "timing" => Ok(Self::Timing),
+            other => Err(anyhow!("unknown json field: {}", other)),
+        }
+    }
+}
+
+/// When buffered lines are written out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlushPolicy {
+    /// After every event, for interactive pipes
+    #[default]
+    EveryEvent,
+    /// After every N events
+    EveryN(usize),
+    /// Only on [`EventSink::flush`] (and when the buffer is full)
+    Manual,
+}
+
+struct SinkWriter<W: Write> {
+    writer: BufWriter<W>,
+    pending: usize,
+}
+
+/// Newline-delimited JSON sink
+///
+/// Without a field selection each line is the full event (`UnifiedEvent::to_json`);
+/// with one, only the selected fields are written in a flat object. A
+/// [`FieldProjection`] is applied to either form.
+pub struct JsonLinesSink<W: Write + Send = Stdout> {
+    fields: Vec<JsonField>,
+    projection: FieldProjection,
+    flush_policy: FlushPolicy,
+    inner: Mutex<SinkWriter<W>>,
+}
+
+impl JsonLinesSink<Stdout> {
+    pub fn stdout() -> Self {
+        Self::new(std::io::stdout())
+    }
+}
+
+impl<W: Write + Send> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            fields: Vec::new(),
+            projection: FieldProjection::default(),
+            flush_policy: FlushPolicy::default(),
+            inner: Mutex::new(SinkWriter { writer: BufWriter::new(writer), pending: 0 }),
+        }
+    }
+
+    pub fn with_fields(mut self, fields: impl IntoIterator<Item = JsonField>) -> Self {
+        self.fields = fields.into_iter().collect();
+        self
+    }
+
+    pub fn with_projection(mut self, projection: FieldProjection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    pub fn with_flush_policy(mut self, flush_policy: FlushPolicy) -> Self {
+        self.flush_policy = flush_policy;
+        self
+    }
+
+    /// Renders one output line (without the newline)
+    pub fn render(&self, event: &dyn UnifiedEvent) -> Value {
+        let line = self.render_fields(event);
+        if self.projection.is_empty() {
+            line
+        } else {
+            self.projection.apply(line)
+        }
+    }
+
+    fn render_fields(&self, event: &dyn UnifiedEvent) -> Value {
+        if self.fields.is_empty() {
+            return event.to_json();
+        }
+        let metadata = event.metadata();
+        let trade = if self
+            .fields
+            .iter()
+            .any(|f| matches!(f, JsonField::Accounts | JsonField::Mints | JsonField::Amounts))
+        {
+            TradeInfo::from_event(event)
+        } else {
+            None
+        };
+
+        let mut object = Map::new();
+        for field in &self.fields {
+            match field {
+                JsonField::EventType => {
+                    object.insert("event_type".into(), event.event_type().to_string().into());
+                }
+                JsonField::Signature => {
+                    object.insert("signature".into(), event.signature().to_string().into());
+                }
+                JsonField::Slot => {
+                    object.insert("slot".into(), event.slot().into());
+                }
+                JsonField::TransactionIndex => {
+                    object.insert("transaction_index".into(), metadata.transaction_index.into());
+                }
+                JsonField::Protocol => {
+                    object.insert("protocol".into(), format!("{:?}", metadata.protocol).into());
+                }
+                JsonField::Accounts => {
+                    if let Some(trade) = &trade {
+                        object.insert("pool".into(), trade.pool.to_string().into());
+                        object.insert("user".into(), trade.user.to_string().into());
+                    }
+                }
+                JsonField::Mints => {
+                    if let Some(trade) = &trade {
+                        object.insert("input_mint".into(), trade.input_mint.to_string().into());
+                        object.insert("output_mint".into(), trade.output_mint.to_string().into());
+                    }
+                }
+                JsonField::Amounts => {
+                    if let Some(trade) = &trade {
+                        object.insert("amount_in".into(), trade.amount_in.into());
+                        object.insert("amount_out".into(), trade.amount_out.into());
+                    }
+                }
+                JsonField::Timing => {
+                    object.insert("recv_us".into(), metadata.recv_us.into());
+                    object.insert("handle_us".into(), metadata.handle_us.into());
+                }
+            }
+        }
+        Value::Object(object)
+    }
+}
+
+impl<W: Write + Send> EventSink for JsonLinesSink<W> {
+    fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+        self.write_record(&self.encode(event)?)
+    }
+
+    fn flush(&self) -> AnyResult<()> {
+        let mut inner = self.inner.lock();
+        inner.writer.flush()?;
+        inner.pending = 0;
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> RecordSink for JsonLinesSink<W> {
+    fn encode(&self, event: &dyn UnifiedEvent) -> AnyResult<Vec<u8>> {
+        Ok(serde_json::to_vec(&self.render(event))?)
+    }
+
+    fn write_record(&self, record: &[u8]) -> AnyResult<()> {
+        let mut inner = self.inner.lock();
+        inner.writer.write_all(record)?;
+        inner.writer.write_all(b"\n")?;
+        inner.pending += 1;
+        let should_flush = match self.flush_policy {
+            FlushPolicy::EveryEvent => true,
+            FlushPolicy::EveryN(n) => inner.pending >= n.max(1),
+            FlushPolicy::Manual => false,
+        };
+        if should_flush {
+            inner.writer.flush()?;
+            inner.pending = 0;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> Drop for JsonLinesSink<W> {
+    fn drop(&mut self) {
+        let _ = self.inner.get_mut().writer.flush();
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use std::sync::Arc;
+
+    use solana_sdk::signature::Signature;
+
+    use super::*;
+    use crate::streaming::event_parser::common::{EventMetadata, EventType};
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    /// Shared buffer, readable after the sink is dropped
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuf {
+        fn lines(&self) -> Vec<Value> {
+            let bytes = self.0.lock().clone();
+            bytes
+                .split(|b| *b == b'\n')
+                .filter(|line| !line.is_empty())
+                .map(|line| serde_json::from_slice(line).unwrap())
+                .collect()
+        }
+    }
+
+    fn swap(slot: u64, amount_in: u64) -> RaydiumCpmmSwapEvent {
+        RaydiumCpmmSwapEvent {
+            metadata: EventMetadata {
+                signature: Signature::new_unique(),
+                slot,
+                event_type: EventType::RaydiumCpmmSwapBaseInput,
+                ..Default::default()
+            },
+            amount_in,
+            minimum_amount_out: amount_in / 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_round_trip_flushed_on_drop() {
+        let buf = SharedBuf::default();
+        let sink = JsonLinesSink::new(buf.clone()).with_flush_policy(FlushPolicy::Manual);
+        let events: Vec<RaydiumCpmmSwapEvent> = (1..=3).map(|i| swap(i, i * 1_000)).collect();
+        for event in &events {
+            sink.send(event).unwrap();
+        }
+        // 手动刷新策略下数据仍在缓冲区
+        assert!(buf.lines().is_empty());
+        drop(sink);
+
+        let decoded: Vec<RaydiumCpmmSwapEvent> =
+            buf.lines().into_iter().map(|line| serde_json::from_value(line).unwrap()).collect();
+        assert_eq!(decoded, events);
+    }
+
+    #[test]
+    fn test_selected_fields_written_per_event() {
+        let buf = SharedBuf::default();
+        let sink = JsonLinesSink::new(buf.clone()).with_fields([
+            JsonField::Signature,
+            JsonField::Slot,
+            JsonField::Amounts,
+        ]);
+        let event = swap(7, 5_000);
+        sink.send(&event).unwrap();
+
+        let lines = buf.lines();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["signature"], event.metadata.signature.to_string());
+        assert_eq!(lines[0]["slot"], 7);
+        assert_eq!(
+            (lines[0]["amount_in"].as_u64(), lines[0]["amount_out"].as_u64()),
+            (Some(5_000), Some(2_500))
+        );
+        assert!(lines[0].get("user").is_none());
+    }
+}
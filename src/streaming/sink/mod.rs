@@ -0,0 +1,72 @@
+// 输出模块 - 将事件写入外部目标
+pub mod delivery;
+pub mod deny_list;
+pub mod error;
+pub mod fanout;
+pub mod json_lines;
+pub mod projection;
+pub mod schema;
+#[cfg(feature = "shm")]
+pub mod shm_ring;
+pub mod spill;
+#[cfg(feature = "compression")]
+pub mod zstd_frames;
+
+// 重新导出主要类型
+pub use delivery::*;
+pub use deny_list::*;
+pub use error::*;
+pub use fanout::*;
+pub use json_lines::*;
+pub use projection::*;
+pub use schema::*;
+#[cfg(feature = "shm")]
+pub use shm_ring::*;
+pub use spill::*;
+#[cfg(feature = "compression")]
+pub use zstd_frames::*;
+
+use std::sync::Arc;
+
+use crate::common::AnyResult;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Destination for parsed events
+pub trait EventSink: Send + Sync {
+    /// Writes one event
+    fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()>;
+
+    /// Flushes buffered output
+    fn flush(&self) -> AnyResult<()> {
+        Ok(())
+    }
+}
+
+/// Callback usable with the `subscribe_*` methods, write errors are logged
+pub fn sink_callback<S: EventSink + 'static>(
+    sink: Arc<S>,
+) -> impl Fn(Box<dyn UnifiedEvent>) + Send + Sync + 'static {
+    move |event: Box<dyn UnifiedEvent>| {
+        if let Err(e) = sink.send(event.as_ref()) {
+            log::error!("Failed to write event to sink: {}", e);
+        }
+    }
+}
+
+/// Self-describing JSON form of an event, used by the FFI and Python bindings
+///
+/// The routing fields sit next to the full event body, so consumers can filter
+/// without knowing every event schema. `schema_version` identifies the layout, see
+/// [`migrate_record`] for reading records of older versions.
+pub fn event_envelope(event: &dyn UnifiedEvent) -> serde_json::Value {
+    serde_json::json!({
+        "schema_version": ENVELOPE_SCHEMA_VERSION,
+        "event_type": event.event_type().to_string(),
+        "protocol": event.metadata().protocol,
+        "signature": event.signature().to_string(),
+        "slot": event.slot(),
+        "transaction_index": event.transaction_index(),
+        "recv_us": event.recv_us(),
+        "event": event.to_json(),
+    })
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Per-field projection applied to sink output
+///
+/// Paths are dotted (`metadata.slot`, `swap_data.from_amount`). With `include` set
+/// only those paths are kept; `exclude` is applied afterwards, so account lists can
+/// be dropped from otherwise full events.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldProjection {
+    /// Paths to keep, empty keeps everything
+    pub include: Vec<String>,
+    /// Paths to drop
+    pub exclude: Vec<String>,
+}
+
+impl FieldProjection {
+    pub fn include(paths: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self { include: paths.into_iter().map(Into::into).collect(), exclude: Vec::new() }
+    }
+
+    pub fn exclude(paths: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self { include: Vec::new(), exclude: paths.into_iter().map(Into::into).collect() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn apply(&self, value: Value) -> Value {
+        let mut value = if self.include.is_empty() {
+            value
+        } else {
+            let mut projected = Value::Object(Map::new());
+            for path in &self.include {
+                let keys: Vec<&str> = path.split('.').collect();
+                let found = keys.iter().try_fold(&value, |v, key| v.get(*key));
+                if let Some(found) = found {
+                    insert_path(&mut projected, &keys, found.clone());
+                }
+            }
+            projected
+        };
+        for path in &self.exclude {
+            let keys: Vec<&str> = path.split('.').collect();
+            let (last, parents) = keys.split_last().expect("split yields at least one key");
+            let parent = parents.iter().try_fold(&mut value, |v, key| v.get_mut(*key));
+            if let Some(Value::Object(map)) = parent {
+                map.remove(*last);
+            }
+        }
+        value
+    }
+}
+
+fn insert_path(root: &mut Value, keys: &[&str], value: Value) {
+    let mut current = root;
+    for key in &keys[..keys.len() - 1] {
+        let Value::Object(map) = current else {
+            return;
+        };
+        current = map.entry(key.to_string()).or_insert_with(|| Value::Object(Map::new()));
+    }
+    if let Value::Object(map) = current {
+        map.insert(keys[keys.len() - 1].to_string(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_projection_include_exclude() {
+        let event = json!({
+            "metadata": {"slot": 7, "signature": "sig", "swap_data": {"from_amount": 5}},
+            "amount_in": 10,
+            "payer": [1, 2, 3],
+        });
+        let projection = FieldProjection::include(["metadata.slot", "amount_in", "missing"]);
+        assert_eq!(
+            projection.apply(event.clone()),
+            json!({"metadata": {"slot": 7}, "amount_in": 10})
+        );
+
+        let projection = FieldProjection::exclude(["payer", "metadata.swap_data"]);
+        assert_eq!(
+            projection.apply(event),
+            json!({"metadata": {"slot": 7, "signature": "sig"}, "amount_in": 10})
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[cfg(feature = "compression")]
+use super::{EventSink, SlotFramedZstdSink};
+use crate::common::AnyResult;
+use crate::streaming::event_parser::common::EventMetadata;
+
+/// Layout version of the records written by [`event_envelope`](super::event_envelope)
+///
+/// - 0: bare event (`UnifiedEvent::to_json`), as written by `JsonLinesSink` and
+///   `SlotFramedZstdSink` without a field selection
+/// - 1: envelope without `schema_version`
+/// - 2: envelope with `schema_version`
+pub const ENVELOPE_SCHEMA_VERSION: u32 = 2;
+
+/// Layout version of a recorded line, None if it is neither an envelope nor an event
+pub fn record_version(record: &Value) -> Option<u32> {
+    if let Some(version) = record.get("schema_version") {
+        return version.as_u64().and_then(|version| u32::try_from(version).ok());
+    }
+    if record.get("event").is_some_and(Value::is_object) && record.get("event_type").is_some() {
+        return Some(1);
+    }
+    record.get("metadata").is_some_and(Value::is_object).then_some(0)
+}
+
+/// Upgrades a recorded line to the current envelope
+///
+/// Event metadata is round-tripped through the current [`EventMetadata`], so fields
+/// added after the record was written appear with their defaults (e.g. `stale` false).
+/// Records of the current version are returned unchanged; newer ones are an error.
+pub fn migrate_record(record: Value) -> AnyResult<Value> {
+    match record_version(&record).ok_or_else(|| anyhow!("unrecognized record layout"))? {
+        ENVELOPE_SCHEMA_VERSION => Ok(record),
+        0 => upgrade_bare_event(record),
+        1 => Ok(upgrade_envelope(record)),
+        newer => bail!(
+            "record schema version {} is newer than the supported {}",
+            newer,
+            ENVELOPE_SCHEMA_VERSION
+        ),
+    }
+}
+
+/// 按当前 EventMetadata 重新序列化元数据，补齐之后新增的字段
+fn current_metadata(event: &mut Value) -> Option<EventMetadata> {
+    let slot = event.get_mut("metadata")?;
+    let metadata: EventMetadata = serde_json::from_value(slot.clone()).ok()?;
+    *slot = serde_json::to_value(&metadata).ok()?;
+    Some(metadata)
+}
+
+fn upgrade_bare_event(mut event: Value) -> AnyResult<Value> {
+    let metadata = current_metadata(&mut event).context("unreadable event metadata")?;
+    Ok(serde_json::json!({
+        "schema_version": ENVELOPE_SCHEMA_VERSION,
+        "event_type": metadata.event_type.to_string(),
+        "protocol": metadata.protocol,
+        "signature": metadata.signature.to_string(),
+        "slot": metadata.slot,
+        "transaction_index": metadata.transaction_index,
+        "recv_us": metadata.recv_us,
+        "event": event,
+    }))
+}
+
+fn upgrade_envelope(mut envelope: Value) -> Value {
+    // 投影掉部分元数据的记录无法还原，原样保留
+    if let Some(event) = envelope.get_mut("event") {
+        current_metadata(event);
+    }
+    envelope["schema_version"] = ENVELOPE_SCHEMA_VERSION.into();
+    envelope
+}
+
+/// Reads JSON-lines records of any schema version as current envelopes
+///
+/// Works on the output of `JsonLinesSink` (without a field selection), the decompressed
+/// lines of a `SlotFrameReader` and envelopes collected from the FFI, Python or
+/// shared-memory outputs. Blank lines are skipped; errors carry the line number.
+pub struct EnvelopeReader<R: BufRead> {
+    lines: Lines<R>,
+    line: u64,
+}
+
+impl<R: BufRead> EnvelopeReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { lines: reader.lines(), line: 0 }
+    }
+}
+
+impl<R: BufRead> Iterator for EnvelopeReader<R> {
+    type Item = AnyResult<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e.into())),
+            };
+            self.line += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = serde_json::from_str::<Value>(&line)
+                .map_err(anyhow::Error::from)
+                .and_then(migrate_record);
+            return Some(record.with_context(|| format!("line {}", self.line)));
+        }
+    }
+}
+
+/// Counters of a migration run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationStats {
+    pub records: u64,
+    /// Records written by an older schema version
+    pub upgraded: u64,
+}
+
+/// 逐行迁移，遇到无法读取的行立即失败，避免输出被误当作完整结果
+fn migrate_with(
+    reader: impl BufRead,
+    mut write: impl FnMut(u64, &[u8]) -> AnyResult<()>,
+) -> AnyResult<MigrationStats> {
+    let mut stats = MigrationStats::default();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Value =
+            serde_json::from_str(&line).with_context(|| format!("line {}", index + 1))?;
+        let upgraded = record_version(&record) != Some(ENVELOPE_SCHEMA_VERSION);
+        let envelope = migrate_record(record).with_context(|| format!("line {}", index + 1))?;
+        let slot = envelope.get("slot").and_then(Value::as_u64).unwrap_or_default();
+        write(slot, &serde_json::to_vec(&envelope)?)?;
+        stats.records += 1;
+        stats.upgraded += upgraded as u64;
+    }
+    Ok(stats)
+}
+
+/// Rewrites JSON-lines records of any schema version as current envelopes
+///
+/// Stops at the first unreadable line.
+pub fn migrate_lines(reader: impl BufRead, mut writer: impl Write) -> AnyResult<MigrationStats> {
+    let stats = migrate_with(reader, |_, line| {
+        writer.write_all(line)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    })?;
+    writer.flush()?;
+    Ok(stats)
+}
+
+fn is_zstd(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "zst")
+}
+
+fn open_archive(path: &Path) -> AnyResult<Box<dyn BufRead>> {
+    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    if is_zstd(path) {
+        #[cfg(feature = "compression")]
+        return Ok(Box::new(BufReader::new(zstd::stream::read::Decoder::new(file)?)));
+        #[cfg(not(feature = "compression"))]
+        bail!("reading {} needs the compression feature", path.display());
+    }
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// Migrates a recorded archive file to the current envelope schema
+///
+/// `.zst` files (with the `compression` feature) are read as zstd streams and written
+/// as slot-framed archives with a new `<output>.idx`; other files are JSON lines.
+/// The input is left untouched, so `output` must be a different path. Records of
+/// config-defined protocols only decode once their names are registered, e.g. by
+/// loading the same protocol configs before migrating.
+pub fn migrate_archive(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+) -> AnyResult<MigrationStats> {
+    let (input, output) = (input.as_ref(), output.as_ref());
+    if input == output {
+        bail!("migrating {} in place is not supported", input.display());
+    }
+    let reader = open_archive(input)?;
+    if is_zstd(output) {
+        #[cfg(feature = "compression")]
+        {
+            let sink = SlotFramedZstdSink::create(output)?;
+            let stats = migrate_with(reader, |slot, line| sink.write_line(slot, line))?;
+            sink.flush()?;
+            return Ok(stats);
+        }
+        #[cfg(not(feature = "compression"))]
+        bail!("writing {} needs the compression feature", output.display());
+    }
+    migrate_lines(reader, BufWriter::new(File::create(output)?))
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+    use crate::streaming::event_parser::UnifiedEvent;
+    use crate::streaming::sink::event_envelope;
+
+    #[test]
+    fn test_migrate_records() {
+        let event = RaydiumCpmmSwapEvent {
+            metadata: EventMetadata { slot: 42, recv_us: 7, ..Default::default() },
+            amount_in: 100,
+            ..Default::default()
+        };
+        let current = event_envelope(&event);
+
+        // 旧版本的裸事件还没有后来新增的元数据字段
+        let mut bare = event.to_json();
+        let metadata = bare["metadata"].as_object_mut().unwrap();
+        metadata.remove("stale");
+        metadata.remove("tags");
+        assert_eq!(record_version(&bare), Some(0));
+        assert_eq!(migrate_record(bare.clone()).unwrap(), current);
+
+        let mut unversioned = current.clone();
+        unversioned.as_object_mut().unwrap().remove("schema_version");
+        assert_eq!(record_version(&unversioned), Some(1));
+        assert_eq!(migrate_record(unversioned.clone()).unwrap(), current);
+        assert_eq!(migrate_record(current.clone()).unwrap(), current);
+
+        let mut newer = current.clone();
+        newer["schema_version"] = (ENVELOPE_SCHEMA_VERSION + 1).into();
+        assert!(migrate_record(newer).is_err());
+        assert!(migrate_record(serde_json::json!({"slot": 1})).is_err());
+
+        let input = format!("{}\n\n{}\n{}\n", bare, unversioned, current);
+        let mut output = Vec::new();
+        let stats = migrate_lines(input.as_bytes(), &mut output).unwrap();
+        assert_eq!(stats, MigrationStats { records: 3, upgraded: 2 });
+        let records: Vec<Value> =
+            EnvelopeReader::new(output.as_slice()).collect::<AnyResult<_>>().unwrap();
+        assert_eq!(records, vec![current.clone(), current.clone(), current]);
+        let error = EnvelopeReader::new("{}\nnot json\n".as_bytes()).nth(1).unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "line 2");
+    }
+}
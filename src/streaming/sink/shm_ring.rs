@@ -0,0 +1,345 @@
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{anyhow, bail};
+use memmap2::MmapMut;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use super::{event_envelope, EventSink, FieldProjection, RecordSink, SinkError};
+use crate::common::AnyResult;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// "SSSHMRNG"
+pub const SHM_RING_MAGIC: u64 = 0x5353_5348_4d52_4e47;
+pub const SHM_RING_VERSION: u32 = 1;
+/// Bytes before the first slot: layout header, write cursor and read cursor, one cache
+/// line each
+pub const SHM_RING_HEADER_LEN: usize = 192;
+/// Per-slot header: record length (u32) + reserved (u32)
+pub const SHM_SLOT_HEADER_LEN: usize = 8;
+
+const WRITE_SEQ_OFFSET: usize = 64;
+const READ_SEQ_OFFSET: usize = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShmRingConfig {
+    /// Bytes per slot including the slot header; records longer than
+    /// `slot_size - SHM_SLOT_HEADER_LEN` are rejected
+    pub slot_size: usize,
+    /// Number of slots, rounded up to a power of two
+    pub capacity: usize,
+}
+
+impl Default for ShmRingConfig {
+    fn default() -> Self {
+        Self { slot_size: 4096, capacity: 16_384 }
+    }
+}
+
+/// Counters of a [`ShmRingSink`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShmRingStats {
+    pub published: u64,
+    /// Records waiting for the consumer
+    pub pending: u64,
+    /// Dropped because the ring was full
+    pub dropped: u64,
+    /// Rejected because they did not fit in a slot
+    pub oversized: u64,
+}
+
+/// Mapped ring, shared by both sides
+struct Ring {
+    map: MmapMut,
+    slot_size: usize,
+    mask: u64,
+}
+
+impl Ring {
+    fn cursor(&self, offset: usize) -> &AtomicU64 {
+        // mmap 按页对齐，偏移量是 8 的倍数
+        unsafe { &*(self.map.as_ptr().add(offset) as *const AtomicU64) }
+    }
+
+    fn write_seq(&self) -> &AtomicU64 {
+        self.cursor(WRITE_SEQ_OFFSET)
+    }
+
+    fn read_seq(&self) -> &AtomicU64 {
+        self.cursor(READ_SEQ_OFFSET)
+    }
+
+    fn capacity(&self) -> u64 {
+        self.mask + 1
+    }
+
+    fn slot_offset(&self, seq: u64) -> usize {
+        SHM_RING_HEADER_LEN + (seq & self.mask) as usize * self.slot_size
+    }
+}
+
+fn open_file(path: &Path, create: bool) -> AnyResult<File> {
+    Ok(OpenOptions::new().read(true).write(true).create(create).truncate(create).open(path)?)
+}
+
+/// Sink publishing events into a shared-memory ring for a consumer on the same host
+///
+/// Single producer, single consumer over a memory-mapped file (put it on `/dev/shm`
+/// for a pure in-memory ring). Each event is a JSON envelope ([`event_envelope`]) in a
+/// fixed-size slot; the consumer polls with [`ShmRingReader`] or any implementation of
+/// the layout below. Publishing never blocks: when the consumer falls `capacity`
+/// records behind, new records are dropped and `send` returns
+/// [`SinkError::Unavailable`], so the sink can be wrapped in a `SpillingSink`.
+///
+/// ```text
+/// 0    magic u64 | version u32 | slot_size u32 | capacity u64
+/// 64   write_seq u64 (producer)
+/// 128  read_seq u64 (consumer)
+/// 192  slot 0: len u32 | reserved u32 | record ... (slot_size bytes per slot)
+/// ```
+///
+/// Integers are little-endian. A record is published by storing `write_seq + 1` with
+/// release ordering after the slot is written, and consumed by storing `read_seq + 1`.
+pub struct ShmRingSink {
+    projection: FieldProjection,
+    ring: Mutex<Ring>,
+    stats: Mutex<ShmRingStats>,
+}
+
+impl ShmRingSink {
+    /// Creates (or replaces) the ring file and maps it
+    pub fn create(path: impl AsRef<Path>, config: ShmRingConfig) -> AnyResult<Self> {
+        if !valid_slot_size(config.slot_size) {
+            bail!("slot_size must be a multiple of 8 larger than {}", SHM_SLOT_HEADER_LEN);
+        }
+        let capacity = config.capacity.max(1).next_power_of_two();
+        let file = open_file(path.as_ref(), true)?;
+        file.set_len((SHM_RING_HEADER_LEN + capacity * config.slot_size) as u64)?;
+        let mut map = unsafe { MmapMut::map_mut(&file)? };
+        map[8..12].copy_from_slice(&SHM_RING_VERSION.to_le_bytes());
+        map[12..16].copy_from_slice(&(config.slot_size as u32).to_le_bytes());
+        map[16..24].copy_from_slice(&(capacity as u64).to_le_bytes());
+        let ring = Ring { map, slot_size: config.slot_size, mask: capacity as u64 - 1 };
+        ring.write_seq().store(0, Ordering::Relaxed);
+        ring.read_seq().store(0, Ordering::Relaxed);
+        // magic 最后写入，读端据此判断布局已初始化
+        ring.cursor(0).store(SHM_RING_MAGIC, Ordering::Release);
+        Ok(Self {
+            projection: FieldProjection::default(),
+            ring: Mutex::new(ring),
+            stats: Mutex::new(ShmRingStats::default()),
+        })
+    }
+
+    pub fn with_projection(mut self, projection: FieldProjection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    /// Largest record that fits in a slot
+    pub fn max_record_len(&self) -> usize {
+        self.ring.lock().slot_size - SHM_SLOT_HEADER_LEN
+    }
+
+    pub fn stats(&self) -> ShmRingStats {
+        let ring = self.ring.lock();
+        let pending = ring
+            .write_seq()
+            .load(Ordering::Relaxed)
+            .saturating_sub(ring.read_seq().load(Ordering::Acquire));
+        ShmRingStats { pending, ..*self.stats.lock() }
+    }
+}
+
+impl RecordSink for ShmRingSink {
+    fn encode(&self, event: &dyn UnifiedEvent) -> AnyResult<Vec<u8>> {
+        let envelope = event_envelope(event);
+        let envelope =
+            if self.projection.is_empty() { envelope } else { self.projection.apply(envelope) };
+        Ok(serde_json::to_vec(&envelope)?)
+    }
+
+    fn write_record(&self, record: &[u8]) -> AnyResult<()> {
+        let mut ring = self.ring.lock();
+        if record.len() > ring.slot_size - SHM_SLOT_HEADER_LEN {
+            self.stats.lock().oversized += 1;
+            return Err(SinkError::Rejected(format!(
+                "record of {} bytes exceeds the {} byte slot",
+                record.len(),
+                ring.slot_size - SHM_SLOT_HEADER_LEN
+            ))
+            .into());
+        }
+        let seq = ring.write_seq().load(Ordering::Relaxed);
+        if seq.saturating_sub(ring.read_seq().load(Ordering::Acquire)) >= ring.capacity() {
+            self.stats.lock().dropped += 1;
+            return Err(SinkError::Unavailable("shared-memory ring is full".into()).into());
+        }
+        let offset = ring.slot_offset(seq);
+        let slot = &mut ring.map[offset..offset + SHM_SLOT_HEADER_LEN + record.len()];
+        slot[..4].copy_from_slice(&(record.len() as u32).to_le_bytes());
+        slot[SHM_SLOT_HEADER_LEN..].copy_from_slice(record);
+        ring.write_seq().store(seq + 1, Ordering::Release);
+        self.stats.lock().published += 1;
+        Ok(())
+    }
+}
+
+impl EventSink for ShmRingSink {
+    fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+        self.write_record(&self.encode(event)?)
+    }
+}
+
+/// 读写两端共用的槽位大小校验，读端不能信任文件头
+fn valid_slot_size(slot_size: usize) -> bool {
+    slot_size > SHM_SLOT_HEADER_LEN && slot_size % 8 == 0
+}
+
+/// Consumer side of a [`ShmRingSink`], typically in another process
+///
+/// The read position lives in the ring, so a restarted consumer resumes where the
+/// previous one stopped. Only one reader may be attached at a time.
+pub struct ShmRingReader {
+    ring: Ring,
+}
+
+impl ShmRingReader {
+    /// Maps an existing ring created by [`ShmRingSink::create`]
+    pub fn open(path: impl AsRef<Path>) -> AnyResult<Self> {
+        let file = open_file(path.as_ref(), false)?;
+        if (file.metadata()?.len() as usize) < SHM_RING_HEADER_LEN {
+            bail!("{} is not a shared-memory ring", path.as_ref().display());
+        }
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        let header = |range: std::ops::Range<usize>| &map[range];
+        let version = u32::from_le_bytes(header(8..12).try_into()?);
+        let slot_size = u32::from_le_bytes(header(12..16).try_into()?) as usize;
+        let capacity = u64::from_le_bytes(header(16..24).try_into()?);
+        let ring = Ring { map, slot_size, mask: capacity.wrapping_sub(1) };
+        if ring.cursor(0).load(Ordering::Acquire) != SHM_RING_MAGIC {
+            bail!("{} is not a shared-memory ring", path.as_ref().display());
+        }
+        if version != SHM_RING_VERSION {
+            bail!("unsupported shared-memory ring version {}", version);
+        }
+        let map_len = usize::try_from(capacity)
+            .ok()
+            .and_then(|capacity| capacity.checked_mul(slot_size))
+            .and_then(|slots| slots.checked_add(SHM_RING_HEADER_LEN));
+        if !valid_slot_size(slot_size)
+            || !capacity.is_power_of_two()
+            || map_len.is_none_or(|map_len| ring.map.len() < map_len)
+        {
+            return Err(anyhow!("corrupt shared-memory ring header"));
+        }
+        Ok(Self { ring })
+    }
+
+    /// Records published but not yet read
+    pub fn pending(&self) -> u64 {
+        self.ring
+            .write_seq()
+            .load(Ordering::Acquire)
+            .saturating_sub(self.ring.read_seq().load(Ordering::Relaxed))
+    }
+
+    /// Copies the next record into `buf` (replacing its contents); false when the ring
+    /// is empty
+    ///
+    /// A slot whose length header exceeds the slot is skipped and reported as an error,
+    /// so the next call continues with the following record.
+    pub fn try_read_into(&mut self, buf: &mut Vec<u8>) -> AnyResult<bool> {
+        let seq = self.ring.read_seq().load(Ordering::Relaxed);
+        if seq >= self.ring.write_seq().load(Ordering::Acquire) {
+            return Ok(false);
+        }
+        let offset = self.ring.slot_offset(seq);
+        let len =
+            u32::from_le_bytes(self.ring.map[offset..offset + 4].try_into().unwrap()) as usize;
+        let max_len = self.ring.slot_size - SHM_SLOT_HEADER_LEN;
+        if len > max_len {
+            self.ring.read_seq().store(seq + 1, Ordering::Release);
+            bail!("corrupt record {}: length {} exceeds the {} byte slot", seq, len, max_len);
+        }
+        let start = offset + SHM_SLOT_HEADER_LEN;
+        buf.clear();
+        buf.extend_from_slice(&self.ring.map[start..start + len]);
+        self.ring.read_seq().store(seq + 1, Ordering::Release);
+        Ok(true)
+    }
+
+    /// Next record, None when the ring is empty
+    pub fn try_read(&mut self) -> AnyResult<Option<Vec<u8>>> {
+        let mut buf = Vec::new();
+        Ok(self.try_read_into(&mut buf)?.then_some(buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::sink::is_transient;
+
+    #[test]
+    fn test_shm_ring_round_trip() {
+        let path = std::env::temp_dir().join(format!("shm-ring-{}.ring", std::process::id()));
+        let sink =
+            ShmRingSink::create(&path, ShmRingConfig { slot_size: 64, capacity: 3 }).unwrap();
+        let mut reader = ShmRingReader::open(&path).unwrap();
+
+        for i in 0..4u8 {
+            sink.write_record(&[i; 10]).unwrap();
+        }
+        let full = sink.write_record(b"overflow").unwrap_err();
+        assert!(is_transient(&full));
+        assert!(sink.write_record(&[0; 57]).is_err());
+        assert_eq!(
+            sink.stats(),
+            ShmRingStats { published: 4, pending: 4, dropped: 1, oversized: 1 }
+        );
+
+        assert_eq!(reader.try_read().unwrap(), Some(vec![0; 10]));
+        sink.write_record(b"wrapped").unwrap();
+        let mut buf = Vec::new();
+        let mut records = Vec::new();
+        while reader.try_read_into(&mut buf).unwrap() {
+            records.push(buf.clone());
+        }
+        assert_eq!(records, vec![vec![1; 10], vec![2; 10], vec![3; 10], b"wrapped".to_vec()]);
+        assert_eq!(reader.pending(), 0);
+
+        // 长度头越界的槽位报错并被跳过
+        sink.write_record(b"corrupt").unwrap();
+        sink.write_record(b"next").unwrap();
+        let offset = reader.ring.slot_offset(5);
+        reader.ring.map[offset..offset + 4].copy_from_slice(&1_000u32.to_le_bytes());
+        assert!(reader.try_read().is_err());
+        assert_eq!(reader.try_read().unwrap(), Some(b"next".to_vec()));
+        assert_eq!(reader.try_read().unwrap(), None);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_shm_ring_rejects_corrupt_header() {
+        let path =
+            std::env::temp_dir().join(format!("shm-ring-header-{}.ring", std::process::id()));
+        let sink =
+            ShmRingSink::create(&path, ShmRingConfig { slot_size: 64, capacity: 4 }).unwrap();
+        let write_header = |range: std::ops::Range<usize>, bytes: &[u8]| {
+            sink.ring.lock().map[range].copy_from_slice(bytes);
+        };
+
+        write_header(12..16, &4u32.to_le_bytes());
+        assert!(ShmRingReader::open(&path).is_err());
+        write_header(12..16, &64u32.to_le_bytes());
+        write_header(16..24, &(1u64 << 62).to_le_bytes());
+        assert!(ShmRingReader::open(&path).is_err());
+        write_header(16..24, &4u64.to_le_bytes());
+        assert!(ShmRingReader::open(&path).is_ok());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,436 @@
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use super::{is_transient, EventSink};
+use crate::common::AnyResult;
+use crate::streaming::event_parser::common::high_performance_clock::{system_clock, Clock};
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Per-record header in the spill file: written_us (i64) + length (u32)
+const RECORD_HEADER_LEN: u64 = 12;
+
+/// Sink that writes serialized records, so failed writes can be spilled and replayed
+///
+/// `send` must behave like `write_record(&encode(event)?)`.
+pub trait RecordSink: EventSink {
+    /// Serializes the event into the record the sink writes
+    fn encode(&self, event: &dyn UnifiedEvent) -> AnyResult<Vec<u8>>;
+
+    /// Writes one record produced by [`encode`](Self::encode)
+    fn write_record(&self, record: &[u8]) -> AnyResult<()>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpillConfig {
+    /// Directory of the spill files, one `<name>.spill` per sink
+    pub dir: PathBuf,
+    /// Spill size limit, the oldest records are dropped beyond it
+    pub max_bytes: u64,
+    /// Spilled records older than this are dropped instead of replayed
+    pub max_age_secs: u64,
+    /// Attempts of a direct write before the record is spilled
+    pub retry_attempts: u32,
+    /// Backoff between attempts and between replays while the sink is down, doubled
+    /// after every failure up to `max_backoff_ms`
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for SpillConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("spill"),
+            max_bytes: 1 << 30,
+            max_age_secs: 3600,
+            retry_attempts: 3,
+            initial_backoff_ms: 50,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+/// Counters of a [`SpillingSink`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpillStats {
+    /// Records waiting on disk
+    pub pending: usize,
+    pub pending_bytes: u64,
+    pub spilled: u64,
+    pub replayed: u64,
+    /// Dropped because the spill was full
+    pub dropped: u64,
+    /// Dropped because they were older than `max_age_secs`
+    pub expired: u64,
+    /// Spilled records the sink rejected permanently on replay
+    pub rejected: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SpilledRecord {
+    offset: u64,
+    len: u32,
+    written_us: i64,
+}
+
+/// Append-only record file, truncated whenever it is fully replayed
+struct DiskQueue {
+    file: File,
+    records: VecDeque<SpilledRecord>,
+    end: u64,
+}
+
+impl DiskQueue {
+    /// Opens the file and recovers the records left by a previous run
+    fn open(path: &Path) -> AnyResult<Self> {
+        let file =
+            OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        let mut records = VecDeque::new();
+        let mut end = 0;
+        let mut reader = BufReader::new(&file);
+        let mut header = [0u8; RECORD_HEADER_LEN as usize];
+        while reader.read_exact(&mut header).is_ok() {
+            let written_us = i64::from_le_bytes(header[..8].try_into().unwrap());
+            let len = u32::from_le_bytes(header[8..].try_into().unwrap());
+            let mut body = vec![0u8; len as usize];
+            if reader.read_exact(&mut body).is_err() {
+                break;
+            }
+            records.push_back(SpilledRecord { offset: end, len, written_us });
+            end += RECORD_HEADER_LEN + len as u64;
+        }
+        drop(reader);
+        // 丢弃崩溃时写了一半的记录
+        file.set_len(end)?;
+        Ok(Self { file, records, end })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    fn bytes(&self) -> u64 {
+        self.records.front().map_or(0, |record| self.end - record.offset)
+    }
+
+    fn push(&mut self, record: &[u8], written_us: i64) -> AnyResult<()> {
+        self.file.seek(SeekFrom::Start(self.end))?;
+        self.file.write_all(&written_us.to_le_bytes())?;
+        self.file.write_all(&(record.len() as u32).to_le_bytes())?;
+        self.file.write_all(record)?;
+        self.records.push_back(SpilledRecord {
+            offset: self.end,
+            len: record.len() as u32,
+            written_us,
+        });
+        self.end += RECORD_HEADER_LEN + record.len() as u64;
+        Ok(())
+    }
+
+    fn front(&mut self) -> AnyResult<Option<(SpilledRecord, Vec<u8>)>> {
+        let Some(record) = self.records.front().copied() else {
+            return Ok(None);
+        };
+        let mut body = vec![0u8; record.len as usize];
+        self.file.seek(SeekFrom::Start(record.offset + RECORD_HEADER_LEN))?;
+        self.file.read_exact(&mut body)?;
+        Ok(Some((record, body)))
+    }
+
+    fn pop_front(&mut self) -> AnyResult<()> {
+        self.records.pop_front();
+        if self.records.is_empty() {
+            self.file.set_len(0)?;
+            self.end = 0;
+        }
+        Ok(())
+    }
+}
+
+struct SpillState {
+    queue: DiskQueue,
+    backoff_us: i64,
+    /// 下游不可用期间，下次尝试回放的时间
+    next_attempt_us: i64,
+}
+
+/// Sink wrapper that spills records to disk while the inner sink is unavailable
+///
+/// Transient failures ([`is_transient`]) are retried with backoff, then the record is
+/// appended to `<dir>/<name>.spill`. While records are spilled, new ones are queued
+/// behind them so order is kept, and every `send` / `flush` after the backoff replays
+/// the spill first. Records left on disk at shutdown are replayed by the next run
+/// (at-least-once: a crash during replay can repeat a record). Permanent failures
+/// of direct writes are returned to the caller.
+pub struct SpillingSink<S: RecordSink> {
+    inner: S,
+    config: SpillConfig,
+    path: PathBuf,
+    clock: Arc<dyn Clock>,
+    state: Mutex<SpillState>,
+    spilled: AtomicU64,
+    replayed: AtomicU64,
+    dropped: AtomicU64,
+    expired: AtomicU64,
+    rejected: AtomicU64,
+}
+
+impl<S: RecordSink> SpillingSink<S> {
+    pub fn new(name: &str, inner: S, config: SpillConfig) -> AnyResult<Self> {
+        std::fs::create_dir_all(&config.dir)?;
+        let path = config.dir.join(format!("{}.spill", name));
+        let queue = DiskQueue::open(&path)?;
+        if !queue.is_empty() {
+            log::info!("Recovered {} spilled records from {}", queue.records.len(), path.display());
+        }
+        Ok(Self {
+            inner,
+            config,
+            path,
+            clock: system_clock(),
+            state: Mutex::new(SpillState { queue, backoff_us: 0, next_attempt_us: 0 }),
+            spilled: AtomicU64::new(0),
+            replayed: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            expired: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+        })
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn stats(&self) -> SpillStats {
+        let state = self.state.lock();
+        SpillStats {
+            pending: state.queue.records.len(),
+            pending_bytes: state.queue.bytes(),
+            spilled: self.spilled.load(Ordering::Relaxed),
+            replayed: self.replayed.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            expired: self.expired.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Replays the spill now, ignoring the backoff; returns the records still pending
+    pub fn retry_now(&self) -> AnyResult<usize> {
+        let mut state = self.state.lock();
+        state.next_attempt_us = 0;
+        self.replay(&mut state)?;
+        Ok(state.queue.records.len())
+    }
+
+    fn write_with_retry(&self, record: &[u8]) -> AnyResult<()> {
+        let mut backoff = Duration::from_millis(self.config.initial_backoff_ms);
+        let mut attempt = 1;
+        loop {
+            match self.inner.write_record(record) {
+                Err(e) if attempt < self.config.retry_attempts && is_transient(&e) => {
+                    std::thread::sleep(backoff);
+                    backoff = (backoff * 2).min(Duration::from_millis(self.config.max_backoff_ms));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn back_off(&self, state: &mut SpillState, now: i64) {
+        let initial = self.config.initial_backoff_ms as i64 * 1000;
+        let max = self.config.max_backoff_ms as i64 * 1000;
+        state.backoff_us =
+            if state.backoff_us == 0 { initial } else { (state.backoff_us * 2).min(max) };
+        state.next_attempt_us = now + state.backoff_us;
+    }
+
+    /// Writes spilled records in order until the spill is empty or the sink fails
+    fn replay(&self, state: &mut SpillState) -> AnyResult<()> {
+        let now = self.clock.now_micros();
+        if now < state.next_attempt_us {
+            return Ok(());
+        }
+        let max_age_us = self.config.max_age_secs as i64 * 1_000_000;
+        while let Some((record, body)) = state.queue.front()? {
+            if now - record.written_us > max_age_us {
+                self.expired.fetch_add(1, Ordering::Relaxed);
+                state.queue.pop_front()?;
+                continue;
+            }
+            match self.inner.write_record(&body) {
+                Ok(()) => {
+                    self.replayed.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(e) if is_transient(&e) => {
+                    self.back_off(state, now);
+                    return Ok(());
+                }
+                Err(e) => {
+                    // 不可重试的记录不能阻塞后续回放
+                    log::error!("Dropping spilled record rejected by sink: {}", e);
+                    self.rejected.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            state.queue.pop_front()?;
+        }
+        state.backoff_us = 0;
+        Ok(())
+    }
+
+    fn spill(&self, state: &mut SpillState, record: &[u8]) -> AnyResult<()> {
+        let size = RECORD_HEADER_LEN + record.len() as u64;
+        if size > self.config.max_bytes {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        }
+        while !state.queue.is_empty() && state.queue.bytes() + size > self.config.max_bytes {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            state.queue.pop_front()?;
+        }
+        state.queue.push(record, self.clock.now_micros())?;
+        self.spilled.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+impl<S: RecordSink> EventSink for SpillingSink<S> {
+    fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+        let record = self.inner.encode(event)?;
+        let mut state = self.state.lock();
+        if !state.queue.is_empty() {
+            self.replay(&mut state)?;
+        }
+        if state.queue.is_empty() {
+            match self.write_with_retry(&record) {
+                Ok(()) => return Ok(()),
+                Err(e) if is_transient(&e) => {
+                    log::warn!("Sink unavailable, spilling to {}: {}", self.path.display(), e);
+                    self.back_off(&mut state, self.clock.now_micros());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        self.spill(&mut state, &record)
+    }
+
+    fn flush(&self) -> AnyResult<()> {
+        let mut state = self.state.lock();
+        self.replay(&mut state)?;
+        state.queue.file.flush()?;
+        match self.inner.flush() {
+            Err(e) if is_transient(&e) && !state.queue.is_empty() => Ok(()),
+            result => result,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+
+    use super::*;
+    use crate::streaming::event_parser::common::high_performance_clock::MockClock;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+    use crate::streaming::sink::SinkError;
+
+    #[derive(Default)]
+    struct FlakySink {
+        down: AtomicBool,
+        written: Mutex<Vec<Vec<u8>>>,
+    }
+
+    impl EventSink for FlakySink {
+        fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+            self.write_record(&self.encode(event)?)
+        }
+    }
+
+    impl RecordSink for FlakySink {
+        fn encode(&self, event: &dyn UnifiedEvent) -> AnyResult<Vec<u8>> {
+            Ok(serde_json::to_vec(&event.to_json())?)
+        }
+
+        fn write_record(&self, record: &[u8]) -> AnyResult<()> {
+            if self.down.load(Ordering::Relaxed) {
+                return Err(SinkError::Unavailable("connection refused".to_string()).into());
+            }
+            self.written.lock().push(record.to_vec());
+            Ok(())
+        }
+    }
+
+    fn swap(amount_in: u64) -> RaydiumCpmmSwapEvent {
+        RaydiumCpmmSwapEvent { amount_in, ..Default::default() }
+    }
+
+    #[test]
+    fn test_spill_and_replay_in_order() {
+        let dir = std::env::temp_dir().join(format!("spill-test-{}", std::process::id()));
+        let config = SpillConfig {
+            dir: dir.clone(),
+            retry_attempts: 1,
+            initial_backoff_ms: 1000,
+            max_age_secs: 60,
+            ..Default::default()
+        };
+        let clock = Arc::new(MockClock::new(1_000_000));
+        let open = |config: &SpillConfig| {
+            SpillingSink::new("test", FlakySink::default(), config.clone())
+                .unwrap()
+                .with_clock(clock.clone())
+        };
+
+        let sink = open(&config);
+        sink.inner().down.store(true, Ordering::Relaxed);
+        for amount in 1..=3 {
+            sink.send(&swap(amount)).unwrap();
+        }
+        assert_eq!(sink.stats().pending, 3);
+        drop(sink);
+
+        // 重启后恢复磁盘上的记录，并在下游恢复后按序回放
+        let sink = open(&config);
+        assert_eq!(sink.stats().pending, 3);
+        sink.send(&swap(4)).unwrap();
+        let written = sink.inner().written.lock().clone();
+        let amounts: Vec<u64> = written
+            .iter()
+            .map(|r| {
+                serde_json::from_slice::<serde_json::Value>(r).unwrap()["amount_in"]
+                    .as_u64()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(amounts, vec![1, 2, 3, 4]);
+        assert_eq!(sink.stats().replayed, 3);
+
+        // 超过 max_age 的记录不再回放
+        sink.inner().down.store(true, Ordering::Relaxed);
+        sink.send(&swap(5)).unwrap();
+        clock.advance(Duration::from_secs(61));
+        sink.inner().down.store(false, Ordering::Relaxed);
+        assert_eq!(sink.retry_now().unwrap(), 0);
+        assert_eq!(sink.stats().expired, 1);
+        assert_eq!(sink.inner().written.lock().len(), 4);
+        drop(sink);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
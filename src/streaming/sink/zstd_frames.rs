@@ -0,0 +1,319 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use zstd::stream::read::Decoder;
+use zstd::stream::write::Encoder;
+
+use super::{EventSink, FieldProjection};
+use crate::common::AnyResult;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Default zstd level, favours speed over ratio
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+/// Default uncompressed size after which a frame is closed at the next slot change
+pub const DEFAULT_FRAME_BYTES: usize = 1 << 20;
+
+/// One zstd frame of a slot-framed archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotFrame {
+    /// Byte offset of the frame in the archive
+    pub offset: u64,
+    /// Compressed length in bytes
+    pub len: u64,
+    pub first_slot: u64,
+    pub last_slot: u64,
+    pub events: u64,
+}
+
+/// Index file path of an archive, `<archive>.idx`
+pub fn slot_index_path(archive: impl AsRef<Path>) -> PathBuf {
+    let mut path = archive.as_ref().as_os_str().to_owned();
+    path.push(".idx");
+    PathBuf::from(path)
+}
+
+/// Loads the JSON-lines frame index written next to an archive
+pub fn load_slot_index(path: impl AsRef<Path>) -> AnyResult<Vec<SlotFrame>> {
+    BufReader::new(File::open(path)?)
+        .lines()
+        .filter(|line| !line.as_ref().is_ok_and(|line| line.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+struct CountingWriter<W: Write> {
+    inner: W,
+    written: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+struct FrameState<W: Write> {
+    /// 无打开的帧时持有底层 writer
+    writer: Option<CountingWriter<W>>,
+    encoder: Option<Encoder<'static, CountingWriter<W>>>,
+    frame: SlotFrame,
+    frame_bytes: usize,
+    index: Option<Box<dyn Write + Send>>,
+    frames: Vec<SlotFrame>,
+}
+
+impl<W: Write> FrameState<W> {
+    fn open(&mut self, level: i32, slot: u64) -> AnyResult<()> {
+        let writer = self.writer.take().ok_or_else(|| anyhow!("archive writer is closed"))?;
+        self.frame = SlotFrame {
+            offset: writer.written,
+            len: 0,
+            first_slot: slot,
+            last_slot: slot,
+            events: 0,
+        };
+        self.frame_bytes = 0;
+        self.encoder = Some(Encoder::new(writer, level)?);
+        Ok(())
+    }
+
+    fn close(&mut self) -> AnyResult<()> {
+        let Some(encoder) = self.encoder.take() else {
+            return Ok(());
+        };
+        let mut writer = encoder.finish()?;
+        writer.flush()?;
+        self.frame.len = writer.written - self.frame.offset;
+        self.writer = Some(writer);
+        if let Some(index) = self.index.as_mut() {
+            serde_json::to_writer(&mut *index, &self.frame)?;
+            index.write_all(b"\n")?;
+            index.flush()?;
+        }
+        self.frames.push(self.frame);
+        Ok(())
+    }
+}
+
+/// JSON-lines archive compressed as a sequence of independent zstd frames
+///
+/// A frame never splits a slot: it is closed at the first slot change after
+/// `frame_bytes` of uncompressed output (and on [`flush`](EventSink::flush)). Each
+/// closed frame is appended to the index (`<archive>.idx` with [`create`](Self::create)),
+/// so [`SlotFrameReader`] can start decompressing at the frame holding a given slot.
+/// The archive as a whole is a regular zstd stream, e.g. `zstd -dc events.jsonl.zst`.
+pub struct SlotFramedZstdSink<W: Write + Send = BufWriter<File>> {
+    level: i32,
+    frame_bytes: usize,
+    projection: FieldProjection,
+    state: Mutex<FrameState<W>>,
+}
+
+impl SlotFramedZstdSink<BufWriter<File>> {
+    /// Creates the archive and its index next to it
+    pub fn create(path: impl AsRef<Path>) -> AnyResult<Self> {
+        let archive = BufWriter::new(File::create(path.as_ref())?);
+        let index = BufWriter::new(File::create(slot_index_path(path))?);
+        Ok(Self::new(archive, Some(Box::new(index))))
+    }
+}
+
+impl<W: Write + Send> SlotFramedZstdSink<W> {
+    pub fn new(writer: W, index: Option<Box<dyn Write + Send>>) -> Self {
+        Self {
+            level: DEFAULT_ZSTD_LEVEL,
+            frame_bytes: DEFAULT_FRAME_BYTES,
+            projection: FieldProjection::default(),
+            state: Mutex::new(FrameState {
+                writer: Some(CountingWriter { inner: writer, written: 0 }),
+                encoder: None,
+                frame: SlotFrame { offset: 0, len: 0, first_slot: 0, last_slot: 0, events: 0 },
+                frame_bytes: 0,
+                index,
+                frames: Vec::new(),
+            }),
+        }
+    }
+
+    pub fn with_level(mut self, level: i32) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn with_frame_bytes(mut self, frame_bytes: usize) -> Self {
+        self.frame_bytes = frame_bytes.max(1);
+        self
+    }
+
+    pub fn with_projection(mut self, projection: FieldProjection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    /// Frames closed so far
+    pub fn frames(&self) -> Vec<SlotFrame> {
+        self.state.lock().frames.clone()
+    }
+
+    /// Appends an already rendered JSON line (without the newline) of `slot`
+    pub(crate) fn write_line(&self, slot: u64, line: &[u8]) -> AnyResult<()> {
+        let mut state = self.state.lock();
+        if state.encoder.is_some()
+            && state.frame_bytes >= self.frame_bytes
+            && slot != state.frame.last_slot
+        {
+            state.close()?;
+        }
+        if state.encoder.is_none() {
+            state.open(self.level, slot)?;
+        }
+        let state = &mut *state;
+        let encoder = state.encoder.as_mut().expect("frame is open");
+        encoder.write_all(line)?;
+        encoder.write_all(b"\n")?;
+        state.frame_bytes += line.len() + 1;
+        state.frame.events += 1;
+        // 乱序到达的 slot 也计入当前帧的范围
+        state.frame.first_slot = state.frame.first_slot.min(slot);
+        state.frame.last_slot = state.frame.last_slot.max(slot);
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> EventSink for SlotFramedZstdSink<W> {
+    fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+        let mut line = event.to_json();
+        if !self.projection.is_empty() {
+            line = self.projection.apply(line);
+        }
+        self.write_line(event.slot(), &serde_json::to_vec(&line)?)
+    }
+
+    /// Closes the open frame, making everything written so far readable
+    fn flush(&self) -> AnyResult<()> {
+        let mut state = self.state.lock();
+        state.close()?;
+        if let Some(writer) = state.writer.as_mut() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> Drop for SlotFramedZstdSink<W> {
+    fn drop(&mut self) {
+        if let Err(e) = self.state.get_mut().close() {
+            log::error!("Failed to finish zstd frame: {}", e);
+        }
+    }
+}
+
+/// Reads a slot-framed archive starting at a slot
+pub struct SlotFrameReader<R: Read + Seek = File> {
+    archive: R,
+    frames: Vec<SlotFrame>,
+}
+
+impl SlotFrameReader<File> {
+    /// Opens the archive and its `<archive>.idx` index
+    pub fn open(path: impl AsRef<Path>) -> AnyResult<Self> {
+        let frames = load_slot_index(slot_index_path(path.as_ref()))?;
+        Ok(Self::new(File::open(path)?, frames))
+    }
+}
+
+impl<R: Read + Seek> SlotFrameReader<R> {
+    pub fn new(archive: R, mut frames: Vec<SlotFrame>) -> Self {
+        frames.sort_by_key(|frame| frame.offset);
+        Self { archive, frames }
+    }
+
+    pub fn frames(&self) -> &[SlotFrame] {
+        &self.frames
+    }
+
+    /// Decompressed lines from the first frame that may hold `slot` to the end
+    ///
+    /// Frames are cut at slot changes, so only the lines of the first frame need
+    /// filtering by the caller (lines of lower slots written out of order).
+    pub fn lines_from_slot(
+        &mut self,
+        slot: u64,
+    ) -> AnyResult<impl Iterator<Item = std::io::Result<String>> + '_> {
+        let offset = self
+            .frames
+            .iter()
+            .find(|frame| frame.last_slot >= slot)
+            .map(|frame| frame.offset)
+            .unwrap_or_else(|| self.frames.last().map_or(0, |frame| frame.offset + frame.len));
+        self.archive.seek(SeekFrom::Start(offset))?;
+        Ok(BufReader::new(Decoder::new(&mut self.archive)?).lines())
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::streaming::event_parser::common::EventMetadata;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    /// Shared buffer, readable after the sink is dropped
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_slot_framed_archive_seek() {
+        let archive = SharedBuf::default();
+        let sink = SlotFramedZstdSink::new(archive.clone(), None).with_frame_bytes(1);
+        for slot in [10, 10, 11, 12, 12] {
+            let event = RaydiumCpmmSwapEvent {
+                metadata: EventMetadata { slot, ..Default::default() },
+                amount_in: slot,
+                ..Default::default()
+            };
+            sink.send(&event).unwrap();
+        }
+        sink.flush().unwrap();
+        let frames = sink.frames();
+        assert_eq!(
+            frames.iter().map(|f| (f.first_slot, f.events)).collect::<Vec<_>>(),
+            vec![(10, 2), (11, 1), (12, 2)]
+        );
+        drop(sink);
+
+        let bytes = archive.0.lock().clone();
+        assert_eq!(
+            zstd::stream::decode_all(Cursor::new(&bytes)).unwrap().split(|b| *b == b'\n').count(),
+            6
+        );
+
+        let mut reader = SlotFrameReader::new(Cursor::new(bytes), frames);
+        let lines: Vec<String> = reader.lines_from_slot(11).unwrap().map(Result::unwrap).collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("\"amount_in\":11"));
+    }
+}
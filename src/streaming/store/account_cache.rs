@@ -0,0 +1,397 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::streaming::grpc::AccountPretty;
+
+/// Default upper bound on cached accounts
+pub const DEFAULT_MAX_CACHED_ACCOUNTS: usize = 50_000;
+/// Default number of recent slots [`AccountSnapshotCache::snapshot_at_slot`] can look back
+pub const DEFAULT_SNAPSHOT_HISTORY_SLOTS: u64 = 8;
+/// getMultipleAccounts 单次请求的最大账户数
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Raw state of an account as last seen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountSnapshot {
+    pub slot: u64,
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+impl AccountSnapshot {
+    fn same_state(&self, other: &AccountSnapshot) -> bool {
+        self.lamports == other.lamports && self.owner == other.owner && self.data == other.data
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivergenceKind {
+    /// RPC state differs from the streamed state
+    Changed,
+    /// Account exists in the cache but not on chain
+    Closed,
+}
+
+/// Streamed state disagreed with a full RPC re-fetch; the cache now holds the RPC state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheDivergence {
+    pub pubkey: Pubkey,
+    pub kind: DivergenceKind,
+    /// Slot of the last streamed update
+    pub cached_slot: u64,
+    /// Context slot of the RPC response
+    pub rpc_slot: u64,
+}
+
+/// Frozen view of the cache, see [`AccountSnapshotCache::snapshot_at_slot`]
+///
+/// Values are shared with the cache, so taking a view copies no account data; later
+/// updates replace the cache's entries and leave the view untouched.
+#[derive(Debug, Clone, Default)]
+pub struct AccountCacheView {
+    /// Every account is in its latest state at or before this slot
+    pub slot: u64,
+    accounts: HashMap<Pubkey, Arc<AccountSnapshot>>,
+}
+
+impl AccountCacheView {
+    pub fn get(&self, pubkey: &Pubkey) -> Option<&AccountSnapshot> {
+        self.accounts.get(pubkey).map(Arc::as_ref)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Pubkey, &AccountSnapshot)> {
+        self.accounts.iter().map(|(pubkey, snapshot)| (pubkey, snapshot.as_ref()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+#[derive(Default)]
+struct CacheState {
+    accounts: HashMap<Pubkey, Arc<AccountSnapshot>>,
+    /// 被覆盖但仍在历史窗口内的旧版本，按 slot 升序
+    history: HashMap<Pubkey, VecDeque<Arc<AccountSnapshot>>>,
+    highest_slot: u64,
+    /// 需要定期校验但尚未收到推送的账户
+    tracked: HashSet<Pubkey>,
+}
+
+impl CacheState {
+    /// 仍能给出一致视图的最早 slot
+    fn horizon(&self, history_slots: u64) -> u64 {
+        self.highest_slot.saturating_sub(history_slots)
+    }
+
+    /// 丢弃在历史窗口开始前就已被覆盖的版本
+    fn prune_history(&mut self, history_slots: u64) {
+        let horizon = self.horizon(history_slots);
+        let accounts = &self.accounts;
+        self.history.retain(|pubkey, versions| {
+            while !versions.is_empty() {
+                let successor = versions.get(1).or(accounts.get(pubkey));
+                if successor.is_some_and(|next| next.slot > horizon) {
+                    break;
+                }
+                versions.pop_front();
+            }
+            !versions.is_empty()
+        });
+    }
+}
+
+/// Latest raw state of streamed accounts, reconciled against RPC snapshots
+///
+/// Geyser only pushes accounts when they change, so a dropped update leaves the
+/// consumer with stale pool state and nothing to notice it by. Feeding account
+/// updates here (see `YellowstoneGrpc::enable_snapshot_diff`) and periodically
+/// calling `refresh` re-fetches every cached or tracked account and reports a
+/// [`CacheDivergence`] for each mismatch. Accounts seen for the first time via RPC
+/// are seeded silently, since an unchanged account is never streamed.
+///
+/// [`snapshot_at_slot`](Self::snapshot_at_slot) gives strategy code a consistent view
+/// of all accounts at a slot boundary while updates keep flowing in; superseded states
+/// are kept for the last `history_slots` slots for that purpose.
+pub struct AccountSnapshotCache {
+    max_accounts: usize,
+    history_slots: u64,
+    state: RwLock<CacheState>,
+}
+
+impl Default for AccountSnapshotCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CACHED_ACCOUNTS)
+    }
+}
+
+impl AccountSnapshotCache {
+    pub fn new(max_accounts: usize) -> Self {
+        Self {
+            max_accounts: max_accounts.max(1),
+            history_slots: DEFAULT_SNAPSHOT_HISTORY_SLOTS,
+            state: RwLock::new(CacheState::default()),
+        }
+    }
+
+    /// Sets how many recent slots `snapshot_at_slot` can look back, 0 keeps no history
+    pub fn with_history_slots(mut self, history_slots: u64) -> Self {
+        self.history_slots = history_slots;
+        self
+    }
+
+    /// Records a streamed account update, older slots are ignored
+    pub fn on_account(&self, account: &AccountPretty) {
+        let snapshot = AccountSnapshot {
+            slot: account.slot,
+            lamports: account.lamports,
+            owner: account.owner,
+            data: account.data.clone(),
+        };
+        self.insert(account.pubkey, snapshot);
+    }
+
+    /// Adds accounts to re-fetch even if they were never streamed
+    pub fn track(&self, pubkeys: impl IntoIterator<Item = Pubkey>) {
+        self.state.write().tracked.extend(pubkeys);
+    }
+
+    /// Forgets cached and tracked accounts, returns how many were cached
+    pub fn remove(&self, pubkeys: &[Pubkey]) -> usize {
+        let mut state = self.state.write();
+        pubkeys
+            .iter()
+            .filter(|pubkey| {
+                state.tracked.remove(*pubkey);
+                state.history.remove(*pubkey);
+                state.accounts.remove(*pubkey).is_some()
+            })
+            .count()
+    }
+
+    pub fn get(&self, pubkey: &Pubkey) -> Option<AccountSnapshot> {
+        self.state.read().accounts.get(pubkey).map(|snapshot| snapshot.as_ref().clone())
+    }
+
+    /// Latest state without copying the account data
+    pub fn get_shared(&self, pubkey: &Pubkey) -> Option<Arc<AccountSnapshot>> {
+        self.state.read().accounts.get(pubkey).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.read().accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copy of every cached account
+    pub fn snapshot(&self) -> Vec<(Pubkey, AccountSnapshot)> {
+        let state = self.state.read();
+        state
+            .accounts
+            .iter()
+            .map(|(pubkey, snapshot)| (*pubkey, snapshot.as_ref().clone()))
+            .collect()
+    }
+
+    /// Consistent view of every account as of the end of `slot`
+    ///
+    /// Each account is in its latest state at or before `slot`; accounts first seen
+    /// after it are left out. None when `slot` is older than the retained history
+    /// (`history_slots` behind the highest slot seen), since states superseded before
+    /// that are gone.
+    pub fn snapshot_at_slot(&self, slot: u64) -> Option<AccountCacheView> {
+        let state = self.state.read();
+        if slot < state.horizon(self.history_slots) {
+            return None;
+        }
+        let accounts = state
+            .accounts
+            .iter()
+            .filter_map(|(pubkey, latest)| {
+                if latest.slot <= slot {
+                    return Some((*pubkey, latest.clone()));
+                }
+                let versions = state.history.get(pubkey)?;
+                let version = versions.iter().rev().find(|version| version.slot <= slot)?;
+                Some((*pubkey, version.clone()))
+            })
+            .collect();
+        Some(AccountCacheView { slot, accounts })
+    }
+
+    /// View of the latest state of every account
+    pub fn snapshot_latest(&self) -> AccountCacheView {
+        let state = self.state.read();
+        AccountCacheView { slot: state.highest_slot, accounts: state.accounts.clone() }
+    }
+
+    /// Compares an RPC result with the cache and corrects it
+    ///
+    /// `rpc` is None when the account does not exist at `rpc_slot`. Accounts
+    /// streamed after `rpc_slot` are left alone.
+    pub fn reconcile(
+        &self,
+        pubkey: Pubkey,
+        rpc_slot: u64,
+        rpc: Option<AccountSnapshot>,
+    ) -> Option<CacheDivergence> {
+        let mut state = self.state.write();
+        let Some(cached) = state.accounts.get(&pubkey) else {
+            drop(state);
+            if let Some(fetched) = rpc {
+                self.insert(pubkey, fetched);
+            }
+            return None;
+        };
+        let cached_slot = cached.slot;
+        if cached_slot > rpc_slot {
+            return None;
+        }
+        let kind = match &rpc {
+            Some(fetched) if cached.same_state(fetched) => return None,
+            Some(_) => DivergenceKind::Changed,
+            None => DivergenceKind::Closed,
+        };
+        // RPC 修正的是错误状态，旧版本不再可信
+        state.history.remove(&pubkey);
+        match rpc {
+            Some(fetched) => state.accounts.insert(pubkey, Arc::new(fetched)),
+            None => state.accounts.remove(&pubkey),
+        };
+        Some(CacheDivergence { pubkey, kind, cached_slot, rpc_slot })
+    }
+
+    pub(crate) fn insert(&self, pubkey: Pubkey, snapshot: AccountSnapshot) {
+        let mut guard = self.state.write();
+        let state = &mut *guard;
+        let slot = snapshot.slot;
+        if let Some(cached) = state.accounts.get_mut(&pubkey) {
+            if slot < cached.slot {
+                return;
+            }
+            let previous = std::mem::replace(cached, Arc::new(snapshot));
+            // 同一 slot 内的多次更新只保留最后一次
+            if self.history_slots > 0 && previous.slot < slot {
+                state.history.entry(pubkey).or_default().push_back(previous);
+            }
+        } else if state.accounts.len() < self.max_accounts {
+            // 超过上限时不再接收新账户，已缓存账户照常更新
+            state.accounts.insert(pubkey, Arc::new(snapshot));
+        } else {
+            return;
+        }
+        if slot > state.highest_slot {
+            state.highest_slot = slot;
+            state.prune_history(self.history_slots);
+        }
+    }
+
+    fn refresh_keys(&self) -> Vec<Pubkey> {
+        let state = self.state.read();
+        let mut keys: HashSet<Pubkey> = state.accounts.keys().copied().collect();
+        keys.extend(state.tracked.iter().copied());
+        keys.into_iter().collect()
+    }
+}
+
+#[cfg(feature = "transport")]
+mod rpc {
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::{AccountSnapshot, AccountSnapshotCache, CacheDivergence, MAX_MULTIPLE_ACCOUNTS};
+    use crate::common::{AnyResult, SolanaRpcClient};
+
+    impl AccountSnapshotCache {
+        /// Re-fetches all cached and tracked accounts and reconciles them
+        pub async fn refresh(&self, rpc: &SolanaRpcClient) -> AnyResult<Vec<CacheDivergence>> {
+            let mut divergences = Vec::new();
+            for chunk in self.refresh_keys().chunks(MAX_MULTIPLE_ACCOUNTS) {
+                let response =
+                    rpc.get_multiple_accounts_with_commitment(chunk, rpc.commitment()).await?;
+                let rpc_slot = response.context.slot;
+                for (pubkey, account) in chunk.iter().zip(response.value) {
+                    let snapshot = account.map(|account| AccountSnapshot {
+                        slot: rpc_slot,
+                        lamports: account.lamports,
+                        owner: Pubkey::new_from_array(account.owner.to_bytes()),
+                        data: account.data,
+                    });
+                    divergences.extend(self.reconcile(*pubkey, rpc_slot, snapshot));
+                }
+            }
+            Ok(divergences)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(slot: u64, data: u8) -> AccountSnapshot {
+        AccountSnapshot { slot, lamports: 1, owner: Pubkey::default(), data: vec![data] }
+    }
+
+    #[test]
+    fn test_reconcile_corrects_cache() {
+        let cache = AccountSnapshotCache::default();
+        let (pool, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        cache.insert(pool, snapshot(10, 1));
+
+        assert_eq!(cache.reconcile(pool, 12, Some(snapshot(12, 1))), None);
+        let divergence = cache.reconcile(pool, 12, Some(snapshot(12, 2))).unwrap();
+        assert_eq!((divergence.kind, divergence.cached_slot), (DivergenceKind::Changed, 10));
+        assert_eq!(cache.get(&pool).unwrap().data, vec![2]);
+
+        // 推送已领先于 RPC 时不比较
+        cache.insert(pool, snapshot(20, 3));
+        assert_eq!(cache.reconcile(pool, 15, None), None);
+        assert_eq!(cache.reconcile(pool, 21, None).unwrap().kind, DivergenceKind::Closed);
+        assert!(cache.get(&pool).is_none());
+
+        // 首次经 RPC 见到的账户直接写入缓存
+        assert_eq!(cache.reconcile(other, 21, Some(snapshot(21, 4))), None);
+        assert_eq!(cache.get(&other).unwrap().slot, 21);
+    }
+
+    #[test]
+    fn test_snapshot_at_slot() {
+        let cache = AccountSnapshotCache::default().with_history_slots(4);
+        let (pool, vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        cache.insert(pool, snapshot(10, 1));
+        cache.insert(vault, snapshot(10, 1));
+        let view = cache.snapshot_at_slot(10).unwrap();
+
+        cache.insert(pool, snapshot(11, 2));
+        cache.insert(pool, snapshot(12, 3));
+        cache.insert(vault, snapshot(12, 3));
+        // 旧视图不受后续更新影响
+        assert_eq!(view.get(&pool).unwrap().data, vec![1]);
+
+        let view = cache.snapshot_at_slot(11).unwrap();
+        assert_eq!((view.get(&pool).unwrap().data[0], view.get(&vault).unwrap().data[0]), (2, 1));
+        let latest = cache.snapshot_latest();
+        assert_eq!((latest.slot, latest.get(&pool).unwrap().data[0]), (12, 3));
+        assert!(Arc::ptr_eq(&cache.get_shared(&pool).unwrap(), &latest.accounts[&pool]));
+
+        // 账户在视图 slot 之后才出现时不包含
+        cache.insert(Pubkey::new_unique(), snapshot(16, 0));
+        let view = cache.snapshot_at_slot(12).unwrap();
+        assert_eq!(view.len(), 2);
+        assert!(cache.snapshot_at_slot(11).is_none());
+        assert_eq!(cache.state.read().history.len(), 0);
+    }
+}
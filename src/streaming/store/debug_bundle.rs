@@ -0,0 +1,338 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use parking_lot::{Mutex, RwLock};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use yellowstone_grpc_proto::geyser::SubscribeUpdateTransaction;
+
+use super::{AccountSnapshot, AccountSnapshotCache};
+use crate::common::AnyResult;
+use crate::streaming::event_parser::{core::event_parser::EventParser, UnifiedEvent};
+use crate::streaming::sink::EventSink;
+
+/// Default number of raw transaction updates kept for a bundle
+pub const DEFAULT_DEBUG_RECENT_UPDATES: usize = 2_000;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const UPDATES_FILE: &str = "updates.bin";
+const ACCOUNTS_FILE: &str = "accounts.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DebugDumpConfig {
+    /// 转储目录，每个 bundle 一个子目录
+    pub dir: PathBuf,
+    pub recent_updates: usize,
+    /// 两次转储的最小间隔
+    pub min_interval_secs: u64,
+    /// 窗口内解析失败达到该次数即转储，0 表示不按解析失败触发
+    pub parse_error_threshold: usize,
+    pub parse_error_window_secs: u64,
+}
+
+impl Default for DebugDumpConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("debug-dumps"),
+            recent_updates: DEFAULT_DEBUG_RECENT_UPDATES,
+            min_interval_secs: 300,
+            parse_error_threshold: 50,
+            parse_error_window_secs: 10,
+        }
+    }
+}
+
+/// Contents of a bundle's `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub reason: String,
+    pub created_at_ms: i64,
+    pub updates: usize,
+    pub accounts: usize,
+    /// Client configuration at dump time, if one was attached
+    pub config: Option<serde_json::Value>,
+}
+
+#[derive(Default)]
+struct RecorderState {
+    updates: VecDeque<SubscribeUpdateTransaction>,
+    parse_errors: VecDeque<Instant>,
+    last_dump: Option<Instant>,
+}
+
+/// Keeps recent raw updates and writes them to disk when something looks wrong
+///
+/// A bundle holds the last `recent_updates` transaction updates exactly as received,
+/// the attached account cache and the client configuration, so the anomaly can be
+/// reproduced offline with [`DebugBundle::load`] and [`DebugBundle::replay`].
+/// Parse failure spikes and cache divergences trigger a dump from the client (see
+/// `YellowstoneGrpc::enable_debug_dumps`); other detectors call [`trigger`](Self::trigger).
+/// Dumps are rate limited by `min_interval_secs`.
+pub struct DebugRecorder {
+    config: DebugDumpConfig,
+    state: Mutex<RecorderState>,
+    cache: RwLock<Option<Arc<AccountSnapshotCache>>>,
+    client_config: RwLock<Option<serde_json::Value>>,
+}
+
+impl DebugRecorder {
+    pub fn new(config: DebugDumpConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(RecorderState::default()),
+            cache: RwLock::new(None),
+            client_config: RwLock::new(None),
+        }
+    }
+
+    pub fn config(&self) -> &DebugDumpConfig {
+        &self.config
+    }
+
+    /// Includes the cache's accounts in later bundles
+    pub fn attach_cache(&self, cache: Arc<AccountSnapshotCache>) {
+        *self.cache.write() = Some(cache);
+    }
+
+    /// Stores the configuration written to later bundles
+    pub fn set_config<T: Serialize>(&self, config: &T) -> AnyResult<()> {
+        *self.client_config.write() = Some(serde_json::to_value(config)?);
+        Ok(())
+    }
+
+    pub fn record_transaction(&self, update: SubscribeUpdateTransaction) {
+        let mut state = self.state.lock();
+        if state.updates.len() >= self.config.recent_updates.max(1) {
+            state.updates.pop_front();
+        }
+        state.updates.push_back(update);
+    }
+
+    /// Counts a parse failure and dumps once the configured spike is reached
+    pub fn record_parse_error(&self) -> Option<PathBuf> {
+        if self.config.parse_error_threshold == 0 {
+            return None;
+        }
+        let window = Duration::from_secs(self.config.parse_error_window_secs);
+        let now = Instant::now();
+        let errors = {
+            let mut state = self.state.lock();
+            while state.parse_errors.front().is_some_and(|at| now.duration_since(*at) > window) {
+                state.parse_errors.pop_front();
+            }
+            state.parse_errors.push_back(now);
+            state.parse_errors.len()
+        };
+        if errors < self.config.parse_error_threshold {
+            return None;
+        }
+        self.state.lock().parse_errors.clear();
+        self.trigger_logged(&format!("{errors} parse errors"))
+    }
+
+    /// Dumps a bundle unless one was written within `min_interval_secs`
+    ///
+    /// Returns the bundle directory, or None when rate limited.
+    pub fn trigger(&self, reason: &str) -> AnyResult<Option<PathBuf>> {
+        let updates: Vec<SubscribeUpdateTransaction> = {
+            let mut state = self.state.lock();
+            let min_interval = Duration::from_secs(self.config.min_interval_secs);
+            if state.last_dump.is_some_and(|at| at.elapsed() < min_interval) {
+                return Ok(None);
+            }
+            state.last_dump = Some(Instant::now());
+            state.updates.iter().cloned().collect()
+        };
+        let accounts = self.cache.read().as_ref().map(|cache| cache.snapshot()).unwrap_or_default();
+        let created_at_ms = chrono::Utc::now().timestamp_millis();
+        let manifest = BundleManifest {
+            reason: reason.to_string(),
+            created_at_ms,
+            updates: updates.len(),
+            accounts: accounts.len(),
+            config: self.client_config.read().clone(),
+        };
+        let dir = self.config.dir.join(format!("{}-{}", created_at_ms, dir_name(reason)));
+        write_bundle(&dir, &manifest, &updates, &accounts)
+            .with_context(|| format!("failed to write debug bundle {}", dir.display()))?;
+        Ok(Some(dir))
+    }
+
+    /// [`trigger`](Self::trigger) for internal detectors, errors are logged
+    pub(crate) fn trigger_logged(&self, reason: &str) -> Option<PathBuf> {
+        match self.trigger(reason) {
+            Ok(Some(dir)) => {
+                log::warn!("Dumped debug bundle ({}) to {}", reason, dir.display());
+                Some(dir)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                log::error!("Debug dump failed: {e:?}");
+                None
+            }
+        }
+    }
+}
+
+/// 原因文本转为可用作目录名的形式
+fn dir_name(reason: &str) -> String {
+    let name: String = reason
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .take(48)
+        .collect();
+    name.trim_matches('-').to_string()
+}
+
+fn write_bundle(
+    dir: &Path,
+    manifest: &BundleManifest,
+    updates: &[SubscribeUpdateTransaction],
+    accounts: &[(Pubkey, AccountSnapshot)],
+) -> AnyResult<()> {
+    fs::create_dir_all(dir)?;
+    serde_json::to_writer_pretty(fs::File::create(dir.join(MANIFEST_FILE))?, manifest)?;
+    serde_json::to_writer(BufWriter::new(fs::File::create(dir.join(ACCOUNTS_FILE))?), accounts)?;
+    // 长度前缀（u32 LE）+ protobuf 编码，与收到的更新逐字节一致
+    let mut writer = BufWriter::new(fs::File::create(dir.join(UPDATES_FILE))?);
+    for update in updates {
+        let bytes = update.encode_to_vec();
+        writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        writer.write_all(&bytes)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// A bundle read back from disk
+#[derive(Debug, Clone)]
+pub struct DebugBundle {
+    pub manifest: BundleManifest,
+    pub updates: Vec<SubscribeUpdateTransaction>,
+    pub accounts: Vec<(Pubkey, AccountSnapshot)>,
+}
+
+impl DebugBundle {
+    pub fn load(dir: impl AsRef<Path>) -> AnyResult<Self> {
+        let dir = dir.as_ref();
+        let manifest = serde_json::from_reader(fs::File::open(dir.join(MANIFEST_FILE))?)?;
+        let accounts =
+            serde_json::from_reader(BufReader::new(fs::File::open(dir.join(ACCOUNTS_FILE))?))?;
+        let mut bytes = Vec::new();
+        fs::File::open(dir.join(UPDATES_FILE))?.read_to_end(&mut bytes)?;
+        let mut updates = Vec::new();
+        let mut rest = bytes.as_slice();
+        while !rest.is_empty() {
+            anyhow::ensure!(rest.len() >= 4, "truncated update length");
+            let (len, tail) = rest.split_at(4);
+            let len = u32::from_le_bytes(len.try_into()?) as usize;
+            anyhow::ensure!(tail.len() >= len, "truncated update");
+            let (update, tail) = tail.split_at(len);
+            updates.push(SubscribeUpdateTransaction::decode(update)?);
+            rest = tail;
+        }
+        Ok(Self { manifest, updates, accounts })
+    }
+
+    /// Seeds `cache` with the bundled accounts
+    pub fn restore_cache(&self, cache: &AccountSnapshotCache) {
+        for (pubkey, snapshot) in &self.accounts {
+            cache.insert(*pubkey, snapshot.clone());
+        }
+    }
+
+    /// Parses the bundled updates in order and sends the events to `sink`
+    ///
+    /// Updates that fail to parse are logged and skipped. Returns the number of events.
+    pub async fn replay(&self, parser: &EventParser, sink: &dyn EventSink) -> AnyResult<usize> {
+        let mut count = 0;
+        for update in &self.updates {
+            let Some(info) = update.transaction.clone() else {
+                continue;
+            };
+            let signature = Signature::try_from(info.signature.as_slice()).unwrap_or_default();
+            let transaction_index = Some(info.index);
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let collected = events.clone();
+            let callback = Arc::new(move |event: Box<dyn UnifiedEvent>| {
+                collected.lock().push(event);
+            });
+            if let Err(e) = parser
+                .parse_grpc_transaction_owned(
+                    info,
+                    signature,
+                    Some(update.slot),
+                    None,
+                    0,
+                    None,
+                    transaction_index,
+                    callback,
+                )
+                .await
+            {
+                log::warn!("Failed to replay {} from slot {}: {}", signature, update.slot, e);
+                continue;
+            }
+            for event in events.lock().drain(..) {
+                sink.send(event.as_ref())?;
+                count += 1;
+            }
+        }
+        sink.flush()?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
+
+    fn update(slot: u64) -> SubscribeUpdateTransaction {
+        let info = SubscribeUpdateTransactionInfo {
+            signature: vec![slot as u8; 64],
+            index: slot,
+            ..Default::default()
+        };
+        SubscribeUpdateTransaction { transaction: Some(info), slot }
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let dir = std::env::temp_dir().join(format!("debug-bundle-{}", std::process::id()));
+        let config = DebugDumpConfig {
+            dir: dir.clone(),
+            recent_updates: 3,
+            parse_error_threshold: 2,
+            ..Default::default()
+        };
+        let recorder = DebugRecorder::new(config);
+        let cache = Arc::new(AccountSnapshotCache::default());
+        let pool = Pubkey::new_unique();
+        let snapshot =
+            AccountSnapshot { slot: 7, lamports: 1, owner: Pubkey::default(), data: vec![1, 2] };
+        cache.insert(pool, snapshot.clone());
+        recorder.attach_cache(cache);
+        for slot in 1..=5 {
+            recorder.record_transaction(update(slot));
+        }
+
+        assert!(recorder.record_parse_error().is_none());
+        let path = recorder.record_parse_error().unwrap();
+        // 间隔内不再转储
+        assert!(recorder.trigger("absurd profit").unwrap().is_none());
+
+        let bundle = DebugBundle::load(&path).unwrap();
+        assert_eq!(bundle.manifest.reason, "2 parse errors");
+        assert_eq!(bundle.updates, vec![update(3), update(4), update(5)]);
+        assert_eq!(bundle.accounts, vec![(pool, snapshot)]);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::RwLock;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::common::AnyResult;
+use crate::streaming::analytics::TradeInfo;
+use crate::streaming::event_parser::common::high_performance_clock::{system_clock, Clock};
+use crate::streaming::event_parser::common::EventType;
+use crate::streaming::event_parser::core::account_event_parser::{
+    TokenAccountEvent, TokenInfoEvent,
+};
+use crate::streaming::event_parser::UnifiedEvent;
+use crate::streaming::sink::EventSink;
+
+/// Default retention window (five minutes)
+pub const DEFAULT_RETENTION: Duration = Duration::from_secs(300);
+/// Default upper bound on retained events
+pub const DEFAULT_MAX_EVENTS: usize = 100_000;
+
+struct StoredEvent {
+    seq: u64,
+    /// 存入时间（微秒，来自 EventStore 的时钟）
+    stored_at: i64,
+    event: Box<dyn UnifiedEvent>,
+    mints: Vec<Pubkey>,
+    wallets: Vec<Pubkey>,
+}
+
+impl StoredEvent {
+    fn new(seq: u64, stored_at: i64, event: &dyn UnifiedEvent) -> Self {
+        let mut mints = Vec::new();
+        let mut wallets = Vec::new();
+        if let Some(trade) = TradeInfo::from_event(event) {
+            mints.extend([trade.input_mint, trade.output_mint]);
+            wallets.push(trade.user);
+        } else if let Some(e) = event.as_any().downcast_ref::<TokenAccountEvent>() {
+            mints.push(e.mint);
+            wallets.push(e.token_owner);
+        } else if let Some(e) = event.as_any().downcast_ref::<TokenInfoEvent>() {
+            mints.push(e.pubkey);
+        }
+        mints.retain(|m| *m != Pubkey::default());
+        wallets.retain(|w| *w != Pubkey::default());
+        Self { seq, stored_at, event: event.clone_boxed(), mints, wallets }
+    }
+}
+
+#[derive(Default)]
+struct StoreState {
+    events: VecDeque<StoredEvent>,
+    next_seq: u64,
+    /// signature -> sequence numbers of its events
+    signatures: HashMap<Signature, Vec<u64>>,
+}
+
+impl StoreState {
+    fn get(&self, seq: u64) -> Option<&StoredEvent> {
+        let front = self.events.front()?.seq;
+        self.events.get(seq.checked_sub(front)? as usize)
+    }
+
+    fn pop_front(&mut self) {
+        let Some(stored) = self.events.pop_front() else {
+            return;
+        };
+        let signature = stored.event.signature();
+        if let Some(seqs) = self.signatures.get_mut(signature) {
+            seqs.retain(|seq| *seq != stored.seq);
+            if seqs.is_empty() {
+                self.signatures.remove(signature);
+            }
+        }
+    }
+}
+
+/// Ring buffer of recently parsed events
+///
+/// Keeps events for `retention` (and at most `max_events`) so tools can answer
+/// "what just happened" without an external database. Feed it from the stream
+/// callback, or wrap it in an `Arc` and use [`sink_callback`](crate::streaming::sink::sink_callback).
+///
+/// Mint and wallet queries cover swaps (via [`TradeInfo`]) and token account/mint updates.
+/// Signature lookups go through an index and do not scan the buffer.
+pub struct EventStore {
+    retention: Duration,
+    max_events: usize,
+    clock: Arc<dyn Clock>,
+    state: RwLock<StoreState>,
+}
+
+impl Default for EventStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_RETENTION, DEFAULT_MAX_EVENTS)
+    }
+}
+
+impl EventStore {
+    pub fn new(retention: Duration, max_events: usize) -> Self {
+        Self {
+            retention,
+            max_events: max_events.max(1),
+            clock: system_clock(),
+            state: RwLock::new(StoreState::default()),
+        }
+    }
+
+    /// Replaces the time source used for retention (e.g. a `MockClock` in tests)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn retention(&self) -> Duration {
+        self.retention
+    }
+
+    /// Stores a copy of the event and evicts expired ones
+    pub fn insert(&self, event: &dyn UnifiedEvent) {
+        let mut state = self.state.write();
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.signatures.entry(*event.signature()).or_default().push(seq);
+        let now = self.clock.now_micros();
+        state.events.push_back(StoredEvent::new(seq, now, event));
+        while state.events.len() > self.max_events {
+            state.pop_front();
+        }
+        self.evict_expired(&mut state, now);
+    }
+
+    /// Drops events older than the retention window
+    pub fn prune(&self) {
+        self.evict_expired(&mut self.state.write(), self.clock.now_micros());
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.read().events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.read().events.is_empty()
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.state.write();
+        state.events.clear();
+        state.signatures.clear();
+    }
+
+    /// Whether any event of the transaction is still in the window
+    pub fn contains_signature(&self, signature: &Signature) -> bool {
+        !self.by_signature(signature).is_empty()
+    }
+
+    /// All events of a transaction, in arrival order
+    pub fn by_signature(&self, signature: &Signature) -> Vec<Box<dyn UnifiedEvent>> {
+        let state = self.state.read();
+        let now = self.clock.now_micros();
+        let Some(seqs) = state.signatures.get(signature) else {
+            return Vec::new();
+        };
+        seqs.iter()
+            .filter_map(|seq| state.get(*seq))
+            .filter(|stored| self.is_live(stored, now))
+            .map(|stored| stored.event.clone_boxed())
+            .collect()
+    }
+
+    /// Events touching a mint (swap input/output, token account or mint update)
+    pub fn by_mint(&self, mint: &Pubkey) -> Vec<Box<dyn UnifiedEvent>> {
+        self.query(|stored| stored.mints.contains(mint))
+    }
+
+    /// Events initiated by or owned by a wallet (swap user, token account owner)
+    pub fn by_wallet(&self, wallet: &Pubkey) -> Vec<Box<dyn UnifiedEvent>> {
+        self.query(|stored| stored.wallets.contains(wallet))
+    }
+
+    pub fn by_type(&self, event_type: &EventType) -> Vec<Box<dyn UnifiedEvent>> {
+        self.query(|stored| stored.event.event_type() == *event_type)
+    }
+
+    /// The `limit` most recent events, newest first
+    pub fn latest(&self, limit: usize) -> Vec<Box<dyn UnifiedEvent>> {
+        let state = self.state.read();
+        let now = self.clock.now_micros();
+        state
+            .events
+            .iter()
+            .rev()
+            .filter(|stored| self.is_live(stored, now))
+            .take(limit)
+            .map(|stored| stored.event.clone_boxed())
+            .collect()
+    }
+
+    fn query(&self, predicate: impl Fn(&StoredEvent) -> bool) -> Vec<Box<dyn UnifiedEvent>> {
+        let state = self.state.read();
+        let now = self.clock.now_micros();
+        state
+            .events
+            .iter()
+            .filter(|stored| self.is_live(stored, now) && predicate(stored))
+            .map(|stored| stored.event.clone_boxed())
+            .collect()
+    }
+
+    fn is_live(&self, stored: &StoredEvent, now: i64) -> bool {
+        now - stored.stored_at <= self.retention.as_micros() as i64
+    }
+
+    fn evict_expired(&self, state: &mut StoreState, now: i64) {
+        while state.events.front().is_some_and(|stored| !self.is_live(stored, now)) {
+            state.pop_front();
+        }
+    }
+}
+
+#[cfg(feature = "transport")]
+mod rpc {
+    use std::sync::Arc;
+
+    use parking_lot::Mutex;
+    use solana_commitment_config::CommitmentConfig;
+    use solana_rpc_client_api::config::RpcTransactionConfig;
+    use solana_sdk::signature::Signature;
+    use solana_transaction_status::UiTransactionEncoding;
+
+    use super::EventStore;
+    use crate::common::{AnyResult, SolanaRpcClient};
+    use crate::streaming::event_parser::core::event_parser::EventParser;
+    use crate::streaming::event_parser::UnifiedEvent;
+
+    impl EventStore {
+        /// Events of a transaction from the window, or fetched via RPC and parsed on demand
+        ///
+        /// Fetched events are returned but not stored, so they never show up in
+        /// the other queries with a misleading arrival time.
+        pub async fn parse_signature(
+            &self,
+            rpc: &SolanaRpcClient,
+            parser: &EventParser,
+            signature: &Signature,
+        ) -> AnyResult<Vec<Box<dyn UnifiedEvent>>> {
+            let events = self.by_signature(signature);
+            if !events.is_empty() {
+                return Ok(events);
+            }
+            let transaction = rpc
+                .get_transaction_with_config(
+                    signature,
+                    RpcTransactionConfig {
+                        encoding: Some(UiTransactionEncoding::Base64),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        max_supported_transaction_version: Some(0),
+                    },
+                )
+                .await?;
+            let parsed = Arc::new(Mutex::new(Vec::new()));
+            let sink = parsed.clone();
+            parser
+                .parse_encoded_confirmed_transaction_with_status_meta(
+                    *signature,
+                    transaction,
+                    Arc::new(move |event: &Box<dyn UnifiedEvent>| sink.lock().push(event.clone())),
+                )
+                .await?;
+            let events = std::mem::take(&mut *parsed.lock());
+            Ok(events)
+        }
+    }
+}
+
+impl EventSink for EventStore {
+    fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+        self.insert(event);
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::high_performance_clock::MockClock;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    #[test]
+    fn test_event_store_retention() {
+        let clock = Arc::new(MockClock::new(1_000_000));
+        let store = EventStore::new(Duration::from_secs(10), 100).with_clock(clock.clone());
+        let first = RaydiumCpmmSwapEvent::default();
+        store.insert(&first);
+
+        clock.advance(Duration::from_secs(6));
+        let mut second = RaydiumCpmmSwapEvent::default();
+        second.metadata.signature = Signature::new_unique();
+        store.insert(&second);
+        assert_eq!(store.latest(10).len(), 2);
+
+        clock.advance(Duration::from_secs(5));
+        assert!(!store.contains_signature(&first.metadata.signature));
+        assert!(store.contains_signature(&second.metadata.signature));
+        assert_eq!(store.len(), 2);
+
+        store.prune();
+        assert_eq!(store.len(), 1);
+    }
+}
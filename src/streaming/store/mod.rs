@@ -0,0 +1,11 @@
+// 存储模块 - 近期事件的内存缓存与查询
+pub mod account_cache;
+pub mod debug_bundle;
+pub mod event_store;
+pub mod slot_audit;
+
+// 重新导出主要类型
+pub use account_cache::*;
+pub use debug_bundle::*;
+pub use event_store::*;
+pub use slot_audit::*;
@@ -0,0 +1,521 @@
+use std::collections::BTreeMap;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Signature;
+
+use crate::common::AnyResult;
+use crate::streaming::event_parser::UnifiedEvent;
+use crate::streaming::sink::EventSink;
+
+/// Default number of streamed slots kept for verification
+pub const DEFAULT_AUDIT_SLOTS: usize = 512;
+/// Default distance from the newest streamed slot before a slot is verified,
+/// so the RPC node has it finalized
+pub const DEFAULT_FINALITY_LAG_SLOTS: u64 = 64;
+/// Default number of attempts for a slot whose block fetch keeps failing
+pub const DEFAULT_AUDIT_MAX_ATTEMPTS: u32 = 5;
+
+/// Identity of an event within its slot
+///
+/// Several events of one transaction differ by instruction position and type; identical
+/// keys (duplicate deliveries) are counted, not collapsed.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct AuditEventKey {
+    pub signature: Signature,
+    pub event_type: String,
+    pub outer_index: i64,
+    pub inner_index: Option<i64>,
+}
+
+impl AuditEventKey {
+    pub fn of(event: &dyn UnifiedEvent) -> Self {
+        Self {
+            signature: *event.signature(),
+            event_type: event.event_type().to_string(),
+            outer_index: event.outer_index(),
+            inner_index: event.inner_index(),
+        }
+    }
+}
+
+/// Streamed events of one slot compared with a parse of its finalized block
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotAuditReport {
+    pub slot: u64,
+    pub block_events: usize,
+    pub streamed_events: usize,
+    pub matched: usize,
+    /// In the block but never streamed, once per missing occurrence
+    pub missed: Vec<AuditEventKey>,
+    /// Streamed but not in the block (e.g. from a fork, a parse difference or a
+    /// duplicate delivery), once per extra occurrence
+    pub extra: Vec<AuditEventKey>,
+}
+
+impl SlotAuditReport {
+    pub fn is_clean(&self) -> bool {
+        self.missed.is_empty() && self.extra.is_empty()
+    }
+}
+
+/// Totals over all verified slots
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotAuditStats {
+    pub slots_verified: u64,
+    pub clean_slots: u64,
+    pub matched: u64,
+    pub missed: u64,
+    pub extra: u64,
+    /// Slots without a block (skipped by the leader); streamed events count as extra
+    pub slots_skipped: u64,
+    /// Slots dropped unverified because the audit fell more than `max_slots` behind
+    pub slots_pruned: u64,
+    /// Slots given up after repeated block fetch errors
+    pub slots_failed: u64,
+}
+
+/// Occurrences of each event key in a slot
+type EventCounts = BTreeMap<AuditEventKey, usize>;
+
+#[derive(Default)]
+struct AuditState {
+    slots: BTreeMap<u64, EventCounts>,
+    /// 下一个待校验的 slot，按 slot 区间推进，没有任何流事件的 slot 同样校验
+    next_slot: Option<u64>,
+    newest_slot: u64,
+    /// next_slot 连续失败的次数
+    attempts: u32,
+    stats: SlotAuditStats,
+}
+
+impl AuditState {
+    /// Moves the verification cursor past `slot` and forgets its streamed events
+    fn finish(&mut self, slot: u64) -> EventCounts {
+        if self.next_slot.is_some_and(|next| next <= slot) {
+            self.next_slot = Some(slot + 1);
+            self.attempts = 0;
+        }
+        self.slots.remove(&slot).unwrap_or_default()
+    }
+}
+
+/// Occurrences of `a` in excess of those in `b`
+fn excess(a: &EventCounts, b: &EventCounts) -> Vec<AuditEventKey> {
+    a.iter()
+        .flat_map(|(key, count)| {
+            let surplus = count.saturating_sub(b.get(key).copied().unwrap_or(0));
+            std::iter::repeat_n(key.clone(), surplus)
+        })
+        .collect()
+}
+
+/// Correctness audit of the whole pipeline against finalized blocks
+///
+/// Add it as a sink (ahead of any filtering) so it records what was streamed per
+/// slot, then verify slots with [`verify_slot`](Self::verify_slot) or let
+/// [`spawn_periodic`](Self::spawn_periodic) do it: the block is fetched via
+/// `getBlock`, parsed with the same parser and both event sets are compared. The
+/// parser and the stream should cover the same protocols and event types, and failed
+/// transactions are skipped unless the stream includes them.
+///
+/// Verification walks every slot from the first streamed one on, so a slot whose events
+/// were all lost is audited too. At most `max_slots` slots are held: when verification
+/// falls further behind, the oldest slots are dropped unverified and counted in
+/// [`SlotAuditStats::slots_pruned`].
+pub struct SlotAuditor {
+    max_slots: usize,
+    finality_lag: u64,
+    include_failed: bool,
+    max_attempts: u32,
+    state: Mutex<AuditState>,
+}
+
+impl Default for SlotAuditor {
+    fn default() -> Self {
+        Self::new(DEFAULT_AUDIT_SLOTS)
+    }
+}
+
+impl SlotAuditor {
+    pub fn new(max_slots: usize) -> Self {
+        Self {
+            max_slots: max_slots.max(1),
+            finality_lag: DEFAULT_FINALITY_LAG_SLOTS,
+            include_failed: false,
+            max_attempts: DEFAULT_AUDIT_MAX_ATTEMPTS,
+            state: Mutex::new(AuditState::default()),
+        }
+    }
+
+    pub fn with_finality_lag(mut self, finality_lag: u64) -> Self {
+        self.finality_lag = finality_lag;
+        self
+    }
+
+    /// Also compare events of failed transactions, for streams that subscribe to them
+    pub fn with_failed_transactions(mut self, include_failed: bool) -> Self {
+        self.include_failed = include_failed;
+        self
+    }
+
+    /// Attempts per slot before a failing block fetch is given up
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn record(&self, event: &dyn UnifiedEvent) {
+        let slot = event.slot();
+        let mut state = self.state.lock();
+        let next = *state.next_slot.get_or_insert(slot);
+        if slot < next {
+            // 已校验或已丢弃的 slot
+            return;
+        }
+        state.newest_slot = state.newest_slot.max(slot);
+        *state.slots.entry(slot).or_default().entry(AuditEventKey::of(event)).or_default() += 1;
+
+        // 落后超过 max_slots 个 slot 时丢弃最旧的，连同其间没有事件的 slot
+        let oldest_kept = state.newest_slot.saturating_sub(self.max_slots as u64 - 1);
+        if next < oldest_kept {
+            state.stats.slots_pruned += oldest_kept - next;
+            state.slots = state.slots.split_off(&oldest_kept);
+            state.next_slot = Some(oldest_kept);
+            state.attempts = 0;
+        }
+    }
+
+    /// Next slot in order that is at least `finality_lag` behind the newest streamed one
+    pub fn next_slot_to_verify(&self) -> Option<u64> {
+        let state = self.state.lock();
+        let next = state.next_slot?;
+        (state.newest_slot.saturating_sub(next) >= self.finality_lag).then_some(next)
+    }
+
+    /// Records a slot without a block; its streamed events, if any, count as extra
+    pub fn mark_skipped(&self, slot: u64) {
+        let mut state = self.state.lock();
+        let streamed = state.finish(slot);
+        state.stats.slots_skipped += 1;
+        state.stats.extra += streamed.values().sum::<usize>() as u64;
+    }
+
+    /// Records a failed verification attempt; returns whether the slot was given up
+    pub fn record_failure(&self, slot: u64) -> bool {
+        let mut state = self.state.lock();
+        if state.next_slot != Some(slot) {
+            return false;
+        }
+        state.attempts += 1;
+        if state.attempts < self.max_attempts {
+            return false;
+        }
+        state.finish(slot);
+        state.stats.slots_failed += 1;
+        true
+    }
+
+    /// Compares the slot's streamed events with the events parsed from its block and
+    /// stops tracking the slot
+    pub fn compare(&self, slot: u64, block_events: &[Box<dyn UnifiedEvent>]) -> SlotAuditReport {
+        let mut block = EventCounts::new();
+        for event in block_events {
+            *block.entry(AuditEventKey::of(event.as_ref())).or_default() += 1;
+        }
+        let mut state = self.state.lock();
+        let streamed = state.finish(slot);
+        let streamed_events: usize = streamed.values().sum();
+        let missed = excess(&block, &streamed);
+        let report = SlotAuditReport {
+            slot,
+            block_events: block_events.len(),
+            streamed_events,
+            matched: block_events.len() - missed.len(),
+            missed,
+            extra: excess(&streamed, &block),
+        };
+        let stats = &mut state.stats;
+        stats.slots_verified += 1;
+        stats.clean_slots += report.is_clean() as u64;
+        stats.matched += report.matched as u64;
+        stats.missed += report.missed.len() as u64;
+        stats.extra += report.extra.len() as u64;
+        report
+    }
+
+    pub fn stats(&self) -> SlotAuditStats {
+        self.state.lock().stats.clone()
+    }
+}
+
+impl EventSink for SlotAuditor {
+    fn send(&self, event: &dyn UnifiedEvent) -> AnyResult<()> {
+        self.record(event);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "transport")]
+pub use rpc::{fetch_block_events, is_slot_skipped};
+
+#[cfg(feature = "transport")]
+mod rpc {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use parking_lot::Mutex;
+    use solana_commitment_config::CommitmentConfig;
+    use solana_rpc_client_api::client_error::{Error as ClientError, ErrorKind};
+    use solana_rpc_client_api::config::RpcBlockConfig;
+    use solana_rpc_client_api::custom_error::{
+        JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED, JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+    };
+    use solana_rpc_client_api::request::RpcError;
+    use solana_transaction_status::{
+        EncodedConfirmedTransactionWithStatusMeta, TransactionDetails, UiTransactionEncoding,
+    };
+
+    use super::{SlotAuditReport, SlotAuditor};
+    use crate::common::{AnyResult, SolanaRpcClient};
+    use crate::streaming::event_parser::core::event_parser::EventParser;
+    use crate::streaming::event_parser::UnifiedEvent;
+
+    /// Fetches the finalized block via `getBlock` and parses it in transaction order
+    ///
+    /// Failed transactions are skipped unless `include_failed`.
+    pub async fn fetch_block_events(
+        rpc: &SolanaRpcClient,
+        parser: &EventParser,
+        slot: u64,
+        include_failed: bool,
+    ) -> AnyResult<Vec<Box<dyn UnifiedEvent>>> {
+        let block = rpc
+            .get_block_with_config(
+                slot,
+                RpcBlockConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    transaction_details: Some(TransactionDetails::Full),
+                    rewards: Some(false),
+                    commitment: Some(CommitmentConfig::finalized()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await?;
+        let parsed = Arc::new(Mutex::new(Vec::new()));
+        for transaction in block.transactions.unwrap_or_default() {
+            if !include_failed && transaction.meta.as_ref().is_some_and(|meta| meta.err.is_some()) {
+                continue;
+            }
+            let Some(signature) =
+                transaction.transaction.decode().and_then(|tx| tx.signatures.first().copied())
+            else {
+                continue;
+            };
+            let sink = parsed.clone();
+            parser
+                .parse_encoded_confirmed_transaction_with_status_meta(
+                    signature,
+                    EncodedConfirmedTransactionWithStatusMeta {
+                        slot,
+                        transaction,
+                        block_time: block.block_time,
+                    },
+                    Arc::new(move |event: &Box<dyn UnifiedEvent>| sink.lock().push(event.clone())),
+                )
+                .await?;
+        }
+        let events = std::mem::take(&mut *parsed.lock());
+        Ok(events)
+    }
+
+    /// Whether `getBlock` failed because the slot has no block, as opposed to a
+    /// transient error (timeout, rate limit, block not yet available)
+    pub fn is_slot_skipped(error: &anyhow::Error) -> bool {
+        error.downcast_ref::<ClientError>().is_some_and(|error| {
+            matches!(
+                error.kind.as_ref(),
+                ErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+                    if *code == JSON_RPC_SERVER_ERROR_SLOT_SKIPPED
+                        || *code == JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED
+            )
+        })
+    }
+
+    impl SlotAuditor {
+        /// Fetches the finalized block, parses it and compares it with the stream
+        pub async fn verify_slot(
+            &self,
+            rpc: &SolanaRpcClient,
+            parser: &EventParser,
+            slot: u64,
+        ) -> AnyResult<SlotAuditReport> {
+            let events = fetch_block_events(rpc, parser, slot, self.include_failed).await?;
+            Ok(self.compare(slot, &events))
+        }
+
+        /// Every `interval`, verifies all slots that are due and passes each report on
+        ///
+        /// Slots without a block are recorded as skipped; other fetch errors are
+        /// retried on the next tick and the slot is given up after `max_attempts`.
+        pub fn spawn_periodic(
+            self: &Arc<Self>,
+            rpc: Arc<SolanaRpcClient>,
+            parser: Arc<EventParser>,
+            interval: Duration,
+            on_report: impl Fn(SlotAuditReport) + Send + Sync + 'static,
+        ) -> tokio::task::JoinHandle<()> {
+            let auditor = self.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                loop {
+                    ticker.tick().await;
+                    while let Some(slot) = auditor.next_slot_to_verify() {
+                        match auditor.verify_slot(&rpc, &parser, slot).await {
+                            Ok(report) => {
+                                if !report.is_clean() {
+                                    log::warn!(
+                                        "Slot {} audit: {} missed, {} extra events",
+                                        slot,
+                                        report.missed.len(),
+                                        report.extra.len()
+                                    );
+                                }
+                                on_report(report);
+                            }
+                            Err(e) if is_slot_skipped(&e) => auditor.mark_skipped(slot),
+                            Err(e) => {
+                                // 临时错误（超时、限流）下个周期重试
+                                if auditor.record_failure(slot) {
+                                    log::warn!("Slot {} audit given up: {}", slot, e);
+                                } else {
+                                    log::warn!("Slot {} audit failed, retrying: {}", slot, e);
+                                }
+                                break;
+                            }
+                        }
+                    }
+                }
+            })
+        }
+    }
+}
+
+#[cfg(all(test, feature = "raydium-cpmm"))]
+mod tests {
+    use super::*;
+    use crate::streaming::event_parser::common::EventMetadata;
+    use crate::streaming::event_parser::protocols::raydium_cpmm::RaydiumCpmmSwapEvent;
+
+    #[test]
+    fn test_slot_audit_missed_and_extra() {
+        let auditor = SlotAuditor::default().with_finality_lag(2);
+        let swap = |slot, outer_index| RaydiumCpmmSwapEvent {
+            metadata: EventMetadata {
+                slot,
+                outer_index,
+                signature: Signature::new_unique(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (a, b, c) = (swap(10, 0), swap(10, 1), swap(10, 2));
+        auditor.record(&a);
+        auditor.record(&b);
+        assert_eq!(auditor.next_slot_to_verify(), None);
+        auditor.record(&swap(12, 0));
+        assert_eq!(auditor.next_slot_to_verify(), Some(10));
+
+        let block: Vec<Box<dyn UnifiedEvent>> = vec![Box::new(a.clone()), Box::new(c.clone())];
+        let report = auditor.compare(10, &block);
+        assert_eq!(report.matched, 1);
+        assert_eq!(report.missed, vec![AuditEventKey::of(&c)]);
+        assert_eq!(report.extra, vec![AuditEventKey::of(&b)]);
+        assert_eq!(auditor.next_slot_to_verify(), None);
+        assert_eq!(auditor.stats().slots_verified, 1);
+    }
+
+    fn swap_in(signature: Signature, slot: u64, outer_index: i64) -> RaydiumCpmmSwapEvent {
+        RaydiumCpmmSwapEvent {
+            metadata: EventMetadata { slot, outer_index, signature, ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_slot_audit_per_event_and_duplicates() {
+        let auditor = SlotAuditor::default().with_finality_lag(0);
+        let signature = Signature::new_unique();
+        let swaps: Vec<RaydiumCpmmSwapEvent> = (0..3).map(|i| swap_in(signature, 10, i)).collect();
+        // 同一交易三次 swap 只收到第一个，且重复投递
+        auditor.record(&swaps[0]);
+        auditor.record(&swaps[0]);
+
+        let block: Vec<Box<dyn UnifiedEvent>> =
+            swaps.iter().map(|swap| Box::new(swap.clone()) as Box<dyn UnifiedEvent>).collect();
+        let report = auditor.compare(10, &block);
+        assert_eq!((report.block_events, report.streamed_events, report.matched), (3, 2, 1));
+        assert_eq!(report.missed, vec![AuditEventKey::of(&swaps[1]), AuditEventKey::of(&swaps[2])]);
+        assert_eq!(report.extra, vec![AuditEventKey::of(&swaps[0])]);
+    }
+
+    #[test]
+    fn test_slot_audit_verifies_slots_without_streamed_events() {
+        let auditor = SlotAuditor::default().with_finality_lag(2);
+        auditor.record(&swap_in(Signature::new_unique(), 10, 0));
+        auditor.record(&swap_in(Signature::new_unique(), 13, 0));
+        assert_eq!(auditor.next_slot_to_verify(), Some(10));
+        auditor.compare(10, &[]);
+
+        // slot 11 的事件全部丢失，仍按顺序校验
+        assert_eq!(auditor.next_slot_to_verify(), Some(11));
+        let lost = swap_in(Signature::new_unique(), 11, 0);
+        let report = auditor.compare(11, &[Box::new(lost.clone()) as Box<dyn UnifiedEvent>]);
+        assert_eq!(report.missed, vec![AuditEventKey::of(&lost)]);
+        assert_eq!(auditor.next_slot_to_verify(), None);
+        // 已校验 slot 的迟到事件不再记录
+        auditor.record(&swap_in(Signature::new_unique(), 10, 1));
+        assert_eq!(auditor.stats().missed, 1);
+    }
+
+    #[test]
+    fn test_slot_audit_counts_pruned_skipped_and_failed() {
+        let auditor = SlotAuditor::new(4).with_finality_lag(2).with_max_attempts(2);
+        auditor.record(&swap_in(Signature::new_unique(), 10, 0));
+        auditor.record(&swap_in(Signature::new_unique(), 20, 0));
+        // 只保留 17..=20
+        assert_eq!(auditor.stats().slots_pruned, 7);
+        assert_eq!(auditor.next_slot_to_verify(), Some(17));
+
+        assert!(!auditor.record_failure(17));
+        assert!(auditor.record_failure(17));
+        assert_eq!(auditor.stats().slots_failed, 1);
+        assert_eq!(auditor.next_slot_to_verify(), Some(18));
+
+        auditor.record(&swap_in(Signature::new_unique(), 18, 0));
+        auditor.mark_skipped(18);
+        let stats = auditor.stats();
+        assert_eq!((stats.slots_skipped, stats.extra, stats.slots_verified), (1, 1, 0));
+        assert_eq!(auditor.next_slot_to_verify(), None);
+    }
+
+    #[cfg(feature = "transport")]
+    #[test]
+    fn test_slot_skipped_error_classified() {
+        use solana_rpc_client_api::client_error::Error as ClientError;
+        use solana_rpc_client_api::request::{RpcError, RpcResponseErrorData};
+
+        let rpc_error = |code| {
+            anyhow::Error::from(ClientError::from(RpcError::RpcResponseError {
+                code,
+                message: String::new(),
+                data: RpcResponseErrorData::Empty,
+            }))
+        };
+        assert!(is_slot_skipped(&rpc_error(-32007)));
+        assert!(is_slot_skipped(&rpc_error(-32009)));
+        // 区块暂不可用、超时等为临时错误
+        assert!(!is_slot_skipped(&rpc_error(-32004)));
+        assert!(!is_slot_skipped(&anyhow::anyhow!("timed out")));
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::streaming::analytics::TradeInfo;
+use crate::streaming::event_parser::UnifiedEvent;
+
+/// Output format of [`EventPrinter`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrintFormat {
+    /// One line per event
+    #[default]
+    Compact,
+    /// Multi-line block per event
+    Detailed,
+    /// One JSON object per line
+    Json,
+}
+
+/// How much of each event is printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Verbosity {
+    /// Type, slot and signature only
+    Minimal,
+    /// Adds the swap summary and instruction position
+    #[default]
+    Normal,
+    /// Adds timing, compute budget and the full event body
+    Full,
+}
+
+/// Formats events for logs and terminals
+///
+/// Replaces the per-example `match_event!` printing blocks:
+///
+/// ```rust,ignore
+/// let printer = EventPrinter::new(PrintFormat::Compact);
+/// grpc.subscribe_events_immediate(protocols, None, tx_filter, vec![], None, None, printer.callback())
+///     .await?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EventPrinter {
+    format: PrintFormat,
+    verbosity: Verbosity,
+}
+
+impl EventPrinter {
+    pub fn new(format: PrintFormat) -> Self {
+        Self { format, verbosity: Verbosity::default() }
+    }
+
+    pub fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    pub fn format(&self) -> PrintFormat {
+        self.format
+    }
+
+    pub fn verbosity(&self) -> Verbosity {
+        self.verbosity
+    }
+
+    /// Prints the event to stdout
+    pub fn print(&self, event: &dyn UnifiedEvent) {
+        println!("{}", self.format_event(event));
+    }
+
+    /// Callback usable directly with the `subscribe_*` methods
+    pub fn callback(self) -> impl Fn(Box<dyn UnifiedEvent>) + Send + Sync + 'static {
+        let printer = Arc::new(self);
+        move |event: Box<dyn UnifiedEvent>| printer.print(event.as_ref())
+    }
+
+    /// Renders the event without printing it
+    pub fn format_event(&self, event: &dyn UnifiedEvent) -> String {
+        match self.format {
+            PrintFormat::Compact => self.format_compact(event),
+            PrintFormat::Detailed => self.format_detailed(event),
+            PrintFormat::Json => self.format_json(event),
+        }
+    }
+
+    fn format_compact(&self, event: &dyn UnifiedEvent) -> String {
+        let mut line = format!("[{}] {} {}", event.slot(), event.event_type(), event.signature());
+        if self.verbosity == Verbosity::Minimal {
+            return line;
+        }
+        if let Some(trade) = TradeInfo::from_event(event) {
+            let _ = write!(
+                line,
+                " pool={} user={} {} {} -> {} {}",
+                trade.pool,
+                trade.user,
+                trade.amount_in,
+                trade.input_mint,
+                trade.amount_out,
+                trade.output_mint
+            );
+        }
+        if self.verbosity == Verbosity::Full {
+            let metadata = event.metadata();
+            let _ = write!(line, " handle_us={}", metadata.handle_us);
+            if let Some(price) = metadata.compute_budget.unit_price {
+                let _ = write!(line, " cu_price={}", price);
+            }
+            if let Some(units) = metadata.compute_budget.units_consumed {
+                let _ = write!(line, " cu_used={}", units);
+            }
+        }
+        line
+    }
+
+    fn format_detailed(&self, event: &dyn UnifiedEvent) -> String {
+        let metadata = event.metadata();
+        let mut block = format!(
+            "━━ {} ━━\n  signature: {}\n  slot:      {}\n",
+            event.event_type(),
+            event.signature(),
+            event.slot()
+        );
+        if self.verbosity >= Verbosity::Normal {
+            let _ = writeln!(block, "  protocol:  {:?}", metadata.protocol);
+            let _ = writeln!(
+                block,
+                "  position:  tx={:?} outer={} inner={:?}",
+                metadata.transaction_index, metadata.outer_index, metadata.inner_index
+            );
+            if let Some(trade) = TradeInfo::from_event(event) {
+                let _ = writeln!(block, "  pool:      {}", trade.pool);
+                let _ = writeln!(block, "  user:      {}", trade.user);
+                let _ = writeln!(block, "  in:        {} {}", trade.amount_in, trade.input_mint);
+                let _ = writeln!(block, "  out:       {} {}", trade.amount_out, trade.output_mint);
+            }
+        }
+        if self.verbosity == Verbosity::Full {
+            let _ = writeln!(block, "  recv_us:   {}", metadata.recv_us);
+            let _ = writeln!(block, "  handle_us: {}", metadata.handle_us);
+            let _ = writeln!(block, "  compute:   {:?}", metadata.compute_budget);
+            let _ = writeln!(block, "  event:     {:#?}", event);
+        }
+        block
+    }
+
+    fn format_json(&self, event: &dyn UnifiedEvent) -> String {
+        let value = match self.verbosity {
+            Verbosity::Minimal => serde_json::json!({
+                "event_type": event.event_type().to_string(),
+                "slot": event.slot(),
+                "signature": event.signature().to_string(),
+            }),
+            Verbosity::Normal | Verbosity::Full => event.to_json(),
+        };
+        value.to_string()
+    }
+}
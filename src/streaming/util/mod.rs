@@ -0,0 +1,5 @@
+// 工具模块 - 事件输出等通用辅助
+pub mod event_printer;
+
+// 重新导出主要类型
+pub use event_printer::*;
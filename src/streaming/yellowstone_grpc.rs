@@ -1,31 +1,61 @@
-use crate::common::AnyResult;
+use crate::common::{AnyResult, SolanaRpcClient};
+use crate::streaming::analytics::{
+    NewPoolWatchEvent, NewPoolWatcher, ProtocolActivityAggregator, ProtocolActivitySummary,
+    WalletActivity, WalletActivityTracker, NEW_POOL_FILTER_NAME,
+};
 use crate::streaming::common::{
-    EventProcessor, MetricsManager, PerformanceMetrics, StreamClientConfig, SubscriptionHandle,
+    AccountPruner, AccountPruningConfig, EventProcessor, MemoryReport, MetricsManager,
+    PerformanceMetrics, SharedCredentialProvider, StaticCredentialProvider, StreamClientConfig,
+    SubscriptionHandle, Watchlist, WATCHLIST_FILTER_NAME,
 };
 use crate::streaming::event_parser::common::filter::EventTypeFilter;
 use crate::streaming::event_parser::{Protocol, UnifiedEvent};
 use crate::streaming::grpc::pool::factory;
-use crate::streaming::grpc::{EventPretty, SubscriptionManager};
+use crate::streaming::grpc::{
+    BlockMetaOrdering, BlockSubscription, DryRunReport, EventPretty, SubscriptionManager,
+    SubscriptionPlan,
+};
+use crate::streaming::store::{AccountSnapshotCache, DebugDumpConfig, DebugRecorder};
+use crate::streaming::yellowstone_sub_system::SystemEvent;
 use anyhow::anyhow;
 use chrono::Local;
 use futures::channel::mpsc;
 use futures::{SinkExt, StreamExt};
 use log::error;
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use yellowstone_grpc_proto::geyser::subscribe_update::UpdateOneof;
 use yellowstone_grpc_proto::geyser::{
-    CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccountsFilter, SubscribeRequestPing,
+    CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccountsFilter,
+    SubscribeRequestFilterEntry, SubscribeRequestFilterTransactions, SubscribeRequestPing,
 };
 
 /// 交易过滤器
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TransactionFilter {
     pub account_include: Vec<String>,
     pub account_exclude: Vec<String>,
     pub account_required: Vec<String>,
+    /// 仅用这些协议解析该过滤器命中的交易，为空时使用订阅的全部协议
+    pub protocols: Vec<Protocol>,
+    /// 客户端检查：该过滤器命中的交易需由其中之一签名，为空时不检查
+    pub signers: Vec<String>,
+}
+
+impl TransactionFilter {
+    /// Transactions signed by any of the wallets
+    ///
+    /// The server matches the wallets anywhere in the transaction; transactions none of
+    /// them signed are dropped by the client before parsing.
+    pub fn for_wallets(wallets: &[Pubkey]) -> Self {
+        let wallets: Vec<String> = wallets.iter().map(|wallet| wallet.to_string()).collect();
+        Self { account_include: wallets.clone(), signers: wallets, ..Default::default() }
+    }
 }
 
 /// 账户过滤器
@@ -51,8 +81,68 @@ pub struct YellowstoneGrpc {
     pub current_request: Arc<tokio::sync::RwLock<Option<SubscribeRequest>>>,
 
     pub event_type_filter: Arc<tokio::sync::RwLock<Option<EventTypeFilter>>>,
+    /// 整块订阅时的本地交易过滤器（`StreamClientConfig::block_subscription`）
+    pub block_subscription: Arc<parking_lot::RwLock<Option<BlockSubscription>>>,
+    /// 连接状态等系统事件回调（如 `SystemEvent::StaleStreamDetected`）
+    pub system_event_callback: Arc<parking_lot::RwLock<Option<SystemEventCallback>>>,
+    /// 运行时关注的 mint，订阅中以单独的交易过滤器下发
+    pub watchlist: Arc<Watchlist>,
+    /// 推送账户的原始状态缓存，用于定期与 RPC 快照比对
+    pub account_cache: Arc<parking_lot::RwLock<Option<Arc<AccountSnapshotCache>>>>,
+    /// 记录订阅账户活跃度，用于自动移除长时间无更新的账户
+    pub account_pruner: Arc<parking_lot::RwLock<Option<Arc<AccountPruner>>>>,
+    /// 按窗口汇总各协议活跃度，窗口结束时以系统事件推送
+    pub activity_aggregator: Arc<parking_lot::RwLock<Option<Arc<ProtocolActivityAggregator>>>>,
+    /// 新池监视器，被监视的池子以单独的交易过滤器下发
+    pub new_pool_watcher: Arc<parking_lot::RwLock<Option<Arc<NewPoolWatcher>>>>,
+    /// 被监视的池子变化时通知后台任务更新订阅
+    pub new_pool_refresh: Arc<tokio::sync::Notify>,
+    /// 异常时转储近期原始更新的记录器
+    pub debug_recorder: Arc<parking_lot::RwLock<Option<Arc<DebugRecorder>>>>,
+}
+
+/// Announces closed activity windows as [`SystemEvent::ActivitySummary`]
+fn emit_activity_summaries(
+    system_event_callback: &parking_lot::RwLock<Option<SystemEventCallback>>,
+    summaries: Vec<ProtocolActivitySummary>,
+) {
+    if summaries.is_empty() {
+        return;
+    }
+    if let Some(callback) = system_event_callback.read().as_ref() {
+        callback(SystemEvent::ActivitySummary(summaries));
+    }
+}
+
+/// Announces new-pool watch events as [`SystemEvent::NewPoolWatch`]; returns whether the
+/// set of watched pools changed
+fn emit_new_pool_events(
+    system_event_callback: &parking_lot::RwLock<Option<SystemEventCallback>>,
+    events: Vec<NewPoolWatchEvent>,
+) -> bool {
+    let pools_changed = events.iter().any(|event| {
+        matches!(event, NewPoolWatchEvent::PoolDetected(_) | NewPoolWatchEvent::WatchExpired(_))
+    });
+    if let Some(callback) = system_event_callback.read().as_ref() {
+        for event in events {
+            callback(SystemEvent::NewPoolWatch(event));
+        }
+    }
+    pools_changed
+}
+
+/// Explicit `account` keys of a subscribe request's account filters
+fn request_accounts(request: &SubscribeRequest) -> impl Iterator<Item = Pubkey> + '_ {
+    request
+        .accounts
+        .values()
+        .flat_map(|filter| filter.account.iter())
+        .filter_map(|key| key.parse::<Pubkey>().ok())
 }
 
+/// Callback receiving connection-level [`SystemEvent`]s
+pub type SystemEventCallback = Arc<dyn Fn(SystemEvent) + Send + Sync>;
+
 impl YellowstoneGrpc {
     /// 创建客户端，使用默认配置
     pub fn new(endpoint: String, x_token: Option<String>) -> AnyResult<Self> {
@@ -64,12 +154,28 @@ impl YellowstoneGrpc {
         endpoint: String,
         x_token: Option<String>,
         config: StreamClientConfig,
+    ) -> AnyResult<Self> {
+        let credentials = Arc::new(StaticCredentialProvider::new(x_token.clone()));
+        Self::new_with_credentials(endpoint, credentials, config).map(|mut client| {
+            client.x_token = x_token;
+            client
+        })
+    }
+
+    /// 创建客户端，x-token 由凭证提供者在每次连接时解析（支持环境变量、文件及轮换的令牌）
+    pub fn new_with_credentials(
+        endpoint: String,
+        credentials: SharedCredentialProvider,
+        config: StreamClientConfig,
     ) -> AnyResult<Self> {
         let _ = rustls::crypto::ring::default_provider().install_default().ok();
         let metrics = Arc::new(RwLock::new(PerformanceMetrics::new()));
 
-        let subscription_manager =
-            SubscriptionManager::new(endpoint.clone(), x_token.clone(), config.clone());
+        let subscription_manager = SubscriptionManager::new_with_credentials(
+            endpoint.clone(),
+            credentials,
+            config.clone(),
+        );
         let metrics_manager = MetricsManager::new_with_metrics(
             metrics.clone(),
             config.enable_metrics,
@@ -79,7 +185,7 @@ impl YellowstoneGrpc {
 
         Ok(Self {
             endpoint,
-            x_token,
+            x_token: None,
             config,
             metrics: metrics.clone(),
             subscription_manager,
@@ -90,6 +196,15 @@ impl YellowstoneGrpc {
             control_tx: Arc::new(tokio::sync::Mutex::new(None)),
             current_request: Arc::new(tokio::sync::RwLock::new(None)),
             event_type_filter: Arc::new(tokio::sync::RwLock::new(None)),
+            block_subscription: Arc::new(parking_lot::RwLock::new(None)),
+            system_event_callback: Arc::new(parking_lot::RwLock::new(None)),
+            watchlist: Arc::new(Watchlist::default()),
+            account_cache: Arc::new(parking_lot::RwLock::new(None)),
+            account_pruner: Arc::new(parking_lot::RwLock::new(None)),
+            activity_aggregator: Arc::new(parking_lot::RwLock::new(None)),
+            new_pool_watcher: Arc::new(parking_lot::RwLock::new(None)),
+            new_pool_refresh: Arc::new(tokio::sync::Notify::new()),
+            debug_recorder: Arc::new(parking_lot::RwLock::new(None)),
         })
     }
 
@@ -126,6 +241,11 @@ impl YellowstoneGrpc {
         self.metrics_manager.get_metrics()
     }
 
+    /// 获取内部缓存与队列的内存占用估算
+    pub fn memory_report(&self) -> MemoryReport {
+        self.event_processor.memory_report()
+    }
+
     /// 打印性能指标
     pub fn print_metrics(&self) {
         self.metrics_manager.print_metrics();
@@ -136,6 +256,241 @@ impl YellowstoneGrpc {
         self.config.enable_metrics = enabled;
     }
 
+    /// 设置系统事件回调，对之后启动的订阅生效
+    pub fn on_system_event<F>(&self, callback: F)
+    where
+        F: Fn(SystemEvent) + Send + Sync + 'static,
+    {
+        *self.system_event_callback.write() = Some(Arc::new(callback));
+    }
+
+    /// 启用快照比对：缓存之后订阅推送的账户，每 `interval` 经 RPC 全量重新拉取
+    ///
+    /// Re-fetches the cached accounts plus the explicit `account` keys of the current
+    /// subscription. Mismatches correct the cache and are reported as
+    /// [`SystemEvent::CacheDivergence`] through [`on_system_event`](Self::on_system_event).
+    /// Abort the returned handle to stop refreshing.
+    pub fn enable_snapshot_diff(
+        &self,
+        rpc: Arc<SolanaRpcClient>,
+        interval: Duration,
+    ) -> (Arc<AccountSnapshotCache>, tokio::task::JoinHandle<()>) {
+        let cache = self.account_cache.write().get_or_insert_with(Default::default).clone();
+        if let Some(recorder) = self.debug_recorder.read().as_ref() {
+            recorder.attach_cache(cache.clone());
+        }
+        let current_request = self.current_request.clone();
+        let system_event_callback = self.system_event_callback.clone();
+        let debug_recorder = self.debug_recorder.clone();
+        let track_cache = cache.clone();
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Some(request) = current_request.read().await.as_ref() {
+                    track_cache.track(request_accounts(request));
+                }
+                let divergences = match track_cache.refresh(&rpc).await {
+                    Ok(divergences) => divergences,
+                    Err(e) => {
+                        error!("Account snapshot refresh failed: {e:?}");
+                        continue;
+                    }
+                };
+                if !divergences.is_empty() {
+                    if let Some(recorder) = debug_recorder.read().as_ref() {
+                        recorder
+                            .trigger_logged(&format!("{} cache divergences", divergences.len()));
+                    }
+                }
+                let callback = system_event_callback.read().clone();
+                for divergence in divergences {
+                    log::warn!("Account cache divergence: {:?}", divergence);
+                    if let Some(callback) = callback.as_ref() {
+                        callback(SystemEvent::CacheDivergence(divergence));
+                    }
+                }
+            }
+        });
+        (cache, handle)
+    }
+
+    /// 启用账户裁剪：定期把长时间无更新或流动性耗尽的账户移出订阅
+    ///
+    /// Tracks the explicit `account` keys of the current subscription; updates streamed
+    /// by subscriptions started afterwards count as activity. Feed parsed pool events to
+    /// [`AccountPruner::on_event`] for the liquidity threshold. Every
+    /// `check_interval_ms` due accounts are removed with
+    /// [`remove_accounts`](Self::remove_accounts) and announced as
+    /// [`SystemEvent::AccountsPruned`]. Abort the returned handle to stop pruning.
+    pub fn enable_account_pruning(
+        &self,
+        config: AccountPruningConfig,
+    ) -> (Arc<AccountPruner>, tokio::task::JoinHandle<()>) {
+        let pruner = self
+            .account_pruner
+            .write()
+            .get_or_insert_with(|| Arc::new(AccountPruner::new(config)))
+            .clone();
+        let client = self.clone();
+        let task_pruner = pruner.clone();
+        let interval = Duration::from_millis(pruner.config().check_interval_ms.max(1));
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Some(request) = client.current_request.read().await.as_ref() {
+                    task_pruner.track(request_accounts(request));
+                }
+                let pruned = task_pruner.due();
+                if pruned.is_empty() {
+                    continue;
+                }
+                let accounts: Vec<Pubkey> = pruned.iter().map(|entry| entry.account).collect();
+                match client.remove_accounts(&accounts).await {
+                    Ok(removed) => log::info!("Pruned {removed} idle or drained accounts"),
+                    Err(e) => {
+                        error!("Account pruning failed: {e:?}");
+                        continue;
+                    }
+                }
+                if let Some(callback) = client.system_event_callback.read().as_ref() {
+                    callback(SystemEvent::AccountsPruned(pruned));
+                }
+            }
+        });
+        (pruner, handle)
+    }
+
+    /// 启用协议活跃度汇总：按区块时间窗口统计交易数、独立钱包、成交量与热门池
+    ///
+    /// Events of subscriptions started afterwards are fed to the aggregator before the
+    /// event callback. Each closed window is announced as [`SystemEvent::ActivitySummary`]
+    /// through [`on_system_event`](Self::on_system_event), either when events are
+    /// [`ACTIVITY_FLUSH_DELAY_MS`](crate::streaming::analytics::ACTIVITY_FLUSH_DELAY_MS) past
+    /// the window's end or, during idle periods, by a timer once the newest event time
+    /// plus the time since the last event arrived is that far past it. Abort the returned handle to stop the timer. Calling this again returns
+    /// the existing aggregator and no new timer.
+    pub fn enable_activity_summaries(
+        &self,
+        window_ms: i64,
+        top_pools: usize,
+    ) -> (Arc<ProtocolActivityAggregator>, Option<tokio::task::JoinHandle<()>>) {
+        let mut current = self.activity_aggregator.write();
+        if let Some(aggregator) = current.as_ref() {
+            return (aggregator.clone(), None);
+        }
+        let aggregator = Arc::new(ProtocolActivityAggregator::new(window_ms, top_pools));
+        *current = Some(aggregator.clone());
+        drop(current);
+
+        let system_event_callback = self.system_event_callback.clone();
+        let task_aggregator = aggregator.clone();
+        let interval = Duration::from_millis(aggregator.window_ms().clamp(1, 1000) as u64);
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let now_ms = chrono::Utc::now().timestamp_millis();
+                let summaries = task_aggregator.flush_idle(now_ms);
+                emit_activity_summaries(&system_event_callback, summaries);
+            }
+        });
+        (aggregator, Some(handle))
+    }
+
+    /// 启用新池监视：新建池子的前 N 个 slot 内以单独的交易过滤器重点订阅
+    ///
+    /// Events of subscriptions started afterwards are fed to `watcher` before the event
+    /// callback. A detected pool is added to the [`NEW_POOL_FILTER_NAME`] transaction
+    /// filter of the live subscription and removed again when its watch expires; all
+    /// watcher output is announced as [`SystemEvent::NewPoolWatch`] through
+    /// [`on_system_event`](Self::on_system_event). Not available with
+    /// `block_subscription`. Abort the returned handle to stop updating the filter.
+    /// Calling this again returns the existing watcher and no new task.
+    pub fn enable_new_pool_watch(
+        &self,
+        watcher: NewPoolWatcher,
+    ) -> (Arc<NewPoolWatcher>, Option<tokio::task::JoinHandle<()>>) {
+        let mut current = self.new_pool_watcher.write();
+        if let Some(watcher) = current.as_ref() {
+            return (watcher.clone(), None);
+        }
+        let watcher = Arc::new(watcher);
+        *current = Some(watcher.clone());
+        drop(current);
+
+        let client = self.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                client.new_pool_refresh.notified().await;
+                if let Err(e) = client.push_runtime_filters().await {
+                    error!("Failed to update new pool filter: {e:?}");
+                }
+            }
+        });
+        (watcher, Some(handle))
+    }
+
+    /// Removes accounts from the account filters of the running subscription
+    ///
+    /// Filters left without accounts and owners are dropped, an empty filter would
+    /// subscribe to every account. The accounts are also evicted from the snapshot
+    /// cache. Returns how many filter entries were removed.
+    pub async fn remove_accounts(&self, accounts: &[Pubkey]) -> AnyResult<usize> {
+        let control_guard = self.control_tx.lock().await;
+        let Some(control_sender) = control_guard.as_ref() else {
+            return Ok(0);
+        };
+        let mut request = self
+            .current_request
+            .read()
+            .await
+            .as_ref()
+            .ok_or_else(|| anyhow!("No active subscription"))?
+            .clone();
+        let keys: Vec<String> = accounts.iter().map(|account| account.to_string()).collect();
+        let mut removed = 0;
+        request.accounts.retain(|_, filter| {
+            let before = filter.account.len();
+            filter.account.retain(|key| !keys.contains(key));
+            removed += before - filter.account.len();
+            !(before > 0 && filter.account.is_empty() && filter.owner.is_empty())
+        });
+        if let Some(cache) = self.account_cache.read().as_ref() {
+            cache.remove(accounts);
+        }
+        if removed == 0 {
+            return Ok(0);
+        }
+        control_sender
+            .clone()
+            .send(request.clone())
+            .await
+            .map_err(|e| anyhow!("Failed to send account removal: {}", e))?;
+        *self.current_request.write().await = Some(request);
+        Ok(removed)
+    }
+
+    /// 启用调试转储：保留近期原始交易更新，异常时连同账户缓存与配置写入磁盘
+    ///
+    /// Parse failure spikes and snapshot diff divergences dump automatically; call
+    /// [`DebugRecorder::trigger`] for anything else. Takes effect for subscriptions
+    /// started afterwards; load bundles with [`DebugBundle`](crate::streaming::store::DebugBundle).
+    pub fn enable_debug_dumps(&self, config: DebugDumpConfig) -> Arc<DebugRecorder> {
+        let recorder = Arc::new(DebugRecorder::new(config));
+        if let Err(e) = recorder.set_config(&self.config) {
+            error!("Failed to serialize config for debug dumps: {e:?}");
+        }
+        if let Some(cache) = self.account_cache.read().as_ref() {
+            recorder.attach_cache(cache.clone());
+        }
+        *self.debug_recorder.write() = Some(recorder.clone());
+        *self.event_processor.debug_recorder.write() = Some(recorder.clone());
+        recorder
+    }
+
     /// 停止当前订阅
     pub async fn stop(&self) {
         let mut handle_guard = self.subscription_handle.lock().await;
@@ -147,6 +502,66 @@ impl YellowstoneGrpc {
         self.active_subscription.store(false, Ordering::Release);
     }
 
+    /// 由用户过滤器构建订阅请求（整块订阅的转换除外）
+    fn build_request(
+        &self,
+        transaction_filter: Vec<TransactionFilter>,
+        account_filter: Vec<AccountFilter>,
+        event_type_filter: Option<&EventTypeFilter>,
+        commitment: Option<CommitmentLevel>,
+    ) -> SubscribeRequest {
+        let transactions = self
+            .subscription_manager
+            .get_subscribe_request_filter(transaction_filter, event_type_filter);
+        let accounts = self
+            .subscription_manager
+            .subscribe_with_account_request(account_filter, event_type_filter);
+        let mut request = self.subscription_manager.build_subscribe_request(
+            transactions,
+            accounts,
+            commitment,
+            event_type_filter,
+        );
+        self.apply_runtime_filters(&mut request);
+        if self.config.subscribe_entries {
+            request.entry.insert("client".to_string(), SubscribeRequestFilterEntry::default());
+        }
+        request
+    }
+
+    /// The request `subscribe_events_immediate` would send for these filters, without connecting
+    pub fn plan_subscription(
+        &self,
+        transaction_filter: Vec<TransactionFilter>,
+        account_filter: Vec<AccountFilter>,
+        event_type_filter: Option<&EventTypeFilter>,
+        commitment: Option<CommitmentLevel>,
+    ) -> SubscriptionPlan {
+        let mut request =
+            self.build_request(transaction_filter, account_filter, event_type_filter, commitment);
+        if self.config.block_subscription {
+            BlockSubscription::from_request(&mut request);
+        }
+        SubscriptionPlan::new(self.subscription_manager.adapt_request(request))
+    }
+
+    /// Validates a plan against the endpoint (`GetVersion`) without subscribing
+    ///
+    /// Yellowstone has no RPC exposing filter limits, so limit violations still only
+    /// show up as a subscribe error.
+    pub async fn dry_run(&self, plan: &SubscriptionPlan) -> DryRunReport {
+        let (server_version, error) = match self.subscription_manager.server_version().await {
+            Ok(version) => (Some(version), None),
+            Err(e) => (None, Some(e)),
+        };
+        let mut report =
+            DryRunReport::new(plan, server_version, self.config.connection.proto_compat);
+        if let Some(e) = error {
+            report.warnings.push(format!("GetVersion failed: {e}"));
+        }
+        report
+    }
+
     /// Simplified immediate event subscription (recommended for simple scenarios)
     ///
     /// # Parameters
@@ -188,18 +603,27 @@ impl YellowstoneGrpc {
             metrics_handle = self.metrics_manager.start_auto_monitoring().await;
         }
 
-        let transactions = self
-            .subscription_manager
-            .get_subscribe_request_filter(transaction_filter, event_type_filter.as_ref());
-        let accounts = self
-            .subscription_manager
-            .subscribe_with_account_request(account_filter, event_type_filter.as_ref());
-
+        let filter_scopes = SubscriptionManager::get_transaction_filter_scopes(&transaction_filter);
+        let filter_signers =
+            SubscriptionManager::get_transaction_filter_signers(&transaction_filter);
         // 订阅事件
-        let (mut subscribe_tx, mut stream, subscribe_request) = self
-            .subscription_manager
-            .subscribe_with_request(transactions, accounts, commitment, event_type_filter.as_ref())
-            .await?;
+        let mut subscribe_request = self.build_request(
+            transaction_filter,
+            account_filter,
+            event_type_filter.as_ref(),
+            commitment,
+        );
+        if self.config.block_subscription {
+            *self.block_subscription.write() =
+                BlockSubscription::from_request(&mut subscribe_request);
+        }
+        let (mut subscribe_tx, mut stream) =
+            self.subscription_manager.subscribe(subscribe_request.clone()).await?;
+
+        // 仅在订阅了区块元数据时才需要按区块排序
+        let mut block_meta_ordering = (self.config.block_meta_first
+            && !subscribe_request.blocks_meta.is_empty())
+        .then(BlockMetaOrdering::default);
 
         // 用 Arc<Mutex<>> 包装 subscribe_tx 以支持多线程共享
         let subscribe_tx = Arc::new(Mutex::new(subscribe_tx));
@@ -208,25 +632,109 @@ impl YellowstoneGrpc {
         *self.control_tx.lock().await = Some(control_tx);
 
         // 启动流处理任务
+        let activity_aggregator = self.activity_aggregator.read().clone();
+        let callback: Arc<dyn Fn(Box<dyn UnifiedEvent>) + Send + Sync> = match activity_aggregator {
+            Some(aggregator) => {
+                let system_event_callback = self.system_event_callback.clone();
+                Arc::new(move |event: Box<dyn UnifiedEvent>| {
+                    let summaries = aggregator.on_event(event.as_ref());
+                    emit_activity_summaries(&system_event_callback, summaries);
+                    callback(event);
+                })
+            }
+            None => Arc::new(callback),
+        };
+        let new_pool_watcher = self.new_pool_watcher.read().clone();
+        let callback: Arc<dyn Fn(Box<dyn UnifiedEvent>) + Send + Sync> = match new_pool_watcher {
+            Some(watcher) => {
+                let system_event_callback = self.system_event_callback.clone();
+                let new_pool_refresh = self.new_pool_refresh.clone();
+                Arc::new(move |event: Box<dyn UnifiedEvent>| {
+                    let events = watcher.on_event(event.as_ref());
+                    if emit_new_pool_events(&system_event_callback, events) {
+                        new_pool_refresh.notify_one();
+                    }
+                    callback(event);
+                })
+            }
+            None => callback,
+        };
         let mut event_processor = self.event_processor.clone();
         event_processor.set_protocols_and_event_type_filter(
             super::common::EventSource::Grpc,
             protocols,
             event_type_filter,
             self.config.backpressure.clone(),
-            Some(Arc::new(callback)),
+            Some(callback),
         );
-        let stream_handle = tokio::spawn(async move {
+        event_processor.set_filter_scopes(filter_scopes);
+        event_processor.set_filter_signers(filter_signers);
+        let block_subscription = self.block_subscription.clone();
+        let subscription_manager = self.subscription_manager.clone();
+        let current_request = self.current_request.clone();
+        let system_event_callback = self.system_event_callback.read().clone();
+        let account_cache = self.account_cache.read().clone();
+        let account_pruner = self.account_pruner.read().clone();
+        let debug_recorder = self.debug_recorder.read().clone();
+        let endpoint = self.endpoint.clone();
+        let ping_interval = Duration::from_secs(self.config.connection.ping_interval);
+        let stale_timeout = Duration::from_secs(self.config.connection.stale_timeout);
+        let stream_handle = self.config.runtime.spawn_reader(async move {
+            let mut health_check = tokio::time::interval(Duration::from_secs(1));
+            let mut last_message = Instant::now();
+            let mut last_ping = Instant::now();
+            let mut ping_id = 0;
             loop {
                 tokio::select! {
+                    _ = health_check.tick() => {
+                        if !ping_interval.is_zero() && last_ping.elapsed() >= ping_interval {
+                            last_ping = Instant::now();
+                            ping_id += 1;
+                            let ping = SubscribeRequest {
+                                ping: Some(SubscribeRequestPing { id: ping_id }),
+                                ..Default::default()
+                            };
+                            if let Err(e) = subscribe_tx.lock().await.send(ping).await {
+                                error!("Failed to send ping: {}", e);
+                            }
+                        }
+                        if stale_timeout.is_zero() || last_message.elapsed() < stale_timeout {
+                            continue;
+                        }
+                        // 连接未关闭但长时间无消息，按当前请求重新订阅
+                        let silence_ms = last_message.elapsed().as_millis() as u64;
+                        log::warn!("No message from {} for {}ms, reconnecting", endpoint, silence_ms);
+                        if let Some(callback) = system_event_callback.as_ref() {
+                            callback(SystemEvent::StaleStreamDetected { endpoint: endpoint.clone(), silence_ms });
+                        }
+                        last_message = Instant::now();
+                        let Some(request) = current_request.read().await.clone() else {
+                            break;
+                        };
+                        match subscription_manager.subscribe(request).await {
+                            Ok((new_tx, new_stream)) => {
+                                *subscribe_tx.lock().await = new_tx;
+                                stream = new_stream;
+                            }
+                            Err(e) => error!("Reconnect to {} failed: {e:?}", endpoint),
+                        }
+                    }
                     message = stream.next() => {
+                        last_message = Instant::now();
                         match message {
                             Some(Ok(msg)) => {
                                 let created_at = msg.created_at;
+                                let filters = msg.filters;
                                 match msg.update_oneof {
                                     Some(UpdateOneof::Account(account)) => {
                                         let account_pretty = factory::create_account_pretty_pooled(account);
                                         log::debug!("Received account: {:?}", account_pretty);
+                                        if let Some(cache) = account_cache.as_ref() {
+                                            cache.on_account(&account_pretty);
+                                        }
+                                        if let Some(pruner) = account_pruner.as_ref() {
+                                            pruner.touch(&account_pretty.pubkey);
+                                        }
                                         if let Err(e) = event_processor
                                             .process_grpc_event_transaction_with_metrics(
                                                 EventPretty::Account(account_pretty),
@@ -240,6 +748,7 @@ impl YellowstoneGrpc {
                                     Some(UpdateOneof::BlockMeta(sut)) => {
                                         let block_meta_pretty = factory::create_block_meta_pretty_pooled(sut, created_at);
                                         log::debug!("Received block meta: {:?}", block_meta_pretty);
+                                        let slot = block_meta_pretty.slot;
                                         if let Err(e) = event_processor
                                             .process_grpc_event_transaction_with_metrics(
                                                 EventPretty::BlockMeta(block_meta_pretty),
@@ -249,24 +758,85 @@ impl YellowstoneGrpc {
                                         {
                                             error!("Error processing block meta event: {e:?}");
                                         }
+                                        let held = block_meta_ordering
+                                            .as_mut()
+                                            .map(|ordering| ordering.on_block_meta(slot))
+                                            .unwrap_or_default();
+                                        for transaction_pretty in held {
+                                            if let Err(e) = event_processor
+                                                .process_grpc_event_transaction_with_metrics(
+                                                    EventPretty::Transaction(transaction_pretty),
+                                                    bot_wallet,
+                                                )
+                                                .await
+                                            {
+                                                error!("Error processing transaction event: {e:?}");
+                                            }
+                                        }
                                     }
                                     Some(UpdateOneof::Transaction(sut)) => {
-                                        let transaction_pretty = factory::create_transaction_pretty_pooled(sut, created_at);
+                                        if let Some(recorder) = debug_recorder.as_ref() {
+                                            recorder.record_transaction(sut.clone());
+                                        }
+                                        let mut transaction_pretty = factory::create_transaction_pretty_pooled(sut, created_at);
+                                        transaction_pretty.filters = filters;
                                         log::debug!(
                                             "Received transaction: {} at slot {}",
                                             transaction_pretty.signature,
                                             transaction_pretty.slot
                                         );
-                                        if let Err(e) = event_processor
-                                            .process_grpc_event_transaction_with_metrics(
-                                                EventPretty::Transaction(transaction_pretty),
-                                                bot_wallet,
-                                            )
-                                            .await
-                                        {
-                                            error!("Error processing transaction event: {e:?}");
+                                        let ready = match block_meta_ordering.as_mut() {
+                                            Some(ordering) => ordering.on_transaction(transaction_pretty),
+                                            None => vec![transaction_pretty],
+                                        };
+                                        for transaction_pretty in ready {
+                                            if let Err(e) = event_processor
+                                                .process_grpc_event_transaction_with_metrics(
+                                                    EventPretty::Transaction(transaction_pretty),
+                                                    bot_wallet,
+                                                )
+                                                .await
+                                            {
+                                                error!("Error processing transaction event: {e:?}");
+                                            }
                                         }
                                     }
+                                    Some(UpdateOneof::Block(block)) => {
+                                        let split = block_subscription
+                                            .read()
+                                            .as_ref()
+                                            .map(|subscription| subscription.split(block, created_at));
+                                        let Some((block_meta_pretty, transactions)) = split else {
+                                            continue;
+                                        };
+                                        log::debug!(
+                                            "Received block {} with {} matching transactions",
+                                            block_meta_pretty.slot,
+                                            transactions.len()
+                                        );
+                                        // 区块元数据在前，随后按区块内顺序处理交易
+                                        let events = std::iter::once(EventPretty::BlockMeta(block_meta_pretty))
+                                            .chain(transactions.into_iter().map(EventPretty::Transaction));
+                                        for event_pretty in events {
+                                            if let Err(e) = event_processor
+                                                .process_grpc_event_transaction_with_metrics(
+                                                    event_pretty,
+                                                    bot_wallet,
+                                                )
+                                                .await
+                                            {
+                                                error!("Error processing block event: {e:?}");
+                                            }
+                                        }
+                                    }
+                                    Some(UpdateOneof::Entry(entry)) => {
+                                        event_processor.entry_index.record(
+                                            entry.slot,
+                                            entry.index,
+                                            entry.starting_transaction_index,
+                                            entry.executed_transaction_count,
+                                        );
+                                    }
                                     Some(UpdateOneof::Ping(_)) => {
                                         // 只在需要时获取锁，并立即释放
                                         if let Ok(mut tx_guard) = subscribe_tx.try_lock() {
@@ -295,6 +865,7 @@ impl YellowstoneGrpc {
                         }
                     }
                     Some(update) = control_rx.next() => {
+                        let update = subscription_manager.adapt_request(update);
                         if let Err(e) = subscribe_tx.lock().await.send(update).await {
                             error!("Failed to send subscription update: {}", e);
                             break;
@@ -302,7 +873,7 @@ impl YellowstoneGrpc {
                     }
                 }
             }
-        });
+        })?;
 
         // 保存订阅句柄
         let subscription_handle = SubscriptionHandle::new(stream_handle, None, metrics_handle);
@@ -346,6 +917,9 @@ impl YellowstoneGrpc {
             .ok_or_else(|| anyhow!("No active subscription"))?
             .clone();
 
+        let filter_scopes = SubscriptionManager::get_transaction_filter_scopes(&transaction_filter);
+        let filter_signers =
+            SubscriptionManager::get_transaction_filter_signers(&transaction_filter);
         request.transactions = self
             .subscription_manager
             .get_subscribe_request_filter(
@@ -361,6 +935,13 @@ impl YellowstoneGrpc {
                 self.event_type_filter.read().await.as_ref(),
             )
             .unwrap_or_default();
+        self.apply_runtime_filters(&mut request);
+
+        let block_subscription = if self.config.block_subscription {
+            BlockSubscription::from_request(&mut request)
+        } else {
+            None
+        };
 
         control_sender
             .send(request.clone())
@@ -368,11 +949,153 @@ impl YellowstoneGrpc {
             .map_err(|e| anyhow!("Failed to send update: {}", e))?;
 
         *self.current_request.write().await = Some(request);
+        if self.config.block_subscription {
+            *self.block_subscription.write() = block_subscription;
+        }
+        self.event_processor.set_filter_scopes(filter_scopes);
+        self.event_processor.set_filter_signers(filter_signers);
+
+        Ok(())
+    }
+
+    /// Runtime-watched mints, shared with clones of this client
+    pub fn watchlist(&self) -> &Arc<Watchlist> {
+        &self.watchlist
+    }
+
+    /// Starts streaming transactions that touch any of `mints`, without reconnecting
+    ///
+    /// Returns how many mints were newly watched. Before subscribing the mints are only
+    /// recorded and included in the first subscribe request.
+    pub async fn watch_mints(&self, mints: Vec<Pubkey>) -> AnyResult<usize> {
+        let added = self.watchlist.add(mints);
+        if added > 0 {
+            self.push_runtime_filters().await?;
+        }
+        Ok(added)
+    }
+
+    /// Stops streaming transactions for `mints`, returns how many were watched
+    pub async fn unwatch_mints(&self, mints: Vec<Pubkey>) -> AnyResult<usize> {
+        let removed = self.watchlist.remove(mints);
+        if removed > 0 {
+            self.push_runtime_filters().await?;
+        }
+        Ok(removed)
+    }
+
+    /// Stops parsing the protocol on the live subscription, without reconnecting
+    ///
+    /// Instructions and accounts of the protocol are skipped from the next update on,
+    /// e.g. while its parser misbehaves after a program upgrade. Returns false if the
+    /// protocol was already paused.
+    pub fn pause_protocol(&self, protocol: Protocol) -> bool {
+        self.event_processor.protocol_switches.pause(&protocol)
+    }
+
+    /// Resumes a paused protocol, returns false if it was not paused
+    pub fn resume_protocol(&self, protocol: Protocol) -> bool {
+        self.event_processor.protocol_switches.resume(&protocol)
+    }
+
+    pub fn paused_protocols(&self) -> Vec<Protocol> {
+        self.event_processor.protocol_switches.paused()
+    }
+
+    /// Subscribes to the wallets' transactions and delivers their DEX activity in chain order
+    ///
+    /// Every event where a wallet is the acting party (swap user, liquidity owner, pool
+    /// creator) is passed to `callback`; the returned tracker keeps each wallet's history
+    /// and accepts more wallets (followed by `update_subscription` with a new filter).
+    pub async fn subscribe_wallets<F>(
+        &self,
+        wallets: Vec<Pubkey>,
+        protocols: Vec<Protocol>,
+        commitment: Option<CommitmentLevel>,
+        callback: F,
+    ) -> AnyResult<Arc<WalletActivityTracker>>
+    where
+        F: Fn(WalletActivity) + Send + Sync + 'static,
+    {
+        let tracker = Arc::new(WalletActivityTracker::new(wallets.iter().copied()));
+        let events = tracker.clone();
+        self.subscribe_events_immediate(
+            protocols,
+            None,
+            vec![TransactionFilter::for_wallets(&wallets)],
+            vec![],
+            None,
+            commitment,
+            move |event: Box<dyn UnifiedEvent>| {
+                if let Some(activity) = events.on_event(event.as_ref()) {
+                    callback(activity);
+                }
+            },
+        )
+        .await?;
+        Ok(tracker)
+    }
+
+    /// Sets the watchlist and new-pool transaction filters, which change at runtime
+    fn apply_runtime_filters(&self, request: &mut SubscribeRequest) {
+        let mints = self.watchlist.mints();
+        set_account_filter(request, WATCHLIST_FILTER_NAME, &mints);
+        let pools = self
+            .new_pool_watcher
+            .read()
+            .as_ref()
+            .map(|watcher| watcher.watched_pools())
+            .unwrap_or_default();
+        set_account_filter(request, NEW_POOL_FILTER_NAME, &pools);
+    }
 
+    /// Sends the current request with the updated runtime filters
+    async fn push_runtime_filters(&self) -> AnyResult<()> {
+        let control_guard = self.control_tx.lock().await;
+        let Some(control_sender) = control_guard.as_ref() else {
+            return Ok(());
+        };
+        if self.config.block_subscription {
+            // 整块订阅已把交易过滤器合并进区块过滤器，需通过 update_subscription 重建
+            return Err(anyhow!("Runtime filter updates need update_subscription() in block mode"));
+        }
+        let mut request = self
+            .current_request
+            .read()
+            .await
+            .as_ref()
+            .ok_or_else(|| anyhow!("No active subscription"))?
+            .clone();
+        self.apply_runtime_filters(&mut request);
+        control_sender
+            .clone()
+            .send(request.clone())
+            .await
+            .map_err(|e| anyhow!("Failed to send filter update: {}", e))?;
+        *self.current_request.write().await = Some(request);
         Ok(())
     }
 }
 
+/// Transaction filter `name` including any of `accounts`, removed when there are none
+fn set_account_filter(request: &mut SubscribeRequest, name: &str, accounts: &[Pubkey]) {
+    if accounts.is_empty() {
+        request.transactions.remove(name);
+        return;
+    }
+    request.transactions.insert(
+        name.to_string(),
+        SubscribeRequestFilterTransactions {
+            vote: Some(false),
+            failed: Some(false),
+            signature: None,
+            account_include: accounts.iter().map(|account| account.to_string()).collect(),
+            account_exclude: vec![],
+            account_required: vec![],
+        },
+    );
+}
+
 // 实现 Clone trait 以支持模块间共享
 impl Clone for YellowstoneGrpc {
     fn clone(&self) -> Self {
@@ -389,6 +1112,15 @@ impl Clone for YellowstoneGrpc {
             control_tx: self.control_tx.clone(),
             event_type_filter: self.event_type_filter.clone(),
             current_request: self.current_request.clone(),
+            block_subscription: self.block_subscription.clone(),
+            system_event_callback: self.system_event_callback.clone(),
+            watchlist: self.watchlist.clone(),
+            account_cache: self.account_cache.clone(),
+            account_pruner: self.account_pruner.clone(),
+            activity_aggregator: self.activity_aggregator.clone(),
+            new_pool_watcher: self.new_pool_watcher.clone(),
+            new_pool_refresh: self.new_pool_refresh.clone(),
+            debug_recorder: self.debug_recorder.clone(),
         }
     }
 }
@@ -1,14 +1,17 @@
 use crate::{
     common::AnyResult,
     streaming::{
+        analytics::{NewPoolWatchEvent, ProtocolActivitySummary},
+        common::AccountPruned,
         grpc::{pool::factory, EventPretty},
+        store::CacheDivergence,
         yellowstone_grpc::{TransactionFilter, YellowstoneGrpc},
     },
 };
 use futures::{SinkExt, StreamExt};
 use log::error;
 use solana_program::pubkey;
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
 use yellowstone_grpc_proto::geyser::{
     subscribe_update::UpdateOneof, SubscribeRequest, SubscribeRequestPing,
@@ -20,12 +23,25 @@ const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 pub enum SystemEvent {
     NewTransfer(TransferInfo),
     Error(String),
+    /// 流在 `stale_timeout` 内无任何消息，已强制重连
+    StaleStreamDetected {
+        endpoint: String,
+        silence_ms: u64,
+    },
+    /// 快照比对发现缓存与 RPC 不一致，缓存已修正（见 `YellowstoneGrpc::enable_snapshot_diff`）
+    CacheDivergence(CacheDivergence),
+    /// 账户因长时间无更新或流动性耗尽已移出订阅（见 `YellowstoneGrpc::enable_account_pruning`）
+    AccountsPruned(Vec<AccountPruned>),
+    /// 一个汇总窗口已结束的各协议活跃度（见 `YellowstoneGrpc::enable_activity_summaries`）
+    ActivitySummary(Vec<ProtocolActivitySummary>),
+    /// 新池监视的检测、早期交易与到期（见 `YellowstoneGrpc::enable_new_pool_watch`）
+    NewPoolWatch(NewPoolWatchEvent),
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct TransferInfo {
     pub slot: u64,
-    pub signature: String,
+    pub signature: Signature,
     pub tx: Option<SubscribeUpdateTransactionInfo>,
 }
 
@@ -42,8 +58,13 @@ impl YellowstoneGrpc {
         let addrs = vec![SYSTEM_PROGRAM_ID.to_string()];
         let account_include = account_include.unwrap_or_default();
         let account_exclude = account_exclude.unwrap_or_default();
-        let tx_filter =
-            vec![TransactionFilter { account_include, account_exclude, account_required: addrs }];
+        let tx_filter = vec![TransactionFilter {
+            account_include,
+            account_exclude,
+            account_required: addrs,
+            protocols: vec![],
+            signers: vec![],
+        }];
         let transactions = self.subscription_manager.get_subscribe_request_filter(tx_filter, None);
         let (mut subscribe_tx, mut stream, _) = self
             .subscription_manager
@@ -102,7 +123,7 @@ impl YellowstoneGrpc {
             EventPretty::Transaction(transaction_pretty) => {
                 callback(SystemEvent::NewTransfer(TransferInfo {
                     slot: transaction_pretty.slot,
-                    signature: transaction_pretty.signature.to_string(),
+                    signature: transaction_pretty.signature,
                     tx: Some(transaction_pretty.grpc_tx),
                 }));
             }
@@ -0,0 +1,153 @@
+//! Subscription tests against the in-process mock Geyser server
+
+#![cfg(feature = "transport")]
+
+mod support;
+
+use std::time::Duration;
+
+use solana_streamer_sdk::streaming::common::StreamClientConfig;
+use solana_streamer_sdk::streaming::event_parser::protocols::BlockMetaEvent;
+use solana_streamer_sdk::streaming::event_parser::{Protocol, UnifiedEvent};
+use solana_streamer_sdk::streaming::grpc::RequestInterceptor;
+use solana_streamer_sdk::streaming::yellowstone_grpc::TransactionFilter;
+use solana_streamer_sdk::streaming::YellowstoneGrpc;
+use support::mock_geyser::{block_meta, MockGeyser};
+use tokio::sync::mpsc;
+use yellowstone_grpc_proto::geyser::SubscribeUpdate;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const POOL: &str = "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C";
+
+/// Starts the mock and subscribes to it, returns the client and the delivered block slots
+async fn subscribe(
+    mock: &MockGeyser,
+    config: StreamClientConfig,
+) -> (YellowstoneGrpc, mpsc::UnboundedReceiver<u64>) {
+    subscribe_with_token(mock, None, config).await
+}
+
+async fn subscribe_with_token(
+    mock: &MockGeyser,
+    x_token: Option<String>,
+    config: StreamClientConfig,
+) -> (YellowstoneGrpc, mpsc::UnboundedReceiver<u64>) {
+    let endpoint = mock.start().await;
+    let client = YellowstoneGrpc::new_with_config(endpoint, x_token, config).unwrap();
+    let (tx, rx) = mpsc::unbounded_channel();
+    let callback = move |event: Box<dyn UnifiedEvent>| {
+        if let Some(block) = event.as_any().downcast_ref::<BlockMetaEvent>() {
+            let _ = tx.send(block.slot);
+        }
+    };
+    let filter =
+        TransactionFilter { account_include: vec![POOL.to_string()], ..Default::default() };
+    client
+        .subscribe_events_immediate(
+            vec![Protocol::RaydiumCpmm],
+            None,
+            vec![filter],
+            vec![],
+            None,
+            None,
+            callback,
+        )
+        .await
+        .unwrap();
+    (client, rx)
+}
+
+fn config() -> StreamClientConfig {
+    let mut config = StreamClientConfig::default();
+    config.connection.ping_interval = 0;
+    config
+}
+
+async fn recv_slots(rx: &mut mpsc::UnboundedReceiver<u64>, n: usize) -> Vec<u64> {
+    let mut slots = Vec::new();
+    while slots.len() < n {
+        let slot = tokio::time::timeout(TIMEOUT, rx.recv()).await.expect("event").unwrap();
+        slots.push(slot);
+    }
+    slots
+}
+
+async fn wait_until(condition: impl Fn() -> bool) {
+    tokio::time::timeout(TIMEOUT, async {
+        while !condition() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("condition");
+}
+
+#[tokio::test]
+async fn test_events_delivered_in_stream_order() {
+    let script: Vec<SubscribeUpdate> = (100..110).map(block_meta).collect();
+    let mock = MockGeyser::new(vec![script]);
+    let (client, mut rx) = subscribe(&mock, config()).await;
+
+    assert_eq!(recv_slots(&mut rx, 10).await, (100..110).collect::<Vec<_>>());
+    wait_until(|| !mock.requests().is_empty()).await;
+    let requests = mock.requests();
+    assert_eq!(requests[0].transactions.values().next().unwrap().account_include, [POOL]);
+    client.stop().await;
+}
+
+#[tokio::test]
+async fn test_filter_update_sent_on_open_stream() {
+    let mock = MockGeyser::new(vec![vec![block_meta(1)]]);
+    let (client, mut rx) = subscribe(&mock, config()).await;
+    recv_slots(&mut rx, 1).await;
+
+    let other = "11111111111111111111111111111111".to_string();
+    let filter = TransactionFilter { account_include: vec![other.clone()], ..Default::default() };
+    client.update_subscription(vec![filter], vec![]).await.unwrap();
+
+    wait_until(|| mock.requests().len() >= 2).await;
+    let update = mock.requests().pop().unwrap();
+    assert_eq!(update.transactions.values().next().unwrap().account_include, [other]);
+    // 更新在同一连接上发送，不重新订阅
+    assert_eq!(mock.subscribe_calls(), 1);
+    client.stop().await;
+}
+
+#[tokio::test]
+async fn test_resubscribes_after_stale_stream() {
+    let mock = MockGeyser::new(vec![vec![block_meta(1)], vec![block_meta(2)]]);
+    let mut config = config();
+    config.connection.stale_timeout = 1;
+    let (client, mut rx) = subscribe(&mock, config).await;
+
+    assert_eq!(recv_slots(&mut rx, 2).await, [1, 2]);
+    assert_eq!(mock.subscribe_calls(), 2);
+    // 重连沿用原请求
+    wait_until(|| mock.requests().len() >= 2).await;
+    let requests = mock.requests();
+    assert_eq!(requests[0].transactions, requests[1].transactions);
+    client.stop().await;
+}
+
+#[tokio::test]
+async fn test_token_and_headers_reach_request_metadata() {
+    let mock = MockGeyser::new(vec![vec![block_meta(1)]]);
+    // 钩子在静态请求头之后执行，可以读取它们
+    let sign = RequestInterceptor::new(|mut request| {
+        let client_id = request.metadata().get("x-client-id").cloned().unwrap();
+        request.metadata_mut().insert("x-signed", client_id);
+        Ok(request)
+    });
+    let mut config = config();
+    config.connection =
+        config.connection.with_header("X-Client-Id", "tests").with_interceptor(sign);
+    let (client, mut rx) = subscribe_with_token(&mock, Some("secret".to_string()), config).await;
+    recv_slots(&mut rx, 1).await;
+
+    let metadata = mock.subscribe_metadata();
+    assert_eq!(metadata.len(), 1);
+    assert_eq!(metadata[0].get("x-token").unwrap(), "secret");
+    assert_eq!(metadata[0].get("x-client-id").unwrap(), "tests");
+    assert_eq!(metadata[0].get("x-signed").unwrap(), "tests");
+    client.stop().await;
+}
@@ -0,0 +1,167 @@
+//! In-process Yellowstone gRPC server serving canned updates
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{metadata::MetadataMap, transport::Server, Request, Response, Status, Streaming};
+use yellowstone_grpc_proto::geyser::{
+    geyser_server::{Geyser, GeyserServer},
+    subscribe_update::UpdateOneof,
+    GetBlockHeightRequest, GetBlockHeightResponse, GetLatestBlockhashRequest,
+    GetLatestBlockhashResponse, GetSlotRequest, GetSlotResponse, GetVersionRequest,
+    GetVersionResponse, IsBlockhashValidRequest, IsBlockhashValidResponse, PingRequest,
+    PongResponse, SubscribeReplayInfoRequest, SubscribeReplayInfoResponse, SubscribeRequest,
+    SubscribeUpdate, SubscribeUpdateBlockMeta,
+};
+
+type UpdateStream = Pin<Box<dyn Stream<Item = Result<SubscribeUpdate, Status>> + Send>>;
+
+/// Block meta update for `slot`, parented to the previous slot
+pub fn block_meta(slot: u64) -> SubscribeUpdate {
+    SubscribeUpdate {
+        filters: vec!["client".to_string()],
+        created_at: None,
+        update_oneof: Some(UpdateOneof::BlockMeta(SubscribeUpdateBlockMeta {
+            slot,
+            blockhash: format!("hash-{slot}"),
+            parent_slot: slot.saturating_sub(1),
+            ..Default::default()
+        })),
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    /// 第 n 次 Subscribe 发送的更新，用完后的连接不发送任何消息
+    scripts: Mutex<VecDeque<Vec<SubscribeUpdate>>>,
+    /// 所有连接上收到的请求，按到达顺序
+    requests: Mutex<Vec<SubscribeRequest>>,
+    /// 每次 Subscribe 调用携带的请求头
+    metadata: Mutex<Vec<MetadataMap>>,
+    subscribe_calls: AtomicUsize,
+}
+
+/// Mock Geyser service
+///
+/// Each `Subscribe` call sends the next script, then keeps the stream open without
+/// sending anything, so clients see a silent (stale) connection. Filters are not
+/// applied; every request the client sends is recorded instead.
+#[derive(Clone, Default)]
+pub struct MockGeyser {
+    state: Arc<MockState>,
+}
+
+impl MockGeyser {
+    pub fn new(scripts: Vec<Vec<SubscribeUpdate>>) -> Self {
+        let mock = Self::default();
+        *mock.state.scripts.lock() = scripts.into();
+        mock
+    }
+
+    /// Serves on a random local port, returns the endpoint URL
+    pub async fn start(&self) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let service = GeyserServer::new(self.clone());
+        tokio::spawn(async move {
+            Server::builder()
+                .add_service(service)
+                .serve_with_incoming(TcpListenerStream::new(listener))
+                .await
+                .unwrap();
+        });
+        endpoint
+    }
+
+    pub fn requests(&self) -> Vec<SubscribeRequest> {
+        self.state.requests.lock().clone()
+    }
+
+    /// Metadata of each `Subscribe` call, in call order
+    pub fn subscribe_metadata(&self) -> Vec<MetadataMap> {
+        self.state.metadata.lock().clone()
+    }
+
+    pub fn subscribe_calls(&self) -> usize {
+        self.state.subscribe_calls.load(Ordering::SeqCst)
+    }
+}
+
+#[tonic::async_trait]
+impl Geyser for MockGeyser {
+    type SubscribeStream = UpdateStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<Streaming<SubscribeRequest>>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        self.state.metadata.lock().push(request.metadata().clone());
+        self.state.subscribe_calls.fetch_add(1, Ordering::SeqCst);
+        let updates = self.state.scripts.lock().pop_front().unwrap_or_default();
+
+        let state = self.state.clone();
+        let mut inbound = request.into_inner();
+        tokio::spawn(async move {
+            while let Some(Ok(request)) = inbound.next().await {
+                state.requests.lock().push(request);
+            }
+        });
+
+        let stream =
+            futures::stream::iter(updates.into_iter().map(Ok)).chain(futures::stream::pending());
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn subscribe_replay_info(
+        &self,
+        _request: Request<SubscribeReplayInfoRequest>,
+    ) -> Result<Response<SubscribeReplayInfoResponse>, Status> {
+        Err(Status::unimplemented("mock"))
+    }
+
+    async fn ping(&self, request: Request<PingRequest>) -> Result<Response<PongResponse>, Status> {
+        Ok(Response::new(PongResponse { count: request.into_inner().count }))
+    }
+
+    async fn get_latest_blockhash(
+        &self,
+        _request: Request<GetLatestBlockhashRequest>,
+    ) -> Result<Response<GetLatestBlockhashResponse>, Status> {
+        Err(Status::unimplemented("mock"))
+    }
+
+    async fn get_block_height(
+        &self,
+        _request: Request<GetBlockHeightRequest>,
+    ) -> Result<Response<GetBlockHeightResponse>, Status> {
+        Err(Status::unimplemented("mock"))
+    }
+
+    async fn get_slot(
+        &self,
+        _request: Request<GetSlotRequest>,
+    ) -> Result<Response<GetSlotResponse>, Status> {
+        Err(Status::unimplemented("mock"))
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        _request: Request<IsBlockhashValidRequest>,
+    ) -> Result<Response<IsBlockhashValidResponse>, Status> {
+        Err(Status::unimplemented("mock"))
+    }
+
+    async fn get_version(
+        &self,
+        _request: Request<GetVersionRequest>,
+    ) -> Result<Response<GetVersionResponse>, Status> {
+        let version = r#"{"version":{"package":"yellowstone-grpc-geyser","version":"9.0.0"}}"#;
+        Ok(Response::new(GetVersionResponse { version: version.to_string() }))
+    }
+}